@@ -0,0 +1,56 @@
+//! Layout computation for each layout system at increasing window counts.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use rift_wm::actor::app::WindowId;
+use rift_wm::common::collections::HashMap;
+use rift_wm::common::config::GapSettings;
+use rift_wm::layout_engine::{
+    BspLayoutSystem, LayoutSystem, MasterStackLayoutSystem, ScrollingLayoutSystem,
+    TraditionalLayoutSystem,
+};
+
+const WINDOW_COUNTS: [u32; 3] = [10, 50, 200];
+
+fn screen() -> CGRect { CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(2560.0, 1440.0)) }
+
+fn bench_system<S: LayoutSystem + Default>(c: &mut Criterion, name: &str) {
+    let mut group = c.benchmark_group(format!("calculate_layout/{name}"));
+    let gaps = GapSettings::default();
+    let constraints = HashMap::default();
+    for count in WINDOW_COUNTS {
+        let mut system = S::default();
+        let layout = system.create_layout();
+        // Spread windows over a few apps, as a real workspace would.
+        for idx in 1..=count {
+            system.add_window_after_selection(layout, WindowId::new((idx % 7) as i32 + 1, idx));
+        }
+        group.bench_with_input(BenchmarkId::from_parameter(count), &count, |b, _| {
+            b.iter(|| {
+                system.calculate_layout(
+                    layout,
+                    black_box(screen()),
+                    0.0,
+                    &constraints,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+fn layout_systems(c: &mut Criterion) {
+    bench_system::<TraditionalLayoutSystem>(c, "traditional");
+    bench_system::<BspLayoutSystem>(c, "bsp");
+    bench_system::<MasterStackLayoutSystem>(c, "master_stack");
+    bench_system::<ScrollingLayoutSystem>(c, "scrolling");
+}
+
+criterion_group!(benches, layout_systems);
+criterion_main!(benches);
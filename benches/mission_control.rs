@@ -0,0 +1,118 @@
+//! Geometry behind the mission control overlay: window tiling, workspace grid
+//! placement and preview capture sizing.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use rift_wm::actor::app::WindowId;
+use rift_wm::model::server::WindowData;
+use rift_wm::sys::app::WindowInfo;
+use rift_wm::ui::mission_control::{MissionControlOverlay, WindowLayoutKind, WorkspaceGrid};
+
+const WINDOW_COUNTS: [u32; 3] = [10, 50, 200];
+
+fn bounds() -> CGRect { CGRect::new(CGPoint::new(48.0, 48.0), CGSize::new(2464.0, 1344.0)) }
+
+/// Windows of assorted sizes and positions, so the layout has real sorting to do.
+fn windows(count: u32) -> Vec<WindowData> {
+    (1..=count)
+        .map(|idx| {
+            let i = f64::from(idx);
+            WindowData {
+                id: WindowId::new(1, idx),
+                is_floating: false,
+                is_focused: idx == 1,
+                is_hidden: false,
+                app_name: None,
+                info: WindowInfo {
+                    is_standard: true,
+                    is_root: true,
+                    is_minimized: false,
+                    is_resizable: true,
+                    title: format!("Window {idx}"),
+                    frame: CGRect::new(
+                        CGPoint::new((i * 137.0) % 2000.0, (i * 89.0) % 1100.0),
+                        CGSize::new(400.0 + (i * 53.0) % 900.0, 300.0 + (i * 31.0) % 600.0),
+                    ),
+                    min_size: None,
+                    max_size: None,
+                    sys_id: None,
+                    bundle_id: None,
+                    path: None,
+                    ax_role: None,
+                    ax_subrole: None,
+                },
+            }
+        })
+        .collect()
+}
+
+fn window_layouts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mission_control/window_rects");
+    for count in WINDOW_COUNTS {
+        let windows = windows(count);
+        group.bench_with_input(BenchmarkId::new("exploded", count), &windows, |b, windows| {
+            b.iter(|| MissionControlOverlay::compute_exploded_layout(windows, black_box(bounds())))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("preserve_original", count),
+            &windows,
+            |b, windows| {
+                b.iter(|| {
+                    MissionControlOverlay::compute_window_rects(
+                        windows,
+                        black_box(bounds()),
+                        WindowLayoutKind::PreserveOriginal,
+                    )
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn workspace_grid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mission_control/workspace_grid");
+    for tiles in [4usize, 10, 32] {
+        group.bench_with_input(BenchmarkId::from_parameter(tiles), &tiles, |b, &tiles| {
+            b.iter(|| {
+                let grid = WorkspaceGrid::new(black_box(tiles), bounds()).unwrap();
+                (0..tiles).map(|idx| grid.rect_for(idx)).collect::<Vec<_>>()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn capture_targets(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mission_control/capture_targets");
+    for count in WINDOW_COUNTS {
+        let windows = windows(count);
+        let rects = MissionControlOverlay::compute_window_rects(
+            &windows,
+            bounds(),
+            WindowLayoutKind::PreserveOriginal,
+        )
+        .unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(count), &windows, |b, windows| {
+            b.iter(|| {
+                windows
+                    .iter()
+                    .zip(&rects)
+                    .map(|(window, &rect)| {
+                        MissionControlOverlay::capture_target_size(
+                            window,
+                            black_box(rect),
+                            WindowLayoutKind::PreserveOriginal,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, window_layouts, workspace_grid, capture_targets);
+criterion_main!(benches);
@@ -1,3 +1,6 @@
+use std::path::Path;
+use std::process::Command;
+
 fn main() {
     println!("cargo:rustc-link-search=framework=/System/Library/PrivateFrameworks");
 
@@ -7,4 +10,39 @@ fn main() {
     println!("cargo:rustc-link-lib=framework=IOKit");
     println!("cargo:rustc-link-lib=framework=MultitouchSupport");
     println!("cargo:rustc-link-lib=framework=Carbon");
+
+    // Reported by `rift-cli query build-info`.
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let profile = std::env::var("PROFILE").unwrap_or_default();
+    println!("cargo:rustc-env=RIFT_TARGET={target}");
+    println!("cargo:rustc-env=RIFT_PROFILE={profile}");
+    // Builds outside a git checkout, such as packaged ones, can set the commit themselves.
+    println!("cargo:rerun-if-env-changed=RIFT_GIT_COMMIT");
+    let commit = std::env::var("RIFT_GIT_COMMIT")
+        .ok()
+        .filter(|commit| !commit.is_empty())
+        .or_else(|| git(&["rev-parse", "--short", "HEAD"]));
+    if let Some(commit) = commit {
+        println!("cargo:rustc-env=RIFT_GIT_COMMIT={commit}");
+    }
+    // HEAD only names the branch, which doesn't change on commit, so also watch the
+    // ref it points at, loose or packed.
+    let mut watched = vec!["HEAD".to_string(), "packed-refs".to_string()];
+    watched.extend(git(&["symbolic-ref", "-q", "HEAD"]));
+    for name in watched {
+        if let Some(path) = git(&["rev-parse", "--git-path", &name])
+            && Path::new(&path).exists()
+        {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8(output.stdout).ok()?;
+    Some(stdout.trim().to_string())
 }
@@ -75,6 +75,7 @@
             ];
 
             # https://devenv.sh/reference/options/
+            packages = [ pkgs.just ];
 
           };
 
@@ -8,6 +8,9 @@ pub mod config;
 pub mod config_watcher;
 pub mod drag_swap;
 pub mod event_tap;
+pub mod frame_audit;
+pub mod idle;
+pub mod kill_switch;
 pub mod menu_bar;
 pub mod mission_control;
 pub mod mission_control_observer;
@@ -15,7 +18,10 @@ pub mod notification_center;
 pub mod process;
 pub mod raise_manager;
 pub mod reactor;
+pub mod secure_input;
+pub mod server_app;
 pub mod stack_line;
+pub mod update_check;
 pub mod window_notify;
 pub mod wm_controller;
 
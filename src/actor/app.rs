@@ -32,7 +32,7 @@ pub use crate::sys::app::{AppInfo, WindowInfo, pid_t};
 use crate::sys::axuielement::{
     AX_STANDARD_WINDOW_SUBROLE, AX_WINDOW_ROLE, AXUIElement, Error as AxError,
 };
-use crate::sys::enhanced_ui::with_enhanced_ui_disabled;
+use crate::sys::enhanced_ui::{self, AnimationQuirk, with_enhanced_ui_disabled};
 use crate::sys::event;
 use crate::sys::executor::Executor;
 use crate::sys::observer::Observer;
@@ -191,6 +191,10 @@ pub struct AppThreadHandle {
 }
 
 impl AppThreadHandle {
+    pub(crate) fn new(requests_tx: actor::Sender<Request>) -> Self {
+        AppThreadHandle { requests_tx }
+    }
+
     pub(crate) fn new_for_test(requests_tx: actor::Sender<Request>) -> Self {
         let this = AppThreadHandle { requests_tx };
         this
@@ -229,6 +233,10 @@ pub enum Request {
     /// marked `Quiet::Yes` automatically.
     Raise(Vec<WindowId>, CancellationToken, u64, Quiet),
     Activate(Quiet),
+
+    /// Re-add AX notifications that macOS may have dropped (e.g. across Fast User
+    /// Switching), then report visible windows so the reactor can revalidate them.
+    RefreshObservers,
 }
 
 struct RaiseRequest(Vec<WindowId>, CancellationToken, u64, Quiet);
@@ -267,6 +275,9 @@ struct State {
     is_frontmost: bool,
     raises_tx: actor::Sender<RaiseRequest>,
     tx_store: Option<WindowTxStore>,
+    /// Whether the app had `AXEnhancedUserInterface` on, so animations turn it
+    /// back on when they end.
+    enhanced_ui: bool,
 }
 
 struct AppWindowState {
@@ -489,6 +500,11 @@ impl State {
                     warn!(?wid, error = ?err, "Failed to close window");
                 }
             }
+            Request::RefreshObservers => {
+                self.refresh_observers();
+                *request = Request::GetVisibleWindows;
+                return self.handle_request(request);
+            }
             Request::GetVisibleWindows => {
                 let window_elems = match self.app.windows() {
                     Ok(elems) => elems,
@@ -664,7 +680,7 @@ impl State {
                     let started_animation = !std::mem::replace(&mut window.is_animating, true);
                     (window.elem.clone(), started_animation)
                 };
-                if started_animation && !had_animations {
+                if started_animation && !had_animations && self.enhanced_ui {
                     let _ = self.app.set_bool_attribute("AXEnhancedUserInterface", false);
                 }
                 self.stop_notifications_for_animation(&elem);
@@ -688,7 +704,7 @@ impl State {
                     .window_mut(wid)
                     .map(|window| std::mem::replace(&mut window.is_animating, false))
                     .unwrap_or(false);
-                if ended_animation && !self.has_active_window_animations() {
+                if ended_animation && !self.has_active_window_animations() && self.enhanced_ui {
                     let _ = self.app.set_bool_attribute("AXEnhancedUserInterface", true);
                 }
                 self.restart_notifications_after_animation(&elem);
@@ -1076,6 +1092,7 @@ impl State {
         }
 
         self.is_hidden = true;
+        self.send_event(Event::ApplicationHidden(self.pid));
         let mut to_minimize = Vec::new();
         for (wid, window) in self.windows.iter_mut() {
             if window.hidden_by_app {
@@ -1096,6 +1113,7 @@ impl State {
         }
 
         self.is_hidden = false;
+        self.send_event(Event::ApplicationShown(self.pid));
         let mut to_restore = Vec::new();
         for (wid, window) in self.windows.iter_mut() {
             if !window.hidden_by_app {
@@ -1335,6 +1353,22 @@ impl State {
         Err(AxError::NotFound)
     }
 
+    fn refresh_observers(&self) {
+        // Re-adding a live registration fails with "already registered", which is harmless.
+        for notif in APP_NOTIFICATIONS {
+            if let Err(err) = self.observer.add_notification(&self.app, notif) {
+                trace!(?notif, "Re-adding app notification: {err}");
+            }
+        }
+        for window in self.windows.values().filter(|w| !w.is_animating) {
+            for notif in WINDOW_NOTIFICATIONS {
+                if let Err(err) = self.observer.add_notification(&window.elem, notif) {
+                    trace!(?notif, "Re-adding window notification: {err}");
+                }
+            }
+        }
+    }
+
     fn stop_notifications_for_animation(&self, elem: &AXUIElement) {
         for notif in WINDOW_ANIMATION_NOTIFICATIONS {
             let res = self.observer.remove_notification(elem, notif);
@@ -1357,7 +1391,7 @@ impl State {
 
     fn remove_window(&mut self, wid: WindowId) -> Option<AppWindowState> {
         let window = self.windows.remove(&wid)?;
-        if window.is_animating && !self.has_active_window_animations() {
+        if window.is_animating && !self.has_active_window_animations() && self.enhanced_ui {
             let _ = self.app.set_bool_attribute("AXEnhancedUserInterface", true);
         }
         Some(window)
@@ -1374,7 +1408,7 @@ impl Drop for State {
 
 fn app_thread_main(
     pid: pid_t,
-    info: AppInfo,
+    mut info: AppInfo,
     events_tx: reactor::Sender,
     tx_store: Option<WindowTxStore>,
 ) {
@@ -1407,6 +1441,11 @@ fn app_thread_main(
     let observer =
         observer.install(move |elem, notif| _ = notifications_tx.send((elem, notif.to_owned())));
 
+    info.animation_quirk = enhanced_ui::animation_quirk(&app);
+    if let Some(quirk) = info.animation_quirk {
+        info!(?bundle_id, ?quirk, "Not animating this app's windows");
+    }
+
     let (raises_tx, raises_rx) = actor::channel();
     let state = State {
         pid,
@@ -1423,6 +1462,7 @@ fn app_thread_main(
         is_frontmost: false,
         raises_tx,
         tx_store,
+        enhanced_ui: info.animation_quirk == Some(AnimationQuirk::EnhancedUserInterface),
     };
 
     let (requests_tx, requests_rx) = actor::channel();
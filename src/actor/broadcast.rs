@@ -1,6 +1,15 @@
+//! Events pushed to IPC subscribers and CLI hooks.
+//!
+//! Each event serializes to one JSON object whose `type` field is the variant
+//! name in snake_case (`workspace_created`, `window_managed`, ...) and whose
+//! other fields are the variant's fields, also in snake_case. That shape is what
+//! scripts depend on: existing fields keep their name and meaning, and new
+//! information arrives as new fields or new event types. The tests at the bottom
+//! of this file pin it down.
+
 use serde::{Deserialize, Serialize};
 
-use crate::actor::app::WindowId;
+use crate::actor::app::{WindowId, pid_t};
 use crate::layout_engine::{LayoutKind, VirtualWorkspaceId};
 use crate::sys::screen::SpaceId;
 
@@ -13,6 +22,15 @@ pub struct StackInfo {
     pub windows: Vec<String>,
 }
 
+/// A display whose space id was reissued while the system was asleep.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SpaceRemap {
+    pub display_uuid: String,
+    pub old_space: SpaceId,
+    pub new_space: SpaceId,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
@@ -23,6 +41,13 @@ pub enum BroadcastEvent {
         workspace_name: String,
         display_uuid: Option<String>,
     },
+    /// The workspaces of a space were put in a new order.
+    WorkspacesReordered {
+        workspace_ids: Vec<VirtualWorkspaceId>,
+        workspace_names: Vec<String>,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
     WindowsChanged {
         workspace_id: VirtualWorkspaceId,
         workspace_name: String,
@@ -49,7 +74,316 @@ pub enum BroadcastEvent {
         space_id: SpaceId,
         display_uuid: Option<String>,
     },
+    FocusModeChanged {
+        focus_mode: Option<String>,
+    },
+    /// Sent after unlocking or switching back to this user session.
+    SessionResumed,
+    /// Summary of what changed across a sleep/wake cycle, sent once the
+    /// system has settled after waking.
+    WakeReconciled {
+        remapped_spaces: Vec<SpaceRemap>,
+        added_displays: Vec<String>,
+        removed_displays: Vec<String>,
+        moved_windows: Vec<WindowId>,
+    },
+    /// Secure event input turned on or off. Hotkeys cannot fire while it is on.
+    SecureInputChanged {
+        enabled: bool,
+    },
+    /// Another app took over the menu bar. `pid` is `None` when no app owns it,
+    /// and the names are `None` for apps rift does not manage.
+    MenuBarOwnerChanged {
+        pid: Option<pid_t>,
+        bundle_id: Option<String>,
+        app_name: Option<String>,
+    },
+    /// The user has been idle for an idle rule's `after_secs`, or came back
+    /// after that happened. Sent once per rule.
+    IdleChanged {
+        idle: bool,
+        after_secs: u64,
+    },
+    WorkspaceCreated {
+        workspace_id: VirtualWorkspaceId,
+        workspace_name: String,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    WorkspaceRenamed {
+        workspace_id: VirtualWorkspaceId,
+        previous_name: String,
+        workspace_name: String,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    /// Only happens when macOS replaces a space and rift drops the workspaces it
+    /// had set up for the new one in favour of the old space's.
+    WorkspaceDeleted {
+        workspace_id: VirtualWorkspaceId,
+        workspace_name: String,
+        space_id: SpaceId,
+        display_uuid: Option<String>,
+    },
+    /// Rift started managing a window, tiled or floating.
+    WindowManaged {
+        window_id: WindowId,
+        floating: bool,
+    },
+    /// Rift stopped managing a window, usually because it closed.
+    WindowUnmanaged {
+        window_id: WindowId,
+    },
+    WindowFloatingChanged {
+        window_id: WindowId,
+        floating: bool,
+    },
+    DisplayAdded {
+        display_uuid: String,
+    },
+    DisplayRemoved {
+        display_uuid: String,
+    },
+    /// A mode that changes how rift behaves was entered or left. Always delivered,
+    /// even when entering the mode pauses every other event.
+    ModeChanged {
+        mode: Mode,
+        active: bool,
+        /// The space the mode applies to, for per-space modes.
+        space_id: Option<SpaceId>,
+    },
+    /// The update checker found a release newer than the running version.
+    UpdateAvailable {
+        current_version: String,
+        latest_version: String,
+        url: String,
+    },
+}
+
+/// See [`BroadcastEvent::ModeChanged`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// `toggle_presentation_mode`; other events are not sent while it is on.
+    Presentation,
+    /// `pause_space` / `resume_space`.
+    SpacePaused,
+    /// The panic hotkey. There is no way back short of restarting rift.
+    KillSwitch,
+}
+
+impl Mode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Mode::Presentation => "presentation",
+            Mode::SpacePaused => "space_paused",
+            Mode::KillSwitch => "kill_switch",
+        }
+    }
 }
 
-pub type BroadcastSender = crate::actor::Sender<BroadcastEvent>;
-pub type BroadcastReceiver = crate::actor::Receiver<BroadcastEvent>;
+/// What travels down a broadcast channel. Pausing goes down the same channel as
+/// the events so that it takes effect exactly between the events sent before
+/// and after it, however far behind the receiving end is.
+#[derive(Debug, Clone)]
+pub enum Message {
+    Event(BroadcastEvent),
+    /// See [`BroadcastSender::set_paused`].
+    SetPaused(bool),
+    /// See [`BroadcastSender::set_hooks_paused`].
+    SetHooksPaused(bool),
+}
+
+#[derive(Debug, Clone)]
+pub struct BroadcastSender(crate::actor::Sender<Message>);
+
+pub type BroadcastReceiver = crate::actor::Receiver<Message>;
+
+pub fn channel() -> (BroadcastSender, BroadcastReceiver) {
+    let (tx, rx) = crate::actor::channel();
+    (BroadcastSender(tx), rx)
+}
+
+impl BroadcastSender {
+    pub fn send(&self, event: BroadcastEvent) { self.0.send(Message::Event(event)) }
+
+    /// Stops (or resumes) delivering the events sent after this to IPC subscribers
+    /// and CLI hooks. Events sent while paused are dropped, not queued, except for
+    /// [`BroadcastEvent::ModeChanged`].
+    pub fn set_paused(&self, paused: bool) { self.0.send(Message::SetPaused(paused)) }
+
+    /// Like [`set_paused`](Self::set_paused), but only for CLI hooks; IPC
+    /// subscribers still get events.
+    pub fn set_hooks_paused(&self, paused: bool) { self.0.send(Message::SetHooksPaused(paused)) }
+}
+
+/// The pause state of a broadcast channel, kept by whoever delivers its events.
+#[derive(Debug, Default)]
+pub struct Delivery {
+    paused: bool,
+    hooks_paused: bool,
+}
+
+impl Delivery {
+    /// Takes the next message off the channel. Returns the event it carries if it
+    /// should be delivered, along with whether CLI hooks should see it too.
+    pub fn accept(&mut self, message: Message) -> Option<(BroadcastEvent, bool)> {
+        match message {
+            Message::Event(event) => {
+                if self.paused && !matches!(event, BroadcastEvent::ModeChanged { .. }) {
+                    return None;
+                }
+                Some((event, !self.hooks_paused))
+            }
+            Message::SetPaused(paused) => {
+                self.paused = paused;
+                None
+            }
+            Message::SetHooksPaused(paused) => {
+                self.hooks_paused = paused;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn to_json(event: BroadcastEvent) -> serde_json::Value { serde_json::to_value(event).unwrap() }
+
+    #[test]
+    fn window_events_have_a_stable_shape() {
+        let window_id = WindowId::new(42, 7);
+        let id = serde_json::to_value(window_id).unwrap();
+        assert_eq!(
+            to_json(BroadcastEvent::WindowManaged { window_id, floating: true }),
+            json!({ "type": "window_managed", "window_id": id, "floating": true })
+        );
+        assert_eq!(
+            to_json(BroadcastEvent::WindowUnmanaged { window_id }),
+            json!({ "type": "window_unmanaged", "window_id": id })
+        );
+        assert_eq!(
+            to_json(BroadcastEvent::WindowFloatingChanged { window_id, floating: false }),
+            json!({ "type": "window_floating_changed", "window_id": id, "floating": false })
+        );
+    }
+
+    #[test]
+    fn menu_bar_owner_event_has_a_stable_shape() {
+        let event = BroadcastEvent::MenuBarOwnerChanged {
+            pid: Some(42),
+            bundle_id: Some("us.zoom.xos".into()),
+            app_name: None,
+        };
+        assert_eq!(
+            to_json(event),
+            json!({
+                "type": "menu_bar_owner_changed",
+                "pid": 42,
+                "bundle_id": "us.zoom.xos",
+                "app_name": null,
+            })
+        );
+    }
+
+    #[test]
+    fn display_and_mode_events_have_a_stable_shape() {
+        assert_eq!(
+            to_json(BroadcastEvent::DisplayAdded { display_uuid: "A".into() }),
+            json!({ "type": "display_added", "display_uuid": "A" })
+        );
+        assert_eq!(
+            to_json(BroadcastEvent::DisplayRemoved { display_uuid: "A".into() }),
+            json!({ "type": "display_removed", "display_uuid": "A" })
+        );
+        assert_eq!(
+            to_json(BroadcastEvent::ModeChanged {
+                mode: Mode::SpacePaused,
+                active: true,
+                space_id: Some(SpaceId::new(3)),
+            }),
+            json!({ "type": "mode_changed", "mode": "space_paused", "active": true, "space_id": 3 })
+        );
+    }
+
+    #[test]
+    fn delivery_applies_pauses_in_order_with_the_events() {
+        let (tx, mut rx) = channel();
+        let managed = |pid| BroadcastEvent::WindowManaged {
+            window_id: WindowId::new(pid, 1),
+            floating: false,
+        };
+        let mode = |active| BroadcastEvent::ModeChanged {
+            mode: Mode::Presentation,
+            active,
+            space_id: None,
+        };
+        tx.send(managed(1));
+        tx.send(mode(true));
+        tx.set_paused(true);
+        tx.send(managed(2));
+        tx.set_paused(false);
+        tx.send(mode(false));
+        tx.set_hooks_paused(true);
+        tx.send(managed(3));
+
+        let mut delivery = Delivery::default();
+        let mut delivered = Vec::new();
+        while let Ok((_, message)) = rx.try_recv() {
+            if let Some((event, run_hooks)) = delivery.accept(message) {
+                delivered.push((to_json(event)["type"].clone(), run_hooks));
+            }
+        }
+        assert_eq!(delivered, [
+            (json!("window_managed"), true),
+            (json!("mode_changed"), true),
+            (json!("mode_changed"), true),
+            (json!("window_managed"), false),
+        ]);
+    }
+
+    #[test]
+    fn update_available_has_a_stable_shape() {
+        assert_eq!(
+            to_json(BroadcastEvent::UpdateAvailable {
+                current_version: "0.2.0".into(),
+                latest_version: "0.3.0".into(),
+                url: "https://example.com".into(),
+            }),
+            json!({
+                "type": "update_available",
+                "current_version": "0.2.0",
+                "latest_version": "0.3.0",
+                "url": "https://example.com",
+            })
+        );
+    }
+
+    #[test]
+    fn workspace_events_have_a_stable_shape() {
+        let event = to_json(BroadcastEvent::WorkspaceRenamed {
+            workspace_id: VirtualWorkspaceId::default(),
+            previous_name: "one".into(),
+            workspace_name: "web".into(),
+            space_id: SpaceId::new(1),
+            display_uuid: None,
+        });
+        let mut keys: Vec<&str> = event.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, [
+            "display_uuid",
+            "previous_name",
+            "space_id",
+            "type",
+            "workspace_id",
+            "workspace_name"
+        ]);
+        assert_eq!(event["type"], "workspace_renamed");
+        assert_eq!(event["previous_name"], "one");
+    }
+}
@@ -1,10 +1,12 @@
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::actor::{self, reactor};
-use crate::common::config::{Config, ConfigCommand};
+use crate::common::config::validate::{self, ValidationReport};
+use crate::common::config::{Config, ConfigCommand, OuterGaps, set_value_at_path};
+use crate::sys::power::{self, PowerSource};
 
 pub type Sender = actor::Sender<Event>;
 pub type Receiver = actor::Receiver<Event>;
@@ -19,12 +21,24 @@ pub enum Event {
         #[serde(skip)]
         response: r#continue::Sender<Result<(), String>>,
     },
+    /// Checks `source`, or the file the config was loaded from, without applying it.
+    #[serde(skip)]
+    ValidateConfig {
+        source: Option<String>,
+        #[serde(skip)]
+        response: r#continue::Sender<ValidationReport>,
+    },
+    PowerSourceChanged(PowerSource),
 }
 
 pub struct ConfigActor {
+    /// The config as loaded or edited, without any power profile applied.
     config: Config,
     reactor_tx: reactor::Sender,
     config_path: PathBuf,
+    power_source: PowerSource,
+    /// Whether the reactor was last sent `config` with a profile applied.
+    profile_applied: bool,
 }
 
 impl ConfigActor {
@@ -38,13 +52,19 @@ impl ConfigActor {
         config_path: PathBuf,
     ) -> Sender {
         let (tx, rx) = actor::channel();
+        let power_tx = tx.clone();
         std::thread::Builder::new()
             .name("config".to_string())
             .spawn(move || {
+                power::watch_power_source(move |source| {
+                    power_tx.send(Event::PowerSourceChanged(source))
+                });
                 let actor = ConfigActor {
                     config,
                     reactor_tx,
                     config_path,
+                    power_source: power::current_power_source(),
+                    profile_applied: false,
                 };
                 crate::sys::executor::Executor::run(actor.run(rx));
             })
@@ -53,6 +73,10 @@ impl ConfigActor {
     }
 
     async fn run(mut self, mut events: Receiver) {
+        info!(power_source = ?self.power_source, "Config actor started");
+        if !self.config.settings.power_profiles.for_source(self.power_source).is_empty() {
+            self.send_to_reactor();
+        }
         while let Some((_span, event)) = events.recv().await {
             match event {
                 Event::QueryConfig(resp) => {
@@ -63,10 +87,48 @@ impl ConfigActor {
                     let res = self.handle_config_command(cmd);
                     let _ = response.send(res);
                 }
+                Event::ValidateConfig { source, response } => {
+                    let report = match source {
+                        Some(source) => ValidationReport {
+                            path: None,
+                            diagnostics: validate::validate_source(&source),
+                        },
+                        None => validate::validate_file(&self.config_path),
+                    };
+                    let _ = response.send(report);
+                }
+                Event::PowerSourceChanged(source) => self.handle_power_source_changed(source),
             }
         }
     }
 
+    fn handle_power_source_changed(&mut self, source: PowerSource) {
+        // The notification also fires for battery level changes.
+        if source == self.power_source {
+            return;
+        }
+        info!(?source, "Power source changed");
+        self.power_source = source;
+        let profiles = &self.config.settings.power_profiles;
+        if self.profile_applied || !profiles.for_source(source).is_empty() {
+            self.send_to_reactor();
+        }
+    }
+
+    /// Sends the config with the current power source's profile applied.
+    fn send_to_reactor(&mut self) {
+        let config = match self.config.with_power_profile(self.power_source) {
+            Ok(config) => config,
+            Err(e) => {
+                warn!("Ignoring power profile: {e}");
+                self.config.clone()
+            }
+        };
+        self.profile_applied =
+            !self.config.settings.power_profiles.for_source(self.power_source).is_empty();
+        self.reactor_tx.send(reactor::Event::ConfigUpdated(config));
+    }
+
     fn handle_config_query(&self) -> Config { self.config.clone() }
 
     fn handle_config_command(&mut self, cmd: ConfigCommand) -> Result<(), String> {
@@ -171,6 +233,41 @@ impl ConfigActor {
                     errors.push("Invalid inner gap values. All values must be >= 0.0".to_string());
                 }
             }
+            ConfigCommand::SetExternalBar {
+                display,
+                top,
+                left,
+                bottom,
+                right,
+            } => {
+                if [top, left, bottom, right].into_iter().all(|v| v >= 0.0) {
+                    let bar = &mut new_config.settings.layout.external_bar;
+                    match &display {
+                        Some(uuid) => {
+                            bar.per_display.insert(uuid.clone(), OuterGaps {
+                                top,
+                                left,
+                                bottom,
+                                right,
+                            });
+                        }
+                        None => {
+                            bar.top = top;
+                            bar.left = left;
+                            bar.bottom = bottom;
+                            bar.right = right;
+                        }
+                    }
+                    config_changed = true;
+                    info!(
+                        "Updated external bar reservation for {:?} to: top={}, left={}, bottom={}, right={}",
+                        display, top, left, bottom, right
+                    );
+                } else {
+                    errors
+                        .push("Invalid external bar values. All values must be >= 0.0".to_string());
+                }
+            }
             ConfigCommand::SetWorkspaceNames(names) => {
                 if names.len() <= 32 {
                     new_config.virtual_workspaces.workspace_names = names.clone();
@@ -183,45 +280,20 @@ impl ConfigActor {
 
             ConfigCommand::Set { key, value } => match serde_json::to_value(&new_config) {
                 Ok(mut cfg_val) => {
-                    let parts: Vec<&str> = key.split('.').collect();
-                    if parts.is_empty() {
-                        errors.push("Empty config key provided".to_string());
+                    if let Err(e) = set_value_at_path(&mut cfg_val, &key, value.clone()) {
+                        errors.push(e);
                     } else {
-                        let mut cur = &mut cfg_val;
-                        let mut failed = false;
-                        for (i, part) in parts.iter().enumerate() {
-                            if i + 1 == parts.len() {
-                                if let Some(obj) = cur.as_object_mut() {
-                                    obj.insert(part.to_string(), value.clone());
-                                } else {
-                                    errors.push(format!("Invalid config path: {}", key));
-                                    failed = true;
-                                }
-                            } else if let Some(obj) = cur.as_object_mut() {
-                                if !obj.contains_key(*part) {
-                                    obj.insert(part.to_string(), serde_json::json!({}));
-                                }
-                                cur = obj.get_mut(*part).unwrap();
-                            } else {
-                                errors.push(format!("Invalid config path: {}", key));
-                                failed = true;
-                                break;
+                        match serde_json::from_value::<Config>(cfg_val) {
+                            Ok(cfg2) => {
+                                new_config = cfg2;
+                                config_changed = true;
+                                info!("Updated {} to {}", key, value);
                             }
-                        }
-
-                        if !failed {
-                            match serde_json::from_value::<Config>(cfg_val) {
-                                Ok(cfg2) => {
-                                    new_config = cfg2;
-                                    config_changed = true;
-                                    info!("Updated {} to {}", key, value);
-                                }
-                                Err(e) => {
-                                    errors.push(format!(
-                                        "Failed to deserialize config after setting '{}': {}",
-                                        key, e
-                                    ));
-                                }
+                            Err(e) => {
+                                errors.push(format!(
+                                    "Failed to deserialize config after setting '{}': {}",
+                                    key, e
+                                ));
                             }
                         }
                     }
@@ -269,9 +341,12 @@ impl ConfigActor {
                 return Err(validation_issues.join("; "));
             }
 
+            if new_config.settings.logging != self.config.settings.logging {
+                crate::common::log::apply_settings(&new_config.settings.logging);
+            }
             self.config = new_config;
 
-            self.reactor_tx.send(reactor::Event::ConfigUpdated(self.config.clone()));
+            self.send_to_reactor();
         }
 
         Ok(())
@@ -158,6 +158,8 @@ impl ConfigWatcher {
             match msg {
                 ConfigEvent::ApplyConfig { response, .. } => std::mem::forget(response),
                 ConfigEvent::QueryConfig(response) => std::mem::forget(response),
+                ConfigEvent::ValidateConfig { response, .. } => std::mem::forget(response),
+                ConfigEvent::PowerSourceChanged(_) => {}
             }
             return Err("Config actor unavailable".to_string());
         }
@@ -6,7 +6,7 @@ use std::rc::Rc;
 use objc2::exception;
 use objc2_app_kit::{
     NSEvent, NSEventPhase, NSEventType, NSMainMenuWindowLevel, NSPopUpMenuWindowLevel,
-    NSTouchPhase, NSTouchType, NSWindowLevel,
+    NSRunningApplication, NSTouchPhase, NSTouchType, NSWindowLevel,
 };
 use objc2_core_foundation::{CGPoint, CGRect};
 use objc2_core_graphics::{
@@ -15,19 +15,24 @@ use objc2_core_graphics::{
 };
 use tracing::{debug, error, trace, warn};
 
-use super::reactor::{self, Event};
-use super::stack_line;
+use super::reactor::{self, Event, ModifierDragMode};
+use super::{kill_switch, stack_line};
 use crate::actor;
 use crate::actor::wm_controller::{self, WmCommand, WmEvent};
+use crate::common::action_trace;
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{Config, HapticPattern, LayoutMode};
+use crate::common::config::{
+    Config, HapticPattern, HotkeyResolution, LayoutMode, ScreenCorner, TitlebarAction,
+    TitlebarClick,
+};
 use crate::common::log::trace_misc;
 use crate::layout_engine::LayoutCommand as LC;
+use crate::sys::app::{NSRunningApplicationExt, pid_t};
 use crate::sys::event::{self, Hotkey, KeyCode, MouseState, set_mouse_state};
 use crate::sys::geometry::CGRectExt;
 use crate::sys::hotkey::{
-    Modifiers, is_modifier_key, key_code_from_event, modifier_flag_for_key,
-    modifiers_from_flags_with_keys,
+    Modifiers, character_key_code, is_modifier_key, key_code_from_event, modifier_flag_for_key,
+    modifiers_from_flags, modifiers_from_flags_with_keys,
 };
 use crate::sys::screen::{CoordinateConverter, SpaceId};
 use crate::sys::window_server::{self, WindowServerId, window_level};
@@ -55,6 +60,18 @@ pub enum Request {
     ConfigUpdated(Config),
     LayoutModesChanged(Vec<(SpaceId, crate::common::config::LayoutMode)>),
     SetLowPowerMode(bool),
+    /// Display bounds where a fullscreen app has paused rift's hotkeys and mouse handling.
+    SetPausedDisplays(Vec<CGRect>),
+    /// Recreate the tap even if the mask is unchanged. macOS can silently invalidate
+    /// taps across Fast User Switching without sending a tap-disabled event.
+    ReinstallTap,
+    /// Remove the tap while the screen is locked, asleep or showing the screensaver,
+    /// so nothing of ours sees input meant for the lock screen; `false` puts it back.
+    Suspend(bool),
+    /// Park the pointer in the configured corner of its display and hide it.
+    BanishPointer,
+    /// Turn hiding the pointer while typing on or off.
+    TogglePointerHiding,
 }
 
 pub struct EventTap {
@@ -83,8 +100,11 @@ struct State {
     event_processing_enabled: bool,
     focus_follows_mouse_enabled: bool,
     stack_line_enabled: bool,
+    /// Scrolling over a stack line indicator switches workspaces.
+    stack_line_scroll: bool,
     disable_hotkey_active: bool,
     low_power_mode: bool,
+    paused_displays: Vec<CGRect>,
     pressed_keys: HashSet<KeyCode>,
     current_flags: CGEventFlags,
     screen_spaces: Vec<(CGRect, SpaceId)>,
@@ -93,6 +113,24 @@ struct State {
     last_mouse_move_timestamp: u64,
     window_level_cache: HashMap<WindowServerId, CachedWindowLevel>,
     window_level_cache_last_prune_at: u64,
+    hide_pointer_while_typing: bool,
+    /// The pointer was sent to a corner by `banish_pointer` and is waiting for
+    /// the mouse to move.
+    pointer_banished: bool,
+    /// Whether typing hides the pointer in the last app keys went to, by pid.
+    pointer_hides_in_app: Option<(pid_t, bool)>,
+    titlebar_clicks: HashMap<TitlebarClick, TitlebarAction>,
+    titlebar_height: f64,
+    /// A title bar click was handled on mouse down, so its mouse up is not
+    /// the app's either.
+    swallow_mouse_up: bool,
+    drag_modifiers: Option<Modifiers>,
+    resize_drag_modifiers: Option<Modifiers>,
+    /// A mouse button went down with the modifier drag modifiers held; the drag
+    /// events that follow move or resize the window instead of reaching its app.
+    modifier_dragging: bool,
+    /// The tap is removed until [`Request::Suspend`] brings it back.
+    suspended: bool,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -114,8 +152,10 @@ impl Default for State {
             event_processing_enabled: false,
             focus_follows_mouse_enabled: true,
             stack_line_enabled: false,
+            stack_line_scroll: false,
             disable_hotkey_active: false,
             low_power_mode: power::is_low_power_mode_enabled(),
+            paused_displays: Vec::new(),
             pressed_keys: HashSet::default(),
             current_flags: CGEventFlags::empty(),
             screen_spaces: Vec::new(),
@@ -124,6 +164,16 @@ impl Default for State {
             last_mouse_move_timestamp: 0,
             window_level_cache: HashMap::default(),
             window_level_cache_last_prune_at: 0,
+            hide_pointer_while_typing: false,
+            pointer_banished: false,
+            pointer_hides_in_app: None,
+            titlebar_clicks: HashMap::default(),
+            titlebar_height: 0.0,
+            swallow_mouse_up: false,
+            drag_modifiers: None,
+            resize_drag_modifiers: None,
+            modifier_dragging: false,
+            suspended: false,
         }
     }
 }
@@ -263,6 +313,14 @@ impl EventTap {
         state.stack_line_enabled && self.stack_line_tx.is_some()
     }
 
+    #[inline]
+    fn stack_line_scroll_enabled(&self, state: &State) -> bool {
+        state.event_processing_enabled
+            && state.stack_line_enabled
+            && state.stack_line_scroll
+            && self.stack_line_tx.is_some()
+    }
+
     #[inline]
     fn focus_follows_mouse_handler_enabled(state: &State) -> bool {
         state.focus_follows_mouse_config_enabled && state.focus_follows_mouse_enabled
@@ -307,14 +365,19 @@ impl EventTap {
     }
 
     fn keyboard_handlers_enabled(&self) -> bool {
-        self.disable_hotkey.borrow().is_some() || !self.hotkeys.borrow().is_empty()
+        kill_switch::is_armed()
+            || self.disable_hotkey.borrow().is_some()
+            || !self.hotkeys.borrow().is_empty()
+            || self.state.borrow().hide_pointer_while_typing
     }
 
     fn mouse_move_handlers_enabled(&self) -> bool {
         let state = self.state.borrow();
         state.event_processing_enabled
             && (self.stack_line_hover_enabled(&state)
-                || Self::focus_follows_mouse_handler_enabled(&state))
+                || Self::focus_follows_mouse_handler_enabled(&state)
+                || state.hide_pointer_while_typing
+                || state.pointer_banished)
     }
 
     fn desired_event_mask(&self) -> CGEventMask {
@@ -322,6 +385,7 @@ impl EventTap {
             self.gesture_handlers_enabled(),
             self.keyboard_handlers_enabled(),
             self.mouse_move_handlers_enabled(),
+            self.stack_line_scroll_enabled(&self.state.borrow()),
         )
     }
 
@@ -350,6 +414,9 @@ impl EventTap {
     }
 
     fn rebuild_event_tap_mask_if_needed(self: &Rc<Self>) {
+        if self.state.borrow().suspended {
+            return;
+        }
         let next_mask = self.desired_event_mask();
         let current_mask = *self.event_mask.borrow();
         if next_mask == current_mask {
@@ -366,6 +433,19 @@ impl EventTap {
         *self.event_mask.borrow_mut() = next_mask;
     }
 
+    fn reinstall_tap(self: &Rc<Self>) {
+        let mask = self.desired_event_mask();
+        // Drop the old tap first so two taps never see the same event.
+        drop(self.tap.borrow_mut().take());
+        match self.create_tap_with_mask(mask) {
+            Some(tap) => {
+                *self.tap.borrow_mut() = Some(tap);
+                *self.event_mask.borrow_mut() = mask;
+            }
+            None => error!("Failed to reinstall event tap; hotkeys will not work"),
+        }
+    }
+
     pub fn new(
         config: Config,
         events_tx: reactor::Sender,
@@ -383,17 +463,27 @@ impl EventTap {
         state.mouse_hides_on_focus = config.settings.mouse_hides_on_focus;
         state.focus_follows_mouse_config_enabled = config.settings.focus_follows_mouse;
         state.stack_line_enabled = config.settings.ui.stack_line.enabled;
+        state.stack_line_scroll = config.settings.ui.stack_line.scroll_workspaces.enabled;
         state.default_layout_mode = config.settings.layout.mode;
+        state.hide_pointer_while_typing = config.settings.pointer.hide_while_typing;
+        state.titlebar_clicks = config.settings.titlebar.bindings();
+        state.titlebar_height = config.settings.titlebar.height;
+        state.drag_modifiers = config.settings.modifier_drag.move_modifiers();
+        state.resize_drag_modifiers = config.settings.modifier_drag.resize_modifiers();
         state.disable_hotkey_active = disable_hotkey
             .as_ref()
             .map(|target| state.compute_disable_hotkey_active(target))
             .unwrap_or(false);
         let event_mask = build_event_mask(
             swipe.is_some() || scroll.is_some(),
-            disable_hotkey.is_some(),
+            kill_switch::is_armed() || disable_hotkey.is_some() || state.hide_pointer_while_typing,
             state.event_processing_enabled
                 && ((state.stack_line_enabled && stack_line_tx.is_some())
                     || Self::focus_follows_mouse_handler_enabled(&state)),
+            state.event_processing_enabled
+                && state.stack_line_enabled
+                && state.stack_line_scroll
+                && stack_line_tx.is_some(),
         );
         EventTap {
             config: RefCell::new(config),
@@ -425,13 +515,13 @@ impl EventTap {
             return;
         }
 
-        if this.state.borrow().mouse_hides_on_focus {
-            if let Err(e) = window_server::allow_hide_mouse() {
-                error!(
-                    "Could not enable mouse hiding: {e:?}. \
-                    mouse_hides_on_focus will have no effect."
-                );
-            }
+        // Needed for mouse_hides_on_focus and for the pointer commands, which can
+        // be bound whatever the config says.
+        if let Err(e) = window_server::allow_hide_mouse() {
+            error!(
+                "Could not enable mouse hiding: {e:?}. \
+                mouse_hides_on_focus and pointer hiding will have no effect."
+            );
         }
 
         while let Some((span, request)) = requests_rx.recv().await {
@@ -525,12 +615,19 @@ impl EventTap {
                 let mouse_hides_on_focus = new_config.settings.mouse_hides_on_focus;
                 let focus_follows_mouse_config_enabled = new_config.settings.focus_follows_mouse;
                 let stack_line_enabled = new_config.settings.ui.stack_line.enabled;
+                let stack_line_scroll = new_config.settings.ui.stack_line.scroll_workspaces.enabled;
                 let default_layout_mode = new_config.settings.layout.mode;
+                let hide_pointer_while_typing = new_config.settings.pointer.hide_while_typing;
+                let titlebar_clicks = new_config.settings.titlebar.bindings();
+                let titlebar_height = new_config.settings.titlebar.height;
+                let drag_modifiers = new_config.settings.modifier_drag.move_modifiers();
+                let resize_drag_modifiers = new_config.settings.modifier_drag.resize_modifiers();
                 let disable_hotkey = new_config
                     .settings
                     .focus_follows_mouse_disable_hotkey
                     .clone()
                     .and_then(|spec| spec.to_hotkey());
+                kill_switch::set_hotkey(new_config.settings.panic_hotkey());
                 *self.config.borrow_mut() = new_config;
                 *self.disable_hotkey.borrow_mut() = disable_hotkey;
                 {
@@ -538,7 +635,14 @@ impl EventTap {
                     state.mouse_hides_on_focus = mouse_hides_on_focus;
                     state.focus_follows_mouse_config_enabled = focus_follows_mouse_config_enabled;
                     state.stack_line_enabled = stack_line_enabled;
+                    state.stack_line_scroll = stack_line_scroll;
                     state.default_layout_mode = default_layout_mode;
+                    state.hide_pointer_while_typing = hide_pointer_while_typing;
+                    state.pointer_hides_in_app = None;
+                    state.titlebar_clicks = titlebar_clicks;
+                    state.titlebar_height = titlebar_height;
+                    state.drag_modifiers = drag_modifiers;
+                    state.resize_drag_modifiers = resize_drag_modifiers;
                     let prev_active = state.disable_hotkey_active;
                     state.disable_hotkey_active = self
                         .disable_hotkey
@@ -578,6 +682,71 @@ impl EventTap {
                     state.last_mouse_move_timestamp = 0;
                 }
             }
+            Request::SetPausedDisplays(displays) => {
+                debug!("fullscreen pause covers {} displays", displays.len());
+                state.paused_displays = displays;
+                state.reset(true);
+            }
+            Request::ReinstallTap => {
+                state.reset(true);
+                if state.suspended {
+                    return;
+                }
+                drop(state);
+                self.reinstall_tap();
+                return;
+            }
+            Request::Suspend(suspended) => {
+                if state.suspended == suspended {
+                    return;
+                }
+                state.suspended = suspended;
+                state.reset(true);
+                state.pressed_keys.clear();
+                state.modifier_dragging = false;
+                state.swallow_mouse_up = false;
+                if suspended {
+                    state.show_pointer();
+                    state.pointer_banished = false;
+                    drop(state);
+                    debug!("Removing event tap while the screen is locked");
+                    drop(self.tap.borrow_mut().take());
+                } else {
+                    drop(state);
+                    debug!("Reinstalling event tap after unlock");
+                    self.reinstall_tap();
+                }
+                return;
+            }
+            Request::BanishPointer => {
+                let corner = self.config.borrow().settings.pointer.banish_corner;
+                let cursor = event::current_cursor_location().ok();
+                let screen = cursor
+                    .and_then(|cursor| state.screens.iter().find(|s| s.contains(cursor)))
+                    .or(state.screens.first())
+                    .copied();
+                let Some(screen) = screen else {
+                    warn!("No display to banish the pointer to");
+                    return;
+                };
+                if let Err(e) = event::warp_mouse(banish_point(screen, corner)) {
+                    warn!("Failed to warp mouse: {e:?}");
+                } else {
+                    state.above_window = (None, NSWindowLevel::MIN);
+                }
+                state.hide_pointer();
+                state.pointer_banished = true;
+                should_rebuild_mask = true;
+            }
+            Request::TogglePointerHiding => {
+                let enabled = !state.hide_pointer_while_typing;
+                debug!(enabled, "Toggled pointer hiding");
+                state.hide_pointer_while_typing = enabled;
+                if !enabled && !state.pointer_banished {
+                    state.show_pointer();
+                }
+                should_rebuild_mask = true;
+            }
         }
         drop(state);
 
@@ -618,6 +787,9 @@ impl EventTap {
                 && nsevent.r#type() == NSEventType::Gesture
             {
                 let cursor = CGEvent::location(Some(event));
+                if state.is_paused_at(cursor) {
+                    return true;
+                }
                 let mode = state.layout_mode_at_point(cursor).unwrap_or(state.default_layout_mode);
                 let is_scrolling_mode = matches!(mode, LayoutMode::Scrolling);
                 if is_scrolling_mode && let Some(handler) = scroll_handler.as_ref() {
@@ -644,11 +816,13 @@ impl EventTap {
             }
         }
 
+        let paused_here = state.is_paused_at(CGEvent::location(Some(event)));
+
         match event_type {
             CGEventType::LeftMouseDown | CGEventType::RightMouseDown => {
                 set_mouse_state(MouseState::Down);
 
-                if let Some(tx) = &self.stack_line_tx {
+                if !paused_here && let Some(tx) = &self.stack_line_tx {
                     let loc = CGEvent::location(Some(event));
                     let _ = tx.try_send(stack_line::Event::MouseDown(loc));
                 }
@@ -672,16 +846,49 @@ impl EventTap {
             return true;
         }
 
+        if paused_here {
+            trace!("Display paused for a fullscreen app, ignoring {:?}", event_type);
+            return true;
+        }
+
         if state.hidden {
             debug!("Showing mouse");
             if let Err(e) = event::show_mouse() {
                 warn!("Failed to show mouse: {e:?}");
             }
             state.hidden = false;
+            state.pointer_banished = false;
         }
         match event_type {
+            CGEventType::LeftMouseDown | CGEventType::RightMouseDown => {
+                if let Some((wsid, action)) = state.titlebar_action(event_type, event) {
+                    debug!(?wsid, ?action, "Handling title bar click");
+                    _ = self.events_tx.send(Event::TitlebarClicked(wsid, action));
+                    state.swallow_mouse_up = true;
+                    return false;
+                }
+                if let Some((wsid, mode)) = state.modifier_drag(event_type, event) {
+                    debug!(?wsid, ?mode, "Starting modifier drag");
+                    let loc = CGEvent::location(Some(event));
+                    _ = self.events_tx.send(Event::ModifierDragStarted(wsid, loc, mode));
+                    state.modifier_dragging = true;
+                    state.swallow_mouse_up = true;
+                    return false;
+                }
+            }
+            CGEventType::LeftMouseDragged | CGEventType::RightMouseDragged => {
+                if state.modifier_dragging {
+                    let loc = CGEvent::location(Some(event));
+                    _ = self.events_tx.send(Event::ModifierDragged(loc));
+                    return false;
+                }
+            }
             CGEventType::RightMouseUp | CGEventType::LeftMouseUp => {
                 _ = self.events_tx.send(Event::MouseUp);
+                state.modifier_dragging = false;
+                if replace(&mut state.swallow_mouse_up, false) {
+                    return false;
+                }
             }
             CGEventType::MouseMoved => {
                 let loc = CGEvent::location(Some(event));
@@ -710,6 +917,15 @@ impl EventTap {
                     }
                 }
             }
+            CGEventType::ScrollWheel => {
+                if state.stack_line_scroll
+                    && let Some(tx) = &self.stack_line_tx
+                    && let Some(nsevent) = NSEvent::eventWithCGEvent(event)
+                {
+                    let loc = CGEvent::location(Some(event));
+                    let _ = tx.try_send(stack_line::Event::Scroll(loc, nsevent.scrollingDeltaY()));
+                }
+            }
             _ => (),
         }
 
@@ -997,7 +1213,15 @@ impl EventTap {
         self.refresh_disable_hotkey_state(state);
 
         if event_type == CGEventType::KeyDown {
+            // Checked before anything that could suspend hotkeys, so it always works.
+            if kill_switch::check_key_down(event) {
+                return false;
+            }
             if let Some(key_code) = key_code_opt {
+                let key_code = match self.config.borrow().settings.hotkey_resolution {
+                    HotkeyResolution::Physical => key_code,
+                    HotkeyResolution::Character => character_key_code(key_code),
+                };
                 let hotkey = Hotkey::new(
                     modifiers_from_flags_with_keys(state.current_flags, &state.pressed_keys),
                     key_code,
@@ -1006,18 +1230,68 @@ impl EventTap {
                     debug!(?hotkey, "Hotkey triggered but no WM sender available");
                     return true;
                 };
+                // Key events carry the pointer location, which picks the display.
+                if state.is_paused_at(CGEvent::location(Some(event))) {
+                    trace!(?hotkey, "Hotkeys suspended on a display paused for fullscreen");
+                    return true;
+                }
                 let bindings = self.hotkeys.borrow();
                 if let Some(commands) = bindings.get(&hotkey) {
+                    let _action = action_trace::start("hotkey", &hotkey).entered();
                     for cmd in commands {
                         wm_sender.send(WmEvent::Command(cmd.clone()));
                     }
                     return false;
                 }
             }
+            self.hide_pointer_for_typing(event, state);
         }
 
         true
     }
+
+    /// Hides the pointer for a keystroke that types something, unless the app
+    /// receiving it is excluded in `settings.pointer`.
+    fn hide_pointer_for_typing(&self, event: &CGEvent, state: &mut State) {
+        if !state.hide_pointer_while_typing || state.hidden || !state.event_processing_enabled {
+            return;
+        }
+        // Shortcuts like Cmd+C don't mean the user has started typing.
+        let flags = CGEvent::flags(Some(event));
+        if flags.intersects(CGEventFlags::MaskCommand | CGEventFlags::MaskControl) {
+            return;
+        }
+        let pid = CGEvent::integer_value_field(Some(event), CGEventField::EventTargetUnixProcessID);
+        let pid = pid as pid_t;
+        let hides = match state.pointer_hides_in_app {
+            Some((cached, hides)) if cached == pid => hides,
+            _ => {
+                let bundle_id = NSRunningApplication::with_process_id(pid)
+                    .and_then(|app| app.bundle_id())
+                    .map(|id| id.to_string());
+                let hides = self.config.borrow().settings.pointer.hides_in(bundle_id.as_deref());
+                state.pointer_hides_in_app = Some((pid, hides));
+                hides
+            }
+        };
+        if hides {
+            state.hide_pointer();
+        }
+    }
+}
+
+/// The point just inside `corner` of `screen`, so the pointer stays on that display.
+fn banish_point(screen: CGRect, corner: ScreenCorner) -> CGPoint {
+    let left = screen.origin.x;
+    let top = screen.origin.y;
+    let right = left + screen.size.width - 1.0;
+    let bottom = top + screen.size.height - 1.0;
+    match corner {
+        ScreenCorner::TopLeft => CGPoint::new(left, top),
+        ScreenCorner::TopRight => CGPoint::new(right, top),
+        ScreenCorner::BottomLeft => CGPoint::new(left, bottom),
+        ScreenCorner::BottomRight => CGPoint::new(right, bottom),
+    }
 }
 
 unsafe extern "C-unwind" fn mouse_callback(
@@ -1067,6 +1341,59 @@ impl State {
         true
     }
 
+    /// The window and action for a mouse down that lands on a title bar with a
+    /// click bound in `[settings.titlebar.clicks]`.
+    fn titlebar_action(
+        &self,
+        event_type: CGEventType,
+        event: &CGEvent,
+    ) -> Option<(WindowServerId, TitlebarAction)> {
+        if self.titlebar_clicks.is_empty() {
+            return None;
+        }
+        let click_state =
+            CGEvent::integer_value_field(Some(event), CGEventField::MouseEventClickState);
+        let click = TitlebarClick {
+            modifiers: modifiers_from_flags(self.current_flags),
+            right: matches!(event_type, CGEventType::RightMouseDown),
+            double: click_state == 2,
+        };
+        let action = *self.titlebar_clicks.get(&click)?;
+        let wsid = window_from_mouse_event(event)?;
+        let window = window_server::get_window(wsid).filter(|window| window.layer == 0)?;
+        let loc = CGEvent::location(Some(event));
+        let on_titlebar =
+            window.frame.contains(loc) && loc.y - window.frame.origin.y <= self.titlebar_height;
+        on_titlebar.then_some((wsid, action))
+    }
+
+    /// The window under a mouse down made with the `[settings.modifier_drag]`
+    /// modifiers held, and whether dragging it moves or resizes it.
+    fn modifier_drag(
+        &self,
+        event_type: CGEventType,
+        event: &CGEvent,
+    ) -> Option<(WindowServerId, ModifierDragMode)> {
+        let modifiers = modifiers_from_flags(self.current_flags);
+        let mode = if self.resize_drag_modifiers == Some(modifiers) {
+            ModifierDragMode::Resize
+        } else if self.drag_modifiers == Some(modifiers) {
+            match event_type {
+                CGEventType::RightMouseDown => ModifierDragMode::Resize,
+                _ => ModifierDragMode::Move,
+            }
+        } else {
+            return None;
+        };
+        let wsid = window_from_mouse_event(event)?;
+        window_server::get_window(wsid).filter(|window| window.layer == 0)?;
+        Some((wsid, mode))
+    }
+
+    fn is_paused_at(&self, loc: CGPoint) -> bool {
+        self.paused_displays.iter().any(|bounds| bounds.contains(loc))
+    }
+
     fn layout_mode_at_point(&self, loc: CGPoint) -> Option<crate::common::config::LayoutMode> {
         self.screen_spaces
             .iter()
@@ -1183,6 +1510,28 @@ impl State {
         new_window
     }
 
+    fn hide_pointer(&mut self) {
+        if self.hidden {
+            return;
+        }
+        debug!("Hiding mouse");
+        if let Err(e) = event::hide_mouse() {
+            warn!("Failed to hide mouse: {e:?}");
+        }
+        self.hidden = true;
+    }
+
+    fn show_pointer(&mut self) {
+        if !self.hidden {
+            return;
+        }
+        debug!("Showing mouse");
+        if let Err(e) = event::show_mouse() {
+            warn!("Failed to show mouse: {e:?}");
+        }
+        self.hidden = false;
+    }
+
     fn reset(&mut self, enabled: bool) {
         if enabled {
             self.above_window = (None, NSWindowLevel::MIN);
@@ -1276,6 +1625,7 @@ fn build_event_mask(
     gestures_enabled: bool,
     keyboard_enabled: bool,
     mouse_move_enabled: bool,
+    scroll_wheel_enabled: bool,
 ) -> CGEventMask {
     let mut m: u64 = 0;
     let add = |m: &mut u64, ty: CGEventType| *m |= 1u64 << (ty.0 as u64);
@@ -1293,6 +1643,9 @@ fn build_event_mask(
     if mouse_move_enabled {
         add(&mut m, CGEventType::MouseMoved);
     }
+    if scroll_wheel_enabled {
+        add(&mut m, CGEventType::ScrollWheel);
+    }
     if keyboard_enabled {
         for ty in [
             CGEventType::KeyDown,
@@ -1344,4 +1697,19 @@ mod tests {
             Some(crate::common::config::LayoutMode::Scrolling)
         );
     }
+
+    #[test]
+    fn banish_point_stays_inside_the_display() {
+        let screen = CGRect::new(
+            CGPoint::new(1440.0, -200.0),
+            objc2_core_foundation::CGSize::new(1920.0, 1080.0),
+        );
+        assert_eq!(
+            banish_point(screen, ScreenCorner::TopLeft),
+            CGPoint::new(1440.0, -200.0)
+        );
+        let bottom_right = banish_point(screen, ScreenCorner::BottomRight);
+        assert_eq!(bottom_right, CGPoint::new(3359.0, 879.0));
+        assert!(screen.contains(bottom_right));
+    }
 }
@@ -0,0 +1,30 @@
+//! Drives the reactor's periodic frame audit.
+//!
+//! Some apps move or resize their own windows without an accessibility
+//! notification, leaving the reactor's idea of the layout out of date. The
+//! reactor does the comparing; this only wakes it up. The interval is read at
+//! startup, while `enabled` is checked by the reactor on every tick so that
+//! turning the audit on or off takes effect on reload.
+
+use crate::actor::reactor::{self, Event};
+use crate::common::config::FrameAuditSettings;
+use crate::sys::timer::Timer;
+
+pub struct FrameAuditMonitor {
+    events_tx: reactor::Sender,
+    settings: FrameAuditSettings,
+}
+
+impl FrameAuditMonitor {
+    pub fn new(events_tx: reactor::Sender, settings: FrameAuditSettings) -> Self {
+        Self { events_tx, settings }
+    }
+
+    pub async fn run(self) {
+        let interval = self.settings.interval();
+        let mut timer = Timer::repeating(interval, interval);
+        while timer.next().await.is_some() {
+            self.events_tx.send(Event::AuditFrames);
+        }
+    }
+}
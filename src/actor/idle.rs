@@ -0,0 +1,29 @@
+//! Feeds the reactor how long the user has been away from the keyboard and mouse.
+//!
+//! macOS has no notification for this, so the HID idle time is polled. Rules
+//! live in the reactor so that a reload takes effect on the next poll; only
+//! the poll interval is fixed at startup.
+
+use crate::actor::reactor::{self, Event};
+use crate::common::config::IdleSettings;
+use crate::sys::idle::seconds_since_last_input;
+use crate::sys::timer::Timer;
+
+pub struct IdleMonitor {
+    events_tx: reactor::Sender,
+    settings: IdleSettings,
+}
+
+impl IdleMonitor {
+    pub fn new(events_tx: reactor::Sender, settings: IdleSettings) -> Self {
+        Self { events_tx, settings }
+    }
+
+    pub async fn run(self) {
+        let interval = self.settings.poll_interval();
+        let mut timer = Timer::repeating(interval, interval);
+        while timer.next().await.is_some() {
+            self.events_tx.send(Event::InputIdle(seconds_since_last_input()));
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! The panic hotkey: a way out when rift stops input from getting through.
+//!
+//! The binding lives in process-wide state instead of the event tap's hotkey
+//! table so that every tap rift installs can check it, including the consuming
+//! taps of overlays that otherwise see input before anything else. It starts
+//! out on [`Settings::default_panic_hotkey`] and only changes when a config
+//! supplies a usable replacement, so it is live even with no `[keys]` at all.
+//!
+//! [`Settings::default_panic_hotkey`]: crate::common::config::Settings::default_panic_hotkey
+
+use objc2_core_graphics::CGEvent;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tracing::warn;
+
+use crate::actor::wm_controller::{Sender as WmSender, WmEvent};
+use crate::common::config::Settings;
+use crate::sys::hotkey::{Hotkey, key_code_from_event, modifiers_from_flags};
+
+struct KillSwitch {
+    hotkey: Hotkey,
+    wm_sender: Option<WmSender>,
+}
+
+static KILL_SWITCH: Lazy<Mutex<KillSwitch>> = Lazy::new(|| {
+    Mutex::new(KillSwitch {
+        hotkey: Settings::default_panic_hotkey(),
+        wm_sender: None,
+    })
+});
+
+/// Connects the switch to the WM controller, which does the actual teardown.
+pub fn arm(wm_sender: WmSender) { KILL_SWITCH.lock().wm_sender = Some(wm_sender); }
+
+pub fn set_hotkey(hotkey: Hotkey) { KILL_SWITCH.lock().hotkey = hotkey; }
+
+pub fn is_armed() -> bool { KILL_SWITCH.lock().wm_sender.is_some() }
+
+/// Checks a key-down event against the panic hotkey and fires the switch if it
+/// matches. Returns true if it did, in which case the caller should swallow
+/// the event. Left and right modifiers are not told apart here.
+pub fn check_key_down(event: &CGEvent) -> bool {
+    let Some(key_code) = key_code_from_event(event) else {
+        return false;
+    };
+    let switch = KILL_SWITCH.lock();
+    let Some(wm_sender) = &switch.wm_sender else {
+        return false;
+    };
+    let modifiers = modifiers_from_flags(CGEvent::flags(Some(event)));
+    if switch.hotkey.key_code != key_code || switch.hotkey.modifiers.to_generic() != modifiers {
+        return false;
+    }
+    warn!(hotkey = %switch.hotkey, "Panic hotkey pressed");
+    wm_sender.send(WmEvent::KillSwitch);
+    true
+}
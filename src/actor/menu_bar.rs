@@ -1,17 +1,19 @@
 use std::path::Path;
 use std::process::Command as ProcessCommand;
 use std::sync::mpsc::{self, RecvTimeoutError};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use objc2::MainThreadMarker;
 use tokio::sync::mpsc::UnboundedSender;
 
 use crate::actor::{config, reactor};
 use crate::common::config::{Config, ConfigCommand};
+use crate::common::version::Release;
 use crate::layout_engine::LayoutCommand;
 use crate::model::VirtualWorkspaceId;
-use crate::model::server::{WindowData, WorkspaceData};
+use crate::model::server::{SpaceData, WindowData, WorkspaceData};
 use crate::sys::screen::SpaceId;
+use crate::ui::common::WorkspaceScroll;
 use crate::ui::menu_bar::{MenuAction, MenuIcon};
 use crate::{actor, common};
 
@@ -19,6 +21,11 @@ use crate::{actor, common};
 pub struct Update {
     pub active_space: SpaceId,
     pub active_space_is_activated: bool,
+    pub active_space_is_paused: bool,
+    /// Secure input is on, so no hotkey can fire.
+    pub secure_input: bool,
+    /// The space currently shown on each display.
+    pub displays: Vec<SpaceData>,
     pub workspaces: Vec<WorkspaceData>,
     pub active_workspace_idx: Option<u64>,
     pub active_workspace: Option<VirtualWorkspaceId>,
@@ -28,6 +35,8 @@ pub struct Update {
 pub enum Event {
     Update(Update),
     ConfigUpdated(Config),
+    /// A newer release is out; see [`crate::actor::update_check`].
+    UpdateAvailable(Release),
 }
 
 enum DebounceCommand {
@@ -46,6 +55,8 @@ pub struct Menu {
     mtm: MainThreadMarker,
     last_signature: Option<u64>,
     last_update: Option<Update>,
+    workspace_scroll: WorkspaceScroll,
+    available_release: Option<Release>,
 }
 
 pub type Sender = actor::Sender<Event>;
@@ -76,6 +87,8 @@ impl Menu {
             mtm,
             last_signature: None,
             last_update: None,
+            workspace_scroll: WorkspaceScroll::default(),
+            available_release: None,
         }
     }
 
@@ -111,6 +124,9 @@ impl Menu {
                                     let _ = debounce_tx.send(DebounceCommand::Arm);
                                 }
                                 Event::ConfigUpdated(cfg) => self.handle_config_updated(cfg),
+                                Event::UpdateAvailable(release) => {
+                                    self.handle_update_available(release)
+                                }
                             }
                         }
                         None => {
@@ -136,6 +152,7 @@ impl Menu {
         match event {
             Event::Update(update) => self.handle_update(update),
             Event::ConfigUpdated(cfg) => self.handle_config_updated(cfg),
+            Event::UpdateAvailable(release) => self.handle_update_available(release),
         }
     }
 
@@ -150,6 +167,9 @@ impl Menu {
         let sig = sig(
             update.active_space.get() as u64,
             update.active_space_is_activated,
+            update.active_space_is_paused,
+            update.secure_input,
+            &update.displays,
             update.active_workspace_idx,
             &update.workspaces,
             &update.windows,
@@ -163,6 +183,10 @@ impl Menu {
         icon.update(
             update.active_space,
             update.active_space_is_activated,
+            update.active_space_is_paused,
+            update.secure_input,
+            self.available_release.as_ref().map(|release| release.version.as_str()),
+            &update.displays,
             &update.workspaces,
             update.active_workspace,
             &update.windows,
@@ -188,6 +212,14 @@ impl Menu {
         }
     }
 
+    fn handle_update_available(&mut self, release: Release) {
+        self.available_release = Some(release);
+        self.last_signature = None;
+        if let Some(update) = self.last_update.take() {
+            self.handle_update(update);
+        }
+    }
+
     fn handle_action(&mut self, action: MenuAction) {
         match action {
             MenuAction::SetLayout(mode) => self
@@ -201,17 +233,50 @@ impl Menu {
             MenuAction::SwitchToWorkspace(workspace) => {
                 self.send_layout_command(LayoutCommand::SwitchToWorkspace(workspace));
             }
+            MenuAction::Scroll(delta_y) => {
+                let settings = &self.config.settings.ui.menu_bar.scroll_workspaces;
+                if let Some(command) =
+                    self.workspace_scroll.on_scroll(settings, delta_y, Instant::now())
+                {
+                    self.send_layout_command(command);
+                }
+            }
             MenuAction::ToggleSpaceActivated => {
                 self.reactor_tx.send(reactor::Event::Command(reactor::Command::Reactor(
                     reactor::ReactorCommand::ToggleSpaceActivated,
                 )));
             }
+            MenuAction::ToggleDisplayActivated(idx) => {
+                let Some(display) = self.last_update.as_ref().and_then(|u| u.displays.get(idx))
+                else {
+                    return;
+                };
+                self.reactor_tx.send(reactor::Event::Command(reactor::Command::Reactor(
+                    reactor::ReactorCommand::ToggleDisplayActivated {
+                        display_uuid: display.display_uuid.clone(),
+                    },
+                )));
+            }
+            MenuAction::SetSpacePaused(paused) => {
+                let command = if paused {
+                    reactor::ReactorCommand::PauseSpace { space_id: None }
+                } else {
+                    reactor::ReactorCommand::ResumeSpace { space_id: None }
+                };
+                self.reactor_tx
+                    .send(reactor::Event::Command(reactor::Command::Reactor(command)));
+            }
             MenuAction::OpenGitHub => {
                 Self::open_path_or_url("https://github.com/acsandmann/rift");
             }
             MenuAction::OpenDocumentation => {
                 Self::open_path_or_url("https://github.com/acsandmann/rift#readme");
             }
+            MenuAction::OpenRelease => {
+                if let Some(release) = &self.available_release {
+                    Self::open_path_or_url(&release.url);
+                }
+            }
             MenuAction::OpenMatrix => {
                 Self::open_path_or_url("https://matrix.to/#/#rift:matrix.org");
             }
@@ -246,6 +311,8 @@ impl Menu {
             match msg {
                 config::Event::ApplyConfig { response, .. } => std::mem::forget(response),
                 config::Event::QueryConfig(response) => std::mem::forget(response),
+                config::Event::ValidateConfig { response, .. } => std::mem::forget(response),
+                config::Event::PowerSourceChanged(_) => {}
             }
         }
     }
@@ -287,6 +354,9 @@ impl Menu {
 fn sig(
     active_space: u64,
     active_space_is_activated: bool,
+    active_space_is_paused: bool,
+    secure_input: bool,
+    displays: &[SpaceData],
     active_workspace: Option<u64>,
     workspaces: &[WorkspaceData],
     windows: &[WindowData],
@@ -297,6 +367,12 @@ fn sig(
     if active_space_is_activated {
         x ^= 0x9E37_79B9_7F4A_7C15u64;
     }
+    if active_space_is_paused {
+        x ^= 0xC2B2_AE3D_27D4_EB4Fu64;
+    }
+    if secure_input {
+        x ^= 0x1656_67B1_9E37_79F9u64;
+    }
     let mut s = active_space
         .wrapping_add(windows.len() as u64)
         .wrapping_add((workspaces.len() as u64).rotate_left(5));
@@ -307,6 +383,12 @@ fn sig(
         s = s.wrapping_add(ws_tag);
     }
 
+    for (idx, display) in displays.iter().enumerate() {
+        let v = display.space_id.rotate_left(idx as u32 * 3) ^ display.is_active as u64;
+        x ^= v.rotate_left(21);
+        s = s.wrapping_add(v);
+    }
+
     for ws in workspaces {
         let v = workspace_sig(ws);
         x ^= v.rotate_left(9);
@@ -372,6 +454,7 @@ mod tests {
             index: 0,
             name: "main".to_string(),
             layout_mode: layout_mode.to_string(),
+            root_container: None,
             is_active: true,
             window_count: 1,
             windows: Vec::new(),
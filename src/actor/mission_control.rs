@@ -1,16 +1,19 @@
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use objc2_app_kit::NSScreen;
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use objc2_foundation::MainThreadMarker;
-use tracing::instrument;
+use tracing::{debug, instrument, warn};
 
 use crate::actor::{self, reactor};
-use crate::common::config::Config;
-use crate::sys::event::current_cursor_location;
-use crate::sys::geometry::CGRectExt;
-use crate::sys::screen::{NSScreenExt, ScreenCache, get_active_space_number};
-use crate::ui::mission_control::{MissionControlAction, MissionControlMode, MissionControlOverlay};
+use crate::common::config::{Config, MissionControlSelection};
+use crate::sys::appearance::{Appearance, current_appearance};
+use crate::sys::timer::Timer;
+use crate::ui::mission_control::{
+    MissionControlAction, MissionControlMode, MissionControlOverlay, SelectionMemory,
+};
+use crate::ui::overlay_host::{CreationBackoff, preferred_screen, screen_metrics};
+use crate::ui::theme::OverlayTheme;
 
 #[derive(Debug)]
 pub enum Event {
@@ -18,6 +21,8 @@ pub enum Event {
     ShowCurrent,
     Dismiss,
     RefreshCurrentWorkspace,
+    ConfigUpdated(Config),
+    AppearanceChanged(Appearance),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -26,6 +31,10 @@ enum MissionControlViewMode {
     CurrentWorkspace,
 }
 
+/// How long a show request waits for the overlay window to become available
+/// before it is dropped, so the overlay never pops up long after the keypress.
+const PENDING_SHOW_TIMEOUT: Duration = Duration::from_secs(3);
+
 pub type Sender = actor::Sender<Event>;
 pub type Receiver = actor::Receiver<Event>;
 
@@ -33,10 +42,17 @@ pub struct MissionControlActor {
     config: Config,
     rx: Receiver,
     reactor: reactor::ReactorHandle,
-    overlay: Option<MissionControlOverlay>,
+    overlay: Option<Rc<MissionControlOverlay>>,
     mtm: MainThreadMarker,
     mission_control_active: bool,
     current_view_mode: Option<MissionControlViewMode>,
+    overlay_backoff: CreationBackoff,
+    /// A show request that failed because the overlay window could not be
+    /// created, and when it was made.
+    pending_show: Option<(MissionControlViewMode, Instant)>,
+    appearance: Appearance,
+    /// Carried over from one overlay to the next, since each is dropped on dismiss.
+    selection_memory: SelectionMemory,
 }
 
 impl MissionControlActor {
@@ -54,22 +70,63 @@ impl MissionControlActor {
             mtm,
             mission_control_active: false,
             current_view_mode: None,
+            overlay_backoff: CreationBackoff::default(),
+            pending_show: None,
+            appearance: current_appearance(mtm),
+            selection_memory: SelectionMemory::default(),
         }
     }
 
     pub async fn run(mut self) {
-        while let Some((span, event)) = self.rx.recv().await {
-            let _guard = span.enter();
-            if self.config.settings.ui.mission_control.enabled {
-                self.handle_event(event);
+        let mut retry_timer = Timer::manual();
+        loop {
+            let retry_in = match self.pending_show {
+                Some(_) => self.overlay_backoff.remaining(Instant::now()),
+                None => Duration::MAX,
+            };
+            retry_timer.set_next_fire(retry_in);
+
+            tokio::select! {
+                maybe = self.rx.recv() => {
+                    let Some((span, event)) = maybe else { break };
+                    let _guard = span.enter();
+                    if self.config.settings.ui.mission_control.enabled
+                        || matches!(event, Event::ConfigUpdated(_) | Event::AppearanceChanged(_))
+                    {
+                        self.handle_event(event);
+                    }
+                }
+                _ = retry_timer.next() => self.retry_pending_show(),
             }
         }
     }
 
-    fn ensure_overlay(&mut self) -> &MissionControlOverlay {
+    /// Returns the overlay, creating it if needed. Creation can fail while the
+    /// window server is unavailable; the actor then carries on without it and
+    /// tries again after a backoff.
+    fn ensure_overlay(&mut self) -> Option<&MissionControlOverlay> {
         if self.overlay.is_none() {
+            let now = Instant::now();
+            if !self.overlay_backoff.ready(now) {
+                return None;
+            }
             let (frame, scale) = self.initial_overlay_geometry();
-            let overlay = MissionControlOverlay::new(self.config.clone(), self.mtm, frame, scale);
+            let overlay = match MissionControlOverlay::new(
+                self.config.clone(),
+                self.mtm,
+                frame,
+                scale,
+                self.appearance,
+            ) {
+                Ok(overlay) => overlay,
+                Err(err) => {
+                    let delay = self.overlay_backoff.record_failure(now);
+                    warn!(%err, ?delay, "Failed to create mission control overlay");
+                    return None;
+                }
+            };
+            self.overlay_backoff.record_success();
+            overlay.restore_selection_memory(self.selection_memory.clone());
             let self_ptr: *mut MissionControlActor = self as *mut _;
             overlay.set_action_handler(Rc::new(move |action| unsafe {
                 let this: &mut MissionControlActor = &mut *self_ptr;
@@ -77,51 +134,58 @@ impl MissionControlActor {
             }));
             self.overlay = Some(overlay);
         }
-        self.overlay.as_ref().unwrap()
+        self.overlay.as_deref()
     }
 
-    fn initial_overlay_geometry(&self) -> (CGRect, f64) {
-        let fallback = (
-            CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1280.0, 800.0)),
-            1.0,
-        );
-        let mut cache = ScreenCache::new(self.mtm);
-        let Some((screens, _)) = cache.refresh() else {
-            return fallback;
+    fn retry_pending_show(&mut self) {
+        let Some((view, requested_at)) = self.pending_show.take() else {
+            return;
         };
+        if requested_at.elapsed() > PENDING_SHOW_TIMEOUT {
+            debug!(?view, "Overlay unavailable; dropping mission control request");
+            return;
+        }
+        self.show(view);
+    }
 
-        let selected = current_cursor_location()
-            .ok()
-            .and_then(|cursor| screens.iter().find(|screen| screen.frame.contains(cursor)))
-            .or_else(|| {
-                let active_space = get_active_space_number()?;
-                screens.iter().find(|screen| screen.space == Some(active_space))
-            })
-            .or_else(|| screens.first());
-
-        let Some(selected) = selected else {
-            return fallback;
-        };
+    fn show(&mut self, view: MissionControlViewMode) {
+        match view {
+            MissionControlViewMode::AllWorkspaces => self.show_all_workspaces(),
+            MissionControlViewMode::CurrentWorkspace => self.show_current_workspace(),
+        }
+    }
 
-        let scale = NSScreen::screens(self.mtm)
-            .iter()
-            .find_map(|ns| {
-                let id = ns.get_number().ok()?;
-                if id == selected.id {
-                    Some(ns.backingScaleFactor())
-                } else {
-                    None
-                }
-            })
-            .unwrap_or(1.0);
+    /// Shows `mode` in the overlay, or remembers `view` for a retry if the
+    /// overlay can't be created right now. Returns whether it was shown.
+    fn show_mode(&mut self, view: MissionControlViewMode, mode: MissionControlMode) -> bool {
+        let Some(overlay) = self.ensure_overlay() else {
+            let requested_at = match self.pending_show {
+                Some((_, requested_at)) => requested_at,
+                None => Instant::now(),
+            };
+            self.pending_show = Some((view, requested_at));
+            return false;
+        };
+        overlay.update(mode);
+        self.pending_show = None;
+        self.mission_control_active = true;
+        self.current_view_mode = Some(view);
+        true
+    }
 
-        (selected.frame, scale)
+    fn initial_overlay_geometry(&self) -> (CGRect, f64) {
+        let fallback = (CGRect::new(CGPoint::ZERO, CGSize::new(1280.0, 800.0)), 1.0);
+        let metrics = screen_metrics(self.mtm);
+        preferred_screen(&metrics, None)
+            .map_or(fallback, |metric| (metric.screen.frame, metric.scale))
     }
 
     fn dispose_overlay(&mut self) {
         if let Some(overlay) = self.overlay.take() {
+            self.selection_memory = overlay.selection_memory();
             overlay.hide();
         }
+        self.pending_show = None;
         self.mission_control_active = false;
         self.current_view_mode = None;
     }
@@ -137,6 +201,15 @@ impl MissionControlActor {
                 )));
                 self.dispose_overlay();
             }
+            MissionControlAction::MoveWorkspace { from, to } => {
+                // The overlay already shows the new order, so it stays open.
+                let _ = self.reactor.try_send(reactor::Event::Command(reactor::Command::Layout(
+                    crate::layout_engine::LayoutCommand::SetWorkspaceIndex {
+                        workspace: Some(from),
+                        index: to,
+                    },
+                )));
+            }
             MissionControlAction::FocusWindow { window_id, window_server_id } => {
                 let _ = self.reactor.try_send(reactor::Event::Command(reactor::Command::Reactor(
                     reactor::ReactorCommand::FocusWindow { window_id, window_server_id },
@@ -150,20 +223,25 @@ impl MissionControlActor {
     fn handle_event(&mut self, event: Event) {
         match event {
             Event::ShowAll => {
-                if self.mission_control_active {
+                if self.mission_control_active || self.pending_show.is_some() {
                     self.dispose_overlay();
                 } else {
                     self.show_all_workspaces();
                 }
             }
             Event::ShowCurrent => {
-                if self.mission_control_active {
+                if self.mission_control_active || self.pending_show.is_some() {
                     self.dispose_overlay();
                 } else {
                     self.show_current_workspace();
                 }
             }
             Event::Dismiss => self.dispose_overlay(),
+            Event::ConfigUpdated(config) => self.handle_config_updated(config),
+            Event::AppearanceChanged(appearance) => {
+                self.appearance = appearance;
+                self.restyle_overlay();
+            }
             Event::RefreshCurrentWorkspace => {
                 if self.mission_control_active {
                     match self.current_view_mode {
@@ -180,31 +258,62 @@ impl MissionControlActor {
         }
     }
 
+    fn handle_config_updated(&mut self, config: Config) {
+        let was_hiding_minimized = self.config.settings.ui.mission_control.hide_minimized;
+        let was_showing_empty = self.config.settings.ui.mission_control.show_empty_workspaces;
+        self.config = config;
+        let ui = &self.config.settings.ui;
+        if !ui.mission_control.enabled {
+            self.dispose_overlay();
+            return;
+        }
+        if let Some(overlay) = self.overlay.as_deref() {
+            overlay.set_fade_enabled(ui.mission_control.fade_enabled);
+            overlay.set_fade_duration_ms(ui.mission_control.fade_duration_ms);
+            overlay.set_remember_selection(
+                ui.mission_control.selection == MissionControlSelection::Remember,
+            );
+            // Only a changed setting overrides what was toggled with M or E.
+            if ui.mission_control.hide_minimized != was_hiding_minimized {
+                overlay.set_hide_minimized(ui.mission_control.hide_minimized);
+            }
+            if ui.mission_control.show_empty_workspaces != was_showing_empty {
+                overlay.set_show_empty_workspaces(ui.mission_control.show_empty_workspaces);
+            }
+            overlay.set_grid_columns(self.config.virtual_workspaces.grid_columns);
+        }
+        self.restyle_overlay();
+    }
+
+    fn restyle_overlay(&self) {
+        if let Some(overlay) = self.overlay.as_deref() {
+            let theme = OverlayTheme::new(&self.config.settings.ui.theme, self.appearance);
+            overlay.set_theme(theme);
+        }
+    }
+
     fn show_all_workspaces(&mut self) {
-        self.mission_control_active = true;
-        self.current_view_mode = Some(MissionControlViewMode::AllWorkspaces);
-        {
-            let overlay = self.ensure_overlay();
-            overlay.update(MissionControlMode::AllWorkspaces(Vec::new()));
+        let view = MissionControlViewMode::AllWorkspaces;
+        if !self.show_mode(view, MissionControlMode::AllWorkspaces(Vec::new())) {
+            return;
         }
 
-        let resp = self.reactor.query_workspaces(None);
-        let overlay = self.ensure_overlay();
-        overlay.update(MissionControlMode::AllWorkspaces(resp));
+        let resp = self.reactor.query_workspaces_with_minimized(None);
+        if let Some(overlay) = self.overlay.as_deref() {
+            overlay.update(MissionControlMode::AllWorkspaces(resp));
+        }
     }
 
     fn show_current_workspace(&mut self) {
-        self.mission_control_active = true;
-        self.current_view_mode = Some(MissionControlViewMode::CurrentWorkspace);
-        {
-            let overlay = self.ensure_overlay();
-            overlay.update(MissionControlMode::CurrentWorkspace(Vec::new()));
+        let view = MissionControlViewMode::CurrentWorkspace;
+        if !self.show_mode(view, MissionControlMode::CurrentWorkspace(Vec::new())) {
+            return;
         }
 
-        let windows = self.reactor.query_windows(None);
-
-        let overlay = self.ensure_overlay();
-        overlay.update(MissionControlMode::CurrentWorkspace(windows));
+        let windows = self.reactor.query_windows_with_minimized(None);
+        if let Some(overlay) = self.overlay.as_deref() {
+            overlay.update(MissionControlMode::CurrentWorkspace(windows));
+        }
     }
 
     fn refresh_all_workspaces_highlight(&mut self) {
@@ -11,27 +11,67 @@ use objc2::rc::{Allocated, Retained};
 use objc2::{AnyThread, ClassType, DeclaredClass, Encode, Encoding, define_class, msg_send, sel};
 use objc2_app_kit::{self, NSRunningApplication, NSWorkspace, NSWorkspaceApplicationKey};
 use objc2_foundation::{
-    MainThreadMarker, NSNotification, NSNotificationCenter, NSObject, NSProcessInfo, NSString,
+    MainThreadMarker, NSDistributedNotificationCenter, NSNotification, NSNotificationCenter,
+    NSObject, NSProcessInfo, NSString,
 };
 use tracing::{debug, info_span, trace, warn};
 
 use super::wm_controller::{self, WmEvent};
 use crate::sys::app::NSRunningApplicationExt;
+use crate::sys::appearance::{Appearance, current_appearance};
 use crate::sys::dispatch::DispatchExt;
 use crate::sys::power::{init_power_state, set_low_power_mode_state};
 use crate::sys::screen::{CoordinateConverter, ScreenCache, ScreenInfo, SpaceId};
 use crate::sys::skylight::{CGDisplayRegisterReconfigurationCallback, DisplayReconfigFlags};
-use crate::sys::{display_churn, window_server};
+use crate::sys::{display_churn, focus_mode, window_server};
 
 const REFRESH_DEFAULT_DELAY_NS: i64 = 150_000_000;
 const REFRESH_RETRY_DELAY_NS: i64 = 150_000_000;
 const REFRESH_MAX_RETRIES: u8 = 10;
 
+// Space ids and display modes can keep shifting for a few seconds after wake.
+const WAKE_RECONCILE_DELAY_NS: i64 = 3_000_000_000;
+
 const DISPLAY_CHURN_QUIET_NS: i64 = 3_000_000_000;
 const DISPLAY_STABILIZE_RETRY_NS: i64 = 200_000_000;
 const DISPLAY_STABILIZE_MAX_ATTEMPTS: u8 = 25;
 const DISPLAY_STABLE_REQUIRED_HITS: u8 = 2;
 
+// The Focus database is written after the notification is posted.
+const FOCUS_MODE_READ_DELAY_NS: i64 = 250_000_000;
+const FOCUS_MODE_NOTIFICATIONS: [&str; 3] = [
+    "com.apple.notificationcenterui.dndprefs_changed",
+    "_NSDoNotDisturbEnabledNotification",
+    "_NSDoNotDisturbDisabledNotification",
+];
+
+// NSApp's effective appearance is updated after the theme notification is delivered.
+const APPEARANCE_READ_DELAY_NS: i64 = 100_000_000;
+
+const SCREEN_LOCKED: &str = "com.apple.screenIsLocked";
+const SCREEN_UNLOCKED: &str = "com.apple.screenIsUnlocked";
+const SCREENSAVER_STARTED: &str = "com.apple.screensaver.didstart";
+const SCREENSAVER_STOPPED: &str = "com.apple.screensaver.didstop";
+const SCREEN_LOCK_NOTIFICATIONS: [&str; 4] = [
+    SCREEN_LOCKED,
+    SCREEN_UNLOCKED,
+    SCREENSAVER_STARTED,
+    SCREENSAVER_STOPPED,
+];
+
+/// The reasons nobody can be looking at or using the desktop right now. Any one
+/// of them is enough for rift to step back; see [`WmEvent::ScreenLocked`].
+#[derive(Debug, Default, Clone, Copy)]
+struct ScreenAway {
+    locked: bool,
+    screensaver: bool,
+    displays_asleep: bool,
+}
+
+impl ScreenAway {
+    fn any(self) -> bool { self.locked || self.screensaver || self.displays_asleep }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct DisplayTopologyFingerprint(Vec<(String, u64, u64, u64, u64)>);
 
@@ -53,6 +93,10 @@ struct Instance {
     display_topology_state: RefCell<Option<DisplayTopologyState>>,
     refresh_deferred_until_stable: Cell<bool>,
     last_sent_spaces: RefCell<Option<Vec<Option<SpaceId>>>>,
+    last_focus_mode: RefCell<Option<String>>,
+    last_appearance: Cell<Option<Appearance>>,
+    wake_epoch: Cell<u64>,
+    screen_away: Cell<ScreenAway>,
 }
 
 unsafe impl Encode for Instance {
@@ -99,15 +143,39 @@ define_class! {
             // parameters so the reactor/layout engine sees updated bounds.
             self.schedule_screen_refresh();
             self.send_event(WmEvent::SystemWoke);
+            self.schedule_wake_reconcile();
+        }
+
+        #[unsafe(method(recvSessionActiveEvent:))]
+        fn recv_session_active_event(&self, notif: &NSNotification) {
+            trace!("{notif:#?}");
+            // Displays may have been rearranged by the other user while we were away.
+            self.schedule_screen_refresh();
+            self.send_event(WmEvent::SessionResumed);
+        }
+
+        #[unsafe(method(recvSessionResignEvent:))]
+        fn recv_session_resign_event(&self, notif: &NSNotification) {
+            trace!("{notif:#?}");
+            self.send_event(WmEvent::SessionResigned);
         }
 
         #[unsafe(method(recvSleepEvent:))]
         fn recv_sleep_event(&self, notif: &NSNotification) {
             trace!("{notif:#?}");
+            // Invalidate any reconciliation still pending from a previous wake.
+            let epoch = &self.ivars().wake_epoch;
+            epoch.set(epoch.get().wrapping_add(1));
             let mut cache = self.ivars().screen_cache.borrow_mut();
             cache.mark_sleeping(true);
         }
 
+        #[unsafe(method(recvScreenLockEvent:))]
+        fn recv_screen_lock_event(&self, notif: &NSNotification) {
+            trace!("{notif:#?}");
+            self.handle_screen_lock_event(notif);
+        }
+
         #[unsafe(method(recvPowerEvent:))]
         fn recv_power_event(&self, notif: &NSNotification) {
             trace!("{notif:#?}");
@@ -125,6 +193,24 @@ define_class! {
             trace!("{notif:#?}");
             self.handle_dock_pref_changed();
         }
+
+        #[unsafe(method(recvFocusModeChanged:))]
+        fn recv_focus_mode_changed(&self, notif: &NSNotification) {
+            trace!("{notif:#?}");
+            self.schedule_focus_mode_check();
+        }
+
+        #[unsafe(method(recvAppearanceChanged:))]
+        fn recv_appearance_changed(&self, notif: &NSNotification) {
+            trace!("{notif:#?}");
+            self.schedule_appearance_check();
+        }
+
+        #[unsafe(method(recvInputSourceChanged:))]
+        fn recv_input_source_changed(&self, notif: &NSNotification) {
+            trace!("{notif:#?}");
+            self.send_event(WmEvent::InputSourceChanged);
+        }
     }
 }
 
@@ -141,6 +227,10 @@ impl NotificationCenterInner {
             display_topology_state: RefCell::new(None),
             refresh_deferred_until_stable: Cell::new(false),
             last_sent_spaces: RefCell::new(None),
+            last_focus_mode: RefCell::new(None),
+            last_appearance: Cell::new(None),
+            wake_epoch: Cell::new(0),
+            screen_away: Cell::new(ScreenAway::default()),
         };
         let handler: Retained<Self> = unsafe { msg_send![Self::alloc(), initWith: instance] };
         unsafe {
@@ -170,6 +260,35 @@ impl NotificationCenterInner {
         }
     }
 
+    fn handle_screen_lock_event(&self, notif: &NSNotification) {
+        use objc2_app_kit::*;
+        let name = &*notif.name();
+        let ivars = self.ivars();
+        let mut away = ivars.screen_away.get();
+        let was_away = away.any();
+        if unsafe { NSWorkspaceScreensDidSleepNotification } == name {
+            away.displays_asleep = true;
+        } else if unsafe { NSWorkspaceScreensDidWakeNotification } == name {
+            away.displays_asleep = false;
+        } else {
+            match name.to_string().as_str() {
+                SCREEN_LOCKED => away.locked = true,
+                SCREEN_UNLOCKED => away.locked = false,
+                SCREENSAVER_STARTED => away.screensaver = true,
+                SCREENSAVER_STOPPED => away.screensaver = false,
+                _ => {
+                    warn!("Unexpected screen lock event: {notif:?}");
+                    return;
+                }
+            }
+        }
+        ivars.screen_away.set(away);
+        if away.any() != was_away {
+            debug!(?away, "Screen lock state changed");
+            self.send_event(WmEvent::ScreenLocked(away.any()));
+        }
+    }
+
     fn handle_power_event(&self, _notif: &NSNotification) {
         let span = info_span!("notification_center::handle_power_event");
         let _s = span.enter();
@@ -482,11 +601,16 @@ impl NotificationCenterInner {
 
     fn handle_dock_pref_changed(&self) {
         trace!("Dock preferences changed; scheduling refresh");
+        // The usable frame depends on dock placement, so the cached frames are stale.
+        self.ivars().screen_cache.borrow_mut().mark_dirty();
         self.schedule_screen_refresh();
     }
 
     fn handle_menu_bar_pref_changed(&self) {
         trace!("Menu bar autohide changed; scheduling refresh");
+        // Menu bar (and notch) insets are baked into the cached frames; force a rebuild so
+        // layouts reclaim or give back the strip at the top of each display.
+        self.ivars().screen_cache.borrow_mut().mark_dirty();
         self.schedule_screen_refresh();
     }
 
@@ -494,6 +618,69 @@ impl NotificationCenterInner {
         self.schedule_screen_refresh_after(REFRESH_DEFAULT_DELAY_NS, 0);
     }
 
+    fn schedule_wake_reconcile(&self) {
+        let epoch = &self.ivars().wake_epoch;
+        let expected_epoch = epoch.get().wrapping_add(1);
+        epoch.set(expected_epoch);
+
+        let handler_ptr = self as *const _ as *mut Self;
+        queue::main().after_f_s(
+            Time::new_after(Time::NOW, WAKE_RECONCILE_DELAY_NS),
+            (handler_ptr, expected_epoch),
+            |(handler_ptr, expected_epoch)| unsafe {
+                let handler = &*handler_ptr;
+                if handler.ivars().wake_epoch.get() != expected_epoch {
+                    debug!("Skipping stale wake reconciliation");
+                    return;
+                }
+                // Re-poll screens first so the reactor diffs against settled state.
+                handler.send_screen_parameters();
+                handler.send_event(WmEvent::WakeReconcile);
+            },
+        );
+    }
+
+    fn schedule_focus_mode_check(&self) {
+        let handler_ptr = self as *const _ as *mut Self;
+        queue::main().after_f_s(
+            Time::new_after(Time::NOW, FOCUS_MODE_READ_DELAY_NS),
+            handler_ptr,
+            |handler_ptr| unsafe {
+                let handler = &*handler_ptr;
+                handler.send_focus_mode(false);
+            },
+        );
+    }
+
+    fn send_focus_mode(&self, force: bool) {
+        let focus_mode = focus_mode::current_focus_mode();
+        let previous = self.ivars().last_focus_mode.replace(focus_mode.clone());
+        if force || previous != focus_mode {
+            debug!(?previous, ?focus_mode, "Focus mode changed");
+            self.send_event(WmEvent::FocusModeChanged(focus_mode));
+        }
+    }
+
+    fn schedule_appearance_check(&self) {
+        let handler_ptr = self as *const _ as *mut Self;
+        queue::main().after_f_s(
+            Time::new_after(Time::NOW, APPEARANCE_READ_DELAY_NS),
+            handler_ptr,
+            |handler_ptr| unsafe {
+                let handler = &*handler_ptr;
+                handler.send_appearance();
+            },
+        );
+    }
+
+    fn send_appearance(&self) {
+        let appearance = current_appearance(MainThreadMarker::new().unwrap());
+        if self.ivars().last_appearance.replace(Some(appearance)) != Some(appearance) {
+            debug!(?appearance, "Appearance changed");
+            self.send_event(WmEvent::AppearanceChanged(appearance));
+        }
+    }
+
     fn schedule_screen_refresh_after(&self, delay_ns: i64, attempt: u8) {
         let ivars = self.ivars();
         if attempt == 0 && ivars.display_churn_active.get() {
@@ -581,6 +768,8 @@ impl NotificationCenter {
         let workspace = &NSWorkspace::sharedWorkspace();
         let workspace_center = &workspace.notificationCenter();
         let default_center = &NSNotificationCenter::defaultCenter();
+        // Preference-change notifications are only posted to the distributed center.
+        let distributed_center = &NSDistributedNotificationCenter::defaultCenter();
         unsafe {
             use objc2_app_kit::*;
             workspace_center.addObserver_selector_name_object(
@@ -609,19 +798,83 @@ impl NotificationCenter {
                 workspace_center,
                 workspace,
             );
+            register_unsafe(
+                sel!(recvScreenLockEvent:),
+                NSWorkspaceScreensDidSleepNotification,
+                workspace_center,
+                workspace,
+            );
+            register_unsafe(
+                sel!(recvScreenLockEvent:),
+                NSWorkspaceScreensDidWakeNotification,
+                workspace_center,
+                workspace,
+            );
+            register_unsafe(
+                sel!(recvSessionActiveEvent:),
+                NSWorkspaceSessionDidBecomeActiveNotification,
+                workspace_center,
+                workspace,
+            );
+            register_unsafe(
+                sel!(recvSessionResignEvent:),
+                NSWorkspaceSessionDidResignActiveNotification,
+                workspace_center,
+                workspace,
+            );
             register_unsafe(
                 sel!(recvAppEvent:),
                 NSWorkspaceDidDeactivateApplicationNotification,
                 workspace_center,
                 workspace,
             );
-            default_center.addObserver_selector_name_object(
+            distributed_center.addObserver_selector_name_object(
                 &handler,
                 sel!(recvDockPrefChanged:),
                 Some(&NSString::from_str("com.apple.dock.prefchanged")),
                 None,
             );
+            for name in FOCUS_MODE_NOTIFICATIONS {
+                distributed_center.addObserver_selector_name_object(
+                    &handler,
+                    sel!(recvFocusModeChanged:),
+                    Some(&NSString::from_str(name)),
+                    None,
+                );
+            }
+            for name in SCREEN_LOCK_NOTIFICATIONS {
+                distributed_center.addObserver_selector_name_object(
+                    &handler,
+                    sel!(recvScreenLockEvent:),
+                    Some(&NSString::from_str(name)),
+                    None,
+                );
+            }
+            distributed_center.addObserver_selector_name_object(
+                &handler,
+                sel!(recvAppearanceChanged:),
+                Some(&NSString::from_str("AppleInterfaceThemeChangedNotification")),
+                None,
+            );
+            // Layout switchers change the system-wide source; the text input context
+            // notification covers switches made while one of our own panels has focus.
+            distributed_center.addObserver_selector_name_object(
+                &handler,
+                sel!(recvInputSourceChanged:),
+                Some(&NSString::from_str(
+                    "com.apple.Carbon.TISNotifySelectedKeyboardInputSourceChanged",
+                )),
+                None,
+            );
             default_center.addObserver_selector_name_object(
+                &handler,
+                sel!(recvInputSourceChanged:),
+                Some(&NSString::from_str(
+                    "NSTextInputContextKeyboardSelectionDidChangeNotification",
+                )),
+                None,
+            );
+            distributed_center.addObserver_selector_name_object(
                 &handler,
                 sel!(recvMenuBarPrefChanged:),
                 Some(&NSString::from_str(
@@ -649,6 +902,8 @@ impl NotificationCenter {
 
         self.inner.send_screen_parameters();
         self.inner.send_event(WmEvent::AppEventsRegistered);
+        self.inner.send_focus_mode(true);
+        self.inner.send_appearance();
         if let Some(app) = workspace.frontmostApplication() {
             self.inner.send_event(WmEvent::AppGloballyActivated(app.pid()));
         }
@@ -39,6 +39,7 @@ fn app_info_for_pid(pid: pid_t) -> AppInfo {
         AppInfo {
             bundle_id: None,
             localized_name: None,
+            animation_quirk: None,
         }
     }
 }
@@ -10,6 +10,7 @@ use crate::actor::{self, event_tap, reactor};
 use crate::common::collections::{HashMap, HashSet};
 use crate::sys::app::pid_t;
 use crate::sys::timer::Timer;
+use crate::sys::window_server::{self, WindowServerId};
 
 /// Messages that can be sent to the raise manager
 #[derive(Debug)]
@@ -22,6 +23,9 @@ pub enum Event {
     RaiseTimeout {
         sequence_id: u64,
     },
+    /// Drop queued sequences and cancel the active one; sent when a workspace
+    /// switch makes them pointless.
+    CancelPending,
 }
 
 /// A queued layout response waiting to be processed.
@@ -34,6 +38,10 @@ pub struct RaiseRequest {
     pub focus_window: Option<(WindowId, Option<CGPoint>)>,
     pub app_handles: HashMap<i32, AppThreadHandle>,
     pub focus_quiet: Quiet,
+    /// Every window of the request back to front, the focus window last. When
+    /// set, the window server is first asked to stack them in one go; if it
+    /// does, only the focus window still has to be raised by its app.
+    pub window_order: Vec<WindowServerId>,
 }
 
 pub struct RaiseManager {
@@ -43,6 +51,8 @@ pub struct RaiseManager {
     queued_sequences: VecDeque<RaiseRequest>,
     next_sequence_id: u64,
     event_tap_tx: Option<event_tap::Sender>,
+    /// Stacks windows through the window server; replaced in tests.
+    order_windows: fn(&[WindowServerId]) -> bool,
 }
 
 /// Tracks an executing sequence of raises.
@@ -121,6 +131,7 @@ impl RaiseManager {
             queued_sequences: VecDeque::new(),
             next_sequence_id: 1,
             event_tap_tx: None,
+            order_windows: order_with_window_server,
         }
     }
 
@@ -131,6 +142,7 @@ impl RaiseManager {
                 focus_window,
                 app_handles,
                 focus_quiet,
+                window_order,
             }) => {
                 debug!(
                     "Processing layout response with {} raise_windows",
@@ -143,6 +155,7 @@ impl RaiseManager {
                     focus_window,
                     app_handles,
                     focus_quiet,
+                    window_order,
                 });
             }
             Event::RaiseCompleted { window_id, sequence_id } => {
@@ -170,6 +183,13 @@ impl RaiseManager {
                     }
                 }
             }
+            Event::CancelPending => {
+                if let Some(sequence) = self.active_sequence.take() {
+                    debug!("Cancelling raise sequence {}", sequence.sequence_id);
+                    sequence.raise_token.cancel();
+                }
+                self.queued_sequences.clear();
+            }
         }
 
         // Process sequences until no more progress can be made (fixed-point iteration)
@@ -196,15 +216,23 @@ impl RaiseManager {
     fn start_new_sequence(
         &mut self,
         RaiseRequest {
-            raise_windows,
+            mut raise_windows,
             focus_window,
             app_handles,
             focus_quiet,
+            window_order,
         }: RaiseRequest,
     ) {
         let sequence_id = self.next_sequence_id;
         self.next_sequence_id += 1;
 
+        // Raising window by window through each app lets the stack flicker and
+        // races apps that raise themselves, so try one window server reorder.
+        if window_order.len() > 1 && (self.order_windows)(&window_order) {
+            trace!(?window_order, "Window server stacked the raise batch");
+            raise_windows.clear();
+        }
+
         // Send all raise requests with completion notification
         let mut pending_raises = HashSet::default();
         let raise_token = CancellationToken::new();
@@ -316,6 +344,16 @@ impl RaiseManager {
     }
 }
 
+fn order_with_window_server(window_order: &[WindowServerId]) -> bool {
+    match window_server::order_windows(window_order) {
+        Ok(()) => true,
+        Err(err) => {
+            trace!(?err, "Window server would not stack the raise batch");
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -343,6 +381,7 @@ mod tests {
             focus_window,
             app_handles,
             focus_quiet,
+            window_order: Vec::new(),
         })
     }
 
@@ -469,6 +508,74 @@ mod tests {
         });
     }
 
+    fn ordered_layout_response(app_handles: HashMap<i32, AppThreadHandle>) -> Event {
+        Event::RaiseRequest(RaiseRequest {
+            raise_windows: vec![vec![WindowId::new(1, 1), WindowId::new(1, 2)]],
+            focus_window: Some((WindowId::new(1, 3), None)),
+            app_handles,
+            focus_quiet: Quiet::No,
+            window_order: [1, 2, 3].into_iter().map(WindowServerId::new).collect(),
+        })
+    }
+
+    #[test]
+    fn test_window_server_ordering_leaves_only_the_focus_raise() {
+        Executor::run(async {
+            let mut raise_manager = RaiseManager::new();
+            raise_manager.order_windows = |_| true;
+            let (app_handles, mut app_rx) = create_test_app_handles();
+
+            raise_manager.handle_message(ordered_layout_response(app_handles));
+            let requests = collect_requests(&mut app_rx);
+            assert_eq!(requests.len(), 1);
+            assert_raise_request(&requests[0], WindowId::new(1, 3), 1, Quiet::No);
+        });
+    }
+
+    #[test]
+    fn test_failed_window_server_ordering_falls_back_to_app_raises() {
+        Executor::run(async {
+            let mut raise_manager = RaiseManager::new();
+            raise_manager.order_windows = |_| false;
+            let (app_handles, mut app_rx) = create_test_app_handles();
+
+            raise_manager.handle_message(ordered_layout_response(app_handles));
+            let requests = collect_requests(&mut app_rx);
+            assert_eq!(requests.len(), 1);
+            let Request::Raise(wids, _, _, _) = &requests[0] else {
+                panic!("Expected raise request, got: {:?}", requests[0]);
+            };
+            assert_eq!(*wids, vec![WindowId::new(1, 1), WindowId::new(1, 2)]);
+        });
+    }
+
+    #[test]
+    fn test_cancel_pending_drops_active_and_queued_sequences() {
+        Executor::run(async {
+            let mut raise_manager = RaiseManager::new();
+            let (app_handles, _app_rx) = create_test_app_handles();
+
+            for wid in [WindowId::new(1, 1), WindowId::new(1, 2)] {
+                let msg = create_layout_response(vec![wid], None, app_handles.clone(), Quiet::No);
+                raise_manager.handle_message(msg);
+            }
+            assert_eq!(raise_manager.queued_sequences.len(), 1);
+            let token = raise_manager.active_sequence.as_ref().unwrap().raise_token.clone();
+
+            raise_manager.handle_message(Event::CancelPending);
+            assert!(raise_manager.active_sequence.is_none());
+            assert!(raise_manager.queued_sequences.is_empty());
+            assert!(token.is_cancelled());
+
+            // A late completion for the cancelled sequence is ignored.
+            raise_manager.handle_message(Event::RaiseCompleted {
+                window_id: WindowId::new(1, 1),
+                sequence_id: 1,
+            });
+            assert!(raise_manager.active_sequence.is_none());
+        });
+    }
+
     #[test]
     fn test_all_raises_complete_triggers_focus() {
         Executor::run(async {
@@ -788,6 +895,7 @@ mod tests {
                 focus_window: Some((WindowId::new(1, 7), None)),
                 app_handles,
                 focus_quiet: Quiet::No,
+                window_order: Vec::new(),
             });
 
             // Handle the batched raise request
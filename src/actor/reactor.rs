@@ -14,6 +14,10 @@ mod replay;
 pub mod transaction_manager;
 mod utils;
 
+#[cfg(test)]
+mod scenarios;
+#[cfg(test)]
+mod simulation;
 #[cfg(test)]
 mod testing;
 
@@ -21,7 +25,7 @@ mod testing;
 mod tests;
 
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use events::app::AppEventHandler;
 use events::command::CommandEventHandler;
@@ -31,6 +35,7 @@ use events::system::SystemEventHandler;
 use events::window::WindowEventHandler;
 use main_window::MainWindowTracker;
 use managers::LayoutManager;
+use objc2_app_kit::NSWindowLevel;
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 pub use replay::{Record, replay};
 use serde::{Deserialize, Serialize};
@@ -40,39 +45,47 @@ use transaction_manager::TransactionId;
 
 use super::event_tap;
 use crate::actor::app::{AppInfo, AppThreadHandle, Quiet, Request, WindowId, WindowInfo, pid_t};
-use crate::actor::broadcast::{BroadcastEvent, BroadcastSender};
+use crate::actor::broadcast::{self, BroadcastEvent, BroadcastSender};
 use crate::actor::raise_manager::{self, RaiseManager, RaiseRequest};
 use crate::actor::reactor::events::window_discovery::WindowDiscoveryHandler;
 use crate::actor::{self, menu_bar, stack_line};
 use crate::common::collections::{BTreeMap, HashMap, HashSet};
-use crate::common::config::Config;
+use crate::common::config::{
+    Config, HiddenWorkspaceActivation, RestoreFrames, StackLinePlacement, TitlebarAction,
+};
 use crate::layout_engine::{self as layout, Direction, LayoutEngine, LayoutEvent};
+use crate::model::exclusion_zones::ExclusionZoneStore;
 use crate::model::space_activation::{SpaceActivationConfig, SpaceActivationPolicy};
 use crate::model::tx_store::WindowTxStore;
-use crate::model::virtual_workspace::{AppRuleResult, HideCorner};
+use crate::model::virtual_workspace::{AppRuleResult, HideCorner, VirtualWorkspaceId};
+use crate::model::window_identity::{WindowFingerprint, WindowIdentityStore};
 use crate::sys::event::MouseState;
 use crate::sys::executor::Executor;
-use crate::sys::geometry::{CGRectDef, CGRectExt};
+use crate::sys::geometry::{CGPointDef, CGRectDef, CGRectExt, SameAs};
 use crate::sys::screen::ScreenId;
 pub use crate::sys::screen::ScreenInfo;
 use crate::sys::screen::{SpaceId, get_active_space_number, order_visible_spaces_by_position};
+use crate::sys::timer::Timer;
 use crate::sys::window_server::{
-    self, WindowServerId, WindowServerInfo, current_cursor_location, space_is_fullscreen,
-    wait_for_native_fullscreen_transition, window_level, window_sub_level,
+    self, WindowServerId, WindowServerInfo, current_cursor_location, set_window_level,
+    space_is_fullscreen, wait_for_native_fullscreen_transition, window_level, window_sub_level,
 };
 
 pub type Sender = actor::Sender<Event>;
 type Receiver = actor::Receiver<Event>;
 pub use query::ReactorQueryHandle;
 
+/// `kCGFloatingWindowLevel`: above normal windows, below menus and the Dock.
+const TOPMOST_LEVEL: NSWindowLevel = 3;
+
 pub(crate) use crate::model::reactor::{
     AppState, FullscreenSpaceTrack, FullscreenWindowTrack, PendingSpaceChange, WindowFilter,
     WindowState,
 };
 pub use crate::model::reactor::{
-    Command, DisplaySelector, DragSession, DragState, MenuState, MissionControlState,
-    ReactorCommand, RefocusState, Requested, StaleCleanupState, WorkspaceSwitchOrigin,
-    WorkspaceSwitchState,
+    Command, DisplaySelector, DragSession, DragState, GatherSource, LaunchedApp, MenuState,
+    MissionControlState, ModifierDrag, ModifierDragMode, ReactorCommand, RefocusState, Requested,
+    StaleCleanupState, ThrowTarget, WindowPlacement, WorkspaceSwitchOrigin, WorkspaceSwitchState,
 };
 
 #[derive(Clone)]
@@ -156,6 +169,9 @@ pub enum Event {
     ApplicationGloballyActivated(pid_t),
     ApplicationGloballyDeactivated(pid_t),
     ApplicationMainWindowChanged(pid_t, Option<WindowId>, Quiet),
+    /// The app was hidden (Cmd-H). Its windows are then reported minimized.
+    ApplicationHidden(pid_t),
+    ApplicationShown(pid_t),
 
     WindowsDiscovered {
         pid: pid_t,
@@ -203,8 +219,45 @@ pub enum Event {
     /// The mouse cursor moved over a new window. Only sent if focus-follows-
     /// mouse is enabled.
     MouseMovedOverWindow(WindowServerId),
+    /// A click bound in `[settings.titlebar.clicks]` landed on this window's
+    /// title bar. The event tap kept it from the app.
+    TitlebarClicked(WindowServerId, TitlebarAction),
+    /// A mouse button went down on this window with the `[settings.modifier_drag]`
+    /// modifiers held. The event tap keeps the drag and its mouse up from the app.
+    ModifierDragStarted(
+        WindowServerId,
+        #[serde(with = "CGPointDef")] CGPoint,
+        ModifierDragMode,
+    ),
+    /// The pointer moved during a modifier drag.
+    ModifierDragged(#[serde(with = "CGPointDef")] CGPoint),
     /// System woke from sleep; used to re-subscribe SLS notifications.
     SystemWoke,
+    /// Delayed follow-up to `SystemWoke`, sent once spaces and displays have settled.
+    WakeReconcile,
+    /// Periodic tick for checking window frames against the window server.
+    AuditFrames,
+    /// Periodic tick carrying the seconds since the last keyboard or mouse input.
+    InputIdle(f64),
+    /// Our login session became active again after a lock or Fast User Switch.
+    SessionResumed,
+    /// Our login session stopped being the console session.
+    SessionResigned,
+    /// The lock screen, display sleep or the screensaver started (`true`) or ended.
+    ScreenLocked(bool),
+    /// The panic hotkey was pressed: stop managing windows and put them back
+    /// where they were before rift moved them.
+    KillSwitch,
+    /// The active macOS Focus mode changed. `None` means no Focus is on.
+    FocusModeChanged(Option<String>),
+    /// Secure event input turned on or off; hotkeys are dead while it is on.
+    SecureInputChanged(bool),
+    /// The app owning the menu bar, checked whenever activation changes.
+    MenuBarOwnerChanged(Option<pid_t>),
+    /// The user finished drawing an exclusion zone (global coordinates).
+    ExclusionZoneSelected(#[serde(with = "CGRectDef")] CGRect),
+    /// `exec` launched an app; place its first new window as asked.
+    PlaceNextWindow(WindowPlacement),
 
     #[serde(skip)]
     DisplayChurnBegin,
@@ -244,6 +297,9 @@ pub enum Event {
 pub struct Reactor {
     pub config: Config,
     pub one_space: bool,
+    focus_mode: Option<String>,
+    secure_input: bool,
+    menu_bar_owner: Option<pid_t>,
     app_manager: managers::AppManager,
     layout_manager: managers::LayoutManager,
     window_manager: managers::WindowManager,
@@ -263,6 +319,22 @@ pub struct Reactor {
     pending_space_change_manager: managers::PendingSpaceChangeManager,
     active_spaces: HashSet<SpaceId>,
     display_topology_manager: DisplayTopologyManager,
+    window_style_manager: managers::WindowStyleManager,
+    workspace_hide_manager: managers::WorkspaceHideManager,
+    presentation_manager: managers::PresentationManager,
+    exclusion_zones: ExclusionZoneStore,
+    window_identities: WindowIdentityStore,
+    /// Frames of the Notification Center banners currently on screen.
+    notification_banners: HashMap<WindowServerId, CGRect>,
+    new_window_focus_manager: managers::NewWindowFocusManager,
+    window_placement_manager: managers::WindowPlacementManager,
+    fullscreen_pause_manager: managers::FullscreenPauseManager,
+    wake_reconcile_manager: managers::WakeReconcileManager,
+    frame_audit_manager: managers::FrameAuditManager,
+    idle_manager: managers::IdleManager,
+    animation_manager: animation::AnimationManager,
+    /// Windows of auto-detected external bars seen appearing.
+    external_bar_windows: HashSet<WindowServerId>,
 }
 
 #[derive(Clone, Debug)]
@@ -314,6 +386,10 @@ impl Reactor {
         reactor.menu_manager.menu_tx = Some(menu_tx);
         reactor.communication_manager.stack_line_tx = Some(stack_line_tx);
         reactor.communication_manager.events_tx = Some(events_tx_clone.clone());
+        reactor.exclusion_zones =
+            ExclusionZoneStore::load(crate::common::config::exclusion_zones_file());
+        reactor.window_identities =
+            WindowIdentityStore::load(crate::common::config::window_identities_file());
         let query_handle = ReactorQueryHandle::new(events_tx_clone.clone());
         thread::Builder::new()
             .name("reactor".to_string())
@@ -342,8 +418,14 @@ impl Reactor {
         Reactor {
             config: config.clone(),
             one_space,
+            focus_mode: None,
+            secure_input: false,
+            menu_bar_owner: None,
             app_manager: managers::AppManager::new(),
-            layout_manager: managers::LayoutManager { layout_engine },
+            layout_manager: managers::LayoutManager {
+                layout_engine,
+                debug_overlay: false,
+            },
             window_manager: managers::WindowManager {
                 windows: HashMap::default(),
                 window_ids: HashMap::default(),
@@ -358,7 +440,11 @@ impl Reactor {
                 fullscreen_by_space: HashMap::default(),
                 has_seen_display_set: false,
             },
-            space_activation_policy: SpaceActivationPolicy::new(),
+            space_activation_policy: {
+                let mut policy = SpaceActivationPolicy::new();
+                policy.set_display_rules(config.settings.display_activation.clone());
+                policy
+            },
             main_window_tracker: MainWindowTracker::default(),
             drag_manager: managers::DragManager {
                 drag_state: DragState::Inactive,
@@ -366,6 +452,7 @@ impl Reactor {
                     config.settings.window_snapping,
                 ),
                 skip_layout_for_window: None,
+                modifier_drag: None,
             },
             workspace_switch_manager: managers::WorkspaceSwitchManager {
                 workspace_switch_state: WorkspaceSwitchState::Inactive,
@@ -373,8 +460,9 @@ impl Reactor {
                 active_workspace_switch: None,
                 pending_workspace_switch_origin: None,
                 pending_workspace_mouse_warp: None,
+                queued_switches: 0,
             },
-            recording_manager: managers::RecordingManager { record },
+            recording_manager: managers::RecordingManager::new(record),
             communication_manager: managers::CommunicationManager {
                 event_tap_tx: None,
                 stack_line_tx: None,
@@ -407,6 +495,20 @@ impl Reactor {
             },
             active_spaces: HashSet::default(),
             display_topology_manager: DisplayTopologyManager::default(),
+            window_style_manager: managers::WindowStyleManager::new(),
+            workspace_hide_manager: managers::WorkspaceHideManager::new(),
+            presentation_manager: managers::PresentationManager::new(),
+            exclusion_zones: ExclusionZoneStore::default(),
+            window_identities: WindowIdentityStore::default(),
+            notification_banners: HashMap::default(),
+            new_window_focus_manager: managers::NewWindowFocusManager::new(),
+            window_placement_manager: managers::WindowPlacementManager::new(),
+            fullscreen_pause_manager: managers::FullscreenPauseManager::new(),
+            wake_reconcile_manager: managers::WakeReconcileManager::new(),
+            frame_audit_manager: managers::FrameAuditManager::new(),
+            idle_manager: managers::IdleManager::new(),
+            animation_manager: animation::AnimationManager::new(),
+            external_bar_windows: HashSet::default(),
         }
     }
 
@@ -793,19 +895,49 @@ impl Reactor {
 
     async fn run_reactor_loop(mut reactor: Reactor, mut events: Receiver) {
         const MAX_EVENT_BATCH: usize = 64;
-
-        while let Some((span, event)) = events.recv().await {
-            let _guard = span.enter();
-            reactor.handle_loop_event(event);
-            // Drain a bounded batch to reduce recv/select overhead.
-            for _ in 1..MAX_EVENT_BATCH {
-                let Ok((span, event)) = events.try_recv() else {
+        // Fires when the last layout's settle grace runs out, so that `--wait`
+        // callers are answered even if no other event comes in by then.
+        let mut settle_timer = Timer::manual();
+        let mut identity_timer = Timer::manual();
+
+        loop {
+            let delay = animation::AnimationManager::settle_check_delay(&reactor);
+            settle_timer.set_next_fire(delay.unwrap_or(Duration::MAX));
+            let delay = reactor.window_identities.flush_delay(Instant::now());
+            identity_timer.set_next_fire(delay.unwrap_or(Duration::MAX));
+            let first = tokio::select! {
+                first = events.recv() => first,
+                _ = settle_timer.next() => {
+                    animation::AnimationManager::notify_settled(&mut reactor);
+                    continue;
+                }
+                _ = identity_timer.next() => {
+                    reactor.window_identities.flush();
+                    continue;
+                }
+            };
+            let Some(first) = first else {
+                break;
+            };
+            // Drain a bounded batch to reduce recv/select overhead, and so that
+            // workspace switches hammered in quick succession can be coalesced.
+            let mut batch = vec![first];
+            while batch.len() < MAX_EVENT_BATCH {
+                let Ok(next) = events.try_recv() else {
                     break;
                 };
+                batch.push(next);
+            }
+            let queued = managers::WorkspaceSwitchManager::switches_after(
+                batch.iter().map(|(_, event)| event),
+            );
+            for ((span, event), queued_switches) in batch.into_iter().zip(queued) {
                 let _guard = span.enter();
+                reactor.workspace_switch_manager.queued_switches = queued_switches;
                 reactor.handle_loop_event(event);
             }
         }
+        reactor.window_identities.flush();
     }
 
     fn handle_loop_event(&mut self, event: Event) {
@@ -820,6 +952,7 @@ impl Reactor {
         }
         Self::note_windowserver_activity(&event);
         self.handle_event(event);
+        animation::AnimationManager::notify_settled(self);
     }
 
     fn note_windowserver_activity(event: &Event) {
@@ -840,10 +973,23 @@ impl Reactor {
         }
     }
 
+    /// Events too frequent for the debug log or the trace kept for bug reports.
+    fn is_noisy_event(event: &Event) -> bool {
+        matches!(
+            event,
+            Event::WindowFrameChanged(..)
+                | Event::MouseUp
+                | Event::ModifierDragged(_)
+                | Event::AuditFrames
+                | Event::InputIdle(_)
+        )
+    }
+
     fn log_event(&self, event: &Event) {
-        match event {
-            Event::WindowFrameChanged(..) | Event::MouseUp => trace!(?event, "Event"),
-            _ => debug!(?event, "Event"),
+        if Self::is_noisy_event(event) {
+            trace!(?event, "Event")
+        } else {
+            debug!(?event, "Event")
         }
     }
 
@@ -863,6 +1009,29 @@ impl Reactor {
         )
     }
 
+    fn should_refresh_fullscreen_pause(&self, event: &Event) -> bool {
+        // Config updates always re-evaluate so that disabling the feature unpauses.
+        if matches!(event, Event::ConfigUpdated(..)) {
+            return true;
+        }
+        let settings = &self.config.settings.fullscreen_pause;
+        (settings.enabled || !settings.menu_bar_owners.is_empty())
+            && matches!(
+                event,
+                Event::ApplicationGloballyActivated(..)
+                    | Event::MenuBarOwnerChanged(..)
+                    | Event::ApplicationGloballyDeactivated(..)
+                    | Event::ApplicationMainWindowChanged(..)
+                    | Event::ApplicationTerminated(..)
+                    | Event::WindowCreated(..)
+                    | Event::WindowDestroyed(..)
+                    | Event::WindowServerAppeared(..)
+                    | Event::WindowServerDestroyed(..)
+                    | Event::SpaceChanged(..)
+                    | Event::ScreenParametersChanged(..)
+            )
+    }
+
     fn should_process_during_churn(event: &Event) -> bool {
         matches!(
             event,
@@ -875,6 +1044,12 @@ impl Reactor {
                 | Event::MissionControlNativeEntered
                 | Event::MissionControlNativeExited
                 | Event::SystemWoke
+                | Event::WakeReconcile
+                | Event::SessionResumed
+                | Event::ScreenLocked(..)
+                | Event::FocusModeChanged(..)
+                | Event::SecureInputChanged(..)
+                | Event::MenuBarOwnerChanged(..)
                 | Event::ApplicationLaunched { .. }
                 | Event::ApplicationTerminated(..)
                 | Event::ApplicationThreadTerminated(..)
@@ -883,9 +1058,12 @@ impl Reactor {
                 | Event::ApplicationGloballyActivated(..)
                 | Event::ApplicationGloballyDeactivated(..)
                 | Event::ApplicationMainWindowChanged(..)
+                | Event::ApplicationHidden(..)
+                | Event::ApplicationShown(..)
                 | Event::RegisterWmSender(..)
                 | Event::ConfigUpdated(..)
                 | Event::Command(..)
+                | Event::PlaceNextWindow(..)
                 | Event::RaiseCompleted { .. }
                 | Event::RaiseTimeout { .. }
                 | Event::MenuOpened(..)
@@ -930,6 +1108,9 @@ impl Reactor {
     fn handle_event(&mut self, event: Event) {
         self.log_event(&event);
         self.recording_manager.record.on_event(&event);
+        if !Self::is_noisy_event(&event) {
+            self.recording_manager.remember(&event);
+        }
 
         match event {
             Event::DisplayChurnBegin => {
@@ -962,6 +1143,7 @@ impl Reactor {
         }
 
         let should_update_notifications = Self::should_update_notifications(&event);
+        let should_refresh_fullscreen_pause = self.should_refresh_fullscreen_pause(&event);
 
         let raised_window = self.main_window_tracker.handle_event(&event);
         let mut is_resize = false;
@@ -1008,6 +1190,12 @@ impl Reactor {
                     self.set_login_window_active(false);
                 }
             }
+            Event::ApplicationHidden(pid) => {
+                AppEventHandler::handle_application_hidden(self, pid, true);
+            }
+            Event::ApplicationShown(pid) => {
+                AppEventHandler::handle_application_hidden(self, pid, false);
+            }
             Event::ResyncAppForWindow(wsid) => {
                 AppEventHandler::handle_resync_app_for_window(self, wsid);
             }
@@ -1084,7 +1272,34 @@ impl Reactor {
             Event::MouseMovedOverWindow(wsid) => {
                 WindowEventHandler::handle_mouse_moved_over_window(self, wsid);
             }
+            Event::TitlebarClicked(wsid, action) => {
+                WindowEventHandler::handle_titlebar_clicked(self, wsid, action);
+            }
+            Event::ModifierDragStarted(wsid, loc, mode) => {
+                DragEventHandler::handle_modifier_drag_started(self, wsid, loc, mode);
+            }
+            Event::ModifierDragged(loc) => DragEventHandler::handle_modifier_dragged(self, loc),
             Event::SystemWoke => SystemEventHandler::handle_system_woke(self),
+            Event::WakeReconcile => SystemEventHandler::handle_wake_reconcile(self),
+            Event::AuditFrames => managers::FrameAuditManager::audit(self),
+            Event::InputIdle(idle_secs) => managers::IdleManager::observe(self, idle_secs),
+            Event::SessionResumed => SystemEventHandler::handle_session_resumed(self),
+            Event::SessionResigned => SystemEventHandler::handle_session_resigned(self),
+            Event::ScreenLocked(locked) => SystemEventHandler::handle_screen_locked(self, locked),
+            Event::KillSwitch => SystemEventHandler::handle_kill_switch(self),
+            Event::FocusModeChanged(focus_mode) => {
+                SystemEventHandler::handle_focus_mode_changed(self, focus_mode);
+            }
+            Event::SecureInputChanged(enabled) => {
+                SystemEventHandler::handle_secure_input_changed(self, enabled);
+            }
+            Event::MenuBarOwnerChanged(pid) => {
+                AppEventHandler::handle_menu_bar_owner_changed(self, pid);
+            }
+            Event::ExclusionZoneSelected(rect) => self.handle_exclusion_zone_selected(rect),
+            Event::PlaceNextWindow(placement) => {
+                self.window_placement_manager.expect_window(placement);
+            }
             Event::MissionControlNativeEntered => {
                 SpaceEventHandler::handle_mission_control_native_entered(self);
             }
@@ -1111,6 +1326,7 @@ impl Reactor {
             is_resize,
             window_was_destroyed,
             should_update_notifications,
+            should_refresh_fullscreen_pause,
         );
     }
 
@@ -1120,19 +1336,36 @@ impl Reactor {
         is_resize: bool,
         window_was_destroyed: bool,
         should_update_notifications: bool,
+        should_refresh_fullscreen_pause: bool,
     ) {
         if self.display_topology_manager.is_churning_or_awaiting_commit() {
             return;
         }
 
-        if let Some(raised_window) = raised_window {
+        if should_refresh_fullscreen_pause {
+            managers::FullscreenPauseManager::refresh(self);
+        }
+
+        if let Some(raised_window) = raised_window
+            && !managers::NewWindowFocusManager::restore_focus(self, raised_window)
+        {
             if let Some(space) = self.best_space_for_window_id(raised_window) {
+                // Focused from outside rift (Dock, cmd-tab, an opened URL) while
+                // behind others in a stack: selecting it brings it to the front.
+                let engine = &self.layout_manager.layout_engine;
+                let was_hidden = engine.is_hidden_in_stack(space, raised_window);
                 self.send_layout_event(LayoutEvent::WindowFocused(space, raised_window));
+                if was_hidden {
+                    debug!(?raised_window, "Revealing externally focused stacked window");
+                    managers::LayoutManager::refresh_stack_line(self, space);
+                }
             }
         }
 
         let mut layout_changed = false;
-        if !self.is_in_drag() || window_was_destroyed {
+        if (!self.is_in_drag() || window_was_destroyed)
+            && !self.workspace_switch_manager.superseded()
+        {
             layout_changed = self.update_layout_or_warn(
                 is_resize,
                 matches!(
@@ -1142,6 +1375,7 @@ impl Reactor {
             );
             self.maybe_send_menu_update();
         }
+        self.layout_manager.layout_engine.broadcast_state_changes();
 
         self.workspace_switch_manager.mark_workspace_switch_inactive();
         if self.workspace_switch_manager.active_workspace_switch.is_some() && !layout_changed {
@@ -1172,9 +1406,113 @@ impl Reactor {
         self.update_event_tap_layout_mode();
     }
 
+    /// Matches a newly seen standard window to the identity it had before rift or
+    /// its app restarted, so it can be addressed by `rift_id`.
+    fn assign_window_identity(&mut self, window_id: WindowId) {
+        let Some(window) = self.window_manager.windows.get(&window_id) else {
+            return;
+        };
+        if !window.info.is_standard {
+            return;
+        }
+        let app_bundle_id = self
+            .app_manager
+            .apps
+            .get(&window_id.pid)
+            .and_then(|app| app.info.bundle_id.as_deref());
+        let Some(bundle_id) = window.info.bundle_id.as_deref().or(app_bundle_id) else {
+            return;
+        };
+        let fingerprint = WindowFingerprint {
+            bundle_id,
+            ax_identifier: window.info.ax_identifier.as_deref(),
+            title: &window.info.title,
+            server_id: window.info.sys_id.map(|id| id.as_u32()),
+        };
+        self.window_identities.assign(window_id, fingerprint);
+        let frame = self.window_identities.float_frame(window_id, &self.display_configuration());
+        self.layout_manager.layout_engine.set_remembered_float_frame(window_id, frame);
+    }
+
+    /// Names the set of connected displays, so remembered geometry from one desk
+    /// setup is not applied on another.
+    fn display_configuration(&self) -> String {
+        let mut uuids: Vec<&str> = self
+            .space_manager
+            .screens
+            .iter()
+            .map(|screen| screen.display_uuid.as_str())
+            .collect();
+        uuids.sort_unstable();
+        uuids.dedup();
+        uuids.join(",")
+    }
+
+    /// Where stack lines go on `workspace` of `space` (the active one by
+    /// default), with the workspace's rule applied over the settings.
+    fn stack_line_placement(
+        &self,
+        space: SpaceId,
+        workspace: Option<VirtualWorkspaceId>,
+    ) -> StackLinePlacement {
+        let settings = &self.config.settings.ui.stack_line;
+        let mut placement = StackLinePlacement {
+            horizontal: settings.horiz_placement,
+            vertical: settings.vert_placement,
+            inside: settings.inside,
+        };
+        let engine = &self.layout_manager.layout_engine;
+        let Some(workspace) = workspace.or_else(|| engine.active_workspace(space)) else {
+            return placement;
+        };
+        let vwm = engine.virtual_workspace_manager();
+        if let Some(horizontal) =
+            vwm.workspace_rule_setting(space, workspace, |rule| rule.stack_line_horiz_placement)
+        {
+            placement.horizontal = horizontal;
+        }
+        if let Some(vertical) =
+            vwm.workspace_rule_setting(space, workspace, |rule| rule.stack_line_vert_placement)
+        {
+            placement.vertical = vertical;
+        }
+        if let Some(inside) =
+            vwm.workspace_rule_setting(space, workspace, |rule| rule.stack_line_inside)
+        {
+            placement.inside = inside;
+        }
+        placement
+    }
+
+    fn remember_float_frame(&mut self, window_id: WindowId, frame: CGRect) {
+        let displays = self.display_configuration();
+        self.window_identities.remember_float_frame(window_id, &displays, frame);
+        self.layout_manager
+            .layout_engine
+            .set_remembered_float_frame(window_id, Some(frame));
+    }
+
+    /// Reloads remembered float geometry for every window after displays change.
+    fn refresh_remembered_float_frames(&mut self) {
+        let displays = self.display_configuration();
+        let window_ids: Vec<WindowId> = self.window_manager.windows.keys().copied().collect();
+        for window_id in window_ids {
+            let frame = self.window_identities.float_frame(window_id, &displays);
+            self.layout_manager.layout_engine.set_remembered_float_frame(window_id, frame);
+        }
+    }
+
     fn create_window_data(&self, window_id: WindowId) -> Option<WindowData> {
+        self.window_data(window_id, false)
+    }
+
+    /// Minimized windows stop being manageable; with `include_minimized`, ones
+    /// that were laid out are still reported so mission control can show them.
+    fn window_data(&self, window_id: WindowId, include_minimized: bool) -> Option<WindowData> {
         let window_state = self.window_manager.windows.get(&window_id)?;
-        if !window_state.matches_filter(WindowFilter::EffectivelyManageable) {
+        if !window_state.matches_filter(WindowFilter::EffectivelyManageable)
+            && !(include_minimized && window_state.minimized_in.is_some())
+        {
             return None;
         }
         let app = self.app_manager.apps.get(&window_id.pid)?;
@@ -1186,6 +1524,9 @@ impl Reactor {
             id: window_id,
             is_floating: self.layout_manager.layout_engine.is_window_floating(window_id),
             is_focused: self.main_window() == Some(window_id),
+            is_hidden: app.is_hidden && window_state.info.is_minimized,
+            float_reason: self.layout_manager.layout_engine.auto_float_reason(window_id),
+            rift_id: self.window_identities.rift_id(window_id),
             app_name,
             info: WindowInfo {
                 title: window_state.info.title.clone(),
@@ -1196,6 +1537,19 @@ impl Reactor {
         })
     }
 
+    /// Minimized windows that were in `workspace` when they were minimized.
+    fn minimized_windows_in(&self, workspace: VirtualWorkspaceId) -> Vec<WindowId> {
+        let mut wids: Vec<WindowId> = self
+            .window_manager
+            .windows
+            .iter()
+            .filter(|(_, window)| window.minimized_in == Some(workspace))
+            .map(|(&wid, _)| wid)
+            .collect();
+        wids.sort_unstable();
+        wids
+    }
+
     fn update_complete_window_server_info(&mut self, ws_info: Vec<WindowServerInfo>) {
         self.window_manager.visible_windows.clear();
         self.update_partial_window_server_info(ws_info);
@@ -1387,6 +1741,62 @@ impl Reactor {
         }
     }
 
+    /// Moves windows that an unplugged display left outside every remaining
+    /// screen onto `fallback_display`, or the main display when that is unset.
+    /// Returns whether any window moved; the next layout pass animates them in.
+    fn adopt_orphaned_windows(&mut self) -> bool {
+        let fallback = match &self.config.settings.fallback_display {
+            Some(selector) => self.screen_for_selector(selector, None),
+            // The first screen is the one with the menu bar.
+            None => self.space_manager.screens.first(),
+        };
+        let Some((target_space, target_size)) =
+            fallback.and_then(|screen| screen.space.map(|space| (space, screen.frame.size)))
+        else {
+            return false;
+        };
+        if !self.is_space_active(target_space) {
+            return false;
+        }
+
+        let live_spaces: Vec<SpaceId> =
+            self.space_manager.screens.iter().filter_map(|screen| screen.space).collect();
+        let on_screen = |frame: &CGRect| {
+            self.space_manager
+                .screens
+                .iter()
+                .any(|screen| screen.frame.intersection(frame).area() > 0.0)
+        };
+        let orphans: Vec<(WindowId, SpaceId)> = self
+            .layout_manager
+            .layout_engine
+            .windows_off_spaces(&live_spaces)
+            .into_iter()
+            .filter(|(wid, _)| {
+                self.window_manager.windows.get(wid).is_some_and(|window| {
+                    !window.info.is_minimized && !on_screen(&window.frame_monotonic)
+                })
+            })
+            .collect();
+
+        for &(wid, source_space) in &orphans {
+            self.layout_manager.layout_engine.adopt_window(
+                source_space,
+                target_space,
+                target_size,
+                wid,
+            );
+        }
+        if !orphans.is_empty() {
+            info!(
+                count = orphans.len(),
+                ?target_space,
+                "Moved windows left behind by a removed display"
+            );
+        }
+        !orphans.is_empty()
+    }
+
     fn finalize_space_change(
         &mut self,
         spaces: &[Option<SpaceId>],
@@ -1425,6 +1835,16 @@ impl Reactor {
         }
     }
 
+    pub(crate) fn broadcast_mode_changed(
+        &self,
+        mode: broadcast::Mode,
+        active: bool,
+        space_id: Option<SpaceId>,
+    ) {
+        let event = BroadcastEvent::ModeChanged { mode, active, space_id };
+        let _ = self.communication_manager.event_broadcaster.send(event);
+    }
+
     fn broadcast_window_title_changed(
         &mut self,
         window_id: WindowId,
@@ -2151,11 +2571,22 @@ impl Reactor {
             return;
         }
 
+        let policy = self.config.settings.hidden_workspace_activation;
+        if policy == HiddenWorkspaceActivation::Ignore {
+            debug!(bundle_id = %bundle_id_str, "Ignoring activation on a hidden workspace");
+            return;
+        }
+
         debug!(
             "App activation detected: {} (pid: {}), checking for workspace switch",
             bundle_id_str, pid
         );
+        self.apply_hidden_workspace_activation(pid, policy);
+    }
 
+    /// Switches to the workspace of the activated app's window or pulls that
+    /// window onto the workspace shown, as `policy` says.
+    fn apply_hidden_workspace_activation(&mut self, pid: pid_t, policy: HiddenWorkspaceActivation) {
         let app_window = self
             .main_window()
             .filter(|wid| wid.pid == pid && self.window_is_standard(*wid))
@@ -2178,7 +2609,31 @@ impl Reactor {
             return;
         };
 
-        self.maybe_auto_switch_to_window_workspace(pid, app_window_id, window_space);
+        match policy {
+            HiddenWorkspaceActivation::Pull => {
+                self.pull_window_to_active_workspace(app_window_id, window_space);
+            }
+            HiddenWorkspaceActivation::Switch => {
+                self.maybe_auto_switch_to_window_workspace(pid, app_window_id, window_space);
+            }
+            HiddenWorkspaceActivation::Ignore => {}
+        }
+    }
+
+    /// Moves an activated window onto the workspace shown on its space, instead of
+    /// switching away from what the user is looking at.
+    fn pull_window_to_active_workspace(&mut self, window_id: WindowId, window_space: SpaceId) {
+        let engine = &mut self.layout_manager.layout_engine;
+        let Some(active_index) = engine.active_workspace_idx(window_space) else {
+            return;
+        };
+        debug!(
+            ?window_id,
+            active_index, "Pulling activated window to the active workspace"
+        );
+        let response =
+            engine.move_window_to_workspace(window_space, window_id, active_index as usize);
+        self.handle_layout_response(response, None);
     }
 
     fn maybe_auto_switch_to_window_workspace(
@@ -2294,44 +2749,55 @@ impl Reactor {
         }
 
         for wid in hide_windows {
+            // Windows leaving for another workspace can be ordered out instead
+            // of moved; scratchpads always move.
+            let vwm = &self.layout_manager.layout_engine.virtual_workspace_manager;
+            let on_hidden_workspace = self.best_space_for_window_id(wid).is_some_and(|space| {
+                vwm.workspace_for_window(space, wid)
+                    .is_some_and(|ws| Some(ws) != vwm.active_workspace(space))
+            });
+            let ordered_out =
+                on_hidden_workspace && managers::WorkspaceHideManager::hide(self, wid);
             if let Some(app) = self.app_manager.apps.get(&wid.pid) {
-                let wsid = self
-                    .window_manager
-                    .windows
-                    .get(&wid)
-                    .and_then(|w| w.info.sys_id)
-                    .unwrap_or(crate::sys::window_server::WindowServerId(0));
-                let txid = self.transaction_manager.generate_next_txid(wsid);
-
-                // Calculate hidden position using virtual workspace logic
-                let mut offscreen = CGPoint { x: 20000.0, y: 20000.0 };
-
-                if let Some(w) = self.window_manager.windows.get(&wid) {
-                    if let Some(space) = self.best_space_for_window_id(wid) {
-                        if let Some(screen) = self.space_manager.screen_by_space(space) {
-                            let bundle_id = app.info.bundle_id.as_deref();
-                            let hidden_rect = self
-                                .layout_manager
-                                .layout_engine
-                                .virtual_workspace_manager
-                                .calculate_hidden_position(
-                                    screen.frame,
-                                    w.frame_monotonic.size,
-                                    HideCorner::BottomRight,
-                                    bundle_id,
-                                );
-                            offscreen = hidden_rect.origin;
+                if !ordered_out {
+                    let wsid = self
+                        .window_manager
+                        .windows
+                        .get(&wid)
+                        .and_then(|w| w.info.sys_id)
+                        .unwrap_or(crate::sys::window_server::WindowServerId(0));
+                    let txid = self.transaction_manager.generate_next_txid(wsid);
+
+                    // Calculate hidden position using virtual workspace logic
+                    let mut offscreen = CGPoint { x: 20000.0, y: 20000.0 };
+
+                    if let Some(w) = self.window_manager.windows.get(&wid) {
+                        if let Some(space) = self.best_space_for_window_id(wid) {
+                            if let Some(screen) = self.space_manager.screen_by_space(space) {
+                                let bundle_id = app.info.bundle_id.as_deref();
+                                let hidden_rect = self
+                                    .layout_manager
+                                    .layout_engine
+                                    .virtual_workspace_manager
+                                    .calculate_hidden_position(
+                                        screen.frame,
+                                        w.frame_monotonic.size,
+                                        HideCorner::BottomRight,
+                                        bundle_id,
+                                    );
+                                offscreen = hidden_rect.origin;
+                            }
                         }
                     }
-                }
 
-                tracing::info!("Hiding window {:?} by moving it to {:?}", wid, offscreen);
-                // eui=true to ensure consistent behavior with other moves
-                let _ = app.handle.send(Request::SetWindowPos(wid, offscreen, txid, true));
+                    tracing::info!("Hiding window {:?} by moving it to {:?}", wid, offscreen);
+                    // eui=true to ensure consistent behavior with other moves
+                    let _ = app.handle.send(Request::SetWindowPos(wid, offscreen, txid, true));
 
-                // Update local cache to reflect it's hidden/moved
-                if let Some(w) = self.window_manager.windows.get_mut(&wid) {
-                    w.frame_monotonic.origin = offscreen;
+                    // Update local cache to reflect it's hidden/moved
+                    if let Some(w) = self.window_manager.windows.get_mut(&wid) {
+                        w.frame_monotonic.origin = offscreen;
+                    }
                 }
 
                 // If we're hiding the currently focused window and no replacement focus is provided,
@@ -2452,13 +2918,26 @@ impl Reactor {
             .collect();
         let focus_window = focus_window.filter(|wid| self.is_window_on_active_space(*wid));
 
-        let mut windows_by_app_and_screen = HashMap::default();
+        // Groups stay in the order the layout listed their first windows, back to front.
+        let mut windows_by_app_and_screen: Vec<((pid_t, Option<SpaceId>), Vec<WindowId>)> =
+            Vec::new();
         for &wid in &raise_windows {
-            windows_by_app_and_screen
-                .entry((wid.pid, self.best_space_for_window_id(wid)))
-                .or_insert(vec![])
-                .push(wid);
+            let key = (wid.pid, self.best_space_for_window_id(wid));
+            match windows_by_app_and_screen.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, wids)) => wids.push(wid),
+                None => windows_by_app_and_screen.push((key, vec![wid])),
+            }
         }
+        let window_order = match workspace_switch_space {
+            Some(_) => raise_windows
+                .iter()
+                .filter(|&&wid| Some(wid) != focus_window)
+                .chain(focus_window.iter())
+                .map(|wid| self.window_manager.windows.get(wid)?.info.sys_id)
+                .collect::<Option<Vec<_>>>()
+                .unwrap_or_default(),
+            None => Vec::new(),
+        };
         let focus_window_with_warp = focus_window.map(|wid| {
             let warp = if self.config.settings.mouse_follows_focus {
                 if self.workspace_switch_manager.workspace_switch_state
@@ -2477,10 +2956,11 @@ impl Reactor {
         });
 
         let msg = raise_manager::Event::RaiseRequest(RaiseRequest {
-            raise_windows: windows_by_app_and_screen.into_values().collect(),
+            raise_windows: windows_by_app_and_screen.into_iter().map(|(_, wids)| wids).collect(),
             focus_window: focus_window_with_warp,
             app_handles,
             focus_quiet,
+            window_order,
         });
 
         if let Err(e) = self.communication_manager.raise_manager_tx.try_send(msg) {
@@ -2739,6 +3219,7 @@ impl Reactor {
                 focus_window: Some((wid, warp)),
                 app_handles,
                 focus_quiet: quiet,
+                window_order: Vec::new(),
             }));
     }
 
@@ -2835,6 +3316,141 @@ impl Reactor {
         self.request_visible_windows_for_apps(true);
     }
 
+    /// Moves managed windows back to the frames they had when rift first saw
+    /// them, as far as `policy` allows. Returns how many windows were moved.
+    fn restore_original_frames(&mut self, policy: RestoreFrames) -> usize {
+        if policy == RestoreFrames::Keep {
+            return 0;
+        }
+        let layout_engine = &self.layout_manager.layout_engine;
+        let restores: Vec<(WindowId, Option<WindowServerId>, CGRect)> = self
+            .window_manager
+            .windows
+            .iter()
+            .filter(|&(&wid, window)| {
+                window.is_effectively_manageable()
+                    && !window.info.is_minimized
+                    && !window.frame_monotonic.same_as(window.original_frame)
+                    && (policy == RestoreFrames::Restore || layout_engine.is_window_floating(wid))
+            })
+            .map(|(&wid, window)| (wid, window.info.sys_id, window.original_frame))
+            .collect();
+
+        let mut restored = 0;
+        for (wid, wsid, frame) in restores {
+            let Some(app) = self.app_manager.apps.get(&wid.pid) else {
+                continue;
+            };
+            let txid = match wsid {
+                Some(wsid) => {
+                    let txid = self.transaction_manager.generate_next_txid(wsid);
+                    self.transaction_manager.set_last_sent_txid(wsid, txid);
+                    txid
+                }
+                None => TransactionId::default(),
+            };
+            if app.handle.send(Request::SetWindowFrame(wid, frame, txid, true)).is_err() {
+                continue;
+            }
+            if let Some(window) = self.window_manager.windows.get_mut(&wid) {
+                window.frame_monotonic = frame;
+            }
+            restored += 1;
+        }
+        restored
+    }
+
+    /// Lifts a window above normal windows, or drops it back to the level it
+    /// had before. Returns whether it is topmost afterwards, or `None` if its
+    /// level could not be changed.
+    fn toggle_topmost(&mut self, wid: WindowId) -> Option<bool> {
+        let window = self.window_manager.windows.get_mut(&wid)?;
+        let wsid = window.info.sys_id?;
+        let (level, topmost_from) = match window.topmost_from {
+            Some(original) => (original, None),
+            None => (TOPMOST_LEVEL, Some(window_level(wsid.as_u32()).unwrap_or(0))),
+        };
+        if let Err(err) = set_window_level(wsid.as_u32(), level) {
+            warn!(?wid, ?err, "Failed to change window level");
+            return None;
+        }
+        window.topmost_from = topmost_from;
+        Some(topmost_from.is_some())
+    }
+
+    /// Puts every topmost window back at its own level.
+    fn clear_topmost_windows(&mut self) {
+        for (wid, window) in self.window_manager.windows.iter_mut() {
+            let (Some(level), Some(wsid)) = (window.topmost_from.take(), window.info.sys_id) else {
+                continue;
+            };
+            if let Err(err) = set_window_level(wsid.as_u32(), level) {
+                warn!(?wid, ?err, "Failed to restore window level");
+            }
+        }
+    }
+
+    /// Floating windows of the active workspace on `space` with their frames,
+    /// frontmost first.
+    fn floats_front_to_back(&self, space: SpaceId) -> Vec<(WindowId, CGRect)> {
+        let engine = &self.layout_manager.layout_engine;
+        window_server::space_stacking_order(space.get())
+            .into_iter()
+            .filter_map(|wsid| self.window_manager.window_ids.get(&WindowServerId::new(wsid)))
+            .filter(|&&wid| {
+                engine.is_window_floating(wid) && engine.is_window_in_active_workspace(space, wid)
+            })
+            .filter_map(|&wid| Some((wid, self.window_manager.windows.get(&wid)?.frame_monotonic)))
+            .collect()
+    }
+
+    /// Stacks `back_to_front` above the other windows and focuses the last one.
+    /// The window server reorders them in one go when it can; otherwise each
+    /// app raises its own.
+    fn raise_floats(&mut self, space: SpaceId, back_to_front: Vec<WindowId>) {
+        let Some(&focus) = back_to_front.last() else {
+            return;
+        };
+        self.send_layout_event(LayoutEvent::WindowFocused(space, focus));
+        let mut app_handles = HashMap::default();
+        for &wid in &back_to_front {
+            self.insert_app_handle_for_window(&mut app_handles, wid);
+        }
+        let window_order = back_to_front
+            .iter()
+            .filter_map(|wid| self.window_manager.windows.get(wid)?.info.sys_id)
+            .collect();
+        let msg = raise_manager::Event::RaiseRequest(RaiseRequest {
+            raise_windows: back_to_front.iter().map(|&wid| vec![wid]).collect(),
+            focus_window: Some((focus, None)),
+            app_handles,
+            focus_quiet: Quiet::No,
+            window_order,
+        });
+        if let Err(e) = self.communication_manager.raise_manager_tx.try_send(msg) {
+            warn!("Failed to send raise request to raise manager: {}", e);
+        }
+    }
+
+    /// Asks the stack line actor to outline the selection on `space` if it is a
+    /// container, or to drop the outline if it is a window again.
+    fn outline_selected_container(&self, space: Option<SpaceId>) {
+        let Some(tx) = &self.communication_manager.stack_line_tx else {
+            return;
+        };
+        let engine = &self.layout_manager.layout_engine;
+        let windows = space.and_then(|space| engine.selected_container_windows(space));
+        let bounds = windows
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|wid| self.window_manager.windows.get(wid))
+            .map(|window| window.frame_monotonic)
+            .reduce(|a, b| a.union(&b));
+        if let Err(err) = tx.try_send(stack_line::Event::SelectionOutline(bounds)) {
+            warn!("Failed to send selection outline to stack line: {}", err);
+        }
+    }
+
     fn request_close_window(&mut self, wid: WindowId) {
         if let Some(app) = self.app_manager.apps.get(&wid.pid) {
             if let Err(err) = app.handle.send(Request::CloseWindow(wid)) {
@@ -2863,6 +3479,123 @@ impl Reactor {
         candidate.filter(|space| self.is_space_active(*space))
     }
 
+    /// Applies the `focus_modes` overrides for the current Focus mode (or clears them).
+    pub(crate) fn apply_focus_mode_overrides(&mut self) {
+        let focus_mode = self.focus_mode.as_deref();
+        let settings = self.config.virtual_workspaces_for_focus_mode(focus_mode);
+        self.layout_manager.layout_engine.update_virtual_workspace_settings(&settings);
+        let disable_hooks =
+            self.config.focus_mode_rule(focus_mode).is_some_and(|rule| rule.disable_hooks);
+        self.communication_manager.event_broadcaster.set_hooks_paused(disable_hooks);
+    }
+
+    /// Recomputes the space reserved on each display for auto-detected external bars
+    /// and user-drawn exclusion zones. Returns whether the insets changed.
+    pub(crate) fn refresh_reserved_insets(&mut self) -> bool {
+        let bar = &self.config.settings.layout.external_bar;
+        let bars = if bar.auto_detect {
+            window_server::visible_window_frames_for_owners(&bar.auto_detect_owners)
+        } else {
+            Vec::new()
+        };
+        let banners: Vec<CGRect> = if self.config.settings.layout.avoid_notification_banners {
+            self.notification_banners.values().copied().collect()
+        } else {
+            Vec::new()
+        };
+        let insets = self
+            .space_manager
+            .screens
+            .iter()
+            .filter_map(|screen| {
+                let uuid = screen.display_uuid_owned()?;
+                let mut insets = layout::utils::bar_insets_for_screen(screen.frame, &bars);
+                let mut zones: Vec<CGRect> = self
+                    .exclusion_zones
+                    .rects_for_display(&uuid)
+                    .map(|rect| {
+                        let origin = CGPoint::new(
+                            screen.frame.origin.x + rect.origin.x,
+                            screen.frame.origin.y + rect.origin.y,
+                        );
+                        CGRect::new(origin, rect.size)
+                    })
+                    .collect();
+                zones.extend(banners.iter().copied());
+                let zone_insets = layout::utils::exclusion_insets_for_screen(screen.frame, &zones);
+                insets.top = insets.top.max(zone_insets.top);
+                insets.left = insets.left.max(zone_insets.left);
+                insets.bottom = insets.bottom.max(zone_insets.bottom);
+                insets.right = insets.right.max(zone_insets.right);
+                Some((uuid, insets))
+            })
+            .filter(|(_, insets)| *insets != crate::common::config::OuterGaps::default())
+            .collect();
+        self.layout_manager.layout_engine.set_reserved_insets(insets)
+    }
+
+    /// Records a Notification Center banner appearing (`Some(frame)`) or going away,
+    /// and re-tiles around it when `avoid_notification_banners` is on.
+    pub(crate) fn set_notification_banner(&mut self, wsid: WindowServerId, frame: Option<CGRect>) {
+        match frame {
+            Some(frame) => self.notification_banners.insert(wsid, frame),
+            None => self.notification_banners.remove(&wsid),
+        };
+        let avoid = self.config.settings.layout.avoid_notification_banners;
+        if avoid && self.refresh_reserved_insets() {
+            let _ = self.update_layout_or_warn(false, false);
+        }
+    }
+
+    /// Registers a rect picked with the region selection overlay as an exclusion
+    /// zone on the display that contains most of it.
+    fn handle_exclusion_zone_selected(&mut self, rect: CGRect) {
+        let Some(screen) = self
+            .space_manager
+            .screens
+            .iter()
+            .max_by(|a, b| {
+                a.frame
+                    .intersection(&rect)
+                    .area()
+                    .total_cmp(&b.frame.intersection(&rect).area())
+            })
+            .filter(|screen| screen.frame.intersection(&rect).area() > 0.0)
+        else {
+            warn!(?rect, "Selected exclusion zone is not on any known display");
+            return;
+        };
+        let Some(uuid) = screen.display_uuid_owned() else {
+            warn!(?rect, "Display for selected exclusion zone has no UUID");
+            return;
+        };
+        let origin = CGPoint::new(
+            rect.origin.x - screen.frame.origin.x,
+            rect.origin.y - screen.frame.origin.y,
+        );
+        let relative = CGRect::new(origin, rect.size);
+        let id = self.exclusion_zones.add(uuid.clone(), relative);
+        info!(id, display = %uuid, "Added exclusion zone");
+        if self.refresh_reserved_insets() {
+            let _ = self.update_layout_or_warn(false, false);
+        }
+    }
+
+    /// Whether windows of the process called `name` are detected as external bars.
+    pub(crate) fn is_external_bar_owner(&self, name: &str) -> bool {
+        let bar = &self.config.settings.layout.external_bar;
+        bar.auto_detect
+            && bar.auto_detect_owners.iter().any(|owner| owner.eq_ignore_ascii_case(name))
+    }
+
+    /// Re-reads the external bars after their app launched or quit or one of their
+    /// windows came or went, and re-tiles if the space they take up changed.
+    pub(crate) fn external_bar_changed(&mut self) {
+        if self.refresh_reserved_insets() {
+            let _ = self.update_layout_or_warn(false, false);
+        }
+    }
+
     fn space_for_cursor_screen(&self) -> Option<SpaceId> {
         current_cursor_location().ok().and_then(|point| self.space_for_point(point))
     }
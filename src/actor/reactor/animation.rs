@@ -1,13 +1,15 @@
+use std::sync::mpsc::SyncSender;
 use std::time::{Duration, Instant};
 
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
-use tracing::{debug, trace};
+use tracing::{debug, instrument, trace};
 
 use super::TransactionId;
 use crate::actor::app::{AppThreadHandle, Request, WindowId, pid_t};
 use crate::actor::reactor::Reactor;
 use crate::common::collections::HashMap;
 use crate::common::config::AnimationEasing;
+use crate::model::server::AnimationStateData;
 use crate::sys::geometry::{Round, SameAs};
 use crate::sys::power;
 use crate::sys::screen::SpaceId;
@@ -142,9 +144,90 @@ fn ease(t: f64) -> f64 {
 
 fn blend(a: f64, b: f64, s: f64) -> f64 { (1.0 - s) * a + s * b }
 
-pub struct AnimationManager;
+/// How long after a layout its windows may take to report their new frames
+/// before they count as settled anyway, e.g. when an app refuses a size.
+const SETTLE_GRACE: Duration = Duration::from_secs(1);
+
+/// Moves windows into place and tells anyone waiting once they have landed.
+#[derive(Default)]
+pub struct AnimationManager {
+    /// Until when the frames sent by the last layout are expected to land.
+    settle_deadline: Option<Instant>,
+    /// `execute --wait` callers to answer once nothing is in flight.
+    settle_waiters: Vec<SyncSender<AnimationStateData>>,
+}
 
 impl AnimationManager {
+    pub fn new() -> Self { Self::default() }
+
+    /// Notes that a layout just sent windows new frames.
+    pub fn frames_sent(&mut self) { self.settle_deadline = Some(Instant::now() + SETTLE_GRACE); }
+
+    pub fn state(reactor: &Reactor) -> AnimationStateData {
+        Self::state_at(reactor, Instant::now())
+    }
+
+    fn state_at(reactor: &Reactor, now: Instant) -> AnimationStateData {
+        let workspace_switch = reactor.workspace_switch_manager.active_workspace_switch.is_some();
+        let pending_windows = reactor.transaction_manager.pending_count();
+        let settling =
+            reactor.animation_manager.settle_deadline.is_some_and(|deadline| now < deadline);
+        AnimationStateData {
+            in_flight: workspace_switch || (pending_windows > 0 && settling),
+            workspace_switch,
+            pending_windows,
+        }
+    }
+
+    /// Answers `resp` once nothing is in flight, right away if that is already so.
+    pub fn wait_for_settle(reactor: &mut Reactor, resp: SyncSender<AnimationStateData>) {
+        let state = Self::state(reactor);
+        if state.in_flight {
+            reactor.animation_manager.settle_waiters.push(resp);
+        } else {
+            let _ = resp.send(state);
+        }
+    }
+
+    /// Answers the waiters if nothing is in flight any more, e.g. after an event
+    /// or once the settle grace has run out.
+    pub fn notify_settled(reactor: &mut Reactor) {
+        Self::notify_settled_at(reactor, Instant::now())
+    }
+
+    pub(super) fn notify_settled_at(reactor: &mut Reactor, now: Instant) {
+        if reactor.animation_manager.settle_waiters.is_empty() {
+            return;
+        }
+        let state = Self::state_at(reactor, now);
+        if state.in_flight {
+            return;
+        }
+        let waiters = reactor.animation_manager.settle_waiters.len();
+        trace!(waiters, "Animations settled");
+        for waiter in reactor.animation_manager.settle_waiters.drain(..) {
+            // The caller may have given up waiting already.
+            let _ = waiter.send(state);
+        }
+    }
+
+    /// How long the reactor can go without events before the waiters have to
+    /// be looked at again, because frames still on their way stop counting
+    /// once the settle grace runs out.
+    pub fn settle_check_delay(reactor: &Reactor) -> Option<Duration> {
+        let manager = &reactor.animation_manager;
+        if manager.settle_waiters.is_empty() {
+            return None;
+        }
+        let deadline = manager.settle_deadline?;
+        Some(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    #[instrument(
+        name = "animation::animate_layout",
+        skip(reactor, layout),
+        fields(windows = layout.len())
+    )]
     pub fn animate_layout(
         reactor: &mut Reactor,
         space: SpaceId,
@@ -212,8 +295,10 @@ impl AnimationManager {
                 .virtual_workspace_manager()
                 .workspace_for_window(space, wid)
                 .map_or(false, |ws| ws == active_ws);
+            // Apps that animate accessibility moves themselves turn ours into jank.
+            let is_quirky = app_state.info.animation_quirk.is_some();
 
-            if is_active {
+            if is_active && !is_quirky {
                 trace!(?wid, ?current_frame, ?target_frame, "Animating visible window");
                 animated_wids_wsids.push(wid.idx.into());
                 anim.add_window(&app_state.handle, wid, current_frame, target_frame, false, txid);
@@ -226,7 +311,8 @@ impl AnimationManager {
                     ?wid,
                     ?current_frame,
                     ?target_frame,
-                    "Direct positioning hidden window"
+                    is_quirky,
+                    "Direct positioning hidden or quirky window"
                 );
                 if let Some(wsid) = window_server_id {
                     reactor.transaction_manager.update_txid_entries([(wsid, txid, target_frame)]);
@@ -262,6 +348,112 @@ impl AnimationManager {
         any_frame_changed
     }
 
+    /// Lays out a workspace switch: tiled windows move instantly, floats slide
+    /// when `float_switch_animation` is on.
+    #[instrument(
+        name = "animation::switch_layout",
+        skip(reactor, layout),
+        fields(windows = layout.len())
+    )]
+    pub fn switch_layout(
+        reactor: &mut Reactor,
+        space: SpaceId,
+        layout: &[(WindowId, CGRect)],
+        skip_wid: Option<WindowId>,
+    ) -> bool {
+        let settings = &reactor.config.settings;
+        if !settings.animate
+            || !settings.float_switch_animation.enabled
+            || power::is_low_power_mode_enabled()
+        {
+            return Self::instant_layout(reactor, layout, skip_wid);
+        }
+        let engine = &reactor.layout_manager.layout_engine;
+        let (floats, tiled): (Vec<_>, Vec<_>) =
+            layout.iter().copied().partition(|(wid, _)| engine.is_window_floating(*wid));
+        let tiled_changed = Self::instant_layout(reactor, &tiled, skip_wid);
+        Self::slide_floats(reactor, space, &floats, skip_wid) || tiled_changed
+    }
+
+    /// Floats arriving on the active workspace rise into place from `distance`
+    /// below their frame; floats leaving sink by as much and then jump to their
+    /// hidden position.
+    fn slide_floats(
+        reactor: &mut Reactor,
+        space: SpaceId,
+        layout: &[(WindowId, CGRect)],
+        skip_wid: Option<WindowId>,
+    ) -> bool {
+        let settings = reactor.config.settings.float_switch_animation;
+        let active_ws = reactor.layout_manager.layout_engine.active_workspace(space);
+        let mut anim = Animation::new(
+            reactor.config.settings.animation_fps,
+            settings.duration,
+            reactor.config.settings.animation_easing.clone(),
+        );
+        let sink = |frame: CGRect| {
+            CGRect::new(
+                CGPoint::new(frame.origin.x, frame.origin.y + settings.distance),
+                frame.size,
+            )
+        };
+        let mut leaving = Vec::new();
+        let mut any_frame_changed = false;
+
+        for &(wid, target_frame) in layout {
+            if skip_wid == Some(wid) {
+                continue;
+            }
+            let Some(window) = reactor.window_manager.windows.get_mut(&wid) else {
+                continue;
+            };
+            let target_frame = target_frame.round();
+            let current_frame = window.frame_monotonic;
+            let Some(wsid) = window.info.sys_id else {
+                continue;
+            };
+            if target_frame.same_as(current_frame) {
+                continue;
+            }
+            let Some(app_state) = reactor.app_manager.apps.get(&wid.pid) else {
+                continue;
+            };
+            window.frame_monotonic = target_frame;
+            any_frame_changed = true;
+            let txid = reactor.transaction_manager.generate_next_txid(wsid);
+            reactor.transaction_manager.update_txid_entries([(wsid, txid, target_frame)]);
+            if app_state.info.animation_quirk.is_some() {
+                _ = app_state.handle.send(Request::SetWindowFrame(wid, target_frame, txid, true));
+                continue;
+            }
+
+            let arriving = active_ws.is_some()
+                && reactor
+                    .layout_manager
+                    .layout_engine
+                    .virtual_workspace_manager()
+                    .workspace_for_window(space, wid)
+                    == active_ws;
+            trace!(?wid, arriving, "Sliding float for workspace switch");
+            if arriving {
+                let from = sink(target_frame);
+                anim.add_window(&app_state.handle, wid, from, target_frame, true, txid);
+            } else {
+                let to = sink(current_frame);
+                anim.add_window(&app_state.handle, wid, current_frame, to, false, txid);
+                leaving.push((wid, target_frame, txid));
+            }
+        }
+
+        anim.run();
+        for (wid, target_frame, txid) in leaving {
+            if let Some(app_state) = reactor.app_manager.apps.get(&wid.pid) {
+                _ = app_state.handle.send(Request::SetWindowFrame(wid, target_frame, txid, true));
+            }
+        }
+        any_frame_changed
+    }
+
     pub fn instant_layout(
         reactor: &mut Reactor,
         layout: &[(WindowId, CGRect)],
@@ -1,6 +1,7 @@
 use tracing::{debug, warn};
 
-use crate::actor::app::{AppInfo, AppThreadHandle, Quiet, WindowId};
+use crate::actor::app::{AppInfo, AppThreadHandle, Quiet, WindowId, pid_t};
+use crate::actor::broadcast::BroadcastEvent;
 use crate::actor::reactor::{AppState, Reactor};
 use crate::layout_engine::LayoutEvent;
 use crate::sys::app::WindowInfo;
@@ -19,9 +20,18 @@ impl AppEventHandler {
         _is_frontmost: bool,
         _main_window: Option<WindowId>,
     ) {
-        reactor.app_manager.apps.insert(pid, AppState { info: info.clone(), handle });
+        let is_bar =
+            info.localized_name.as_deref().is_some_and(|n| reactor.is_external_bar_owner(n));
+        reactor.app_manager.apps.insert(pid, AppState {
+            info: info.clone(),
+            handle,
+            is_hidden: false,
+        });
         reactor.update_partial_window_server_info(window_server_info);
         reactor.on_windows_discovered_with_app_info(pid, visible_windows, vec![], Some(info));
+        if is_bar {
+            reactor.external_bar_changed();
+        }
     }
 
     pub fn handle_application_terminated(reactor: &mut Reactor, pid: i32) {
@@ -33,8 +43,18 @@ impl AppEventHandler {
     }
 
     pub fn handle_application_thread_terminated(reactor: &mut Reactor, pid: i32) {
-        reactor.app_manager.apps.remove(&pid);
+        let app = reactor.app_manager.apps.remove(&pid);
         reactor.send_layout_event(LayoutEvent::AppClosed(pid));
+        let name = app.as_ref().and_then(|app| app.info.localized_name.as_deref());
+        if name.is_some_and(|name| reactor.is_external_bar_owner(name)) {
+            reactor.external_bar_changed();
+        }
+    }
+
+    pub fn handle_application_hidden(reactor: &mut Reactor, pid: i32, hidden: bool) {
+        if let Some(app) = reactor.app_manager.apps.get_mut(&pid) {
+            app.is_hidden = hidden;
+        }
     }
 
     pub fn handle_resync_app_for_window(reactor: &mut Reactor, wsid: WindowServerId) {
@@ -63,6 +83,22 @@ impl AppEventHandler {
         reactor.handle_app_activation_workspace_switch(pid);
     }
 
+    pub fn handle_menu_bar_owner_changed(reactor: &mut Reactor, pid: Option<pid_t>) {
+        if reactor.menu_bar_owner == pid {
+            return;
+        }
+        debug!(?pid, "menu bar owner changed");
+        reactor.menu_bar_owner = pid;
+        let info = pid.and_then(|pid| reactor.app_manager.apps.get(&pid)).map(|app| &app.info);
+        let _ = reactor.communication_manager.event_broadcaster.send(
+            BroadcastEvent::MenuBarOwnerChanged {
+                pid,
+                bundle_id: info.and_then(|info| info.bundle_id.clone()),
+                app_name: info.and_then(|info| info.localized_name.clone()),
+            },
+        );
+    }
+
     pub fn handle_windows_discovered(
         reactor: &mut Reactor,
         pid: i32,
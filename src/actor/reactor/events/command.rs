@@ -1,20 +1,35 @@
-use tracing::{error, info, warn};
+use std::time::Duration;
+
+use objc2_core_foundation::CGRect;
+use tracing::{debug, error, info, warn};
 
 use super::super::ScreenInfo;
 use crate::actor::app::{AppThreadHandle, Quiet, WindowId};
+use crate::actor::reactor::managers::{
+    self, LayoutManager, PresentationManager, WorkspaceHideManager, WorkspaceSwitchManager,
+};
 use crate::actor::reactor::transaction_manager::TransactionId;
 use crate::actor::reactor::{
-    Command, DisplaySelector, Reactor, ReactorCommand, WorkspaceSwitchOrigin,
+    Command, DisplaySelector, GatherSource, Reactor, ReactorCommand, ThrowTarget,
+    WorkspaceSwitchOrigin,
 };
 use crate::actor::stack_line::Event as StackLineEvent;
-use crate::actor::wm_controller::WmEvent;
-use crate::actor::{menu_bar, raise_manager};
+use crate::actor::wm_controller::{ExecCmd, PlacedExecCmd, WmCmd, WmEvent};
+use crate::actor::{broadcast, menu_bar, raise_manager};
 use crate::common::collections::HashMap;
-use crate::common::config::{self as config, Config};
+use crate::common::config::{
+    self as config, Config, DirectionalFocus, LayoutMode, RestoreFrames, WorkspaceSelector,
+};
 use crate::common::log::{MetricsCommand, handle_command};
-use crate::layout_engine::{EventResponse, LayoutCommand, LayoutEvent};
+use crate::layout_engine::utils::nearest_in_direction;
+use crate::layout_engine::{Direction, EventResponse, LayoutCommand, LayoutEvent};
+use crate::sys::geometry::{CGRectExt, SameAs};
+use crate::sys::screen::SpaceId;
 use crate::sys::window_server::{self as window_server, WindowServerId};
 
+/// How long `save_and_exit` waits for restored frames to be applied.
+const EXIT_RESTORE_GRACE: Duration = Duration::from_millis(300);
+
 pub struct CommandEventHandler;
 
 impl CommandEventHandler {
@@ -39,22 +54,21 @@ impl CommandEventHandler {
 
     pub fn handle_command_layout(reactor: &mut Reactor, cmd: LayoutCommand) {
         info!(?cmd);
-        let is_workspace_switch = matches!(
-            cmd,
-            LayoutCommand::NextWorkspace(_)
-                | LayoutCommand::PrevWorkspace(_)
-                | LayoutCommand::SwitchToWorkspace(_)
-                | LayoutCommand::SwitchToLastWorkspace
-        );
+        let is_workspace_switch = WorkspaceSwitchManager::is_switch_command(&cmd);
         let requires_workspace_space = matches!(
             cmd,
             LayoutCommand::NextWorkspace(_)
                 | LayoutCommand::PrevWorkspace(_)
                 | LayoutCommand::SwitchToWorkspace(_)
+                | LayoutCommand::FocusWorkspace(_)
                 | LayoutCommand::SetWorkspaceLayout { .. }
                 | LayoutCommand::CreateWorkspace
                 | LayoutCommand::SwitchToLastWorkspace
+                | LayoutCommand::MoveWorkspaceLeft
+                | LayoutCommand::MoveWorkspaceRight
+                | LayoutCommand::SetWorkspaceIndex { .. }
         );
+        let outlines_selection = matches!(cmd, LayoutCommand::Ascend | LayoutCommand::Descend);
         let command_space = reactor.workspace_command_space();
         let workspace_space = if requires_workspace_space {
             if let Some(space) = command_space {
@@ -65,6 +79,14 @@ impl CommandEventHandler {
             None
         };
         if is_workspace_switch {
+            if reactor.workspace_switch_manager.active_workspace_switch.is_some() {
+                // Raises for the previous switch would focus windows that are
+                // about to be hidden again.
+                _ = reactor
+                    .communication_manager
+                    .raise_manager_tx
+                    .try_send(raise_manager::Event::CancelPending);
+            }
             reactor
                 .workspace_switch_manager
                 .start_workspace_switch(WorkspaceSwitchOrigin::Manual);
@@ -72,13 +94,25 @@ impl CommandEventHandler {
             reactor.workspace_switch_manager.mark_workspace_switch_inactive();
         }
 
+        if let LayoutCommand::MoveFocus(direction) = cmd {
+            if let Some(target) = Self::geometric_focus_target(reactor, direction) {
+                debug!(?target, ?direction, "Moving focus by window frames");
+                Self::handle_command_reactor_focus_window(reactor, target, None);
+                return;
+            }
+        }
+
         let response = match &cmd {
             LayoutCommand::NextWorkspace(_)
             | LayoutCommand::PrevWorkspace(_)
             | LayoutCommand::SwitchToWorkspace(_)
+            | LayoutCommand::FocusWorkspace(_)
             | LayoutCommand::SetWorkspaceLayout { .. }
             | LayoutCommand::CreateWorkspace
-            | LayoutCommand::SwitchToLastWorkspace => {
+            | LayoutCommand::SwitchToLastWorkspace
+            | LayoutCommand::MoveWorkspaceLeft
+            | LayoutCommand::MoveWorkspaceRight
+            | LayoutCommand::SetWorkspaceIndex { .. } => {
                 if let Some(space) = workspace_space {
                     reactor
                         .layout_manager
@@ -114,10 +148,55 @@ impl CommandEventHandler {
             }
         };
 
+        if is_workspace_switch && reactor.workspace_switch_manager.superseded() {
+            debug!("Workspace switch superseded by a queued one");
+            return;
+        }
         reactor.handle_layout_response(response, workspace_space);
         if requires_workspace_space {
             reactor.update_event_tap_layout_mode();
         }
+        if outlines_selection {
+            reactor.outline_selected_container(command_space);
+        }
+    }
+
+    /// The window `move_focus` should land on when it involves a window outside
+    /// the layout tree, going by frames. `None` leaves the move to the tree,
+    /// which also covers moving from one tiled window to another.
+    fn geometric_focus_target(reactor: &Reactor, direction: Direction) -> Option<WindowId> {
+        let mode = reactor.config.settings.directional_focus;
+        if mode == DirectionalFocus::Tree {
+            return None;
+        }
+        let focused = reactor.main_window()?;
+        let from = reactor.window_manager.windows.get(&focused)?.frame_monotonic;
+        let space = reactor.best_space_for_window_id(focused)?;
+        let engine = &reactor.layout_manager.layout_engine;
+        let tiled = engine.visible_tiled_windows(space);
+        let mut candidates = tiled.clone();
+        candidates.extend(engine.active_floating_windows_in_workspace(space));
+        if mode == DirectionalFocus::All {
+            let apps = &reactor.app_manager.apps;
+            for (&wid, window) in &reactor.window_manager.windows {
+                let app_hidden = apps.get(&wid.pid).is_none_or(|app| app.is_hidden);
+                let unmanaged = !window.is_effectively_manageable()
+                    && window.info.is_standard
+                    && !window.info.is_minimized
+                    && !app_hidden;
+                if unmanaged && reactor.best_space_for_window_state(window) == Some(space) {
+                    candidates.push(wid);
+                }
+            }
+        }
+        let frames = candidates.into_iter().filter(|&wid| wid != focused).filter_map(|wid| {
+            Some((wid, reactor.window_manager.windows.get(&wid)?.frame_monotonic))
+        });
+        let target = nearest_in_direction(from, direction, frames)?;
+        if tiled.contains(&focused) && tiled.contains(&target) {
+            return None;
+        }
+        Some(target)
     }
 
     pub fn handle_command_metrics(_reactor: &mut Reactor, cmd: MetricsCommand) {
@@ -125,18 +204,43 @@ impl CommandEventHandler {
     }
 
     pub fn handle_config_updated(reactor: &mut Reactor, new_cfg: Config) {
-        let old_keys = reactor.config.keys.clone();
+        let config = match reactor.presentation_manager.saved_config.as_mut() {
+            Some(saved) => {
+                *saved = new_cfg;
+                PresentationManager::presentation_config(saved)
+            }
+            None => new_cfg,
+        };
+        Self::apply_config(reactor, config);
+    }
+
+    /// Puts `config` into effect, quieted further if the kill switch is engaged.
+    /// Unlike [`handle_config_updated`](Self::handle_config_updated), this leaves
+    /// the config presentation mode restores alone.
+    pub fn apply_config(reactor: &mut Reactor, config: Config) {
+        let old_display_rules = reactor.config.settings.display_activation.clone();
 
-        reactor.config = new_cfg;
+        reactor.config = if reactor.space_activation_policy.kill_switch_engaged() {
+            managers::kill_switch_config(&config)
+        } else {
+            config
+        };
         reactor
             .layout_manager
             .layout_engine
             .set_layout_settings(&reactor.config.settings.layout);
 
-        reactor
-            .layout_manager
-            .layout_engine
-            .update_virtual_workspace_settings(&reactor.config.virtual_workspaces);
+        reactor.apply_focus_mode_overrides();
+
+        if old_display_rules != reactor.config.settings.display_activation {
+            reactor
+                .space_activation_policy
+                .set_display_rules(reactor.config.settings.display_activation.clone());
+            let cfg = reactor.activation_cfg();
+            let screens = reactor.screens_for_current_spaces();
+            reactor.space_activation_policy.on_spaces_updated(cfg, &screens);
+            reactor.recompute_and_set_active_spaces_from_current_screens();
+        }
 
         reactor.drag_manager.update_config(reactor.config.settings.window_snapping);
 
@@ -152,12 +256,13 @@ impl CommandEventHandler {
             }
         }
 
+        reactor.refresh_reserved_insets();
         let _ = reactor.update_layout_or_warn(false, true);
 
-        if old_keys != reactor.config.keys {
-            if let Some(wm) = &reactor.communication_manager.wm_sender {
-                wm.send(WmEvent::ConfigUpdated(reactor.config.clone()));
-            }
+        // The WM controller checks for changed hotkeys itself, and also passes the
+        // config on to the event tap and mission control.
+        if let Some(wm) = &reactor.communication_manager.wm_sender {
+            wm.send(WmEvent::ConfigUpdated(reactor.config.clone()));
         }
     }
 
@@ -178,6 +283,9 @@ impl CommandEventHandler {
             ReactorCommand::ToggleSpaceActivated => {
                 Self::handle_command_reactor_toggle_space_activated(reactor);
             }
+            ReactorCommand::ToggleDisplayActivated { display_uuid } => {
+                Self::handle_command_reactor_toggle_display_activated(reactor, display_uuid);
+            }
             ReactorCommand::FocusWindow { window_id, window_server_id } => {
                 Self::handle_command_reactor_focus_window(reactor, window_id, window_server_id)
             }
@@ -213,9 +321,122 @@ impl CommandEventHandler {
             ReactorCommand::MoveWindowToDisplay { selector, window_id } => {
                 Self::handle_command_reactor_move_window_to_display(reactor, &selector, window_id);
             }
+            ReactorCommand::ToggleWindowStyling => {
+                Self::handle_command_reactor_toggle_window_styling(reactor);
+            }
+            ReactorCommand::TogglePresentationMode { monocle } => {
+                Self::handle_command_reactor_toggle_presentation_mode(reactor, monocle);
+            }
+            ReactorCommand::ToggleDebugOverlay => {
+                Self::handle_command_reactor_toggle_debug_overlay(reactor);
+            }
+            ReactorCommand::SelectExclusionZone => {
+                if !send_wm_cmd(reactor, crate::actor::wm_controller::WmCmd::SelectExclusionZone) {
+                    warn!("Cannot select an exclusion zone without the wm controller");
+                }
+            }
+            ReactorCommand::RemoveExclusionZone { id } => {
+                if reactor.exclusion_zones.remove(id) {
+                    info!(id, "Removed exclusion zone");
+                    Self::relayout_for_exclusion_zones(reactor);
+                } else {
+                    warn!(id, "No exclusion zone with this id");
+                }
+            }
+            ReactorCommand::ClearExclusionZones { display_uuid } => {
+                let removed = reactor.exclusion_zones.clear(display_uuid.as_deref());
+                info!(removed, ?display_uuid, "Cleared exclusion zones");
+                if removed > 0 {
+                    Self::relayout_for_exclusion_zones(reactor);
+                }
+            }
+            ReactorCommand::PauseSpace { space_id } => {
+                Self::handle_command_reactor_set_space_paused(reactor, space_id, true);
+            }
+            ReactorCommand::ResumeSpace { space_id } => {
+                Self::handle_command_reactor_set_space_paused(reactor, space_id, false);
+            }
+            ReactorCommand::RestoreAll { only_floats } => {
+                let policy = if only_floats {
+                    RestoreFrames::OnlyFloats
+                } else {
+                    RestoreFrames::Restore
+                };
+                let restored = reactor.restore_original_frames(policy);
+                info!(restored, only_floats, "Restored original window frames");
+            }
+            ReactorCommand::ToggleTopmost { window_server_id } => {
+                Self::handle_command_reactor_toggle_topmost(reactor, window_server_id);
+            }
+            ReactorCommand::FloatLower => Self::handle_command_reactor_float_lower(reactor),
+            ReactorCommand::FloatCycle => Self::handle_command_reactor_float_cycle(reactor),
+            ReactorCommand::ProjectOpen { name } => {
+                Self::handle_command_reactor_project_open(reactor, &name);
+            }
+            ReactorCommand::ProjectClose { name } => {
+                Self::handle_command_reactor_project_close(reactor, name.as_deref());
+            }
+            ReactorCommand::GatherWindows { from } => {
+                Self::handle_command_reactor_gather_windows(reactor, from);
+            }
+            ReactorCommand::BanishPointer => {
+                if !send_wm_cmd(reactor, crate::actor::wm_controller::WmCmd::BanishPointer) {
+                    warn!("Cannot banish the pointer without the wm controller");
+                }
+            }
+            ReactorCommand::TogglePointerHiding => {
+                let cmd = crate::actor::wm_controller::WmCmd::TogglePointerHiding;
+                if !send_wm_cmd(reactor, cmd) {
+                    warn!("Cannot toggle pointer hiding without the wm controller");
+                }
+            }
+            ReactorCommand::ZoomIntoWindow => {
+                if !send_wm_cmd(reactor, crate::actor::wm_controller::WmCmd::ZoomIntoWindow) {
+                    warn!("Cannot zoom without the wm controller");
+                }
+            }
+            ReactorCommand::ZoomIntoRegion => {
+                if !send_wm_cmd(reactor, crate::actor::wm_controller::WmCmd::ZoomIntoRegion) {
+                    warn!("Cannot zoom without the wm controller");
+                }
+            }
+            ReactorCommand::ShowWindowHints => {
+                if !send_wm_cmd(reactor, crate::actor::wm_controller::WmCmd::ShowWindowHints) {
+                    warn!("Cannot show window hints without the wm controller");
+                }
+            }
+            ReactorCommand::Throw { target, follow } => {
+                Self::handle_command_reactor_throw(reactor, &target, follow);
+            }
         }
     }
 
+    pub fn handle_command_reactor_set_space_paused(
+        reactor: &mut Reactor,
+        space_id: Option<SpaceId>,
+        paused: bool,
+    ) {
+        let Some(space) = space_id.or_else(|| reactor.workspace_command_space()) else {
+            warn!("No space to {}", if paused { "pause" } else { "resume" });
+            return;
+        };
+        let changed = if paused {
+            reactor.space_activation_policy.pause_space(space)
+        } else {
+            reactor.space_activation_policy.resume_space(space)
+        };
+        if !changed {
+            return;
+        }
+        info!(?space, paused, "Changed space pause state");
+        reactor.broadcast_mode_changed(broadcast::Mode::SpacePaused, paused, Some(space));
+        if !paused {
+            // Catch up on everything that changed while the space was frozen.
+            reactor.update_layout_or_warn(false, false);
+        }
+        reactor.maybe_send_menu_update();
+    }
+
     pub fn handle_command_reactor_serialize(reactor: &mut Reactor) {
         if let Ok(state) = reactor.serialize_state() {
             println!("{}", state);
@@ -223,13 +444,24 @@ impl CommandEventHandler {
     }
 
     pub fn handle_command_reactor_save_and_exit(reactor: &mut Reactor) {
-        match reactor.layout_manager.layout_engine.save(config::restore_file()) {
-            Ok(()) => std::process::exit(0),
+        reactor.window_identities.flush();
+        let code = match reactor.layout_manager.layout_engine.save(config::restore_file()) {
+            Ok(()) => 0,
             Err(e) => {
                 error!("Could not save layout: {e}");
-                std::process::exit(3);
+                3
             }
+        };
+        reactor.clear_topmost_windows();
+        WorkspaceHideManager::show_all(reactor);
+        let restored =
+            reactor.restore_original_frames(reactor.config.settings.restore_frames_on_exit);
+        if restored > 0 {
+            // The app threads move the windows, so give them a moment before exiting.
+            info!(restored, "Restored original window frames before exiting");
+            std::thread::sleep(EXIT_RESTORE_GRACE);
         }
+        std::process::exit(code);
     }
 
     pub fn handle_command_reactor_toggle_space_activated(reactor: &mut Reactor) {
@@ -256,6 +488,103 @@ impl CommandEventHandler {
         reactor.recompute_and_set_active_spaces_from_current_screens();
     }
 
+    pub fn handle_command_reactor_toggle_display_activated(
+        reactor: &mut Reactor,
+        display_uuid: String,
+    ) {
+        let cfg = reactor.activation_cfg();
+        let Some(space) = reactor
+            .space_manager
+            .screens
+            .iter()
+            .find(|screen| screen.display_uuid == display_uuid)
+            .and_then(|screen| screen.space)
+        else {
+            warn!(%display_uuid, "No space is visible on this display");
+            return;
+        };
+
+        reactor.space_activation_policy.toggle_space_activated(
+            cfg,
+            crate::model::space_activation::ToggleSpaceContext {
+                space,
+                display_uuid: Some(display_uuid),
+            },
+        );
+
+        reactor.recompute_and_set_active_spaces_from_current_screens();
+    }
+
+    pub fn handle_command_reactor_toggle_window_styling(reactor: &mut Reactor) {
+        let enabled = !reactor.window_style_manager.enabled;
+        reactor.window_style_manager.enabled = enabled;
+        info!(enabled, "Toggled window shadow/corner styling");
+        reactor.update_layout_or_warn(false, false);
+    }
+
+    pub fn handle_command_reactor_toggle_debug_overlay(reactor: &mut Reactor) {
+        let enabled = !reactor.layout_manager.debug_overlay;
+        reactor.layout_manager.debug_overlay = enabled;
+        info!(enabled, "Toggled layout debug overlay");
+        if !enabled {
+            LayoutManager::send_debug_overlay(reactor, Vec::new());
+        }
+        reactor.update_layout_or_warn(false, false);
+    }
+
+    pub fn handle_command_reactor_toggle_presentation_mode(reactor: &mut Reactor, monocle: bool) {
+        if let Some(saved) = reactor.presentation_manager.saved_config.take() {
+            info!("Leaving presentation mode");
+            reactor.communication_manager.event_broadcaster.set_paused(false);
+            reactor.broadcast_mode_changed(broadcast::Mode::Presentation, false, None);
+            if let Some((space, index, mode)) = reactor.presentation_manager.monocle_restore.take()
+            {
+                Self::set_workspace_layout_on_space(reactor, space, index, mode);
+            }
+            Self::handle_config_updated(reactor, saved);
+            return;
+        }
+
+        info!(monocle, "Entering presentation mode");
+        reactor.broadcast_mode_changed(broadcast::Mode::Presentation, true, None);
+        reactor.communication_manager.event_broadcaster.set_paused(true);
+        reactor.presentation_manager.saved_config = Some(reactor.config.clone());
+        if monocle
+            && let Some(space) = reactor.workspace_command_space()
+            && let Some(index) = reactor.layout_manager.layout_engine.active_workspace_idx(space)
+        {
+            let previous = reactor.layout_manager.layout_engine.active_layout_mode_at(space);
+            if previous != LayoutMode::Stack {
+                let index = index as usize;
+                Self::set_workspace_layout_on_space(reactor, space, index, LayoutMode::Stack);
+                reactor.presentation_manager.monocle_restore = Some((space, index, previous));
+            }
+        }
+        let config = reactor.config.clone();
+        Self::handle_config_updated(reactor, config);
+    }
+
+    fn relayout_for_exclusion_zones(reactor: &mut Reactor) {
+        if reactor.refresh_reserved_insets() {
+            let _ = reactor.update_layout_or_warn(false, false);
+        }
+    }
+
+    fn set_workspace_layout_on_space(
+        reactor: &mut Reactor,
+        space: SpaceId,
+        index: usize,
+        mode: LayoutMode,
+    ) {
+        let cmd = LayoutCommand::SetWorkspaceLayout { workspace: Some(index), mode };
+        let response = reactor
+            .layout_manager
+            .layout_engine
+            .handle_virtual_workspace_command(space, &cmd);
+        reactor.handle_layout_response(response, Some(space));
+        reactor.update_event_tap_layout_mode();
+    }
+
     pub fn handle_command_reactor_focus_window(
         reactor: &mut Reactor,
         window_id: WindowId,
@@ -283,6 +612,7 @@ impl CommandEventHandler {
                 focus_window: Some((window_id, None)),
                 app_handles,
                 focus_quiet: Quiet::No,
+                window_order: Vec::new(),
             });
             if let Err(e) = reactor.communication_manager.raise_manager_tx.try_send(request) {
                 warn!("Failed to send raise request: {}", e);
@@ -397,12 +727,25 @@ impl CommandEventHandler {
             return;
         };
 
+        if let Some(response) = Self::move_window_to_display(reactor, window_id, selector) {
+            reactor.handle_layout_response(response, None);
+            let _ = reactor.update_layout_or_warn(false, false);
+        }
+    }
+
+    /// Moves `window_id` to the display picked by `selector`, returning the
+    /// layout response to apply, or `None` if it cannot or need not move.
+    fn move_window_to_display(
+        reactor: &mut Reactor,
+        window_id: WindowId,
+        selector: &DisplaySelector,
+    ) -> Option<EventResponse> {
         let (window_server_id, window_frame) = match reactor.window_manager.windows.get(&window_id)
         {
             Some(state) => (state.info.sys_id, state.frame_monotonic),
             None => {
                 warn!(?window_id, "Move window to display ignored: unknown window");
-                return;
+                return None;
             }
         };
 
@@ -413,7 +756,7 @@ impl CommandEventHandler {
                 ?window_id,
                 "Move window to display ignored: source space unknown"
             );
-            return;
+            return None;
         };
         if !reactor.is_space_active(source_space) {
             warn!(
@@ -421,7 +764,7 @@ impl CommandEventHandler {
                 ?source_space,
                 "Move window to display ignored: source space is inactive"
             );
-            return;
+            return None;
         }
 
         let origin_screen = reactor.space_manager.screen_by_space(source_space);
@@ -435,14 +778,14 @@ impl CommandEventHandler {
                 ?selector,
                 "Move window to display ignored: target display not found"
             );
-            return;
+            return None;
         };
         let Some(target_space) = target_screen.space else {
             warn!(
                 uuid = ?target_screen.display_uuid,
                 "Move window to display ignored: display has no active space"
             );
-            return;
+            return None;
         };
         if !reactor.is_space_active(target_space) {
             warn!(
@@ -450,13 +793,41 @@ impl CommandEventHandler {
                 ?target_space,
                 "Move window to display ignored: target display space is inactive"
             );
-            return;
+            return None;
         }
 
         if target_space == source_space {
-            return;
+            return None;
         }
 
+        Some(Self::move_window_to_screen(
+            reactor,
+            window_id,
+            source_space,
+            &target_screen,
+        ))
+    }
+
+    /// Moves a window from `source_space` onto `target_screen` and its space,
+    /// centred there until the layout gives it a frame.
+    fn move_window_to_screen(
+        reactor: &mut Reactor,
+        window_id: WindowId,
+        source_space: SpaceId,
+        target_screen: &ScreenInfo,
+    ) -> EventResponse {
+        let Some(target_space) = target_screen.space else {
+            return EventResponse::default();
+        };
+        let Some((window_server_id, window_frame)) = reactor
+            .window_manager
+            .windows
+            .get(&window_id)
+            .map(|state| (state.info.sys_id, state.frame_monotonic))
+        else {
+            return EventResponse::default();
+        };
+
         let mut target_frame = window_frame;
         let size = window_frame.size;
         let dest_rect = target_screen.frame;
@@ -468,44 +839,207 @@ impl CommandEventHandler {
         origin.x = origin.x.max(min.x).min(max.x - size.width);
         origin.y = origin.y.max(min.y).min(max.y - size.height);
         target_frame.origin = origin;
+        Self::request_window_frame(reactor, window_id, window_server_id, target_frame);
+
+        reactor.layout_manager.layout_engine.move_window_to_space(
+            source_space,
+            target_space,
+            target_screen.frame.size,
+            window_id,
+        )
+    }
 
+    /// Asks the window's app to move it to `frame`, and records that as where it is.
+    fn request_window_frame(
+        reactor: &mut Reactor,
+        window_id: WindowId,
+        window_server_id: Option<WindowServerId>,
+        frame: CGRect,
+    ) {
         if let Some(app) = reactor.app_manager.apps.get(&window_id.pid) {
-            if let Some(wsid) = window_server_id {
-                let txid = reactor.transaction_manager.generate_next_txid(wsid);
-                reactor.transaction_manager.set_last_sent_txid(wsid, txid);
-                let _ = app.handle.send(crate::actor::app::Request::SetWindowFrame(
-                    window_id,
-                    target_frame,
-                    txid,
-                    true,
-                ));
-            } else {
-                let txid = TransactionId::default();
-                let _ = app.handle.send(crate::actor::app::Request::SetWindowFrame(
-                    window_id,
-                    target_frame,
-                    txid,
-                    true,
-                ));
-            }
+            let txid = match window_server_id {
+                Some(wsid) => {
+                    let txid = reactor.transaction_manager.generate_next_txid(wsid);
+                    reactor.transaction_manager.set_last_sent_txid(wsid, txid);
+                    txid
+                }
+                None => TransactionId::default(),
+            };
+            let _ = app.handle.send(crate::actor::app::Request::SetWindowFrame(
+                window_id, frame, txid, true,
+            ));
         }
 
         if let Some(state) = reactor.window_manager.windows.get_mut(&window_id) {
-            state.frame_monotonic = target_frame;
+            state.frame_monotonic = frame;
         }
+    }
 
-        let response = reactor.layout_manager.layout_engine.move_window_to_space(
-            source_space,
-            target_space,
-            target_screen.frame.size,
-            window_id,
-        );
+    pub fn handle_command_reactor_throw(reactor: &mut Reactor, target: &ThrowTarget, follow: bool) {
+        if reactor.is_in_drag() {
+            warn!("Ignoring throw while a drag is active");
+            return;
+        }
+        let Some(window_id) = reactor.main_window() else {
+            warn!("Throw ignored: no focused window");
+            return;
+        };
+        match target {
+            ThrowTarget::Edge(direction) => Self::throw_to_edge(reactor, window_id, *direction),
+            ThrowTarget::Display(selector) => {
+                Self::throw_to_display(reactor, window_id, selector, follow)
+            }
+            ThrowTarget::Workspace(selector) => Self::throw_to_workspace(reactor, selector, follow),
+        }
+    }
 
-        reactor.handle_layout_response(response, None);
+    /// A tiled window is moved to the end of its layout; a floating one keeps
+    /// its size and slides to the edge of the tiling area.
+    fn throw_to_edge(reactor: &mut Reactor, window_id: WindowId, direction: Direction) {
+        let Some(state) = reactor.window_manager.windows.get(&window_id) else {
+            return;
+        };
+        let (window_server_id, frame) = (state.info.sys_id, state.frame_monotonic);
+        let Some(space) = reactor.best_space_for_window_state(state) else {
+            warn!(?window_id, "Throw ignored: window is on no known space");
+            return;
+        };
+        let engine = &mut reactor.layout_manager.layout_engine;
+        if !engine.is_window_floating(window_id) {
+            if engine.move_window_to_edge(space, window_id, direction) {
+                let _ = reactor.update_layout_or_warn(false, false);
+            }
+            return;
+        }
 
+        let Some(area) = engine.tiling_area(space) else {
+            return;
+        };
+        let mut target = frame;
+        target.size.width = frame.size.width.min(area.size.width);
+        target.size.height = frame.size.height.min(area.size.height);
+        match direction {
+            Direction::Left => target.origin.x = area.min().x,
+            Direction::Right => target.origin.x = area.max().x - target.size.width,
+            Direction::Up => target.origin.y = area.min().y,
+            Direction::Down => target.origin.y = area.max().y - target.size.height,
+        }
+        if target.same_as(frame) {
+            return;
+        }
+        Self::request_window_frame(reactor, window_id, window_server_id, target);
+        reactor.remember_float_frame(window_id, target);
+    }
+
+    fn throw_to_display(
+        reactor: &mut Reactor,
+        window_id: WindowId,
+        selector: &DisplaySelector,
+        follow: bool,
+    ) {
+        let source_space = reactor.workspace_command_space();
+        let Some(mut response) = Self::move_window_to_display(reactor, window_id, selector) else {
+            return;
+        };
+        if !follow {
+            // Focus what the window leaves behind instead of the window itself.
+            let engine = &reactor.layout_manager.layout_engine;
+            response.focus_window = source_space.and_then(|space| {
+                engine.selected_window(space).or_else(|| {
+                    engine.active_floating_windows_in_workspace(space).into_iter().next()
+                })
+            });
+            response.raise_windows = response.focus_window.into_iter().collect();
+        }
+        reactor.handle_layout_response(response, None);
         let _ = reactor.update_layout_or_warn(false, false);
     }
 
+    /// Moving to a workspace already leaves focus behind; following switches
+    /// to that workspace, where the window is the one remembered as focused.
+    fn throw_to_workspace(reactor: &mut Reactor, selector: &WorkspaceSelector, follow: bool) {
+        let workspace = match selector {
+            WorkspaceSelector::Index(index) => *index,
+            WorkspaceSelector::Name(name) => {
+                // Workspaces can be added and renamed at runtime, so the names in
+                // the config are not enough.
+                let Some(space) = reactor.workspace_command_space() else {
+                    warn!(?name, "Throw ignored: no active space");
+                    return;
+                };
+                let engine = &mut reactor.layout_manager.layout_engine;
+                let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+                let Some(index) = workspaces.iter().position(|(_, n)| n == name) else {
+                    warn!(?name, "Throw ignored: no workspace with that name");
+                    return;
+                };
+                index
+            }
+        };
+        let window_id = None;
+        Self::handle_command_layout(reactor, LayoutCommand::MoveWindowToWorkspace {
+            workspace,
+            window_id,
+        });
+        if follow {
+            Self::handle_command_layout(reactor, LayoutCommand::SwitchToWorkspace(workspace));
+        }
+    }
+
+    pub fn handle_command_reactor_gather_windows(reactor: &mut Reactor, from: GatherSource) {
+        if reactor.is_in_drag() {
+            warn!("Ignoring gather-windows while a drag is active");
+            return;
+        }
+        let Some(space) = reactor.workspace_command_space() else {
+            warn!("No space to gather windows into");
+            return;
+        };
+        let Some(screen) = reactor.space_manager.screen_by_space(space).cloned() else {
+            warn!(?space, "Gather windows ignored: space is not on a display");
+            return;
+        };
+        let engine = &reactor.layout_manager.layout_engine;
+        let Some(workspace_index) = engine.active_workspace_idx(space) else {
+            warn!(?space, "Gather windows ignored: no active workspace");
+            return;
+        };
+        let other_spaces: Vec<SpaceId> = if from.includes_displays() {
+            reactor.iter_active_spaces().filter(|&other| other != space).collect()
+        } else {
+            Vec::new()
+        };
+        let windows: Vec<(WindowId, SpaceId)> = engine
+            .windows_to_gather(space, from.includes_workspaces(), &other_spaces)
+            .into_iter()
+            .filter(|(wid, _)| {
+                reactor.window_manager.windows.get(wid).is_some_and(|w| !w.info.is_minimized)
+            })
+            .collect();
+
+        // The moved windows are not focused one by one; the layout update below
+        // places them all and focus stays where it was.
+        for &(window_id, source_space) in &windows {
+            if source_space == space {
+                let _ = reactor.layout_manager.layout_engine.move_window_to_workspace(
+                    space,
+                    window_id,
+                    workspace_index as usize,
+                );
+            } else {
+                let _ = Self::move_window_to_screen(reactor, window_id, source_space, &screen);
+            }
+        }
+        info!(
+            ?from,
+            gathered = windows.len(),
+            "Gathered windows into the active workspace"
+        );
+        if !windows.is_empty() {
+            let _ = reactor.update_layout_or_warn(false, false);
+        }
+    }
+
     pub fn handle_command_reactor_close_window(
         reactor: &mut Reactor,
         window_server_id: Option<WindowServerId>,
@@ -519,6 +1053,137 @@ impl CommandEventHandler {
             warn!("Close window command ignored because no window is tracked");
         }
     }
+
+    pub fn handle_command_reactor_toggle_topmost(
+        reactor: &mut Reactor,
+        window_server_id: Option<WindowServerId>,
+    ) {
+        let target = window_server_id
+            .and_then(|wsid| reactor.window_manager.window_ids.get(&wsid).copied())
+            .or_else(|| reactor.main_window());
+        let Some(wid) = target else {
+            warn!("Toggle topmost command ignored because no window is tracked");
+            return;
+        };
+        if let Some(topmost) = reactor.toggle_topmost(wid) {
+            info!(?wid, topmost, "Toggled topmost");
+        }
+    }
+
+    pub fn handle_command_reactor_project_open(reactor: &mut Reactor, name: &str) {
+        let projects = &reactor.config.virtual_workspaces.projects;
+        let Some(project) = projects.iter().find(|p| p.name == name).cloned() else {
+            warn!(name, "Project open ignored: no such project in the config");
+            return;
+        };
+        let Some(space) = reactor.workspace_command_space() else {
+            warn!(name, "Project open ignored: no active space");
+            return;
+        };
+        let engine = &mut reactor.layout_manager.layout_engine;
+        let Some(index) = engine.named_workspace_index(space, name) else {
+            return;
+        };
+        let is_empty = engine.workspace_windows_at(space, Some(index)).is_empty();
+
+        if is_empty && let Some(mode) = project.layout {
+            Self::set_workspace_layout_on_space(reactor, space, index, mode);
+        }
+        Self::handle_command_layout(reactor, LayoutCommand::SwitchToWorkspace(index));
+        if !is_empty {
+            return;
+        }
+
+        info!(name, apps = project.apps.len(), "Opening project");
+        for app in project.apps {
+            // The wm controller registers the placement once it knows which
+            // process the launch started.
+            let placed = match app {
+                ExecCmd::Placed(placed) => PlacedExecCmd {
+                    workspace: Some(WorkspaceSelector::Index(index)),
+                    ..placed
+                },
+                command => PlacedExecCmd {
+                    command: Box::new(command),
+                    workspace: Some(WorkspaceSelector::Index(index)),
+                    floating: false,
+                    display: None,
+                },
+            };
+            if !send_wm_cmd(reactor, WmCmd::Exec(ExecCmd::Placed(placed))) {
+                warn!(name, "Cannot launch project apps without the wm controller");
+                return;
+            }
+        }
+    }
+
+    /// Asks every window on the workspace to close the way its close button
+    /// would, so apps can still prompt about unsaved work.
+    pub fn handle_command_reactor_project_close(reactor: &mut Reactor, name: Option<&str>) {
+        let Some(space) = reactor.workspace_command_space() else {
+            warn!(?name, "Project close ignored: no active space");
+            return;
+        };
+        let engine = &mut reactor.layout_manager.layout_engine;
+        let index = match name {
+            Some(name) => {
+                let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+                let Some(index) = workspaces.iter().position(|(_, n)| n == name) else {
+                    warn!(name, "Project close ignored: no workspace with this name");
+                    return;
+                };
+                Some(index)
+            }
+            None => None,
+        };
+        let windows = engine.workspace_windows_at(space, index);
+        info!(?name, windows = windows.len(), "Closing project windows");
+        for wid in windows {
+            reactor.request_close_window(wid);
+        }
+    }
+
+    pub fn handle_command_reactor_float_lower(reactor: &mut Reactor) {
+        let Some((wid, space)) = Self::focused_float(reactor) else {
+            return;
+        };
+        let back_to_front: Vec<WindowId> = reactor
+            .floats_front_to_back(space)
+            .into_iter()
+            .rev()
+            .map(|(other, _)| other)
+            .filter(|&other| other != wid)
+            .collect();
+        reactor.raise_floats(space, back_to_front);
+    }
+
+    pub fn handle_command_reactor_float_cycle(reactor: &mut Reactor) {
+        let Some((wid, space)) = Self::focused_float(reactor) else {
+            return;
+        };
+        let floats = reactor.floats_front_to_back(space);
+        let Some(&(_, frame)) = floats.iter().find(|(other, _)| *other == wid) else {
+            return;
+        };
+        let bottom = floats.iter().rev().find(|(other, other_frame)| {
+            *other != wid && other_frame.intersection(&frame).area() > 0.0
+        });
+        if let Some(&(bottom, _)) = bottom {
+            reactor.raise_floats(space, vec![bottom]);
+        }
+    }
+
+    fn focused_float(reactor: &Reactor) -> Option<(WindowId, SpaceId)> {
+        let Some(wid) = reactor.main_window() else {
+            warn!("Float command ignored because no window is focused");
+            return None;
+        };
+        if !reactor.layout_manager.layout_engine.is_window_floating(wid) {
+            debug!(?wid, "Float command ignored because the focused window is tiled");
+            return None;
+        }
+        Some((wid, reactor.best_space_for_window_id(wid)?))
+    }
 }
 
 fn send_wm_cmd(reactor: &mut Reactor, cmd: crate::actor::wm_controller::WmCmd) -> bool {
@@ -1,12 +1,20 @@
-use tracing::trace;
+use objc2_core_foundation::CGPoint;
+use tracing::{debug, trace};
 
-use crate::actor::reactor::{DragState, Reactor};
-use crate::layout_engine::LayoutCommand;
+use crate::actor::app::Request;
+use crate::actor::reactor::events::command::CommandEventHandler;
+use crate::actor::reactor::{
+    DragState, ModifierDrag, ModifierDragMode, Reactor, TransactionId, WindowFilter,
+};
+use crate::layout_engine::{LayoutCommand, LayoutEvent};
+use crate::sys::geometry::SameAs;
+use crate::sys::window_server::WindowServerId;
 
 pub struct DragEventHandler;
 
 impl DragEventHandler {
     pub fn handle_mouse_up(reactor: &mut Reactor) {
+        reactor.drag_manager.modifier_drag = None;
         let mut need_layout_refresh = false;
 
         let pending_swap = reactor.get_pending_drag_swap();
@@ -72,4 +80,101 @@ impl DragEventHandler {
 
         reactor.drag_manager.skip_layout_for_window = None;
     }
+
+    pub fn handle_modifier_drag_started(
+        reactor: &mut Reactor,
+        wsid: WindowServerId,
+        start: CGPoint,
+        mode: ModifierDragMode,
+    ) {
+        if reactor.is_mission_control_active() {
+            return;
+        }
+        let Some(&wid) = reactor.window_manager.window_ids.get(&wsid) else {
+            return;
+        };
+        let Some(window) = reactor.window_manager.windows.get(&wid) else {
+            return;
+        };
+        if !window.matches_filter(WindowFilter::EffectivelyManageable) {
+            return;
+        }
+        debug!(?wid, ?mode, "Starting modifier drag");
+        reactor.drag_manager.modifier_drag = Some(ModifierDrag {
+            window: wid,
+            mode,
+            start,
+            start_frame: window.frame_monotonic,
+        });
+        CommandEventHandler::handle_command_reactor_focus_window(reactor, wid, Some(wsid));
+    }
+
+    /// Moves or resizes the dragged window to follow the pointer, then treats the
+    /// new frame the way a drag by the title bar or a window edge would be treated:
+    /// floating windows keep it, tiled ones swap places or change their ratios.
+    pub fn handle_modifier_dragged(reactor: &mut Reactor, loc: CGPoint) {
+        let Some(drag) = reactor.drag_manager.modifier_drag.clone() else {
+            return;
+        };
+        let wid = drag.window;
+        let Some(window) = reactor.window_manager.windows.get(&wid) else {
+            reactor.drag_manager.modifier_drag = None;
+            return;
+        };
+        let (old_frame, wsid) = (window.frame_monotonic, window.info.sys_id);
+        let new_frame = drag.frame_at(loc);
+        if old_frame.same_as(new_frame) {
+            return;
+        }
+
+        let Some(app) = reactor.app_manager.apps.get(&wid.pid) else {
+            return;
+        };
+        let txid = match wsid {
+            Some(wsid) => {
+                let txid = reactor.transaction_manager.generate_next_txid(wsid);
+                reactor.transaction_manager.set_last_sent_txid(wsid, txid);
+                txid
+            }
+            None => TransactionId::default(),
+        };
+        if app.handle.send(Request::SetWindowFrame(wid, new_frame, txid, true)).is_err() {
+            return;
+        }
+        if let Some(window) = reactor.window_manager.windows.get_mut(&wid) {
+            window.frame_monotonic = new_frame;
+        }
+        if reactor.layout_manager.layout_engine.is_window_floating(wid) {
+            reactor.remember_float_frame(wid, new_frame);
+        }
+
+        reactor.ensure_active_drag(wid, &old_frame);
+        reactor.update_active_drag(wid, &new_frame);
+        match drag.mode {
+            ModifierDragMode::Move => reactor.maybe_swap_on_drag(wid, new_frame),
+            ModifierDragMode::Resize => {
+                let on_active_space = reactor
+                    .best_space_for_window(&new_frame, wsid)
+                    .is_some_and(|space| reactor.is_space_active(space));
+                if !on_active_space {
+                    return;
+                }
+                let screens = reactor
+                    .space_manager
+                    .screens
+                    .iter()
+                    .filter_map(|screen| {
+                        let display_uuid = screen.display_uuid_owned();
+                        Some((screen.space?, screen.frame, display_uuid))
+                    })
+                    .collect::<Vec<_>>();
+                reactor.send_layout_event(LayoutEvent::WindowResized {
+                    wid,
+                    old_frame,
+                    new_frame,
+                    screens,
+                });
+            }
+        }
+    }
 }
@@ -5,10 +5,12 @@ use objc2_core_foundation::CGSize;
 use tracing::{debug, info, trace, warn};
 
 use crate::actor::app::Request;
+use crate::actor::broadcast::BroadcastEvent;
 use crate::actor::reactor::{
     Event, FullscreenSpaceTrack, FullscreenWindowTrack, LayoutEvent, PendingSpaceChange, Reactor,
     ScreenInfo, StaleCleanupState,
 };
+use crate::actor::stack_line::Event as StackLineEvent;
 use crate::actor::wm_controller::WmEvent;
 use crate::common::collections::{HashMap, HashSet};
 use crate::sys::app::AppInfo;
@@ -25,6 +27,18 @@ impl SpaceEventHandler {
         wsid: WindowServerId,
         sid: SpaceId,
     ) {
+        if reactor.notification_banners.contains_key(&wsid) {
+            // The same window may come back for the next banner.
+            reactor.window_manager.observed_window_server_ids.remove(&wsid);
+            reactor.set_notification_banner(wsid, None);
+            return;
+        }
+        if reactor.external_bar_windows.remove(&wsid) {
+            reactor.window_manager.observed_window_server_ids.remove(&wsid);
+            reactor.external_bar_changed();
+            return;
+        }
+
         if crate::sys::window_server::space_is_fullscreen(sid.get()) {
             let (pid, window_id) = if let Some(&wid) = reactor.window_manager.window_ids.get(&wsid)
             {
@@ -105,6 +119,15 @@ impl SpaceEventHandler {
         // why dont we get notifications that its being launched?
         if let Some(window_server_info) = crate::sys::window_server::get_window(wsid) {
             if window_server_info.layer != 0 {
+                if crate::sys::app::is_notification_center(window_server_info.pid) {
+                    reactor.set_notification_banner(wsid, Some(window_server_info.frame));
+                } else if reactor.config.settings.layout.external_bar.auto_detect
+                    && crate::sys::window_server::window_owner_name(wsid)
+                        .is_some_and(|name| reactor.is_external_bar_owner(&name))
+                {
+                    reactor.external_bar_windows.insert(wsid);
+                    reactor.external_bar_changed();
+                }
                 trace!(
                     ?wsid,
                     layer = window_server_info.layer,
@@ -181,6 +204,7 @@ impl SpaceEventHandler {
         let new_displays: HashSet<String> =
             screens.iter().map(|s| s.display_uuid.clone()).collect();
         let displays_changed = previous_displays != new_displays;
+        let display_removed = !previous_displays.is_subset(&new_displays);
         let display_order_changed = previous_screens
             .iter()
             .map(|s| s.display_uuid.as_str())
@@ -224,6 +248,18 @@ impl SpaceEventHandler {
         if displays_changed {
             let active_list: Vec<String> = new_displays.iter().cloned().collect();
             reactor.layout_manager.layout_engine.prune_display_state(&active_list);
+
+            let mut removed: Vec<&String> = previous_displays.difference(&new_displays).collect();
+            let mut added: Vec<&String> = new_displays.difference(&previous_displays).collect();
+            removed.sort_unstable();
+            added.sort_unstable();
+            let broadcaster = &reactor.communication_manager.event_broadcaster;
+            for display_uuid in removed.into_iter().cloned() {
+                let _ = broadcaster.send(BroadcastEvent::DisplayRemoved { display_uuid });
+            }
+            for display_uuid in added.into_iter().cloned() {
+                let _ = broadcaster.send(BroadcastEvent::DisplayAdded { display_uuid });
+            }
         }
         if !new_displays.is_empty() {
             reactor.space_manager.has_seen_display_set = true;
@@ -247,6 +283,9 @@ impl SpaceEventHandler {
                 .map(|screen| (screen.id, screen.frame.size))
                 .collect();
             reactor.space_manager.screens = screens;
+            if displays_changed {
+                reactor.refresh_remembered_float_frames();
+            }
             let resized_screens: HashSet<ScreenId> = reactor
                 .space_manager
                 .screens
@@ -312,8 +351,12 @@ impl SpaceEventHandler {
                     reactor.send_layout_event(LayoutEvent::SpaceExposed(space, size));
                 }
             }
+            reactor.refresh_reserved_insets();
             let ws_info = reactor.authoritative_window_snapshot_for_active_spaces();
             reactor.finalize_space_change(&spaces, ws_info);
+            if display_removed && reactor.adopt_orphaned_windows() {
+                let _ = reactor.update_layout_or_warn(false, false);
+            }
         }
         reactor.try_apply_pending_space_change();
         reactor.maybe_commit_display_topology_snapshot();
@@ -417,13 +460,34 @@ impl SpaceEventHandler {
     }
 
     pub fn handle_mission_control_native_entered(reactor: &mut Reactor) {
+        // Both the Dock and the window server report it; act on whichever is first.
+        if reactor.is_mission_control_active() {
+            return;
+        }
         reactor.set_mission_control_active(true);
+
+        // Exposé takes the mouse; a drag left pending would end in a swap or
+        // resize once it lets go.
+        reactor.take_active_drag_session();
+        reactor.drag_manager.reset();
+        reactor.drag_manager.modifier_drag = None;
+        reactor.drag_manager.skip_layout_for_window = None;
+
+        if let Some(tx) = &reactor.communication_manager.stack_line_tx {
+            _ = tx.try_send(StackLineEvent::Suspend(true));
+        }
+        if let Some(wm) = &reactor.communication_manager.wm_sender {
+            wm.send(WmEvent::MissionControlNativeEntered);
+        }
     }
 
     pub fn handle_mission_control_native_exited(reactor: &mut Reactor) {
         if reactor.is_mission_control_active() {
             reactor.set_mission_control_active(false);
         }
+        if let Some(tx) = &reactor.communication_manager.stack_line_tx {
+            _ = tx.try_send(StackLineEvent::Suspend(false));
+        }
         reactor.repair_spaces_after_mission_control();
         reactor.refresh_windows_after_mission_control();
     }
@@ -1,9 +1,18 @@
-use tracing::debug;
+use tracing::{debug, info, warn};
 
-use crate::actor::app::WindowId;
+use crate::actor::app::{Request, WindowId};
+use crate::actor::broadcast::{self, BroadcastEvent};
 use crate::actor::raise_manager;
+use crate::actor::reactor::events::command::CommandEventHandler;
+use crate::actor::reactor::managers::{WakeReconcileManager, WorkspaceHideManager};
 use crate::actor::reactor::{MenuState, Reactor};
+use crate::actor::stack_line::Event as StackLineEvent;
 use crate::actor::wm_controller::Sender as WmSender;
+use crate::common::collections::HashSet;
+use crate::common::config::RestoreFrames;
+use crate::sys::geometry::SameAs;
+use crate::sys::screen::{SpaceId, managed_display_space_ids};
+use crate::sys::window_server::{self, WindowServerId};
 
 pub struct SystemEventHandler;
 
@@ -60,6 +69,152 @@ impl SystemEventHandler {
             reactor.window_manager.window_ids.keys().map(|wsid| wsid.as_u32()).collect();
         crate::sys::window_notify::update_window_notifications(&ids);
         reactor.notification_manager.last_sls_notification_ids = ids;
+        WakeReconcileManager::capture(reactor);
+    }
+
+    pub fn handle_wake_reconcile(reactor: &mut Reactor) {
+        let Some(before) = reactor.wake_reconcile_manager.spaces_by_display.take() else {
+            debug!("Wake reconciliation requested without a wake snapshot; ignoring");
+            return;
+        };
+        let after = WakeReconcileManager::current_spaces(reactor);
+        let live_spaces: HashSet<SpaceId> =
+            managed_display_space_ids().into_values().flatten().collect();
+        let diff = WakeReconcileManager::diff(&before, &after, &live_spaces);
+
+        for remap in &diff.remapped_spaces {
+            reactor
+                .space_activation_policy
+                .transfer_space_activation(remap.old_space, remap.new_space);
+            reactor
+                .layout_manager
+                .layout_engine
+                .remap_space(remap.old_space, remap.new_space);
+        }
+        if !diff.remapped_spaces.is_empty() {
+            reactor.recompute_and_set_active_spaces_from_current_screens();
+        }
+
+        let moved_windows = Self::windows_moved_while_asleep(reactor);
+        reactor.force_refresh_all_windows();
+
+        info!(
+            remapped_spaces = ?diff.remapped_spaces,
+            added_displays = ?diff.added_displays,
+            removed_displays = ?diff.removed_displays,
+            moved_windows = moved_windows.len(),
+            "Wake reconciliation finished"
+        );
+        let _ =
+            reactor
+                .communication_manager
+                .event_broadcaster
+                .send(BroadcastEvent::WakeReconciled {
+                    remapped_spaces: diff.remapped_spaces,
+                    added_displays: diff.added_displays,
+                    removed_displays: diff.removed_displays,
+                    moved_windows,
+                });
+    }
+
+    /// Windows whose window server frame no longer matches the last frame we saw.
+    fn windows_moved_while_asleep(reactor: &Reactor) -> Vec<WindowId> {
+        let ids: Vec<WindowServerId> = reactor.window_manager.window_ids.keys().copied().collect();
+        window_server::get_windows(&ids)
+            .into_iter()
+            .filter_map(|info| {
+                let wid = *reactor.window_manager.window_ids.get(&info.id)?;
+                let window = reactor.window_manager.windows.get(&wid)?;
+                (!window.frame_monotonic.same_as(info.frame)).then_some(wid)
+            })
+            .collect()
+    }
+
+    pub fn handle_session_resumed(reactor: &mut Reactor) {
+        info!("Session resumed; revalidating windows and observers");
+        reactor.set_login_window_active(false);
+        for app in reactor.app_manager.apps.values() {
+            // Errors mean the app terminated (and a termination event is coming); ignore.
+            _ = app.handle.send(Request::RefreshObservers);
+        }
+        Self::handle_system_woke(reactor);
+        let raw_spaces = reactor.raw_spaces_for_current_screens();
+        reactor.reconcile_spaces_with_display_history(&raw_spaces, false);
+        let _ = reactor
+            .communication_manager
+            .event_broadcaster
+            .send(BroadcastEvent::SessionResumed);
+    }
+
+    pub fn handle_session_resigned(reactor: &mut Reactor) {
+        info!("Session resigned; suspending management until it resumes");
+        reactor.set_login_window_active(true);
+    }
+
+    /// Hides the stack line and forgets any drag in progress while the lock
+    /// screen is up, then redraws once it is gone. Windows can move while
+    /// locked (a display sleeping, say), so everything is re-read on unlock.
+    pub fn handle_screen_locked(reactor: &mut Reactor, locked: bool) {
+        if locked {
+            reactor.take_active_drag_session();
+            reactor.drag_manager.reset();
+            reactor.drag_manager.modifier_drag = None;
+            reactor.drag_manager.skip_layout_for_window = None;
+        }
+        // Native Mission Control keeps the indicators hidden until it closes.
+        if (locked || !reactor.is_mission_control_active())
+            && let Some(tx) = &reactor.communication_manager.stack_line_tx
+        {
+            _ = tx.try_send(StackLineEvent::Suspend(locked));
+        }
+        if !locked {
+            reactor.request_visible_windows_for_apps(false);
+        }
+    }
+
+    /// Answers the panic hotkey. The WM controller has already closed overlays
+    /// and dropped hotkeys; this stops managing every space, switches off
+    /// animations and the other features that act on their own, and puts
+    /// windows back where rift found them.
+    pub fn handle_kill_switch(reactor: &mut Reactor) {
+        if !reactor.space_activation_policy.engage_kill_switch() {
+            return;
+        }
+        reactor.recompute_and_set_active_spaces_from_current_screens();
+
+        let config = reactor.config.clone();
+        CommandEventHandler::apply_config(reactor, config);
+
+        reactor.clear_topmost_windows();
+        WorkspaceHideManager::show_all(reactor);
+        let restored = reactor.restore_original_frames(RestoreFrames::Restore);
+        warn!(restored, "Kill switch engaged; rift no longer manages any space");
+        reactor.broadcast_mode_changed(broadcast::Mode::KillSwitch, true, None);
+    }
+
+    pub fn handle_focus_mode_changed(reactor: &mut Reactor, focus_mode: Option<String>) {
+        if reactor.focus_mode == focus_mode {
+            return;
+        }
+        debug!(?focus_mode, "focus mode changed");
+        reactor.focus_mode = focus_mode.clone();
+        reactor.apply_focus_mode_overrides();
+        let _ = reactor
+            .communication_manager
+            .event_broadcaster
+            .send(BroadcastEvent::FocusModeChanged { focus_mode });
+    }
+
+    pub fn handle_secure_input_changed(reactor: &mut Reactor, enabled: bool) {
+        if reactor.secure_input == enabled {
+            return;
+        }
+        reactor.secure_input = enabled;
+        let _ = reactor
+            .communication_manager
+            .event_broadcaster
+            .send(BroadcastEvent::SecureInputChanged { enabled });
+        reactor.maybe_send_menu_update();
     }
 
     pub fn handle_raise_completed(reactor: &mut Reactor, window_id: WindowId, sequence_id: u64) {
@@ -2,12 +2,14 @@ use objc2_core_foundation::CGRect;
 use tracing::{debug, trace, warn};
 
 use crate::actor::app::WindowId;
+use crate::actor::reactor::events::command::CommandEventHandler;
 use crate::actor::reactor::events::drag::DragEventHandler;
+use crate::actor::reactor::managers::{NewWindowFocusManager, WindowPlacementManager};
 use crate::actor::reactor::{
     DragState, Quiet, Reactor, Requested, TransactionId, WindowFilter, WindowState, utils,
 };
-use crate::common::config::LayoutMode;
-use crate::layout_engine::LayoutEvent;
+use crate::common::config::{LayoutMode, TitlebarAction};
+use crate::layout_engine::{LayoutCommand, LayoutEvent};
 use crate::sys::app::WindowInfo as Window;
 use crate::sys::event::{MouseState, get_mouse_state};
 use crate::sys::geometry::SameAs;
@@ -53,6 +55,7 @@ impl WindowEventHandler {
 
         let server_id = window_state.info.sys_id;
         reactor.window_manager.windows.insert(wid, window_state);
+        reactor.assign_window_identity(wid);
 
         if is_manageable {
             let active_space = active_space_for_window(reactor, &frame, server_id);
@@ -65,7 +68,11 @@ impl WindowEventHandler {
                     }
                     reactor.process_windows_for_app_rules(wid.pid, vec![wid], app_info);
                 }
+                let previous_focus = reactor.main_window();
                 maybe_dispatch_window_added_in_space(reactor, wid, space);
+                WindowPlacementManager::on_window_created(reactor, wid);
+                WindowPlacementManager::place_new_float(reactor, wid);
+                NewWindowFocusManager::on_window_created(reactor, wid, previous_focus);
             }
         }
         // TODO: drag state is maybe managed by ensure_active_drag
@@ -88,6 +95,7 @@ impl WindowEventHandler {
             debug!(?wid, "Received WindowDestroyed for unknown window - ignoring");
         }
         reactor.window_manager.windows.remove(&wid);
+        reactor.window_identities.release(wid);
         reactor.send_layout_event(LayoutEvent::WindowRemoved(wid));
 
         if let DragState::PendingSwap { session, target } = &reactor.drag_manager.drag_state {
@@ -112,16 +120,26 @@ impl WindowEventHandler {
         if reactor.drag_manager.skip_layout_for_window == Some(wid) {
             reactor.drag_manager.skip_layout_for_window = None;
         }
+        let modifier_dragged = reactor.drag_manager.modifier_drag.as_ref().map(|drag| drag.window);
+        if modifier_dragged == Some(wid) {
+            reactor.drag_manager.modifier_drag = None;
+        }
         true
     }
 
     pub fn handle_window_minimized(reactor: &mut Reactor, wid: WindowId) {
+        let workspace = reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager()
+            .workspace_for_window_any(wid);
         if let Some(window) = reactor.window_manager.windows.get_mut(&wid) {
             if window.info.is_minimized {
                 return;
             }
             window.info.is_minimized = true;
             window.is_manageable = false;
+            window.minimized_in = workspace;
             if let Some(ws_id) = window.info.sys_id {
                 reactor.window_manager.visible_windows.remove(&ws_id);
             }
@@ -139,6 +157,7 @@ impl WindowEventHandler {
                         return;
                     }
                     window.info.is_minimized = false;
+                    window.minimized_in = None;
                     (
                         window.frame_monotonic,
                         window.info.sys_id,
@@ -300,6 +319,9 @@ impl WindowEventHandler {
                 }
                 window.frame_monotonic = new_frame;
             }
+            if reactor.layout_manager.layout_engine.is_window_floating(wid) {
+                reactor.remember_float_frame(wid, new_frame);
+            }
 
             let dragging = effective_mouse_state == Some(MouseState::Down) || reactor.is_in_drag();
 
@@ -415,6 +437,7 @@ impl WindowEventHandler {
                 return;
             }
             window.info.title = new_title.clone();
+            reactor.window_identities.update_title(wid, &new_title);
             reactor.broadcast_window_title_changed(wid, previous_title, new_title);
             reactor.maybe_reapply_app_rules_for_window(wid);
         }
@@ -438,6 +461,61 @@ impl WindowEventHandler {
             }
         }
     }
+
+    /// Focuses the window whose title bar was clicked, then applies `action`
+    /// to it.
+    pub fn handle_titlebar_clicked(
+        reactor: &mut Reactor,
+        wsid: WindowServerId,
+        action: TitlebarAction,
+    ) {
+        let Some(&wid) = reactor.window_manager.window_ids.get(&wsid) else {
+            return;
+        };
+        let Some(space) = reactor.window_manager.windows.get(&wid).and_then(|window| {
+            active_space_for_window(reactor, &window.frame_monotonic, window.info.sys_id)
+        }) else {
+            return;
+        };
+        CommandEventHandler::handle_command_reactor_focus_window(reactor, wid, Some(wsid));
+
+        let command = match action {
+            TitlebarAction::ToggleZoom => LayoutCommand::ToggleFullscreenWithinGaps,
+            TitlebarAction::ToggleFloat => LayoutCommand::ToggleWindowFloating,
+            TitlebarAction::CycleStack => {
+                if let Some(next) = next_window_in_stack(reactor, space, wid) {
+                    CommandEventHandler::handle_command_reactor_focus_window(reactor, next, None);
+                }
+                return;
+            }
+        };
+        CommandEventHandler::handle_command_layout(reactor, command);
+    }
+}
+
+/// The window after `wid` in the stack or tab group holding it, wrapping around.
+fn next_window_in_stack(reactor: &mut Reactor, space: SpaceId, wid: WindowId) -> Option<WindowId> {
+    let screen = reactor.space_manager.screen_by_space(space)?;
+    let frame = screen.frame;
+    let display_uuid = screen.display_uuid_owned();
+    let placement = reactor.stack_line_placement(space, None);
+    let stack_line = &reactor.config.settings.ui.stack_line;
+    let engine = &mut reactor.layout_manager.layout_engine;
+    let gaps = engine.gaps_for_display(display_uuid.as_deref());
+    engine
+        .collect_group_containers(
+            space,
+            frame,
+            &gaps,
+            stack_line.reserved_thickness(placement),
+            placement.horizontal,
+            placement.vertical,
+        )
+        .into_iter()
+        .find_map(|group| {
+            let index = group.window_ids.iter().position(|&w| w == wid)?;
+            Some(group.window_ids[(index + 1) % group.window_ids.len()])
+        })
 }
 
 fn active_space_for_window(
@@ -1,6 +1,7 @@
 use tracing::{trace, warn};
 
 use crate::actor::app::{AppInfo, WindowId, WindowInfo, pid_t};
+use crate::actor::reactor::managers::WindowPlacementManager;
 use crate::actor::reactor::{Event, LayoutEvent, Reactor, WindowFilter, WindowState, utils};
 use crate::common::collections::{BTreeMap, HashSet};
 use crate::model::virtual_workspace::AppRuleResult;
@@ -30,9 +31,13 @@ impl WindowDiscoveryHandler {
             Self::identify_stale_windows(reactor, pid, &known_visible);
         Self::cleanup_stale_windows(reactor, pid, stale_windows, pending_refresh);
         let new_windows = Self::process_window_list(reactor, new, &app_info);
+        let new_wids: Vec<WindowId> = new_windows.iter().map(|(wid, _)| *wid).collect();
         Self::update_window_states(reactor, new_windows, &app_info);
 
         Self::emit_layout_events(reactor, pid, &known_visible, &app_info);
+        for wid in new_wids {
+            WindowPlacementManager::on_window_created(reactor, wid);
+        }
     }
 
     fn sync_window_server_id_mapping(
@@ -120,6 +125,11 @@ impl WindowDiscoveryHandler {
                     return None;
                 }
 
+                // Hidden workspaces can be ordered out on purpose.
+                if reactor.workspace_hide_manager.is_ordered_out(wid) {
+                    return None;
+                }
+
                 let Some(ws_id) = state.info.sys_id else {
                     trace!(
                         ?wid,
@@ -251,6 +261,7 @@ impl WindowDiscoveryHandler {
                         existing.info.path = info.path.clone();
                         existing.info.ax_role = info.ax_role.clone();
                         existing.info.ax_subrole = info.ax_subrole.clone();
+                        existing.info.ax_identifier = info.ax_identifier.clone();
                         existing.is_manageable = manageable;
                     }
                 } else {
@@ -264,6 +275,7 @@ impl WindowDiscoveryHandler {
                     );
                     state.is_manageable = manageable;
                     reactor.window_manager.windows.insert(*wid, state);
+                    reactor.assign_window_identity(*wid);
                 }
                 Self::sync_window_server_id_mapping(reactor, *wid, None, info.sys_id);
             }
@@ -299,6 +311,7 @@ impl WindowDiscoveryHandler {
                     existing.info.path = info.path.clone();
                     existing.info.ax_role = info.ax_role.clone();
                     existing.info.ax_subrole = info.ax_subrole.clone();
+                    existing.info.ax_identifier = info.ax_identifier.clone();
                     existing.is_manageable = manageable;
                 }
             } else {
@@ -328,6 +341,7 @@ impl WindowDiscoveryHandler {
             );
             state.is_manageable = manageable;
             reactor.window_manager.windows.insert(wid, state);
+            reactor.assign_window_identity(wid);
         }
     }
 
@@ -69,6 +69,8 @@ impl MainWindowTracker {
         None
     }
 
+    pub fn frontmost_pid(&self) -> Option<pid_t> { self.global_frontmost }
+
     pub fn main_window(&self) -> Option<WindowId> {
         let Some(pid) = self.global_frontmost else {
             return None;
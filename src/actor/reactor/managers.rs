@@ -1,25 +1,38 @@
-use std::time::Instant;
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use objc2_core_foundation::{CGPoint, CGRect};
-use tracing::trace;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_core_graphics::CGDisplayBounds;
+use serde::Serialize;
+use tracing::{debug, info, instrument, trace};
 
+use super::events::command::CommandEventHandler;
 use super::replay::Record;
 use super::{
-    AppState, Event, FullscreenSpaceTrack, PendingSpaceChange, ScreenInfo, WindowState,
-    WorkspaceSwitchOrigin, WorkspaceSwitchState,
+    AppState, Command, Event, FullscreenSpaceTrack, PendingSpaceChange, ScreenInfo,
+    WindowPlacement, WindowState, WorkspaceSwitchOrigin, WorkspaceSwitchState,
 };
 use crate::actor;
-use crate::actor::app::{WindowId, pid_t};
-use crate::actor::broadcast::{BroadcastEvent, BroadcastSender, StackInfo};
+use crate::actor::app::{Request, WindowId, pid_t};
+use crate::actor::broadcast::{BroadcastEvent, BroadcastSender, SpaceRemap, StackInfo};
 use crate::actor::drag_swap::DragManager as DragSwapManager;
 use crate::actor::reactor::Reactor;
 use crate::actor::reactor::animation::AnimationManager;
 use crate::actor::{event_tap, menu_bar, raise_manager, stack_line, window_notify, wm_controller};
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{LayoutMode, WindowSnappingSettings};
-use crate::layout_engine::LayoutEngine;
+use crate::common::config::{
+    Config, FloatPlacement, GapSettings, LayoutMode, NewWindowFocus, StackLinePlacement,
+    WindowSnappingSettings, WorkspaceHideMethod,
+};
+use crate::layout_engine::engine::GroupContainerInfo;
+use crate::layout_engine::utils::largest_empty_area;
+use crate::layout_engine::{LayoutCommand, LayoutEngine};
+use crate::sys::idle::{IdleTransition, IdleWatcher};
 use crate::sys::screen::SpaceId;
-use crate::sys::window_server::{WindowServerId, WindowServerInfo};
+use crate::sys::window_server::{self, WindowServerId, WindowServerInfo};
+use crate::sys::window_style::{self, WindowStyle};
+use crate::ui::debug_overlay::DebugOverlayInfo;
 
 /// Manages window state and lifecycle
 pub struct WindowManager {
@@ -100,6 +113,8 @@ pub struct DragManager {
     pub drag_state: super::DragState,
     pub drag_swap_manager: DragSwapManager,
     pub skip_layout_for_window: Option<WindowId>,
+    /// The drag in progress with the `[settings.modifier_drag]` modifiers, if any.
+    pub modifier_drag: Option<super::ModifierDrag>,
 }
 
 impl DragManager {
@@ -142,9 +157,47 @@ pub struct WorkspaceSwitchManager {
     pub active_workspace_switch: Option<u64>,
     pub pending_workspace_switch_origin: Option<WorkspaceSwitchOrigin>,
     pub pending_workspace_mouse_warp: Option<WindowId>,
+    /// Workspace switches queued behind the event being handled. While nonzero,
+    /// a switch only moves the active workspace; raises and the layout pass are
+    /// left to the last one.
+    pub queued_switches: usize,
 }
 
 impl WorkspaceSwitchManager {
+    pub fn is_switch_command(cmd: &LayoutCommand) -> bool {
+        matches!(
+            cmd,
+            LayoutCommand::NextWorkspace(_)
+                | LayoutCommand::PrevWorkspace(_)
+                | LayoutCommand::SwitchToWorkspace(_)
+                | LayoutCommand::FocusWorkspace(_)
+                | LayoutCommand::SwitchToLastWorkspace
+        )
+    }
+
+    /// For each event of a batch, the number of workspace switches after it.
+    /// Superseded switches still apply in order, so relative ones add up and
+    /// the final workspace is the one the last key press asked for.
+    pub fn switches_after<'a>(events: impl IntoIterator<Item = &'a Event>) -> Vec<usize> {
+        let is_switch: Vec<bool> = events
+            .into_iter()
+            .map(|event| {
+                matches!(event, Event::Command(Command::Layout(cmd))
+                    if Self::is_switch_command(cmd))
+            })
+            .collect();
+        let mut remaining = is_switch.iter().filter(|&&switch| switch).count();
+        is_switch
+            .into_iter()
+            .map(|switch| {
+                remaining -= usize::from(switch);
+                remaining
+            })
+            .collect()
+    }
+
+    pub fn superseded(&self) -> bool { self.queued_switches > 0 }
+
     pub fn start_workspace_switch(&mut self, origin: WorkspaceSwitchOrigin) {
         self.workspace_switch_generation = self.workspace_switch_generation.wrapping_add(1);
         self.active_workspace_switch = Some(self.workspace_switch_generation);
@@ -182,11 +235,68 @@ pub struct CommunicationManager {
 /// Manages recording state
 pub struct RecordingManager {
     pub record: Record,
+    /// Debug lines for the latest events, oldest first, kept for `rift-cli report`.
+    pub recent_events: VecDeque<String>,
+}
+
+impl RecordingManager {
+    /// Formatting stops here, so a large payload such as `WindowsDiscovered` only
+    /// costs its variant name and leading ids.
+    const MAX_EVENT_LEN: usize = 200;
+    const RECENT_EVENTS: usize = 200;
+
+    pub fn new(record: Record) -> Self {
+        Self {
+            record,
+            recent_events: VecDeque::with_capacity(Self::RECENT_EVENTS),
+        }
+    }
+
+    pub fn remember(&mut self, event: &Event) {
+        let mut line = CappedLine(String::with_capacity(Self::MAX_EVENT_LEN));
+        let cut_short = match event {
+            // The config's commands are exactly what the report strips out.
+            Event::ConfigUpdated(_) => line.write_str("ConfigUpdated(..)"),
+            event => write!(line, "{event:?}"),
+        }
+        .is_err();
+        let mut line = line.0;
+        if cut_short {
+            line.push_str("...");
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        if self.recent_events.len() == Self::RECENT_EVENTS {
+            self.recent_events.pop_front();
+        }
+        self.recent_events.push_back(format!("{} {line}", now.as_millis()));
+    }
+}
+
+/// A `fmt::Write` that fails once it holds [`RecordingManager::MAX_EVENT_LEN`]
+/// bytes, which makes a `Debug` impl writing into it give up early.
+struct CappedLine(String);
+
+impl fmt::Write for CappedLine {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let room = RecordingManager::MAX_EVENT_LEN - self.0.len();
+        if s.len() <= room {
+            self.0.push_str(s);
+            return Ok(());
+        }
+        let mut end = room;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.0.push_str(&s[..end]);
+        Err(fmt::Error)
+    }
 }
 
 /// Manages layout engine state
 pub struct LayoutManager {
     pub layout_engine: LayoutEngine,
+    /// Whether `toggle_debug_overlay` has turned on the layout debug overlay.
+    pub debug_overlay: bool,
 }
 
 pub type LayoutResult = Vec<(SpaceId, Vec<(WindowId, CGRect)>)>;
@@ -235,10 +345,16 @@ impl LayoutManager {
         is_resize: bool,
         is_workspace_switch: bool,
     ) -> Result<bool, crate::model::reactor::ReactorError> {
+        if reactor.is_mission_control_active() {
+            // Exposé arranges the windows itself; the exit handler lays out again.
+            trace!("Skipping layout while Mission Control is showing");
+            return Ok(false);
+        }
         let layout_result = Self::calculate_layout(reactor);
         Self::apply_layout(reactor, layout_result, is_resize, is_workspace_switch)
     }
 
+    #[instrument(name = "layout_engine::calculate_layout", skip_all)]
     fn calculate_layout(reactor: &mut Reactor) -> LayoutResult {
         if reactor.window_manager.windows.is_empty() {
             return LayoutResult::new();
@@ -259,25 +375,32 @@ impl LayoutManager {
             if !reactor.is_space_active(space) {
                 continue;
             }
+            if reactor.space_activation_policy.is_space_paused(space) {
+                trace!(?space, "Skipping layout on paused space");
+                continue;
+            }
             let display_uuid_opt = screen.display_uuid_owned();
+            if reactor.fullscreen_pause_manager.is_paused(display_uuid_opt.as_deref()) {
+                trace!(?space, "Skipping layout on display paused for a fullscreen app");
+                continue;
+            }
             let gaps = reactor
-                .config
-                .settings
-                .layout
-                .gaps
-                .effective_for_display(display_uuid_opt.as_deref());
+                .layout_manager
+                .layout_engine
+                .gaps_for_display(display_uuid_opt.as_deref());
             reactor
                 .layout_manager
                 .layout_engine
                 .update_space_display(space, display_uuid_opt.clone());
+            let placement = reactor.stack_line_placement(space, None);
             let mut layout =
                 reactor.layout_manager.layout_engine.calculate_layout_with_virtual_workspaces(
                     space,
                     screen.frame.clone(),
                     &gaps,
-                    reactor.config.settings.ui.stack_line.thickness(),
-                    reactor.config.settings.ui.stack_line.horiz_placement,
-                    reactor.config.settings.ui.stack_line.vert_placement,
+                    reactor.config.settings.ui.stack_line.reserved_thickness(placement),
+                    placement.horizontal,
+                    placement.vertical,
                     |wid| reactor.window_manager.windows.get(&wid).map(|w| w.frame_monotonic),
                     &all_screen_frames,
                 );
@@ -304,6 +427,74 @@ impl LayoutManager {
         layout_result
     }
 
+    /// Sends the stack-line indicators for `space` the stacks as they are now,
+    /// without waiting for the next layout pass.
+    pub fn refresh_stack_line(reactor: &mut Reactor, space: SpaceId) {
+        let Some(screen) = reactor.space_manager.screen_by_space(space) else {
+            return;
+        };
+        let screen_frame = screen.frame;
+        let display_uuid = screen.display_uuid_owned();
+        let is_active_space = reactor.main_window_space() == Some(space);
+        let placement = reactor.stack_line_placement(space, None);
+        let engine = &mut reactor.layout_manager.layout_engine;
+        let has_fullscreen =
+            is_active_space && engine.active_workspace_for_space_has_fullscreen(space);
+        let gaps = engine.gaps_for_display(display_uuid.as_deref());
+        let stack_line = &reactor.config.settings.ui.stack_line;
+        let group_infos = engine.collect_group_containers(
+            space,
+            screen_frame,
+            &gaps,
+            stack_line.reserved_thickness(placement),
+            placement.horizontal,
+            placement.vertical,
+        );
+        Self::send_stack_line_groups(reactor, space, &group_infos, placement, has_fullscreen);
+    }
+
+    fn send_stack_line_groups(
+        reactor: &Reactor,
+        space: SpaceId,
+        group_infos: &[GroupContainerInfo],
+        placement: StackLinePlacement,
+        active_workspace_for_space_has_fullscreen: bool,
+    ) {
+        if !reactor.config.settings.ui.stack_line.enabled {
+            return;
+        }
+        let Some(tx) = &reactor.communication_manager.stack_line_tx else {
+            return;
+        };
+        // Smart borders send no groups, so a lone window shows no indicator.
+        let smart_borders = reactor.layout_manager.layout_engine.smart_borders_active(space);
+        let groups: Vec<stack_line::GroupInfo> = group_infos
+            .iter()
+            .filter(|_| !smart_borders)
+            .map(|g| stack_line::GroupInfo {
+                node_id: g.node_id,
+                space_id: space,
+                container_kind: g.container_kind,
+                frame: g.frame,
+                total_count: g.total_count,
+                selected_index: g.selected_index,
+                window_ids: g.window_ids.clone(),
+                placement,
+            })
+            .collect();
+        let active_space_ids: Vec<SpaceId> = reactor.iter_active_spaces().collect();
+
+        if let Err(e) = tx.try_send(stack_line::Event::GroupsUpdated {
+            active_space_ids,
+            space_id: space,
+            groups,
+            active_workspace_for_space_has_fullscreen,
+        }) {
+            tracing::warn!("Failed to send groups update to stack_line: {}", e);
+        }
+    }
+
+    #[instrument(name = "reactor::apply_layout", skip(reactor, layout_result))]
     fn apply_layout(
         reactor: &mut Reactor,
         layout_result: LayoutResult,
@@ -318,60 +509,48 @@ impl LayoutManager {
             .take()
             .or(reactor.drag_manager.drag_swap_manager.dragged());
         let mut any_frame_changed = false;
+        let mut debug_overlays = Vec::new();
 
         let active_space = reactor.main_window_space();
         for (space, layout) in layout_result {
             if let Some(screen) = reactor.space_manager.screen_by_space(space) {
                 let screen_frame = screen.frame;
                 let display_uuid = screen.display_uuid_owned();
-                let gaps = reactor
-                    .config
-                    .settings
-                    .layout
-                    .gaps
-                    .effective_for_display(display_uuid.as_deref());
+                let gaps =
+                    reactor.layout_manager.layout_engine.gaps_for_display(display_uuid.as_deref());
                 let active_workspace_for_space_has_fullscreen = active_space == Some(space)
                     && reactor
                         .layout_manager
                         .layout_engine
                         .active_workspace_for_space_has_fullscreen(space);
+                let placement = reactor.stack_line_placement(space, None);
                 let group_infos = reactor.layout_manager.layout_engine.collect_group_containers(
                     space,
                     screen_frame,
                     &gaps,
-                    reactor.config.settings.ui.stack_line.thickness(),
-                    reactor.config.settings.ui.stack_line.horiz_placement,
-                    reactor.config.settings.ui.stack_line.vert_placement,
+                    reactor.config.settings.ui.stack_line.reserved_thickness(placement),
+                    placement.horizontal,
+                    placement.vertical,
                 );
+                if reactor.layout_manager.debug_overlay {
+                    debug_overlays.extend(Self::debug_overlay_info(
+                        reactor,
+                        space,
+                        screen_frame,
+                        display_uuid.as_deref(),
+                        &gaps,
+                        placement,
+                    ));
+                }
 
                 // Keep internal stack-line UI actor fed from the same group snapshot.
-                if reactor.config.settings.ui.stack_line.enabled
-                    && let Some(tx) = &reactor.communication_manager.stack_line_tx
-                {
-                    let groups: Vec<crate::actor::stack_line::GroupInfo> = group_infos
-                        .iter()
-                        .map(|g| crate::actor::stack_line::GroupInfo {
-                            node_id: g.node_id,
-                            space_id: space,
-                            container_kind: g.container_kind,
-                            frame: g.frame,
-                            total_count: g.total_count,
-                            selected_index: g.selected_index,
-                            window_ids: g.window_ids.clone(),
-                        })
-                        .collect();
-                    let active_space_ids: Vec<crate::sys::screen::SpaceId> =
-                        reactor.iter_active_spaces().collect();
-
-                    if let Err(e) = tx.try_send(crate::actor::stack_line::Event::GroupsUpdated {
-                        active_space_ids,
-                        space_id: space,
-                        groups,
-                        active_workspace_for_space_has_fullscreen,
-                    }) {
-                        tracing::warn!("Failed to send groups update to stack_line: {}", e);
-                    }
-                }
+                Self::send_stack_line_groups(
+                    reactor,
+                    space,
+                    &group_infos,
+                    placement,
+                    active_workspace_for_space_has_fullscreen,
+                );
 
                 if let Some(workspace_id) =
                     reactor.layout_manager.layout_engine.active_workspace(space)
@@ -410,19 +589,840 @@ impl LayoutManager {
                 }
             }
 
+            let layout = WorkspaceHideManager::sync_layout(reactor, space, layout);
             let suppress_animation = is_workspace_switch
                 || reactor.workspace_switch_manager.active_workspace_switch.is_some();
-            if suppress_animation {
-                any_frame_changed |= AnimationManager::instant_layout(reactor, &layout, skip_wid);
+            let frames_changed = if suppress_animation {
+                AnimationManager::switch_layout(reactor, space, &layout, skip_wid)
             } else {
-                any_frame_changed |=
-                    AnimationManager::animate_layout(reactor, space, &layout, is_resize, skip_wid);
+                AnimationManager::animate_layout(reactor, space, &layout, is_resize, skip_wid)
+            };
+            if frames_changed {
+                reactor.animation_manager.frames_sent();
             }
+            any_frame_changed |= frames_changed;
+            WindowStyleManager::sync_layout(reactor, &layout);
         }
 
+        if reactor.layout_manager.debug_overlay {
+            Self::send_debug_overlay(reactor, debug_overlays);
+        }
         reactor.maybe_send_menu_update();
         Ok(any_frame_changed)
     }
+
+    fn debug_overlay_info(
+        reactor: &Reactor,
+        space: SpaceId,
+        screen: CGRect,
+        display_uuid: Option<&str>,
+        gaps: &GapSettings,
+        placement: StackLinePlacement,
+    ) -> Option<DebugOverlayInfo> {
+        let layout = reactor.layout_manager.layout_engine.debug_layout_outline(
+            space,
+            screen,
+            gaps,
+            reactor.config.settings.ui.stack_line.reserved_thickness(placement),
+            placement.horizontal,
+            placement.vertical,
+        )?;
+        let exclusion_zones = display_uuid
+            .into_iter()
+            .flat_map(|uuid| reactor.exclusion_zones.rects_for_display(uuid))
+            .map(|rect| {
+                let origin =
+                    CGPoint::new(screen.origin.x + rect.origin.x, screen.origin.y + rect.origin.y);
+                CGRect::new(origin, rect.size)
+            })
+            .collect();
+        Some(DebugOverlayInfo {
+            space_id: space,
+            screen,
+            layout,
+            exclusion_zones,
+        })
+    }
+
+    /// Hands the layout debug overlay its next frame; an empty list hides it.
+    pub fn send_debug_overlay(reactor: &Reactor, spaces: Vec<DebugOverlayInfo>) {
+        let Some(tx) = &reactor.communication_manager.stack_line_tx else {
+            return;
+        };
+        if let Err(e) = tx.try_send(stack_line::Event::DebugOverlay(spaces)) {
+            tracing::warn!("Failed to send debug overlay to stack_line: {}", e);
+        }
+    }
+}
+
+/// Tracks presentation mode and the state it has to restore
+pub struct PresentationManager {
+    /// The user's config, kept aside while presentation mode is active.
+    pub saved_config: Option<Config>,
+    /// Workspace (space, index) switched to monocle and its previous layout mode.
+    pub monocle_restore: Option<(SpaceId, usize, LayoutMode)>,
+}
+
+impl PresentationManager {
+    pub fn new() -> Self {
+        PresentationManager {
+            saved_config: None,
+            monocle_restore: None,
+        }
+    }
+
+    pub fn is_active(&self) -> bool { self.saved_config.is_some() }
+
+    /// Derives the config used while presenting: [`quiet_config`], with windows
+    /// also left alone when a notification banner shows.
+    pub fn presentation_config(config: &Config) -> Config {
+        let mut config = quiet_config(config);
+        config.settings.layout.avoid_notification_banners = false;
+        config
+    }
+}
+
+/// `config` without animations or stack lines, shared by presentation mode and
+/// the kill switch.
+pub fn quiet_config(config: &Config) -> Config {
+    let mut config = config.clone();
+    config.settings.animate = false;
+    if config.settings.layout.scrolling.animate.is_some() {
+        config.settings.layout.scrolling.animate = Some(false);
+    }
+    config.settings.ui.stack_line.enabled = false;
+    config
+}
+
+/// The config rift runs with once the kill switch is engaged: [`quiet_config`],
+/// and focus no longer follows the mouse.
+pub fn kill_switch_config(config: &Config) -> Config {
+    let mut config = quiet_config(config);
+    config.settings.focus_follows_mouse = false;
+    config
+}
+
+/// Tracks displays where a fullscreen game or video player has paused rift
+pub struct FullscreenPauseManager {
+    /// Display UUIDs currently paused, with the display bounds handed to the event tap.
+    pub paused_displays: HashMap<String, CGRect>,
+}
+
+impl FullscreenPauseManager {
+    pub fn new() -> Self {
+        FullscreenPauseManager {
+            paused_displays: HashMap::default(),
+        }
+    }
+
+    pub fn is_paused(&self, display_uuid: Option<&str>) -> bool {
+        display_uuid.is_some_and(|uuid| self.paused_displays.contains_key(uuid))
+    }
+
+    fn detect(reactor: &Reactor) -> HashMap<String, CGRect> {
+        let mut paused = Self::detect_fullscreen(reactor);
+        paused.extend(Self::detect_menu_bar_owner(reactor));
+        paused
+    }
+
+    fn detect_fullscreen(reactor: &Reactor) -> HashMap<String, CGRect> {
+        let Some(pid) = reactor.main_window_tracker.frontmost_pid() else {
+            return HashMap::default();
+        };
+        let bundle_id =
+            reactor.app_manager.apps.get(&pid).and_then(|app| app.info.bundle_id.as_deref());
+        if !reactor.config.settings.fullscreen_pause.applies_to(bundle_id) {
+            return HashMap::default();
+        }
+        reactor
+            .space_manager
+            .screens
+            .iter()
+            .filter_map(|screen| {
+                let uuid = screen.display_uuid_owned()?;
+                let display_id = screen.screen_id.as_u32();
+                let bounds = CGDisplayBounds(display_id);
+                let fullscreen_space = screen
+                    .space
+                    .is_some_and(|space| window_server::space_is_fullscreen(space.get()));
+                let paused = window_server::display_is_captured(display_id)
+                    || window_server::pid_covers_bounds(pid, bounds, fullscreen_space, |wsid| {
+                        reactor.window_manager.window_ids.contains_key(&wsid)
+                    });
+                paused.then_some((uuid, bounds))
+            })
+            .collect()
+    }
+
+    /// The display with the active menu bar, while a `menu_bar_owners` app owns it.
+    fn detect_menu_bar_owner(reactor: &Reactor) -> Option<(String, CGRect)> {
+        let pid = reactor.menu_bar_owner?;
+        let bundle_id = reactor.app_manager.apps.get(&pid)?.info.bundle_id.as_deref();
+        if !reactor.config.settings.fullscreen_pause.pauses_for_menu_bar_owner(bundle_id) {
+            return None;
+        }
+        let uuid = crate::sys::screen::active_menu_bar_display_uuid()?;
+        let screen = reactor.space_manager.screens.iter().find(|s| s.display_uuid == uuid)?;
+        Some((uuid, CGDisplayBounds(screen.screen_id.as_u32())))
+    }
+
+    /// Re-evaluates which displays are paused. Returns whether anything changed.
+    pub fn refresh(reactor: &mut Reactor) -> bool {
+        let paused = Self::detect(reactor);
+        if paused == reactor.fullscreen_pause_manager.paused_displays {
+            return false;
+        }
+        info!(displays = ?paused.keys().collect::<Vec<_>>(), "Fullscreen pause changed");
+        if let Some(tx) = reactor.communication_manager.event_tap_tx.as_ref() {
+            tx.send(event_tap::Request::SetPausedDisplays(
+                paused.values().copied().collect(),
+            ));
+        }
+        reactor.fullscreen_pause_manager.paused_displays = paused;
+        true
+    }
+}
+
+/// Applies the `new_window_focus` policy to freshly created windows
+pub struct NewWindowFocusManager {
+    /// A new window that may not keep focus, the window to give focus back to, and
+    /// the deadline after which the app taking focus is no longer treated as an echo
+    /// of the window being opened.
+    pub guard: Option<(WindowId, WindowId, Instant)>,
+}
+
+impl NewWindowFocusManager {
+    const GUARD_DURATION: Duration = Duration::from_secs(1);
+
+    pub fn new() -> Self { NewWindowFocusManager { guard: None } }
+
+    fn policy(reactor: &Reactor, wid: WindowId) -> Option<NewWindowFocus> {
+        let window = reactor.window_manager.windows.get(&wid)?;
+        let app_info = reactor.app_manager.apps.get(&wid.pid).map(|app| &app.info);
+        reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager
+            .find_matching_app_rule(
+                app_info.and_then(|info| info.bundle_id.as_deref()),
+                app_info.and_then(|info| info.localized_name.as_deref()),
+                Some(window.info.title.as_str()),
+                window.info.ax_role.as_deref(),
+                window.info.ax_subrole.as_deref(),
+            )
+            .and_then(|rule| rule.new_window_focus)
+            .or(reactor.config.settings.new_window_focus)
+    }
+
+    /// Focuses `wid` or keeps focus on `previous`, depending on the policy.
+    pub fn on_window_created(reactor: &mut Reactor, wid: WindowId, previous: Option<WindowId>) {
+        let Some(policy) = Self::policy(reactor, wid) else {
+            return;
+        };
+        let on_current_workspace = reactor.best_space_for_window_id(wid).is_some_and(|space| {
+            reactor.layout_manager.layout_engine.is_window_in_active_workspace(space, wid)
+        });
+        let from_focused_app = previous.is_some_and(|prev| prev.pid == wid.pid);
+
+        if policy.should_focus(on_current_workspace, from_focused_app) {
+            debug!(?wid, ?policy, "Focusing new window");
+            if reactor.main_window() != Some(wid) {
+                CommandEventHandler::handle_command_reactor_focus_window(reactor, wid, None);
+            }
+            return;
+        }
+
+        let Some(previous) = previous.filter(|prev| *prev != wid) else {
+            return;
+        };
+        debug!(?wid, ?previous, ?policy, "Keeping focus off new window");
+        reactor.new_window_focus_manager.guard =
+            Some((wid, previous, Instant::now() + Self::GUARD_DURATION));
+        if reactor.main_window() == Some(wid) {
+            Self::restore_focus(reactor, wid);
+        }
+    }
+
+    /// Hands focus back if `raised` is a new window that was not allowed to take it.
+    /// Returns whether focus was restored.
+    pub fn restore_focus(reactor: &mut Reactor, raised: WindowId) -> bool {
+        let Some((wid, previous, deadline)) = reactor.new_window_focus_manager.guard else {
+            return false;
+        };
+        if Instant::now() > deadline {
+            reactor.new_window_focus_manager.guard = None;
+            return false;
+        }
+        if wid != raised {
+            return false;
+        }
+        reactor.new_window_focus_manager.guard = None;
+        if !reactor.window_manager.windows.contains_key(&previous) {
+            return false;
+        }
+        CommandEventHandler::handle_command_reactor_focus_window(reactor, previous, None);
+        true
+    }
+}
+
+/// Holds the one-shot placements `exec` registers until a new window claims them
+pub struct WindowPlacementManager {
+    /// Placements still waiting for a window, oldest first, with their deadlines.
+    pub pending: VecDeque<(WindowPlacement, Instant)>,
+    /// How many new floats have cascaded down each space since it last wrapped.
+    pub cascade: HashMap<SpaceId, usize>,
+}
+
+impl WindowPlacementManager {
+    /// How far each cascaded float sits from the previous one.
+    const CASCADE_STEP: f64 = 28.0;
+    /// Apps that take longer than this to show a window lose their placement, so it
+    /// cannot land on some unrelated window much later.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    pub fn new() -> Self {
+        WindowPlacementManager {
+            pending: VecDeque::new(),
+            cascade: HashMap::default(),
+        }
+    }
+
+    pub fn expect_window(&mut self, placement: WindowPlacement) {
+        self.pending.push_back((placement, Instant::now() + Self::TIMEOUT));
+    }
+
+    /// Hands the oldest live placement waiting on `wid`'s app to `wid`, a window
+    /// rift has just started managing, and moves the window where it asks.
+    pub fn on_window_created(reactor: &mut Reactor, wid: WindowId) {
+        let now = Instant::now();
+        let pending = &mut reactor.window_placement_manager.pending;
+        pending.retain(|(_, deadline)| *deadline > now);
+        let engine = &reactor.layout_manager.layout_engine;
+        if pending.is_empty()
+            || engine.virtual_workspace_manager().workspace_for_window_any(wid).is_none()
+        {
+            return;
+        }
+        let info = reactor.app_manager.apps.get(&wid.pid).map(|app| &app.info);
+        let Some(index) =
+            pending.iter().position(|(placement, _)| placement.app.matches(wid.pid, info))
+        else {
+            return;
+        };
+        let Some((placement, _)) = pending.remove(index) else {
+            return;
+        };
+        debug!(?wid, ?placement, "Placing window launched by exec");
+
+        if placement.floating && !engine.is_window_floating(wid) {
+            let space = reactor.best_space_for_window_id(wid);
+            reactor.layout_manager.layout_engine.float_window(space, wid);
+        }
+        if let Some(selector) = &placement.display {
+            CommandEventHandler::handle_command_reactor_move_window_to_display(
+                reactor,
+                selector,
+                Some(wid.idx.get()),
+            );
+        }
+        if let Some(workspace) = placement.workspace
+            && let Some(space) = reactor.best_space_for_window_id(wid)
+        {
+            let cmd = LayoutCommand::MoveWindowToWorkspace {
+                workspace,
+                window_id: Some(wid.idx.get()),
+            };
+            let engine = &mut reactor.layout_manager.layout_engine;
+            let response = engine.handle_virtual_workspace_command(space, &cmd);
+            reactor.handle_layout_response(response, None);
+        }
+        let _ = reactor.update_layout_or_warn(false, false);
+    }
+
+    /// Puts `wid`, a window that just opened floating, where
+    /// `layout.float_placement` says. Windows that already have a floating
+    /// position, such as a remembered one, keep it.
+    pub fn place_new_float(reactor: &mut Reactor, wid: WindowId) {
+        let policy = reactor.config.settings.layout.float_placement;
+        // Floats without a position are centered by the layout already.
+        if policy == FloatPlacement::Center {
+            return;
+        }
+        let Some(space) = reactor.best_space_for_window_id(wid) else {
+            return;
+        };
+        let engine = &reactor.layout_manager.layout_engine;
+        if !engine.is_window_floating(wid) || !engine.is_window_in_active_workspace(space, wid) {
+            return;
+        }
+        let Some(workspace) = engine.active_workspace(space) else {
+            return;
+        };
+        let workspaces = engine.virtual_workspace_manager();
+        if workspaces.get_floating_position(space, workspace, wid).is_some() {
+            return;
+        }
+        let (Some(screen), Some(window)) = (
+            reactor.space_manager.screen_by_space(space),
+            reactor.window_manager.windows.get(&wid),
+        ) else {
+            return;
+        };
+        let area = screen.frame;
+        let size = window.frame_monotonic.size;
+        let centered_on = |point: CGPoint| {
+            let origin = CGPoint::new(point.x - size.width / 2.0, point.y - size.height / 2.0);
+            CGRect::new(origin, size)
+        };
+
+        let frame = match policy {
+            FloatPlacement::Center => return,
+            FloatPlacement::Cursor => match window_server::current_cursor_location() {
+                Ok(point) => centered_on(point),
+                Err(_) => return,
+            },
+            FloatPlacement::Cascade => {
+                let step = reactor.window_placement_manager.cascade.entry(space).or_default();
+                let offset = *step as f64 * Self::CASCADE_STEP;
+                let mut origin = CGPoint::new(area.origin.x + offset, area.origin.y + offset);
+                if origin.x + size.width > area.max().x || origin.y + size.height > area.max().y {
+                    *step = 0;
+                    origin = area.origin;
+                }
+                *step += 1;
+                CGRect::new(origin, size)
+            }
+            FloatPlacement::Smart => {
+                let occupied: Vec<CGRect> = engine
+                    .windows_in_active_workspace(space)
+                    .into_iter()
+                    .filter(|&other| other != wid)
+                    .filter_map(|other| reactor.window_manager.windows.get(&other))
+                    .map(|other| other.frame_monotonic)
+                    .collect();
+                match largest_empty_area(area, &occupied) {
+                    Some(free) => centered_on(free.mid()),
+                    None => return,
+                }
+            }
+        };
+        let origin = CGPoint::new(
+            frame.origin.x.min(area.max().x - size.width).max(area.origin.x),
+            frame.origin.y.min(area.max().y - size.height).max(area.origin.y),
+        );
+        let frame = CGRect::new(origin, size);
+        debug!(?wid, ?policy, ?frame, "Placing new floating window");
+        let engine = &mut reactor.layout_manager.layout_engine;
+        engine.store_floating_window_positions(space, &[(wid, frame)]);
+    }
+}
+
+/// Tracks shadow/corner styling applied to tiled windows
+pub struct WindowStyleManager {
+    pub enabled: bool,
+    pub applied: HashMap<WindowId, (WindowStyle, CGSize)>,
+}
+
+impl WindowStyleManager {
+    pub fn new() -> Self {
+        WindowStyleManager {
+            enabled: true,
+            applied: HashMap::default(),
+        }
+    }
+
+    pub(super) fn desired_style(reactor: &Reactor, wid: WindowId) -> WindowStyle {
+        let default = WindowStyle::default();
+        if !reactor.window_style_manager.enabled
+            || reactor.presentation_manager.is_active()
+            || reactor.layout_manager.layout_engine.is_window_floating(wid)
+        {
+            return default;
+        }
+        let Some(window) = reactor.window_manager.windows.get(&wid) else {
+            return default;
+        };
+        let app_info = reactor.app_manager.apps.get(&wid.pid).map(|app| &app.info);
+        let rule = reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager
+            .find_matching_app_rule(
+                app_info.and_then(|info| info.bundle_id.as_deref()),
+                app_info.and_then(|info| info.localized_name.as_deref()),
+                Some(window.info.title.as_str()),
+                window.info.ax_role.as_deref(),
+                window.info.ax_subrole.as_deref(),
+            );
+        match rule {
+            Some(rule) => WindowStyle {
+                shadow: rule.shadow.unwrap_or(default.shadow),
+                corner_radius: rule.corner_radius.unwrap_or(default.corner_radius),
+            },
+            None => default,
+        }
+    }
+
+    /// Brings the styling of every window in `layout` in line with the app
+    /// rules, touching the window server only when something changed.
+    pub fn sync_layout(reactor: &mut Reactor, layout: &[(WindowId, CGRect)]) {
+        let windows = &reactor.window_manager.windows;
+        reactor.window_style_manager.applied.retain(|wid, _| windows.contains_key(wid));
+
+        for &(wid, frame) in layout {
+            let desired = Self::desired_style(reactor, wid);
+            let current = reactor.window_style_manager.applied.get(&wid).copied();
+            let (current_style, current_size) =
+                current.unwrap_or((WindowStyle::default(), frame.size));
+            if current.is_none() && desired == WindowStyle::default() {
+                continue;
+            }
+            let Some(wsid) = reactor.window_manager.windows.get(&wid).and_then(|w| w.info.sys_id)
+            else {
+                continue;
+            };
+
+            if desired.shadow != current_style.shadow
+                && let Err(err) = window_style::set_shadow(wsid, desired.shadow)
+            {
+                debug!(?wid, ?err, "Failed to update window shadow");
+            }
+            let radius_changed = desired.corner_radius != current_style.corner_radius;
+            let size_changed = desired.corner_radius > 0.0 && current_size != frame.size;
+            if (radius_changed || size_changed)
+                && let Err(err) =
+                    window_style::set_corner_radius(wsid, frame.size, desired.corner_radius)
+            {
+                debug!(?wid, ?err, "Failed to update window corner radius");
+            }
+
+            if desired == WindowStyle::default() {
+                reactor.window_style_manager.applied.remove(&wid);
+            } else {
+                reactor.window_style_manager.applied.insert(wid, (desired, frame.size));
+            }
+        }
+    }
+}
+
+/// Orders the windows of hidden workspaces out of the window server, for
+/// `settings.workspace_hide_method = "order_out"`.
+#[derive(Default)]
+pub struct WorkspaceHideManager {
+    /// Windows rift ordered out and has to order back in once they are shown.
+    ordered_out: HashSet<WindowId>,
+    /// Windows the window server wouldn't order out, which are moved instead.
+    refused: HashSet<WindowId>,
+}
+
+impl WorkspaceHideManager {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn is_ordered_out(&self, wid: WindowId) -> bool { self.ordered_out.contains(&wid) }
+
+    /// Orders out the windows in `layout` that sit on hidden workspaces of
+    /// `space` and orders back in the ones being shown again.
+    ///
+    /// Returns what is left of `layout` to apply. Ordered-out windows are
+    /// dropped from it so they keep their frames.
+    pub fn sync_layout(
+        reactor: &mut Reactor,
+        space: SpaceId,
+        layout: Vec<(WindowId, CGRect)>,
+    ) -> Vec<(WindowId, CGRect)> {
+        let windows = &reactor.window_manager.windows;
+        let manager = &mut reactor.workspace_hide_manager;
+        manager.ordered_out.retain(|wid| windows.contains_key(wid));
+        manager.refused.retain(|wid| windows.contains_key(wid));
+        let order_out =
+            reactor.config.settings.workspace_hide_method == WorkspaceHideMethod::OrderOut;
+        if !order_out && manager.ordered_out.is_empty() {
+            return layout;
+        }
+
+        let hidden: HashSet<WindowId> = reactor
+            .layout_manager
+            .layout_engine
+            .virtual_workspace_manager
+            .windows_in_inactive_workspaces(space)
+            .into_iter()
+            .collect();
+        layout
+            .into_iter()
+            .filter(|&(wid, _)| {
+                if hidden.contains(&wid) {
+                    !Self::hide(reactor, wid)
+                } else {
+                    Self::show(reactor, wid);
+                    true
+                }
+            })
+            .collect()
+    }
+
+    /// Orders a window out if the config asks for it, returning whether it is
+    /// now out of sight without being moved.
+    pub fn hide(reactor: &mut Reactor, wid: WindowId) -> bool {
+        let Some(wsid) = reactor.window_manager.windows.get(&wid).and_then(|w| w.info.sys_id)
+        else {
+            return false;
+        };
+        let manager = &mut reactor.workspace_hide_manager;
+        // The app may have ordered the window back in itself, e.g. when activated.
+        if manager.ordered_out.contains(&wid) && !window_server::window_is_ordered_in(wsid) {
+            return true;
+        }
+        let order_out =
+            reactor.config.settings.workspace_hide_method == WorkspaceHideMethod::OrderOut;
+        if !order_out || manager.refused.contains(&wid) {
+            manager.ordered_out.remove(&wid);
+            return false;
+        }
+        match window_server::order_out_window(wsid) {
+            Ok(()) => {
+                manager.ordered_out.insert(wid);
+                true
+            }
+            Err(err) => {
+                debug!(?wid, ?err, "Window server won't order out window; moving it");
+                manager.ordered_out.remove(&wid);
+                manager.refused.insert(wid);
+                false
+            }
+        }
+    }
+
+    /// Orders every window rift ordered out back in, so none are left
+    /// invisible once rift stops managing them.
+    pub fn show_all(reactor: &mut Reactor) {
+        let ordered_out: Vec<WindowId> =
+            reactor.workspace_hide_manager.ordered_out.iter().copied().collect();
+        for wid in ordered_out {
+            Self::show(reactor, wid);
+        }
+    }
+
+    /// Orders a window back in if rift ordered it out.
+    pub fn show(reactor: &mut Reactor, wid: WindowId) {
+        if !reactor.workspace_hide_manager.ordered_out.remove(&wid) {
+            return;
+        }
+        let Some(wsid) = reactor.window_manager.windows.get(&wid).and_then(|w| w.info.sys_id)
+        else {
+            return;
+        };
+        if let Err(err) = window_server::order_in_window(wsid) {
+            debug!(?wid, ?err, "Failed to order window back in");
+        }
+    }
+}
+
+/// Remembers which space each display showed when the system woke, so the delayed
+/// wake reconciliation can tell which space ids macOS reissued in the meantime.
+pub struct WakeReconcileManager {
+    pub spaces_by_display: Option<Vec<(String, SpaceId)>>,
+}
+
+/// Differences between the displays seen at wake and those seen once things settled.
+#[derive(Debug, Default, PartialEq)]
+pub struct WakeSpaceDiff {
+    pub remapped_spaces: Vec<SpaceRemap>,
+    pub added_displays: Vec<String>,
+    pub removed_displays: Vec<String>,
+}
+
+impl WakeReconcileManager {
+    pub fn new() -> Self { WakeReconcileManager { spaces_by_display: None } }
+
+    pub fn capture(reactor: &mut Reactor) {
+        let spaces = Self::current_spaces(reactor);
+        debug!(?spaces, "Captured spaces at wake");
+        reactor.wake_reconcile_manager.spaces_by_display = Some(spaces);
+    }
+
+    pub fn current_spaces(reactor: &Reactor) -> Vec<(String, SpaceId)> {
+        reactor
+            .space_manager
+            .screens
+            .iter()
+            .filter_map(|screen| Some((screen.display_uuid_owned()?, screen.space?)))
+            .collect()
+    }
+
+    /// Compares the per-display spaces from before and after. A display whose space
+    /// changed only counts as remapped when the old space no longer exists
+    /// (`live_spaces`); otherwise the user simply switched spaces. An empty
+    /// `live_spaces` means the query failed, in which case nothing is remapped.
+    pub fn diff(
+        before: &[(String, SpaceId)],
+        after: &[(String, SpaceId)],
+        live_spaces: &HashSet<SpaceId>,
+    ) -> WakeSpaceDiff {
+        let mut diff = WakeSpaceDiff::default();
+        for (display_uuid, old_space) in before {
+            match after.iter().find(|(uuid, _)| uuid == display_uuid) {
+                None => diff.removed_displays.push(display_uuid.clone()),
+                Some(&(_, new_space))
+                    if new_space != *old_space
+                        && !live_spaces.is_empty()
+                        && !live_spaces.contains(old_space) =>
+                {
+                    diff.remapped_spaces.push(SpaceRemap {
+                        display_uuid: display_uuid.clone(),
+                        old_space: *old_space,
+                        new_space,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        diff.added_displays = after
+            .iter()
+            .filter(|(uuid, _)| !before.iter().any(|(prev, _)| prev == uuid))
+            .map(|(uuid, _)| uuid.clone())
+            .collect();
+        diff
+    }
+}
+
+/// Counters kept by the frame audit, reported with the other metrics.
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct FrameAuditStats {
+    pub audits: u64,
+    /// Windows found further from their expected frame than the tolerance.
+    pub discrepancies: u64,
+    /// Tiled windows sent back to their layout frame.
+    pub corrections: u64,
+    /// Floating windows whose new frame was taken as the expected one.
+    pub adopted: u64,
+}
+
+/// Periodically checks managed windows against the window server, for apps
+/// that move or resize themselves without sending a notification.
+#[derive(Default)]
+pub struct FrameAuditManager {
+    pub stats: FrameAuditStats,
+}
+
+impl FrameAuditManager {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn audit(reactor: &mut Reactor) {
+        let settings = &reactor.config.settings.frame_audit;
+        if !settings.enabled || reactor.is_in_drag() || reactor.is_mission_control_active() {
+            return;
+        }
+        let (tolerance, max_corrections) = (settings.tolerance, settings.max_corrections);
+        reactor.frame_audit_manager.stats.audits += 1;
+
+        let layout_engine = &reactor.layout_manager.layout_engine;
+        let mut expected: HashMap<WindowServerId, (WindowId, CGRect, bool)> = HashMap::default();
+        for &space in &reactor.active_spaces {
+            for wid in layout_engine.windows_in_active_workspace(space) {
+                let Some(window) = reactor.window_manager.windows.get(&wid) else {
+                    continue;
+                };
+                let Some(wsid) = window.info.sys_id else {
+                    continue;
+                };
+                // A frame still in flight would look like drift until the app applies it.
+                if !window.is_effectively_manageable()
+                    || window.info.is_minimized
+                    || reactor.transaction_manager.get_target_frame(wsid).is_some()
+                {
+                    continue;
+                }
+                let floating = layout_engine.is_window_floating(wid);
+                expected.insert(wsid, (wid, window.frame_monotonic, floating));
+            }
+        }
+        if expected.is_empty() {
+            return;
+        }
+
+        let ids: Vec<WindowServerId> = expected.keys().copied().collect();
+        let mut corrections = 0;
+        for info in window_server::get_windows(&ids) {
+            let Some(&(wid, frame, floating)) = expected.get(&info.id) else {
+                continue;
+            };
+            let drift = frame_drift(frame, info.frame);
+            if drift <= tolerance {
+                continue;
+            }
+            reactor.frame_audit_manager.stats.discrepancies += 1;
+            info!(?wid, ?frame, actual = ?info.frame, drift, floating, "Window moved on its own");
+
+            if floating {
+                if let Some(window) = reactor.window_manager.windows.get_mut(&wid) {
+                    window.frame_monotonic = info.frame;
+                }
+                reactor.frame_audit_manager.stats.adopted += 1;
+                continue;
+            }
+            if corrections >= max_corrections {
+                debug!(?wid, "Correction limit reached; leaving it for the next audit");
+                continue;
+            }
+            let Some(app) = reactor.app_manager.apps.get(&wid.pid) else {
+                continue;
+            };
+            let txid = reactor.transaction_manager.generate_next_txid(info.id);
+            reactor.transaction_manager.set_last_sent_txid(info.id, txid);
+            reactor.transaction_manager.update_txid_entries([(info.id, txid, frame)]);
+            if app.handle.send(Request::SetWindowFrame(wid, frame, txid, true)).is_ok() {
+                corrections += 1;
+                reactor.frame_audit_manager.stats.corrections += 1;
+            }
+        }
+    }
+}
+
+/// How far the furthest edge of `actual` is from the same edge of `expected`.
+pub fn frame_drift(expected: CGRect, actual: CGRect) -> f64 {
+    let edges = |r: CGRect| [r.min().x, r.min().y, r.max().x, r.max().y];
+    edges(expected)
+        .into_iter()
+        .zip(edges(actual))
+        .map(|(a, b)| (a - b).abs())
+        .fold(0.0, f64::max)
+}
+
+/// Runs the `settings.idle` rules as the user steps away and comes back.
+#[derive(Default)]
+pub struct IdleManager {
+    watcher: IdleWatcher,
+}
+
+impl IdleManager {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn observe(reactor: &mut Reactor, idle_secs: f64) {
+        let rules = &reactor.config.settings.idle.rules;
+        let thresholds: Vec<u64> = rules.iter().map(|rule| rule.after_secs).collect();
+        for transition in reactor.idle_manager.watcher.observe(idle_secs, &thresholds) {
+            let (index, idle) = match transition {
+                IdleTransition::Idle(index) => (index, true),
+                IdleTransition::Resumed(index) => (index, false),
+            };
+            // A reload can drop rules while the user is away.
+            let Some(rule) = reactor.config.settings.idle.rules.get(index) else {
+                continue;
+            };
+            let after_secs = rule.after_secs;
+            let commands = if idle { &rule.on_idle } else { &rule.on_resume };
+            info!(after_secs, idle, "Idle rule triggered");
+            if let Some(wm) = reactor.communication_manager.wm_sender.as_ref() {
+                for command in commands {
+                    wm.send(wm_controller::WmEvent::Command(command.clone()));
+                }
+            }
+            let _ = reactor
+                .communication_manager
+                .event_broadcaster
+                .send(BroadcastEvent::IdleChanged { idle, after_secs });
+        }
+    }
 }
 
 /// Manages window server information
@@ -440,7 +1440,16 @@ pub struct PendingSpaceChangeManager {
 mod tests {
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 
-    use super::bound_frame_to_screen;
+    use super::{
+        RecordingManager, WakeReconcileManager, WorkspaceSwitchManager, bound_frame_to_screen,
+        frame_drift,
+    };
+    use crate::actor::broadcast::SpaceRemap;
+    use crate::actor::reactor::replay::Record;
+    use crate::actor::reactor::{Command, Event};
+    use crate::common::collections::HashSet;
+    use crate::layout_engine::LayoutCommand;
+    use crate::sys::screen::SpaceId;
 
     fn rect(x: f64, y: f64, w: f64, h: f64) -> CGRect {
         CGRect::new(CGPoint::new(x, y), CGSize::new(w, h))
@@ -481,4 +1490,74 @@ mod tests {
         assert_eq!(bounded.origin.x, 2998.0);
         assert_eq!(bounded.size.width, 600.0);
     }
+
+    #[test]
+    fn switches_after_counts_only_later_workspace_switches() {
+        let switch = |cmd| Event::Command(Command::Layout(cmd));
+        let events = [
+            switch(LayoutCommand::NextWorkspace(None)),
+            Event::MouseUp,
+            switch(LayoutCommand::CreateWorkspace),
+            switch(LayoutCommand::SwitchToWorkspace(2)),
+            switch(LayoutCommand::PrevWorkspace(None)),
+            Event::MouseUp,
+        ];
+        let queued = WorkspaceSwitchManager::switches_after(&events);
+        assert_eq!(queued, vec![2, 2, 2, 1, 0, 0]);
+        assert!(WorkspaceSwitchManager::switches_after(&[]).is_empty());
+    }
+
+    #[test]
+    fn frame_drift_is_the_largest_edge_difference() {
+        let frame = rect(100.0, 100.0, 800.0, 600.0);
+        assert_eq!(frame_drift(frame, frame), 0.0);
+        assert_eq!(frame_drift(frame, rect(103.0, 100.0, 800.0, 600.0)), 3.0);
+        // Growing to the left moves one edge only.
+        assert_eq!(frame_drift(frame, rect(90.0, 100.0, 810.0, 600.0)), 10.0);
+        assert_eq!(frame_drift(frame, rect(100.0, 100.0, 800.0, 540.0)), 60.0);
+    }
+
+    #[test]
+    fn wake_diff_only_remaps_spaces_that_disappeared() {
+        let before = vec![
+            ("A".to_string(), SpaceId::new(1)),
+            ("B".to_string(), SpaceId::new(2)),
+            ("C".to_string(), SpaceId::new(3)),
+        ];
+        let after = vec![
+            ("A".to_string(), SpaceId::new(10)),
+            ("B".to_string(), SpaceId::new(4)),
+            ("D".to_string(), SpaceId::new(5)),
+        ];
+        let live: HashSet<SpaceId> = [10, 2, 4, 5].into_iter().map(SpaceId::new).collect();
+
+        let diff = WakeReconcileManager::diff(&before, &after, &live);
+        assert_eq!(diff.remapped_spaces, vec![SpaceRemap {
+            display_uuid: "A".to_string(),
+            old_space: SpaceId::new(1),
+            new_space: SpaceId::new(10),
+        }]);
+        assert_eq!(diff.removed_displays, vec!["C".to_string()]);
+        assert_eq!(diff.added_displays, vec!["D".to_string()]);
+
+        let unknown = WakeReconcileManager::diff(&before, &after, &HashSet::default());
+        assert!(unknown.remapped_spaces.is_empty());
+    }
+
+    #[test]
+    fn remembered_events_are_cut_off_while_formatting() {
+        let mut recording = RecordingManager::new(Record::new(None));
+        recording.remember(&Event::SecureInputChanged(true));
+        let titles = "x".repeat(4 * RecordingManager::MAX_EVENT_LEN);
+        recording.remember(&Event::FocusModeChanged(Some(titles)));
+
+        let lines: Vec<&str> = recording
+            .recent_events
+            .iter()
+            .map(|line| line.split_once(' ').expect("timestamped").1)
+            .collect();
+        assert_eq!(lines[0], "SecureInputChanged(true)");
+        assert!(lines[1].starts_with("FocusModeChanged(Some(\"xxx"));
+        assert_eq!(lines[1].len(), RecordingManager::MAX_EVENT_LEN + "...".len());
+    }
 }
@@ -1,16 +1,32 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::sync::mpsc::{RecvError, SyncSender, sync_channel};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use objc2_core_foundation::CGRect;
+use objc2_core_graphics::CGImage;
+use objc2_foundation::NSProcessInfo;
+use tracing::info;
 
 use crate::actor::app::WindowId;
 use crate::actor::menu_bar;
+use crate::actor::reactor::animation::AnimationManager;
+use crate::actor::reactor::managers::WindowStyleManager;
 use crate::actor::reactor::{Event, Reactor, Sender};
-use crate::common::collections::HashSet;
+use crate::common::collections::{HashMap, HashSet};
+use crate::common::config::{AppWorkspaceRule, ScratchpadConfig, WorkspaceSelector, desktop_dir};
+use crate::common::report;
+use crate::model::exclusion_zones::ExclusionZone;
 use crate::model::server::{
-    ApplicationData, DisplayData, LayoutStateData, WindowData, WorkspaceData, WorkspaceLayoutData,
+    AnimationStateData, ApplicationData, CursorData, DisplayData, LayoutStateData, MatchedAppRule,
+    MenuBarOwnerData, ReportData, ScreenshotData, ScreenshotTarget, SpaceData, WindowData,
+    WindowExplanation, WorkspaceData, WorkspaceLayoutData,
 };
 use crate::model::virtual_workspace::VirtualWorkspaceId;
+use crate::sys::geometry::CGRectExt;
+use crate::sys::power;
 use crate::sys::screen::{ScreenInfo, SpaceId, get_active_space_number, managed_display_space_ids};
+use crate::sys::window_server::{self, WindowServerId};
 
 #[derive(Clone)]
 pub struct ReactorQueryHandle {
@@ -32,13 +48,42 @@ impl ReactorQueryHandle {
     }
 
     pub fn query_workspaces(&self, space_id: Option<SpaceId>) -> Vec<WorkspaceData> {
-        self.send_query(|resp| QueryRequest::Workspaces { space_id, resp })
-            .unwrap_or_default()
+        self.send_query(|resp| QueryRequest::Workspaces {
+            space_id,
+            include_minimized: false,
+            resp,
+        })
+        .unwrap_or_default()
     }
 
     pub fn query_windows(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
-        self.send_query(|resp| QueryRequest::Windows { space_id, resp })
-            .unwrap_or_default()
+        self.send_query(|resp| QueryRequest::Windows {
+            space_id,
+            include_minimized: false,
+            resp,
+        })
+        .unwrap_or_default()
+    }
+
+    /// [`Self::query_workspaces`] with minimized windows listed in the workspace
+    /// they were minimized from, for mission control.
+    pub fn query_workspaces_with_minimized(&self, space_id: Option<SpaceId>) -> Vec<WorkspaceData> {
+        self.send_query(|resp| QueryRequest::Workspaces {
+            space_id,
+            include_minimized: true,
+            resp,
+        })
+        .unwrap_or_default()
+    }
+
+    /// [`Self::query_windows`] with the active workspace's minimized windows.
+    pub fn query_windows_with_minimized(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
+        self.send_query(|resp| QueryRequest::Windows {
+            space_id,
+            include_minimized: true,
+            resp,
+        })
+        .unwrap_or_default()
     }
 
     pub fn query_active_workspace(&self, space_id: Option<SpaceId>) -> Option<VirtualWorkspaceId> {
@@ -79,16 +124,76 @@ impl ReactorQueryHandle {
     pub fn query_metrics(&self) -> serde_json::Value {
         self.send_query(QueryRequest::Metrics).unwrap_or_else(|_| serde_json::json!({}))
     }
+
+    pub fn query_exclusion_zones(&self) -> Vec<ExclusionZone> {
+        self.send_query(QueryRequest::ExclusionZones).unwrap_or_default()
+    }
+
+    pub fn query_paused_spaces(&self) -> Vec<SpaceId> {
+        self.send_query(QueryRequest::PausedSpaces).unwrap_or_default()
+    }
+
+    pub fn query_spaces(&self) -> Vec<SpaceData> {
+        self.send_query(QueryRequest::Spaces).unwrap_or_default()
+    }
+
+    pub fn query_cursor(&self) -> Option<CursorData> {
+        self.send_query(QueryRequest::Cursor).ok().flatten()
+    }
+
+    pub fn query_menu_bar_owner(&self) -> Option<MenuBarOwnerData> {
+        self.send_query(QueryRequest::MenuBarOwner).ok().flatten()
+    }
+
+    pub fn query_animation_state(&self) -> Option<AnimationStateData> {
+        self.send_query(QueryRequest::AnimationState).ok()
+    }
+
+    /// Blocks until nothing is in flight. If that takes longer than `timeout`,
+    /// fails with the state at that point, or `None` if the reactor is not
+    /// answering at all.
+    pub fn wait_for_settle(
+        &self,
+        timeout: Duration,
+    ) -> Result<AnimationStateData, Option<AnimationStateData>> {
+        let (tx, rx) = sync_channel(1);
+        if self.tx.try_send(Event::Query(QueryRequest::WaitForSettle(tx))).is_err() {
+            return Err(None);
+        }
+        rx.recv_timeout(timeout).map_err(|_| self.query_animation_state())
+    }
+
+    pub fn query_explain_window(&self, window_id: WindowId) -> Option<WindowExplanation> {
+        self.send_query(|resp| QueryRequest::ExplainWindow { window_id, resp })
+            .ok()
+            .flatten()
+    }
+
+    pub fn query_screenshot(
+        &self,
+        target: ScreenshotTarget,
+        path: Option<PathBuf>,
+    ) -> Result<ScreenshotData, String> {
+        self.send_query(|resp| QueryRequest::Screenshot { target, path, resp })
+            .map_err(|_| "rift is not responding".to_string())?
+    }
+
+    pub fn query_report(&self, redact_titles: bool) -> Option<serde_json::Value> {
+        self.send_query(|resp| QueryRequest::Report { redact_titles, resp }).ok()
+    }
 }
 
 #[derive(Debug)]
 pub enum QueryRequest {
     Workspaces {
         space_id: Option<SpaceId>,
+        /// Also list minimized windows; only mission control shows them.
+        include_minimized: bool,
         resp: SyncSender<Vec<WorkspaceData>>,
     },
     Windows {
         space_id: Option<SpaceId>,
+        include_minimized: bool,
         resp: SyncSender<Vec<WindowData>>,
     },
     ActiveWorkspace {
@@ -111,16 +216,46 @@ pub enum QueryRequest {
         resp: SyncSender<Option<LayoutStateData>>,
     },
     Metrics(SyncSender<serde_json::Value>),
+    ExclusionZones(SyncSender<Vec<ExclusionZone>>),
+    PausedSpaces(SyncSender<Vec<SpaceId>>),
+    Spaces(SyncSender<Vec<SpaceData>>),
+    Cursor(SyncSender<Option<CursorData>>),
+    MenuBarOwner(SyncSender<Option<MenuBarOwnerData>>),
+    AnimationState(SyncSender<AnimationStateData>),
+    /// Answered only once nothing is in flight any more.
+    WaitForSettle(SyncSender<AnimationStateData>),
+    ExplainWindow {
+        window_id: WindowId,
+        resp: SyncSender<Option<WindowExplanation>>,
+    },
+    Screenshot {
+        target: ScreenshotTarget,
+        /// Defaults to a timestamped file on the desktop.
+        path: Option<PathBuf>,
+        resp: SyncSender<Result<ScreenshotData, String>>,
+    },
+    Report {
+        redact_titles: bool,
+        resp: SyncSender<serde_json::Value>,
+    },
 }
 
 impl Reactor {
     pub(super) fn handle_query_request(&mut self, req: QueryRequest) {
         match req {
-            QueryRequest::Workspaces { space_id, resp } => {
-                let _ = resp.send(self.query_workspaces(space_id));
+            QueryRequest::Workspaces {
+                space_id,
+                include_minimized,
+                resp,
+            } => {
+                let _ = resp.send(self.handle_workspace_query(space_id, include_minimized));
             }
-            QueryRequest::Windows { space_id, resp } => {
-                let _ = resp.send(self.query_windows(space_id));
+            QueryRequest::Windows {
+                space_id,
+                include_minimized,
+                resp,
+            } => {
+                let _ = resp.send(self.handle_windows_query(space_id, include_minimized));
             }
             QueryRequest::ActiveWorkspace { space_id, resp } => {
                 let _ = resp.send(self.query_active_workspace(space_id));
@@ -143,6 +278,37 @@ impl Reactor {
             QueryRequest::Metrics(resp) => {
                 let _ = resp.send(self.query_metrics());
             }
+            QueryRequest::ExclusionZones(resp) => {
+                let _ = resp.send(self.exclusion_zones.zones().to_vec());
+            }
+            QueryRequest::PausedSpaces(resp) => {
+                let mut spaces: Vec<SpaceId> =
+                    self.space_activation_policy.paused_spaces().collect();
+                spaces.sort();
+                let _ = resp.send(spaces);
+            }
+            QueryRequest::Spaces(resp) => {
+                let _ = resp.send(self.handle_spaces_query(managed_display_space_ids()));
+            }
+            QueryRequest::Cursor(resp) => {
+                let _ = resp.send(self.handle_cursor_query());
+            }
+            QueryRequest::MenuBarOwner(resp) => {
+                let _ = resp.send(self.handle_menu_bar_owner_query());
+            }
+            QueryRequest::AnimationState(resp) => {
+                let _ = resp.send(AnimationManager::state(self));
+            }
+            QueryRequest::WaitForSettle(resp) => AnimationManager::wait_for_settle(self, resp),
+            QueryRequest::ExplainWindow { window_id, resp } => {
+                let _ = resp.send(self.handle_explain_window_query(window_id));
+            }
+            QueryRequest::Screenshot { target, path, resp } => {
+                self.handle_screenshot_query(target, path, resp);
+            }
+            QueryRequest::Report { redact_titles, resp } => {
+                let _ = resp.send(self.handle_report_query(redact_titles));
+            }
         }
     }
 
@@ -153,11 +319,11 @@ impl Reactor {
     }
 
     pub fn query_workspaces(&mut self, space_id: Option<SpaceId>) -> Vec<WorkspaceData> {
-        self.handle_workspace_query(space_id)
+        self.handle_workspace_query(space_id, false)
     }
 
     pub fn query_windows(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
-        self.handle_windows_query(space_id)
+        self.handle_windows_query(space_id, false)
     }
 
     pub fn query_active_workspace(&self, space_id: Option<SpaceId>) -> Option<VirtualWorkspaceId> {
@@ -197,16 +363,21 @@ impl Reactor {
             None => return,
         };
 
-        let workspaces = self.handle_workspace_query(Some(active_space));
+        let workspaces = self.handle_workspace_query(Some(active_space), false);
         let active_space_is_activated = self.is_space_active(active_space);
+        let active_space_is_paused = self.space_activation_policy.is_space_paused(active_space);
         let active_workspace = self.layout_manager.layout_engine.active_workspace(active_space);
         let active_workspace_idx =
             self.layout_manager.layout_engine.active_workspace_idx(active_space);
-        let windows = self.handle_windows_query(Some(active_space));
+        let windows = self.handle_windows_query(Some(active_space), false);
+        let displays = self.handle_spaces_query(HashMap::default());
 
         menu_tx.send(menu_bar::Event::Update(menu_bar::Update {
             active_space,
             active_space_is_activated,
+            active_space_is_paused,
+            secure_input: self.secure_input,
+            displays,
             workspaces,
             active_workspace_idx,
             active_workspace,
@@ -214,7 +385,11 @@ impl Reactor {
         }));
     }
 
-    fn handle_workspace_query(&mut self, space_id_param: Option<SpaceId>) -> Vec<WorkspaceData> {
+    fn handle_workspace_query(
+        &mut self,
+        space_id_param: Option<SpaceId>,
+        include_minimized: bool,
+    ) -> Vec<WorkspaceData> {
         let mut workspaces = Vec::new();
 
         let space_id = space_id_param.or_else(|| self.default_query_space());
@@ -235,7 +410,7 @@ impl Reactor {
                 false
             };
 
-            let workspace_windows_ids: Vec<crate::actor::app::WindowId> =
+            let mut workspace_windows_ids: Vec<crate::actor::app::WindowId> =
                 if let Some(space) = space_id {
                     if is_active {
                         self.layout_manager.layout_engine.windows_in_active_workspace(space)
@@ -250,6 +425,9 @@ impl Reactor {
                 } else {
                     Vec::new()
                 };
+            if include_minimized {
+                workspace_windows_ids.extend(self.minimized_windows_in(*workspace_id));
+            }
 
             let predicted_positions = if !is_active {
                 if let Some(space) = space_id {
@@ -263,16 +441,17 @@ impl Reactor {
 
                     if let Some(screen) = screen_info {
                         let display_uuid = screen.display_uuid_opt();
-                        let gaps =
-                            self.config.settings.layout.gaps.effective_for_display(display_uuid);
+                        let gaps = self.layout_manager.layout_engine.gaps_for_display(display_uuid);
+                        let placement = self.stack_line_placement(space, Some(*workspace_id));
+                        let stack_line = &self.config.settings.ui.stack_line;
                         self.layout_manager.layout_engine.calculate_layout_for_workspace(
                             space,
                             *workspace_id,
                             screen.frame,
                             &gaps,
-                            self.config.settings.ui.stack_line.thickness(),
-                            self.config.settings.ui.stack_line.horiz_placement,
-                            self.config.settings.ui.stack_line.vert_placement,
+                            stack_line.reserved_thickness(placement),
+                            placement.horizontal,
+                            placement.vertical,
                         )
                     } else {
                         vec![]
@@ -289,7 +468,7 @@ impl Reactor {
 
             let mut windows: Vec<WindowData> = Vec::new();
             for wid in workspace_windows_ids.into_iter() {
-                if let Some(mut wd) = self.create_window_data(wid) {
+                if let Some(mut wd) = self.window_data(wid, include_minimized) {
                     if !is_active {
                         if let Some(pred) = predicted_map.get(&wid).copied() {
                             wd.info.frame = pred;
@@ -308,11 +487,15 @@ impl Reactor {
                         .map(|ws| ws.layout_mode().to_string())
                 })
                 .unwrap_or_else(|| "unknown".to_string());
+            let root_container = space_id.and_then(|space| {
+                self.layout_manager.layout_engine.root_container_kind(space, *workspace_id)
+            });
 
             workspaces.push(WorkspaceData {
                 id: format!("{:?}", workspace_id),
                 name: workspace_name.to_string(),
                 layout_mode,
+                root_container,
                 is_active,
                 window_count: windows.len(),
                 windows,
@@ -370,6 +553,335 @@ impl Reactor {
         self.layout_manager.layout_engine.active_workspace(space_id)
     }
 
+    /// Spaces without an entry in `display_space_ids` fall back to the one each
+    /// screen is currently showing.
+    fn handle_spaces_query(
+        &self,
+        display_space_ids: HashMap<String, Vec<SpaceId>>,
+    ) -> Vec<SpaceData> {
+        let cfg = self.activation_cfg();
+        let policy = &self.space_activation_policy;
+        let mut out = Vec::new();
+        for screen in &self.space_manager.screens {
+            let display_uuid = screen.display_uuid_opt();
+            let spaces = display_uuid
+                .and_then(|uuid| display_space_ids.get(uuid).cloned())
+                .unwrap_or_else(|| screen.space.into_iter().collect());
+            for space in spaces {
+                let reason = policy.activation_reason(cfg, Some(space), display_uuid);
+                out.push(SpaceData {
+                    space_id: space.get(),
+                    display_uuid: screen.display_uuid.clone(),
+                    display_name: screen.name.clone(),
+                    is_visible: screen.space == Some(space),
+                    is_active: self.is_space_active(space),
+                    is_paused: policy.is_space_paused(space),
+                    reason,
+                    display_rule_default_disable: display_uuid
+                        .and_then(|uuid| policy.display_rule_default(uuid)),
+                });
+            }
+        }
+        out
+    }
+
+    /// Returns None until an activation has shown who owns the menu bar.
+    pub(super) fn handle_menu_bar_owner_query(&self) -> Option<MenuBarOwnerData> {
+        let pid = self.menu_bar_owner?;
+        let info = self.app_manager.apps.get(&pid).map(|app| &app.info);
+        Some(MenuBarOwnerData {
+            pid,
+            bundle_id: info.and_then(|info| info.bundle_id.clone()),
+            name: info.and_then(|info| info.localized_name.clone()),
+            display_uuid: crate::sys::screen::active_menu_bar_display_uuid(),
+        })
+    }
+
+    /// Returns None only if the window server won't report the pointer position.
+    fn handle_cursor_query(&mut self) -> Option<CursorData> {
+        let position = window_server::current_cursor_location().ok()?;
+        let display = self
+            .space_manager
+            .screens
+            .iter()
+            .find(|screen| screen.frame.contains(position))
+            .cloned();
+        let wid = window_server::get_window_at_point(position)
+            .and_then(|wsid| self.window_manager.window_ids.get(&wsid).copied());
+        let window = wid.and_then(|wid| self.create_window_data(wid));
+        let workspace = wid.and_then(|wid| {
+            let space = self.best_space_for_window_id(wid)?;
+            let workspace_id = self
+                .layout_manager
+                .layout_engine
+                .virtual_workspace_manager()
+                .workspace_for_window(space, wid)?;
+            self.handle_workspace_layouts_query(Some(space), None)
+                .into_iter()
+                .find(|workspace| workspace.id == format!("{:?}", workspace_id))
+        });
+        Some(CursorData {
+            position,
+            display,
+            window,
+            workspace,
+        })
+    }
+
+    fn handle_explain_window_query(&mut self, window_id: WindowId) -> Option<WindowExplanation> {
+        let window = self.window_manager.windows.get(&window_id)?;
+        let app_info = self.app_manager.apps.get(&window_id.pid).map(|app| &app.info);
+        let app_name = app_info.and_then(|info| info.localized_name.clone());
+        let bundle_id = app_info.and_then(|info| info.bundle_id.clone());
+        let title = window.info.title.clone();
+        let ax_role = window.info.ax_role.clone();
+        let ax_subrole = window.info.ax_subrole.clone();
+        let is_manageable = window.is_manageable;
+        let is_managed = window.is_effectively_manageable();
+        let mut decisions = Vec::new();
+
+        let vwm = self.layout_manager.layout_engine.virtual_workspace_manager();
+        let applied = vwm.find_matching_app_rule(
+            bundle_id.as_deref(),
+            app_name.as_deref(),
+            Some(title.as_str()),
+            ax_role.as_deref(),
+            ax_subrole.as_deref(),
+        );
+        let matched_rules: Vec<MatchedAppRule> = vwm
+            .matching_app_rules(
+                bundle_id.as_deref(),
+                app_name.as_deref(),
+                Some(title.as_str()),
+                ax_role.as_deref(),
+                ax_subrole.as_deref(),
+            )
+            .into_iter()
+            .map(|(index, rule)| MatchedAppRule {
+                index,
+                applied: applied.is_some_and(|applied| std::ptr::eq(applied, rule)),
+                rule: rule.clone(),
+            })
+            .collect();
+        let applied = matched_rules.iter().find(|matched| matched.applied);
+
+        if matched_rules.is_empty() {
+            decisions.push("No app rule matches the window".to_string());
+        }
+        for matched in &matched_rules {
+            let criteria = rule_criteria(&matched.rule);
+            decisions.push(if matched.applied {
+                format!("App rule {} matches ({criteria}) and is applied", matched.index)
+            } else {
+                format!(
+                    "App rule {} matches ({criteria}) but a more specific rule wins",
+                    matched.index
+                )
+            });
+        }
+
+        if !is_manageable {
+            decisions.push(format!(
+                "Not a standard window (role {}, subrole {}), so rift leaves it alone",
+                ax_role.as_deref().unwrap_or("none"),
+                ax_subrole.as_deref().unwrap_or("none"),
+            ));
+        } else if !is_managed {
+            decisions.push("Rift was told not to manage the window (manage = false)".to_string());
+        }
+
+        let is_floating = self.layout_manager.layout_engine.is_window_floating(window_id);
+        let float_reason = self.layout_manager.layout_engine.auto_float_reason(window_id);
+        let rule_floats = applied.is_some_and(|matched| matched.rule.floating);
+        if is_managed {
+            decisions.push(match (is_floating, float_reason) {
+                (true, Some(reason)) => format!("Floating: auto_float matched {reason:?}"),
+                (true, None) if rule_floats => {
+                    "Floating: the applied rule sets floating = true".to_string()
+                }
+                (true, None) => "Floating: toggled by a command or drag".to_string(),
+                (false, _) if rule_floats => {
+                    "Tiled, though the applied rule floats it: toggled since".to_string()
+                }
+                (false, _) => "Tiled".to_string(),
+            });
+        }
+        if let Some(matched) = applied
+            && matched.rule.scratchpad != ScratchpadConfig::Boolean(false)
+        {
+            decisions.push("The applied rule makes the window a scratchpad".to_string());
+        }
+
+        let space = self.best_space_for_window_id(window_id);
+        let workspace_id = space.and_then(|space| {
+            self.layout_manager
+                .layout_engine
+                .virtual_workspace_manager()
+                .workspace_for_window(space, window_id)
+        });
+        let rule_workspace = applied.and_then(|matched| matched.rule.workspace.clone());
+        if is_managed {
+            decisions.push(match rule_workspace {
+                Some(WorkspaceSelector::Index(index)) => {
+                    format!("The applied rule assigns workspace {index}")
+                }
+                Some(WorkspaceSelector::Name(name)) => {
+                    format!("The applied rule assigns workspace \"{name}\"")
+                }
+                None => "No rule assigns a workspace; it opened on the active one".to_string(),
+            });
+        }
+        let workspace = space.zip(workspace_id).and_then(|(space, workspace_id)| {
+            self.handle_workspace_layouts_query(Some(space), None)
+                .into_iter()
+                .find(|workspace| workspace.id == format!("{:?}", workspace_id))
+        });
+
+        let layout_animate = space.and_then(|space| {
+            self.layout_manager.layout_engine.layout_specific_animate_settings(space)
+        });
+        let low_power = power::is_low_power_mode_enabled();
+        decisions.push(match layout_animate {
+            Some(animate) => {
+                format!("layout.scrolling.animate = {animate} overrides settings.animate")
+            }
+            None => format!("settings.animate = {}", self.config.settings.animate),
+        });
+        if low_power {
+            decisions.push("Low Power Mode is on, so layout changes are not animated".to_string());
+        }
+        let animate = layout_animate.unwrap_or(self.config.settings.animate) && !low_power;
+
+        let style = WindowStyleManager::desired_style(self, window_id);
+        if is_managed && is_floating {
+            decisions.push("Shadow and corner_radius rules only style tiled windows".to_string());
+        }
+
+        Some(WindowExplanation {
+            id: window_id,
+            app_name,
+            bundle_id,
+            title,
+            ax_role,
+            ax_subrole,
+            matched_rules,
+            is_managed,
+            is_floating,
+            float_reason,
+            workspace,
+            animate,
+            shadow: style.shadow,
+            corner_radius: style.corner_radius,
+            decisions,
+        })
+    }
+
+    /// Works out what to capture on the reactor thread and leaves the capture,
+    /// compositing and PNG encoding to a worker, which sends the reply.
+    fn handle_screenshot_query(
+        &self,
+        target: ScreenshotTarget,
+        path: Option<PathBuf>,
+        resp: SyncSender<Result<ScreenshotData, String>>,
+    ) {
+        let job = match self.screenshot_job(target) {
+            Ok(job) => job,
+            Err(err) => {
+                let _ = resp.send(Err(err));
+                return;
+            }
+        };
+        let path = path.unwrap_or_else(|| {
+            let stamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            desktop_dir().join(format!("rift-{}-{}.png", target.as_str(), stamp.as_secs()))
+        });
+        std::thread::spawn(move || {
+            let _ = resp.send(job.capture(target, path));
+        });
+    }
+
+    fn screenshot_job(&self, target: ScreenshotTarget) -> Result<ScreenshotJob, String> {
+        let focused = self.main_window();
+        let screen = focused
+            .and_then(|wid| self.best_space_for_window_id(wid))
+            .or_else(|| self.default_query_space())
+            .and_then(|space| self.space_manager.screen_by_space(space));
+
+        match target {
+            ScreenshotTarget::Window => {
+                let wsid = focused
+                    .and_then(|wid| self.window_manager.windows.get(&wid))
+                    .and_then(|window| window.info.sys_id)
+                    .ok_or("No focused window to capture")?;
+                Ok(ScreenshotJob::Window(wsid))
+            }
+            ScreenshotTarget::Workspace => {
+                let screen = screen.ok_or("No display to capture")?;
+                let space = screen.space.ok_or("The display has no active space")?;
+                let engine = &self.layout_manager.layout_engine;
+                // Tiled windows first so floating ones end up drawn over them.
+                let (floating, tiled): (Vec<_>, Vec<_>) = engine
+                    .windows_in_active_workspace(space)
+                    .into_iter()
+                    .partition(|&wid| engine.is_window_floating(wid));
+                let layers = tiled
+                    .into_iter()
+                    .chain(floating)
+                    .filter_map(|wid| self.window_manager.windows.get(&wid))
+                    .filter(|window| !window.info.is_minimized)
+                    .filter_map(|window| Some((window.frame_monotonic, window.info.sys_id?)))
+                    .collect();
+                Ok(ScreenshotJob::Workspace { bounds: screen.frame, layers })
+            }
+            ScreenshotTarget::Display => {
+                let screen = screen.ok_or("No display to capture")?;
+                Ok(ScreenshotJob::Display(screen.frame))
+            }
+        }
+    }
+
+    fn handle_report_query(&mut self, redact_titles: bool) -> serde_json::Value {
+        let mut config = serde_json::to_value(&self.config).unwrap_or_default();
+        report::strip_secrets(&mut config);
+        let spaces: Vec<SpaceId> = self.space_manager.iter_known_spaces().collect();
+        let workspaces: BTreeMap<u64, Vec<WorkspaceData>> = spaces
+            .into_iter()
+            .map(|space| (space.get(), self.handle_workspace_query(Some(space), false)))
+            .collect();
+        let events: Vec<String> = self
+            .recording_manager
+            .recent_events
+            .iter()
+            .map(|line| {
+                if redact_titles {
+                    report::redact_event_titles(line)
+                } else {
+                    line.clone()
+                }
+            })
+            .collect();
+        let generated_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+
+        let data = ReportData {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            os_version: NSProcessInfo::processInfo().operatingSystemVersionString().to_string(),
+            generated_at: generated_at.as_secs(),
+            config,
+            layout: self.layout_manager.layout_engine.serialize_to_string(),
+            displays: self.handle_displays_query(),
+            spaces: self.handle_spaces_query(managed_display_space_ids()),
+            workspaces,
+            applications: self.handle_applications_query(),
+            metrics: self.handle_metrics_query(),
+            events: events.join("\n"),
+        };
+        let mut value = serde_json::to_value(data).unwrap_or_default();
+        if redact_titles {
+            report::redact_titles(&mut value);
+        }
+        value
+    }
+
     fn handle_displays_query(&self) -> Vec<DisplayData> {
         let active_context_space = self.workspace_command_space();
         let active_space_ids = self.active_space_ids();
@@ -413,18 +925,27 @@ impl Reactor {
             .collect()
     }
 
-    fn handle_windows_query(&self, space_id: Option<SpaceId>) -> Vec<WindowData> {
+    fn handle_windows_query(
+        &self,
+        space_id: Option<SpaceId>,
+        include_minimized: bool,
+    ) -> Vec<WindowData> {
         let target_space = space_id
             .or_else(|| self.default_query_space())
             .or_else(|| self.space_manager.first_known_space());
 
         if let Some(space) = target_space {
-            let active_windows =
+            let mut active_windows =
                 self.layout_manager.layout_engine.windows_in_active_workspace(space);
+            if include_minimized
+                && let Some(workspace) = self.layout_manager.layout_engine.active_workspace(space)
+            {
+                active_windows.extend(self.minimized_windows_in(workspace));
+            }
 
             active_windows
                 .into_iter()
-                .filter_map(|wid| self.create_window_data(wid))
+                .filter_map(|wid| self.window_data(wid, include_minimized))
                 .collect()
         } else {
             self.window_manager
@@ -515,6 +1036,7 @@ impl Reactor {
             "applications": self.app_manager.apps.len(),
             "screens": self.space_manager.screens.len(),
             "workspace_stats": workspace_stats,
+            "frame_audit": self.frame_audit_manager.stats,
         })
     }
 
@@ -707,3 +1229,71 @@ impl Reactor {
         serde_json::to_string_pretty(&out)
     }
 }
+
+/// The match criteria a rule sets, as they would be written in the config.
+fn rule_criteria(rule: &AppWorkspaceRule) -> String {
+    [
+        ("app_id", &rule.app_id),
+        ("app_name", &rule.app_name),
+        ("title_regex", &rule.title_regex),
+        ("title_substring", &rule.title_substring),
+        ("ax_role", &rule.ax_role),
+        ("ax_subrole", &rule.ax_subrole),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| value.as_ref().map(|value| format!("{key} = {value:?}")))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// What a screenshot query needs from the reactor, gathered up front so the
+/// capture itself can run off the reactor thread.
+enum ScreenshotJob {
+    Window(WindowServerId),
+    Workspace {
+        bounds: CGRect,
+        /// Back to front.
+        layers: Vec<(CGRect, WindowServerId)>,
+    },
+    Display(CGRect),
+}
+
+impl ScreenshotJob {
+    fn capture(self, target: ScreenshotTarget, path: PathBuf) -> Result<ScreenshotData, String> {
+        let (image, windows) = match self {
+            ScreenshotJob::Window(wsid) => (window_server::capture_window(wsid), 1),
+            ScreenshotJob::Workspace { bounds, layers } => {
+                let layers: Vec<_> = layers
+                    .into_iter()
+                    .filter_map(|(frame, wsid)| Some((frame, window_server::capture_window(wsid)?)))
+                    .collect();
+                // Captures come back at the backing scale, which is what the canvas should use.
+                let scale = layers
+                    .iter()
+                    .map(|(frame, image)| {
+                        let width = CGImage::width(Some(image.cg_image())) as f64;
+                        (width / frame.size.width.max(1.0)).round()
+                    })
+                    .fold(1.0, f64::max);
+                let image = window_server::composite_images(bounds, scale, &layers);
+                (image, layers.len())
+            }
+            ScreenshotJob::Display(bounds) => (window_server::capture_display(bounds), 0),
+        };
+        let image = image.ok_or("Capture failed; rift may lack Screen Recording permission")?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|err| format!("Cannot create {}: {err}", dir.display()))?;
+        }
+        window_server::write_png(&image, &path)?;
+        info!(?target, path = %path.display(), windows, "Saved screenshot");
+        Ok(ScreenshotData {
+            target,
+            width: CGImage::width(Some(image.cg_image())),
+            height: CGImage::height(Some(image.cg_image())),
+            path,
+            windows,
+        })
+    }
+}
@@ -0,0 +1,134 @@
+//! End-to-end regression scenarios run against the headless [`Simulation`].
+
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use test_log::test;
+
+use super::simulation::Simulation;
+use crate::actor::app::WindowId;
+use crate::layout_engine::{Direction, LayoutCommand};
+use crate::sys::geometry::CGRectExt;
+use crate::sys::screen::SpaceId;
+
+const EPSILON: f64 = 0.5;
+
+fn rect(x: f64, y: f64, w: f64, h: f64) -> CGRect {
+    CGRect::new(CGPoint::new(x, y), CGSize::new(w, h))
+}
+
+fn center(frame: CGRect) -> CGPoint {
+    CGPoint::new(
+        frame.origin.x + frame.size.width / 2.0,
+        frame.origin.y + frame.size.height / 2.0,
+    )
+}
+
+fn frames_of(sim: &Simulation, wids: &[WindowId]) -> Vec<CGRect> {
+    wids.iter()
+        .map(|&wid| sim.system.frame(wid).expect("window should exist"))
+        .collect()
+}
+
+/// The windows tile `screen` exactly: each stays on screen, no two overlap, and
+/// together they leave no gap.
+fn assert_tiles_cover(sim: &Simulation, screen: CGRect, wids: &[WindowId]) {
+    let frames = frames_of(sim, wids);
+    let bounds = rect(
+        screen.origin.x - EPSILON,
+        screen.origin.y - EPSILON,
+        screen.size.width + 2.0 * EPSILON,
+        screen.size.height + 2.0 * EPSILON,
+    );
+    for (wid, frame) in wids.iter().zip(&frames) {
+        assert!(
+            bounds.contains_rect(*frame),
+            "{wid:?} at {frame:?} leaves {screen:?}"
+        );
+    }
+    for (i, a) in frames.iter().enumerate() {
+        for b in &frames[i + 1..] {
+            assert!(a.intersection(b).area() < EPSILON, "{a:?} overlaps {b:?}");
+        }
+    }
+    let covered: f64 = frames.iter().map(|frame| frame.area()).sum();
+    assert!(
+        (covered - screen.area()).abs() < EPSILON * screen.size.width,
+        "tiles cover {covered} of {}",
+        screen.area()
+    );
+}
+
+fn direction_towards(from: CGRect, to: CGRect) -> Direction {
+    let (from, to) = (center(from), center(to));
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    match (dx.abs() >= dy.abs(), dx > 0.0, dy > 0.0) {
+        (true, true, _) => Direction::Right,
+        (true, false, _) => Direction::Left,
+        (false, _, true) => Direction::Down,
+        (false, _, false) => Direction::Up,
+    }
+}
+
+#[test]
+fn tiles_cover_the_screen_as_windows_open_and_close() {
+    let screen = rect(0., 0., 1200., 900.);
+    let mut sim = Simulation::new(vec![screen]);
+
+    let mut wids = sim.launch_app(1, 3);
+    assert_tiles_cover(&sim, screen, &wids);
+
+    wids.push(sim.open_window(1));
+    assert_tiles_cover(&sim, screen, &wids);
+
+    let closed = wids.remove(1);
+    sim.close_window(closed);
+    assert_tiles_cover(&sim, screen, &wids);
+}
+
+#[test]
+fn move_focus_raises_the_neighbouring_window() {
+    let screen = rect(0., 0., 1000., 800.);
+    let mut sim = Simulation::new(vec![screen]);
+    let wids = sim.launch_app(1, 2);
+    assert_eq!(sim.system.focused_window(), Some(wids[0]));
+
+    let frames = frames_of(&sim, &wids);
+    sim.layout_command(LayoutCommand::MoveFocus(direction_towards(frames[0], frames[1])));
+    assert_eq!(sim.system.focused_window(), Some(wids[1]));
+
+    sim.layout_command(LayoutCommand::MoveFocus(direction_towards(frames[1], frames[0])));
+    assert_eq!(sim.system.focused_window(), Some(wids[0]));
+}
+
+#[test]
+fn move_focus_crosses_into_another_app() {
+    let screen = rect(0., 0., 1000., 800.);
+    let mut sim = Simulation::new(vec![screen]);
+    let first = sim.launch_app(1, 1)[0];
+    let second = sim.launch_app(2, 1)[0];
+    assert_eq!(sim.system.focused_window(), Some(second));
+    assert_tiles_cover(&sim, screen, &[first, second]);
+
+    let frames = frames_of(&sim, &[second, first]);
+    sim.layout_command(LayoutCommand::MoveFocus(direction_towards(frames[0], frames[1])));
+    assert_eq!(sim.system.focused_window(), Some(first));
+    assert_eq!(sim.reactor.main_window(), Some(first));
+}
+
+#[test]
+fn plugging_a_display_in_and_out_leaves_existing_tiles_alone() {
+    let left = rect(0., 0., 1000., 800.);
+    let mut sim = Simulation::new(vec![left]);
+    let wids = sim.launch_app(1, 2);
+    let before = frames_of(&sim, &wids);
+
+    sim.connect_display(
+        "sim-display-right",
+        rect(1000., 0., 1000., 800.),
+        SpaceId::new(7),
+    );
+    assert_eq!(frames_of(&sim, &wids), before);
+    assert_tiles_cover(&sim, left, &wids);
+
+    sim.disconnect_display("sim-display-right");
+    assert_eq!(frames_of(&sim, &wids), before);
+}
@@ -0,0 +1,244 @@
+//! Headless stand-in for the window server and accessibility layer.
+//!
+//! [`FakeSystem`] keeps an in-memory model of displays, windows and focus. It
+//! turns changes to that model into the [`Event`]s the notification center and
+//! app threads would send, and answers the reactor's app and raise requests by
+//! updating the model. [`Simulation`] pairs it with a [`Reactor`] so whole
+//! scenarios run deterministically, without a GUI session.
+
+use objc2_core_foundation::{CGRect, CGSize};
+
+use super::testing::{Apps, TestWindowState, make_window};
+use super::{Command, Event, Reactor, ScreenInfo};
+use crate::actor;
+use crate::actor::app::{Quiet, WindowId, pid_t};
+use crate::actor::raise_manager;
+use crate::common::config::{LayoutSettings, VirtualWorkspaceSettings};
+use crate::layout_engine::{LayoutCommand, LayoutEngine};
+use crate::sys::event::MouseState;
+use crate::sys::screen::{ScreenId, SpaceId};
+use crate::sys::window_server::{WindowServerId, WindowServerInfo};
+
+/// Upper bound on request/event round trips before [`Simulation::settle`] gives up.
+const MAX_SETTLE_ROUNDS: usize = 64;
+
+pub struct FakeDisplay {
+    pub uuid: String,
+    pub frame: CGRect,
+    pub space: Option<SpaceId>,
+}
+
+pub struct FakeSystem {
+    pub apps: Apps,
+    pub displays: Vec<FakeDisplay>,
+    focused: Option<WindowId>,
+    raise_rx: actor::Receiver<raise_manager::Event>,
+    next_server_id: u32,
+}
+
+impl FakeSystem {
+    fn new(raise_rx: actor::Receiver<raise_manager::Event>) -> FakeSystem {
+        FakeSystem {
+            apps: Apps::new(),
+            displays: Vec::new(),
+            focused: None,
+            raise_rx,
+            next_server_id: 1,
+        }
+    }
+
+    pub fn focused_window(&self) -> Option<WindowId> { self.focused }
+
+    pub fn frame(&self, wid: WindowId) -> Option<CGRect> {
+        self.apps.windows.get(&wid).map(|window| window.frame)
+    }
+
+    fn screens(&self) -> Vec<ScreenInfo> {
+        self.displays
+            .iter()
+            .enumerate()
+            .map(|(idx, display)| ScreenInfo {
+                id: ScreenId::new(idx as u32),
+                frame: display.frame,
+                display_uuid: display.uuid.clone(),
+                name: None,
+                space: display.space,
+            })
+            .collect()
+    }
+
+    fn screen_params_event(&self) -> Event { Event::ScreenParametersChanged(self.screens()) }
+
+    fn next_window_server_id(&mut self) -> WindowServerId {
+        let id = WindowServerId::new(self.next_server_id);
+        self.next_server_id += 1;
+        id
+    }
+
+    fn server_info(pid: pid_t, id: WindowServerId, frame: CGRect) -> WindowServerInfo {
+        WindowServerInfo {
+            id,
+            pid,
+            layer: 0,
+            frame,
+            min_frame: CGSize::ZERO,
+            max_frame: CGSize::ZERO,
+        }
+    }
+
+    fn next_window_idx(&self, pid: pid_t) -> u32 {
+        let last = self.apps.windows.keys().filter(|wid| wid.pid == pid).map(|wid| wid.idx.get());
+        last.max().unwrap_or(0) + 1
+    }
+
+    /// Events macOS sends when `wid` becomes the key window of the frontmost app.
+    fn focus(&mut self, wid: WindowId, quiet: Quiet) -> Vec<Event> {
+        let previous = self.focused.replace(wid);
+        if previous == Some(wid) {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+        if let Some(previous) = previous
+            && previous.pid != wid.pid
+        {
+            events.push(Event::ApplicationDeactivated(previous.pid));
+            events.push(Event::ApplicationGloballyDeactivated(previous.pid));
+        }
+        events.push(Event::ApplicationMainWindowChanged(wid.pid, Some(wid), quiet));
+        events.push(Event::ApplicationActivated(wid.pid, quiet));
+        events.push(Event::ApplicationGloballyActivated(wid.pid));
+        events
+    }
+
+    /// Plays the part of the raise manager: the last window of each request gets focus.
+    fn drain_raises(&mut self) -> Vec<Event> {
+        let mut events = Vec::new();
+        while let Ok((_, event)) = self.raise_rx.try_recv() {
+            let raise_manager::Event::RaiseRequest(request) = event else {
+                continue;
+            };
+            if let Some((wid, _)) = request.focus_window {
+                events.extend(self.focus(wid, request.focus_quiet));
+            }
+        }
+        events
+    }
+}
+
+pub struct Simulation {
+    pub reactor: Reactor,
+    pub system: FakeSystem,
+}
+
+impl Simulation {
+    /// Starts a reactor with one display per frame, each showing its own space.
+    pub fn new(display_frames: Vec<CGRect>) -> Simulation {
+        let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        ));
+        let (raise_tx, raise_rx) = actor::channel();
+        reactor.communication_manager.raise_manager_tx = raise_tx;
+
+        let mut system = FakeSystem::new(raise_rx);
+        system.displays = display_frames
+            .into_iter()
+            .enumerate()
+            .map(|(idx, frame)| FakeDisplay {
+                uuid: format!("sim-display-{idx}"),
+                frame,
+                space: Some(SpaceId::new(idx as u64 + 1)),
+            })
+            .collect();
+
+        let mut sim = Simulation { reactor, system };
+        let event = sim.system.screen_params_event();
+        sim.dispatch(vec![event]);
+        sim
+    }
+
+    fn dispatch(&mut self, events: Vec<Event>) {
+        self.reactor.handle_events(events);
+        self.settle();
+    }
+
+    /// Feeds the reactor's requests back through the fake until nothing changes.
+    pub fn settle(&mut self) {
+        for _ in 0..MAX_SETTLE_ROUNDS {
+            let mut events = self.system.apps.simulate_events();
+            events.extend(self.system.drain_raises());
+            if events.is_empty() {
+                return;
+            }
+            self.reactor.handle_events(events);
+        }
+        panic!("simulation did not settle after {MAX_SETTLE_ROUNDS} rounds");
+    }
+
+    /// Launches a frontmost app with `count` windows, placed on the first display.
+    pub fn launch_app(&mut self, pid: pid_t, count: usize) -> Vec<WindowId> {
+        let mut windows: Vec<_> = (1..=count).map(make_window).collect();
+        for info in &mut windows {
+            info.sys_id = Some(self.system.next_window_server_id());
+        }
+        let wids: Vec<WindowId> = (1..=count as u32).map(|idx| WindowId::new(pid, idx)).collect();
+        let main_window = wids.first().copied();
+        let mut events = self.system.apps.make_app_with_opts(pid, windows, main_window, true, true);
+        if let Some(main_window) = main_window {
+            events.extend(self.system.focus(main_window, Quiet::No));
+        }
+        self.dispatch(events);
+        wids
+    }
+
+    /// Opens a new window for an app that is already running.
+    pub fn open_window(&mut self, pid: pid_t) -> WindowId {
+        let idx = self.system.next_window_idx(pid);
+        let wid = WindowId::new(pid, idx);
+        let mut info = make_window(idx as usize);
+        let server_id = self.system.next_window_server_id();
+        info.sys_id = Some(server_id);
+        self.system.apps.windows.insert(wid, TestWindowState {
+            frame: info.frame,
+            ..Default::default()
+        });
+        let server_info = FakeSystem::server_info(pid, server_id, info.frame);
+        self.dispatch(vec![Event::WindowCreated(
+            wid,
+            info,
+            Some(server_info),
+            Some(MouseState::Up),
+        )]);
+        wid
+    }
+
+    pub fn close_window(&mut self, wid: WindowId) {
+        self.system.apps.windows.remove(&wid);
+        if self.system.focused == Some(wid) {
+            self.system.focused = None;
+        }
+        self.dispatch(vec![Event::WindowDestroyed(wid)]);
+    }
+
+    pub fn layout_command(&mut self, command: LayoutCommand) {
+        self.dispatch(vec![Event::Command(Command::Layout(command))]);
+    }
+
+    /// Plugs in a new display showing `space`.
+    pub fn connect_display(&mut self, uuid: &str, frame: CGRect, space: SpaceId) {
+        self.system.displays.push(FakeDisplay {
+            uuid: uuid.to_string(),
+            frame,
+            space: Some(space),
+        });
+        let event = self.system.screen_params_event();
+        self.dispatch(vec![event]);
+    }
+
+    pub fn disconnect_display(&mut self, uuid: &str) {
+        self.system.displays.retain(|display| display.uuid != uuid);
+        let event = self.system.screen_params_event();
+        self.dispatch(vec![event]);
+    }
+}
@@ -18,7 +18,7 @@ impl Reactor {
         config.settings.default_disable = false;
         config.settings.animate = false;
         let record = Record::new_for_test(tempfile::NamedTempFile::new().unwrap());
-        let (broadcast_tx, _) = actor::channel();
+        let (broadcast_tx, _) = actor::broadcast::channel();
         Reactor::new(config, layout, record, broadcast_tx, None, false)
     }
 
@@ -94,6 +94,7 @@ pub fn make_window(idx: usize) -> WindowInfo {
         path: None,
         ax_role: None,
         ax_subrole: None,
+        ax_identifier: None,
     }
 }
 
@@ -150,6 +151,7 @@ impl Apps {
             info: AppInfo {
                 bundle_id: Some(format!("com.testapp{pid}")),
                 localized_name: Some(format!("TestApp{pid}")),
+                animation_quirk: None,
             },
             handle,
             is_frontmost,
@@ -204,7 +206,7 @@ impl Apps {
             match request {
                 Request::Terminate => break,
                 Request::WindowMaybeDestroyed(_) => {}
-                Request::GetVisibleWindows => {
+                Request::GetVisibleWindows | Request::RefreshObservers => {
                     if got_visible_windows {
                         continue;
                     }
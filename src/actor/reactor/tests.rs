@@ -1,13 +1,20 @@
+use std::time::Instant;
+
 use objc2_core_foundation::{CGPoint, CGSize};
 use test_log::test;
 
+use super::animation::AnimationManager;
 use super::display_topology::TopologyState;
+use super::managers::WindowStyleManager;
 use super::testing::*;
 use super::*;
 use crate::actor::app::Request;
+use crate::actor::wm_controller::ExecCmd;
+use crate::common::config::{DirectionalFocus, LayoutMode, WorkspaceHideMethod, WorkspaceSelector};
 use crate::layout_engine::{Direction, LayoutCommand, LayoutEngine};
 use crate::sys::app::WindowInfo;
 use crate::sys::window_server::WindowServerId;
+use crate::sys::window_style::WindowStyle;
 
 #[test]
 fn it_ignores_stale_resize_events() {
@@ -105,6 +112,90 @@ fn it_manages_windows_on_enabled_spaces() {
     );
 }
 
+#[test]
+fn minimized_and_hidden_windows_stay_in_their_mission_control_workspace() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let minimized = WindowId::new(1, 2);
+    let hidden = WindowId::new(2, 1);
+    reactor.handle_events(vec![
+        Event::WindowMinimized(minimized),
+        Event::ApplicationHidden(2),
+        Event::WindowMinimized(hidden),
+    ]);
+
+    let windows = query_windows_with_minimized(&mut reactor, space);
+    assert!(!find_in(&windows, WindowId::new(1, 1)).info.is_minimized);
+    assert!(find_in(&windows, minimized).info.is_minimized);
+    assert!(!find_in(&windows, minimized).is_hidden);
+    assert!(find_in(&windows, hidden).is_hidden);
+    assert_eq!(
+        reactor.layout_manager.layout_engine.windows_in_active_workspace(space),
+        vec![WindowId::new(1, 1)]
+    );
+    // Queries other than mission control's leave minimized windows out, as before.
+    let ids: Vec<WindowId> = reactor.query_windows(Some(space)).iter().map(|w| w.id).collect();
+    assert_eq!(ids, vec![WindowId::new(1, 1)]);
+    let workspaces = reactor.query_workspaces(Some(space));
+    assert!(workspaces.iter().all(|ws| ws.windows.iter().all(|w| !w.info.is_minimized)));
+
+    reactor.handle_events(vec![Event::WindowDeminiaturized(minimized)]);
+    let windows = query_windows_with_minimized(&mut reactor, space);
+    assert!(!find_in(&windows, minimized).info.is_minimized);
+}
+
+fn query_windows_with_minimized(reactor: &mut Reactor, space: SpaceId) -> Vec<WindowData> {
+    let (resp, rx) = std::sync::mpsc::sync_channel(1);
+    reactor.handle_query_request(query::QueryRequest::Windows {
+        space_id: Some(space),
+        include_minimized: true,
+        resp,
+    });
+    rx.try_recv().expect("the query is answered right away")
+}
+
+#[test]
+fn windows_get_a_rift_id_that_is_released_when_they_close() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let windows = reactor.query_windows(Some(space));
+    let first = find_in(&windows, WindowId::new(1, 1)).rift_id.expect("has a rift_id");
+    let second = find_in(&windows, WindowId::new(1, 2)).rift_id.expect("has a rift_id");
+    assert_ne!(first, second);
+
+    reactor.handle_event(Event::WindowDestroyed(WindowId::new(1, 2)));
+    assert_eq!(reactor.window_identities.rift_id(WindowId::new(1, 2)), None);
+    assert_eq!(
+        reactor.window_identities.rift_id(WindowId::new(1, 1)),
+        Some(first)
+    );
+}
+
+fn find_in(windows: &[WindowData], wid: WindowId) -> &WindowData {
+    windows.iter().find(|w| w.id == wid).expect("window is listed")
+}
+
 #[test]
 fn it_clears_screen_state_when_no_displays_are_reported() {
     let mut reactor = Reactor::new_for_test(LayoutEngine::new(
@@ -353,6 +444,237 @@ fn handle_layout_response_includes_handles_for_raise_and_focus_windows() {
     }
 }
 
+#[test]
+fn it_suspends_and_revalidates_across_session_switches() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(reactor.is_space_active(space));
+
+    reactor.handle_event(Event::SessionResigned);
+    assert!(!reactor.is_space_active(space));
+
+    reactor.handle_event(Event::SessionResumed);
+    assert!(reactor.is_space_active(space));
+    let requests = apps.requests();
+    assert!(
+        requests.iter().any(|r| matches!(r, Request::RefreshObservers)),
+        "expected observers to be refreshed, got {requests:?}"
+    );
+    for event in apps.simulate_events_for_requests(requests) {
+        reactor.handle_event(event);
+    }
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(reactor.window_manager.windows.len(), 2);
+}
+
+#[test]
+fn unlocking_the_screen_rereads_windows() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![full_screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::ScreenLocked(true));
+    let requests = apps.requests();
+    assert!(!requests.iter().any(|r| matches!(r, Request::GetVisibleWindows)));
+
+    reactor.handle_event(Event::ScreenLocked(false));
+    let requests = apps.requests();
+    assert!(
+        requests.iter().any(|r| matches!(r, Request::GetVisibleWindows)),
+        "expected windows to be re-read, got {requests:?}"
+    );
+}
+
+#[test]
+fn native_mission_control_holds_layout_until_it_exits() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![full_screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    let remaining = WindowId::new(1, 1);
+    assert_ne!(apps.windows[&remaining].frame, full_screen);
+
+    // The Dock and the window server both announce it.
+    reactor.handle_event(Event::MissionControlNativeEntered);
+    reactor.handle_event(Event::MissionControlNativeEntered);
+    assert!(reactor.is_mission_control_active());
+    reactor.handle_event(Event::WindowDestroyed(WindowId::new(1, 2)));
+    apps.simulate_until_quiet(&mut reactor);
+    let frame = apps.windows[&remaining].frame;
+    assert_ne!(frame, full_screen, "windows were laid out under Exposé");
+
+    reactor.set_mission_control_active(false);
+    reactor.update_layout_or_warn(false, false);
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(apps.windows[&remaining].frame, full_screen);
+}
+
+#[test]
+fn kill_switch_stops_managing_and_restores_original_frames() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    let windows = make_windows(2);
+    let original: Vec<CGRect> = windows.iter().map(|info| info.frame).collect();
+    reactor.handle_events(apps.make_app(1, windows));
+    apps.simulate_until_quiet(&mut reactor);
+    let wids = [WindowId::new(1, 1), WindowId::new(1, 2)];
+    assert_ne!(
+        apps.windows[&wids[0]].frame, original[0],
+        "windows should have been tiled"
+    );
+
+    reactor.handle_event(Event::KillSwitch);
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(!reactor.is_space_active(space));
+    assert!(!reactor.config.settings.animate);
+    for (wid, frame) in wids.iter().zip(&original) {
+        assert_eq!(apps.windows[wid].frame, *frame);
+    }
+
+    // Rift stays out of the way, even if the screens change.
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_event(Event::KillSwitch);
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(!reactor.is_space_active(space));
+    for (wid, frame) in wids.iter().zip(&original) {
+        assert_eq!(apps.windows[wid].frame, *frame);
+    }
+}
+
+#[test]
+fn restore_all_moves_windows_back_to_their_original_frames() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    let windows = make_windows(2);
+    let original: Vec<CGRect> = windows.iter().map(|info| info.frame).collect();
+    reactor.handle_events(apps.make_app(1, windows));
+    apps.simulate_until_quiet(&mut reactor);
+    let wids = [WindowId::new(1, 1), WindowId::new(1, 2)];
+    let tiled: Vec<CGRect> = wids.iter().map(|wid| apps.windows[wid].frame).collect();
+
+    // Nothing floats, so nothing moves.
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::RestoreAll {
+        only_floats: true,
+    })));
+    apps.simulate_until_quiet(&mut reactor);
+    for (wid, frame) in wids.iter().zip(&tiled) {
+        assert_eq!(apps.windows[wid].frame, *frame);
+    }
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::RestoreAll {
+        only_floats: false,
+    })));
+    apps.simulate_until_quiet(&mut reactor);
+    for (wid, frame) in wids.iter().zip(&original) {
+        assert_eq!(apps.windows[wid].frame, *frame);
+    }
+}
+
+#[test]
+fn toggle_topmost_tracks_the_focused_window_until_toggled_back() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    let topmost = |reactor: &Reactor, idx| {
+        reactor.window_manager.windows[&WindowId::new(1, idx)].topmost_from.is_some()
+    };
+    let toggle = |wsid| {
+        let command = ReactorCommand::ToggleTopmost { window_server_id: Some(wsid) };
+        Event::Command(Command::Reactor(command))
+    };
+
+    reactor.handle_event(toggle(WindowServerId::new(2)));
+    assert!(topmost(&reactor, 2));
+    assert!(!topmost(&reactor, 1));
+
+    reactor.handle_event(toggle(WindowServerId::new(2)));
+    assert!(!topmost(&reactor, 2));
+
+    reactor.handle_event(toggle(WindowServerId::new(1)));
+    reactor.clear_topmost_windows();
+    assert!(!topmost(&reactor, 1));
+}
+
+#[test]
+fn focusing_a_stacked_window_from_outside_selects_it_in_its_stack() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    let (first, last) = (WindowId::new(1, 1), WindowId::new(1, 3));
+    reactor.handle_events(apps.make_app_with_opts(1, make_windows(3), Some(last), true, true));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::ToggleStack)));
+    apps.simulate_until_quiet(&mut reactor);
+    let engine = &reactor.layout_manager.layout_engine;
+    assert!(engine.is_hidden_in_stack(space, first));
+
+    reactor.handle_event(Event::ApplicationMainWindowChanged(1, Some(first), Quiet::No));
+    apps.simulate_until_quiet(&mut reactor);
+
+    let engine = &reactor.layout_manager.layout_engine;
+    assert!(!engine.is_hidden_in_stack(space, first));
+    assert!(engine.is_hidden_in_stack(space, last));
+}
+
 #[test]
 fn it_preserves_layout_after_login_screen() {
     // TODO: This would be better tested with a more complete simulation.
@@ -653,6 +975,7 @@ fn it_retains_windows_without_server_ids_after_login_visibility_failure() {
         path: None,
         ax_role: None,
         ax_subrole: None,
+        ax_identifier: None,
     };
 
     reactor.handle_events(apps.make_app_with_opts(
@@ -880,3 +1203,875 @@ fn fullscreen_space_in_screen_params_does_not_trigger_topology_relayout() {
         Some(user_space)
     );
 }
+
+#[test]
+fn exec_placement_is_claimed_by_the_launched_app_only() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.handle_event(screen_params_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    reactor.handle_events(apps.make_app(2, make_windows(1)));
+    let _events = apps.simulate_events();
+
+    reactor.handle_event(Event::PlaceNextWindow(WindowPlacement {
+        app: LaunchedApp::BundleId("com.testapp2".into()),
+        workspace: None,
+        floating: true,
+        display: None,
+    }));
+    // Another app's window opening while the launched app starts up is left alone.
+    for wid in [
+        WindowId::new(1, 2),
+        WindowId::new(2, 2),
+        WindowId::new(2, 3),
+    ] {
+        reactor.handle_event(Event::WindowCreated(
+            wid,
+            make_window(wid.idx.get() as usize),
+            None,
+            Some(MouseState::Up),
+        ));
+    }
+
+    let engine = &reactor.layout_manager.layout_engine;
+    assert!(!engine.is_window_floating(WindowId::new(1, 2)));
+    assert!(engine.is_window_floating(WindowId::new(2, 2)));
+    assert!(!engine.is_window_floating(WindowId::new(2, 3)));
+    assert!(reactor.window_placement_manager.pending.is_empty());
+}
+
+#[test]
+fn launched_app_is_read_from_open_arguments() {
+    let command = |args: &str| args.split(' ').map(String::from).collect::<Vec<_>>();
+    let app = |args: &str| LaunchedApp::for_command(&command(args), 42);
+    assert_eq!(
+        app("/usr/bin/open -na Terminal"),
+        LaunchedApp::Name("Terminal".into())
+    );
+    assert_eq!(
+        app("open -b com.apple.Safari"),
+        LaunchedApp::BundleId("com.apple.Safari".into())
+    );
+    assert_eq!(
+        app("open /Applications/Zed.app"),
+        LaunchedApp::Name("Zed".into())
+    );
+    assert_eq!(app("open notes.txt"), LaunchedApp::Pid(42));
+    assert_eq!(app("alacritty --working-directory /tmp"), LaunchedApp::Pid(42));
+
+    let info = AppInfo {
+        bundle_id: Some("com.apple.Terminal".into()),
+        localized_name: Some("Terminal".into()),
+        animation_quirk: None,
+    };
+    assert!(app("open -a terminal").matches(7, Some(&info)));
+    assert!(!app("open -b com.apple.Safari").matches(7, Some(&info)));
+    assert!(app("alacritty").matches(42, None));
+}
+
+#[test]
+fn notification_banners_push_tiled_windows_aside_only_while_shown() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.layout.avoid_notification_banners = true;
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![full_screen],
+        vec![Some(SpaceId::new(1))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    let wid = WindowId::new(1, 1);
+    assert_eq!(apps.windows[&wid].frame, full_screen);
+
+    let banner = WindowServerId::new(900);
+    let banner_frame = CGRect::new(CGPoint::new(600., 0.), CGSize::new(400., 100.));
+    reactor.set_notification_banner(banner, Some(banner_frame));
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(
+        apps.windows[&wid].frame,
+        CGRect::new(CGPoint::new(0., 100.), CGSize::new(1000., 900.))
+    );
+
+    reactor.set_notification_banner(banner, None);
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(apps.windows[&wid].frame, full_screen);
+}
+
+#[test]
+fn secure_input_changes_are_tracked_without_touching_layout() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+
+    reactor.handle_event(Event::SecureInputChanged(true));
+    assert!(reactor.secure_input);
+    assert!(apps.requests().is_empty());
+
+    reactor.handle_event(Event::SecureInputChanged(false));
+    assert!(!reactor.secure_input);
+    assert!(apps.requests().is_empty());
+}
+
+#[test]
+fn menu_bar_owner_is_tracked_and_queryable() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(reactor.handle_menu_bar_owner_query().is_none());
+
+    reactor.handle_event(Event::MenuBarOwnerChanged(Some(1)));
+    assert_eq!(reactor.menu_bar_owner, Some(1));
+    let owner = reactor.handle_menu_bar_owner_query();
+    assert_eq!(owner.map(|owner| owner.pid), Some(1));
+    assert!(apps.requests().is_empty());
+
+    reactor.handle_event(Event::MenuBarOwnerChanged(None));
+    assert!(reactor.handle_menu_bar_owner_query().is_none());
+}
+
+#[test]
+fn modifier_drag_resizes_from_the_corner_nearest_the_pointer() {
+    let drag = |mode, start| ModifierDrag {
+        window: WindowId::new(1, 1),
+        mode,
+        start,
+        start_frame: CGRect::new(CGPoint::new(100., 100.), CGSize::new(400., 300.)),
+    };
+
+    let moved = drag(ModifierDragMode::Move, CGPoint::new(150., 150.));
+    assert_eq!(
+        moved.frame_at(CGPoint::new(200., 120.)),
+        CGRect::new(CGPoint::new(150., 70.), CGSize::new(400., 300.))
+    );
+
+    let bottom_right = drag(ModifierDragMode::Resize, CGPoint::new(450., 350.));
+    assert_eq!(
+        bottom_right.frame_at(CGPoint::new(500., 400.)),
+        CGRect::new(CGPoint::new(100., 100.), CGSize::new(450., 350.))
+    );
+
+    let top_left = drag(ModifierDragMode::Resize, CGPoint::new(150., 150.));
+    assert_eq!(
+        top_left.frame_at(CGPoint::new(100., 200.)),
+        CGRect::new(CGPoint::new(50., 150.), CGSize::new(450., 250.))
+    );
+    assert_eq!(
+        top_left.frame_at(CGPoint::new(1000., 1000.)).size,
+        CGSize::new(64., 64.)
+    );
+}
+
+#[test]
+fn settle_waiters_are_released_when_the_grace_period_runs_out() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    // A window that never reports the frame it was sent.
+    let target = CGRect::new(CGPoint::new(0., 0.), CGSize::new(500., 500.));
+    let txid = TransactionId::default().next();
+    reactor.transaction_manager.store_txid(WindowServerId::new(7), txid, target);
+    reactor.animation_manager.frames_sent();
+
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    AnimationManager::wait_for_settle(&mut reactor, tx);
+    AnimationManager::notify_settled(&mut reactor);
+    assert!(rx.try_recv().is_err());
+
+    // No event arrives; the reactor loop wakes up on its own once the grace is over.
+    let delay = AnimationManager::settle_check_delay(&reactor).expect("a wake-up is scheduled");
+    AnimationManager::notify_settled_at(&mut reactor, Instant::now() + delay);
+    let state = rx.try_recv().expect("the waiter is released");
+    assert!(!state.in_flight);
+    assert_eq!(state.pending_windows, 1);
+    assert_eq!(AnimationManager::settle_check_delay(&reactor), None);
+}
+
+/// Mode changes and pause switches sent to the IPC bridge, in order.
+fn presentation_messages(rx: &mut crate::actor::broadcast::BroadcastReceiver) -> Vec<String> {
+    use crate::actor::broadcast::Message;
+    let mut messages = Vec::new();
+    while let Ok((_, message)) = rx.try_recv() {
+        match message {
+            Message::Event(BroadcastEvent::ModeChanged { active, .. }) => {
+                messages.push(format!("mode_changed {active}"))
+            }
+            Message::SetPaused(paused) => messages.push(format!("paused {paused}")),
+            _ => {}
+        }
+    }
+    messages
+}
+
+#[test]
+fn presentation_mode_round_trip_quiets_rift_and_pauses_broadcasts_in_order() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (broadcast_tx, mut broadcast_rx) = crate::actor::broadcast::channel();
+    reactor.communication_manager.event_broadcaster = broadcast_tx;
+    let mut config = reactor.config.clone();
+    config.settings.animate = true;
+    config.settings.ui.stack_line.enabled = true;
+    config.settings.layout.avoid_notification_banners = true;
+    CommandEventHandler::handle_config_updated(&mut reactor, config);
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    let previous = reactor.layout_manager.layout_engine.active_layout_mode_at(space);
+    assert_ne!(previous, LayoutMode::Stack);
+    presentation_messages(&mut broadcast_rx);
+
+    let toggle = || {
+        Event::Command(Command::Reactor(ReactorCommand::TogglePresentationMode {
+            monocle: true,
+        }))
+    };
+    reactor.handle_event(toggle());
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(reactor.presentation_manager.is_active());
+    assert!(!reactor.config.settings.animate);
+    assert!(!reactor.config.settings.ui.stack_line.enabled);
+    assert!(!reactor.config.settings.layout.avoid_notification_banners);
+    assert_eq!(
+        WindowStyleManager::desired_style(&reactor, WindowId::new(1, 1)),
+        WindowStyle::default()
+    );
+    assert_eq!(
+        reactor.layout_manager.layout_engine.active_layout_mode_at(space),
+        LayoutMode::Stack
+    );
+    // The mode change goes out before anything is held back.
+    assert_eq!(presentation_messages(&mut broadcast_rx), [
+        "mode_changed true",
+        "paused true"
+    ]);
+
+    reactor.handle_event(toggle());
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(!reactor.presentation_manager.is_active());
+    assert!(reactor.config.settings.animate);
+    assert!(reactor.config.settings.ui.stack_line.enabled);
+    assert!(reactor.config.settings.layout.avoid_notification_banners);
+    assert_eq!(
+        reactor.layout_manager.layout_engine.active_layout_mode_at(space),
+        previous
+    );
+    assert_eq!(presentation_messages(&mut broadcast_rx), [
+        "paused false",
+        "mode_changed false"
+    ]);
+}
+
+#[test]
+fn leaving_presentation_mode_restores_config_reloaded_while_presenting() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let toggle = || {
+        Event::Command(Command::Reactor(ReactorCommand::TogglePresentationMode {
+            monocle: false,
+        }))
+    };
+    let mut config = reactor.config.clone();
+    config.settings.animate = true;
+    CommandEventHandler::handle_config_updated(&mut reactor, config);
+    reactor.handle_event(toggle());
+    assert!(!reactor.config.settings.animate);
+
+    // A reload while presenting replaces the saved config but stays quiet.
+    let mut reloaded = reactor.presentation_manager.saved_config.clone().unwrap();
+    reloaded.settings.focus_follows_mouse = !reloaded.settings.focus_follows_mouse;
+    let focus_follows_mouse = reloaded.settings.focus_follows_mouse;
+    CommandEventHandler::handle_config_updated(&mut reactor, reloaded);
+    assert!(!reactor.config.settings.animate);
+    assert_eq!(reactor.config.settings.focus_follows_mouse, focus_follows_mouse);
+
+    reactor.handle_event(toggle());
+    assert!(reactor.presentation_manager.saved_config.is_none());
+    assert!(reactor.config.settings.animate);
+    assert_eq!(reactor.config.settings.focus_follows_mouse, focus_follows_mouse);
+}
+
+#[test]
+fn kill_switch_leaves_the_presentation_snapshot_alone_but_stays_quiet_after_it() {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let mut config = reactor.config.clone();
+    config.settings.animate = true;
+    config.settings.focus_follows_mouse = true;
+    CommandEventHandler::handle_config_updated(&mut reactor, config);
+    let toggle = || {
+        Event::Command(Command::Reactor(ReactorCommand::TogglePresentationMode {
+            monocle: false,
+        }))
+    };
+    reactor.handle_event(toggle());
+
+    reactor.handle_event(Event::KillSwitch);
+    assert!(!reactor.config.settings.focus_follows_mouse);
+    let saved = reactor.presentation_manager.saved_config.as_ref().unwrap();
+    assert!(saved.settings.animate);
+    assert!(saved.settings.focus_follows_mouse);
+
+    reactor.handle_event(toggle());
+    assert!(!reactor.config.settings.animate);
+    assert!(!reactor.config.settings.focus_follows_mouse);
+}
+
+fn project_test_reactor() -> (
+    Reactor,
+    crate::actor::Receiver<crate::actor::wm_controller::WmEvent>,
+) {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let (wm_tx, wm_rx) = actor::channel();
+    reactor.communication_manager.wm_sender = Some(wm_tx);
+    let mut config = reactor.config.clone();
+    config.virtual_workspaces.projects = vec![crate::common::config::ProjectDefinition {
+        name: "web".into(),
+        layout: Some(LayoutMode::Bsp),
+        apps: vec![ExecCmd::Array(vec![
+            "open".into(),
+            "-a".into(),
+            "Safari".into(),
+        ])],
+    }];
+    CommandEventHandler::handle_config_updated(&mut reactor, config);
+    (reactor, wm_rx)
+}
+
+#[test]
+fn project_open_switches_to_its_workspace_and_launches_apps_there() {
+    use crate::actor::wm_controller::{WmCmd, WmCommand, WmEvent};
+    let mut apps = Apps::new();
+    let (mut reactor, mut wm_rx) = project_test_reactor();
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(1)));
+    apps.simulate_until_quiet(&mut reactor);
+    while wm_rx.try_recv().is_ok() {}
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::ProjectOpen {
+        name: "web".into(),
+    })));
+    apps.simulate_until_quiet(&mut reactor);
+    let engine = &mut reactor.layout_manager.layout_engine;
+    let index = engine.active_workspace_idx(space).unwrap() as usize;
+    let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+    assert_eq!(workspaces[index].1, "web");
+    assert_eq!(engine.active_layout_mode_at(space), LayoutMode::Bsp);
+
+    let mut launched = Vec::new();
+    while let Ok((_, event)) = wm_rx.try_recv() {
+        if let WmEvent::Command(WmCommand::Wm(WmCmd::Exec(ExecCmd::Placed(placed)))) = event {
+            launched.push(placed);
+        }
+    }
+    assert_eq!(launched.len(), 1);
+    assert_eq!(launched[0].workspace, Some(WorkspaceSelector::Index(index)));
+    assert_eq!(
+        *launched[0].command,
+        ExecCmd::Array(vec!["open".into(), "-a".into(), "Safari".into()])
+    );
+    assert!(!launched[0].floating);
+}
+
+#[test]
+fn project_close_asks_the_windows_of_the_named_workspace_to_close() {
+    let mut apps = Apps::new();
+    let (mut reactor, _wm_rx) = project_test_reactor();
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    let engine = &mut reactor.layout_manager.layout_engine;
+    let first = engine.virtual_workspace_manager_mut().list_workspaces(space)[0].1.clone();
+
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::ProjectOpen {
+        name: "web".into(),
+    })));
+    apps.simulate_until_quiet(&mut reactor);
+    let close = |name: Option<&str>| {
+        Event::Command(Command::Reactor(ReactorCommand::ProjectClose {
+            name: name.map(str::to_owned),
+        }))
+    };
+    let closed = |apps: &mut Apps| -> Vec<WindowId> {
+        let requests = apps.requests();
+        let mut closed: Vec<_> = requests
+            .into_iter()
+            .filter_map(|request| match request {
+                Request::CloseWindow(wid) => Some(wid),
+                _ => None,
+            })
+            .collect();
+        closed.sort();
+        closed
+    };
+
+    // The active workspace is the project's, which has no windows yet.
+    reactor.handle_event(close(None));
+    assert!(closed(&mut apps).is_empty());
+    reactor.handle_event(close(Some("no such workspace")));
+    assert!(closed(&mut apps).is_empty());
+    reactor.handle_event(close(Some(&first)));
+    assert_eq!(closed(&mut apps), [WindowId::new(1, 1), WindowId::new(1, 2)]);
+}
+
+/// Two displays side by side, with app 1's three windows tiled on the left one
+/// and its first window focused.
+fn throw_test_reactor(apps: &mut Apps) -> Reactor {
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let left = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    let right = CGRect::new(CGPoint::new(1000., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(
+        vec![left, right],
+        vec![Some(SpaceId::new(1)), Some(SpaceId::new(2))],
+        vec![],
+    ));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(3),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor
+}
+
+fn throw(target: ThrowTarget) -> Event {
+    Event::Command(Command::Reactor(ReactorCommand::Throw { target, follow: false }))
+}
+
+#[test]
+fn throw_to_an_edge_moves_a_tiled_window_to_the_end_of_its_layout() {
+    let mut apps = Apps::new();
+    let mut reactor = throw_test_reactor(&mut apps);
+    let thrown = WindowId::new(1, 1);
+    let x = |apps: &Apps, idx| apps.windows[&WindowId::new(1, idx)].frame.origin.x;
+    assert!(x(&apps, 1) < x(&apps, 2));
+
+    reactor.handle_event(throw(ThrowTarget::Edge(Direction::Right)));
+    apps.simulate_until_quiet(&mut reactor);
+    assert!(x(&apps, 1) > x(&apps, 2));
+    assert!(x(&apps, 1) > x(&apps, 3));
+    assert_eq!(reactor.main_window(), Some(thrown));
+}
+
+#[test]
+fn throw_to_a_display_moves_the_window_onto_that_displays_workspace() {
+    let mut apps = Apps::new();
+    let mut reactor = throw_test_reactor(&mut apps);
+    let thrown = WindowId::new(1, 1);
+
+    reactor.handle_event(throw(ThrowTarget::Display(DisplaySelector::Index(1))));
+    apps.simulate_until_quiet(&mut reactor);
+    let engine = &mut reactor.layout_manager.layout_engine;
+    assert_eq!(engine.workspace_windows_at(SpaceId::new(2), None), [thrown]);
+    assert!(!engine.workspace_windows_at(SpaceId::new(1), None).contains(&thrown));
+    assert!(apps.windows[&thrown].frame.origin.x >= 1000.);
+}
+
+#[test]
+fn throw_to_a_workspace_finds_workspaces_added_at_runtime_by_name() {
+    let mut apps = Apps::new();
+    let mut reactor = throw_test_reactor(&mut apps);
+    let space = SpaceId::new(1);
+    let thrown = WindowId::new(1, 1);
+    let engine = &mut reactor.layout_manager.layout_engine;
+    assert!(
+        !reactor
+            .config
+            .virtual_workspaces
+            .workspace_names
+            .contains(&"scratch".to_owned())
+    );
+    let index = engine.named_workspace_index(space, "scratch").unwrap();
+
+    let selector = WorkspaceSelector::Name("scratch".into());
+    reactor.handle_event(throw(ThrowTarget::Workspace(selector)));
+    apps.simulate_until_quiet(&mut reactor);
+    let engine = &mut reactor.layout_manager.layout_engine;
+    assert_eq!(engine.workspace_windows_at(space, Some(index)), [thrown]);
+    assert!(!engine.workspace_windows_at(space, None).contains(&thrown));
+
+    // Unknown names leave the windows where they are.
+    let selector = WorkspaceSelector::Name("nowhere".into());
+    reactor.handle_event(throw(ThrowTarget::Workspace(selector)));
+    apps.simulate_until_quiet(&mut reactor);
+    let engine = &mut reactor.layout_manager.layout_engine;
+    assert_eq!(engine.workspace_windows_at(space, Some(index)), [thrown]);
+}
+
+#[test]
+fn pointer_commands_are_forwarded_to_the_wm_controller() {
+    use crate::actor::wm_controller::{WmCmd, WmCommand, WmEvent};
+    let (mut reactor, mut wm_rx) = project_test_reactor();
+    while wm_rx.try_recv().is_ok() {}
+
+    for command in [
+        ReactorCommand::BanishPointer,
+        ReactorCommand::TogglePointerHiding,
+        ReactorCommand::BanishPointer,
+    ] {
+        reactor.handle_event(Event::Command(Command::Reactor(command)));
+    }
+    let mut forwarded = Vec::new();
+    while let Ok((_, event)) = wm_rx.try_recv() {
+        if let WmEvent::Command(WmCommand::Wm(cmd)) = event {
+            forwarded.push(cmd);
+        }
+    }
+    assert_eq!(forwarded, [
+        WmCmd::BanishPointer,
+        WmCmd::TogglePointerHiding,
+        WmCmd::BanishPointer
+    ]);
+
+    // Without a wm controller the commands are dropped rather than panicking.
+    reactor.communication_manager.wm_sender = None;
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::BanishPointer)));
+}
+
+/// Activates an app whose only window sits on a workspace that isn't shown,
+/// returning the reactor and the active workspace index afterwards.
+fn activate_on_hidden_workspace(policy: HiddenWorkspaceActivation) -> (Reactor, u64) {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.hidden_workspace_activation = policy;
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        make_windows(1),
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+    reactor.handle_event(Event::Command(Command::Layout(
+        LayoutCommand::SwitchToWorkspace(1),
+    )));
+    apps.simulate_until_quiet(&mut reactor);
+    assert_eq!(
+        reactor.layout_manager.layout_engine.active_workspace_idx(space),
+        Some(1)
+    );
+
+    reactor.apply_hidden_workspace_activation(1, policy);
+    apps.simulate_until_quiet(&mut reactor);
+    let active = reactor.layout_manager.layout_engine.active_workspace_idx(space).unwrap();
+    (reactor, active)
+}
+
+#[test]
+fn hidden_workspace_activation_switches_pulls_or_ignores() {
+    let space = SpaceId::new(1);
+    let wid = WindowId::new(1, 1);
+    let window_is_shown = |reactor: &Reactor| {
+        let engine = &reactor.layout_manager.layout_engine;
+        engine.virtual_workspace_manager().workspace_for_window(space, wid)
+            == engine.active_workspace(space)
+    };
+
+    // Switching follows the window back to the first workspace.
+    let (reactor, active) = activate_on_hidden_workspace(HiddenWorkspaceActivation::Switch);
+    assert_eq!(active, 0);
+    assert!(window_is_shown(&reactor));
+
+    // Pulling keeps the second workspace and brings the window over.
+    let (reactor, active) = activate_on_hidden_workspace(HiddenWorkspaceActivation::Pull);
+    assert_eq!(active, 1);
+    assert!(window_is_shown(&reactor));
+
+    let (reactor, active) = activate_on_hidden_workspace(HiddenWorkspaceActivation::Ignore);
+    assert_eq!(active, 1);
+    assert!(!window_is_shown(&reactor));
+}
+
+/// Switches away from a workspace holding two windows and back, returning
+/// the windows' frames while hidden and whether rift ordered them out.
+fn hide_workspace_windows(method: WorkspaceHideMethod) -> (Vec<CGRect>, Vec<CGRect>, Vec<bool>) {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    reactor.config.settings.workspace_hide_method = method;
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    reactor.handle_events(apps.make_app(1, make_windows(2)));
+    apps.simulate_until_quiet(&mut reactor);
+    let wids = [WindowId::new(1, 1), WindowId::new(1, 2)];
+    let frames = |apps: &Apps| wids.map(|wid| apps.windows[&wid].frame).to_vec();
+    let shown = frames(&apps);
+
+    let switch = |reactor: &mut Reactor, apps: &mut Apps, index| {
+        reactor.handle_event(Event::Command(Command::Layout(
+            LayoutCommand::SwitchToWorkspace(index),
+        )));
+        apps.simulate_until_quiet(reactor);
+    };
+    switch(&mut reactor, &mut apps, 1);
+    let hidden = frames(&apps);
+    let ordered_out = wids
+        .iter()
+        .map(|&wid| reactor.workspace_hide_manager.is_ordered_out(wid))
+        .collect();
+
+    switch(&mut reactor, &mut apps, 0);
+    assert_eq!(frames(&apps), shown);
+    assert!(wids.iter().all(|&wid| !reactor.workspace_hide_manager.is_ordered_out(wid)));
+    (shown, hidden, ordered_out)
+}
+
+#[test]
+fn order_out_hides_workspace_windows_without_moving_them() {
+    let (shown, hidden, ordered_out) = hide_workspace_windows(WorkspaceHideMethod::OrderOut);
+    assert_eq!(hidden, shown);
+    assert_eq!(ordered_out, [true, true]);
+
+    let (shown, hidden, ordered_out) = hide_workspace_windows(WorkspaceHideMethod::Move);
+    assert!(hidden.iter().zip(&shown).all(|(hidden, shown)| hidden != shown));
+    assert_eq!(ordered_out, [false, false]);
+}
+
+/// The window the last raise request sent asks to focus, if any.
+fn last_focus_request(
+    raise_manager_rx: &mut actor::Receiver<raise_manager::Event>,
+) -> Option<WindowId> {
+    let mut focus = None;
+    while let Ok((_, msg)) = raise_manager_rx.try_recv() {
+        if let raise_manager::Event::RaiseRequest(RaiseRequest { focus_window, .. }) = msg {
+            focus = focus_window.map(|(wid, _)| wid);
+        }
+    }
+    focus
+}
+
+#[test]
+fn directional_focus_reaches_a_float_by_its_frame() {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    let full_screen = CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.));
+    reactor.handle_event(screen_params_event(vec![full_screen], vec![Some(space)], vec![]));
+    // Windows 1 and 2 tile the left and right halves; the float straddles the
+    // middle, nearer the right half's center than window 1 is.
+    let mut windows = make_windows(3);
+    windows[2].frame = CGRect::new(CGPoint::new(400., 400.), CGSize::new(200., 200.));
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        windows,
+        Some(WindowId::new(1, 2)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    reactor
+        .layout_manager
+        .layout_engine
+        .float_window(Some(space), WindowId::new(1, 3));
+    apps.simulate_until_quiet(&mut reactor);
+    let (raise_manager_tx, mut raise_manager_rx) = actor::channel();
+    reactor.communication_manager.raise_manager_tx = raise_manager_tx;
+    let move_focus = |reactor: &mut Reactor, direction| {
+        reactor.handle_event(Event::Command(Command::Layout(LayoutCommand::MoveFocus(
+            direction,
+        ))));
+    };
+
+    move_focus(&mut reactor, Direction::Left);
+    assert_eq!(
+        last_focus_request(&mut raise_manager_rx),
+        Some(WindowId::new(1, 3))
+    );
+
+    // Walking the tree alone skips the float for the tiled window behind it.
+    reactor.config.settings.directional_focus = DirectionalFocus::Tree;
+    move_focus(&mut reactor, Direction::Left);
+    assert_eq!(
+        last_focus_request(&mut raise_manager_rx),
+        Some(WindowId::new(1, 1))
+    );
+}
+
+/// One app with four overlapping windows and a fifth off to the side, all
+/// floating except the last, with the first focused.
+fn float_stack_test_reactor() -> (Reactor, Apps, actor::Receiver<raise_manager::Event>) {
+    let mut apps = Apps::new();
+    let mut reactor = Reactor::new_for_test(LayoutEngine::new(
+        &crate::common::config::VirtualWorkspaceSettings::default(),
+        &crate::common::config::LayoutSettings::default(),
+        None,
+    ));
+    let space = SpaceId::new(1);
+    reactor.handle_event(screen_params_event(
+        vec![CGRect::new(CGPoint::new(0., 0.), CGSize::new(1000., 1000.))],
+        vec![Some(space)],
+        vec![],
+    ));
+    let mut windows = make_windows(5);
+    for (i, window) in windows.iter_mut().enumerate() {
+        window.frame = CGRect::new(
+            CGPoint::new(100. + 20. * i as f64, 100.),
+            CGSize::new(300., 300.),
+        );
+    }
+    windows[3].frame.origin = CGPoint::new(600., 600.);
+    reactor.handle_events(apps.make_app_with_opts(
+        1,
+        windows,
+        Some(WindowId::new(1, 1)),
+        true,
+        true,
+    ));
+    reactor.handle_event(Event::ApplicationGloballyActivated(1));
+    for idx in 1..=4 {
+        reactor
+            .layout_manager
+            .layout_engine
+            .float_window(Some(space), WindowId::new(1, idx));
+    }
+    apps.simulate_until_quiet(&mut reactor);
+
+    let (raise_manager_tx, raise_manager_rx) = actor::channel();
+    reactor.communication_manager.raise_manager_tx = raise_manager_tx;
+    (reactor, apps, raise_manager_rx)
+}
+
+/// The windows and stacking order of the last raise request sent, if any.
+fn raised_floats(
+    raise_manager_rx: &mut actor::Receiver<raise_manager::Event>,
+) -> Option<(Vec<Vec<WindowId>>, Vec<WindowServerId>)> {
+    let mut raised = None;
+    while let Ok((_, msg)) = raise_manager_rx.try_recv() {
+        let raise_manager::Event::RaiseRequest(RaiseRequest {
+            raise_windows,
+            focus_window,
+            window_order,
+            ..
+        }) = msg
+        else {
+            continue;
+        };
+        assert_eq!(
+            focus_window.map(|(wid, _)| wid),
+            raise_windows.last().map(|w| w[0])
+        );
+        raised = Some((raise_windows, window_order));
+    }
+    raised
+}
+
+#[test]
+fn float_lower_and_cycle_restack_the_floats_of_the_space() {
+    let (mut reactor, _apps, mut raise_manager_rx) = float_stack_test_reactor();
+    let wid = |idx| WindowId::new(1, idx);
+    let wsid = WindowServerId::new;
+    // Front to back; the tiled window 5 sits in between and is left out.
+    crate::sys::window_server::set_test_stacking_order(vec![1, 3, 5, 2, 4]);
+
+    // Lowering the focused float raises every other float in its current
+    // order, which leaves the focused one at the bottom.
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::FloatLower)));
+    assert_eq!(
+        raised_floats(&mut raise_manager_rx),
+        Some((vec![vec![wid(4)], vec![wid(2)], vec![wid(3)]], vec![
+            wsid(4),
+            wsid(2),
+            wsid(3)
+        ],))
+    );
+
+    // Cycling brings up the bottom float that overlaps the focused one; 4 is
+    // further down but does not overlap it.
+    reactor.handle_event(Event::Command(Command::Reactor(ReactorCommand::FloatCycle)));
+    assert_eq!(
+        raised_floats(&mut raise_manager_rx),
+        Some((vec![vec![wid(2)]], vec![wsid(2)]))
+    );
+}
+
+#[test]
+fn float_lower_and_cycle_ignore_a_focused_tiled_window() {
+    let (mut reactor, mut apps, mut raise_manager_rx) = float_stack_test_reactor();
+    crate::sys::window_server::set_test_stacking_order(vec![5, 1, 3, 2, 4]);
+    reactor.handle_event(Event::ApplicationMainWindowChanged(
+        1,
+        Some(WindowId::new(1, 5)),
+        Quiet::No,
+    ));
+    apps.simulate_until_quiet(&mut reactor);
+    while raise_manager_rx.try_recv().is_ok() {}
+    assert_eq!(reactor.main_window(), Some(WindowId::new(1, 5)));
+
+    for command in [ReactorCommand::FloatLower, ReactorCommand::FloatCycle] {
+        reactor.handle_event(Event::Command(Command::Reactor(command)));
+        assert_eq!(raised_floats(&mut raise_manager_rx), None);
+    }
+}
@@ -56,6 +56,9 @@ impl TransactionManager {
         self.store.last_txid(&wsid)
     }
 
+    /// Number of windows still expected to report a frame rift asked for.
+    pub fn pending_count(&self) -> usize { self.store.pending_count() }
+
     /// Gets the target frame for a window's transaction, if it exists.
     pub fn get_target_frame(&self, wsid: WindowServerId) -> Option<CGRect> {
         self.store.get(&wsid)?.target
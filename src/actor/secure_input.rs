@@ -0,0 +1,34 @@
+//! Notices when macOS secure input turns on or off.
+//!
+//! Password fields, some terminals and VPN clients enable secure event input,
+//! and while it is on no event tap receives keystrokes. Nothing tells the tap
+//! this is happening, so hotkeys just look dead. Polling the flag lets the WM
+//! controller say why, and put the tap back once the flag clears.
+
+use std::time::Duration;
+
+use crate::actor::wm_controller::{self, WmEvent};
+use crate::sys::event_tap::secure_input_enabled;
+use crate::sys::timer::Timer;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct SecureInputMonitor {
+    wm_sender: wm_controller::Sender,
+}
+
+impl SecureInputMonitor {
+    pub fn new(wm_sender: wm_controller::Sender) -> Self { Self { wm_sender } }
+
+    pub async fn run(self) {
+        let mut enabled = false;
+        let mut timer = Timer::repeating(Duration::ZERO, POLL_INTERVAL);
+        while timer.next().await.is_some() {
+            let now = secure_input_enabled();
+            if now != enabled {
+                enabled = now;
+                self.wm_sender.send(WmEvent::SecureInputChanged(enabled));
+            }
+        }
+    }
+}
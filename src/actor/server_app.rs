@@ -0,0 +1,245 @@
+//! A stand-in for the app actor, used for apps whose accessibility interface
+//! is missing or unusable.
+//!
+//! Windows are listed, moved and raised through the window server alone. The
+//! app is never told about any of this and there is no way to resize its
+//! windows, so the frames reported back are whatever the windows ended up with.
+//! The window server usually refuses to move another app's windows too, in
+//! which case the windows stay put; they float by default for that reason.
+
+use std::num::NonZeroU32;
+use std::thread;
+
+use objc2::rc::Retained;
+use objc2_app_kit::{NSApplicationActivationOptions, NSRunningApplication};
+use objc2_core_foundation::CGPoint;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use crate::actor;
+use crate::actor::app::{AppInfo, AppThreadHandle, Quiet, Request, WindowId, WindowInfo, pid_t};
+use crate::actor::reactor::transaction_manager::TransactionId;
+use crate::actor::reactor::{self, Event, Requested};
+use crate::common::collections::HashMap;
+use crate::sys::app::NSRunningApplicationExt;
+use crate::sys::executor::Executor;
+use crate::sys::window_server::{self, WindowServerId, WindowServerInfo};
+
+pub fn spawn_server_app_thread(pid: pid_t, info: AppInfo, events_tx: reactor::Sender) {
+    thread::Builder::new()
+        .name(format!(
+            "{}({pid}, window server)",
+            info.bundle_id.as_deref().unwrap_or("")
+        ))
+        .spawn(move || server_app_thread_main(pid, info, events_tx))
+        .unwrap();
+}
+
+struct State {
+    pid: pid_t,
+    info: AppInfo,
+    running_app: Retained<NSRunningApplication>,
+    events_tx: reactor::Sender,
+    /// The last transaction applied to each window, echoed back with its frame.
+    windows: HashMap<WindowId, TransactionId>,
+    main_window: Option<WindowId>,
+    /// Set after the first refused move, which is logged once rather than on every layout.
+    move_refused: bool,
+}
+
+impl State {
+    async fn run(mut self, handle: AppThreadHandle, mut requests_rx: actor::Receiver<Request>) {
+        let window_server_info = window_server::app_windows_on_screen(self.pid);
+        let visible_windows = window_server_info.iter().map(|info| self.track(info)).collect();
+        self.send_event(Event::ApplicationLaunched {
+            pid: self.pid,
+            handle,
+            info: self.info.clone(),
+            is_frontmost: self.running_app.isActive(),
+            main_window: None,
+            visible_windows,
+            window_server_info,
+        });
+
+        while let Some((span, request)) = requests_rx.recv().await {
+            let _guard = span.enter();
+            debug!(?self.info.bundle_id, ?self.pid, ?request, "Got request");
+            if self.handle_request(request) {
+                break;
+            }
+        }
+    }
+
+    /// Returns true once the thread should exit.
+    fn handle_request(&mut self, request: Request) -> bool {
+        match request {
+            Request::Terminate => {
+                self.send_event(Event::ApplicationThreadTerminated(self.pid));
+                return true;
+            }
+            Request::GetVisibleWindows | Request::RefreshObservers => self.report_windows(),
+            Request::WindowMaybeDestroyed(wid) => {
+                if self.windows.contains_key(&wid) {
+                    self.report_windows();
+                }
+            }
+            Request::CloseWindow(wid) => {
+                debug!(?wid, "Closing a window needs accessibility; ignoring");
+            }
+            Request::SetWindowFrame(wid, frame, txid, _) => {
+                self.move_window(wid, frame.origin, txid)
+            }
+            Request::SetWindowPos(wid, origin, txid, _) => self.move_window(wid, origin, txid),
+            Request::SetBatchWindowFrame(frames, txid) => {
+                for (wid, frame) in frames {
+                    self.move_window(wid, frame.origin, txid);
+                }
+            }
+            Request::BeginWindowAnimation(_) => {}
+            Request::EndWindowAnimation(wid) => {
+                let txid = self.windows.get(&wid).copied();
+                self.report_frame(wid, txid.filter(|txid| *txid != TransactionId::default()));
+            }
+            Request::Raise(wids, token, sequence_id, quiet) => {
+                self.raise(&wids, &token, sequence_id, quiet)
+            }
+            Request::Activate(_) => {
+                #[allow(deprecated)]
+                let opts = NSApplicationActivationOptions::ActivateIgnoringOtherApps;
+                let _ = self.running_app.activateWithOptions(opts);
+            }
+        }
+        false
+    }
+
+    fn track(&mut self, server_info: &WindowServerInfo) -> (WindowId, WindowInfo) {
+        let wid = self.window_id(server_info.id);
+        self.windows.entry(wid).or_default();
+        let info = WindowInfo {
+            is_standard: true,
+            is_root: window_server::window_parent(server_info.id).is_none(),
+            is_minimized: false,
+            is_resizable: false,
+            title: String::new(),
+            frame: server_info.frame,
+            min_size: None,
+            max_size: None,
+            sys_id: Some(server_info.id),
+            bundle_id: self.info.bundle_id.clone(),
+            path: None,
+            ax_role: None,
+            ax_subrole: None,
+            ax_identifier: None,
+        };
+        (wid, info)
+    }
+
+    fn report_windows(&mut self) {
+        let on_screen = window_server::app_windows_on_screen(self.pid);
+        self.windows
+            .retain(|wid, _| on_screen.iter().any(|info| info.id.as_u32() == wid.idx.get()));
+        let mut new = Vec::with_capacity(on_screen.len());
+        let mut known_visible = Vec::with_capacity(on_screen.len());
+        for server_info in &on_screen {
+            let wid = self.window_id(server_info.id);
+            if self.windows.contains_key(&wid) {
+                known_visible.push(wid);
+            }
+            new.push(self.track(server_info));
+        }
+        self.send_event(Event::WindowsDiscovered {
+            pid: self.pid,
+            new,
+            known_visible,
+        });
+    }
+
+    fn move_window(&mut self, wid: WindowId, origin: CGPoint, txid: TransactionId) {
+        let Some(last_txid) = self.windows.get_mut(&wid) else {
+            return;
+        };
+        *last_txid = txid;
+        if let Err(err) = window_server::move_window(server_id(wid), origin) {
+            if self.move_refused {
+                debug!(?wid, ?err, "Failed to move window");
+            } else {
+                warn!(
+                    ?wid,
+                    ?err,
+                    "Window server refused to move window; it will stay put"
+                );
+                self.move_refused = true;
+            }
+        }
+        self.report_frame(wid, Some(txid));
+    }
+
+    fn report_frame(&self, wid: WindowId, txid: Option<TransactionId>) {
+        let Some(server_info) = window_server::get_window(server_id(wid)) else {
+            return;
+        };
+        self.send_event(Event::WindowFrameChanged(
+            wid,
+            server_info.frame,
+            txid,
+            Requested(true),
+            None,
+        ));
+    }
+
+    fn raise(
+        &mut self,
+        wids: &[WindowId],
+        token: &CancellationToken,
+        sequence_id: u64,
+        quiet: Quiet,
+    ) {
+        let Some(&last) = wids.last() else {
+            return;
+        };
+        if token.is_cancelled() {
+            return;
+        }
+        if let Err(err) = window_server::make_key_window(self.pid, server_id(last)) {
+            warn!(?self.pid, ?err, "Failed to make window key");
+        }
+        // Making the window key already brings it forward when ordering other
+        // apps' windows is refused.
+        let server_ids: Vec<_> = wids.iter().map(|&wid| server_id(wid)).collect();
+        let _ = window_server::order_windows(&server_ids);
+        for &wid in wids {
+            self.send_event(Event::RaiseCompleted { window_id: wid, sequence_id });
+        }
+        if self.main_window.replace(last) != Some(last) {
+            self.send_event(Event::ApplicationMainWindowChanged(self.pid, Some(last), quiet));
+        }
+    }
+
+    fn window_id(&self, id: WindowServerId) -> WindowId {
+        let idx = NonZeroU32::new(id.as_u32()).expect("Window server id was 0");
+        WindowId { pid: self.pid, idx }
+    }
+
+    fn send_event(&self, event: Event) { self.events_tx.send(event); }
+}
+
+/// Window ids are made from window server ids, the same as in the app actor.
+fn server_id(wid: WindowId) -> WindowServerId { WindowServerId::new(wid.idx.get()) }
+
+fn server_app_thread_main(pid: pid_t, info: AppInfo, events_tx: reactor::Sender) {
+    let Some(running_app) = NSRunningApplication::with_process_id(pid) else {
+        info!(?pid, "Making NSRunningApplication failed; exiting app thread");
+        return;
+    };
+    let (requests_tx, requests_rx) = actor::channel();
+    let state = State {
+        pid,
+        info,
+        running_app,
+        events_tx,
+        windows: HashMap::default(),
+        main_window: None,
+        move_refused: false,
+    };
+    Executor::run(state.run(AppThreadHandle::new(requests_tx), requests_rx));
+}
@@ -1,5 +1,6 @@
 use std::collections::hash_map::Entry;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use objc2::MainThreadMarker;
 use objc2_app_kit::NSCursor;
@@ -10,10 +11,17 @@ use crate::actor::app::WindowId;
 use crate::actor::reactor::{Command, ReactorCommand};
 use crate::actor::{self, reactor};
 use crate::common::collections::HashMap;
-use crate::common::config::{Config, HorizontalPlacement, VerticalPlacement};
+use crate::common::config::{Config, HorizontalPlacement, StackLinePlacement, VerticalPlacement};
 use crate::layout_engine::LayoutKind;
 use crate::model::tree::NodeId;
+use crate::sys::appearance::{Appearance, current_appearance};
 use crate::sys::screen::{CoordinateConverter, SpaceId};
+use crate::sys::timer::Timer;
+use crate::sys::window_server;
+use crate::ui::common::WorkspaceScroll;
+use crate::ui::debug_overlay::{DebugOverlayInfo, DebugOverlayWindow};
+use crate::ui::overlay_host::CreationBackoff;
+use crate::ui::selection_outline::SelectionOutlineWindow;
 use crate::ui::stack_line::{GroupDisplayData, GroupIndicatorWindow, GroupKind, IndicatorConfig};
 
 #[derive(Debug, Clone)]
@@ -25,6 +33,7 @@ pub struct GroupInfo {
     pub total_count: usize,
     pub selected_index: usize,
     pub window_ids: Vec<WindowId>,
+    pub placement: StackLinePlacement,
 }
 
 #[derive(Debug)]
@@ -37,21 +46,49 @@ pub enum Event {
     },
     ScreenParametersChanged(CoordinateConverter),
     ConfigUpdated(Config),
+    AppearanceChanged(Appearance),
     MouseDown(CGPoint),
     MouseMoved(CGPoint),
+    /// A scroll at a screen point, with its `scrollingDeltaY`.
+    Scroll(CGPoint, f64),
+    /// Outline the selected container for a moment, or drop the outline when
+    /// the selection is a single window again.
+    SelectionOutline(Option<CGRect>),
+    /// Redraw the layout debug overlay, one entry per active space; an empty
+    /// list takes it down.
+    DebugOverlay(Vec<DebugOverlayInfo>),
+    /// Hide every indicator while native Mission Control is showing, and stop
+    /// answering clicks on them; `false` lets the next groups update bring them back.
+    Suspend(bool),
 }
 
+const SELECTION_OUTLINE_DURATION: Duration = Duration::from_millis(900);
+
+/// How many hidden indicator windows are kept around for groups that show up later.
+const MAX_SPARE_INDICATORS: usize = 8;
+
 pub struct StackLine {
     config: Config,
     rx: Receiver,
-    #[allow(dead_code)]
     mtm: MainThreadMarker,
     indicators: HashMap<NodeId, GroupIndicatorWindow>,
+    /// Ordered-out windows of groups that went away, reused before creating new ones.
+    spare_indicators: Vec<GroupIndicatorWindow>,
     #[allow(dead_code)]
     reactor_tx: reactor::Sender,
     coordinate_converter: CoordinateConverter,
     group_sigs_by_space: HashMap<SpaceId, Vec<GroupSig>>,
     cursor_over_indicator: bool,
+    /// Gates window creation after a failure; the next `GroupsUpdated` after
+    /// the backoff expires retries it.
+    indicator_backoff: CreationBackoff,
+    appearance: Appearance,
+    selection_outline: Option<SelectionOutlineWindow>,
+    selection_outline_until: Option<Instant>,
+    /// Windows of the layout debug overlay, reused in order across updates.
+    debug_overlays: Vec<DebugOverlayWindow>,
+    suspended: bool,
+    workspace_scroll: WorkspaceScroll,
 }
 
 pub type Sender = actor::Sender<Event>;
@@ -70,10 +107,18 @@ impl StackLine {
             rx,
             mtm,
             indicators: HashMap::default(),
+            spare_indicators: Vec::new(),
             reactor_tx,
             coordinate_converter,
             group_sigs_by_space: HashMap::default(),
             cursor_over_indicator: false,
+            indicator_backoff: CreationBackoff::default(),
+            appearance: current_appearance(mtm),
+            selection_outline: None,
+            selection_outline_until: None,
+            debug_overlays: Vec::new(),
+            suspended: false,
+            workspace_scroll: WorkspaceScroll::default(),
         }
     }
 
@@ -82,9 +127,22 @@ impl StackLine {
             tracing::debug!("stack line disabled at start; will listen for config changes");
         }
 
-        while let Some((span, event)) = self.rx.recv().await {
-            let _guard = span.enter();
-            self.handle_event(event);
+        let mut outline_timer = Timer::manual();
+        loop {
+            let hide_in = match self.selection_outline_until {
+                Some(until) => until.saturating_duration_since(Instant::now()),
+                None => Duration::MAX,
+            };
+            outline_timer.set_next_fire(hide_in);
+
+            tokio::select! {
+                maybe = self.rx.recv() => {
+                    let Some((span, event)) = maybe else { break };
+                    let _guard = span.enter();
+                    self.handle_event(event);
+                }
+                _ = outline_timer.next() => self.hide_selection_outline(),
+            }
         }
     }
 
@@ -96,9 +154,13 @@ impl StackLine {
             && !matches!(
                 event,
                 Event::ConfigUpdated(_)
+                    | Event::AppearanceChanged(_)
                     | Event::ScreenParametersChanged(_)
                     | Event::MouseDown(_)
                     | Event::MouseMoved(_)
+                    | Event::SelectionOutline(_)
+                    | Event::DebugOverlay(_)
+                    | Event::Suspend(_)
             )
         {
             return;
@@ -123,12 +185,99 @@ impl StackLine {
             Event::ConfigUpdated(config) => {
                 self.handle_config_updated(config);
             }
+            Event::AppearanceChanged(appearance) => {
+                self.appearance = appearance;
+                if self.is_enabled() {
+                    self.restyle_indicators();
+                }
+            }
             Event::MouseDown(point) => {
                 self.handle_mouse_down(point);
             }
             Event::MouseMoved(point) => {
                 self.handle_mouse_moved(point);
             }
+            Event::Scroll(point, delta_y) => self.handle_scroll(point, delta_y),
+            Event::SelectionOutline(Some(frame)) => self.show_selection_outline(frame),
+            Event::SelectionOutline(None) => self.hide_selection_outline(),
+            Event::DebugOverlay(spaces) => self.show_debug_overlays(&spaces),
+            Event::Suspend(suspended) => self.set_suspended(suspended),
+        }
+    }
+
+    fn set_suspended(&mut self, suspended: bool) {
+        self.suspended = suspended;
+        if !suspended {
+            return;
+        }
+        self.hide_selection_outline();
+        self.show_debug_overlays(&[]);
+        for indicator in self.indicators.values() {
+            if let Err(err) = indicator.set_visibility(true) {
+                tracing::warn!(?err, "failed to hide stack line indicator");
+            }
+        }
+    }
+
+    fn show_selection_outline(&mut self, frame: CGRect) {
+        if self.suspended {
+            return;
+        }
+        if self.selection_outline.is_none() {
+            let now = Instant::now();
+            if !self.indicator_backoff.ready(now) {
+                return;
+            }
+            match SelectionOutlineWindow::new(frame) {
+                Ok(outline) => {
+                    self.indicator_backoff.record_success();
+                    self.selection_outline = Some(outline);
+                }
+                Err(err) => {
+                    let delay = self.indicator_backoff.record_failure(now);
+                    tracing::warn!(?err, ?delay, "failed to create selection outline window");
+                    return;
+                }
+            }
+        }
+        let color = self.indicator_config().selected_color;
+        let Some(outline) = &self.selection_outline else { return };
+        if let Err(err) = outline.show(frame, color) {
+            tracing::warn!(?err, "failed to show selection outline");
+            return;
+        }
+        self.selection_outline_until = Some(Instant::now() + SELECTION_OUTLINE_DURATION);
+    }
+
+    fn hide_selection_outline(&mut self) {
+        self.selection_outline_until = None;
+        if let Some(outline) = &self.selection_outline
+            && let Err(err) = outline.hide()
+        {
+            tracing::warn!(?err, "failed to hide selection outline");
+        }
+    }
+
+    fn show_debug_overlays(&mut self, spaces: &[DebugOverlayInfo]) {
+        let spaces: &[DebugOverlayInfo] = if self.suspended { &[] } else { spaces };
+        for overlay in self.debug_overlays.iter().skip(spaces.len()) {
+            if let Err(err) = overlay.hide() {
+                tracing::warn!(?err, "failed to hide debug overlay");
+            }
+        }
+        for (idx, info) in spaces.iter().enumerate() {
+            if idx == self.debug_overlays.len() {
+                match DebugOverlayWindow::new(self.mtm, info.screen) {
+                    Ok(overlay) => self.debug_overlays.push(overlay),
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to create debug overlay window");
+                        return;
+                    }
+                }
+            }
+            if let Err(err) = self.debug_overlays[idx].show(info) {
+                tracing::warn!(?err, space = ?info.space_id, "failed to show debug overlay");
+            }
         }
     }
 
@@ -138,19 +287,23 @@ impl StackLine {
         space_id: SpaceId,
         groups: Vec<GroupInfo>,
         space_has_fullscreen: bool,
+    ) {
+        window_server::with_updates_disabled(|| {
+            self.sync_indicators(active_space_ids, space_id, groups, space_has_fullscreen)
+        });
+    }
+
+    fn sync_indicators(
+        &mut self,
+        active_space_ids: Vec<SpaceId>,
+        space_id: SpaceId,
+        groups: Vec<GroupInfo>,
+        space_has_fullscreen: bool,
     ) {
         let active: crate::common::collections::HashSet<SpaceId> =
             active_space_ids.iter().copied().collect();
 
-        self.indicators.retain(|_node_id, indicator| match indicator.space_id() {
-            Some(indicator_space_id) if !active.contains(&indicator_space_id) => {
-                if let Err(err) = indicator.clear() {
-                    tracing::warn!(?err, "failed to clear stack line indicator for inactive space");
-                }
-                false
-            }
-            _ => true,
-        });
+        self.release_indicators_where(|_, space| !active.contains(&space));
         self.group_sigs_by_space.retain(|sid, _| active.contains(sid));
 
         let sigs: Vec<GroupSig> = groups.iter().map(GroupSig::from_group_info).collect();
@@ -165,18 +318,8 @@ impl StackLine {
 
             let group_nodes: std::collections::HashSet<NodeId> =
                 groups.iter().map(|g| g.node_id).collect();
-            self.indicators.retain(|&node_id, indicator| match indicator.space_id() {
-                Some(indicator_space_id) if indicator_space_id == space_id => {
-                    if group_nodes.contains(&node_id) {
-                        true
-                    } else {
-                        if let Err(err) = indicator.clear() {
-                            tracing::warn!(?err, "failed to clear stack line indicator");
-                        }
-                        false
-                    }
-                }
-                _ => true,
+            self.release_indicators_where(|node_id, indicator_space_id| {
+                indicator_space_id == space_id && !group_nodes.contains(&node_id)
             });
 
             for group in groups {
@@ -188,7 +331,7 @@ impl StackLine {
 
         for indicator in self.indicators.values() {
             if indicator.space_id() == Some(space_id) {
-                if let Err(err) = indicator.set_visibility(space_has_fullscreen) {
+                if let Err(err) = indicator.set_visibility(space_has_fullscreen || self.suspended) {
                     tracing::warn!(?err, "failed to set stack line indicator visibility");
                 }
             }
@@ -215,27 +358,17 @@ impl StackLine {
                 }
             }
             self.indicators.clear();
+            self.spare_indicators.clear();
             self.group_sigs_by_space.clear();
         } else if new_enabled {
-            let new_config = self.indicator_config();
-            for (node_id, indicator) in &self.indicators {
-                if let Some(group_data) = indicator.group_data() {
-                    if let Err(err) = indicator.update(new_config, group_data) {
-                        tracing::warn!(
-                            ?err,
-                            ?node_id,
-                            "failed to update stack line indicator with new config"
-                        );
-                    }
-                }
-            }
+            self.restyle_indicators();
         }
 
         tracing::debug!("Updated stack line configuration");
     }
 
     fn handle_mouse_down(&mut self, screen_point: CGPoint) {
-        if !self.is_enabled() {
+        if !self.is_enabled() || self.suspended {
             return;
         }
 
@@ -260,9 +393,27 @@ impl StackLine {
         }
     }
 
+    fn handle_scroll(&mut self, screen_point: CGPoint, delta_y: f64) {
+        if self.suspended {
+            return;
+        }
+        let over_indicator = self.indicators.values().any(|indicator| {
+            let frame = indicator.frame();
+            let (mx, my) = hit_margins(frame, indicator.recommended_thickness());
+            point_in_hit_area(screen_point, frame, mx, my)
+        });
+        if !over_indicator {
+            return;
+        }
+        let settings = &self.config.settings.ui.stack_line.scroll_workspaces;
+        if let Some(command) = self.workspace_scroll.on_scroll(settings, delta_y, Instant::now()) {
+            self.reactor_tx.send(reactor::Event::Command(Command::Layout(command)));
+        }
+    }
+
     // this is very hacky but we don't use nswindow so we have to roll this ourselves
     fn handle_mouse_moved(&mut self, screen_point: CGPoint) {
-        let over_indicator = if self.is_enabled() {
+        let over_indicator = if self.is_enabled() && !self.suspended {
             self.indicators.values().any(|indicator| {
                 let frame = indicator.frame();
                 let (mx, my) = hit_margins(frame, indicator.recommended_thickness());
@@ -349,8 +500,7 @@ impl StackLine {
             group.frame,
             group_kind,
             config.bar_thickness,
-            config.horizontal_placement,
-            config.vertical_placement,
+            group.placement,
             config.spacing,
         );
 
@@ -364,16 +514,29 @@ impl StackLine {
             if let Err(err) = indicator.update(config, group_data.clone()) {
                 tracing::warn!(?err, "failed to update stack line indicator");
             }
+        } else if let Some(indicator) = self.spare_indicators.pop() {
+            if let Err(err) = indicator.set_frame(indicator_frame) {
+                tracing::warn!(?err, "failed to set stack line indicator frame");
+            }
+            indicator.set_space_id(group.space_id);
+            let indicator = self.attach_indicator(node_id, indicator, config, group_data.clone());
+            self.indicators.insert(node_id, indicator);
         } else {
+            let now = Instant::now();
+            if !self.indicator_backoff.ready(now) {
+                return;
+            }
             match GroupIndicatorWindow::new(indicator_frame, config) {
                 Ok(indicator) => {
+                    self.indicator_backoff.record_success();
                     indicator.set_space_id(group.space_id);
                     let indicator =
                         self.attach_indicator(node_id, indicator, config, group_data.clone());
                     self.indicators.insert(node_id, indicator);
                 }
                 Err(err) => {
-                    tracing::warn!(?err, "failed to create stack line indicator window");
+                    let delay = self.indicator_backoff.record_failure(now);
+                    tracing::warn!(?err, ?delay, "failed to create stack line indicator window");
                     return;
                 }
             }
@@ -386,6 +549,33 @@ impl StackLine {
         );
     }
 
+    /// Releases the indicators whose node and space match `pred`.
+    fn release_indicators_where(&mut self, pred: impl Fn(NodeId, SpaceId) -> bool) {
+        let stale: Vec<NodeId> = self
+            .indicators
+            .iter()
+            .filter(|&(&node_id, indicator)| {
+                indicator.space_id().is_some_and(|space_id| pred(node_id, space_id))
+            })
+            .map(|(&node_id, _)| node_id)
+            .collect();
+        for node_id in stale {
+            if let Some(indicator) = self.indicators.remove(&node_id) {
+                self.release_indicator(indicator);
+            }
+        }
+    }
+
+    /// Orders `indicator` out and keeps it for reuse, unless the pool is full.
+    fn release_indicator(&mut self, indicator: GroupIndicatorWindow) {
+        if let Err(err) = indicator.clear() {
+            tracing::warn!(?err, "failed to clear stack line indicator");
+        }
+        if self.spare_indicators.len() < MAX_SPARE_INDICATORS {
+            self.spare_indicators.push(indicator);
+        }
+    }
+
     fn attach_indicator(
         &mut self,
         node_id: NodeId,
@@ -410,32 +600,60 @@ impl StackLine {
     }
 
     // TODO: We should just pass in the coordinates from the layout calculation.
+    /// Outside a group the indicator fills the strip the layout left at the
+    /// chosen edge, `spacing` further out; inside, it is `spacing` in from it.
     fn calculate_indicator_frame(
         group_frame: CGRect,
         group_kind: GroupKind,
         thickness: f64,
-        _horizontal_placement: HorizontalPlacement,
-        _vertical_placement: VerticalPlacement,
+        placement: StackLinePlacement,
         spacing: f64,
     ) -> CGRect {
         let min_size = thickness * 2.0;
         let adjusted_width = group_frame.size.width.max(min_size);
         let adjusted_height = group_frame.size.height.max(min_size);
+        let outward = if placement.inside { -spacing } else { spacing };
+        let (min_x, min_y) = (group_frame.origin.x, group_frame.origin.y);
+        let max_x = min_x + group_frame.size.width;
+        let max_y = min_y + group_frame.size.height;
 
         match group_kind {
-            GroupKind::Horizontal => CGRect::new(
-                CGPoint::new(group_frame.origin.x, group_frame.origin.y - spacing),
-                CGSize::new(adjusted_width, thickness),
-            ),
-            GroupKind::Vertical => CGRect::new(
-                CGPoint::new(group_frame.origin.x - spacing, group_frame.origin.y),
-                CGSize::new(thickness, adjusted_height),
-            ),
+            GroupKind::Horizontal => {
+                let y = match placement.horizontal {
+                    HorizontalPlacement::Top => min_y - outward,
+                    HorizontalPlacement::Bottom => max_y - thickness + outward,
+                };
+                CGRect::new(CGPoint::new(min_x, y), CGSize::new(adjusted_width, thickness))
+            }
+            GroupKind::Vertical => {
+                let x = match placement.vertical {
+                    VerticalPlacement::Left => min_x - outward,
+                    VerticalPlacement::Right => max_x - thickness + outward,
+                };
+                CGRect::new(CGPoint::new(x, min_y), CGSize::new(thickness, adjusted_height))
+            }
+        }
+    }
+
+    /// Redraws existing indicators after the config or appearance changed.
+    fn restyle_indicators(&self) {
+        let new_config = self.indicator_config();
+        for (node_id, indicator) in &self.indicators {
+            if let Some(group_data) = indicator.group_data() {
+                if let Err(err) = indicator.update(new_config, group_data) {
+                    tracing::warn!(
+                        ?err,
+                        ?node_id,
+                        "failed to update stack line indicator with new config"
+                    );
+                }
+            }
         }
     }
 
     fn indicator_config(&self) -> IndicatorConfig {
-        IndicatorConfig::from(&self.config.settings.ui.stack_line)
+        let ui = &self.config.settings.ui;
+        IndicatorConfig::new(&ui.stack_line, &ui.theme, self.appearance)
     }
 }
 
@@ -450,6 +668,7 @@ struct GroupSig {
     total: usize,
     selected_index: usize,
     window_ids: Vec<WindowId>,
+    placement: StackLinePlacement,
 }
 
 impl GroupSig {
@@ -465,6 +684,7 @@ impl GroupSig {
             total: g.total_count,
             selected_index: g.selected_index,
             window_ids: g.window_ids.clone(),
+            placement: g.placement,
         }
     }
 }
@@ -499,6 +719,14 @@ fn point_in_hit_area(point: CGPoint, frame: CGRect, mx: f64, my: f64) -> bool {
 mod tests {
     use super::*;
 
+    fn placement(
+        horizontal: HorizontalPlacement,
+        vertical: VerticalPlacement,
+        inside: bool,
+    ) -> StackLinePlacement {
+        StackLinePlacement { horizontal, vertical, inside }
+    }
+
     #[test]
     fn test_group_info_fields() {
         assert_eq!(LayoutKind::VerticalStack.is_group(), true);
@@ -516,8 +744,7 @@ mod tests {
             group_frame,
             GroupKind::Horizontal,
             thickness,
-            HorizontalPlacement::Top,
-            VerticalPlacement::Right,
+            placement(HorizontalPlacement::Top, VerticalPlacement::Right, false),
             spacing,
         );
         assert_eq!(frame_horizontal.origin.x, 100.0);
@@ -529,8 +756,7 @@ mod tests {
             group_frame,
             GroupKind::Vertical,
             thickness,
-            HorizontalPlacement::Top,
-            VerticalPlacement::Left,
+            placement(HorizontalPlacement::Top, VerticalPlacement::Left, false),
             spacing,
         );
         assert_eq!(frame_vertical.origin.x, 100.0 - spacing);
@@ -538,4 +764,27 @@ mod tests {
         assert_eq!(frame_vertical.size.width, thickness);
         assert_eq!(frame_vertical.size.height, 300.0);
     }
+
+    #[test]
+    fn indicator_frame_follows_bottom_right_and_inside_placements() {
+        let group_frame = CGRect::new(CGPoint::new(100.0, 200.0), CGSize::new(400.0, 300.0));
+        let (thickness, spacing) = (6.0, 4.0);
+        let frame = |kind, placement| {
+            StackLine::calculate_indicator_frame(group_frame, kind, thickness, placement, spacing)
+        };
+        let (top, bottom) = (HorizontalPlacement::Top, HorizontalPlacement::Bottom);
+        let (left, right) = (VerticalPlacement::Left, VerticalPlacement::Right);
+
+        let below = frame(GroupKind::Horizontal, placement(bottom, left, false));
+        assert_eq!(below.origin.y, 500.0 - thickness + spacing);
+        assert_eq!(below.size.width, 400.0);
+        let beside = frame(GroupKind::Vertical, placement(top, right, false));
+        assert_eq!(beside.origin.x, 500.0 - thickness + spacing);
+        assert_eq!(beside.size.height, 300.0);
+
+        let over_top = frame(GroupKind::Horizontal, placement(top, left, true));
+        assert_eq!(over_top.origin.y, 200.0 + spacing);
+        let over_right = frame(GroupKind::Vertical, placement(top, right, true));
+        assert_eq!(over_right.origin.x, 500.0 - thickness - spacing);
+    }
 }
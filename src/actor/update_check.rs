@@ -0,0 +1,78 @@
+//! Periodically asks GitHub whether a newer rift is out.
+//!
+//! Off unless `settings.update_check.enabled` is set. The request runs on its
+//! own thread so that a slow network never stalls the main thread. A newer
+//! release is announced once per version: in the menu bar, as a broadcast
+//! event, and through `rift-cli query version`.
+
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+use tracing::debug;
+
+use crate::actor::broadcast::{BroadcastEvent, BroadcastSender};
+use crate::actor::menu_bar;
+use crate::common::config::UpdateCheckSettings;
+use crate::common::version::{self, Release};
+use crate::sys::timer::Timer;
+
+/// Leaves startup alone before the first check.
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(60);
+
+pub struct UpdateChecker {
+    settings: UpdateCheckSettings,
+    broadcast_tx: BroadcastSender,
+    menu_tx: menu_bar::Sender,
+}
+
+impl UpdateChecker {
+    pub fn new(
+        settings: UpdateCheckSettings,
+        broadcast_tx: BroadcastSender,
+        menu_tx: menu_bar::Sender,
+    ) -> Self {
+        Self {
+            settings,
+            broadcast_tx,
+            menu_tx,
+        }
+    }
+
+    pub async fn run(self) {
+        if !self.settings.enabled {
+            return std::future::pending().await;
+        }
+        let mut announced: Option<String> = None;
+        let mut timer = Timer::repeating(FIRST_CHECK_DELAY, self.settings.interval());
+        while timer.next().await.is_some() {
+            let release = match fetch_in_background().await {
+                Ok(release) => release,
+                Err(e) => {
+                    debug!("Update check failed: {e}");
+                    continue;
+                }
+            };
+            version::set_latest_release(release.clone());
+            if !version::is_newer(&release.version, version::VERSION)
+                || announced.as_ref() == Some(&release.version)
+            {
+                continue;
+            }
+            announced = Some(release.version.clone());
+            self.broadcast_tx.send(BroadcastEvent::UpdateAvailable {
+                current_version: version::VERSION.to_string(),
+                latest_version: release.version.clone(),
+                url: release.url.clone(),
+            });
+            self.menu_tx.send(menu_bar::Event::UpdateAvailable(release));
+        }
+    }
+}
+
+async fn fetch_in_background() -> Result<Release, String> {
+    let (tx, rx) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(version::fetch_latest_release());
+    });
+    rx.await.map_err(|_| "update check thread exited".to_string())?
+}
@@ -190,6 +190,10 @@ impl WindowNotify {
                             }
                         }
                     }
+                    CGSEventType::Known(KnownCGSEvent::MissionControlEntered) => {
+                        // The Dock's AXExposeExit reports the way out.
+                        events_tx.send(Event::MissionControlNativeEntered);
+                    }
                     CGSEventType::Known(KnownCGSEvent::SpaceWindowDestroyed) => {
                         if display_churn::is_active() {
                             continue;
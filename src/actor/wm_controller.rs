@@ -4,12 +4,16 @@
 
 use std::borrow::Cow;
 use std::path::PathBuf;
+use std::process::Stdio;
+use std::rc::Rc;
 
 use dispatchr::queue;
 use dispatchr::time::Time;
 use objc2_app_kit::{NSApplicationActivationPolicy, NSRunningApplication};
 use objc2_core_foundation::CGRect;
+use objc2_foundation::MainThreadMarker;
 use once_cell::sync::Lazy;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use strum::VariantNames;
@@ -23,12 +27,17 @@ pub type Sender = actor::Sender<WmEvent>;
 type Receiver = actor::Receiver<WmEvent>;
 
 use self::WmCmd::*;
-use crate::actor::app::AppInfo;
+use crate::actor::app::{AppInfo, WindowId};
 use crate::actor::{self, event_tap, mission_control, reactor};
 use crate::model::tx_store::WindowTxStore;
+use crate::sys::appearance::Appearance;
 use crate::sys::dispatch::DispatchExt;
 use crate::sys::event::Hotkey;
 use crate::sys::screen::{CoordinateConverter, ScreenInfo, SpaceId};
+use crate::sys::window_server::{self, WindowServerInfo};
+use crate::ui::hints::HintOverlay;
+use crate::ui::region_select::RegionSelectOverlay;
+use crate::ui::zoom::{ZoomOverlay, ZoomSource};
 use crate::{layout_engine as layout, sys};
 
 #[derive(Debug)]
@@ -44,7 +53,31 @@ pub enum WmEvent {
     SpaceChanged(Vec<Option<SpaceId>>),
     ScreenParametersChanged(Vec<ScreenInfo>, CoordinateConverter),
     SystemWoke,
+    /// The system has had time to settle after waking; diff against the pre-sleep state.
+    WakeReconcile,
+    /// Our login session became active again (unlock or Fast User Switching back).
+    SessionResumed,
+    /// Another session (or the login window) took over the console.
+    SessionResigned,
+    /// The screen locked, its displays went to sleep or the screensaver started
+    /// (`true`), or the last of those ended (`false`).
+    ScreenLocked(bool),
     PowerStateChanged(bool),
+    FocusModeChanged(Option<String>),
+    AppearanceChanged(Appearance),
+    /// The active keyboard layout or input method changed.
+    InputSourceChanged,
+    /// Secure event input turned on (keystrokes no longer reach the tap) or off.
+    SecureInputChanged(bool),
+    RegionSelectFinished,
+    /// A region was picked for `zoom_into_region`.
+    ZoomRegionSelected(CGRect),
+    ZoomFinished,
+    WindowHintsFinished,
+    /// Native Mission Control or App Exposé opened; rift's overlays get out of its way.
+    MissionControlNativeEntered,
+    /// The panic hotkey was pressed; see [`crate::actor::kill_switch`].
+    KillSwitch,
     ConfigUpdated(crate::common::config::Config),
     Command(WmCommand),
 }
@@ -72,13 +105,39 @@ pub enum WmCmd {
     ShowMissionControlAll,
     ShowMissionControlCurrent,
     DismissMissionControl,
+
+    SelectExclusionZone,
+
+    ZoomIntoWindow,
+    ZoomIntoRegion,
+
+    ShowWindowHints,
+
+    BanishPointer,
+    TogglePointerHiding,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum ExecCmd {
     String(String),
     Array(Vec<String>),
+    Placed(PlacedExecCmd),
+}
+
+/// `exec = { command = "...", workspace = 2, float = true, display = "left" }`:
+/// launches `command` and sends the first new window of the launched app
+/// where it says.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PlacedExecCmd {
+    pub command: Box<ExecCmd>,
+    #[serde(default)]
+    pub workspace: Option<WorkspaceSelector>,
+    #[serde(default, rename = "float")]
+    pub floating: bool,
+    #[serde(default)]
+    pub display: Option<reactor::DisplaySelector>,
 }
 
 static BUILTIN_WM_CMD_VARIANTS: Lazy<Vec<String>> = Lazy::new(|| {
@@ -126,6 +185,12 @@ pub struct WmController {
     receiver: Receiver,
     sender: Sender,
     hotkeys_installed: bool,
+    region_select: Option<Rc<RegionSelectOverlay>>,
+    zoom: Option<Rc<ZoomOverlay>>,
+    hints: Option<Rc<HintOverlay>>,
+    /// Set by the panic hotkey. Commands and config reloads are dropped from
+    /// then on, until rift is restarted.
+    disengaged: bool,
 }
 
 impl WmController {
@@ -156,6 +221,10 @@ impl WmController {
             receiver,
             sender: sender.clone(),
             hotkeys_installed: false,
+            region_select: None,
+            zoom: None,
+            hints: None,
+            disengaged: false,
         };
         (this, sender)
     }
@@ -187,8 +256,36 @@ impl WmController {
             tx.send(mission_control::Event::RefreshCurrentWorkspace);
         }
 
+        if self.disengaged && matches!(event, Command(_) | ConfigUpdated(_)) {
+            debug!("rift is disengaged by the panic hotkey; ignoring");
+            return;
+        }
+
         match event {
             SystemWoke => self.events_tx.send(Event::SystemWoke),
+            WakeReconcile => self.events_tx.send(Event::WakeReconcile),
+            SessionResumed => {
+                info!("Login session resumed; reinstalling event tap and hotkeys");
+                _ = self.event_tap_tx.send(event_tap::Request::ReinstallTap);
+                if self.hotkeys_installed {
+                    self.register_hotkeys();
+                }
+                self.events_tx.send(Event::SessionResumed);
+            }
+            SessionResigned => {
+                info!("Login session resigned");
+                self.events_tx.send(Event::SessionResigned);
+            }
+            ScreenLocked(locked) => {
+                if locked {
+                    info!("Screen locked; tearing down overlays and the event tap");
+                    self.dismiss_overlays();
+                } else {
+                    info!("Screen unlocked; reinstalling the event tap");
+                }
+                _ = self.event_tap_tx.send(event_tap::Request::Suspend(locked));
+                self.events_tx.send(Event::ScreenLocked(locked));
+            }
             DisplayChurnBegin => self.events_tx.send(Event::DisplayChurnBegin),
             DisplayChurnEnd => self.events_tx.send(Event::DisplayChurnEnd),
             AppEventsRegistered => {
@@ -221,12 +318,19 @@ impl WmController {
             AppLaunch(pid, info) => {
                 self.new_app(pid, info);
             }
+            AppGloballyActivated(pid) if sys::app::is_notification_center(pid) => {
+                // Clicking a banner hands activation to Notification Center, but the
+                // window the user is working in has not changed.
+                debug!(pid, "Ignoring Notification Center activation");
+            }
             AppGloballyActivated(pid) => {
                 _ = self.event_tap_tx.send(event_tap::Request::EnforceHidden);
                 self.events_tx.send(Event::ApplicationGloballyActivated(pid));
+                self.events_tx.send(Event::MenuBarOwnerChanged(sys::app::menu_bar_owner()));
             }
             AppGloballyDeactivated(pid) => {
                 self.events_tx.send(Event::ApplicationGloballyDeactivated(pid));
+                self.events_tx.send(Event::MenuBarOwnerChanged(sys::app::menu_bar_owner()));
             }
             AppTerminated(pid) => {
                 sys::app::remove_activation_policy_observer(pid);
@@ -242,6 +346,11 @@ impl WmController {
                 _ = self
                     .event_tap_tx
                     .send(event_tap::Request::ConfigUpdated(self.config.config.clone()));
+                if let Some(tx) = &self.mission_control_tx {
+                    _ = tx.try_send(mission_control::Event::ConfigUpdated(
+                        self.config.config.clone(),
+                    ));
+                }
 
                 if !self.hotkeys_installed {
                     debug!(
@@ -288,6 +397,32 @@ impl WmController {
                 info!("Power state changed: low power mode = {}", is_low_power_mode);
                 _ = self.event_tap_tx.send(event_tap::Request::SetLowPowerMode(is_low_power_mode));
             }
+            FocusModeChanged(focus_mode) => {
+                info!(?focus_mode, "Focus mode changed");
+                self.events_tx.send(Event::FocusModeChanged(focus_mode));
+            }
+            AppearanceChanged(appearance) => {
+                debug!(?appearance, "System appearance changed");
+                if let Some(tx) = &self.mission_control_tx {
+                    _ = tx.try_send(mission_control::Event::AppearanceChanged(appearance));
+                }
+                if let Some(tx) = &self.stack_line_tx {
+                    _ = tx.try_send(crate::actor::stack_line::Event::AppearanceChanged(appearance));
+                }
+            }
+            InputSourceChanged => {
+                debug!("Input source changed; re-resolving character hotkeys");
+                sys::hotkey::refresh_character_keys();
+            }
+            SecureInputChanged(enabled) => {
+                if enabled {
+                    warn!("Secure input is on; hotkeys are unavailable until it is turned off");
+                } else {
+                    info!("Secure input is off; reinstalling event tap");
+                    _ = self.event_tap_tx.send(event_tap::Request::ReinstallTap);
+                }
+                self.events_tx.send(Event::SecureInputChanged(enabled));
+            }
             Command(Wm(crate::actor::wm_controller::WmCmd::ToggleSpaceActivated)) => {
                 self.events_tx.send(reactor::Event::Command(reactor::Command::Reactor(
                     reactor::ReactorCommand::ToggleSpaceActivated,
@@ -304,18 +439,7 @@ impl WmController {
                 )));
             }
             Command(Wm(SwitchToWorkspace(ws_sel))) => {
-                let maybe_index: Option<usize> = match &ws_sel {
-                    WorkspaceSelector::Index(i) => Some(*i),
-                    WorkspaceSelector::Name(name) => self
-                        .config
-                        .config
-                        .virtual_workspaces
-                        .workspace_names
-                        .iter()
-                        .position(|n| n == name),
-                };
-
-                if let Some(workspace_index) = maybe_index {
+                if let Some(workspace_index) = self.workspace_index(&ws_sel) {
                     self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(
                         layout::LayoutCommand::SwitchToWorkspace(workspace_index),
                     )));
@@ -327,18 +451,7 @@ impl WmController {
                 }
             }
             Command(Wm(MoveWindowToWorkspace(ws_sel))) => {
-                let maybe_index: Option<usize> = match &ws_sel {
-                    WorkspaceSelector::Index(i) => Some(*i),
-                    WorkspaceSelector::Name(name) => self
-                        .config
-                        .config
-                        .virtual_workspaces
-                        .workspace_names
-                        .iter()
-                        .position(|n| n == name),
-                };
-
-                if let Some(workspace_index) = maybe_index {
+                if let Some(workspace_index) = self.workspace_index(&ws_sel) {
                     self.events_tx.send(reactor::Event::Command(reactor::Command::Layout(
                         layout::LayoutCommand::MoveWindowToWorkspace {
                             workspace: workspace_index,
@@ -377,9 +490,55 @@ impl WmController {
                     let _ = tx.try_send(mission_control::Event::Dismiss);
                 }
             }
-            Command(Wm(Exec(cmd))) => {
-                self.exec_cmd(cmd);
+            Command(Wm(SelectExclusionZone)) => {
+                let events_tx = self.events_tx.clone();
+                self.start_region_select(move |rect| {
+                    events_tx.send(reactor::Event::ExclusionZoneSelected(rect));
+                });
+            }
+            Command(Wm(ZoomIntoWindow)) => {
+                if !self.dismiss_zoom() {
+                    match window_server::front_window() {
+                        Some(wsid) => self.show_zoom(ZoomSource::Window(wsid)),
+                        None => warn!("No focused window to zoom into"),
+                    }
+                }
+            }
+            Command(Wm(ZoomIntoRegion)) => {
+                if !self.dismiss_zoom() {
+                    let sender = self.sender.clone();
+                    self.start_region_select(move |rect| {
+                        sender.send(WmEvent::ZoomRegionSelected(rect));
+                    });
+                }
             }
+            Command(Wm(ShowWindowHints)) => match self.hints.take() {
+                Some(hints) if !hints.is_finished() => hints.cancel(),
+                _ => self.show_hints(),
+            },
+            Command(Wm(BanishPointer)) => {
+                _ = self.event_tap_tx.send(event_tap::Request::BanishPointer);
+            }
+            Command(Wm(TogglePointerHiding)) => {
+                _ = self.event_tap_tx.send(event_tap::Request::TogglePointerHiding);
+            }
+            RegionSelectFinished => {
+                self.region_select = None;
+            }
+            ZoomRegionSelected(rect) => self.show_zoom(ZoomSource::Region(rect)),
+            ZoomFinished => {
+                if self.zoom.as_ref().is_some_and(|zoom| zoom.is_finished()) {
+                    self.zoom = None;
+                }
+            }
+            WindowHintsFinished => {
+                if self.hints.as_ref().is_some_and(|hints| hints.is_finished()) {
+                    self.hints = None;
+                }
+            }
+            MissionControlNativeEntered => self.dismiss_overlays(),
+            KillSwitch => self.engage_kill_switch(),
+            Command(Wm(Exec(cmd))) => self.exec_cmd(cmd),
             Command(ReactorCommand(cmd)) => {
                 self.events_tx.send(reactor::Event::Command(cmd));
             }
@@ -391,6 +550,10 @@ impl WmController {
             debug!(pid = ?pid, "Failed to resolve NSRunningApplication for new app");
             return;
         };
+        // Banners are never managed, so they never become focus or layout candidates.
+        if info.bundle_id.as_deref() == Some(sys::app::NOTIFICATION_CENTER_BUNDLE_ID) {
+            return;
+        }
 
         if running_app.activationPolicy() != NSApplicationActivationPolicy::Regular
             && info.bundle_id.as_deref() != Some("com.apple.loginwindow")
@@ -424,6 +587,17 @@ impl WmController {
             }
         }
 
+        let window_server_only = self
+            .config
+            .config
+            .virtual_workspaces
+            .window_server_only_for(info.bundle_id.as_deref(), info.localized_name.as_deref());
+        if window_server_only {
+            debug!(pid = ?pid, bundle = ?info.bundle_id, "Managing app without accessibility");
+            actor::server_app::spawn_server_app_thread(pid, info, self.events_tx.clone());
+            return;
+        }
+
         actor::app::spawn_app_thread(
             pid,
             info,
@@ -432,24 +606,181 @@ impl WmController {
         );
     }
 
+    /// Lets the user draw a rectangle and hands it to `on_selected`, unless the
+    /// selection is cancelled.
+    fn start_region_select(&mut self, on_selected: impl Fn(CGRect) + 'static) {
+        if self.region_select.is_some() {
+            debug!("Region selection already in progress");
+            return;
+        }
+        let Some(mtm) = MainThreadMarker::new() else {
+            warn!("Region selection must be started from the main thread");
+            return;
+        };
+        let sender = self.sender.clone();
+        let on_done = move |rect: Option<CGRect>| {
+            if let Some(rect) = rect {
+                on_selected(rect);
+            }
+            // The overlay is still on the stack here, so drop it from the next turn.
+            sender.send(WmEvent::RegionSelectFinished);
+        };
+        match RegionSelectOverlay::show(mtm, on_done) {
+            Ok(overlay) => self.region_select = Some(overlay),
+            Err(e) => warn!("Failed to show region selection overlay: {e:?}"),
+        }
+    }
+
+    fn show_zoom(&mut self, source: ZoomSource) {
+        self.dismiss_zoom();
+        let Some(mtm) = MainThreadMarker::new() else {
+            warn!("Zoom must be shown from the main thread");
+            return;
+        };
+        let sender = self.sender.clone();
+        let on_done = move || sender.send(WmEvent::ZoomFinished);
+        match ZoomOverlay::show(mtm, source, on_done) {
+            Ok(overlay) => self.zoom = Some(overlay),
+            Err(e) => warn!("Failed to show zoom overlay: {e:?}"),
+        }
+    }
+
+    fn show_hints(&mut self) {
+        let Some(mtm) = MainThreadMarker::new() else {
+            warn!("Window hints must be shown from the main thread");
+            return;
+        };
+        let events_tx = self.events_tx.clone();
+        let sender = self.sender.clone();
+        let on_done = move |target: Option<WindowServerInfo>| {
+            if let Some(target) = target {
+                let window_id = WindowId::new(target.pid, target.id.as_u32());
+                let focus = reactor::ReactorCommand::FocusWindow {
+                    window_id,
+                    window_server_id: Some(target.id),
+                };
+                events_tx.send(reactor::Event::Command(reactor::Command::Reactor(focus)));
+            }
+            sender.send(WmEvent::WindowHintsFinished);
+        };
+        let alphabet = &self.config.config.settings.ui.hints.alphabet;
+        match HintOverlay::show(mtm, alphabet, on_done) {
+            Ok(overlay) => self.hints = Some(overlay),
+            Err(e) => warn!("Failed to show window hints: {e:?}"),
+        }
+    }
+
+    /// Takes down the zoom overlay. Returns whether one was showing, so the zoom
+    /// commands can toggle.
+    fn dismiss_zoom(&mut self) -> bool {
+        let Some(zoom) = self.zoom.take() else {
+            return false;
+        };
+        let showing = !zoom.is_finished();
+        zoom.dismiss();
+        showing
+    }
+
+    /// Closes region selection, zoom, window hints and rift's own Mission Control.
+    fn dismiss_overlays(&mut self) {
+        if let Some(overlay) = self.region_select.take() {
+            overlay.cancel();
+        }
+        if let Some(overlay) = self.hints.take() {
+            overlay.cancel();
+        }
+        self.dismiss_zoom();
+        if let Some(tx) = &self.mission_control_tx {
+            _ = tx.try_send(mission_control::Event::Dismiss);
+        }
+    }
+
     fn register_hotkeys(&mut self) {
+        if self.disengaged {
+            return;
+        }
         debug!("register_hotkeys");
         let bindings: Vec<(Hotkey, WmCommand)> = self.config.config.keys.iter().cloned().collect();
         _ = self.event_tap_tx.send(event_tap::Request::SetHotkeys(bindings));
     }
 
-    fn exec_cmd(&self, cmd_args: ExecCmd) {
+    /// Tears down everything that can hold on to input or keep moving windows,
+    /// then has the reactor put windows back where they started.
+    fn engage_kill_switch(&mut self) {
+        if self.disengaged {
+            return;
+        }
+        self.disengaged = true;
+        warn!("Panic hotkey pressed; disengaging rift until it is restarted");
+
+        self.dismiss_overlays();
+        _ = self.event_tap_tx.send(event_tap::Request::SetHotkeys(Vec::new()));
+        _ = self.event_tap_tx.send(event_tap::Request::SetEventProcessing(false));
+        self.events_tx.send(reactor::Event::KillSwitch);
+    }
+
+    fn workspace_index(&self, selector: &WorkspaceSelector) -> Option<usize> {
+        match selector {
+            WorkspaceSelector::Index(i) => Some(*i),
+            WorkspaceSelector::Name(name) => self
+                .config
+                .config
+                .virtual_workspaces
+                .workspace_names
+                .iter()
+                .position(|n| n == name),
+        }
+    }
+
+    /// Registers the placement with the reactor right after the launch, well
+    /// before the app's first window can show up.
+    fn expect_placed_window(&self, placed: &PlacedExecCmd, app: reactor::LaunchedApp) {
+        let workspace = placed.workspace.as_ref().and_then(|sel| {
+            let index = self.workspace_index(sel);
+            if index.is_none() {
+                warn!("exec requested workspace {sel:?} but it could not be resolved; ignoring");
+            }
+            index
+        });
+        self.events_tx.send(reactor::Event::PlaceNextWindow(reactor::WindowPlacement {
+            app,
+            workspace,
+            floating: placed.floating,
+            display: placed.display.clone(),
+        }));
+    }
+
+    fn exec_cmd(&self, exec: ExecCmd) {
+        let cmd_args = exec.as_array().into_owned();
+        let [cmd, args @ ..] = &*cmd_args else {
+            error!("Empty argument list passed to exec");
+            return;
+        };
+        let child = std::process::Command::new(cmd)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn();
+        let child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                error!("Failed to execute command {cmd:?}: {e:?}");
+                return;
+            }
+        };
+        if let ExecCmd::Placed(placed) = &exec {
+            let app = reactor::LaunchedApp::for_command(&cmd_args, child.id() as pid_t);
+            self.expect_placed_window(placed, app);
+        }
         std::thread::spawn(move || {
-            let cmd_args = cmd_args.as_array();
             let [cmd, args @ ..] = &*cmd_args else {
-                error!("Empty argument list passed to exec");
                 return;
             };
-            let output = std::process::Command::new(cmd).args(args).output();
-            let output = match output {
+            let output = match child.wait_with_output() {
                 Ok(o) => o,
                 Err(e) => {
-                    error!("Failed to execute command {cmd:?}: {e:?}");
+                    error!("Failed to wait for command {cmd:?}: {e:?}");
                     return;
                 }
             };
@@ -470,6 +801,7 @@ impl ExecCmd {
         match self {
             ExecCmd::Array(vec) => Cow::Borrowed(&*vec),
             ExecCmd::String(s) => s.split(' ').map(|s| s.to_owned()).collect::<Vec<_>>().into(),
+            ExecCmd::Placed(placed) => placed.command.as_array(),
         }
     }
 }
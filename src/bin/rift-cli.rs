@@ -1,12 +1,19 @@
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
 use std::process::{self};
 
 use clap::{Parser, Subcommand};
 use rift_wm::actor::app::WindowId;
 use rift_wm::actor::reactor::{self, DisplaySelector};
-use rift_wm::common::config::LayoutMode;
+use rift_wm::common::config::{
+    Config, LayoutMode, WorkspaceSelector, config_file, desktop_dir, validate,
+};
+use rift_wm::common::migrate::{self, WmFormat};
+use rift_wm::common::{event_tail, report};
 use rift_wm::ipc::{RiftCommand, RiftMachClient, RiftRequest, RiftResponse};
 use rift_wm::layout_engine as layout;
+use rift_wm::model::server::ScreenshotTarget;
+use rift_wm::sys::screen::SpaceId;
 use rift_wm::sys::window_server::WindowServerId;
 use serde_json::Value;
 
@@ -27,6 +34,13 @@ enum Commands {
     },
     /// Execute commands in rift
     Execute {
+        /// Return only once the layout and any workspace switch the command
+        /// started have settled, so the next command doesn't race them
+        #[arg(long)]
+        wait: bool,
+        /// Give up waiting after this many milliseconds (at most 5000)
+        #[arg(long, default_value_t = 2000, requires = "wait")]
+        wait_timeout_ms: u64,
         #[command(subcommand)]
         command: ExecuteCommands,
     },
@@ -35,11 +49,82 @@ enum Commands {
         #[command(subcommand)]
         subscribe: SubscribeCommands,
     },
+    /// Watch events as they happen
+    Events {
+        #[command(subcommand)]
+        events: EventsCommands,
+    },
+    /// Save a PNG of the focused window, the current workspace or the display
+    ///
+    /// `workspace` draws the workspace's windows at the frames rift laid them out
+    /// at, with floating windows on top and nothing else from the screen.
+    Screenshot {
+        #[arg(value_enum)]
+        target: ScreenshotTarget,
+        /// Where to write the PNG (defaults to ~/Desktop/rift-<target>-<timestamp>.png)
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Collect state for a bug report into a zip
+    ///
+    /// The bundle holds the layout, config (with every command stripped), displays,
+    /// spaces, workspaces, metrics and the latest events, one file each.
+    Report {
+        /// Replace window titles with a placeholder
+        #[arg(long)]
+        redact_titles: bool,
+        /// Where to write the zip (defaults to ~/Desktop/rift-report-<timestamp>.zip)
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// Print the report as JSON instead, e.g. to pipe into `pbcopy`
+        #[arg(long, conflicts_with = "out")]
+        print: bool,
+    },
+    /// Change log levels while rift is running
+    Log {
+        #[command(subcommand)]
+        log: LogCommands,
+    },
     /// Manage the launchd service for rift
     Service {
         #[command(subcommand)]
         service: ServiceCommands,
     },
+    /// Print rift's workspaces and app rules as another window manager's config
+    Export {
+        #[arg(long, value_enum)]
+        format: WmFormat,
+        /// Config file to export (defaults to the one rift loads)
+        #[arg(long)]
+        config: Option<PathBuf>,
+    },
+    /// Print another window manager's config as a rift config fragment
+    Import {
+        #[arg(long, value_enum)]
+        format: WmFormat,
+        /// Path to the yabairc or aerospace.toml to translate
+        path: PathBuf,
+    },
+    /// Work with the config file without a running rift
+    Config {
+        #[command(subcommand)]
+        config: ConfigFileCommands,
+    },
+    /// Stream events to stdout and run requests from stdin, one JSON object per line
+    ///
+    /// Meant to be run as a long-lived child process, e.g. with Hammerspoon's
+    /// `hs.task`. Every output line is `{"type": "event", "event": ...}` or
+    /// `{"type": "response", "id": ..., "ok": true, "data": ...}` (`"error"` instead
+    /// of `"data"` on failure). Each input line is a command string such as
+    /// `workspace 3`, a JSON request such as `"get_workspaces"`, or either one
+    /// wrapped as `{"id": 1, "command": "..."}` / `{"id": 1, "request": ...}` so
+    /// the response carries the same id. Responses come back in request order.
+    /// The bridge exits when stdin is closed.
+    Bridge {
+        /// Event to stream (defaults to all of them)
+        #[arg(long, default_value = "*")]
+        event: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -85,6 +170,24 @@ enum QueryCommands {
     },
     /// Get performance metrics
     Metrics,
+    /// List user-drawn tiling exclusion zones
+    ExclusionZones,
+    /// List spaces whose layout is currently paused
+    PausedSpaces,
+    /// List known spaces per display and why each is (or isn't) managed
+    Spaces,
+    /// Show the pointer position and the display, window and workspace under it
+    Cursor,
+    /// Show which app owns the menu bar (null until an app has been activated)
+    MenuBarOwner,
+    /// Show whether a layout or workspace switch is still in flight
+    AnimationState,
+    /// Explain which app rules match a window and why it is floated, tiled or ignored
+    ExplainWindow { window_id: String },
+    /// Show the running version and whether a newer release is out
+    Version,
+    /// Show the version, commit, target and macOS version, for bug reports
+    BuildInfo,
 }
 
 #[derive(Subcommand)]
@@ -119,6 +222,32 @@ enum ExecuteCommands {
         #[command(subcommand)]
         display_cmd: DisplayCommands,
     },
+    /// Tiling exclusion zone commands
+    ExclusionZone {
+        #[command(subcommand)]
+        zone_cmd: ExclusionZoneCommands,
+    },
+    /// Project workspace commands
+    Project {
+        #[command(subcommand)]
+        project_cmd: ProjectCommands,
+    },
+    /// Mouse pointer commands
+    Pointer {
+        #[command(subcommand)]
+        pointer_cmd: PointerCommands,
+    },
+    /// Magnify a window or region onto its whole display, e.g. for a presentation
+    Zoom {
+        #[command(subcommand)]
+        zoom_cmd: ZoomCommands,
+    },
+    /// Run an i3 or AeroSpace style command, e.g. `move container to workspace 4`
+    Run {
+        /// The command; several can be chained with `;`
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
     /// Save current state and exit rift
     SaveAndExit,
     /// Print layout tree debugging output in the running rift instance
@@ -127,6 +256,39 @@ enum ExecuteCommands {
     Serialize,
     /// Toggle whether the current space is managed by rift
     ToggleSpaceActivated,
+    /// Toggle shadow and corner radius styling from app rules (e.g. for screenshots)
+    ToggleWindowStyling,
+    /// Toggle an overlay drawing the layout tree, split ratios, gaps and reserved regions
+    ToggleDebugOverlay,
+    /// Toggle presentation mode: no animations, stack lines, window styling or IPC events
+    /// until toggled off
+    TogglePresentationMode {
+        /// Also switch the current workspace to a stacked (monocle) layout
+        #[arg(long)]
+        monocle: bool,
+    },
+    /// Freeze layout updates on a space (defaults to the current space) until resumed
+    PauseSpace {
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
+    /// Resume layout updates on a paused space (defaults to the current space)
+    ResumeSpace {
+        #[arg(long)]
+        space_id: Option<u64>,
+    },
+    /// Move windows back to where they were when rift started managing them
+    RestoreAll {
+        /// Leave tiled windows alone and only restore floating ones
+        #[arg(long)]
+        only_floats: bool,
+    },
+    /// Move windows from other workspaces and displays into the current workspace,
+    /// most recently focused first (e.g. after unplugging a monitor)
+    GatherWindows {
+        #[arg(long, value_enum, default_value_t)]
+        from: reactor::GatherSource,
+    },
     /// Show timing metrics
     ShowTiming,
 }
@@ -143,6 +305,9 @@ enum WindowCommands {
     },
     /// Toggle window floating state
     ToggleFloat,
+    /// Pin the focused floating window to its macOS space, keeping it visible
+    /// whichever workspace is active there, or unpin it
+    TogglePin,
     /// Toggle fullscreen mode (fills the whole screen, ignores outer gaps)
     ToggleFullscreen,
     /// Toggle fullscreen within configured outer gaps (respects outer gaps / fills tiling area)
@@ -158,12 +323,57 @@ enum WindowCommands {
     ///   rift-cli execute window resize-by --amount 0.05    # grow by 5%
     ///   rift-cli execute window resize-by --amount -0.10   # shrink by 10%
     ResizeBy { amount: f64 },
+    /// Set, grow or shrink the selection's width or height within its parent.
+    /// Percentages are of the parent container, pixels of the screen.
+    /// Examples:
+    ///   rift-cli execute window resize set width 60%
+    ///   rift-cli execute window resize grow height 50px
+    Resize {
+        /// set, grow or shrink
+        mode: String,
+        /// width or height
+        axis: String,
+        /// e.g. 60% or 50px
+        amount: String,
+    },
     /// Close a window by window server identifier
     Close {
         /// Window Id (window server id or idx from window id)
         #[arg(long)]
         window_id: String,
     },
+    /// Keep a window (the focused one by default) above normal windows, or undo that
+    ToggleTopmost {
+        /// Window Id (window server id or idx from window id)
+        #[arg(long)]
+        window_id: Option<String>,
+    },
+    /// Send the focused floating window behind the other floating windows
+    FloatLower,
+    /// Bring the bottom-most floating window under the focused one to the front
+    FloatCycle,
+    /// Label every window on screen; typing a label focuses that window
+    Hints,
+    /// Move the focused window to the far edge of its workspace, another
+    /// display or a workspace in one step. Give exactly one target.
+    /// Examples:
+    ///   rift-cli execute window throw --edge left
+    ///   rift-cli execute window throw --display right --follow
+    ///   rift-cli execute window throw --workspace web
+    Throw {
+        /// left, right, up or down
+        #[arg(long)]
+        edge: Option<String>,
+        /// A direction, a display index or a display UUID
+        #[arg(long)]
+        display: Option<String>,
+        /// A workspace index or name
+        #[arg(long)]
+        workspace: Option<String>,
+        /// Keep focus on the window where it lands
+        #[arg(long)]
+        follow: bool,
+    },
     /// Add current window to scratchpad
     AddScratchpad,
     /// Toggle scratchpad window
@@ -182,6 +392,10 @@ enum WorkspaceCommands {
     Prev { skip_empty: Option<bool> },
     /// Switch to specific workspace
     Switch { workspace_id: usize },
+    /// Switch to the neighbouring workspace in the workspace grid
+    Focus {
+        direction: String, // up, down, left, right
+    },
     /// Move current window to workspace
     MoveWindow {
         workspace_id: usize,
@@ -191,6 +405,18 @@ enum WorkspaceCommands {
     Create,
     /// Switch to the last workspace
     Last,
+    /// Move the active workspace one position to the left
+    MoveLeft,
+    /// Move the active workspace one position to the right
+    MoveRight,
+    /// Move a workspace (or the active workspace when omitted) to a new position
+    SetIndex {
+        /// Workspace index (0-based). Defaults to active workspace if omitted.
+        #[arg(long)]
+        workspace_id: Option<usize>,
+        /// Position to move the workspace to (0-based)
+        index: usize,
+    },
     /// Set layout mode for a workspace (or active workspace when omitted)
     SetLayout {
         /// Workspace index (0-based). Defaults to active workspace if omitted.
@@ -204,8 +430,10 @@ enum WorkspaceCommands {
 #[derive(Subcommand)]
 enum LayoutCommands {
     /// Move selection up the tree
+    #[command(alias = "focus-parent")]
     Ascend,
     /// Move selection down the tree
+    #[command(alias = "focus-child")]
     Descend,
     /// Move the selected node in a direction
     MoveNode { direction: String },
@@ -217,6 +445,15 @@ enum LayoutCommands {
     ToggleOrientation,
     /// Unjoin previously joined windows
     Unjoin,
+    /// Move the selected window into the stack of its neighbor in a direction
+    AbsorbIntoStack { direction: String },
+    /// Pop the selected window out of its stack
+    ExpelFromStack,
+    /// Set the kind of the selected container, or of the selected window's parent:
+    /// horizontal, vertical, horizontal_stack or vertical_stack
+    SetContainerLayout { kind: String },
+    /// Cycle that container through the given kinds, or through all of them if none are given
+    CycleContainerLayout { kinds: Vec<String> },
     /// Toggle floating on the focused selection (tree focus)
     ToggleFocusFloat,
     /// Adjust master ratio by a delta (master/stack layout only)
@@ -236,6 +473,25 @@ enum LayoutCommands {
     /// Toggle centering of the selected column in scrolling layout.
     /// If invoked again on the same selection, centering is removed.
     CenterSelection,
+    /// Toggle the centered ultrawide column on the focused display (requires `max_width`
+    /// in that display's `layout.per_display` settings)
+    ToggleUltrawideColumn,
+}
+
+#[derive(Subcommand)]
+enum ConfigFileCommands {
+    /// Check the config and report each problem with its line and column
+    ///
+    /// Exits non-zero if rift would refuse to load the file.
+    Validate {
+        /// Config file to check (defaults to the one rift loads)
+        path: Option<PathBuf>,
+        /// Print the diagnostics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a JSON Schema of the config file for editor completion and checks
+    Schema,
 }
 
 #[derive(Subcommand)]
@@ -284,6 +540,16 @@ enum ConfigCommands {
         horizontal: f64,
         vertical: f64,
     },
+    /// Reserve edge space for an external status bar (e.g. sketchybar)
+    SetExternalBar {
+        top: f64,
+        left: f64,
+        bottom: f64,
+        right: f64,
+        /// Only apply to the display with this UUID
+        #[arg(long)]
+        display: Option<String>,
+    },
 
     /// Update workspace settings
     SetWorkspaceNames {
@@ -320,6 +586,46 @@ enum MissionControlCommands {
     Dismiss,
 }
 
+#[derive(Subcommand)]
+enum ExclusionZoneCommands {
+    /// Draw a new exclusion zone with the mouse (Escape cancels)
+    Select,
+    /// Remove an exclusion zone by id
+    Remove { id: u32 },
+    /// Remove all exclusion zones (optionally only on one display)
+    Clear {
+        #[arg(long)]
+        display_uuid: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProjectCommands {
+    /// Open a project from the config, launching its apps on first open
+    Open { name: String },
+    /// Close every window on a project workspace (defaults to the active one)
+    Close {
+        #[arg(long)]
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PointerCommands {
+    /// Move the pointer to the configured screen corner and hide it until the mouse moves
+    Banish,
+    /// Turn hiding the pointer while typing on or off
+    ToggleHiding,
+}
+
+#[derive(Subcommand)]
+enum ZoomCommands {
+    /// Zoom into the focused window (again to stop)
+    Window,
+    /// Draw a region with the mouse and zoom into it (again to stop)
+    Region,
+}
+
 #[derive(Subcommand)]
 enum DisplayCommands {
     /// Focus a display by direction, index, or UUID.
@@ -361,16 +667,45 @@ enum DisplayCommands {
     },
 }
 
+#[derive(Subcommand)]
+enum LogCommands {
+    /// Set a module's level until rift exits, e.g. `reactor=debug`
+    ///
+    /// Modules are reactor, layout, animation, ipc and overlays.
+    Set { directive: String },
+    /// Go back to the level from the config for a module
+    Reset { module: String },
+}
+
+#[derive(Subcommand)]
+enum EventsCommands {
+    /// Print every event as it arrives, one line each, until interrupted
+    ///
+    /// Lines start with the local time, then the event type and its fields as
+    /// `key=value`, colored when printing to a terminal unless NO_COLOR is set.
+    Tail {
+        /// Only print these events; a trailing `*` matches a prefix, e.g. `window_*`
+        #[arg(long)]
+        filter: Vec<String>,
+        /// Print `{"time_ms": ..., "event": ...}` lines instead
+        #[arg(long)]
+        json: bool,
+        /// Print human-readable lines, colored even when not writing to a terminal
+        #[arg(long, conflicts_with = "json")]
+        pretty: bool,
+    },
+}
+
 #[derive(Subcommand)]
 enum SubscribeCommands {
     /// Subscribe to Mach IPC events
     Mach {
-        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, *)
+        /// Event to subscribe to (workspace_changed, workspaces_reordered, windows_changed, window_title_changed, stacks_changed, focus_mode_changed, session_resumed, wake_reconciled, secure_input_changed, idle_changed, workspace_created, workspace_renamed, workspace_deleted, window_managed, window_unmanaged, window_floating_changed, display_added, display_removed, mode_changed, menu_bar_owner_changed, update_available, *)
         event: String,
     },
     /// Subscribe to events via CLI command execution
     Cli {
-        /// Event to subscribe to (workspace_changed, windows_changed, window_title_changed, stacks_changed, *)
+        /// Event to subscribe to (workspace_changed, workspaces_reordered, windows_changed, window_title_changed, stacks_changed, focus_mode_changed, session_resumed, wake_reconciled, secure_input_changed, idle_changed, workspace_created, workspace_renamed, workspace_deleted, window_managed, window_unmanaged, window_floating_changed, display_added, display_removed, mode_changed, menu_bar_owner_changed, update_available, *)
         #[arg(long)]
         event: String,
         /// Command to execute when event occurs
@@ -397,6 +732,10 @@ enum SubscribeCommands {
 fn main() {
     sigpipe::reset();
     let cli = Cli::parse();
+    let wait_timeout_ms = match &cli.command {
+        Commands::Execute { wait, wait_timeout_ms, .. } => wait.then_some(*wait_timeout_ms),
+        _ => None,
+    };
 
     let request = match cli.command {
         Commands::Service { .. } => {
@@ -415,6 +754,81 @@ fn main() {
             }
             process::exit(0);
         }
+        Commands::Events {
+            events: EventsCommands::Tail { filter, json, pretty },
+        } => {
+            if let Err(e) = run_events_tail(filter, json, pretty) {
+                eprintln!("Communication error: {}", e);
+                eprintln!("Hint: ensure the rift service is running (try `rift service start`).");
+                process::exit(1);
+            }
+            process::exit(0);
+        }
+        Commands::Bridge { event } => {
+            if let Err(e) = run_bridge(event) {
+                eprintln!("Communication error: {}", e);
+                eprintln!("Hint: ensure the rift service is running (try `rift service start`).");
+                process::exit(1);
+            }
+            process::exit(0);
+        }
+        Commands::Report { redact_titles, out, print } => {
+            if let Err(e) = run_report(redact_titles, out, print) {
+                eprintln!("Failed to create report: {}", e);
+                process::exit(1);
+            }
+            process::exit(0);
+        }
+        Commands::Export { format, config } => {
+            let path = config.unwrap_or_else(config_file);
+            match Config::read(&path) {
+                Ok(config) => print!("{}", migrate::export(&config, format)),
+                Err(e) => {
+                    eprintln!("Failed to read {}: {}", path.display(), e);
+                    process::exit(1);
+                }
+            }
+            process::exit(0);
+        }
+        Commands::Config {
+            config: ConfigFileCommands::Validate { path, json },
+        } => {
+            let path = path.unwrap_or_else(config_file);
+            let report = validate::validate_file(&path);
+            if json {
+                let value = serde_json::to_value(&report).unwrap_or(Value::Null);
+                if let Err(e) = write_json(&value, true) {
+                    eprintln!("Failed to write report: {}", e);
+                }
+            } else if report.diagnostics.is_empty() {
+                println!("{}: no problems found", path.display());
+            } else {
+                print!("{report}");
+            }
+            process::exit(if report.has_errors() { 1 } else { 0 });
+        }
+        Commands::Config {
+            config: ConfigFileCommands::Schema,
+        } => {
+            if let Err(e) = write_json(&Config::json_schema(), true) {
+                eprintln!("Failed to write schema: {}", e);
+                process::exit(1);
+            }
+            process::exit(0);
+        }
+        Commands::Import { format, path } => {
+            let migration = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|source| migrate::import(format, &source));
+            match migration {
+                Ok(migration) => print!("{}", migration.to_rift_toml(format)),
+                Err(e) => {
+                    eprintln!("Failed to import {}: {}", path.display(), e);
+                    process::exit(1);
+                }
+            }
+            process::exit(0);
+        }
         command => match build_request(command) {
             Ok(req) => req,
             Err(e) => {
@@ -462,15 +876,58 @@ fn main() {
             process::exit(1);
         }
     }
+
+    if let Some(timeout_ms) = wait_timeout_ms {
+        wait_for_settle(&client, timeout_ms);
+    }
+}
+
+/// Blocks until rift reports that nothing is moving any more, exiting with an
+/// error if that takes longer than `timeout_ms`.
+fn wait_for_settle(client: &RiftMachClient, timeout_ms: u64) {
+    match client.send_request(&RiftRequest::WaitForSettle { timeout_ms }) {
+        Ok(RiftResponse::Success { .. }) => {}
+        Ok(RiftResponse::Error { error }) => {
+            match serde_json::to_string_pretty(&error) {
+                Ok(pretty) => eprintln!("{}", pretty),
+                Err(_) => eprintln!("Error: {}", error),
+            }
+            process::exit(1);
+        }
+        Ok(_) => {
+            eprintln!("Received an unknown response shape from rift");
+            process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Communication error: {}", e);
+            process::exit(1);
+        }
+    }
 }
 
 fn build_request(command: Commands) -> Result<RiftRequest, String> {
     match command {
         Commands::Query { query } => build_query_request(query),
-        Commands::Execute { command } => build_execute_request(command),
+        Commands::Execute { command, .. } => build_execute_request(command),
         Commands::Subscribe { subscribe } => build_subscribe_request(subscribe),
-        Commands::Service { .. } => Err(
-            "Service commands are handled locally and should not be sent to the rift server."
+        Commands::Log { log } => build_log_request(log),
+        // rift writes the file, and its working directory is not ours.
+        Commands::Screenshot { target, out } => {
+            let out = out
+                .map(std::path::absolute)
+                .transpose()
+                .map_err(|e| format!("Invalid output path: {e}"))?;
+            Ok(RiftRequest::Screenshot { target, out })
+        }
+        Commands::Service { .. }
+        | Commands::Export { .. }
+        | Commands::Import { .. }
+        | Commands::Config { .. }
+        | Commands::Report { .. }
+        | Commands::Events { .. }
+        | Commands::Bridge { .. } => Err(
+            "Service, export, import, report, events and bridge commands are handled locally and \
+             should not be sent to the rift server."
                 .to_string(),
         ),
     }
@@ -488,6 +945,30 @@ fn build_query_request(query: QueryCommands) -> Result<RiftRequest, String> {
             Ok(RiftRequest::GetWorkspaceLayouts { space_id, workspace_id })
         }
         QueryCommands::Metrics => Ok(RiftRequest::GetMetrics),
+        QueryCommands::ExclusionZones => Ok(RiftRequest::GetExclusionZones),
+        QueryCommands::PausedSpaces => Ok(RiftRequest::GetPausedSpaces),
+        QueryCommands::Spaces => Ok(RiftRequest::GetSpaces),
+        QueryCommands::Cursor => Ok(RiftRequest::GetCursor),
+        QueryCommands::MenuBarOwner => Ok(RiftRequest::GetMenuBarOwner),
+        QueryCommands::AnimationState => Ok(RiftRequest::GetAnimationState),
+        QueryCommands::ExplainWindow { window_id } => Ok(RiftRequest::ExplainWindow { window_id }),
+        QueryCommands::Version => Ok(RiftRequest::GetVersion),
+        QueryCommands::BuildInfo => Ok(RiftRequest::GetBuildInfo),
+    }
+}
+
+fn build_log_request(log: LogCommands) -> Result<RiftRequest, String> {
+    match log {
+        LogCommands::Set { directive } => {
+            let Some((module, level)) = directive.split_once('=') else {
+                return Err(format!("Expected <module>=<level>, got '{directive}'"));
+            };
+            Ok(RiftRequest::SetLogLevel {
+                module: module.trim().to_string(),
+                level: Some(level.trim().to_string()),
+            })
+        }
+        LogCommands::Reset { module } => Ok(RiftRequest::SetLogLevel { module, level: None }),
     }
 }
 
@@ -505,6 +986,13 @@ fn build_subscribe_request(sub: SubscribeCommands) -> Result<RiftRequest, String
 
 fn build_execute_request(execute: ExecuteCommands) -> Result<RiftRequest, String> {
     let rift_command = match execute {
+        // Sent as-is; the server maps it onto rift commands.
+        ExecuteCommands::Run { command } => {
+            return Ok(RiftRequest::ExecuteCommand {
+                command: command.join(" "),
+                args: vec![],
+            });
+        }
         ExecuteCommands::Window { window_cmd } => map_window_command(window_cmd)?,
         ExecuteCommands::Workspace { workspace_cmd } => map_workspace_command(workspace_cmd)?,
         ExecuteCommands::Layout { layout_cmd } => map_layout_command(layout_cmd)?,
@@ -513,6 +1001,10 @@ fn build_execute_request(execute: ExecuteCommands) -> Result<RiftRequest, String
             map_mission_control_command(mission_cmd)?
         }
         ExecuteCommands::Display { display_cmd } => map_display_command(display_cmd)?,
+        ExecuteCommands::ExclusionZone { zone_cmd } => map_exclusion_zone_command(zone_cmd),
+        ExecuteCommands::Project { project_cmd } => map_project_command(project_cmd),
+        ExecuteCommands::Pointer { pointer_cmd } => map_pointer_command(pointer_cmd),
+        ExecuteCommands::Zoom { zoom_cmd } => map_zoom_command(zoom_cmd),
         ExecuteCommands::SaveAndExit => {
             RiftCommand::Reactor(reactor::Command::Reactor(reactor::ReactorCommand::SaveAndExit))
         }
@@ -525,6 +1017,33 @@ fn build_execute_request(execute: ExecuteCommands) -> Result<RiftRequest, String
         ExecuteCommands::ToggleSpaceActivated => RiftCommand::Reactor(reactor::Command::Reactor(
             reactor::ReactorCommand::ToggleSpaceActivated,
         )),
+        ExecuteCommands::ToggleWindowStyling => RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleWindowStyling,
+        )),
+        ExecuteCommands::ToggleDebugOverlay => RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ToggleDebugOverlay,
+        )),
+        ExecuteCommands::TogglePresentationMode { monocle } => RiftCommand::Reactor(
+            reactor::Command::Reactor(reactor::ReactorCommand::TogglePresentationMode { monocle }),
+        ),
+        ExecuteCommands::PauseSpace { space_id } => {
+            RiftCommand::Reactor(reactor::Command::Reactor(reactor::ReactorCommand::PauseSpace {
+                space_id: space_id.map(SpaceId::new),
+            }))
+        }
+        ExecuteCommands::ResumeSpace { space_id } => {
+            RiftCommand::Reactor(reactor::Command::Reactor(reactor::ReactorCommand::ResumeSpace {
+                space_id: space_id.map(SpaceId::new),
+            }))
+        }
+        ExecuteCommands::RestoreAll { only_floats } => {
+            RiftCommand::Reactor(reactor::Command::Reactor(reactor::ReactorCommand::RestoreAll {
+                only_floats,
+            }))
+        }
+        ExecuteCommands::GatherWindows { from } => RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::GatherWindows { from },
+        )),
         ExecuteCommands::ShowTiming => RiftCommand::Reactor(reactor::Command::Metrics(
             rift_wm::common::log::MetricsCommand::ShowTiming,
         )),
@@ -569,12 +1088,23 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
         WindowCommands::ToggleFloat => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleWindowFloating,
         ))),
+        WindowCommands::TogglePin => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::TogglePinToSpace,
+        ))),
         WindowCommands::ToggleFullscreen => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleFullscreen,
         ))),
         WindowCommands::ToggleFullscreenWithinGaps => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::ToggleFullscreenWithinGaps),
         )),
+        WindowCommands::Resize { mode, axis, amount } => {
+            let resize = serde_json::json!({
+                "resize": { "mode": mode, "axis": axis, "amount": amount },
+            });
+            let command =
+                serde_json::from_value(resize).map_err(|e| format!("Invalid resize: {e}"))?;
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(command)))
+        }
         WindowCommands::ResizeGrow => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ResizeWindowGrow,
         ))),
@@ -590,6 +1120,32 @@ fn map_window_command(cmd: WindowCommands) -> Result<RiftCommand, String> {
                 reactor::ReactorCommand::CloseWindow { window_server_id: Some(wsid) },
             )))
         }
+        WindowCommands::ToggleTopmost { window_id } => {
+            let window_server_id = window_id.as_deref().map(parse_window_server_id).transpose()?;
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::ToggleTopmost { window_server_id },
+            )))
+        }
+        WindowCommands::FloatLower => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::FloatLower,
+        ))),
+        WindowCommands::FloatCycle => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::FloatCycle,
+        ))),
+        WindowCommands::Hints => Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+            reactor::ReactorCommand::ShowWindowHints,
+        ))),
+        WindowCommands::Throw {
+            edge,
+            display,
+            workspace,
+            follow,
+        } => {
+            let target = build_throw_target(edge, display, workspace)?;
+            Ok(RiftCommand::Reactor(reactor::Command::Reactor(
+                reactor::ReactorCommand::Throw { target, follow },
+            )))
+        }
         WindowCommands::AddScratchpad => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::AddScratchpad,
         ))),
@@ -651,6 +1207,9 @@ fn map_workspace_command(cmd: WorkspaceCommands) -> Result<RiftCommand, String>
         WorkspaceCommands::Switch { workspace_id } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::SwitchToWorkspace(workspace_id)),
         )),
+        WorkspaceCommands::Focus { direction } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::FocusWorkspace(direction.into())),
+        )),
         WorkspaceCommands::MoveWindow { workspace_id, window_id } => Ok(RiftCommand::Reactor(
             reactor::Command::Layout(LC::MoveWindowToWorkspace {
                 workspace: workspace_id,
@@ -663,6 +1222,15 @@ fn map_workspace_command(cmd: WorkspaceCommands) -> Result<RiftCommand, String>
         WorkspaceCommands::Last => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::SwitchToLastWorkspace,
         ))),
+        WorkspaceCommands::MoveLeft => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::MoveWorkspaceLeft,
+        ))),
+        WorkspaceCommands::MoveRight => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::MoveWorkspaceRight,
+        ))),
+        WorkspaceCommands::SetIndex { workspace_id, index } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::SetWorkspaceIndex { workspace: workspace_id, index }),
+        )),
         WorkspaceCommands::SetLayout { workspace_id, mode } => {
             let mode = parse_layout_mode(&mode)?;
             Ok(RiftCommand::Reactor(reactor::Command::Layout(
@@ -692,6 +1260,26 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::Unjoin => {
             Ok(RiftCommand::Reactor(reactor::Command::Layout(LC::UnjoinWindows)))
         }
+        LayoutCommands::AbsorbIntoStack { direction } => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::AbsorbIntoStack(direction.into())),
+        )),
+        LayoutCommands::ExpelFromStack => Ok(RiftCommand::Reactor(reactor::Command::Layout(
+            LC::ExpelFromStack,
+        ))),
+        LayoutCommands::SetContainerLayout { kind } => {
+            let kind = serde_json::from_value(serde_json::Value::String(kind))
+                .map_err(|e| format!("Invalid layout kind: {e}"))?;
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(
+                LC::SetContainerLayout(kind),
+            )))
+        }
+        LayoutCommands::CycleContainerLayout { kinds } => {
+            let kinds = serde_json::from_value(serde_json::json!(kinds))
+                .map_err(|e| format!("Invalid layout kind: {e}"))?;
+            Ok(RiftCommand::Reactor(reactor::Command::Layout(
+                LC::CycleContainerLayout(kinds),
+            )))
+        }
         LayoutCommands::ToggleFocusFloat => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::ToggleFocusFloating,
         ))),
@@ -721,6 +1309,9 @@ fn map_layout_command(cmd: LayoutCommands) -> Result<RiftCommand, String> {
         LayoutCommands::CenterSelection => Ok(RiftCommand::Reactor(reactor::Command::Layout(
             LC::CenterSelection,
         ))),
+        LayoutCommands::ToggleUltrawideColumn => Ok(RiftCommand::Reactor(
+            reactor::Command::Layout(LC::ToggleUltrawideColumn),
+        )),
     }
 }
 
@@ -792,6 +1383,19 @@ fn map_config_command(cmd: ConfigCommands) -> Result<RiftCommand, String> {
         ConfigCommands::SetInnerGaps { horizontal, vertical } => {
             ConfigCommand::SetInnerGaps { horizontal, vertical }
         }
+        ConfigCommands::SetExternalBar {
+            top,
+            left,
+            bottom,
+            right,
+            display,
+        } => ConfigCommand::SetExternalBar {
+            display,
+            top,
+            left,
+            bottom,
+            right,
+        },
         ConfigCommands::SetWorkspaceNames { names } => ConfigCommand::SetWorkspaceNames(names),
         ConfigCommands::Set { key, value } => {
             let parsed_value: Value = match serde_json::from_str(&value) {
@@ -854,6 +1458,41 @@ fn map_display_command(cmd: DisplayCommands) -> Result<RiftCommand, String> {
     }
 }
 
+fn map_exclusion_zone_command(cmd: ExclusionZoneCommands) -> RiftCommand {
+    let cmd = match cmd {
+        ExclusionZoneCommands::Select => reactor::ReactorCommand::SelectExclusionZone,
+        ExclusionZoneCommands::Remove { id } => reactor::ReactorCommand::RemoveExclusionZone { id },
+        ExclusionZoneCommands::Clear { display_uuid } => {
+            reactor::ReactorCommand::ClearExclusionZones { display_uuid }
+        }
+    };
+    RiftCommand::Reactor(reactor::Command::Reactor(cmd))
+}
+
+fn map_pointer_command(cmd: PointerCommands) -> RiftCommand {
+    let cmd = match cmd {
+        PointerCommands::Banish => reactor::ReactorCommand::BanishPointer,
+        PointerCommands::ToggleHiding => reactor::ReactorCommand::TogglePointerHiding,
+    };
+    RiftCommand::Reactor(reactor::Command::Reactor(cmd))
+}
+
+fn map_zoom_command(cmd: ZoomCommands) -> RiftCommand {
+    let cmd = match cmd {
+        ZoomCommands::Window => reactor::ReactorCommand::ZoomIntoWindow,
+        ZoomCommands::Region => reactor::ReactorCommand::ZoomIntoRegion,
+    };
+    RiftCommand::Reactor(reactor::Command::Reactor(cmd))
+}
+
+fn map_project_command(cmd: ProjectCommands) -> RiftCommand {
+    let cmd = match cmd {
+        ProjectCommands::Open { name } => reactor::ReactorCommand::ProjectOpen { name },
+        ProjectCommands::Close { name } => reactor::ReactorCommand::ProjectClose { name },
+    };
+    RiftCommand::Reactor(reactor::Command::Reactor(cmd))
+}
+
 fn build_display_selector(
     direction: Option<String>,
     index: Option<usize>,
@@ -879,6 +1518,37 @@ fn build_display_selector(
     }
 }
 
+fn build_throw_target(
+    edge: Option<String>,
+    display: Option<String>,
+    workspace: Option<String>,
+) -> Result<reactor::ThrowTarget, String> {
+    let provided =
+        edge.is_some() as usize + display.is_some() as usize + workspace.is_some() as usize;
+    if provided != 1 {
+        return Err("throw requires exactly one of --edge, --display, or --workspace".to_string());
+    }
+
+    if let Some(edge) = edge {
+        Ok(reactor::ThrowTarget::Edge(parse_focus_direction(&edge)?))
+    } else if let Some(display) = display {
+        let selector = match (parse_focus_direction(&display), display.parse()) {
+            (Ok(direction), _) => DisplaySelector::Direction(direction),
+            (_, Ok(index)) => DisplaySelector::Index(index),
+            _ => DisplaySelector::Uuid(display),
+        };
+        Ok(reactor::ThrowTarget::Display(selector))
+    } else if let Some(workspace) = workspace {
+        let selector = match workspace.parse() {
+            Ok(index) => WorkspaceSelector::Index(index),
+            Err(_) => WorkspaceSelector::Name(workspace),
+        };
+        Ok(reactor::ThrowTarget::Workspace(selector))
+    } else {
+        unreachable!("Exactly one throw target is guaranteed to be provided")
+    }
+}
+
 fn parse_focus_direction(value: &str) -> Result<layout::Direction, String> {
     match value.trim().to_ascii_lowercase().as_str() {
         "left" => Ok(layout::Direction::Left),
@@ -906,6 +1576,128 @@ fn write_json(value: &Value, pretty: bool) -> Result<(), String> {
     writer.flush().map_err(|e| e.to_string())
 }
 
+/// Parses one line of bridge input into an optional id and the request to send.
+fn parse_bridge_line(line: &str) -> Result<(Value, RiftRequest), String> {
+    let command = |text: &str| RiftRequest::ExecuteCommand {
+        command: text.to_string(),
+        args: vec![],
+    };
+    if !line.starts_with(['{', '"']) {
+        return Ok((Value::Null, command(line)));
+    }
+    let value: Value = serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {e}"))?;
+    let id = value.get("id").cloned().unwrap_or(Value::Null);
+    if let Some(text) = value.get("command").and_then(Value::as_str) {
+        return Ok((id, command(text)));
+    }
+    let request = value.get("request").cloned().unwrap_or(value);
+    let request = serde_json::from_value(request).map_err(|e| format!("Invalid request: {e}"))?;
+    Ok((id, request))
+}
+
+/// Runs `rift-cli bridge`: events go out on one thread while requests are read
+/// and answered on another, both writing whole lines to stdout.
+fn run_bridge(event: String) -> Result<(), String> {
+    let client = RiftMachClient::connect()?;
+    let subscription = client.subscribe(event)?;
+
+    std::thread::spawn(move || {
+        loop {
+            let event = match subscription.recv_event() {
+                Ok(event) => event,
+                Err(e) => {
+                    eprintln!("Event stream ended: {e}");
+                    process::exit(1);
+                }
+            };
+            let line = serde_json::json!({ "type": "event", "event": event });
+            if write_json(&line, false).is_err() {
+                process::exit(0);
+            }
+        }
+    });
+
+    for line in io::stdin().lines() {
+        let line = line.map_err(|e| format!("Failed to read stdin: {e}"))?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let response = parse_bridge_line(line)
+            .and_then(|(id, request)| Ok((id, client.send_request(&request)?)));
+        let output = match response {
+            Ok((id, RiftResponse::Success { data })) => {
+                serde_json::json!({ "type": "response", "id": id, "ok": true, "data": data })
+            }
+            Ok((id, RiftResponse::Error { error })) => {
+                serde_json::json!({ "type": "response", "id": id, "ok": false, "error": error })
+            }
+            Ok((id, _)) => serde_json::json!({
+                "type": "response",
+                "id": id,
+                "ok": false,
+                "error": "Received an unknown response shape from rift",
+            }),
+            Err(e) => {
+                serde_json::json!({ "type": "response", "id": null, "ok": false, "error": e })
+            }
+        };
+        if write_json(&output, false).is_err() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn run_report(redact_titles: bool, out: Option<PathBuf>, print: bool) -> Result<(), String> {
+    let client = RiftMachClient::connect()?;
+    let report = match client.send_request(&RiftRequest::GetReport { redact_titles })? {
+        RiftResponse::Success { data } => data,
+        RiftResponse::Error { error } => return Err(error.to_string()),
+        _ => return Err("Received an unknown response shape from rift".to_string()),
+    };
+    if print {
+        return write_json(&report, true);
+    }
+
+    let out = out.unwrap_or_else(|| {
+        let stamp = report["generated_at"].as_u64().unwrap_or_default();
+        desktop_dir().join(format!("rift-report-{stamp}.zip"))
+    });
+    report::write_bundle(&report, &out).map_err(|e| e.to_string())?;
+    println!("{}", out.display());
+    Ok(())
+}
+
+/// Runs `rift-cli events tail`. Filtering happens here so that several
+/// patterns can share one subscription.
+fn run_events_tail(filter: Vec<String>, json: bool, pretty: bool) -> Result<(), String> {
+    let color = pretty || (io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none());
+    let client = RiftMachClient::connect()?;
+    let subscription = client.subscribe("*".to_string())?;
+
+    loop {
+        let event = subscription.recv_event()?;
+        if !event_tail::matches(&filter, event_tail::event_type(&event)) {
+            continue;
+        }
+        let now = std::time::SystemTime::now();
+        let written = if json {
+            let line = event_tail::json_line(event_tail::unix_millis(now), event);
+            write_json(&line, false)
+        } else {
+            let line = event_tail::pretty_line(&event_tail::clock(now), &event, color);
+            writeln!(io::stdout(), "{line}").map_err(|e| e.to_string())
+        };
+        if let Err(e) = written {
+            if e.contains("Broken pipe") {
+                return Ok(());
+            }
+            return Err(format!("Failed to write event output: {e}"));
+        }
+    }
+}
+
 fn run_mach_subscription(event: String) -> Result<(), String> {
     let pretty = std::env::var("RIFT_CLI_PRETTY").map(|v| v != "0").unwrap_or(false);
     let client = RiftMachClient::connect()?;
@@ -1,6 +1,8 @@
 use std::future::Future;
-use std::path::PathBuf;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process;
+use std::time::Duration;
 
 use clap::{Parser, Subcommand};
 use objc2::MainThreadMarker;
@@ -8,31 +10,39 @@ use objc2_application_services::AXUIElement;
 use rift_wm::actor::config::ConfigActor;
 use rift_wm::actor::config_watcher::ConfigWatcher;
 use rift_wm::actor::event_tap::EventTap;
+use rift_wm::actor::frame_audit::FrameAuditMonitor;
+use rift_wm::actor::idle::IdleMonitor;
 use rift_wm::actor::menu_bar::Menu;
 use rift_wm::actor::mission_control::MissionControlActor;
 use rift_wm::actor::mission_control_observer::NativeMissionControl;
 use rift_wm::actor::notification_center::NotificationCenter;
 use rift_wm::actor::process::ProcessActor;
 use rift_wm::actor::reactor::{self, Reactor};
+use rift_wm::actor::secure_input::SecureInputMonitor;
 use rift_wm::actor::stack_line::StackLine;
-use rift_wm::actor::window_notify as window_notify_actor;
+use rift_wm::actor::update_check::UpdateChecker;
 use rift_wm::actor::wm_controller::{self, WmController};
-use rift_wm::common::config::{Config, config_file, restore_file};
-use rift_wm::common::log;
+use rift_wm::actor::{kill_switch, window_notify as window_notify_actor};
+use rift_wm::common::config::{Config, config_file, restore_file, validate};
+use rift_wm::common::init::{self, DisplayProposal};
 use rift_wm::common::util::execute_startup_commands;
+use rift_wm::common::{action_trace, log};
 use rift_wm::ipc;
 use rift_wm::layout_engine::LayoutEngine;
 use rift_wm::model::tx_store::WindowTxStore;
 use rift_wm::sys::accessibility::ensure_accessibility_permission;
 use rift_wm::sys::executor::Executor;
 use rift_wm::sys::mach::init_window_sub_level_server_port;
-use rift_wm::sys::screen::{CoordinateConverter, displays_have_separate_spaces};
+use rift_wm::sys::other_wm;
+use rift_wm::sys::screen::{CoordinateConverter, ScreenCache, displays_have_separate_spaces};
 use rift_wm::sys::service::{ServiceCommands, handle_service_command};
 use rift_wm::sys::skylight::{
     CGEnableEventStateCombining, CGSEventType, CGSetLocalEventsSuppressionInterval, KnownCGSEvent,
 };
 use tokio::join;
 
+const OTHER_WM_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
 embed_plist::embed_info_plist!(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/Info.plist"));
 
 #[derive(Parser)]
@@ -51,7 +61,8 @@ struct Cli {
     #[arg(long)]
     no_animate: bool,
 
-    /// No-op compatibility check for the deprecated restore file path.
+    /// Check the config file, print what is wrong with it and exit, non-zero
+    /// if rift would refuse to load it.
     #[arg(long)]
     validate: bool,
 
@@ -68,6 +79,16 @@ struct Cli {
     #[arg(long, value_name = "PATH")]
     config: Option<PathBuf>,
 
+    /// Log a timeline of each hotkey press and IPC command: every stage it
+    /// passes through, on which thread, and how long each took.
+    #[arg(long)]
+    trace_actions: bool,
+
+    /// Also write the action timelines to PATH in Chrome trace format, for
+    /// chrome://tracing or Perfetto. Implies --trace-actions.
+    #[arg(long, value_name = "PATH")]
+    trace_actions_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -79,6 +100,79 @@ enum Commands {
         #[command(subcommand)]
         service: ServiceCommands,
     },
+    /// Write a starter config for the connected displays
+    Init {
+        /// Overwrite an existing config file.
+        #[arg(long)]
+        force: bool,
+        /// Accept the proposed workspace names without asking.
+        #[arg(long, short)]
+        yes: bool,
+        /// Also install the launchd service so rift starts at login.
+        #[arg(long)]
+        service: bool,
+    },
+}
+
+fn init_config(
+    mtm: MainThreadMarker,
+    path: &Path,
+    force: bool,
+    yes: bool,
+    service: bool,
+) -> anyhow::Result<()> {
+    use anyhow::{Context, bail};
+
+    if path.exists() && !force {
+        bail!("{} already exists, pass --force to overwrite it", path.display());
+    }
+    let screens = ScreenCache::new(mtm).refresh().map(|(screens, _)| screens).unwrap_or_default();
+    let mut proposals = init::propose(&screens);
+    let interactive = !yes && io::stdin().is_terminal();
+    let mut service = service;
+    if interactive {
+        for proposal in &mut proposals {
+            ask_workspace_names(proposal)?;
+        }
+        service = service || prompt("Start rift at login? [y/N] ")?.eq_ignore_ascii_case("y");
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    std::fs::write(path, init::render_config(&proposals))
+        .with_context(|| format!("writing {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    for proposal in &proposals {
+        println!("  {}: {}", proposal.name, proposal.workspace_names.join(", "));
+    }
+    if service {
+        let install = ServiceCommands::Install { user_path: None };
+        let message = handle_service_command(&install).map_err(anyhow::Error::msg)?;
+        println!("{message}");
+    }
+    Ok(())
+}
+
+fn ask_workspace_names(proposal: &mut DisplayProposal) -> anyhow::Result<()> {
+    let names = proposal.workspace_names.join(", ");
+    let answer = prompt(&format!("Workspaces on {} [{names}]: ", proposal.name))?;
+    if !answer.is_empty() {
+        proposal.workspace_names = answer
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+    }
+    Ok(())
+}
+
+fn prompt(question: &str) -> io::Result<String> {
+    print!("{question}");
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().lock().read_line(&mut line)?;
+    Ok(line.trim().to_string())
 }
 
 /// this is okay because there is no recovery mechanism for actors
@@ -106,10 +200,23 @@ fn main() {
         }
     }
 
+    let config_path = opt.config.clone().unwrap_or_else(|| config_file());
+    if opt.validate {
+        let report = validate::validate_file(&config_path);
+        print!("{report}");
+        process::exit(if report.has_errors() { 1 } else { 0 });
+    }
+
     if std::env::var_os("RUST_BACKTRACE").is_none() {
         // SAFETY: We are single threaded at this point.
         unsafe { std::env::set_var("RUST_BACKTRACE", "1") };
     }
+    if (opt.trace_actions || opt.trace_actions_file.is_some())
+        && let Err(e) = action_trace::enable(opt.trace_actions_file.as_deref())
+    {
+        eprintln!("Could not start tracing actions: {e}");
+        process::exit(1);
+    }
     log::init_logging();
     install_panic_hook();
 
@@ -122,6 +229,14 @@ fn main() {
         NSApplication::load();
     }
 
+    if let Some(&Commands::Init { force, yes, service }) = opt.command.as_ref() {
+        if let Err(e) = init_config(mtm, &config_path, force, yes, service) {
+            eprintln!("{e:#}");
+            process::exit(1);
+        }
+        process::exit(0);
+    }
+
     ensure_accessibility_permission();
     init_window_sub_level_server_port();
 
@@ -134,22 +249,32 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
         std::process::exit(1);
     }
 
-    let config_path = opt.config.clone().unwrap_or_else(|| config_file());
     let mut config = if config_path.exists() {
-        Config::read(&config_path).unwrap()
+        let report = validate::validate_file(&config_path);
+        eprint!("{report}");
+        match Config::read(&config_path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Failed to load {}: {e}", config_path.display());
+                process::exit(1);
+            }
+        }
     } else {
         Config::default()
     };
     config.settings.animate &= !opt.no_animate;
     config.settings.default_disable |= opt.default_disable;
 
-    if opt.validate {
-        return;
-    }
+    log::apply_settings(&config.settings.logging);
 
+    other_wm::hand_over(
+        config.settings.other_window_manager,
+        OTHER_WM_POLL_INTERVAL,
+        other_wm::running,
+    );
     execute_startup_commands(&config.settings.run_on_start);
 
-    let (broadcast_tx, broadcast_rx) = rift_wm::actor::channel();
+    let (broadcast_tx, broadcast_rx) = rift_wm::actor::broadcast::channel();
 
     let layout = LayoutEngine::new(
         &config.virtual_workspaces,
@@ -187,6 +312,7 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
             CGSEventType::Known(KnownCGSEvent::SpaceWindowCreated),
             CGSEventType::Known(KnownCGSEvent::SpaceCreated),
             CGSEventType::Known(KnownCGSEvent::SpaceDestroyed),
+            CGSEventType::Known(KnownCGSEvent::MissionControlEntered),
             //CGSEventType::Known(KnownCGSEvent::WindowMoved),
             //CGSEventType::Known(KnownCGSEvent::WindowResized),
         ],
@@ -207,11 +333,15 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
     std::thread::spawn(move || {
         let mut rx = mach_bridge_rx;
         let server_state = server_state_for_bridge;
+        let mut delivery = rift_wm::actor::broadcast::Delivery::default();
         loop {
             match rx.blocking_recv() {
-                Some((_span, event)) => {
+                Some((_span, message)) => {
+                    let Some((event, run_hooks)) = delivery.accept(message) else {
+                        continue;
+                    };
                     let state = server_state.read();
-                    state.publish(event);
+                    state.publish(event, run_hooks);
                 }
                 None => {
                     break;
@@ -237,9 +367,22 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
 
     let _ = events_tx.send(reactor::Event::RegisterWmSender(wm_controller_sender.clone()));
 
+    // Armed before any event tap exists so that every tap can honour it.
+    kill_switch::set_hotkey(config.settings.panic_hotkey());
+    kill_switch::arm(wm_controller_sender.clone());
+
     let notification_center = NotificationCenter::new(wm_controller_sender.clone());
 
     let process_actor = ProcessActor::new(wm_controller_sender.clone());
+    let secure_input = SecureInputMonitor::new(wm_controller_sender.clone());
+    let frame_audit =
+        FrameAuditMonitor::new(events_tx.clone(), config.settings.frame_audit.clone());
+    let idle = IdleMonitor::new(events_tx.clone(), config.settings.idle.clone());
+    let update_check = UpdateChecker::new(
+        config.settings.update_check.clone(),
+        broadcast_tx.clone(),
+        menu_tx.clone(),
+    );
 
     let event_tap = EventTap::new(
         config.clone(),
@@ -293,6 +436,10 @@ Enable it in System Settings > Desktop & Dock (Mission Control) and restart Rift
             supervise("mc_native", mission_control_native.run()),
             supervise("mission_control", mission_control.run()),
             supervise("process_actor", process_actor.run()),
+            supervise("secure_input", secure_input.run()),
+            supervise("frame_audit", frame_audit.run()),
+            supervise("idle", idle.run()),
+            supervise("update_check", update_check.run()),
         );
     });
 }
@@ -1,4 +1,10 @@
+pub mod action_trace;
 pub mod collections;
 pub mod config;
+pub mod event_tail;
+pub mod init;
 pub mod log;
+pub mod migrate;
+pub mod report;
 pub mod util;
+pub mod version;
@@ -0,0 +1,408 @@
+//! Timelines of single user actions, behind `rift --trace-actions`.
+//!
+//! A hotkey press or an IPC command opens an `action` span with a fresh id.
+//! Actor channels carry the current span along with every message, so the
+//! spans of the wm controller, reactor, layout engine, animation and app actors
+//! that the action sets off all nest under it, whichever thread they run on.
+//! Once the last of them closes, the action's timeline goes to the log and,
+//! with `--trace-actions-file`, into a Chrome trace that chrome://tracing or
+//! Perfetto can open.
+
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde_json::json;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Level, Span, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+use super::collections::HashSet;
+
+const ACTION_SPAN: &str = "action";
+/// The target timelines are logged under, let through the log filter while
+/// tracing is on.
+pub const REPORT_TARGET: &str = "rift::action_trace";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static NEXT_ACTION: AtomicU64 = AtomicU64::new(1);
+static NEXT_THREAD: AtomicU64 = AtomicU64::new(1);
+/// Chrome trace timestamps count from here.
+static EPOCH: LazyLock<Instant> = LazyLock::new(Instant::now);
+static CHROME_TRACE: Mutex<Option<ChromeTrace>> = Mutex::new(None);
+
+thread_local! {
+    static THREAD_NO: u64 = NEXT_THREAD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Turns action tracing on, writing a Chrome trace to `chrome_trace` if given.
+/// Call it before logging is set up, which only adds the timeline layer if on.
+pub fn enable(chrome_trace: Option<&Path>) -> io::Result<()> {
+    if let Some(path) = chrome_trace {
+        let mut file = File::create(path)?;
+        // The closing bracket is optional in the Chrome trace format, so events
+        // can be appended for as long as rift runs.
+        file.write_all(b"[\n")?;
+        *CHROME_TRACE.lock() = Some(ChromeTrace {
+            file,
+            named_threads: HashSet::default(),
+        });
+    }
+    LazyLock::force(&EPOCH);
+    ENABLED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+pub fn is_enabled() -> bool { ENABLED.load(Ordering::Relaxed) }
+
+/// Opens the span of a new action, or a disabled span when tracing is off.
+/// Enter it while sending the messages that start the action.
+pub fn start(source: &'static str, command: &dyn fmt::Debug) -> Span {
+    if !is_enabled() {
+        return Span::none();
+    }
+    let id = NEXT_ACTION.fetch_add(1, Ordering::Relaxed);
+    tracing::info_span!("action", id, source, command = ?command)
+}
+
+/// The layer that collects the stages of each action and reports the timeline.
+/// Every info span counts as a stage, whatever the log filter says.
+pub fn layer<S>() -> impl Layer<S>
+where S: Subscriber + for<'a> LookupSpan<'a> {
+    ActionTimelineLayer::new(report)
+        .with_filter(filter_fn(|meta| meta.is_span() && *meta.level() <= Level::INFO))
+}
+
+/// A finished action and the stages it went through, ordered by start time.
+#[derive(Debug, Clone)]
+struct ActionTimeline {
+    id: u64,
+    source: String,
+    command: String,
+    thread: ThreadTag,
+    start: Instant,
+    end: Instant,
+    stages: Vec<Stage>,
+}
+
+#[derive(Debug, Clone)]
+struct Stage {
+    /// The span's name, e.g. `reactor::handle_event`.
+    name: &'static str,
+    thread: ThreadTag,
+    start: Instant,
+    end: Instant,
+    /// Time spent inside the span, less than `end - start` if it was entered
+    /// more than once.
+    busy: Duration,
+}
+
+#[derive(Debug, Clone)]
+struct ThreadTag {
+    no: u64,
+    name: Option<String>,
+}
+
+impl ThreadTag {
+    fn current() -> Self {
+        ThreadTag {
+            no: THREAD_NO.with(|no| *no),
+            name: std::thread::current().name().map(str::to_string),
+        }
+    }
+}
+
+/// Kept on every span inside an action.
+struct StageTimer {
+    thread: Option<ThreadTag>,
+    first_enter: Option<Instant>,
+    entered: Option<Instant>,
+    last_exit: Option<Instant>,
+    busy: Duration,
+}
+
+struct ActionTimelineLayer {
+    report: Box<dyn Fn(&ActionTimeline) + Send + Sync>,
+}
+
+impl ActionTimelineLayer {
+    fn new(report: impl Fn(&ActionTimeline) + Send + Sync + 'static) -> Self {
+        Self { report: Box::new(report) }
+    }
+}
+
+impl<S> Layer<S> for ActionTimelineLayer
+where S: Subscriber + for<'a> LookupSpan<'a>
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !is_enabled() {
+            return;
+        }
+        let Some(span) = ctx.span(id) else { return };
+        if span.name() == ACTION_SPAN && span.metadata().target() == module_path!() {
+            let mut fields = ActionFields::default();
+            attrs.record(&mut fields);
+            let now = Instant::now();
+            span.extensions_mut().insert(ActionTimeline {
+                id: fields.id,
+                source: fields.source,
+                command: fields.command,
+                thread: ThreadTag::current(),
+                start: now,
+                end: now,
+                stages: Vec::new(),
+            });
+            return;
+        }
+        let in_action = span.parent().is_some_and(|parent| {
+            let extensions = parent.extensions();
+            extensions.get::<ActionTimeline>().is_some() || extensions.get::<StageTimer>().is_some()
+        });
+        if in_action {
+            span.extensions_mut().insert(StageTimer {
+                thread: None,
+                first_enter: None,
+                entered: None,
+                last_exit: None,
+                busy: Duration::ZERO,
+            });
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        let Some(timer) = extensions.get_mut::<StageTimer>() else {
+            return;
+        };
+        let now = Instant::now();
+        timer.entered = Some(now);
+        if timer.first_enter.is_none() {
+            timer.first_enter = Some(now);
+            timer.thread = Some(ThreadTag::current());
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut extensions = span.extensions_mut();
+        let Some(timer) = extensions.get_mut::<StageTimer>() else {
+            return;
+        };
+        if let Some(entered) = timer.entered.take() {
+            let now = Instant::now();
+            timer.busy += now - entered;
+            timer.last_exit = Some(now);
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else { return };
+        let timeline = span.extensions_mut().remove::<ActionTimeline>();
+        if let Some(mut timeline) = timeline {
+            timeline.end = Instant::now();
+            timeline.stages.sort_by_key(|stage| stage.start);
+            (self.report)(&timeline);
+            return;
+        }
+        let timer = span.extensions_mut().remove::<StageTimer>();
+        let Some(StageTimer {
+            thread: Some(thread),
+            first_enter: Some(start),
+            last_exit: Some(end),
+            busy,
+            ..
+        }) = timer
+        else {
+            return;
+        };
+        let stage = Stage {
+            name: span.name(),
+            thread,
+            start,
+            end,
+            busy,
+        };
+        for ancestor in span.scope().skip(1) {
+            if let Some(timeline) = ancestor.extensions_mut().get_mut::<ActionTimeline>() {
+                timeline.stages.push(stage);
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct ActionFields {
+    id: u64,
+    source: String,
+    command: String,
+}
+
+impl Visit for ActionFields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "id" {
+            self.id = value;
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "source" {
+            self.source = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "command" {
+            self.command = format!("{value:?}");
+        }
+    }
+}
+
+fn report(timeline: &ActionTimeline) {
+    tracing::info!(target: REPORT_TARGET, "{}", describe(timeline).trim_end());
+    if let Some(trace) = CHROME_TRACE.lock().as_mut()
+        && let Err(err) = trace.append(timeline)
+    {
+        tracing::warn!(target: REPORT_TARGET, "Could not write the action trace: {err}");
+    }
+}
+
+/// The timeline as log lines: when each stage started relative to the action,
+/// how long it took and how much of that it was actually running.
+fn describe(timeline: &ActionTimeline) -> String {
+    let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+    let total = ms(timeline.end - timeline.start);
+    let mut out = format!(
+        "action {} ({} {}) took {total:.2}ms\n",
+        timeline.id, timeline.source, timeline.command
+    );
+    for stage in &timeline.stages {
+        let offset = ms(stage.start.saturating_duration_since(timeline.start));
+        let took = ms(stage.end - stage.start);
+        let busy = ms(stage.busy);
+        let thread = stage.thread.name.as_deref().unwrap_or("unnamed");
+        let _ = writeln!(
+            out,
+            "  +{offset:>8.2}ms {took:>8.2}ms (busy {busy:>8.2}ms) {} [{thread}]",
+            stage.name
+        );
+    }
+    out
+}
+
+struct ChromeTrace {
+    file: File,
+    /// Threads whose name has been written already.
+    named_threads: HashSet<u64>,
+}
+
+impl ChromeTrace {
+    fn append(&mut self, timeline: &ActionTimeline) -> io::Result<()> {
+        for event in chrome_events(timeline, *EPOCH, &mut self.named_threads) {
+            writeln!(self.file, "{event},")?;
+        }
+        Ok(())
+    }
+}
+
+/// Complete ("X") events for the action and each of its stages, preceded by
+/// the names of threads that have not been named yet.
+fn chrome_events(
+    timeline: &ActionTimeline,
+    epoch: Instant,
+    named_threads: &mut HashSet<u64>,
+) -> Vec<serde_json::Value> {
+    let pid = std::process::id();
+    let micros = |at: Instant| at.saturating_duration_since(epoch).as_secs_f64() * 1e6;
+    let complete = |name: &str, thread: &ThreadTag, start: Instant, end: Instant| {
+        json!({
+            "name": name,
+            "cat": "action",
+            "ph": "X",
+            "pid": pid,
+            "tid": thread.no,
+            "ts": micros(start),
+            "dur": micros(end) - micros(start),
+            "args": { "action": timeline.id },
+        })
+    };
+
+    let mut events = Vec::new();
+    let stage_threads = timeline.stages.iter().map(|stage| &stage.thread);
+    let threads = std::iter::once(&timeline.thread).chain(stage_threads);
+    for thread in threads {
+        if let Some(name) = &thread.name
+            && named_threads.insert(thread.no)
+        {
+            events.push(json!({
+                "name": "thread_name",
+                "ph": "M",
+                "pid": pid,
+                "tid": thread.no,
+                "args": { "name": name },
+            }));
+        }
+    }
+    let name = format!("{} {}", timeline.source, timeline.command);
+    events.push(complete(&name, &timeline.thread, timeline.start, timeline.end));
+    for stage in &timeline.stages {
+        events.push(complete(stage.name, &stage.thread, stage.start, stage.end));
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn stages_nest_under_their_action() {
+        ENABLED.store(true, Ordering::Relaxed);
+        let timelines = Arc::new(Mutex::new(Vec::new()));
+        let sink = timelines.clone();
+        let layer = ActionTimelineLayer::new(move |timeline| sink.lock().push(timeline.clone()));
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info_span!("unrelated").in_scope(|| {});
+            let action = start("hotkey", &"focus left");
+            let stage = action.in_scope(|| tracing::info_span!("reactor::handle_event"));
+            drop(action);
+            stage.in_scope(|| tracing::info_span!("animation::animate_layout").in_scope(|| {}));
+            // A queued message still holding a stage keeps the action open.
+            assert!(timelines.lock().is_empty());
+            drop(stage);
+        });
+
+        let timelines = timelines.lock();
+        assert_eq!(timelines.len(), 1);
+        let timeline = &timelines[0];
+        assert_eq!(timeline.source, "hotkey");
+        assert_eq!(timeline.command, "\"focus left\"");
+        let names: Vec<_> = timeline.stages.iter().map(|stage| stage.name).collect();
+        assert_eq!(names, ["reactor::handle_event", "animation::animate_layout"]);
+        assert!(describe(timeline).contains("animation::animate_layout"));
+
+        let mut named = HashSet::default();
+        let events = chrome_events(timeline, timeline.start, &mut named);
+        let complete = events.iter().filter(|event| event["ph"] == "X").count();
+        assert_eq!(complete, 3);
+        // Threads are only named once per trace.
+        let again = chrome_events(timeline, timeline.start, &mut named);
+        assert!(again.iter().all(|event| event["ph"] == "X"));
+    }
+}
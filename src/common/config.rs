@@ -1,13 +1,25 @@
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::bail;
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tracing::level_filters::LevelFilter;
 
 use super::collections::HashMap;
-use crate::actor::wm_controller::WmCommand;
-use crate::sys::hotkey::{Hotkey, HotkeySpec};
+use crate::actor::reactor::DisplaySelector;
+use crate::actor::wm_controller::{ExecCmd, WmCommand};
+use crate::common::log::LogModule;
+use crate::layout_engine::{LayoutKind, Orientation};
+use crate::sys::appearance::Appearance;
+use crate::sys::hotkey::{Hotkey, HotkeySpec, Modifiers, keycode_from_char};
+use crate::sys::power::PowerSource;
+
+pub mod validate;
 
 const MAX_WORKSPACES: usize = 32;
 
@@ -42,6 +54,16 @@ pub enum ConfigCommand {
         vertical: f64,
     },
 
+    /// Reserve edge space for an external bar, globally or for one display UUID.
+    SetExternalBar {
+        #[serde(default)]
+        display: Option<String>,
+        top: f64,
+        left: f64,
+        bottom: f64,
+        right: f64,
+    },
+
     SetWorkspaceNames(Vec<String>),
 
     /// Generic setter for arbitrary config paths using dot-separated keys.
@@ -58,11 +80,14 @@ pub enum ConfigCommand {
 
 pub fn data_dir() -> PathBuf { dirs::home_dir().unwrap().join(".rift") }
 pub fn restore_file() -> PathBuf { data_dir().join("layout.ron") }
+pub fn exclusion_zones_file() -> PathBuf { data_dir().join("exclusion_zones.json") }
+pub fn window_identities_file() -> PathBuf { data_dir().join("window_identities.json") }
+pub fn desktop_dir() -> PathBuf { dirs::home_dir().unwrap().join("Desktop") }
 pub fn config_file() -> PathBuf {
     dirs::home_dir().unwrap().join(".config").join("rift").join("config.toml")
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct VirtualWorkspaceSettings {
     #[serde(default = "yes")]
@@ -75,6 +100,10 @@ pub struct VirtualWorkspaceSettings {
     pub preserve_focus_per_workspace: bool,
     #[serde(default = "no")]
     pub workspace_auto_back_and_forth: bool,
+    /// Lay the workspaces of each display out in rows of this many, for
+    /// `focus_workspace` and the Mission Control overview. Unset keeps one row.
+    #[serde(default)]
+    pub grid_columns: Option<usize>,
     #[serde(default = "default_workspace_names")]
     pub workspace_names: Vec<String>,
     #[serde(default)]
@@ -85,27 +114,66 @@ pub struct VirtualWorkspaceSettings {
     pub app_rules: Vec<AppWorkspaceRule>,
     #[serde(default)]
     pub workspace_rules: Vec<WorkspaceLayoutRule>,
+    #[serde(default)]
+    pub projects: Vec<ProjectDefinition>,
+}
+
+/// A named workspace and the apps to launch on it, opened with `project_open`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectDefinition {
+    /// Name of the project and of the workspace it opens on
+    pub name: String,
+    /// Layout mode for the workspace when the project opens on an empty one. Only
+    /// the mode is set; split sizes and window order are up to the layout.
+    #[serde(default)]
+    pub layout: Option<LayoutMode>,
+    /// Launched in order when the workspace has no windows yet. Entries take
+    /// the same forms as `exec`; their windows land on the project's workspace.
+    #[serde(default)]
+    pub apps: Vec<ExecCmd>,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct WorkspaceLayoutRule {
     /// Target workspace by index or name
     pub workspace: WorkspaceSelector,
     /// Layout mode to use for this workspace
-    pub layout: LayoutMode,
+    #[serde(default)]
+    pub layout: Option<LayoutMode>,
+    /// Kind of the root container of a traditional layout, given to it whenever the
+    /// first window lands on the empty workspace. Takes precedence over the
+    /// display's `orientation`.
+    #[serde(default)]
+    pub container: Option<LayoutKind>,
+    /// Overrides `layout.smart_gaps` for this workspace
+    #[serde(default)]
+    pub smart_gaps: Option<bool>,
+    /// Overrides `layout.smart_borders` for this workspace
+    #[serde(default)]
+    pub smart_borders: Option<bool>,
+    /// Overrides `ui.stack_line.horiz_placement` for this workspace
+    #[serde(default)]
+    pub stack_line_horiz_placement: Option<HorizontalPlacement>,
+    /// Overrides `ui.stack_line.vert_placement` for this workspace
+    #[serde(default)]
+    pub stack_line_vert_placement: Option<VerticalPlacement>,
+    /// Overrides `ui.stack_line.inside` for this workspace
+    #[serde(default)]
+    pub stack_line_inside: Option<bool>,
 }
 
 // Allow specifying a workspace by numeric index or by name in the config.
 // This supports both `workspace = 2` and `workspace = "coding"` in app rules.
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Eq)]
 #[serde(untagged)]
 pub enum WorkspaceSelector {
     Index(usize),
     Name(String),
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(untagged)]
 pub enum ScratchpadConfig {
     Boolean(bool),
@@ -118,7 +186,7 @@ impl Default for ScratchpadConfig {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct AppWorkspaceRule {
     /// Application bundle identifier (e.g., "com.apple.Terminal")
@@ -159,6 +227,28 @@ pub struct AppWorkspaceRule {
     /// non-empty string and will be compared against the accessibility subrole
     /// reported by the AX APIs for a window (exact string match).
     pub ax_subrole: Option<String>,
+
+    /// Optional: Set to `false` to remove the system shadow from matching tiled windows.
+    pub shadow: Option<bool>,
+
+    /// Optional: Corner radius (in points) of a shape mask applied to matching tiled windows.
+    pub corner_radius: Option<f64>,
+
+    /// Optional: Overrides `settings.new_window_focus` for windows created by matching apps.
+    pub new_window_focus: Option<NewWindowFocus>,
+
+    /// Optional: Name of a stack group. A matching window that gets tiled joins the stack of
+    /// the first window of the same group on its workspace, which is stacked on demand.
+    /// Rules for different apps can share a group, e.g. `"browsers"`.
+    #[serde(default)]
+    pub stack_with: Option<String>,
+
+    /// Manage the app's windows through the window server alone, for apps with no usable
+    /// accessibility interface. Windows can then be raised, and moved where the window
+    /// server allows it, but not resized, so they always float.
+    /// Chosen when the app launches, so only `app_id` and `app_name` are considered.
+    #[serde(default)]
+    pub window_server_only: bool,
 }
 
 impl Default for VirtualWorkspaceSettings {
@@ -169,11 +259,13 @@ impl Default for VirtualWorkspaceSettings {
             auto_assign_windows: true,
             preserve_focus_per_workspace: true,
             workspace_auto_back_and_forth: false,
+            grid_columns: None,
             workspace_names: default_workspace_names(),
             default_workspace: 0,
             reapply_app_rules_on_title_change: false,
             app_rules: Vec::new(),
             workspace_rules: Vec::new(),
+            projects: Vec::new(),
         }
     }
 }
@@ -192,6 +284,10 @@ impl VirtualWorkspaceSettings {
             ));
         }
 
+        if self.grid_columns == Some(0) {
+            issues.push("grid_columns must be at least 1".to_string());
+        }
+
         if self.workspace_names.len() > self.default_workspace_count {
             issues.push("More workspace names provided than default_workspace_count".to_string());
         }
@@ -269,6 +365,17 @@ impl VirtualWorkspaceSettings {
                 }
             }
 
+            if rule.stack_with.as_deref() == Some("") {
+                issues.push(format!("App rule {} has empty stack_with", index));
+            }
+
+            if rule.window_server_only && app_id_empty && rule.app_name.is_none() {
+                issues.push(format!(
+                    "App rule {} sets window_server_only without an app_id or app_name",
+                    index
+                ));
+            }
+
             if let Some(ref title_sub) = rule.title_substring {
                 if title_sub.is_empty() {
                     issues.push(format!("App rule {} has empty title_substring", index));
@@ -295,16 +402,57 @@ impl VirtualWorkspaceSettings {
                     issues.push(format!("Duplicate ax_subrole '{}' in rule {}", ax_sub, index));
                 }
             }
+
+            if let Some(radius) = rule.corner_radius {
+                if !radius.is_finite() || radius < 0.0 {
+                    issues.push(format!(
+                        "App rule {} has invalid corner_radius {} (must be >= 0)",
+                        index, radius
+                    ));
+                }
+            }
+        }
+
+        let mut seen_projects = crate::common::collections::HashSet::default();
+        for (index, project) in self.projects.iter().enumerate() {
+            if project.name.is_empty() {
+                issues.push(format!("Project {} has an empty name", index));
+            } else if !seen_projects.insert(&project.name) {
+                issues.push(format!("Duplicate project name '{}'", project.name));
+            }
         }
 
         issues
     }
+
+    /// Whether an app rule asks for the app to be managed without accessibility.
+    pub fn window_server_only_for(&self, bundle_id: Option<&str>, app_name: Option<&str>) -> bool {
+        self.app_rules.iter().filter(|rule| rule.window_server_only).any(|rule| {
+            let id_matches = match (&rule.app_id, bundle_id) {
+                (Some(rule_id), Some(id)) => rule_id.eq_ignore_ascii_case(id),
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            let name_matches = match (&rule.app_name, app_name) {
+                (Some(rule_name), Some(name)) => {
+                    let (rule_name, name) = (rule_name.to_lowercase(), name.to_lowercase());
+                    name.contains(&rule_name) || rule_name.contains(&name)
+                }
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            (rule.app_id.is_some() || rule.app_name.is_some()) && id_matches && name_matches
+        })
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
+#[schemars(title = "rift config")]
 struct ConfigFile {
     settings: Settings,
+    /// Key bindings: a hotkey such as "Alt + Shift + H" mapped to a command
+    #[schemars(with = "BTreeMap<String, CommandSchema>")]
     keys: HashMap<String, WmCommand>,
     #[serde(default)]
     virtual_workspaces: VirtualWorkspaceSettings,
@@ -324,7 +472,7 @@ pub struct Config {
 unsafe impl Send for Config {}
 unsafe impl Sync for Config {}
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct Settings {
     #[serde(default = "no")]
@@ -335,8 +483,15 @@ pub struct Settings {
     pub animation_fps: f64,
     #[serde(default)]
     pub animation_easing: AnimationEasing,
+    /// Floating windows slide out of and into view on workspace switches instead
+    /// of popping. Tiled windows still move instantly.
+    #[serde(default)]
+    pub float_switch_animation: FloatSwitchAnimationSettings,
     #[serde(default = "yes")]
     pub default_disable: bool,
+    /// Per-display overrides of `default_disable`, matched against display names and UUIDs.
+    #[serde(default)]
+    pub display_activation: DisplayActivationRules,
     #[serde(default = "yes")]
     pub mouse_follows_focus: bool,
     #[serde(default = "yes")]
@@ -346,12 +501,33 @@ pub struct Settings {
     /// Hotkey that disables focus-follows-mouse while held.
     /// Accepts either a full hotkey (e.g. "Ctrl + A") or a modifier-only spec (e.g. "Ctrl")
     #[serde(default)]
+    #[schemars(with = "Option<String>")]
     pub focus_follows_mouse_disable_hotkey: Option<HotkeySpec>,
     /// Apps that should not trigger automatic workspace switching when activated.
     /// List of bundle identifiers (e.g., "com.apple.Spotlight") that often
     /// inappropriately steal focus and shouldn't cause workspace switches.
     #[serde(default)]
     pub auto_focus_blacklist: Vec<String>,
+    /// Whether newly created windows take focus. When unset, the app that
+    /// opened the window decides (usually macOS focuses it).
+    #[serde(default)]
+    pub new_window_focus: Option<NewWindowFocus>,
+    /// Which windows `move_focus` can land on besides its neighbours in the
+    /// layout tree.
+    #[serde(default)]
+    pub directional_focus: DirectionalFocus,
+    /// What happens when something outside rift (the Dock, Cmd-Tab, opening a
+    /// link) activates an app whose window sits on a workspace that isn't shown.
+    #[serde(default)]
+    pub hidden_workspace_activation: HiddenWorkspaceActivation,
+    /// How windows on workspaces that aren't shown are kept out of sight.
+    #[serde(default)]
+    pub workspace_hide_method: WorkspaceHideMethod,
+    /// Where windows go when the display they were on is unplugged and they are
+    /// left outside every remaining screen: a display index, UUID or direction.
+    /// When unset they move to the main display.
+    #[serde(default)]
+    pub fallback_display: Option<DisplaySelector>,
     #[serde(default)]
     pub layout: LayoutSettings,
     #[serde(default)]
@@ -367,13 +543,281 @@ pub struct Settings {
     #[serde(default)]
     pub run_on_start: Vec<String>,
 
+    /// Pause rift on a display while an app runs exclusive/borderless fullscreen there.
+    #[serde(default)]
+    pub fullscreen_pause: FullscreenPauseSettings,
+
+    /// Overrides applied while a macOS Focus mode is active, keyed by the Focus
+    /// name as shown in System Settings (e.g. "Work"). Matching ignores case.
+    #[serde(default)]
+    pub focus_modes: HashMap<String, FocusModeRule>,
+
     /// Whether to reapply app rules when a window title changes.
     /// Enable hot-reloading of the config file when it changes
     #[serde(default = "yes")]
     pub hot_reload: bool,
+
+    /// Emergency hotkey that disengages rift: overlays close, hotkeys and mouse
+    /// handling stop, and windows go back to where they were before rift moved
+    /// them. An unparseable value falls back to the default instead of failing.
+    #[serde(default = "default_panic_hotkey")]
+    pub panic_hotkey: String,
+
+    /// Whether `save_and_exit` moves windows back to the frames they had when
+    /// rift started managing them, instead of leaving them where rift put them.
+    #[serde(default)]
+    pub restore_frames_on_exit: RestoreFrames,
+
+    /// What to do when another tiling window manager is already running at
+    /// startup. Two of them fighting over the same windows is never useful.
+    #[serde(default)]
+    pub other_window_manager: OtherWmPolicy,
+
+    /// Whether the keys in `[keys]` name physical key positions or the
+    /// characters the active keyboard layout types.
+    #[serde(default)]
+    pub hotkey_resolution: HotkeyResolution,
+
+    /// Hiding the pointer while typing, and where `banish_pointer` sends it.
+    #[serde(default)]
+    pub pointer: PointerSettings,
+
+    /// Rift actions for clicks on window title bars, in place of what macOS does.
+    #[serde(default)]
+    pub titlebar: TitlebarSettings,
+
+    /// Moving and resizing windows by dragging anywhere inside them with a modifier held.
+    #[serde(default)]
+    pub modifier_drag: ModifierDragSettings,
+
+    /// Log levels per module and which modules log to their own files.
+    #[serde(default)]
+    pub logging: LoggingSettings,
+
+    /// Periodically checking that windows are still where rift put them.
+    #[serde(default)]
+    pub frame_audit: FrameAuditSettings,
+
+    /// Commands to run when the user steps away and comes back.
+    #[serde(default)]
+    pub idle: IdleSettings,
+
+    /// Settings overridden while on AC or battery power.
+    #[serde(default)]
+    pub power_profiles: PowerProfiles,
+
+    /// Checking GitHub for a newer release of rift.
+    #[serde(default)]
+    pub update_check: UpdateCheckSettings,
+}
+
+/// Which windows get their original frames back; see
+/// [`Settings::restore_frames_on_exit`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RestoreFrames {
+    /// Restore every window rift managed.
+    Restore,
+    /// Leave windows where they are.
+    #[default]
+    Keep,
+    /// Only restore floating windows; tiled windows stay in their tiles.
+    OnlyFloats,
+}
+
+/// See [`Settings::other_window_manager`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OtherWmPolicy {
+    /// Start anyway without saying anything.
+    Ignore,
+    /// Log a warning naming the other window manager, then start.
+    #[default]
+    Warn,
+    /// Hold off managing windows until the other window manager has quit.
+    Wait,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FullscreenPauseSettings {
+    /// Suspend tiling, hotkeys and mouse handling on a display while the frontmost
+    /// app has captured it, shields it, or covers it from a native fullscreen space
+    /// (games, video players). Windows rift manages never trigger the pause.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Bundle identifiers that may trigger the pause. When empty, any app can.
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Bundle identifiers that never trigger the pause.
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Bundle identifiers that pause the display with the active menu bar while they
+    /// own it, fullscreen or not. These apply even when `enabled` is off.
+    #[serde(default)]
+    pub menu_bar_owners: Vec<String>,
+}
+
+impl FullscreenPauseSettings {
+    /// Whether an app with `bundle_id` is allowed to pause rift.
+    pub fn applies_to(&self, bundle_id: Option<&str>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let listed = |list: &[String]| {
+            bundle_id.is_some_and(|id| list.iter().any(|entry| entry.eq_ignore_ascii_case(id)))
+        };
+        if listed(&self.deny) {
+            return false;
+        }
+        self.allow.is_empty() || listed(&self.allow)
+    }
+
+    /// Whether the app owning the menu bar pauses the display it is shown on.
+    pub fn pauses_for_menu_bar_owner(&self, bundle_id: Option<&str>) -> bool {
+        bundle_id.is_some_and(|id| {
+            self.menu_bar_owners.iter().any(|entry| entry.eq_ignore_ascii_case(id))
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayActivationRules {
+    /// Glob patterns (`*`, `?`) for displays whose spaces start disabled,
+    /// e.g. "Sidecar*" or "SAMSUNG*". Matching ignores case.
+    #[serde(default)]
+    pub disable: Vec<String>,
+    /// Glob patterns for displays whose spaces start enabled. `disable` wins when
+    /// a display matches both lists.
+    #[serde(default)]
+    pub enable: Vec<String>,
+}
+
+impl DisplayActivationRules {
+    /// Returns the `default_disable` value to use for a display, or `None` if no
+    /// pattern matches its name or UUID.
+    pub fn default_disable_for(&self, name: Option<&str>, uuid: &str) -> Option<bool> {
+        let matches = |patterns: &[String]| {
+            patterns.iter().any(|pattern| {
+                name.is_some_and(|name| glob_matches(pattern, name)) || glob_matches(pattern, uuid)
+            })
+        };
+        if matches(&self.disable) {
+            Some(true)
+        } else if matches(&self.enable) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// Case-insensitive glob match supporting `*` (any run) and `?` (any single char).
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct FocusModeRule {
+    /// Workspace index (0 based) new windows default to while this Focus is on.
+    #[serde(default)]
+    pub default_workspace: Option<usize>,
+    /// Skip CLI event hooks (`rift-cli subscribe cli`) while this Focus is on.
+    #[serde(default)]
+    pub disable_hooks: bool,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum NewWindowFocus {
+    /// Always focus the new window.
+    Always,
+    /// Focus the new window only if it lands on the workspace being shown.
+    CurrentWorkspace,
+    /// Focus the new window only if it belongs to the app that was focused.
+    FocusedApp,
+    /// Never let a new window take focus.
+    Never,
+}
+
+impl NewWindowFocus {
+    pub fn should_focus(self, on_current_workspace: bool, from_focused_app: bool) -> bool {
+        match self {
+            NewWindowFocus::Always => true,
+            NewWindowFocus::CurrentWorkspace => on_current_workspace,
+            NewWindowFocus::FocusedApp => from_focused_app,
+            NewWindowFocus::Never => false,
+        }
+    }
+}
+
+/// See [`Settings::hidden_workspace_activation`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HiddenWorkspaceActivation {
+    /// Switch to the workspace the window is on.
+    #[default]
+    Switch,
+    /// Move the window onto the workspace being shown.
+    Pull,
+    /// Stay on the current workspace and leave the window where it is.
+    Ignore,
+}
+
+/// See [`Settings::directional_focus`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DirectionalFocus {
+    /// Only walk the layout tree.
+    Tree,
+    /// Also floating windows, found by where their frames are. Moving between
+    /// two tiled windows still follows the tree.
+    #[default]
+    Floating,
+    /// Floating windows and windows rift doesn't manage, such as ones excluded
+    /// by app rules.
+    All,
+}
+
+/// See [`Settings::workspace_hide_method`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkspaceHideMethod {
+    /// Move the windows into a corner of the screen, mostly out of view.
+    #[default]
+    Move,
+    /// Order the windows out through the window server and leave their frames
+    /// alone. Windows the window server won't order out are moved instead.
+    OrderOut,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum AnimationEasing {
     #[default]
@@ -402,7 +846,7 @@ pub enum AnimationEasing {
     EaseInOutCirc,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct UiSettings {
     #[serde(default)]
@@ -411,9 +855,13 @@ pub struct UiSettings {
     pub stack_line: StackLineSettings,
     #[serde(default)]
     pub mission_control: MissionControlSettings,
+    #[serde(default)]
+    pub hints: HintSettings,
+    #[serde(default)]
+    pub theme: ThemeSettings,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct GestureSettings {
     /// Enable horizontal swipes to switch virtual workspaces
@@ -457,14 +905,14 @@ impl Default for GestureSettings {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default, Copy)]
 #[serde(deny_unknown_fields)]
 pub struct WindowSnappingSettings {
     #[serde(default = "default_drag_swap_fraction")]
     pub drag_swap_fraction: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum MenuBarDisplayMode {
     #[default]
@@ -472,7 +920,7 @@ pub enum MenuBarDisplayMode {
     Active,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ActiveWorkspaceLabel {
     #[default]
@@ -480,7 +928,7 @@ pub enum ActiveWorkspaceLabel {
     Name,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum WorkspaceDisplayStyle {
     #[default]
@@ -488,7 +936,7 @@ pub enum WorkspaceDisplayStyle {
     Label,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct MenuBarSettings {
     #[serde(default = "no")]
@@ -501,9 +949,63 @@ pub struct MenuBarSettings {
     pub active_label: ActiveWorkspaceLabel,
     #[serde(default)]
     pub display_style: WorkspaceDisplayStyle,
+    /// Scrolling over the menu bar item cycles workspaces
+    #[serde(default)]
+    pub scroll_workspaces: WorkspaceScrollSettings,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct FloatSwitchAnimationSettings {
+    /// Needs `animate` as well; low power mode turns it off like other animations.
+    #[serde(default = "no")]
+    pub enabled: bool,
+    /// Seconds the slide takes, separate from `animation_duration` so switches can
+    /// stay snappy while other animations run longer.
+    #[serde(default = "default_float_switch_duration")]
+    pub duration: f64,
+    /// How far, in points, floats travel down as they leave and up as they arrive.
+    #[serde(default = "default_float_switch_distance")]
+    pub distance: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+impl Default for FloatSwitchAnimationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            duration: default_float_switch_duration(),
+            distance: default_float_switch_distance(),
+        }
+    }
+}
+
+/// Scrolling over one of rift's indicators to go to the next or previous
+/// virtual workspace.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceScrollSettings {
+    #[serde(default = "no")]
+    pub enabled: bool,
+    /// Scrolling down goes to the next workspace; this swaps the directions.
+    #[serde(default = "no")]
+    pub invert: bool,
+    /// Scroll events within this long of a switch are dropped, so that one
+    /// flick of a trackpad or a free-spinning wheel moves a single workspace.
+    #[serde(default = "default_workspace_scroll_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+impl Default for WorkspaceScrollSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            invert: false,
+            debounce_ms: default_workspace_scroll_debounce_ms(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct StackLineSettings {
     #[serde(default = "no")]
@@ -514,13 +1016,21 @@ pub struct StackLineSettings {
     pub horiz_placement: HorizontalPlacement,
     #[serde(default)]
     pub vert_placement: VerticalPlacement,
+    /// Draw the indicator over the edge of its group rather than in a strip
+    /// beside it, so the stacked windows keep their full size.
+    #[serde(default = "no")]
+    pub inside: bool,
     /// Distance to position the stack line away from the window edge (in points)
     /// This creates spacing between the window and the stack line
     #[serde(default = "default_stack_line_spacing")]
     pub spacing: f64,
+    /// Scrolling over an indicator cycles workspaces. The scroll still reaches
+    /// the window underneath.
+    #[serde(default)]
+    pub scroll_workspaces: WorkspaceScrollSettings,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct MissionControlSettings {
     #[serde(default = "no")]
@@ -529,78 +1039,982 @@ pub struct MissionControlSettings {
     pub fade_enabled: bool,
     #[serde(default = "default_mission_control_fade_duration_ms")]
     pub fade_duration_ms: f64,
+    #[serde(default)]
+    pub selection: MissionControlSelection,
+    /// Leave minimized windows and windows of hidden apps out when it opens.
+    /// `M` toggles them while it is shown.
+    #[serde(default = "no")]
+    pub hide_minimized: bool,
+    /// Show empty workspaces as placeholder tiles so they can be switched to.
+    /// `E` toggles them while it is shown.
+    #[serde(default = "no")]
+    pub show_empty_workspaces: bool,
 }
 
-fn default_mission_control_fade_duration_ms() -> f64 { 180.0 }
-
-fn default_drag_swap_fraction() -> f64 { 0.3 }
+/// Which tile mission control selects when it opens
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MissionControlSelection {
+    /// The active workspace, or the focused window
+    #[default]
+    AlwaysActive,
+    /// Whatever was selected when that view was last closed, if it still exists
+    Remember,
+}
 
-fn default_master_stack_ratio() -> f64 { 0.6 }
+fn default_mission_control_fade_duration_ms() -> f64 { 180.0 }
 
-fn default_master_stack_count() -> usize { 1 }
+/// Labels drawn over windows by `show_window_hints`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HintSettings {
+    /// Letters the labels are made from. The first ones go on the frontmost
+    /// windows, and labels get a second letter once there are more windows
+    /// than letters.
+    #[serde(default = "default_hint_alphabet")]
+    pub alphabet: String,
+}
 
-fn default_scrolling_column_width_ratio() -> f64 { 0.7 }
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self {
+            alphabet: default_hint_alphabet(),
+        }
+    }
+}
 
-fn default_scrolling_min_column_width_ratio() -> f64 { 0.3 }
+fn default_hint_alphabet() -> String { "asdfghjkl".to_string() }
 
-fn default_scrolling_max_column_width_ratio() -> f64 { 0.9 }
+impl HintSettings {
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let mut letters: Vec<char> = Vec::new();
+        for letter in self.alphabet.chars().flat_map(char::to_lowercase) {
+            if letters.contains(&letter) {
+                continue;
+            }
+            letters.push(letter);
+            if keycode_from_char(&letter.to_string()).is_none() {
+                issues.push(format!("ui.hints.alphabet: no key types '{letter}'"));
+            }
+        }
+        if letters.len() < 2 {
+            issues.push("ui.hints.alphabet needs at least two different letters".to_string());
+        }
+        issues
+    }
+}
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
-#[serde(rename_all = "snake_case")]
-pub enum HorizontalPlacement {
-    #[default]
-    Top,
-    Bottom,
+/// Colors and borders shared by rift's overlays (mission control and the stack line).
+/// The colors here are used in Dark mode; `light` adjusts them for Light mode.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ThemeSettings {
+    /// Follow the system appearance, or pin overlays to the dark or light palette
+    #[serde(default)]
+    pub appearance: ThemeAppearance,
+    /// Highlight for the selected tile and the active stack line segment
+    #[serde(default = "default_theme_accent_color")]
+    pub accent_color: ThemeColor,
+    /// Opacity of the black layer dimming the desktop behind mission control (0.0 - 1.0)
+    #[serde(default = "default_theme_overlay_dim")]
+    pub overlay_dim: f64,
+    /// Fill behind each workspace tile in mission control
+    #[serde(default = "default_theme_tile_background")]
+    pub tile_background: ThemeColor,
+    /// Outline of unselected workspace tiles
+    #[serde(default = "default_theme_border_color")]
+    pub border_color: ThemeColor,
+    /// Outline width of unselected workspace tiles (in points)
+    #[serde(default = "default_theme_border_width")]
+    pub border_width: f64,
+    /// Outline width of the selected tile (in points)
+    #[serde(default = "default_theme_selected_border_width")]
+    pub selected_border_width: f64,
+    /// Background blur behind mission control; 0 turns it off
+    #[serde(default = "default_theme_blur_radius")]
+    pub blur_radius: i32,
+    /// Font for overlay labels, e.g. "Menlo"; the system font if unset or not installed
+    #[serde(default)]
+    pub font_family: Option<String>,
+    /// Size of overlay labels (in points)
+    #[serde(default = "default_theme_font_size")]
+    pub font_size: f64,
+    #[serde(default)]
+    pub light: LightThemeSettings,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
-pub enum VerticalPlacement {
+pub enum ThemeAppearance {
     #[default]
-    Left,
-    Right,
-}
-
-impl StackLineSettings {
-    pub fn thickness(&self) -> f64 { if self.enabled { self.thickness } else { 0.0 } }
+    Auto,
+    Dark,
+    Light,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+/// Light mode palette. Unset colors fall back to rift's light defaults, except
+/// `accent_color`, which falls back to the main theme's accent.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
-pub struct LayoutSettings {
-    /// Layout mode: "traditional", "bsp", "stack", "master_stack", or "scrolling"
-    #[serde(default)]
-    pub mode: LayoutMode,
-    /// Stack system configuration
+pub struct LightThemeSettings {
     #[serde(default)]
-    pub stack: StackSettings,
-    /// Master/stack layout configuration
+    pub accent_color: Option<ThemeColor>,
     #[serde(default)]
-    pub master_stack: MasterStackSettings,
-    /// Gap configuration for window spacing
+    pub overlay_dim: Option<f64>,
     #[serde(default)]
-    pub gaps: GapSettings,
-    /// Scrolling layout configuration (niri-style columns)
+    pub tile_background: Option<ThemeColor>,
     #[serde(default)]
-    pub scrolling: ScrollingLayoutSettings,
+    pub border_color: Option<ThemeColor>,
 }
 
-/// Layout mode enum
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
-#[serde(rename_all = "snake_case")]
-pub enum LayoutMode {
-    /// Traditional container-based tiling (i3/sway style)
-    #[default]
-    Traditional,
-    /// Binary space partitioning tiling
-    Bsp,
-    /// Dedicated stacked layout (single stack container)
-    Stack,
-    /// Master/stack layout (master area + stack area)
-    MasterStack,
-    /// Scrolling column layout (niri-style)
-    Scrolling,
-}
+impl Default for ThemeSettings {
+    fn default() -> Self {
+        Self {
+            appearance: ThemeAppearance::Auto,
+            accent_color: default_theme_accent_color(),
+            overlay_dim: default_theme_overlay_dim(),
+            tile_background: default_theme_tile_background(),
+            border_color: default_theme_border_color(),
+            border_width: default_theme_border_width(),
+            selected_border_width: default_theme_selected_border_width(),
+            blur_radius: default_theme_blur_radius(),
+            font_family: None,
+            font_size: default_theme_font_size(),
+            light: LightThemeSettings::default(),
+        }
+    }
+}
+
+impl ThemeSettings {
+    /// The appearance overlays should use while the system is in `system`.
+    pub fn effective_appearance(&self, system: Appearance) -> Appearance {
+        match self.appearance {
+            ThemeAppearance::Auto => system,
+            ThemeAppearance::Dark => Appearance::Dark,
+            ThemeAppearance::Light => Appearance::Light,
+        }
+    }
+
+    /// These settings with the colors for `appearance` filled in.
+    pub fn palette(&self, appearance: Appearance) -> ThemeSettings {
+        let mut palette = self.clone();
+        if appearance == Appearance::Light {
+            let light = &self.light;
+            palette.accent_color = light.accent_color.unwrap_or(self.accent_color);
+            palette.overlay_dim = light.overlay_dim.unwrap_or(0.2);
+            palette.tile_background =
+                light.tile_background.unwrap_or(ThemeColor::new(0.0, 0.0, 0.0, 0.04));
+            palette.border_color =
+                light.border_color.unwrap_or(ThemeColor::new(0.0, 0.0, 0.0, 0.15));
+        }
+        palette
+    }
+}
+
+fn default_theme_accent_color() -> ThemeColor { ThemeColor::new(0.2, 0.45, 1.0, 0.85) }
+
+fn default_theme_overlay_dim() -> f64 { 0.25 }
+
+fn default_theme_tile_background() -> ThemeColor { ThemeColor::new(1.0, 1.0, 1.0, 0.03) }
+
+fn default_theme_border_color() -> ThemeColor { ThemeColor::new(1.0, 1.0, 1.0, 0.12) }
+
+fn default_theme_border_width() -> f64 { 1.0 }
+
+fn default_theme_selected_border_width() -> f64 { 3.0 }
+
+fn default_theme_blur_radius() -> i32 { 30 }
+
+fn default_theme_font_size() -> f64 { 12.0 }
+
+/// An RGBA color written as `"#RRGGBB"` or `"#RRGGBBAA"` in the config file
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[serde(try_from = "String", into = "String")]
+pub struct ThemeColor {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl ThemeColor {
+    pub const fn new(r: f64, g: f64, b: f64, a: f64) -> Self { Self { r, g, b, a } }
+}
+
+impl FromStr for ThemeColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let hex = trimmed.strip_prefix('#').unwrap_or(trimmed);
+        if !matches!(hex.len(), 6 | 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(format!(
+                "invalid color {s:?}, expected \"#RRGGBB\" or \"#RRGGBBAA\""
+            ));
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).unwrap() as f64 / 255.0;
+        let a = if hex.len() == 8 { channel(6) } else { 1.0 };
+        Ok(Self::new(channel(0), channel(2), channel(4), a))
+    }
+}
+
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { s.parse() }
+}
+
+impl JsonSchema for ThemeColor {
+    fn schema_name() -> Cow<'static, str> { "ThemeColor".into() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "pattern": "^\\s*#?([0-9A-Fa-f]{6}|[0-9A-Fa-f]{8})\\s*$",
+            "description": "An RGBA color written as \"#RRGGBB\" or \"#RRGGBBAA\"",
+        })
+    }
+}
+
+impl From<ThemeColor> for String {
+    fn from(c: ThemeColor) -> String {
+        let byte = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let [r, g, b, a] = [c.r, c.g, c.b, c.a].map(byte);
+        format!("#{r:02X}{g:02X}{b:02X}{a:02X}")
+    }
+}
+
+fn default_drag_swap_fraction() -> f64 { 0.3 }
+
+fn default_master_stack_ratio() -> f64 { 0.6 }
+
+fn default_master_stack_count() -> usize { 1 }
+
+fn default_scrolling_column_width_ratio() -> f64 { 0.7 }
+
+fn default_scrolling_min_column_width_ratio() -> f64 { 0.3 }
+
+fn default_scrolling_max_column_width_ratio() -> f64 { 0.9 }
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HorizontalPlacement {
+    #[default]
+    Top,
+    Bottom,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VerticalPlacement {
+    #[default]
+    Left,
+    Right,
+}
+
+/// Where a stack line indicator sits against its group.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct StackLinePlacement {
+    /// The edge a horizontal stack's indicator runs along.
+    pub horizontal: HorizontalPlacement,
+    /// The edge a vertical stack's indicator runs along.
+    pub vertical: VerticalPlacement,
+    pub inside: bool,
+}
+
+impl StackLineSettings {
+    pub fn thickness(&self) -> f64 { if self.enabled { self.thickness } else { 0.0 } }
+
+    /// The room tiled stacks leave for their indicator when it is placed as given.
+    pub fn reserved_thickness(&self, placement: StackLinePlacement) -> f64 {
+        if placement.inside {
+            0.0
+        } else {
+            self.thickness()
+        }
+    }
+}
+
+/// See [`Settings::hotkey_resolution`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyResolution {
+    /// A key like "H" is the key in the H position of a US keyboard, whatever
+    /// the active layout types there.
+    #[default]
+    Physical,
+    /// A key like "H" is whichever key types h on the active input source, and
+    /// bindings follow along when a layout switcher changes it.
+    Character,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PointerSettings {
+    /// Hide the pointer on the first keystroke and bring it back when the mouse
+    /// moves. `toggle_pointer_hiding` flips this until the config is reloaded.
+    #[serde(default)]
+    pub hide_while_typing: bool,
+    /// The screen corner `banish_pointer` moves the pointer to.
+    #[serde(default)]
+    pub banish_corner: ScreenCorner,
+    /// Bundle identifiers of apps where typing leaves the pointer alone,
+    /// e.g. drawing apps that use keys as tool modifiers.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+}
+
+impl PointerSettings {
+    /// Whether typing into an app with `bundle_id` may hide the pointer.
+    pub fn hides_in(&self, bundle_id: Option<&str>) -> bool {
+        let excluded = |id: &str| self.exclude.iter().any(|entry| entry.eq_ignore_ascii_case(id));
+        !bundle_id.is_some_and(excluded)
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TitlebarSettings {
+    /// Actions keyed by click, e.g. `"double_click" = "toggle_zoom"` or
+    /// `"Alt + click" = "toggle_float"`. Clicks without a binding reach the app.
+    #[serde(default)]
+    pub clicks: HashMap<String, TitlebarAction>,
+    /// How far down from the top edge of a window, in points, counts as its title bar.
+    #[serde(default = "default_titlebar_height")]
+    pub height: f64,
+}
+
+impl Default for TitlebarSettings {
+    fn default() -> Self {
+        TitlebarSettings {
+            clicks: HashMap::default(),
+            height: default_titlebar_height(),
+        }
+    }
+}
+
+impl TitlebarSettings {
+    /// The bindings whose clicks parse; [`Self::validate`] reports the rest.
+    pub fn bindings(&self) -> HashMap<TitlebarClick, TitlebarAction> {
+        self.clicks
+            .iter()
+            .filter_map(|(click, action)| Some((click.parse().ok()?, *action)))
+            .collect()
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues: Vec<String> = self
+            .clicks
+            .keys()
+            .filter_map(|click| click.parse::<TitlebarClick>().err())
+            .map(|err| format!("titlebar.clicks: {err}"))
+            .collect();
+        if self.height.is_nan() || self.height <= 0.0 {
+            issues.push(format!("titlebar.height must be positive, got {}", self.height));
+        }
+        issues
+    }
+}
+
+/// What a bound title bar click does to the window it lands on.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum TitlebarAction {
+    /// Fill the display within the gaps, or go back into the layout.
+    ToggleZoom,
+    /// Focus the next window in the window's stack.
+    CycleStack,
+    ToggleFloat,
+}
+
+/// A title bar click as written in `[settings.titlebar.clicks]`: optional
+/// modifiers, then `click`, `double_click`, `right_click` or `right_double_click`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TitlebarClick {
+    /// Only generic modifiers, the way [`crate::sys::hotkey::modifiers_from_flags`]
+    /// reports them.
+    pub modifiers: Modifiers,
+    pub right: bool,
+    pub double: bool,
+}
+
+impl FromStr for TitlebarClick {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::empty();
+        let mut click = None;
+        for token in s.split('+').map(str::trim) {
+            if modifiers.insert_from_token(token) {
+                continue;
+            }
+            if click.is_some() {
+                bail!("'{s}' names more than one click");
+            }
+            click = Some(match token.to_ascii_lowercase().as_str() {
+                "click" => (false, false),
+                "double_click" => (false, true),
+                "right_click" => (true, false),
+                "right_double_click" => (true, true),
+                _ => bail!("unknown click '{token}' in '{s}'"),
+            });
+        }
+        let Some((right, double)) = click else {
+            bail!("'{s}' does not name a click");
+        };
+        if modifiers == Modifiers::empty() && !right && !double {
+            bail!("a plain click can't be bound, title bars would stop dragging windows");
+        }
+        Ok(TitlebarClick {
+            modifiers: modifiers.to_generic(),
+            right,
+            double,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ModifierDragSettings {
+    /// Modifiers that turn a left drag anywhere in a window into a move and a right
+    /// drag into a resize, e.g. `"Alt"` or `"Ctrl + Alt"`. Unset leaves drags alone.
+    #[serde(default)]
+    pub modifier: Option<String>,
+    /// Modifiers that make a left drag resize, for trackpads where a right drag is
+    /// awkward. Has to differ from `modifier`.
+    #[serde(default)]
+    pub resize_modifier: Option<String>,
+}
+
+impl ModifierDragSettings {
+    /// The modifiers for moving, or `None` when modifier drags are off or the
+    /// setting does not parse.
+    pub fn move_modifiers(&self) -> Option<Modifiers> {
+        parse_drag_modifiers(self.modifier.as_deref()?).ok()
+    }
+
+    pub fn resize_modifiers(&self) -> Option<Modifiers> {
+        parse_drag_modifiers(self.resize_modifier.as_deref()?).ok()
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let settings = [
+            ("modifier", &self.modifier),
+            ("resize_modifier", &self.resize_modifier),
+        ];
+        for (name, value) in settings {
+            if let Some(Err(err)) = value.as_deref().map(parse_drag_modifiers) {
+                issues.push(format!("modifier_drag.{name}: {err}"));
+            }
+        }
+        if self.move_modifiers().is_some() && self.move_modifiers() == self.resize_modifiers() {
+            issues.push("modifier_drag.resize_modifier must differ from modifier".to_string());
+        }
+        issues
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct LoggingSettings {
+    /// Level per module, e.g. `reactor = "debug"`. Modules left out follow `RUST_LOG`.
+    #[serde(default)]
+    pub levels: HashMap<String, String>,
+    /// Modules that log to `<directory>/<module>.log` instead of stderr.
+    #[serde(default)]
+    pub files: Vec<LogModule>,
+    /// Where module log files go. Defaults to `~/.rift/logs`.
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// Size in megabytes at which a module log file is rotated.
+    #[serde(default = "default_log_max_file_mb")]
+    pub max_file_mb: u64,
+    /// Rotated files kept per module, `<module>.log.1` being the newest.
+    #[serde(default = "default_log_keep_files")]
+    pub keep_files: usize,
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        LoggingSettings {
+            levels: HashMap::default(),
+            files: Vec::new(),
+            directory: None,
+            max_file_mb: default_log_max_file_mb(),
+            keep_files: default_log_keep_files(),
+        }
+    }
+}
+
+impl LoggingSettings {
+    /// The configured level of `module`, if it is set and parses.
+    pub fn level(&self, module: LogModule) -> Option<LevelFilter> {
+        self.levels.get(module.name())?.parse().ok()
+    }
+
+    pub fn directory(&self) -> PathBuf {
+        self.directory.clone().unwrap_or_else(|| data_dir().join("logs"))
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (module, level) in &self.levels {
+            if let Err(err) = module.parse::<LogModule>() {
+                issues.push(format!("logging.levels: {err}"));
+            }
+            if level.parse::<LevelFilter>().is_err() {
+                issues.push(format!("logging.levels.{module}: '{level}' is not a log level"));
+            }
+        }
+        if self.max_file_mb == 0 {
+            issues.push("logging.max_file_mb must be at least 1".to_string());
+        }
+        issues
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct FrameAuditSettings {
+    /// Compare every managed window's frame with the window server's and fix the
+    /// ones an app moved or resized on its own.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Seconds between audits. Read at startup.
+    #[serde(default = "default_frame_audit_interval_secs")]
+    pub interval_secs: u64,
+    /// Pixels an edge may be off by before the window counts as moved.
+    #[serde(default = "default_frame_audit_tolerance")]
+    pub tolerance: f64,
+    /// Tiled windows put back per audit; the rest wait for the next one, so an
+    /// app that keeps fighting the layout cannot flood it with frame changes.
+    #[serde(default = "default_frame_audit_max_corrections")]
+    pub max_corrections: usize,
+}
+
+impl Default for FrameAuditSettings {
+    fn default() -> Self {
+        FrameAuditSettings {
+            enabled: false,
+            interval_secs: default_frame_audit_interval_secs(),
+            tolerance: default_frame_audit_tolerance(),
+            max_corrections: default_frame_audit_max_corrections(),
+        }
+    }
+}
+
+impl FrameAuditSettings {
+    pub fn interval(&self) -> Duration { Duration::from_secs(self.interval_secs) }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.interval_secs == 0 {
+            issues.push("frame_audit.interval_secs must be at least 1".to_string());
+        }
+        if self.tolerance < 0.0 {
+            issues.push(format!(
+                "frame_audit.tolerance must be non-negative, got {}",
+                self.tolerance
+            ));
+        }
+        issues
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct IdleSettings {
+    /// Seconds between checks of the input idle time. Read at startup.
+    #[serde(default = "default_idle_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    #[serde(default)]
+    pub rules: Vec<IdleRule>,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        IdleSettings {
+            poll_interval_secs: default_idle_poll_interval_secs(),
+            rules: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct UpdateCheckSettings {
+    /// Ask GitHub for the latest release and say so in the menu bar and with an
+    /// `update_available` event when it is newer. Read at startup.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours between checks. Read at startup.
+    #[serde(default = "default_update_check_interval_hours")]
+    pub interval_hours: u64,
+}
+
+impl Default for UpdateCheckSettings {
+    fn default() -> Self {
+        UpdateCheckSettings {
+            enabled: false,
+            interval_hours: default_update_check_interval_hours(),
+        }
+    }
+}
+
+impl UpdateCheckSettings {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_hours.saturating_mul(3600))
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.interval_hours == 0 {
+            issues.push("update_check.interval_hours must be at least 1".to_string());
+        }
+        issues
+    }
+}
+
+impl IdleSettings {
+    pub fn poll_interval(&self) -> Duration { Duration::from_secs(self.poll_interval_secs) }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.poll_interval_secs == 0 {
+            issues.push("idle.poll_interval_secs must be at least 1".to_string());
+        }
+        for (index, rule) in self.rules.iter().enumerate() {
+            if rule.after_secs < self.poll_interval_secs {
+                issues.push(format!(
+                    "idle.rules[{index}].after_secs is shorter than poll_interval_secs ({})",
+                    self.poll_interval_secs
+                ));
+            }
+        }
+        issues
+    }
+}
+
+/// Overrides keyed by their dotted path under `settings`, like the keys of
+/// `rift-cli config set` without the `settings.` prefix.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct PowerProfiles {
+    #[serde(default)]
+    pub ac: BTreeMap<String, Value>,
+    #[serde(default)]
+    pub battery: BTreeMap<String, Value>,
+}
+
+impl PowerProfiles {
+    pub fn for_source(&self, source: PowerSource) -> &BTreeMap<String, Value> {
+        match source {
+            PowerSource::Ac => &self.ac,
+            PowerSource::Battery => &self.battery,
+        }
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (name, profile) in [("ac", &self.ac), ("battery", &self.battery)] {
+            for key in profile.keys() {
+                if key.split('.').next() == Some("power_profiles") {
+                    issues.push(format!("power_profiles.{name} cannot override {key}"));
+                }
+            }
+        }
+        issues
+    }
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct IdleRule {
+    /// Seconds without keyboard, mouse or trackpad input before `on_idle` runs.
+    pub after_secs: u64,
+    /// Commands, written like `[keys]` values, run once per idle period.
+    #[serde(default)]
+    #[schemars(with = "Vec<CommandSchema>")]
+    pub on_idle: Vec<WmCommand>,
+    /// Commands run on the first input after `on_idle` ran.
+    #[serde(default)]
+    #[schemars(with = "Vec<CommandSchema>")]
+    pub on_resume: Vec<WmCommand>,
+}
+
+/// Parses `"Ctrl + Alt"` style modifiers, left and right sides alike.
+fn parse_drag_modifiers(s: &str) -> anyhow::Result<Modifiers> {
+    let mut modifiers = Modifiers::empty();
+    for token in s.split('+').map(str::trim) {
+        if !modifiers.insert_from_token(token) {
+            bail!("'{token}' in '{s}' is not a modifier");
+        }
+    }
+    if modifiers == Modifiers::empty() {
+        bail!("'{s}' names no modifiers");
+    }
+    Ok(modifiers.to_generic())
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutSettings {
+    /// Layout mode: "traditional", "bsp", "stack", "master_stack", or "scrolling"
+    #[serde(default)]
+    pub mode: LayoutMode,
+    /// Stack system configuration
+    #[serde(default)]
+    pub stack: StackSettings,
+    /// Master/stack layout configuration
+    #[serde(default)]
+    pub master_stack: MasterStackSettings,
+    /// Gap configuration for window spacing
+    #[serde(default)]
+    pub gaps: GapSettings,
+    /// Scrolling layout configuration (niri-style columns)
+    #[serde(default)]
+    pub scrolling: ScrollingLayoutSettings,
+    /// Display-specific layout defaults keyed by display UUID
+    #[serde(default)]
+    pub per_display: HashMap<String, DisplayLayoutOverride>,
+    /// Space reserved for external status bars (sketchybar, etc.)
+    #[serde(default)]
+    pub external_bar: ExternalBarSettings,
+    /// Drop the outer and inner gaps while a workspace shows a single tiled window
+    #[serde(default)]
+    pub smart_gaps: bool,
+    /// Hide stack line indicators, and the room kept for them, while a workspace
+    /// shows a single tiled window
+    #[serde(default)]
+    pub smart_borders: bool,
+    /// Heuristics that float utility windows instead of tiling them
+    #[serde(default)]
+    pub auto_float: AutoFloatSettings,
+    /// Keep tiled windows out from under Notification Center banners while they show
+    #[serde(default)]
+    pub avoid_notification_banners: bool,
+    /// Where windows that open floating are put
+    #[serde(default)]
+    pub float_placement: FloatPlacement,
+}
+
+/// See [`LayoutSettings::float_placement`].
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatPlacement {
+    /// Centered on the display the window opened on.
+    #[default]
+    Center,
+    /// Centered on the mouse pointer.
+    Cursor,
+    /// Down and to the right of the previous new float, starting again from the
+    /// top-left corner when the next one would run off the display.
+    Cascade,
+    /// Centered in the largest part of the display that no other window covers.
+    Smart,
+}
+
+/// Heuristics deciding which new windows start floating. They run once, when rift
+/// first lays a window out; app rules with `floating = true` still apply on top.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AutoFloatSettings {
+    /// Set to false to tile every window the app rules do not float
+    #[serde(default = "yes")]
+    pub enabled: bool,
+    /// AX subroles of dialogs and utility panels
+    #[serde(default = "default_auto_float_subroles")]
+    pub subroles: Vec<String>,
+    /// Float windows that cannot be resized, instead of tiling them at their fixed size
+    #[serde(default = "no")]
+    pub non_resizable: bool,
+    /// Float windows narrower than this many points when they appear (0 disables)
+    #[serde(default)]
+    pub min_width: f64,
+    /// Float windows shorter than this many points when they appear (0 disables)
+    #[serde(default)]
+    pub min_height: f64,
+}
+
+impl Default for AutoFloatSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            subroles: default_auto_float_subroles(),
+            non_resizable: false,
+            min_width: 0.0,
+            min_height: 0.0,
+        }
+    }
+}
+
+/// Why a window started floating, as reported by `query windows`.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoFloatReason {
+    Subrole,
+    NonResizable,
+    TooSmall,
+}
+
+impl AutoFloatSettings {
+    /// Which heuristic, if any, floats a window with these properties.
+    pub fn reason(
+        &self,
+        ax_subrole: Option<&str>,
+        is_resizable: bool,
+        width: f64,
+        height: f64,
+    ) -> Option<AutoFloatReason> {
+        if !self.enabled {
+            return None;
+        }
+        if ax_subrole.is_some_and(|subrole| self.subroles.iter().any(|s| s == subrole)) {
+            return Some(AutoFloatReason::Subrole);
+        }
+        if self.non_resizable && !is_resizable {
+            return Some(AutoFloatReason::NonResizable);
+        }
+        if width < self.min_width || height < self.min_height {
+            return Some(AutoFloatReason::TooSmall);
+        }
+        None
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for (name, value) in [
+            ("min_width", self.min_width),
+            ("min_height", self.min_height),
+        ] {
+            if value.is_nan() || value < 0.0 {
+                issues.push(format!(
+                    "layout.auto_float.{name} must be non-negative, got {value}"
+                ));
+            }
+        }
+        issues
+    }
+}
+
+/// Edge reservations for custom status bars so windows are not tiled underneath them
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ExternalBarSettings {
+    #[serde(default)]
+    pub top: f64,
+    #[serde(default)]
+    pub left: f64,
+    #[serde(default)]
+    pub bottom: f64,
+    #[serde(default)]
+    pub right: f64,
+    /// Reservations keyed by display UUID; these replace the values above for that display
+    #[serde(default)]
+    pub per_display: HashMap<String, OuterGaps>,
+    /// Detect reservations from the on-screen windows of `auto_detect_owners`
+    #[serde(default = "no")]
+    pub auto_detect: bool,
+    /// Process names whose windows are treated as bars when `auto_detect` is enabled
+    #[serde(default = "default_external_bar_owners")]
+    pub auto_detect_owners: Vec<String>,
+}
+
+impl Default for ExternalBarSettings {
+    fn default() -> Self {
+        Self {
+            top: 0.0,
+            left: 0.0,
+            bottom: 0.0,
+            right: 0.0,
+            per_display: HashMap::default(),
+            auto_detect: false,
+            auto_detect_owners: default_external_bar_owners(),
+        }
+    }
+}
+
+impl ExternalBarSettings {
+    pub fn reservation_for_display(&self, display_uuid: Option<&str>) -> OuterGaps {
+        if let Some(reservation) = display_uuid.and_then(|uuid| self.per_display.get(uuid)) {
+            return reservation.clone();
+        }
+        OuterGaps {
+            top: self.top,
+            left: self.left,
+            bottom: self.bottom,
+            right: self.right,
+        }
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        let global = self.reservation_for_display(None);
+        for issue in global.validate() {
+            issues.push(format!("layout.external_bar {issue}"));
+        }
+        for (uuid, reservation) in &self.per_display {
+            for issue in reservation.validate() {
+                issues.push(format!("layout.external_bar.per_display[{uuid}] {issue}"));
+            }
+        }
+        issues
+    }
+}
+
+/// Layout defaults applied to a single display, e.g. an ultrawide monitor or a notched laptop panel
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayLayoutOverride {
+    /// Orientation of the primary (root) split for layouts on this display
+    #[serde(default)]
+    pub orientation: Option<Orientation>,
+    /// Extra padding reserved along the display edges, added on top of the outer gaps
+    #[serde(default)]
+    pub padding: Option<OuterGaps>,
+    /// Maximum number of tiled windows per workspace; further windows start floating
+    #[serde(default)]
+    pub max_tiled_windows: Option<usize>,
+    /// Ultrawide column mode: constrain tiling to this width, centered on the display
+    #[serde(default)]
+    pub max_width: Option<f64>,
+    /// With `max_width`, place secondary windows in columns beside the centered area
+    #[serde(default)]
+    pub side_columns: bool,
+    /// Names for the workspaces of this display, in order, used instead of
+    /// `virtual_workspaces.workspace_names` for workspaces that were not renamed
+    #[serde(default)]
+    pub workspace_names: Vec<String>,
+}
+
+/// Layout mode enum
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMode {
+    /// Traditional container-based tiling (i3/sway style)
+    #[default]
+    Traditional,
+    /// Binary space partitioning tiling
+    Bsp,
+    /// Dedicated stacked layout (single stack container)
+    Stack,
+    /// Master/stack layout (master area + stack area)
+    MasterStack,
+    /// Scrolling column layout (niri-style)
+    Scrolling,
+}
 
 impl ToString for LayoutMode {
     fn to_string(&self) -> String {
@@ -614,7 +2028,7 @@ impl ToString for LayoutMode {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ScrollingLayoutSettings {
     /// Whether to animate window transitions in this layout.
@@ -656,7 +2070,7 @@ impl Default for ScrollingLayoutSettings {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum MasterStackSide {
     #[default]
@@ -666,7 +2080,7 @@ pub enum MasterStackSide {
     Bottom,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ScrollingAlignment {
     Left,
@@ -675,7 +2089,7 @@ pub enum ScrollingAlignment {
     Right,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ScrollingFocusNavigationStyle {
     #[default]
@@ -683,7 +2097,7 @@ pub enum ScrollingFocusNavigationStyle {
     Anchored,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct MasterStackSettings {
     /// Fraction of space reserved for the master area (0.05..0.95)
@@ -700,7 +2114,7 @@ pub struct MasterStackSettings {
     pub new_window_placement: MasterStackNewWindowPlacement,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum MasterStackNewWindowPlacement {
     Master,
@@ -708,7 +2122,7 @@ pub enum MasterStackNewWindowPlacement {
     Focused,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub struct ScrollingGestureSettings {
     /// Enable horizontal scroll gestures to switch columns
@@ -748,7 +2162,7 @@ impl Default for ScrollingGestureSettings {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum StackDefaultOrientation {
     Perpendicular,
@@ -757,7 +2171,7 @@ pub enum StackDefaultOrientation {
     Vertical,
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct StackSettings {
     /// Stack offset - how much each stacked window is offset (in pixels)
@@ -777,7 +2191,7 @@ pub struct StackSettings {
 }
 
 /// Gap configuration for window spacing
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct GapSettings {
     /// Outer gaps (space between windows and screen edges)
@@ -792,7 +2206,7 @@ pub struct GapSettings {
 }
 
 /// Outer gap configuration (space between windows and screen edges)
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct OuterGaps {
     /// Gap at the top of the screen
@@ -810,7 +2224,7 @@ pub struct OuterGaps {
 }
 
 /// Inner gap configuration (space between windows)
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct InnerGaps {
     /// Horizontal gap between windows
@@ -822,7 +2236,7 @@ pub struct InnerGaps {
 }
 
 /// Overrides for gaps on a per-display basis
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Default)]
 #[serde(deny_unknown_fields)]
 pub struct GapOverride {
     /// Override outer gaps completely for the display
@@ -853,7 +2267,28 @@ impl Default for MasterStackSettings {
     }
 }
 
+pub const DEFAULT_PANIC_HOTKEY: &str = "Ctrl + Alt + Meta + Escape";
+
 impl Settings {
+    /// The parsed [`Settings::panic_hotkey`], or the built-in default if it does
+    /// not parse. Bad values are deliberately not a validation error, so a typo
+    /// can never leave rift without a way out.
+    pub fn panic_hotkey(&self) -> Hotkey {
+        let normalized = Config::normalize_hotkey_string(&self.panic_hotkey);
+        Hotkey::from_str(&normalized).unwrap_or_else(|e| {
+            tracing::warn!(
+                panic_hotkey = %self.panic_hotkey,
+                "Invalid panic_hotkey ({e}); using {DEFAULT_PANIC_HOTKEY}"
+            );
+            Self::default_panic_hotkey()
+        })
+    }
+
+    /// The hotkey bound to the panic switch when nothing else is configured.
+    pub fn default_panic_hotkey() -> Hotkey {
+        Hotkey::from_str(DEFAULT_PANIC_HOTKEY).expect("default panic hotkey parses")
+    }
+
     pub fn validate(&self) -> Vec<String> {
         let mut issues = Vec::new();
 
@@ -864,6 +2299,13 @@ impl Settings {
             ));
         }
 
+        if self.float_switch_animation.duration < 0.0 {
+            issues.push(format!(
+                "float_switch_animation.duration must be non-negative, got {}",
+                self.float_switch_animation.duration
+            ));
+        }
+
         if self.animation_fps <= 0.0 {
             issues.push(format!(
                 "animation_fps must be positive, got {}",
@@ -871,12 +2313,70 @@ impl Settings {
             ));
         }
 
-        issues.extend(self.layout.validate());
-
-        if self.gestures.swipe_vertical_tolerance < 0.0 {
+        issues.extend(self.layout.validate());
+
+        for name in self.focus_modes.keys() {
+            if name.trim().is_empty() {
+                issues.push("focus_modes contains an empty Focus name".to_string());
+            }
+        }
+
+        if self.gestures.swipe_vertical_tolerance < 0.0 {
+            issues.push(format!(
+                "gestures.swipe_vertical_tolerance must be non-negative, got {}",
+                self.gestures.swipe_vertical_tolerance
+            ));
+        }
+
+        issues.extend(self.ui.hints.validate());
+        issues.extend(self.ui.theme.validate());
+        issues.extend(self.titlebar.validate());
+        issues.extend(self.modifier_drag.validate());
+        issues.extend(self.logging.validate());
+        issues.extend(self.frame_audit.validate());
+        issues.extend(self.idle.validate());
+        issues.extend(self.update_check.validate());
+        issues.extend(self.power_profiles.validate());
+
+        issues
+    }
+}
+
+impl ThemeSettings {
+    pub fn validate(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+
+        for (name, dim) in [
+            ("overlay_dim", Some(self.overlay_dim)),
+            ("light.overlay_dim", self.light.overlay_dim),
+        ] {
+            if let Some(dim) = dim
+                && !(0.0..=1.0).contains(&dim)
+            {
+                issues.push(format!("ui.theme.{name} must be between 0.0 and 1.0, got {dim}"));
+            }
+        }
+
+        for (name, width) in [
+            ("border_width", self.border_width),
+            ("selected_border_width", self.selected_border_width),
+        ] {
+            if width < 0.0 {
+                issues.push(format!("ui.theme.{name} must be non-negative, got {width}"));
+            }
+        }
+
+        if self.blur_radius < 0 {
+            issues.push(format!(
+                "ui.theme.blur_radius must be non-negative, got {}",
+                self.blur_radius
+            ));
+        }
+
+        if self.font_size.is_nan() || self.font_size <= 0.0 {
             issues.push(format!(
-                "gestures.swipe_vertical_tolerance must be non-negative, got {}",
-                self.gestures.swipe_vertical_tolerance
+                "ui.theme.font_size must be positive, got {}",
+                self.font_size
             ));
         }
 
@@ -896,8 +2396,56 @@ impl LayoutSettings {
 
         issues.extend(self.scrolling.validate());
 
+        issues.extend(self.external_bar.validate());
+
+        issues.extend(self.auto_float.validate());
+
+        for (uuid, overrides) in &self.per_display {
+            if let Some(padding) = &overrides.padding {
+                for issue in padding.validate() {
+                    issues.push(format!("layout.per_display[{uuid}] padding: {issue}"));
+                }
+            }
+            if overrides.max_tiled_windows == Some(0) {
+                issues.push(format!(
+                    "layout.per_display[{uuid}] max_tiled_windows must be at least 1"
+                ));
+            }
+            if let Some(max_width) = overrides.max_width
+                && max_width <= 0.0
+            {
+                issues.push(format!(
+                    "layout.per_display[{uuid}] max_width must be positive, got {max_width}"
+                ));
+            }
+        }
+
         issues
     }
+
+    pub fn display_override(&self, display_uuid: Option<&str>) -> Option<&DisplayLayoutOverride> {
+        display_uuid.and_then(|uuid| self.per_display.get(uuid))
+    }
+
+    /// Resolves the gaps for a display, including per-display padding and external bar
+    /// reservations.
+    pub fn gaps_for_display(&self, display_uuid: Option<&str>) -> GapSettings {
+        let mut gaps = self.gaps.effective_for_display(display_uuid);
+        gaps.outer.add(&self.reserved_edges_for_display(display_uuid));
+        gaps
+    }
+
+    /// Display padding and bar reservations, which stay in place when smart gaps
+    /// drop the configured gaps.
+    pub fn reserved_edges_for_display(&self, display_uuid: Option<&str>) -> OuterGaps {
+        let mut edges = OuterGaps::default();
+        if let Some(padding) = self.display_override(display_uuid).and_then(|o| o.padding.as_ref())
+        {
+            edges.add(padding);
+        }
+        edges.add(&self.external_bar.reservation_for_display(display_uuid));
+        edges
+    }
 }
 
 impl ScrollingLayoutSettings {
@@ -1033,6 +2581,13 @@ impl GapSettings {
 }
 
 impl OuterGaps {
+    pub fn add(&mut self, other: &OuterGaps) {
+        self.top += other.top;
+        self.left += other.left;
+        self.bottom += other.bottom;
+        self.right += other.right;
+    }
+
     /// Validates outer gap configuration values and returns a list of issues found.
     pub fn validate(&self) -> Vec<String> {
         let mut issues = Vec::new();
@@ -1086,10 +2641,34 @@ impl InnerGaps {
     }
 }
 
+/// Sets the value at a dotted path such as `settings.ui.stack_line.enabled`,
+/// creating missing tables along the way.
+pub fn set_value_at_path(root: &mut Value, key: &str, value: Value) -> Result<(), String> {
+    let mut parts = key.split('.').peekable();
+    let mut cur = root;
+    while let Some(part) = parts.next() {
+        let Some(obj) = cur.as_object_mut() else {
+            return Err(format!("Invalid config path: {key}"));
+        };
+        if parts.peek().is_none() {
+            obj.insert(part.to_string(), value);
+            return Ok(());
+        }
+        cur = obj.entry(part).or_insert_with(|| serde_json::json!({}));
+    }
+    Err("Empty config key provided".to_string())
+}
+
 fn yes() -> bool { true }
 
 fn default_stack_offset() -> f64 { 40.0 }
 
+fn default_external_bar_owners() -> Vec<String> { vec!["sketchybar".to_string()] }
+
+fn default_auto_float_subroles() -> Vec<String> {
+    ["AXDialog", "AXSystemDialog", "AXFloatingWindow"].map(String::from).to_vec()
+}
+
 pub fn default_stack_orientation() -> StackDefaultOrientation {
     StackDefaultOrientation::Perpendicular
 }
@@ -1098,6 +2677,8 @@ fn default_master_stack_new_window_placement() -> MasterStackNewWindowPlacement
     MasterStackNewWindowPlacement::Master
 }
 
+fn default_panic_hotkey() -> String { DEFAULT_PANIC_HOTKEY.to_string() }
+
 fn default_animation_duration() -> f64 { 0.3 }
 
 fn default_animation_fps() -> f64 { 100.0 }
@@ -1105,6 +2686,22 @@ fn default_animation_fps() -> f64 { 100.0 }
 #[allow(dead_code)]
 fn no() -> bool { false }
 
+fn default_titlebar_height() -> f64 { 28.0 }
+
+fn default_log_max_file_mb() -> u64 { 10 }
+
+fn default_log_keep_files() -> usize { 3 }
+
+fn default_frame_audit_interval_secs() -> u64 { 10 }
+
+fn default_frame_audit_tolerance() -> f64 { 4.0 }
+
+fn default_frame_audit_max_corrections() -> usize { 3 }
+
+fn default_idle_poll_interval_secs() -> u64 { 5 }
+
+fn default_update_check_interval_hours() -> u64 { 24 }
+
 fn default_workspace_count() -> usize { 4 }
 
 fn default_workspace_names() -> Vec<String> {
@@ -1125,8 +2722,11 @@ fn default_overscroll_threshold() -> f64 { 0.625 }
 
 fn default_stack_line_spacing() -> f64 { 1.0 }
 fn default_stack_line_thickness() -> f64 { 20.0 }
+fn default_workspace_scroll_debounce_ms() -> u64 { 250 }
+fn default_float_switch_duration() -> f64 { 0.15 }
+fn default_float_switch_distance() -> f64 { 40.0 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, PartialEq, Clone, Copy, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum HapticPattern {
     Generic,
@@ -1135,7 +2735,31 @@ pub enum HapticPattern {
     LevelChange,
 }
 
+/// A command as the schema describes it. The commands themselves are checked
+/// when rift loads the file; the schema only pins down their shape.
+struct CommandSchema;
+
+impl JsonSchema for CommandSchema {
+    fn schema_name() -> Cow<'static, str> { "Command".into() }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "A command, either a name such as \"next_workspace\" or a table \
+                            with one key such as { move_focus = \"left\" }",
+            "type": ["string", "object"],
+            "minProperties": 1,
+            "maxProperties": 1,
+        })
+    }
+}
+
 impl Config {
+    /// JSON Schema for the config file, generated from the structs above, so
+    /// editors can complete and check it. Doc comments become descriptions.
+    pub fn json_schema() -> Value {
+        serde_json::to_value(schemars::schema_for!(ConfigFile)).unwrap_or_default()
+    }
+
     pub fn read(path: &Path) -> anyhow::Result<Config> {
         let buf = std::fs::read_to_string(path)?;
         Self::parse(&buf)
@@ -1170,6 +2794,30 @@ impl Config {
         Ok(())
     }
 
+    /// Looks up the `focus_modes` rule for the given Focus name, ignoring case.
+    pub fn focus_mode_rule(&self, focus_mode: Option<&str>) -> Option<&FocusModeRule> {
+        let focus_mode = focus_mode?;
+        self.settings
+            .focus_modes
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(focus_mode))
+            .map(|(_, rule)| rule)
+    }
+
+    /// Virtual workspace settings with the active Focus mode's overrides applied.
+    pub fn virtual_workspaces_for_focus_mode(
+        &self,
+        focus_mode: Option<&str>,
+    ) -> VirtualWorkspaceSettings {
+        let mut settings = self.virtual_workspaces.clone();
+        if let Some(index) =
+            self.focus_mode_rule(focus_mode).and_then(|rule| rule.default_workspace)
+        {
+            settings.default_workspace = index;
+        }
+        settings
+    }
+
     /// Validates the entire configuration and returns a list of issues found.
     pub fn validate(&self) -> Vec<String> {
         let mut issues = Vec::new();
@@ -1180,9 +2828,35 @@ impl Config {
         // Validate virtual workspace settings
         issues.extend(self.virtual_workspaces.validate());
 
+        // Profiles are only checked once they apply cleanly, so a broken
+        // profile is not reported again for every setting it touches.
+        if issues.is_empty() {
+            for source in [PowerSource::Ac, PowerSource::Battery] {
+                match self.with_power_profile(source) {
+                    Ok(config) => issues.extend(config.settings.validate()),
+                    Err(e) => issues.push(e),
+                }
+            }
+        }
+
         issues
     }
 
+    /// This config with the settings overrides for `source` applied.
+    pub fn with_power_profile(&self, source: PowerSource) -> Result<Config, String> {
+        let profile = self.settings.power_profiles.for_source(source);
+        if profile.is_empty() {
+            return Ok(self.clone());
+        }
+        let mut value = serde_json::to_value(self)
+            .map_err(|e| format!("Failed to serialize config for power profile: {e}"))?;
+        for (key, override_value) in profile {
+            set_value_at_path(&mut value, &format!("settings.{key}"), override_value.clone())?;
+        }
+        serde_json::from_value(value)
+            .map_err(|e| format!("power_profiles.{} does not apply: {e}", source.as_str()))
+    }
+
     fn normalize_hotkey_string(key: &str) -> String {
         let mut out = String::with_capacity(key.len());
         let mut word = String::new();
@@ -1240,7 +2914,7 @@ impl Config {
     }
 
     /// no need to pull in a dep for just this
-    fn levenshtein(a: &str, b: &str) -> usize {
+    pub(crate) fn levenshtein(a: &str, b: &str) -> usize {
         let a_chars: Vec<char> = a.chars().collect();
         let b_chars: Vec<char> = b.chars().collect();
         let mut d = vec![vec![0usize; b_chars.len() + 1]; a_chars.len() + 1];
@@ -1395,7 +3069,7 @@ impl Config {
         None
     }
 
-    fn parse(buf: &str) -> anyhow::Result<Config> {
+    pub(crate) fn parse(buf: &str) -> anyhow::Result<Config> {
         // Attempt to deserialize. If it fails, and the error indicates an unknown enum
         // variant, attempt to provide a helpful suggestion.
         match toml::from_str::<ConfigFile>(&buf) {
@@ -1446,6 +3120,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sys::hotkey::{KeyCode, Modifiers};
 
     #[test]
     fn test_normalize_hotkey_string() {
@@ -1496,4 +3171,628 @@ mod tests {
         let (s, _maybe_dep) = suggestion.unwrap();
         assert_eq!(s, "toggle_stack");
     }
+
+    #[test]
+    fn test_per_display_layout_overrides() {
+        let toml = r#"
+            [settings.layout.gaps.outer]
+            top = 10
+            left = 10
+            bottom = 10
+            right = 10
+
+            [settings.layout.per_display."DISPLAY-A"]
+            orientation = "vertical"
+            max_tiled_windows = 3
+
+            [settings.layout.per_display."DISPLAY-A".padding]
+            left = 200
+            right = 200
+        "#;
+
+        let cfg = Config::parse(toml).unwrap();
+        let layout = &cfg.settings.layout;
+        let overrides = layout.display_override(Some("DISPLAY-A")).unwrap();
+        assert_eq!(overrides.orientation, Some(Orientation::Vertical));
+        assert_eq!(overrides.max_tiled_windows, Some(3));
+
+        let gaps = layout.gaps_for_display(Some("DISPLAY-A"));
+        assert_eq!(gaps.outer.left, 210.0);
+        assert_eq!(gaps.outer.top, 10.0);
+        assert_eq!(layout.gaps_for_display(Some("DISPLAY-B")).outer.left, 10.0);
+        assert!(layout.display_override(None).is_none());
+    }
+
+    #[test]
+    fn test_focus_mode_overrides() {
+        let toml = r#"
+            [settings.focus_modes.Work]
+            default_workspace = 2
+            disable_hooks = true
+        "#;
+
+        let cfg = Config::parse(toml).unwrap();
+        let rule = cfg.focus_mode_rule(Some("work")).unwrap();
+        assert!(rule.disable_hooks);
+        assert_eq!(
+            cfg.virtual_workspaces_for_focus_mode(Some("Work")).default_workspace,
+            2
+        );
+        assert_eq!(
+            cfg.virtual_workspaces_for_focus_mode(Some("Sleep")).default_workspace,
+            cfg.virtual_workspaces.default_workspace
+        );
+        assert!(cfg.focus_mode_rule(None).is_none());
+    }
+
+    #[test]
+    fn test_new_window_focus_policy() {
+        let toml = r#"
+            [settings]
+            new_window_focus = "current_workspace"
+
+            [[virtual_workspaces.app_rules]]
+            app_id = "com.example.Chat"
+            new_window_focus = "never"
+        "#;
+
+        let cfg = Config::parse(toml).unwrap();
+        assert_eq!(
+            cfg.settings.new_window_focus,
+            Some(NewWindowFocus::CurrentWorkspace)
+        );
+        assert_eq!(
+            cfg.virtual_workspaces.app_rules[0].new_window_focus,
+            Some(NewWindowFocus::Never)
+        );
+
+        assert!(NewWindowFocus::Always.should_focus(false, false));
+        assert!(NewWindowFocus::CurrentWorkspace.should_focus(true, false));
+        assert!(!NewWindowFocus::CurrentWorkspace.should_focus(false, true));
+        assert!(NewWindowFocus::FocusedApp.should_focus(false, true));
+        assert!(!NewWindowFocus::FocusedApp.should_focus(true, false));
+        assert!(!NewWindowFocus::Never.should_focus(true, true));
+    }
+
+    #[test]
+    fn test_fullscreen_pause_lists() {
+        let mut settings = FullscreenPauseSettings::default();
+        assert!(!settings.applies_to(Some("com.example.Game")));
+
+        settings.enabled = true;
+        assert!(settings.applies_to(Some("com.example.Game")));
+        assert!(settings.applies_to(None));
+
+        settings.deny = vec!["com.apple.Safari".into()];
+        assert!(!settings.applies_to(Some("com.apple.safari")));
+
+        settings.allow = vec!["com.example.Game".into()];
+        assert!(settings.applies_to(Some("com.example.Game")));
+        assert!(!settings.applies_to(Some("com.example.Player")));
+        assert!(!settings.applies_to(None));
+
+        let settings = FullscreenPauseSettings {
+            menu_bar_owners: vec!["us.zoom.xos".into()],
+            ..FullscreenPauseSettings::default()
+        };
+        assert!(settings.pauses_for_menu_bar_owner(Some("us.zoom.xos")));
+        assert!(!settings.pauses_for_menu_bar_owner(Some("com.example.Game")));
+        assert!(!settings.pauses_for_menu_bar_owner(None));
+    }
+
+    #[test]
+    fn test_display_activation_patterns() {
+        let rules = DisplayActivationRules {
+            disable: vec!["sidecar*".into(), "SAMSUNG ?V".into()],
+            enable: vec!["*".into()],
+        };
+        assert_eq!(
+            rules.default_disable_for(Some("Sidecar Display (AirPlay)"), "A"),
+            Some(true)
+        );
+        assert_eq!(rules.default_disable_for(Some("Samsung TV"), "B"), Some(true));
+        assert_eq!(
+            rules.default_disable_for(Some("Samsung Monitor"), "C"),
+            Some(false)
+        );
+        assert_eq!(rules.default_disable_for(None, "37D8832A-2D66"), Some(false));
+
+        let by_uuid = DisplayActivationRules {
+            disable: vec!["37D8832A-*".into()],
+            enable: vec![],
+        };
+        assert_eq!(
+            by_uuid.default_disable_for(Some("DELL"), "37d8832a-2d66"),
+            Some(true)
+        );
+        assert_eq!(by_uuid.default_disable_for(Some("DELL"), "11111111"), None);
+        assert!(!glob_matches("a*b", "acbx"));
+        assert!(glob_matches("a*b*", "acbx"));
+    }
+
+    #[test]
+    fn test_theme_colors() {
+        let toml = r##"
+            [settings.ui.theme]
+            accent_color = "#FF8000"
+            tile_background = "#00000080"
+            overlay_dim = 0.5
+        "##;
+
+        let cfg = Config::parse(toml).unwrap();
+        let theme = &cfg.settings.ui.theme;
+        assert_eq!(theme.accent_color, ThemeColor::new(1.0, 128.0 / 255.0, 0.0, 1.0));
+        assert_eq!(theme.tile_background.a, 128.0 / 255.0);
+        assert_eq!(theme.border_color, ThemeSettings::default().border_color);
+        assert_eq!(String::from(theme.accent_color), "#FF8000FF");
+
+        let light = theme.palette(Appearance::Light);
+        assert_eq!(light.accent_color, theme.accent_color);
+        assert_ne!(light.tile_background, theme.tile_background);
+        assert_eq!(theme.palette(Appearance::Dark), *theme);
+        assert_eq!(theme.effective_appearance(Appearance::Light), Appearance::Light);
+
+        assert!("#12345".parse::<ThemeColor>().is_err());
+        assert!("#GG0000".parse::<ThemeColor>().is_err());
+        let dim = Config::parse("[settings.ui.theme]\noverlay_dim = 2.0").unwrap();
+        assert_eq!(dim.settings.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_panic_hotkey_falls_back_when_invalid() {
+        let default = Config::parse("").unwrap();
+        assert_eq!(default.settings.panic_hotkey(), Settings::default_panic_hotkey());
+
+        let custom = Config::parse("[settings]\npanic_hotkey = \"alt + f12\"").unwrap();
+        assert_eq!(
+            custom.settings.panic_hotkey(),
+            Hotkey::new(Modifiers::ALT, KeyCode::F12)
+        );
+
+        let invalid = Config::parse("[settings]\npanic_hotkey = \"Ctrl + Nonsense\"").unwrap();
+        assert!(invalid.validate().is_empty());
+        assert_eq!(invalid.settings.panic_hotkey(), Settings::default_panic_hotkey());
+    }
+
+    #[test]
+    fn test_enum_settings() {
+        // Each setting with its default and another value it accepts.
+        let cases = [
+            ("restore_frames_on_exit", "keep", "only_floats"),
+            ("other_window_manager", "warn", "wait"),
+            ("hotkey_resolution", "physical", "character"),
+            ("hidden_workspace_activation", "switch", "pull"),
+            ("workspace_hide_method", "move", "order_out"),
+            ("directional_focus", "floating", "tree"),
+        ];
+        let setting =
+            |cfg: &Config, key: &str| serde_json::to_value(&cfg.settings).unwrap()[key].clone();
+        let default = Config::parse("").unwrap();
+        for (key, default_value, value) in cases {
+            assert_eq!(setting(&default, key), default_value, "default {key}");
+            let cfg = Config::parse(&format!("[settings]\n{key} = \"{value}\"")).unwrap();
+            assert_eq!(setting(&cfg, key), value, "{key}");
+            let bad = format!("[settings]\n{key} = \"nonsense\"");
+            assert!(Config::parse(&bad).is_err(), "{key} accepted nonsense");
+        }
+    }
+
+    #[test]
+    fn test_auto_float_heuristics() {
+        let default = Config::parse("").unwrap().settings.layout.auto_float;
+        assert_eq!(default, AutoFloatSettings::default());
+        assert_eq!(
+            default.reason(Some("AXDialog"), true, 800.0, 600.0),
+            Some(AutoFloatReason::Subrole)
+        );
+        assert_eq!(
+            default.reason(Some("AXStandardWindow"), false, 100.0, 100.0),
+            None
+        );
+
+        let toml = "[settings.layout.auto_float]\nnon_resizable = true\nmin_width = 300";
+        let custom = Config::parse(toml).unwrap().settings.layout.auto_float;
+        let standard = Some("AXStandardWindow");
+        assert_eq!(
+            custom.reason(standard, false, 800.0, 600.0),
+            Some(AutoFloatReason::NonResizable)
+        );
+        assert_eq!(
+            custom.reason(standard, true, 200.0, 600.0),
+            Some(AutoFloatReason::TooSmall)
+        );
+        assert_eq!(custom.reason(standard, true, 300.0, 600.0), None);
+
+        let off = Config::parse("[settings.layout.auto_float]\nenabled = false").unwrap();
+        assert_eq!(
+            off.settings.layout.auto_float.reason(Some("AXDialog"), true, 1.0, 1.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_exec_with_placement() {
+        use crate::actor::reactor::DisplaySelector;
+        use crate::actor::wm_controller::{ExecCmd, PlacedExecCmd, WmCmd};
+
+        let toml = r#"
+            [keys]
+            "Alt + Enter" = { exec = "open -a Terminal" }
+            [keys."Alt + Shift + Enter".exec]
+            command = ["open", "-na", "Terminal"]
+            workspace = "code"
+            float = true
+            display = 1
+        "#;
+        let cfg = Config::parse(toml).unwrap();
+        let execs: Vec<&ExecCmd> = cfg
+            .keys
+            .iter()
+            .filter_map(|(_, cmd)| match cmd {
+                WmCommand::Wm(WmCmd::Exec(exec)) => Some(exec),
+                _ => None,
+            })
+            .collect();
+        assert!(execs.contains(&&ExecCmd::String("open -a Terminal".into())));
+        let command = ["open", "-na", "Terminal"].map(String::from).to_vec();
+        let placed = ExecCmd::Placed(PlacedExecCmd {
+            command: Box::new(ExecCmd::Array(command)),
+            workspace: Some(WorkspaceSelector::Name("code".into())),
+            floating: true,
+            display: Some(DisplaySelector::Index(1)),
+        });
+        assert!(execs.contains(&&placed));
+
+        let typo = r#"
+            [keys]
+            "Alt + Enter" = { exec = { command = "open -a Terminal", floating = true } }
+        "#;
+        assert!(Config::parse(typo).is_err());
+    }
+
+    #[test]
+    fn test_hint_alphabet() {
+        let default = Config::parse("").unwrap().settings.ui.hints;
+        assert_eq!(default.alphabet, "asdfghjkl");
+        assert!(default.validate().is_empty());
+
+        let cfg = Config::parse("[settings.ui.hints]\nalphabet = \"JKL\"").unwrap();
+        assert!(cfg.settings.ui.hints.validate().is_empty());
+        let bad = HintSettings { alphabet: "aA".to_string() };
+        assert_eq!(bad.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_pointer_settings() {
+        let default = Config::parse("").unwrap();
+        assert!(!default.settings.pointer.hide_while_typing);
+        assert_eq!(default.settings.pointer.banish_corner, ScreenCorner::BottomRight);
+
+        let toml = r#"
+            [settings.pointer]
+            hide_while_typing = true
+            banish_corner = "top_left"
+            exclude = ["com.adobe.Photoshop"]
+        "#;
+        let pointer = Config::parse(toml).unwrap().settings.pointer;
+        assert!(pointer.hide_while_typing);
+        assert_eq!(pointer.banish_corner, ScreenCorner::TopLeft);
+        assert!(!pointer.hides_in(Some("com.adobe.photoshop")));
+        assert!(pointer.hides_in(Some("com.apple.Terminal")));
+        assert!(pointer.hides_in(None));
+    }
+
+    #[test]
+    fn test_projects() {
+        let toml = r#"
+            [[virtual_workspaces.projects]]
+            name = "web"
+            layout = "bsp"
+            apps = ["open -na Terminal", { command = "open -a Safari", float = true }]
+
+            [[virtual_workspaces.projects]]
+            name = "notes"
+        "#;
+        let cfg = Config::parse(toml).unwrap();
+        let projects = &cfg.virtual_workspaces.projects;
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0].layout, Some(LayoutMode::Bsp));
+        assert_eq!(projects[0].apps.len(), 2);
+        assert!(matches!(projects[0].apps[1], ExecCmd::Placed(ref placed) if placed.floating));
+        assert!(projects[1].apps.is_empty());
+        assert!(cfg.virtual_workspaces.validate().is_empty());
+
+        let duplicate = r#"
+            [[virtual_workspaces.projects]]
+            name = "web"
+            [[virtual_workspaces.projects]]
+            name = "web"
+        "#;
+        let duplicate = Config::parse(duplicate).unwrap();
+        assert_eq!(duplicate.virtual_workspaces.validate().len(), 1);
+    }
+
+    #[test]
+    fn json_schema_covers_the_default_config() {
+        let schema = Config::json_schema();
+        let defs = &schema["$defs"];
+        let default: toml::Table = toml::from_str(include_str!("../../rift.default.toml")).unwrap();
+        let sections = [
+            ("settings", "Settings"),
+            ("virtual_workspaces", "VirtualWorkspaceSettings"),
+        ];
+        for (section, def) in sections {
+            let properties = &defs[def]["properties"];
+            for key in default[section].as_table().unwrap().keys() {
+                let known = properties.get(key).is_some();
+                assert!(known, "{section}.{key} missing from schema");
+            }
+        }
+        assert_eq!(defs["ThemeColor"]["type"], "string");
+        assert!(schema["properties"]["keys"].is_object());
+    }
+
+    #[test]
+    fn test_titlebar_clicks() {
+        let toml = r#"
+            [settings.titlebar.clicks]
+            "double_click" = "toggle_zoom"
+            "Alt + click" = "toggle_float"
+        "#;
+        let bindings = Config::parse(toml).unwrap().settings.titlebar.bindings();
+        let click = |modifiers, double| TitlebarClick {
+            modifiers,
+            right: false,
+            double,
+        };
+        assert_eq!(
+            bindings.get(&click(Modifiers::empty(), true)),
+            Some(&TitlebarAction::ToggleZoom)
+        );
+        assert_eq!(
+            bindings.get(&click(Modifiers::ALT, false)),
+            Some(&TitlebarAction::ToggleFloat)
+        );
+        assert_eq!(
+            "lalt + right_click".parse::<TitlebarClick>().unwrap().modifiers,
+            Modifiers::ALT
+        );
+        for bad in ["click", "triple_click", "Alt", "click + double_click"] {
+            assert!(bad.parse::<TitlebarClick>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_modifier_drag() {
+        let drag = Config::parse("").unwrap().settings.modifier_drag;
+        assert_eq!(drag.move_modifiers(), None);
+        let toml = r#"
+            [settings.modifier_drag]
+            modifier = "lalt"
+            resize_modifier = "Ctrl + Alt"
+        "#;
+        let drag = Config::parse(toml).unwrap().settings.modifier_drag;
+        assert_eq!(drag.move_modifiers(), Some(Modifiers::ALT));
+        let mut ctrl_alt = Modifiers::CONTROL;
+        ctrl_alt.insert(Modifiers::ALT);
+        assert_eq!(drag.resize_modifiers(), Some(ctrl_alt));
+        assert!(drag.validate().is_empty());
+
+        let clash = ModifierDragSettings {
+            modifier: Some("alt".into()),
+            resize_modifier: Some("ralt".into()),
+        };
+        assert_eq!(clash.validate().len(), 1);
+        let bad = ModifierDragSettings {
+            modifier: Some("Alt + a".into()),
+            resize_modifier: None,
+        };
+        assert_eq!(bad.move_modifiers(), None);
+        assert_eq!(bad.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_logging() {
+        let logging = Config::parse("").unwrap().settings.logging;
+        assert_eq!(logging, LoggingSettings::default());
+        let toml = r#"
+            [settings.logging]
+            levels = { reactor = "debug", layout = "off" }
+            files = ["reactor", "animation"]
+            max_file_mb = 5
+        "#;
+        let logging = Config::parse(toml).unwrap().settings.logging;
+        assert_eq!(logging.level(LogModule::Reactor), Some(LevelFilter::DEBUG));
+        assert_eq!(logging.level(LogModule::Layout), Some(LevelFilter::OFF));
+        assert_eq!(logging.level(LogModule::Ipc), None);
+        assert_eq!(logging.files, vec![LogModule::Reactor, LogModule::Animation]);
+        assert!(logging.validate().is_empty());
+
+        let mut bad = LoggingSettings::default();
+        bad.levels.insert("windows".into(), "debug".into());
+        bad.levels.insert("ipc".into(), "loud".into());
+        assert_eq!(bad.validate().len(), 2);
+    }
+
+    #[test]
+    fn test_frame_audit() {
+        let audit = Config::parse("").unwrap().settings.frame_audit;
+        assert_eq!(audit, FrameAuditSettings::default());
+        assert!(!audit.enabled);
+        let toml = r#"
+            [settings.frame_audit]
+            enabled = true
+            interval_secs = 30
+            tolerance = 2.0
+        "#;
+        let audit = Config::parse(toml).unwrap().settings.frame_audit;
+        assert!(audit.enabled);
+        assert_eq!(audit.interval(), Duration::from_secs(30));
+        assert_eq!(audit.tolerance, 2.0);
+        assert_eq!(audit.max_corrections, 3);
+        assert!(audit.validate().is_empty());
+
+        let bad = FrameAuditSettings {
+            interval_secs: 0,
+            tolerance: -1.0,
+            ..FrameAuditSettings::default()
+        };
+        assert_eq!(bad.validate().len(), 2);
+    }
+
+    #[test]
+    fn test_idle_rules() {
+        use crate::actor::wm_controller::WmCmd;
+
+        let idle = Config::parse("").unwrap().settings.idle;
+        assert_eq!(idle, IdleSettings::default());
+        let toml = r#"
+            [[settings.idle.rules]]
+            after_secs = 600
+            on_idle = [{ switch_to_workspace = 3 }]
+            on_resume = ["switch_to_last_workspace"]
+        "#;
+        let idle = Config::parse(toml).unwrap().settings.idle;
+        assert_eq!(idle.poll_interval(), Duration::from_secs(5));
+        let rule = &idle.rules[0];
+        assert_eq!(rule.after_secs, 600);
+        let switch = WmCmd::SwitchToWorkspace(WorkspaceSelector::Index(3));
+        assert_eq!(rule.on_idle, [WmCommand::Wm(switch)]);
+        assert_eq!(rule.on_resume, [WmCommand::Wm(WmCmd::SwitchToLastWorkspace)]);
+        assert!(idle.validate().is_empty());
+
+        let bad = IdleSettings {
+            poll_interval_secs: 900,
+            ..idle
+        };
+        assert_eq!(bad.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_workspace_grid() {
+        use crate::actor::reactor::Command;
+        use crate::layout_engine::{Direction, LayoutCommand};
+
+        let toml = r#"
+            [virtual_workspaces]
+            default_workspace_count = 9
+            grid_columns = 3
+
+            [keys]
+            "Ctrl + Alt + Up" = { focus_workspace = "up" }
+        "#;
+        let config = Config::parse(toml).unwrap();
+        assert_eq!(config.virtual_workspaces.grid_columns, Some(3));
+        assert!(config.virtual_workspaces.validate().is_empty());
+        let command = &config.keys[0].1;
+        let focus = LayoutCommand::FocusWorkspace(Direction::Up);
+        assert_eq!(*command, WmCommand::ReactorCommand(Command::Layout(focus)));
+
+        let bad = VirtualWorkspaceSettings {
+            grid_columns: Some(0),
+            ..VirtualWorkspaceSettings::default()
+        };
+        assert_eq!(bad.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_window_server_only_rules() {
+        let toml = r#"
+            [virtual_workspaces]
+            app_rules = [
+                { app_id = "com.example.legacy", window_server_only = true },
+                { app_name = "Emulator", window_server_only = true },
+                { app_id = "com.example.modern" },
+            ]
+        "#;
+        let config = Config::parse(toml).unwrap();
+        let settings = &config.virtual_workspaces;
+        assert!(settings.validate().is_empty());
+        assert!(settings.window_server_only_for(Some("com.example.legacy"), None));
+        assert!(settings.window_server_only_for(None, Some("Game Emulator")));
+        assert!(!settings.window_server_only_for(Some("com.example.modern"), Some("Modern")));
+
+        let bad = VirtualWorkspaceSettings {
+            app_rules: vec![AppWorkspaceRule {
+                app_id: None,
+                title_substring: Some("Untitled".into()),
+                window_server_only: true,
+                ..settings.app_rules[2].clone()
+            }],
+            ..VirtualWorkspaceSettings::default()
+        };
+        assert_eq!(bad.validate().len(), 1);
+    }
+
+    #[test]
+    fn test_update_check_interval_saturates() {
+        let settings = UpdateCheckSettings {
+            enabled: true,
+            interval_hours: u64::MAX,
+        };
+        assert_eq!(settings.interval(), Duration::from_secs(u64::MAX));
+        assert_eq!(
+            UpdateCheckSettings::default().interval(),
+            Duration::from_secs(24 * 3600)
+        );
+    }
+
+    #[test]
+    fn test_power_profiles() {
+        let toml = r#"
+            [settings]
+            animation_fps = 120
+
+            [settings.ui.stack_line]
+            enabled = true
+
+            [settings.power_profiles.battery]
+            animation_fps = 30
+            "ui.stack_line.enabled" = false
+        "#;
+        let config = Config::parse(toml).unwrap();
+        assert!(config.validate().is_empty());
+        let ac = config.with_power_profile(PowerSource::Ac).unwrap();
+        assert_eq!(ac.settings, config.settings);
+        let battery = config.with_power_profile(PowerSource::Battery).unwrap();
+        assert_eq!(battery.settings.animation_fps, 30.0);
+        assert!(!battery.settings.ui.stack_line.enabled);
+        assert_eq!(battery.settings.power_profiles, config.settings.power_profiles);
+
+        let bad = "[settings.power_profiles.ac]\nanimation_fps = -1";
+        assert_eq!(Config::parse(bad).unwrap().validate().len(), 1);
+        let bad = "[settings.power_profiles.battery]\n\"power_profiles.ac\" = {}";
+        assert!(!Config::parse(bad).unwrap().validate().is_empty());
+    }
+
+    #[test]
+    fn test_fallback_display() {
+        assert_eq!(Config::parse("").unwrap().settings.fallback_display, None);
+        let cfg = Config::parse("[settings]\nfallback_display = 1").unwrap();
+        assert_eq!(cfg.settings.fallback_display, Some(DisplaySelector::Index(1)));
+        let cfg = Config::parse("[settings]\nfallback_display = \"left\"").unwrap();
+        assert_eq!(
+            cfg.settings.fallback_display,
+            Some(DisplaySelector::Direction(crate::layout_engine::Direction::Left))
+        );
+    }
+
+    #[test]
+    fn test_stack_line_placement_overrides() {
+        let cfg = Config::parse(
+            r#"
+            [[virtual_workspaces.workspace_rules]]
+            workspace = "docs"
+            stack_line_horiz_placement = "bottom"
+            stack_line_inside = true
+            "#,
+        )
+        .unwrap();
+        let rule = &cfg.virtual_workspaces.workspace_rules[0];
+        let bottom = Some(HorizontalPlacement::Bottom);
+        assert_eq!(rule.stack_line_horiz_placement, bottom);
+        assert_eq!(rule.stack_line_vert_placement, None);
+        assert_eq!(rule.stack_line_inside, Some(true));
+        assert!(!cfg.settings.ui.stack_line.inside);
+    }
 }
@@ -0,0 +1,361 @@
+//! Checks a config file and points at the offending text, for `rift-cli config validate`,
+//! startup and editors talking to rift over IPC.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use toml::Spanned;
+
+use super::{Config, ConfigFile};
+use crate::common::collections::HashMap;
+use crate::sys::hotkey::Hotkey;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Rift refuses to load the config.
+    Error,
+    /// The config loads, but part of it will not do what it says.
+    Warning,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// 1-based line of the offending text, when it could be located.
+    pub line: Option<usize>,
+    /// 1-based column, counted in characters.
+    pub column: Option<usize>,
+    pub suggestion: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub path: Option<PathBuf>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ValidationReport {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.severity == Severity::Error)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let file = self.path.as_deref().map_or("<config>".into(), Path::to_string_lossy);
+        for d in &self.diagnostics {
+            write!(f, "{file}")?;
+            if let (Some(line), Some(column)) = (d.line, d.column) {
+                write!(f, ":{line}:{column}")?;
+            }
+            let severity = match d.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            writeln!(f, ": {severity}: {}", d.message)?;
+            if let Some(suggestion) = &d.suggestion {
+                writeln!(f, "  help: did you mean `{suggestion}`?")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads and checks the config at `path`.
+pub fn validate_file(path: &Path) -> ValidationReport {
+    let diagnostics = match std::fs::read_to_string(path) {
+        Ok(source) => validate_source(&source),
+        Err(e) => vec![Diagnostic::error(format!("cannot read config: {e}"))],
+    };
+    ValidationReport {
+        path: Some(path.to_path_buf()),
+        diagnostics,
+    }
+}
+
+/// Checks a config's text. Syntax and type errors stop at the first one, as
+/// the parser does; everything after that is reported in full.
+pub fn validate_source(source: &str) -> Vec<Diagnostic> {
+    if let Err(e) = toml::from_str::<toml::Table>(source) {
+        return vec![Diagnostic::from_toml(source, &e)];
+    }
+
+    let mut diagnostics = Vec::new();
+    if let Ok(located) = toml::from_str::<Located>(source) {
+        check_key_bindings(source, &located, &mut diagnostics);
+        check_app_rule_conflicts(source, &located, &mut diagnostics);
+    }
+
+    if let Err(e) = toml::from_str::<ConfigFile>(source) {
+        diagnostics.insert(0, Diagnostic::from_toml(source, &e));
+        return diagnostics;
+    }
+
+    if let Ok(config) = Config::parse(source) {
+        diagnostics.extend(config.validate().into_iter().map(Diagnostic::error));
+    }
+    diagnostics.sort_by_key(|d| d.line.unwrap_or(usize::MAX));
+    diagnostics
+}
+
+/// The parts of the file the checks below need, with their positions.
+#[derive(Deserialize)]
+struct Located {
+    #[serde(default)]
+    keys: BTreeMap<Spanned<String>, toml::Value>,
+    #[serde(default)]
+    modifier_combinations: HashMap<String, String>,
+    #[serde(default)]
+    virtual_workspaces: LocatedWorkspaces,
+}
+
+#[derive(Deserialize, Default)]
+struct LocatedWorkspaces {
+    #[serde(default)]
+    app_rules: Vec<Spanned<toml::Table>>,
+}
+
+const RULE_MATCHERS: [&str; 6] = [
+    "app_id",
+    "app_name",
+    "title_regex",
+    "title_substring",
+    "ax_role",
+    "ax_subrole",
+];
+
+fn check_key_bindings(source: &str, located: &Located, out: &mut Vec<Diagnostic>) {
+    let mut bound: HashMap<Hotkey, Range<usize>> = HashMap::default();
+    for key in located.keys.keys() {
+        let expanded =
+            Config::expand_modifier_combinations(key.get_ref(), &located.modifier_combinations);
+        let normalized = Config::normalize_hotkey_string(&expanded);
+        match Hotkey::from_str(&normalized) {
+            Ok(hotkey) => {
+                if let Some(first) = bound.get(&hotkey) {
+                    let (line, _) = line_column(source, first.start);
+                    let message = format!(
+                        "`{}` is the same key as the binding on line {line}; \
+                         only one of them takes effect",
+                        key.get_ref()
+                    );
+                    out.push(Diagnostic::warning(message).at(source, key.span()));
+                } else {
+                    bound.insert(hotkey, key.span());
+                }
+            }
+            Err(e) => {
+                let message = format!("invalid key binding `{}`: {e}", key.get_ref());
+                let suggestion = suggest_combination(key.get_ref(), located);
+                let mut diagnostic = Diagnostic::error(message).at(source, key.span());
+                diagnostic.suggestion = suggestion;
+                out.push(diagnostic);
+            }
+        }
+    }
+}
+
+/// For `lead + H` with a `leader` combination defined, suggests `leader + H`.
+fn suggest_combination(key: &str, located: &Located) -> Option<String> {
+    let (prefix, rest) = key.split_once(" + ")?;
+    let (best, dist) = located
+        .modifier_combinations
+        .keys()
+        .map(|name| (name, Config::levenshtein(prefix, name)))
+        .min_by_key(|&(_, dist)| dist)?;
+    (dist > 0 && dist <= 2).then(|| format!("{best} + {rest}"))
+}
+
+/// Rules are picked by how specific their matchers are, so two rules with the
+/// same matchers but different outcomes leave one of them unused.
+fn check_app_rule_conflicts(source: &str, located: &Located, out: &mut Vec<Diagnostic>) {
+    let rules = &located.virtual_workspaces.app_rules;
+    for (index, rule) in rules.iter().enumerate() {
+        let own = matchers(rule.get_ref());
+        if own.iter().all(Option::is_none) {
+            continue;
+        }
+        let Some(earlier) = rules[..index].iter().position(|other| {
+            matchers(other.get_ref()) == own && other.get_ref() != rule.get_ref()
+        }) else {
+            continue;
+        };
+        let message = format!(
+            "app rule {index} matches the same windows as app rule {earlier} \
+             but does something different; only one of them applies"
+        );
+        out.push(Diagnostic::warning(message).at(source, rule.span()));
+    }
+}
+
+fn matchers(rule: &toml::Table) -> [Option<&toml::Value>; 6] {
+    RULE_MATCHERS.map(|key| rule.get(key))
+}
+
+impl Diagnostic {
+    fn error(message: String) -> Self { Self::new(Severity::Error, message) }
+
+    fn warning(message: String) -> Self { Self::new(Severity::Warning, message) }
+
+    fn new(severity: Severity, message: String) -> Self {
+        Self {
+            severity,
+            message,
+            line: None,
+            column: None,
+            suggestion: None,
+        }
+    }
+
+    fn at(mut self, source: &str, span: Range<usize>) -> Self {
+        let (line, column) = line_column(source, span.start);
+        self.line = Some(line);
+        self.column = Some(column);
+        self
+    }
+
+    fn from_toml(source: &str, err: &toml::de::Error) -> Self {
+        let message = err.message().trim_end().to_string();
+        let mut diagnostic = Self::error(message.clone());
+        if let Some(span) = err.span() {
+            diagnostic = diagnostic.at(source, span);
+        }
+        diagnostic.suggestion = suggest_from_message(&message);
+        diagnostic
+    }
+}
+
+/// Picks the closest name from the candidates serde lists after an unknown
+/// field or variant.
+fn suggest_from_message(message: &str) -> Option<String> {
+    if message.starts_with("unknown field") {
+        let mut tokens = backticked(message);
+        let unknown = tokens.next()?;
+        let (best, dist) = tokens
+            .map(|cand| (cand, Config::levenshtein(unknown, cand)))
+            .min_by_key(|&(_, dist)| dist)?;
+        return (dist <= (best.len() / 2).max(3)).then(|| best.to_string());
+    }
+    let unknown = Config::extract_unknown_variant(message)?;
+    let (suggestion, replacement) = Config::suggest_similar_command(&unknown)?;
+    Some(replacement.unwrap_or(suggestion))
+}
+
+fn backticked(message: &str) -> impl Iterator<Item = &str> { message.split('`').skip(1).step_by(2) }
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset.min(source.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+[settings]
+
+[keys]
+"Alt + H" = { move_focus = "left" }
+"#;
+
+    fn with(extra: &str) -> String { format!("{BASE}{extra}") }
+
+    #[test]
+    fn default_config_is_clean() {
+        let diagnostics = validate_source(include_str!("../../../rift.default.toml"));
+        assert_eq!(diagnostics, vec![]);
+    }
+
+    #[test]
+    fn unknown_settings_key_is_located_and_suggested() {
+        let source = BASE.replace("[settings]", "[settings]\nanimte = true");
+        let diagnostics = validate_source(&source);
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!(d.severity, Severity::Error);
+        assert!(d.message.contains("animte"), "{}", d.message);
+        assert_eq!((d.line, d.column), (Some(3), Some(1)));
+        assert_eq!(d.suggestion.as_deref(), Some("animate"));
+    }
+
+    #[test]
+    fn type_mismatch_points_at_the_value() {
+        let source = BASE.replace("[settings]", "[settings]\nanimate = \"sometimes\"");
+        let diagnostics = validate_source(&source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(3));
+        assert_eq!(diagnostics[0].column, Some(11));
+    }
+
+    #[test]
+    fn every_bad_key_binding_is_reported() {
+        let source = with(
+            r#"
+"Alt + Nope" = "next_workspace"
+"Hyperr + J" = "next_workspace"
+"#,
+        );
+        let diagnostics = validate_source(&source);
+        let lines: Vec<_> = diagnostics.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![Some(7), Some(8)]);
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+    }
+
+    #[test]
+    fn modifier_combination_typo_is_suggested() {
+        let source = format!(
+            "{}\n[modifier_combinations]\nleader = \"Ctrl + Alt\"\n",
+            with("\"lead + J\" = \"next_workspace\"\n")
+        );
+        let diagnostics = validate_source(&source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].suggestion.as_deref(), Some("leader + J"));
+    }
+
+    #[test]
+    fn the_same_key_bound_twice_is_a_warning() {
+        let source = with("\"alt + h\" = \"next_workspace\"\n");
+        let diagnostics = validate_source(&source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn conflicting_app_rules_are_reported_at_the_later_rule() {
+        let source = with(
+            r#"
+[[virtual_workspaces.app_rules]]
+app_id = "com.example.app"
+title_substring = "Preferences"
+floating = true
+
+[[virtual_workspaces.app_rules]]
+app_id = "com.example.app"
+title_substring = "Preferences"
+workspace = 1
+"#,
+        );
+        let diagnostics = validate_source(&source);
+        let conflicts: Vec<_> =
+            diagnostics.iter().filter(|d| d.message.contains("same windows")).collect();
+        assert_eq!(conflicts.len(), 1);
+        let line = conflicts[0].line.unwrap();
+        assert!(line >= 12, "reported at line {line}");
+    }
+
+    #[test]
+    fn syntax_errors_are_located() {
+        let diagnostics = validate_source("[settings\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(1));
+    }
+}
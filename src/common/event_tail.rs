@@ -0,0 +1,135 @@
+//! Filtering and formatting the live event stream behind `rift-cli events tail`.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use nix::libc;
+use serde_json::{Value, json};
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+const BOLD: &str = "\x1b[1m";
+
+/// Whether an event name passes the `--filter` patterns.
+///
+/// A pattern is an event name, or a prefix ending in `*` such as `window_*`.
+/// An event passes if any pattern matches it, or if there are no patterns.
+pub fn matches(filters: &[String], event_type: &str) -> bool {
+    filters.is_empty()
+        || filters.iter().any(|filter| match filter.strip_suffix('*') {
+            Some(prefix) => event_type.starts_with(prefix),
+            None => filter == event_type,
+        })
+}
+
+/// The event's `type` field, or an empty string for payloads without one.
+pub fn event_type(event: &Value) -> &str { event["type"].as_str().unwrap_or_default() }
+
+/// Milliseconds since the Unix epoch.
+pub fn unix_millis(now: SystemTime) -> u64 {
+    now.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Formats the local time of day as `HH:MM:SS.mmm`.
+pub fn clock(now: SystemTime) -> String {
+    let millis = unix_millis(now);
+    let secs = (millis / 1000) as libc::time_t;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&secs, &mut tm) }.is_null() {
+        // Fall back to UTC rather than failing the whole tail over a clock.
+        let day_secs = millis / 1000 % 86_400;
+        tm.tm_hour = (day_secs / 3600) as i32;
+        tm.tm_min = (day_secs / 60 % 60) as i32;
+        tm.tm_sec = (day_secs % 60) as i32;
+    }
+    let (hour, min, sec, millis) = (tm.tm_hour, tm.tm_min, tm.tm_sec, millis % 1000);
+    format!("{hour:02}:{min:02}:{sec:02}.{millis:03}")
+}
+
+/// The line printed for an event with `--json`.
+pub fn json_line(time_ms: u64, event: Value) -> Value {
+    json!({ "time_ms": time_ms, "event": event })
+}
+
+/// The line printed for an event by default: the time, the event type and
+/// then every other field as `key=value`.
+///
+/// Strings are printed bare and nested values as compact JSON. With `color`
+/// the time and keys are dimmed and the type is colored by what it is about.
+pub fn pretty_line(clock: &str, event: &Value, color: bool) -> String {
+    let event_type = event_type(event);
+    let mut line = String::new();
+    if color {
+        let type_color = type_color(event_type);
+        let _ = write!(line, "{DIM}{clock}{RESET} {BOLD}{type_color}{event_type}{RESET}");
+    } else {
+        let _ = write!(line, "{clock} {event_type}");
+    }
+
+    let Value::Object(fields) = event else {
+        return line;
+    };
+    for (key, value) in fields.iter().filter(|(key, _)| *key != "type") {
+        let value = match value {
+            Value::String(text) => text.clone(),
+            value => value.to_string(),
+        };
+        if color {
+            let _ = write!(line, " {DIM}{key}={RESET}{value}");
+        } else {
+            let _ = write!(line, " {key}={value}");
+        }
+    }
+    line
+}
+
+fn type_color(event_type: &str) -> &'static str {
+    let category = event_type.split('_').next().unwrap_or_default();
+    match category {
+        "window" | "windows" => "\x1b[32m",
+        "workspace" | "workspaces" | "stacks" => "\x1b[34m",
+        "display" | "mode" | "focus" => "\x1b[35m",
+        "session" | "wake" | "idle" | "secure" => "\x1b[33m",
+        _ => "\x1b[36m",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filters_match_names_and_prefixes() {
+        let filters = ["window_*".to_string(), "workspace_changed".to_string()];
+        assert!(matches(&filters, "window_managed"));
+        assert!(matches(&filters, "workspace_changed"));
+        assert!(!matches(&filters, "workspace_created"));
+        assert!(!matches(&filters, "windows_changed"));
+        assert!(matches(&[], "display_added"));
+    }
+
+    #[test]
+    fn pretty_lines_print_fields_after_the_type() {
+        let event = json!({
+            "type": "workspace_changed",
+            "workspace_name": "web",
+            "windows": ["Safari"],
+        });
+        assert_eq!(
+            pretty_line("09:41:00.123", &event, false),
+            r#"09:41:00.123 workspace_changed windows=["Safari"] workspace_name=web"#
+        );
+
+        let colored = pretty_line("09:41:00.123", &event, true);
+        assert!(colored.starts_with("\x1b[2m09:41:00.123\x1b[0m \x1b[1m\x1b[34mworkspace_changed"));
+        assert!(colored.contains("\x1b[2mworkspace_name=\x1b[0mweb"));
+    }
+
+    #[test]
+    fn json_lines_wrap_the_event_with_its_time() {
+        let event = json!({ "type": "idle_changed", "idle": true });
+        let line = json_line(1_700_000_000_000, event.clone());
+        assert_eq!(line, json!({ "time_ms": 1_700_000_000_000u64, "event": event }));
+        assert_eq!(clock(UNIX_EPOCH).len(), "00:00:00.000".len());
+    }
+}
@@ -0,0 +1,134 @@
+//! Writing a starter config for the displays of this machine, behind `rift init`.
+
+use std::fmt::Write as _;
+
+use super::config::VirtualWorkspaceSettings;
+use crate::sys::screen::ScreenInfo;
+
+const DEFAULT_CONFIG: &str = include_str!("../../rift.default.toml");
+
+/// What `rift init` suggests for one display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayProposal {
+    pub name: String,
+    pub uuid: String,
+    /// Taller than wide, so windows are better split top to bottom.
+    pub portrait: bool,
+    pub workspace_names: Vec<String>,
+}
+
+/// Proposes names for each display's workspaces. The first display keeps the
+/// default names, the others get numbered names after the display so that the
+/// workspace indicators tell them apart.
+pub fn propose(screens: &[ScreenInfo]) -> Vec<DisplayProposal> {
+    let defaults = VirtualWorkspaceSettings::default();
+    screens
+        .iter()
+        .filter(|screen| screen.display_uuid_opt().is_some())
+        .enumerate()
+        .map(|(idx, screen)| {
+            let name = screen.name.clone().unwrap_or_else(|| format!("Display {}", idx + 1));
+            let workspace_names = if idx == 0 {
+                defaults.workspace_names.clone()
+            } else {
+                (1..=defaults.default_workspace_count).map(|n| format!("{name} {n}")).collect()
+            };
+            DisplayProposal {
+                name,
+                uuid: screen.display_uuid.clone(),
+                portrait: screen.frame.size.height > screen.frame.size.width,
+                workspace_names,
+            }
+        })
+        .collect()
+}
+
+/// The commented default config followed by a `per_display` section for each
+/// proposal that differs from the defaults.
+pub fn render_config(proposals: &[DisplayProposal]) -> String {
+    let default_names = VirtualWorkspaceSettings::default().workspace_names;
+    let mut out = DEFAULT_CONFIG.to_string();
+    let mut wrote_header = false;
+    for proposal in proposals {
+        let names =
+            (proposal.workspace_names != default_names).then_some(&proposal.workspace_names);
+        if names.is_none() && !proposal.portrait {
+            continue;
+        }
+        if !wrote_header {
+            out.push_str("\n# Displays found by `rift init`.\n");
+            wrote_header = true;
+        }
+        let uuid = toml::Value::from(proposal.uuid.as_str());
+        let _ = writeln!(out, "\n# {}", proposal.name);
+        let _ = writeln!(out, "[settings.layout.per_display.{uuid}]");
+        if proposal.portrait {
+            out.push_str("orientation = \"vertical\"\n");
+        }
+        if let Some(names) = names {
+            let names = toml::Value::from(names.clone());
+            let _ = writeln!(out, "workspace_names = {names}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+    use super::*;
+    use crate::common::config::validate::validate_source;
+    use crate::sys::screen::ScreenId;
+
+    fn screen(id: u32, name: &str, width: f64, height: f64) -> ScreenInfo {
+        ScreenInfo {
+            id: ScreenId::new(id),
+            frame: CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(width, height)),
+            display_uuid: format!("11111111-2222-3333-4444-55555555555{id}"),
+            name: Some(name.to_string()),
+            space: None,
+        }
+    }
+
+    #[test]
+    fn second_display_gets_its_own_names() {
+        let screens = [
+            screen(1, "Built-in", 1512.0, 982.0),
+            screen(2, "DELL", 1080.0, 1920.0),
+        ];
+        let proposals = propose(&screens);
+
+        assert_eq!(
+            proposals[0].workspace_names,
+            VirtualWorkspaceSettings::default().workspace_names
+        );
+        assert!(!proposals[0].portrait);
+        assert_eq!(proposals[1].workspace_names, [
+            "DELL 1", "DELL 2", "DELL 3", "DELL 4"
+        ]);
+        assert!(proposals[1].portrait);
+    }
+
+    #[test]
+    fn rendered_config_is_valid() {
+        let screens = [
+            screen(1, "Built-in", 1512.0, 982.0),
+            screen(2, "DELL", 1080.0, 1920.0),
+        ];
+        let config = render_config(&propose(&screens));
+
+        assert!(
+            validate_source(&config).is_empty(),
+            "{:?}",
+            validate_source(&config)
+        );
+        assert!(
+            config.contains(
+                r#"[settings.layout.per_display."11111111-2222-3333-4444-555555555552"]"#
+            )
+        );
+        assert!(config.contains(r#"workspace_names = ["DELL 1", "DELL 2", "DELL 3", "DELL 4"]"#));
+        assert!(!config.contains("555555555551\"]"));
+    }
+}
@@ -1,22 +1,159 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{LazyLock, OnceLock};
 use std::time::{Duration, Instant};
 
+use parking_lot::Mutex;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tracing::Subscriber;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{EnvFilter, Layer, Registry};
+use tracing_subscriber::{EnvFilter, Layer, Registry, reload};
 use tracing_timing::{Histogram, group};
 use tracing_tree::time::UtcDateTime;
 
+use super::action_trace;
+use super::collections::{HashMap, hash_map};
+use super::config::LoggingSettings;
+
+/// Parts of rift whose log level and log file can be set on their own.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum LogModule {
+    Reactor,
+    Layout,
+    Animation,
+    Ipc,
+    Overlays,
+}
+
+impl LogModule {
+    pub const ALL: [LogModule; 5] = [
+        LogModule::Reactor,
+        LogModule::Layout,
+        LogModule::Animation,
+        LogModule::Ipc,
+        LogModule::Overlays,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LogModule::Reactor => "reactor",
+            LogModule::Layout => "layout",
+            LogModule::Animation => "animation",
+            LogModule::Ipc => "ipc",
+            LogModule::Overlays => "overlays",
+        }
+    }
+
+    /// The module path that the module's tracing targets start with.
+    fn target(self) -> &'static str {
+        match self {
+            LogModule::Reactor => "rift_wm::actor::reactor",
+            LogModule::Layout => "rift_wm::layout_engine",
+            LogModule::Animation => "rift_wm::actor::reactor::animation",
+            LogModule::Ipc => "rift_wm::ipc",
+            LogModule::Overlays => "rift_wm::ui",
+        }
+    }
+
+    fn covers(self, target: &str) -> bool {
+        target
+            .strip_prefix(self.target())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+    }
+
+    fn bit(self) -> u8 { 1 << self as u8 }
+}
+
+impl FromStr for LogModule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        LogModule::ALL.into_iter().find(|module| module.name() == s).ok_or_else(|| {
+            let names: Vec<&str> = LogModule::ALL.iter().map(|module| module.name()).collect();
+            format!("unknown log module '{s}', expected one of: {}", names.join(", "))
+        })
+    }
+}
+
+/// The most specific of `modules` that `target` belongs to, so animation events
+/// go to the animation log even when the reactor has one too.
+fn module_for_target(
+    modules: impl IntoIterator<Item = LogModule>,
+    target: &str,
+) -> Option<LogModule> {
+    modules
+        .into_iter()
+        .filter(|module| module.covers(target))
+        .max_by_key(|module| module.target().len())
+}
+
+struct LogState {
+    settings: LoggingSettings,
+    /// Levels set with `rift-cli log set`. They win over `settings` until rift exits.
+    overrides: HashMap<LogModule, LevelFilter>,
+}
+
+static LOG_STATE: LazyLock<Mutex<LogState>> = LazyLock::new(|| {
+    Mutex::new(LogState {
+        settings: LoggingSettings::default(),
+        overrides: HashMap::default(),
+    })
+});
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+/// One bit per [`LogModule`] that logs to its own file instead of stderr.
+static FILE_ROUTES: AtomicU8 = AtomicU8::new(0);
+static LOG_FILES: LazyLock<Mutex<LogFiles>> = LazyLock::new(|| {
+    Mutex::new(LogFiles {
+        directory: PathBuf::new(),
+        max_bytes: 0,
+        keep_files: 0,
+        open: HashMap::default(),
+    })
+});
+
+fn file_module(target: &str) -> Option<LogModule> {
+    let routes = FILE_ROUTES.load(Ordering::Relaxed);
+    let routed = LogModule::ALL.into_iter().filter(|module| routes & module.bit() != 0);
+    module_for_target(routed, target)
+}
+
 pub fn init_logging() {
+    let (filter, handle) = reload::Layer::new(build_filter(&LOG_STATE.lock()));
+    let _ = FILTER.set(handle);
+    // Filtered per layer rather than globally, so that traced actions still see
+    // the spans this filter hides from the log.
+    let output = tree_layer()
+        .with_filter(filter_fn(|meta| file_module(meta.target()).is_none()))
+        .and_then(file_layers())
+        .and_then(timing_layer())
+        .with_filter(filter);
     tracing_subscriber::registry()
-        .with(tree_layer())
-        .with(timing_layer())
-        .with(EnvFilter::from_default_env())
+        .with(output)
+        .with(action_trace::is_enabled().then(action_trace::layer))
         .init();
 }
 
-pub fn tree_layer() -> impl Layer<Registry> {
+pub fn tree_layer<S>() -> impl Layer<S>
+where S: Subscriber + for<'a> LookupSpan<'a> {
+    tree_layer_to(io::stderr)
+}
+
+fn tree_layer_to<S, W>(make_writer: W) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + 'static, {
     tracing_tree::HierarchicalLayer::default()
+        .with_writer(make_writer)
         .with_indent_amount(2)
         .with_indent_lines(true)
         .with_deferred_spans(true)
@@ -25,6 +162,168 @@ pub fn tree_layer() -> impl Layer<Registry> {
         .with_timer(UtcDateTime::default())
 }
 
+fn file_layers<S>() -> Vec<Box<dyn Layer<S> + Send + Sync>>
+where S: Subscriber + for<'a> LookupSpan<'a> + 'static {
+    LogModule::ALL
+        .into_iter()
+        .map(|module| {
+            tree_layer_to(ModuleFile(module))
+                .with_filter(filter_fn(move |meta| file_module(meta.target()) == Some(module)))
+                .boxed()
+        })
+        .collect()
+}
+
+/// `RUST_LOG` (or errors only) plus a directive for every module with a level,
+/// and for action timelines while they are traced.
+fn build_filter(state: &LogState) -> EnvFilter {
+    let mut directives = std::env::var("RUST_LOG")
+        .ok()
+        .filter(|env| !env.trim().is_empty())
+        .unwrap_or_else(|| "error".to_string());
+    if action_trace::is_enabled() {
+        directives.push_str(&format!(",{}=info", action_trace::REPORT_TARGET));
+    }
+    for module in LogModule::ALL {
+        let level = state.overrides.get(&module).copied().or_else(|| state.settings.level(module));
+        if let Some(level) = level {
+            directives.push_str(&format!(",{}={level}", module.target()));
+        }
+    }
+    EnvFilter::builder().parse_lossy(directives)
+}
+
+fn reload_filter(filter: EnvFilter) {
+    let Some(handle) = FILTER.get() else {
+        return;
+    };
+    if let Err(err) = handle.reload(filter) {
+        tracing::warn!("Could not update log filter: {err}");
+    }
+}
+
+/// Applies `[settings.logging]`. Levels set with [`set_module_level`] stay in effect.
+pub fn apply_settings(settings: &LoggingSettings) {
+    {
+        let mut files = LOG_FILES.lock();
+        files.directory = settings.directory();
+        files.max_bytes = settings.max_file_mb.saturating_mul(1024 * 1024);
+        files.keep_files = settings.keep_files;
+        files.open.clear();
+    }
+    let routes = settings.files.iter().fold(0, |routes, module| routes | module.bit());
+    FILE_ROUTES.store(routes, Ordering::Relaxed);
+
+    let filter = {
+        let mut state = LOG_STATE.lock();
+        state.settings = settings.clone();
+        build_filter(&state)
+    };
+    // Reloading also rebuilds the callsite cache the file routing filters rely on.
+    reload_filter(filter);
+}
+
+/// Sets the level of `module` until rift exits. `None` goes back to the configured level.
+pub fn set_module_level(module: LogModule, level: Option<LevelFilter>) {
+    let filter = {
+        let mut state = LOG_STATE.lock();
+        match level {
+            Some(level) => state.overrides.insert(module, level),
+            None => state.overrides.remove(&module),
+        };
+        build_filter(&state)
+    };
+    reload_filter(filter);
+}
+
+struct LogFiles {
+    directory: PathBuf,
+    max_bytes: u64,
+    keep_files: usize,
+    open: HashMap<LogModule, RollingFile>,
+}
+
+/// Writes to `<module>.log`, opened on first use.
+#[derive(Clone, Copy)]
+struct ModuleFile(LogModule);
+
+impl Write for ModuleFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // Nothing in here may log: the lock is held and it would deadlock.
+        let mut files = LOG_FILES.lock();
+        let LogFiles {
+            directory,
+            max_bytes,
+            keep_files,
+            open,
+        } = &mut *files;
+        let file = match open.entry(self.0) {
+            hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            hash_map::Entry::Vacant(entry) => {
+                let path = directory.join(format!("{}.log", self.0.name()));
+                entry.insert(RollingFile::open(path)?)
+            }
+        };
+        if *max_bytes > 0 && file.len > 0 && file.len + buf.len() as u64 > *max_bytes {
+            file.rotate(*keep_files)?;
+        }
+        file.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> { Ok(()) }
+}
+
+impl<'a> MakeWriter<'a> for ModuleFile {
+    type Writer = ModuleFile;
+
+    fn make_writer(&'a self) -> Self::Writer { *self }
+}
+
+struct RollingFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+}
+
+impl RollingFile {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { path, file, len })
+    }
+
+    /// Moves `x.log` to `x.log.1`, `x.log.1` to `x.log.2` and so on, dropping
+    /// whatever falls past `keep` and starting `x.log` empty.
+    fn rotate(&mut self, keep: usize) -> io::Result<()> {
+        if keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..keep).rev() {
+                let _ = fs::rename(numbered(&self.path, n), numbered(&self.path, n + 1));
+            }
+            fs::rename(&self.path, numbered(&self.path, 1))?;
+        }
+        *self = Self::open(self.path.clone())?;
+        Ok(())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.file.write_all(buf)?;
+        self.len += buf.len() as u64;
+        Ok(())
+    }
+}
+
+fn numbered(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
 type TimingLayer = tracing_timing::TimingLayer<group::ByName, group::ByMessage>;
 
 fn timing_layer() -> TimingLayer {
@@ -79,3 +378,21 @@ pub fn trace_misc<T>(desc: &str, f: impl FnOnce() -> T) -> T {
     tracing::trace!(time = ?(end - start), "{desc}");
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_go_to_the_most_specific_module() {
+        let animation = "rift_wm::actor::reactor::animation";
+        let module = module_for_target(LogModule::ALL, animation);
+        assert_eq!(module, Some(LogModule::Animation));
+        let module = module_for_target([LogModule::Reactor, LogModule::Layout], animation);
+        assert_eq!(module, Some(LogModule::Reactor));
+        let unrelated = "rift_wm::actor::reactor_extra";
+        assert_eq!(module_for_target(LogModule::ALL, unrelated), None);
+        assert_eq!("overlays".parse(), Ok(LogModule::Overlays));
+        assert!("windows".parse::<LogModule>().is_err());
+    }
+}
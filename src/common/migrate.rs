@@ -0,0 +1,561 @@
+//! Translating rift's workspaces and app rules to and from other window managers.
+//!
+//! Only what maps cleanly is carried over: the default layout, workspace names
+//! and app rules that place a window on a workspace or float it. Anything else
+//! is reported as a note so that the output can be reviewed by hand instead of
+//! silently losing settings.
+
+use std::fmt::Write as _;
+
+use anyhow::{Context, bail};
+
+use super::config::{AppWorkspaceRule, Config, LayoutMode, WorkspaceSelector};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WmFormat {
+    /// A yabairc shell script.
+    Yabai,
+    /// An aerospace.toml file.
+    Aerospace,
+}
+
+impl WmFormat {
+    fn name(self) -> &'static str {
+        match self {
+            WmFormat::Yabai => "yabai",
+            WmFormat::Aerospace => "aerospace",
+        }
+    }
+}
+
+/// The part of an app rule that other window managers understand.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MigratedRule {
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+    pub title_regex: Option<String>,
+    pub workspace: Option<WorkspaceSelector>,
+    pub floating: bool,
+}
+
+impl MigratedRule {
+    fn from_rift(rule: &AppWorkspaceRule) -> Self {
+        let title_regex = rule
+            .title_regex
+            .clone()
+            .or_else(|| rule.title_substring.as_deref().map(regex::escape));
+        MigratedRule {
+            app_id: rule.app_id.clone(),
+            app_name: rule.app_name.clone(),
+            title_regex,
+            workspace: rule.workspace.clone(),
+            // Neither target can leave a window entirely alone, floating is the closest.
+            floating: rule.floating || !rule.manage,
+        }
+    }
+}
+
+/// Settings read from another window manager's config.
+#[derive(Debug, Default, PartialEq)]
+pub struct Migration {
+    pub layout: Option<LayoutMode>,
+    pub workspace_names: Vec<String>,
+    pub app_rules: Vec<MigratedRule>,
+    /// Things that could not be translated, for the user to look at.
+    pub notes: Vec<String>,
+}
+
+impl Migration {
+    /// Renders the settings as a fragment of a rift config file.
+    pub fn to_rift_toml(&self, from: WmFormat) -> String {
+        let mut out = format!(
+            "# Imported from {} by `rift-cli import`; review before merging it into your config.\n",
+            from.name()
+        );
+        for note in &self.notes {
+            let _ = writeln!(out, "# note: {note}");
+        }
+        if let Some(mode) = self.layout {
+            let mode = toml_str(layout_name(mode));
+            let _ = writeln!(out, "\n[settings.layout]\nmode = {mode}");
+        }
+        if !self.workspace_names.is_empty() {
+            let names: Vec<String> = self.workspace_names.iter().map(|n| toml_str(n)).collect();
+            let names = names.join(", ");
+            let _ = writeln!(out, "\n[virtual_workspaces]\nworkspace_names = [{names}]");
+        }
+        for rule in &self.app_rules {
+            out.push_str("\n[[virtual_workspaces.app_rules]]\n");
+            for (key, value) in [
+                ("app_id", &rule.app_id),
+                ("app_name", &rule.app_name),
+                ("title_regex", &rule.title_regex),
+            ] {
+                if let Some(value) = value {
+                    let _ = writeln!(out, "{key} = {}", toml_str(value));
+                }
+            }
+            match &rule.workspace {
+                Some(WorkspaceSelector::Index(idx)) => {
+                    let _ = writeln!(out, "workspace = {idx}");
+                }
+                Some(WorkspaceSelector::Name(name)) => {
+                    let _ = writeln!(out, "workspace = {}", toml_str(name));
+                }
+                None => {}
+            }
+            if rule.floating {
+                out.push_str("floating = true\n");
+            }
+        }
+        out
+    }
+}
+
+pub fn export(config: &Config, format: WmFormat) -> String {
+    match format {
+        WmFormat::Yabai => export_yabai(config),
+        WmFormat::Aerospace => export_aerospace(config),
+    }
+}
+
+pub fn import(format: WmFormat, source: &str) -> anyhow::Result<Migration> {
+    match format {
+        WmFormat::Yabai => Ok(import_yabai(source)),
+        WmFormat::Aerospace => import_aerospace(source),
+    }
+}
+
+fn layout_name(mode: LayoutMode) -> &'static str {
+    match mode {
+        LayoutMode::Traditional => "traditional",
+        LayoutMode::Bsp => "bsp",
+        LayoutMode::Stack => "stack",
+        LayoutMode::MasterStack => "master_stack",
+        LayoutMode::Scrolling => "scrolling",
+    }
+}
+
+fn toml_str(s: &str) -> String { toml::Value::String(s.to_string()).to_string() }
+
+/// Single-quotes `s` for a POSIX shell.
+fn shell_quote(s: &str) -> String { format!("'{}'", s.replace('\'', r"'\''")) }
+
+/// The 0-based index a selector refers to, if it names a configured workspace.
+fn workspace_index(config: &Config, workspace: &WorkspaceSelector) -> Option<usize> {
+    match workspace {
+        WorkspaceSelector::Index(idx) => Some(*idx),
+        WorkspaceSelector::Name(name) => {
+            config.virtual_workspaces.workspace_names.iter().position(|n| n == name)
+        }
+    }
+}
+
+fn workspace_label(config: &Config, workspace: &WorkspaceSelector) -> String {
+    let names = &config.virtual_workspaces.workspace_names;
+    match workspace {
+        WorkspaceSelector::Index(idx) => {
+            names.get(*idx).cloned().unwrap_or_else(|| (idx + 1).to_string())
+        }
+        WorkspaceSelector::Name(name) => name.clone(),
+    }
+}
+
+fn export_yabai(config: &Config) -> String {
+    let mut out = String::from(
+        "#!/usr/bin/env sh\n\
+         # Exported from rift by `rift-cli export --format yabai`; review before use.\n\
+         # rift's workspaces become macOS spaces 1..N, which have to exist already.\n\n",
+    );
+    let mode = config.settings.layout.mode;
+    let layout = match mode {
+        LayoutMode::Stack => "stack",
+        LayoutMode::Bsp => "bsp",
+        other => {
+            let _ = writeln!(out, "# {} has no yabai layout; using bsp", layout_name(other));
+            "bsp"
+        }
+    };
+    let _ = writeln!(out, "yabai -m config layout {layout}\n");
+
+    for (idx, name) in config.virtual_workspaces.workspace_names.iter().enumerate() {
+        let _ = writeln!(out, "yabai -m space {} --label {}", idx + 1, shell_quote(name));
+    }
+    out.push('\n');
+
+    for rule in config.virtual_workspaces.app_rules.iter().map(MigratedRule::from_rift) {
+        let Some(app_name) = &rule.app_name else {
+            let _ = writeln!(
+                out,
+                "# skipped a rule for {}: yabai matches apps by name, not bundle id",
+                rule.app_id.as_deref().unwrap_or("a window title")
+            );
+            continue;
+        };
+        let mut args = vec![format!("app=^{}$", regex::escape(app_name))];
+        if let Some(title) = &rule.title_regex {
+            args.push(format!("title={title}"));
+        }
+        if let Some(idx) = rule.workspace.as_ref().and_then(|ws| workspace_index(config, ws)) {
+            args.push(format!("space={}", idx + 1));
+        }
+        if rule.floating {
+            args.push("manage=off".to_string());
+        }
+        let args: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+        let _ = writeln!(out, "yabai -m rule --add {}", args.join(" "));
+    }
+    out
+}
+
+fn export_aerospace(config: &Config) -> String {
+    let mut out = String::from(
+        "# Exported from rift by `rift-cli export --format aerospace`; review before use.\n\n",
+    );
+    let layout = match config.settings.layout.mode {
+        LayoutMode::Stack => "accordion",
+        _ => "tiles",
+    };
+    let _ = writeln!(out, "default-root-container-layout = {}", toml_str(layout));
+    let names: Vec<String> =
+        config.virtual_workspaces.workspace_names.iter().map(|n| toml_str(n)).collect();
+    let _ = writeln!(out, "persistent-workspaces = [{}]", names.join(", "));
+
+    for rule in config.virtual_workspaces.app_rules.iter().map(MigratedRule::from_rift) {
+        let mut conditions = Vec::new();
+        if let Some(app_id) = &rule.app_id {
+            conditions.push(("app-id", app_id.clone()));
+        }
+        if let Some(app_name) = &rule.app_name {
+            conditions.push(("app-name-regex-substring", regex::escape(app_name)));
+        }
+        if let Some(title) = &rule.title_regex {
+            conditions.push(("window-title-regex-substring", title.clone()));
+        }
+        let mut run = Vec::new();
+        if rule.floating {
+            run.push(toml_str("layout floating"));
+        }
+        if let Some(workspace) = &rule.workspace {
+            let target = workspace_label(config, workspace);
+            run.push(toml_str(&format!("move-node-to-workspace {target}")));
+        }
+        if conditions.is_empty() || run.is_empty() {
+            continue;
+        }
+        out.push_str("\n[[on-window-detected]]\n");
+        for (key, value) in conditions {
+            let _ = writeln!(out, "if.{key} = {}", toml_str(&value));
+        }
+        let _ = writeln!(out, "run = [{}]", run.join(", "));
+    }
+    out
+}
+
+/// Splits a shell command line into words, honouring quotes and backslashes.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_word = true;
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                in_word = true;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => word.extend(chars.next()),
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                in_word = true;
+                word.extend(chars.next());
+            }
+            '#' if !in_word => break,
+            c if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            c => {
+                in_word = true;
+                word.push(c);
+            }
+        }
+    }
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
+/// Turns a yabai `app=` regex into a plain name, if it is one.
+fn plain_app_name(pattern: &str) -> Option<String> {
+    let inner = pattern.strip_prefix('^').unwrap_or(pattern);
+    let inner = inner.strip_suffix('$').unwrap_or(inner);
+    let unescaped = inner.replace(r"\.", ".").replace(r"\ ", " ");
+    let special = ['\\', '*', '+', '?', '(', ')', '[', ']', '|', '{', '^', '$'];
+    (!unescaped.contains(special)).then_some(unescaped)
+}
+
+fn set_workspace_name(names: &mut Vec<String>, idx: usize, name: String) {
+    while names.len() <= idx {
+        names.push((names.len() + 1).to_string());
+    }
+    names[idx] = name;
+}
+
+fn import_yabai(source: &str) -> Migration {
+    let mut migration = Migration::default();
+    let mut ignored = 0;
+    for line in source.lines() {
+        let words = shell_words(line);
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        let ["yabai", "-m", rest @ ..] = words.as_slice() else {
+            continue;
+        };
+        match rest {
+            ["config", "layout", layout] => match *layout {
+                "bsp" => migration.layout = Some(LayoutMode::Bsp),
+                "stack" => migration.layout = Some(LayoutMode::Stack),
+                other => migration.notes.push(format!("layout {other} has no rift equivalent")),
+            },
+            ["space", space, "--label", label] => match space.parse::<usize>() {
+                Ok(n) if n > 0 => {
+                    set_workspace_name(&mut migration.workspace_names, n - 1, label.to_string())
+                }
+                _ => migration.notes.push(format!("could not label space {space}")),
+            },
+            ["rule", "--add", args @ ..] => {
+                if let Some(rule) = yabai_rule(args, &mut migration.notes) {
+                    migration.app_rules.push(rule);
+                }
+            }
+            _ => ignored += 1,
+        }
+    }
+    if ignored > 0 {
+        migration.notes.push(format!("{ignored} other yabai commands were left out"));
+    }
+    migration
+}
+
+fn yabai_rule(args: &[&str], notes: &mut Vec<String>) -> Option<MigratedRule> {
+    let mut rule = MigratedRule::default();
+    for arg in args {
+        let Some((key, value)) = arg.split_once('=') else {
+            continue;
+        };
+        let key = key.trim_end_matches('!');
+        match key {
+            "app" => match plain_app_name(value) {
+                Some(name) => rule.app_name = Some(name),
+                None => {
+                    notes.push(format!("skipped a rule with app regex {value}"));
+                    return None;
+                }
+            },
+            "title" => rule.title_regex = Some(value.to_string()),
+            "space" => {
+                rule.workspace = Some(match value.parse::<usize>() {
+                    Ok(n) if n > 0 => WorkspaceSelector::Index(n - 1),
+                    _ => WorkspaceSelector::Name(value.to_string()),
+                })
+            }
+            "manage" => rule.floating = value == "off",
+            other => notes.push(format!("ignored rule option {other}={value}")),
+        }
+    }
+    (rule.app_name.is_some() || rule.title_regex.is_some()).then_some(rule)
+}
+
+fn import_aerospace(source: &str) -> anyhow::Result<Migration> {
+    let table: toml::Table = toml::from_str(source).context("not a valid aerospace.toml")?;
+    let mut migration = Migration::default();
+
+    match table.get("default-root-container-layout").and_then(|v| v.as_str()) {
+        Some("tiles") => migration.layout = Some(LayoutMode::Traditional),
+        Some("accordion") => migration.layout = Some(LayoutMode::Stack),
+        Some(other) => migration.notes.push(format!("layout {other} has no rift equivalent")),
+        None => {}
+    }
+
+    let mut names: Vec<String> = table
+        .get("persistent-workspaces")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(str::to_string))
+        .collect();
+    // Without persistent workspaces, take them from the main mode's bindings.
+    if names.is_empty()
+        && let Some(bindings) = table
+            .get("mode")
+            .and_then(|m| m.get("main"))
+            .and_then(|m| m.get("binding"))
+            .and_then(|b| b.as_table())
+    {
+        for command in bindings.values().filter_map(|v| v.as_str()) {
+            if let Some(name) = command.strip_prefix("workspace ")
+                && !name.starts_with('-')
+                && !names.iter().any(|n| n == name)
+            {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    let callbacks: &[toml::Value] = match table.get("on-window-detected") {
+        None => &[],
+        Some(toml::Value::Array(callbacks)) => callbacks,
+        Some(_) => bail!("on-window-detected must be an array of tables"),
+    };
+    for callback in callbacks {
+        let conditions = callback.get("if");
+        let condition = |key: &str| {
+            conditions.and_then(|c| c.get(key)).and_then(|v| v.as_str()).map(str::to_string)
+        };
+        let mut rule = MigratedRule {
+            app_id: condition("app-id"),
+            app_name: condition("app-name-regex-substring"),
+            title_regex: condition("window-title-regex-substring"),
+            ..Default::default()
+        };
+        let commands: Vec<&str> = match callback.get("run") {
+            Some(toml::Value::String(cmd)) => vec![cmd.as_str()],
+            Some(toml::Value::Array(cmds)) => cmds.iter().filter_map(|v| v.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        for command in commands {
+            if command == "layout floating" {
+                rule.floating = true;
+            } else if let Some(target) = command.strip_prefix("move-node-to-workspace ") {
+                if !names.iter().any(|n| n == target) {
+                    names.push(target.to_string());
+                }
+                rule.workspace = Some(WorkspaceSelector::Name(target.to_string()));
+            } else {
+                migration.notes.push(format!("ignored on-window-detected command {command:?}"));
+            }
+        }
+        if rule.app_id.is_none() && rule.app_name.is_none() && rule.title_regex.is_none() {
+            migration.notes.push("skipped an on-window-detected rule with no `if`".into());
+            continue;
+        }
+        if rule.workspace.is_some() || rule.floating {
+            migration.app_rules.push(rule);
+        }
+    }
+    migration.workspace_names = names;
+    Ok(migration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RIFT: &str = r#"
+        [settings.layout]
+        mode = "bsp"
+
+        [virtual_workspaces]
+        workspace_names = ["code", "web", "chat"]
+
+        [[virtual_workspaces.app_rules]]
+        app_name = "Slack"
+        workspace = "chat"
+
+        [[virtual_workspaces.app_rules]]
+        app_id = "com.apple.systempreferences"
+        app_name = "System Settings"
+        floating = true
+
+        [[virtual_workspaces.app_rules]]
+        app_id = "com.apple.Safari"
+        workspace = 1
+    "#;
+
+    #[test]
+    fn yabai_export_round_trips_through_the_importer() {
+        let config = Config::parse(RIFT).unwrap();
+        let script = export(&config, WmFormat::Yabai);
+        assert!(script.contains("yabai -m space 3 --label 'chat'"));
+        assert!(script.contains("yabai -m rule --add 'app=^Slack$' 'space=3'"));
+        assert!(script.contains("# skipped a rule for com.apple.Safari"));
+
+        let migration = import(WmFormat::Yabai, &script).unwrap();
+        assert_eq!(migration.layout, Some(LayoutMode::Bsp));
+        assert_eq!(migration.workspace_names, ["code", "web", "chat"]);
+        assert_eq!(migration.app_rules, [
+            MigratedRule {
+                app_name: Some("Slack".into()),
+                workspace: Some(WorkspaceSelector::Index(2)),
+                ..Default::default()
+            },
+            MigratedRule {
+                app_name: Some("System Settings".into()),
+                floating: true,
+                ..Default::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn aerospace_export_round_trips_through_the_importer() {
+        let config = Config::parse(RIFT).unwrap();
+        let exported = export(&config, WmFormat::Aerospace);
+        let migration = import(WmFormat::Aerospace, &exported).unwrap();
+        assert_eq!(migration.workspace_names, ["code", "web", "chat"]);
+        assert_eq!(migration.app_rules.len(), 3);
+        assert_eq!(migration.app_rules[2], MigratedRule {
+            app_id: Some("com.apple.Safari".into()),
+            workspace: Some(WorkspaceSelector::Name("web".into())),
+            ..Default::default()
+        });
+
+        // The rendered fragment is itself a valid rift config.
+        let fragment = migration.to_rift_toml(WmFormat::Aerospace);
+        let imported = Config::parse(&fragment).unwrap().virtual_workspaces;
+        assert_eq!(imported.workspace_names, ["code", "web", "chat"]);
+        assert_eq!(imported.app_rules.len(), 3);
+        assert!(imported.app_rules[1].floating);
+    }
+
+    #[test]
+    fn importers_note_what_they_cannot_translate() {
+        let yabai = r#"
+            yabai -m config layout float
+            yabai -m config window_gap 6   # gaps are not carried over
+            yabai -m rule --add app="^(Finder|Preview)$" manage=off
+            yabai -m rule --add app="^Arc$" sticky=on
+        "#;
+        let migration = import(WmFormat::Yabai, yabai).unwrap();
+        assert_eq!(migration.layout, None);
+        assert_eq!(migration.app_rules.len(), 1);
+        assert_eq!(migration.notes.len(), 4, "{:?}", migration.notes);
+
+        let aerospace = r#"
+            [mode.main.binding]
+            alt-1 = "workspace 1"
+            alt-2 = "workspace B"
+            alt-tab = "workspace-back-and-forth"
+
+            [[on-window-detected]]
+            if.app-id = "com.apple.finder"
+            run = ["layout floating", "move-node-to-workspace B"]
+        "#;
+        let migration = import(WmFormat::Aerospace, aerospace).unwrap();
+        assert_eq!(migration.workspace_names.len(), 2);
+        assert!(migration.workspace_names.contains(&"B".to_string()));
+        assert!(migration.app_rules[0].floating);
+        assert!(import(WmFormat::Aerospace, "on-window-detected = 3").is_err());
+    }
+}
@@ -0,0 +1,126 @@
+//! Sanitizing and packaging the state dump behind `rift-cli report`.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, bail};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+const STRIPPED: &str = "<stripped>";
+const REDACTED: &str = "<redacted>";
+
+/// Config keys whose values are shell commands, which can carry tokens or private paths.
+const SECRET_KEYS: [&str; 5] = ["exec", "apps", "run_on_start", "command", "args"];
+
+static EVENT_TITLE: Lazy<Regex> = Lazy::new(|| {
+    // The closing quote is optional since long events are cut off in the trace.
+    Regex::new(r#"(title: |WindowTitleChanged\(WindowId \{[^}]*\}, )"(?:[^"\\]|\\.)*"?"#).unwrap()
+});
+
+/// Replaces every command the config would run with a placeholder.
+pub fn strip_secrets(config: &mut Value) { replace_keys(config, &SECRET_KEYS, STRIPPED); }
+
+/// Replaces every `title` field, e.g. in window or workspace data.
+pub fn redact_titles(value: &mut Value) { replace_keys(value, &["title"], REDACTED); }
+
+/// Redacts the window titles in an event's debug representation.
+pub fn redact_event_titles(event: &str) -> String {
+    EVENT_TITLE.replace_all(event, format!(r#"${{1}}"{REDACTED}""#)).into_owned()
+}
+
+fn replace_keys(value: &mut Value, keys: &[&str], placeholder: &str) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if keys.contains(&key.as_str()) && !value.is_null() {
+                    *value = Value::String(placeholder.to_string());
+                } else {
+                    replace_keys(value, keys, placeholder);
+                }
+            }
+        }
+        Value::Array(values) => {
+            for value in values {
+                replace_keys(value, keys, placeholder);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Writes each section of `report` to its own file and zips them into `out`.
+///
+/// Sections that are strings (the layout and the event trace) are written as
+/// text, everything else as pretty JSON.
+pub fn write_bundle(report: &Value, out: &Path) -> anyhow::Result<()> {
+    let Value::Object(sections) = report else {
+        bail!("Expected the report to be an object");
+    };
+    let name = out.file_stem().context("The output path has no file name")?;
+    let staging = std::env::temp_dir().join(name);
+    if staging.exists() {
+        fs::remove_dir_all(&staging)?;
+    }
+    fs::create_dir_all(&staging)?;
+    for (section, value) in sections {
+        let (file, contents) = match value {
+            Value::String(text) => (format!("{section}.txt"), text.clone()),
+            value => (format!("{section}.json"), serde_json::to_string_pretty(value)?),
+        };
+        fs::write(staging.join(file), contents)?;
+    }
+
+    let status = Command::new("/usr/bin/ditto")
+        .args(["-c", "-k", "--keepParent"])
+        .arg(&staging)
+        .arg(out)
+        .status()
+        .context("Failed to run ditto")?;
+    fs::remove_dir_all(&staging)?;
+    if !status.success() {
+        bail!("ditto exited with {status}");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn secrets_and_titles_are_replaced_wherever_they_nest() {
+        let mut config = json!({
+            "settings": { "run_on_start": ["rift-cli subscribe cli"], "animate": true },
+            "keys": [["Alt + Enter", { "exec": "open -a Terminal" }]],
+            "virtual_workspaces": { "projects": [{ "name": "web", "apps": ["code ~/web"] }] },
+        });
+        strip_secrets(&mut config);
+        assert_eq!(config["settings"]["run_on_start"], STRIPPED);
+        assert_eq!(config["settings"]["animate"], true);
+        assert_eq!(config["keys"][0][1]["exec"], STRIPPED);
+        assert_eq!(config["virtual_workspaces"]["projects"][0]["apps"], STRIPPED);
+
+        let mut windows = json!([{ "title": "Inbox", "info": { "title": "Inbox" } }]);
+        redact_titles(&mut windows);
+        let expected = json!([{ "title": REDACTED, "info": { "title": REDACTED } }]);
+        assert_eq!(windows, expected);
+    }
+
+    #[test]
+    fn event_titles_are_redacted() {
+        let created = r#"WindowInfo { title: "a \"b\"", is_minimized: false }"#;
+        let expected = r#"WindowInfo { title: "<redacted>", is_minimized: false }"#;
+        assert_eq!(redact_event_titles(created), expected);
+        let changed = r#"WindowTitleChanged(WindowId { pid: 1, idx: 2 }, "Secret plans")"#;
+        let expected = r#"WindowTitleChanged(WindowId { pid: 1, idx: 2 }, "<redacted>")"#;
+        assert_eq!(redact_event_titles(changed), expected);
+        let cut_off = r#"WindowInfo { title: "Quarterly num..."#;
+        let expected = r#"WindowInfo { title: "<redacted>""#;
+        assert_eq!(redact_event_titles(cut_off), expected);
+    }
+}
@@ -0,0 +1,152 @@
+//! The running build, and the newest release the update checker has seen.
+
+use std::cmp::Ordering;
+use std::process::Command;
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/acsandmann/rift/releases/latest";
+const ACCEPT_HEADER: &str = "Accept: application/vnd.github+json";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+static LATEST_RELEASE: Mutex<Option<Release>> = Mutex::new(None);
+
+/// What `rift-cli query build-info` reports, for bug reports.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BuildInfo {
+    pub version: String,
+    /// Short hash of the commit rift was built from, when built from a git checkout.
+    pub git_commit: Option<String>,
+    pub target: String,
+    pub profile: String,
+    pub os_version: String,
+}
+
+impl BuildInfo {
+    pub fn current() -> Self {
+        use objc2_foundation::NSProcessInfo;
+        BuildInfo {
+            version: VERSION.to_string(),
+            git_commit: option_env!("RIFT_GIT_COMMIT").map(str::to_string),
+            target: env!("RIFT_TARGET").to_string(),
+            profile: env!("RIFT_PROFILE").to_string(),
+            os_version: NSProcessInfo::processInfo().operatingSystemVersionString().to_string(),
+        }
+    }
+}
+
+/// A published release of rift.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Release {
+    pub version: String,
+    pub url: String,
+}
+
+/// What `rift-cli query version` reports. The latest release is only known
+/// once the update checker has run.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct VersionInfo {
+    pub version: String,
+    pub latest_release: Option<Release>,
+    pub update_available: bool,
+}
+
+pub fn version_info() -> VersionInfo {
+    let latest_release = LATEST_RELEASE.lock().clone();
+    let update_available = latest_release.as_ref().is_some_and(|r| is_newer(&r.version, VERSION));
+    VersionInfo {
+        version: VERSION.to_string(),
+        latest_release,
+        update_available,
+    }
+}
+
+pub fn set_latest_release(release: Release) { *LATEST_RELEASE.lock() = Some(release); }
+
+/// Asks GitHub for the latest release. Blocks for up to [`FETCH_TIMEOUT`].
+pub fn fetch_latest_release() -> Result<Release, String> {
+    let timeout = FETCH_TIMEOUT.as_secs().to_string();
+    let output = Command::new("/usr/bin/curl")
+        .args(["-sSfL", "--max-time", &timeout])
+        .args(["--header", ACCEPT_HEADER, LATEST_RELEASE_URL])
+        .output()
+        .map_err(|e| format!("failed to run curl: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    parse_release(&output.stdout)
+}
+
+fn parse_release(body: &[u8]) -> Result<Release, String> {
+    #[derive(Deserialize)]
+    struct GitHubRelease {
+        tag_name: String,
+        html_url: String,
+    }
+    let release: GitHubRelease =
+        serde_json::from_slice(body).map_err(|e| format!("unexpected response: {e}"))?;
+    Ok(Release {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        url: release.html_url,
+    })
+}
+
+/// Whether `candidate` is a later version than `current`, comparing the dotted
+/// numeric parts. A pre-release suffix sorts before the release it leads up to.
+pub fn is_newer(candidate: &str, current: &str) -> bool {
+    compare_versions(candidate, current) == Ordering::Greater
+}
+
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| {
+        let v = v.trim_start_matches('v');
+        let (numbers, pre) = v.split_once('-').map_or((v, None), |(n, p)| (n, Some(p.to_string())));
+        let numbers: Vec<u64> = numbers.split('.').map(|n| n.parse().unwrap_or(0)).collect();
+        (numbers, pre)
+    };
+    let (a_numbers, a_pre) = split(a);
+    let (b_numbers, b_pre) = split(b);
+    let len = a_numbers.len().max(b_numbers.len());
+    for idx in 0..len {
+        let a = a_numbers.get(idx).copied().unwrap_or(0);
+        let b = b_numbers.get(idx).copied().unwrap_or(0);
+        match a.cmp(&b) {
+            Ordering::Equal => {}
+            other => return other,
+        }
+    }
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => a.cmp(&b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions() {
+        assert!(is_newer("0.3.0", "0.2.9"));
+        assert!(is_newer("v0.2.10", "0.2.9"));
+        assert!(is_newer("1.0.0", "1.0.0-rc.1"));
+        assert!(!is_newer("0.2.9", "0.2.9"));
+        assert!(!is_newer("0.2", "0.2.0"));
+        assert!(!is_newer("0.3.0-beta", "0.3.0"));
+    }
+
+    #[test]
+    fn parses_github_release() {
+        let body = br#"{"tag_name": "v0.4.1", "html_url": "https://example.com/r", "assets": []}"#;
+        assert_eq!(parse_release(body).unwrap(), Release {
+            version: "0.4.1".to_string(),
+            url: "https://example.com/r".to_string(),
+        });
+    }
+}
@@ -2,8 +2,10 @@ use std::ffi::{CStr, c_char};
 use std::time::Duration;
 
 use r#continue::continuation;
+use tracing::level_filters::LevelFilter;
 use tracing::{error, info, trace};
 
+pub mod alias;
 pub mod cli_exec;
 pub mod protocol;
 pub mod subscriptions;
@@ -12,6 +14,9 @@ pub use protocol::{RiftCommand, RiftRequest, RiftResponse};
 
 use crate::actor::config as config_actor;
 use crate::actor::reactor::{self, Event};
+use crate::common::action_trace;
+use crate::common::log::{self, LogModule};
+use crate::common::version::{self, BuildInfo};
 use crate::ipc::subscriptions::SharedServerState;
 use crate::sys::dispatch::block_on;
 use crate::sys::mach::{
@@ -22,6 +27,10 @@ use crate::sys::mach::{
 
 type ClientPort = u32;
 
+/// Requests are served one at a time, so a long settle wait would hold up
+/// every other client.
+const MAX_SETTLE_WAIT: Duration = Duration::from_secs(5);
+
 pub fn run_mach_server(
     reactor: reactor::ReactorHandle,
     config_tx: config_actor::Sender,
@@ -190,6 +199,8 @@ impl MachHandler {
         match event {
             config_actor::Event::QueryConfig(response) => std::mem::forget(response),
             config_actor::Event::ApplyConfig { response, .. } => std::mem::forget(response),
+            config_actor::Event::ValidateConfig { response, .. } => std::mem::forget(response),
+            config_actor::Event::PowerSourceChanged(_) => {}
         }
     }
 
@@ -216,6 +227,38 @@ impl MachHandler {
         }
     }
 
+    /// Runs an i3/AeroSpace-style command string; see [`alias`].
+    fn execute_alias(&self, command: &str, args: &[String]) -> RiftResponse {
+        let input = std::iter::once(command).chain(args.iter().map(String::as_str));
+        let input = input.collect::<Vec<_>>().join(" ");
+        let workspace_names = self
+            .perform_config_query(|tx| config_actor::Event::QueryConfig(tx))
+            .map(|config| config.virtual_workspaces.workspace_names)
+            .unwrap_or_default();
+        let commands = match alias::parse(&input, &workspace_names) {
+            Ok(commands) => commands,
+            Err(e) => {
+                return RiftResponse::Error {
+                    error: serde_json::json!({ "message": format!("Invalid command: {e}") }),
+                };
+            }
+        };
+        for command in commands {
+            if let Err(e) = self.reactor.try_send(Event::Command(command)) {
+                error!("Failed to send command to reactor: {}", e);
+                return RiftResponse::Error {
+                    error: serde_json::json!({
+                        "message": "Failed to execute command",
+                        "details": e.to_string(),
+                    }),
+                };
+            }
+        }
+        RiftResponse::Success {
+            data: serde_json::json!("Command executed successfully"),
+        }
+    }
+
     fn handle_request(&self, request: RiftRequest, client_port: ClientPort) -> RiftResponse {
         trace!("Handling request: {:?} from client {}", request, client_port);
 
@@ -335,6 +378,149 @@ impl MachHandler {
                 RiftResponse::Success { data: metrics }
             }
 
+            RiftRequest::GetExclusionZones => {
+                let zones = self.reactor.query_exclusion_zones();
+                RiftResponse::Success {
+                    data: serde_json::to_value(zones).unwrap(),
+                }
+            }
+
+            RiftRequest::GetPausedSpaces => {
+                let spaces = self.reactor.query_paused_spaces();
+                RiftResponse::Success {
+                    data: serde_json::to_value(spaces).unwrap(),
+                }
+            }
+
+            RiftRequest::GetSpaces => {
+                let spaces = self.reactor.query_spaces();
+                RiftResponse::Success {
+                    data: serde_json::to_value(spaces).unwrap(),
+                }
+            }
+
+            RiftRequest::GetCursor => match self.reactor.query_cursor() {
+                Some(cursor) => RiftResponse::Success {
+                    data: serde_json::to_value(cursor).unwrap(),
+                },
+                None => RiftResponse::Error {
+                    error: serde_json::json!({ "message": "Cursor position is unavailable" }),
+                },
+            },
+
+            RiftRequest::GetMenuBarOwner => {
+                let owner = self.reactor.query_menu_bar_owner();
+                RiftResponse::Success {
+                    data: serde_json::to_value(owner).unwrap(),
+                }
+            }
+
+            RiftRequest::GetAnimationState => match self.reactor.query_animation_state() {
+                Some(state) => RiftResponse::Success {
+                    data: serde_json::to_value(state).unwrap(),
+                },
+                None => RiftResponse::Error {
+                    error: serde_json::json!({ "message": "rift is not responding" }),
+                },
+            },
+
+            RiftRequest::GetVersion => RiftResponse::Success {
+                data: serde_json::to_value(version::version_info()).unwrap(),
+            },
+
+            RiftRequest::GetBuildInfo => RiftResponse::Success {
+                data: serde_json::to_value(BuildInfo::current()).unwrap(),
+            },
+
+            RiftRequest::WaitForSettle { timeout_ms } => {
+                let timeout = Duration::from_millis(timeout_ms).min(MAX_SETTLE_WAIT);
+                match self.reactor.wait_for_settle(timeout) {
+                    Ok(state) => RiftResponse::Success {
+                        data: serde_json::to_value(state).unwrap(),
+                    },
+                    Err(Some(state)) => RiftResponse::Error {
+                        error: serde_json::json!({
+                            "message": "Timed out waiting for animations to settle",
+                            "state": state,
+                        }),
+                    },
+                    Err(None) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "rift is not responding" }),
+                    },
+                }
+            }
+
+            RiftRequest::ExplainWindow { window_id } => {
+                let Some(window_id) = crate::actor::app::WindowId::from_debug_string(&window_id)
+                else {
+                    error!("Invalid window_id format: {}", window_id);
+                    return RiftResponse::Error {
+                        error: serde_json::json!({
+                            "message": "Invalid window_id format",
+                            "window_id": window_id,
+                        }),
+                    };
+                };
+
+                match self.reactor.query_explain_window(window_id) {
+                    Some(explanation) => RiftResponse::Success {
+                        data: serde_json::to_value(explanation).unwrap(),
+                    },
+                    None => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "Window not found" }),
+                    },
+                }
+            }
+
+            RiftRequest::Screenshot { target, out } => {
+                match self.reactor.query_screenshot(target, out) {
+                    Ok(screenshot) => RiftResponse::Success {
+                        data: serde_json::to_value(screenshot).unwrap(),
+                    },
+                    Err(message) => RiftResponse::Error {
+                        error: serde_json::json!({ "message": message }),
+                    },
+                }
+            }
+
+            RiftRequest::GetReport { redact_titles } => {
+                match self.reactor.query_report(redact_titles) {
+                    Some(report) => RiftResponse::Success { data: report },
+                    None => RiftResponse::Error {
+                        error: serde_json::json!({ "message": "rift is not responding" }),
+                    },
+                }
+            }
+
+            RiftRequest::SetLogLevel { module, level } => {
+                let module = match module.parse::<LogModule>() {
+                    Ok(module) => module,
+                    Err(message) => {
+                        return RiftResponse::Error {
+                            error: serde_json::json!({ "message": message }),
+                        };
+                    }
+                };
+                let level = match level.as_deref().map(str::parse::<LevelFilter>).transpose() {
+                    Ok(level) => level,
+                    Err(e) => {
+                        return RiftResponse::Error {
+                            error: serde_json::json!({
+                                "message": "Invalid log level",
+                                "details": e.to_string(),
+                            }),
+                        };
+                    }
+                };
+                log::set_module_level(module, level);
+                RiftResponse::Success {
+                    data: serde_json::json!({
+                        "module": module.name(),
+                        "level": level.map(|level| level.to_string()),
+                    }),
+                }
+            }
+
             RiftRequest::GetConfig => {
                 match self.perform_config_query(|tx| config_actor::Event::QueryConfig(tx)) {
                     Ok(config) => match serde_json::to_value(&config) {
@@ -355,6 +541,32 @@ impl MachHandler {
                 }
             }
 
+            RiftRequest::ValidateConfig { source } => {
+                match self.perform_config_query(|tx| config_actor::Event::ValidateConfig {
+                    source,
+                    response: tx,
+                }) {
+                    Ok(report) => match serde_json::to_value(&report) {
+                        Ok(value) => RiftResponse::Success { data: value },
+                        Err(e) => RiftResponse::Error {
+                            error: serde_json::json!({
+                                "message": "Failed to serialize diagnostics",
+                                "details": e.to_string(),
+                            }),
+                        },
+                    },
+                    Err(e) => {
+                        error!("{}", e);
+                        RiftResponse::Error {
+                            error: serde_json::json!({
+                                "message": "Failed to validate config",
+                                "details": e,
+                            }),
+                        }
+                    }
+                }
+            }
+
             RiftRequest::ExecuteCommand { command, args } => {
                 match serde_json::from_str::<RiftCommand>(&command) {
                     Ok(RiftCommand::Config(_)) => {
@@ -394,9 +606,10 @@ impl MachHandler {
                         }
                     }
                     Ok(RiftCommand::Reactor(reactor_command)) => {
+                        let action = action_trace::start("ipc", &reactor_command);
                         let event = Event::Command(reactor_command);
 
-                        if let Err(e) = self.reactor.try_send(event) {
+                        if let Err(e) = action.in_scope(|| self.reactor.try_send(event)) {
                             error!("Failed to send command to reactor: {}", e);
                             return RiftResponse::Error {
                                 error: serde_json::json!({ "message": "Failed to execute command", "details": format!("{}", e) }),
@@ -407,6 +620,9 @@ impl MachHandler {
                             data: serde_json::json!("Command executed successfully"),
                         }
                     }
+                    Err(_) if !command.trim_start().starts_with(['{', '"']) => {
+                        self.execute_alias(&command, &args)
+                    }
                     Err(e) => {
                         error!("Failed to parse command: {}", e);
                         RiftResponse::Error {
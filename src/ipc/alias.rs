@@ -0,0 +1,349 @@
+//! i3 and AeroSpace style command strings, for scripts and skhd configs written
+//! against those window managers.
+//!
+//! `workspace 3`, `move container to workspace 4` or `split v` are mapped onto
+//! the nearest rift command. Several commands can be chained with `;` or `,` as
+//! in i3. Workspaces are given by name, or by 1-based number as i3 numbers them;
+//! a name that is itself a number wins over the number.
+
+use crate::actor::reactor::{Command, DisplaySelector, ReactorCommand};
+use crate::layout_engine::{
+    Direction, LayoutCommand, LayoutKind, ResizeAmount, ResizeAxis, ResizeMode,
+};
+
+/// Parses `input` into the rift commands it stands for, in order.
+pub fn parse(input: &str, workspace_names: &[String]) -> Result<Vec<Command>, String> {
+    let commands: Vec<Command> = input
+        .split([';', ','])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| parse_one(part, workspace_names))
+        .collect::<Result<_, _>>()?;
+    if commands.is_empty() {
+        return Err("empty command".into());
+    }
+    Ok(commands)
+}
+
+fn parse_one(input: &str, workspace_names: &[String]) -> Result<Command, String> {
+    if input.starts_with('[') {
+        return Err(format!("criteria are not supported: {input}"));
+    }
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let workspace = |name: &[&str]| resolve_workspace(name, workspace_names);
+    let layout = |cmd| Ok(Command::Layout(cmd));
+    let reactor = |cmd| Ok(Command::Reactor(cmd));
+
+    if let ["move", rest @ ..] = words.as_slice() {
+        if let Some(name) = workspace_target(rest) {
+            return layout(LayoutCommand::MoveWindowToWorkspace {
+                workspace: workspace(name)?,
+                window_id: None,
+            });
+        }
+        if let Some(direction) = output_target(rest) {
+            return reactor(ReactorCommand::MoveWindowToDisplay {
+                selector: DisplaySelector::Direction(direction),
+                window_id: None,
+            });
+        }
+    }
+    if let ["resize", mode, axis, amount @ ..] = words.as_slice()
+        && let (Some(mode), Some(axis)) = (resize_mode(mode), resize_axis(axis))
+        && let Some(amount) = i3_resize_amount(amount)
+    {
+        return layout(LayoutCommand::Resize { mode, axis, amount });
+    }
+    if let ["resize", axis, amount] = words.as_slice()
+        && let Some(axis) = resize_axis(axis)
+    {
+        // AeroSpace: `+N` and `-N` step by N pixels, a bare N sets the size.
+        let (mode, pixels) = match (amount.strip_prefix('+'), amount.strip_prefix('-')) {
+            (Some(pixels), _) => (ResizeMode::Grow, pixels),
+            (_, Some(pixels)) => (ResizeMode::Shrink, pixels),
+            _ => (ResizeMode::Set, *amount),
+        };
+        let pixels = pixels.parse().map_err(|_| format!("invalid resize amount: {input}"))?;
+        let amount = ResizeAmount::Pixels(pixels);
+        return layout(LayoutCommand::Resize { mode, axis, amount });
+    }
+    if let ["focus", rest @ ..] = words.as_slice()
+        && let Some(direction) = output_target(rest)
+    {
+        let selector = DisplaySelector::Direction(direction);
+        return reactor(ReactorCommand::FocusDisplay(selector));
+    }
+
+    match words.as_slice() {
+        ["workspace", "next" | "next_on_output"] => layout(LayoutCommand::NextWorkspace(None)),
+        ["workspace", "prev" | "prev_on_output"] => layout(LayoutCommand::PrevWorkspace(None)),
+        ["workspace", "back_and_forth"] | ["workspace-back-and-forth"] => {
+            layout(LayoutCommand::SwitchToLastWorkspace)
+        }
+        ["workspace", "--wrap-around", "next"] => layout(LayoutCommand::NextWorkspace(None)),
+        ["workspace", "--wrap-around", "prev"] => layout(LayoutCommand::PrevWorkspace(None)),
+        ["workspace", name @ ..] => layout(LayoutCommand::SwitchToWorkspace(workspace(name)?)),
+
+        ["move-node-to-workspace", name @ ..] => layout(LayoutCommand::MoveWindowToWorkspace {
+            workspace: workspace(name)?,
+            window_id: None,
+        }),
+        ["move-node-to-monitor", direction] => reactor(ReactorCommand::MoveWindowToDisplay {
+            selector: DisplaySelector::Direction(parse_direction(direction)?),
+            window_id: None,
+        }),
+        ["move", "scratchpad"] => layout(LayoutCommand::AddScratchpad),
+        ["move", "window" | "container", direction] | ["move", direction] => {
+            layout(LayoutCommand::MoveNode(parse_direction(direction)?))
+        }
+
+        ["focus", "parent"] | ["focus-parent"] => layout(LayoutCommand::Ascend),
+        ["focus", "child"] | ["focus-child"] => layout(LayoutCommand::Descend),
+        ["focus", "mode_toggle"] => layout(LayoutCommand::ToggleFocusFloating),
+        ["focus-monitor", direction] => reactor(ReactorCommand::FocusDisplay(
+            DisplaySelector::Direction(parse_direction(direction)?),
+        )),
+        ["focus", direction] => layout(LayoutCommand::MoveFocus(parse_direction(direction)?)),
+        ["join-with", direction] => layout(LayoutCommand::JoinWindow(parse_direction(direction)?)),
+
+        ["split", "v" | "vertical"] => layout(LayoutCommand::Split(LayoutKind::Vertical)),
+        ["split", "h" | "horizontal"] => layout(LayoutCommand::Split(LayoutKind::Horizontal)),
+        ["split", "t" | "toggle" | "opposite"] => layout(LayoutCommand::ToggleOrientation),
+        ["layout", "cycle"] | ["layout", "toggle"] | ["layout", "toggle", "all"] => {
+            layout(LayoutCommand::CycleContainerLayout(vec![]))
+        }
+        ["layout", "toggle", kinds @ ..] => {
+            layout(LayoutCommand::CycleContainerLayout(container_kinds(kinds)?))
+        }
+        ["layout", kind] if container_kind(kind).is_ok() => {
+            layout(LayoutCommand::SetContainerLayout(container_kind(kind)?))
+        }
+        // AeroSpace's `layout` cycles through the modes it is given; rift only has toggles.
+        ["layout", modes @ ..] if modes.contains(&"floating") => {
+            layout(LayoutCommand::ToggleWindowFloating)
+        }
+        ["layout", modes @ ..]
+            if modes.iter().any(|m| matches!(*m, "stacking" | "tabbed" | "accordion")) =>
+        {
+            layout(LayoutCommand::ToggleStack)
+        }
+        ["layout", modes @ ..] if modes.contains(&"horizontal") && modes.contains(&"vertical") => {
+            layout(LayoutCommand::ToggleOrientation)
+        }
+        ["floating", "toggle"] => layout(LayoutCommand::ToggleWindowFloating),
+        ["fullscreen"] | ["fullscreen", "toggle"] => layout(LayoutCommand::ToggleFullscreen),
+
+        ["resize", "grow", ..] => layout(LayoutCommand::ResizeWindowGrow),
+        ["resize", "shrink", ..] => layout(LayoutCommand::ResizeWindowShrink),
+        ["resize", "smart", amount] if amount.starts_with('+') => {
+            layout(LayoutCommand::ResizeWindowGrow)
+        }
+        ["resize", "smart", amount] if amount.starts_with('-') => {
+            layout(LayoutCommand::ResizeWindowShrink)
+        }
+
+        ["scratchpad", "show"] => layout(LayoutCommand::ToggleScratchpad),
+        ["kill"] | ["close"] => reactor(ReactorCommand::CloseWindow { window_server_id: None }),
+        ["exit"] => reactor(ReactorCommand::SaveAndExit),
+        _ => Err(format!("unrecognized command: {input}")),
+    }
+}
+
+/// The workspace in `move [window|container] [to] workspace [number] <name>`.
+fn workspace_target<'a>(words: &'a [&'a str]) -> Option<&'a [&'a str]> {
+    let words = match words {
+        ["window" | "container", rest @ ..] => rest,
+        rest => rest,
+    };
+    let words = words.strip_prefix(&["to"]).unwrap_or(words);
+    let words = match words {
+        ["--no-auto-back-and-forth", rest @ ..] => rest,
+        rest => rest,
+    };
+    words.strip_prefix(&["workspace"])
+}
+
+/// The direction in `[window|container] [to] output <direction>`.
+fn output_target(words: &[&str]) -> Option<Direction> {
+    match words {
+        ["window" | "container", rest @ ..] => output_target(rest),
+        ["to", rest @ ..] => output_target(rest),
+        ["output", direction] => parse_direction(direction).ok(),
+        _ => None,
+    }
+}
+
+fn resize_mode(word: &str) -> Option<ResizeMode> {
+    match word {
+        "set" => Some(ResizeMode::Set),
+        "grow" => Some(ResizeMode::Grow),
+        "shrink" => Some(ResizeMode::Shrink),
+        _ => None,
+    }
+}
+
+fn resize_axis(word: &str) -> Option<ResizeAxis> {
+    match word {
+        "width" => Some(ResizeAxis::Width),
+        "height" => Some(ResizeAxis::Height),
+        _ => None,
+    }
+}
+
+/// The amount in i3's `10 px or 10 ppt`. i3 uses the ppt half for tiled
+/// windows, and so does rift; a bare number is in pixels.
+fn i3_resize_amount(words: &[&str]) -> Option<ResizeAmount> {
+    let amounts: Vec<ResizeAmount> = words
+        .split(|word| *word == "or")
+        .map(|part| match part {
+            [number] | [number, "px"] => number.parse().ok().map(ResizeAmount::Pixels),
+            [number, "ppt"] => number.parse().ok().map(ResizeAmount::Percent),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let percent = amounts.iter().find(|amount| matches!(amount, ResizeAmount::Percent(_)));
+    percent.or(amounts.first()).copied()
+}
+
+/// i3's `stacking` lists titles top to bottom, and `tabbed` side by side.
+fn container_kind(word: &str) -> Result<LayoutKind, String> {
+    match word {
+        "splith" | "h_tiles" => Ok(LayoutKind::Horizontal),
+        "splitv" | "v_tiles" => Ok(LayoutKind::Vertical),
+        "stacking" | "stacked" | "v_accordion" => Ok(LayoutKind::VerticalStack),
+        "tabbed" | "h_accordion" => Ok(LayoutKind::HorizontalStack),
+        _ => Err(format!("unknown layout: {word}")),
+    }
+}
+
+fn container_kinds(words: &[&str]) -> Result<Vec<LayoutKind>, String> {
+    let mut kinds = vec![];
+    for word in words {
+        match *word {
+            "split" => kinds.extend([LayoutKind::Horizontal, LayoutKind::Vertical]),
+            word => kinds.push(container_kind(word)?),
+        }
+    }
+    Ok(kinds)
+}
+
+fn parse_direction(word: &str) -> Result<Direction, String> {
+    match word {
+        "left" => Ok(Direction::Left),
+        "right" => Ok(Direction::Right),
+        "up" => Ok(Direction::Up),
+        "down" => Ok(Direction::Down),
+        other => Err(format!("expected left, right, up or down, got {other:?}")),
+    }
+}
+
+fn resolve_workspace(words: &[&str], workspace_names: &[String]) -> Result<usize, String> {
+    let words = words.strip_prefix(&["number"]).unwrap_or(words);
+    if words.is_empty() {
+        return Err("missing workspace".into());
+    }
+    let name = words.join(" ");
+    if let Some(idx) = workspace_names.iter().position(|n| n.eq_ignore_ascii_case(&name)) {
+        return Ok(idx);
+    }
+    // i3 reads a leading number as the workspace number, as in `workspace 2:web`.
+    let digits: String = name.chars().take_while(char::is_ascii_digit).collect();
+    match digits.parse::<usize>() {
+        Ok(number) if number > 0 => Ok(number - 1),
+        _ => Err(format!("no workspace named {name:?}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> { ["web", "code", "10"].map(String::from).to_vec() }
+
+    fn one(input: &str) -> Command {
+        let mut commands = parse(input, &names()).unwrap();
+        assert_eq!(commands.len(), 1, "{input}");
+        commands.remove(0)
+    }
+
+    #[test]
+    fn workspaces_resolve_by_name_then_by_number() {
+        let switch = |idx| Command::Layout(LayoutCommand::SwitchToWorkspace(idx));
+        assert_eq!(one("workspace 3"), switch(2));
+        assert_eq!(one("workspace number 4"), switch(3));
+        assert_eq!(one("workspace Code"), switch(1));
+        assert_eq!(one("workspace 10"), switch(2));
+        assert_eq!(one("workspace 5:mail"), switch(4));
+        assert!(parse("workspace mail", &names()).is_err());
+    }
+
+    #[test]
+    fn i3_and_aerospace_spellings_map_to_the_same_command() {
+        let move_to =
+            Command::Layout(LayoutCommand::MoveWindowToWorkspace { workspace: 3, window_id: None });
+        assert_eq!(one("move container to workspace 4"), move_to);
+        assert_eq!(one("move window to workspace number 4"), move_to);
+        assert_eq!(one("move-node-to-workspace 4"), move_to);
+
+        let vertical = Command::Layout(LayoutCommand::Split(LayoutKind::Vertical));
+        assert_eq!(one("split v"), vertical);
+        assert_eq!(one("split vertical"), vertical);
+
+        let to_output = Command::Reactor(ReactorCommand::MoveWindowToDisplay {
+            selector: DisplaySelector::Direction(Direction::Right),
+            window_id: None,
+        });
+        assert_eq!(one("move container to output right"), to_output);
+        assert_eq!(one("move-node-to-monitor right"), to_output);
+
+        let focus_left = Command::Layout(LayoutCommand::MoveFocus(Direction::Left));
+        assert_eq!(one("focus left"), focus_left);
+        let move_up = Command::Layout(LayoutCommand::MoveNode(Direction::Up));
+        assert_eq!(one("move up"), move_up);
+        assert_eq!(one("focus-parent"), one("focus parent"));
+
+        let tabbed =
+            Command::Layout(LayoutCommand::SetContainerLayout(LayoutKind::HorizontalStack));
+        assert_eq!(one("layout tabbed"), tabbed);
+        assert_eq!(one("layout h_accordion"), tabbed);
+        let toggle_split = Command::Layout(LayoutCommand::CycleContainerLayout(vec![
+            LayoutKind::Horizontal,
+            LayoutKind::Vertical,
+        ]));
+        assert_eq!(one("layout toggle split"), toggle_split);
+        assert_eq!(one("layout cycle"), one("layout toggle all"));
+        assert!(parse("layout toggle sideways", &names()).is_err());
+    }
+
+    #[test]
+    fn resize_amounts_prefer_ppt_and_aerospace_signs_pick_the_mode() {
+        let resize =
+            |mode, axis, amount| Command::Layout(LayoutCommand::Resize { mode, axis, amount });
+        let (width, height) = (ResizeAxis::Width, ResizeAxis::Height);
+
+        let grow = resize(ResizeMode::Grow, width, ResizeAmount::Percent(10.0));
+        assert_eq!(one("resize grow width 10 px or 10 ppt"), grow);
+        let set = resize(ResizeMode::Set, height, ResizeAmount::Percent(60.0));
+        assert_eq!(one("resize set height 60 ppt"), set);
+        let shrink = resize(ResizeMode::Shrink, width, ResizeAmount::Pixels(50.0));
+        assert_eq!(one("resize shrink width 50"), shrink);
+        assert_eq!(one("resize width -50"), shrink);
+        let set_px = resize(ResizeMode::Set, height, ResizeAmount::Pixels(800.0));
+        assert_eq!(one("resize height 800"), set_px);
+        assert!(parse("resize width wide", &names()).is_err());
+    }
+
+    #[test]
+    fn chained_commands_run_in_order_and_errors_name_the_culprit() {
+        let commands = parse("move container to workspace web; workspace web", &names()).unwrap();
+        assert_eq!(commands, [
+            Command::Layout(LayoutCommand::MoveWindowToWorkspace { workspace: 0, window_id: None }),
+            Command::Layout(LayoutCommand::SwitchToWorkspace(0)),
+        ]);
+
+        let err = parse("focus left; sticky enable", &names()).unwrap_err();
+        assert!(err.contains("sticky enable"), "{err}");
+        assert!(parse("[class=\"Firefox\"] focus", &names()).is_err());
+        assert!(parse(" ; ", &names()).is_err());
+    }
+}
@@ -51,6 +51,21 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::WorkspacesReordered {
+                workspace_ids,
+                workspace_names,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "workspaces_reordered".into());
+                let ids: Vec<String> = workspace_ids.iter().map(ToString::to_string).collect();
+                env_vars.insert("RIFT_WORKSPACE_IDS".into(), ids.join(","));
+                env_vars.insert("RIFT_WORKSPACE_NAMES".into(), workspace_names.join(","));
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
             BroadcastEvent::WindowsChanged {
                 workspace_id,
                 workspace_name,
@@ -117,6 +132,135 @@ impl CliExecutor for DefaultCliExecutor {
                     env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
                 }
             }
+            BroadcastEvent::FocusModeChanged { focus_mode } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "focus_mode_changed".into());
+                if let Some(focus_mode) = focus_mode.as_ref() {
+                    env_vars.insert("RIFT_FOCUS_MODE".into(), focus_mode.clone());
+                }
+            }
+            BroadcastEvent::SessionResumed => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "session_resumed".into());
+            }
+            BroadcastEvent::WakeReconciled {
+                remapped_spaces,
+                added_displays,
+                removed_displays,
+                moved_windows,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "wake_reconciled".into());
+                env_vars.insert(
+                    "RIFT_REMAPPED_SPACE_COUNT".into(),
+                    remapped_spaces.len().to_string(),
+                );
+                env_vars.insert("RIFT_ADDED_DISPLAYS".into(), added_displays.join(","));
+                env_vars.insert("RIFT_REMOVED_DISPLAYS".into(), removed_displays.join(","));
+                env_vars.insert("RIFT_MOVED_WINDOW_COUNT".into(), moved_windows.len().to_string());
+            }
+            BroadcastEvent::SecureInputChanged { enabled } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "secure_input_changed".into());
+                env_vars.insert("RIFT_SECURE_INPUT".into(), enabled.to_string());
+            }
+            BroadcastEvent::MenuBarOwnerChanged { pid, bundle_id, app_name } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "menu_bar_owner_changed".into());
+                if let Some(pid) = pid {
+                    env_vars.insert("RIFT_PID".into(), pid.to_string());
+                }
+                if let Some(bundle_id) = bundle_id.as_ref() {
+                    env_vars.insert("RIFT_BUNDLE_ID".into(), bundle_id.clone());
+                }
+                if let Some(app_name) = app_name.as_ref() {
+                    env_vars.insert("RIFT_APP_NAME".into(), app_name.clone());
+                }
+            }
+            BroadcastEvent::IdleChanged { idle, after_secs } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "idle_changed".into());
+                env_vars.insert("RIFT_IDLE".into(), idle.to_string());
+                env_vars.insert("RIFT_IDLE_AFTER_SECS".into(), after_secs.to_string());
+            }
+            BroadcastEvent::WorkspaceCreated {
+                workspace_id,
+                workspace_name,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "workspace_created".into());
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
+            BroadcastEvent::WorkspaceDeleted {
+                workspace_id,
+                workspace_name,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "workspace_deleted".into());
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
+            BroadcastEvent::WorkspaceRenamed {
+                workspace_id,
+                previous_name,
+                workspace_name,
+                space_id,
+                display_uuid,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "workspace_renamed".into());
+                env_vars.insert("RIFT_WORKSPACE_ID".into(), workspace_id.to_string());
+                env_vars.insert("RIFT_PREVIOUS_WORKSPACE_NAME".into(), previous_name.clone());
+                env_vars.insert("RIFT_WORKSPACE_NAME".into(), workspace_name.clone());
+                env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                if let Some(display_uuid) = display_uuid.as_ref() {
+                    env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+                }
+            }
+            BroadcastEvent::WindowManaged { window_id, floating } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "window_managed".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                env_vars.insert("RIFT_FLOATING".into(), floating.to_string());
+            }
+            BroadcastEvent::WindowUnmanaged { window_id } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "window_unmanaged".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+            }
+            BroadcastEvent::WindowFloatingChanged { window_id, floating } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "window_floating_changed".into());
+                env_vars.insert("RIFT_WINDOW_ID".into(), window_id.to_debug_string());
+                env_vars.insert("RIFT_FLOATING".into(), floating.to_string());
+            }
+            BroadcastEvent::DisplayAdded { display_uuid } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "display_added".into());
+                env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+            }
+            BroadcastEvent::DisplayRemoved { display_uuid } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "display_removed".into());
+                env_vars.insert("RIFT_DISPLAY_UUID".into(), display_uuid.clone());
+            }
+            BroadcastEvent::ModeChanged { mode, active, space_id } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "mode_changed".into());
+                env_vars.insert("RIFT_MODE".into(), mode.as_str().into());
+                env_vars.insert("RIFT_MODE_ACTIVE".into(), active.to_string());
+                if let Some(space_id) = space_id {
+                    env_vars.insert("RIFT_SPACE_ID".into(), space_id.to_string());
+                }
+            }
+            BroadcastEvent::UpdateAvailable {
+                current_version,
+                latest_version,
+                url,
+            } => {
+                env_vars.insert("RIFT_EVENT_TYPE".into(), "update_available".into());
+                env_vars.insert("RIFT_CURRENT_VERSION".into(), current_version.clone());
+                env_vars.insert("RIFT_LATEST_VERSION".into(), latest_version.clone());
+                env_vars.insert("RIFT_RELEASE_URL".into(), url.clone());
+            }
         }
 
         let event_json = match serde_json::to_string(event) {
@@ -1,6 +1,10 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::model::server::ScreenshotTarget;
+
 #[non_exhaustive]
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -24,7 +28,45 @@ pub enum RiftRequest {
     },
     GetApplications,
     GetMetrics,
+    GetExclusionZones,
+    GetPausedSpaces,
+    GetSpaces,
+    GetCursor,
+    GetMenuBarOwner,
+    GetAnimationState,
+    /// The running version and, once the update checker has run, the latest release.
+    GetVersion,
+    /// Version, commit, target and macOS version, for bug reports.
+    GetBuildInfo,
+    /// Answers once no layout or workspace switch is in flight, or fails after
+    /// `timeout_ms` with the state at that point.
+    WaitForSettle {
+        timeout_ms: u64,
+    },
+    ExplainWindow {
+        window_id: String,
+    },
     GetConfig,
+    /// Diagnostics for `source`, or for the config file rift loaded when it is
+    /// `None`. Nothing is applied, so editors can send unsaved text.
+    ValidateConfig {
+        #[serde(default)]
+        source: Option<String>,
+    },
+    /// Writes a PNG; `out` must be absolute and defaults to a file on the desktop.
+    Screenshot {
+        target: ScreenshotTarget,
+        out: Option<PathBuf>,
+    },
+    /// State for a bug report, with config commands stripped and optionally window titles.
+    GetReport {
+        redact_titles: bool,
+    },
+    /// Sets a module's log level until rift exits; `None` restores the configured one.
+    SetLogLevel {
+        module: String,
+        level: Option<String>,
+    },
     ExecuteCommand {
         command: String,
         args: Vec<String>,
@@ -176,17 +176,36 @@ impl ServerState {
         })
     }
 
-    pub fn publish(&self, event: BroadcastEvent) {
-        self.forward_event_to_cli_subscribers(event.clone());
+    pub fn publish(&self, event: BroadcastEvent, run_hooks: bool) {
+        if run_hooks {
+            self.forward_event_to_cli_subscribers(event.clone());
+        }
         self.forward_event_to_subscribers(event);
     }
 
     fn forward_event_to_subscribers(&self, event: BroadcastEvent) {
         let event_name = match &event {
             BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
+            BroadcastEvent::WorkspacesReordered { .. } => "workspaces_reordered",
             BroadcastEvent::WindowsChanged { .. } => "windows_changed",
             BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
             BroadcastEvent::StacksChanged { .. } => "stacks_changed",
+            BroadcastEvent::FocusModeChanged { .. } => "focus_mode_changed",
+            BroadcastEvent::SessionResumed => "session_resumed",
+            BroadcastEvent::WakeReconciled { .. } => "wake_reconciled",
+            BroadcastEvent::SecureInputChanged { .. } => "secure_input_changed",
+            BroadcastEvent::MenuBarOwnerChanged { .. } => "menu_bar_owner_changed",
+            BroadcastEvent::IdleChanged { .. } => "idle_changed",
+            BroadcastEvent::WorkspaceCreated { .. } => "workspace_created",
+            BroadcastEvent::WorkspaceRenamed { .. } => "workspace_renamed",
+            BroadcastEvent::WorkspaceDeleted { .. } => "workspace_deleted",
+            BroadcastEvent::WindowManaged { .. } => "window_managed",
+            BroadcastEvent::WindowUnmanaged { .. } => "window_unmanaged",
+            BroadcastEvent::WindowFloatingChanged { .. } => "window_floating_changed",
+            BroadcastEvent::DisplayAdded { .. } => "display_added",
+            BroadcastEvent::DisplayRemoved { .. } => "display_removed",
+            BroadcastEvent::ModeChanged { .. } => "mode_changed",
+            BroadcastEvent::UpdateAvailable { .. } => "update_available",
         };
 
         let mut targets: HashSet<ClientPort> = HashSet::default();
@@ -232,9 +251,26 @@ impl ServerState {
     fn forward_event_to_cli_subscribers(&self, event: BroadcastEvent) {
         let event_name = match &event {
             BroadcastEvent::WorkspaceChanged { .. } => "workspace_changed",
+            BroadcastEvent::WorkspacesReordered { .. } => "workspaces_reordered",
             BroadcastEvent::WindowsChanged { .. } => "windows_changed",
             BroadcastEvent::WindowTitleChanged { .. } => "window_title_changed",
             BroadcastEvent::StacksChanged { .. } => "stacks_changed",
+            BroadcastEvent::FocusModeChanged { .. } => "focus_mode_changed",
+            BroadcastEvent::SessionResumed => "session_resumed",
+            BroadcastEvent::WakeReconciled { .. } => "wake_reconciled",
+            BroadcastEvent::SecureInputChanged { .. } => "secure_input_changed",
+            BroadcastEvent::MenuBarOwnerChanged { .. } => "menu_bar_owner_changed",
+            BroadcastEvent::IdleChanged { .. } => "idle_changed",
+            BroadcastEvent::WorkspaceCreated { .. } => "workspace_created",
+            BroadcastEvent::WorkspaceRenamed { .. } => "workspace_renamed",
+            BroadcastEvent::WorkspaceDeleted { .. } => "workspace_deleted",
+            BroadcastEvent::WindowManaged { .. } => "window_managed",
+            BroadcastEvent::WindowUnmanaged { .. } => "window_unmanaged",
+            BroadcastEvent::WindowFloatingChanged { .. } => "window_floating_changed",
+            BroadcastEvent::DisplayAdded { .. } => "display_added",
+            BroadcastEvent::DisplayRemoved { .. } => "display_removed",
+            BroadcastEvent::ModeChanged { .. } => "mode_changed",
+            BroadcastEvent::UpdateAvailable { .. } => "update_available",
         };
 
         // Collect relevant subscriptions without full HashMap clone
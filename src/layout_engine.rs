@@ -8,12 +8,12 @@ mod workspaces;
 
 pub use engine::{EventResponse, LayoutCommand, LayoutEngine, LayoutEvent};
 pub(crate) use floating::FloatingManager;
+pub use graph::{Direction, LayoutKind, Orientation, ResizeAmount, ResizeAxis, ResizeMode};
 pub(crate) use scratchpad::ScratchpadManager;
-pub use graph::{Direction, LayoutKind, Orientation};
 pub(crate) use systems::LayoutId;
 pub use systems::{
     BspLayoutSystem, LayoutSystem, LayoutSystemKind, MasterStackLayoutSystem,
-    ScrollingLayoutSystem, StackLayoutSystem, TraditionalLayoutSystem,
+    ScrollingLayoutSystem, Share, StackLayoutSystem, TraditionalLayoutSystem,
 };
 pub(crate) use workspaces::WorkspaceLayouts;
 
@@ -3,17 +3,20 @@ use std::path::PathBuf;
 
 use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info, warn};
+use tracing::{debug, info, instrument, warn};
 
 use super::{Direction, FloatingManager, LayoutId, LayoutSystemKind, WorkspaceLayouts};
 use crate::actor::app::{AppInfo, WindowId, pid_t};
 use crate::actor::broadcast::{BroadcastEvent, BroadcastSender};
 use crate::common::collections::{HashMap, HashSet};
-use crate::common::config::{LayoutMode, LayoutSettings, VirtualWorkspaceSettings};
+use crate::common::config::{
+    AutoFloatReason, LayoutMode, LayoutSettings, VirtualWorkspaceSettings,
+};
 use crate::layout_engine::LayoutSystem;
-use crate::layout_engine::systems::WindowLayoutConstraints;
+use crate::layout_engine::systems::{Share, WindowLayoutConstraints};
 use crate::model::virtual_workspace::{
-    AppRuleAssignment, AppRuleResult, VirtualWorkspace, VirtualWorkspaceId, VirtualWorkspaceManager,
+    AppRuleAssignment, AppRuleResult, VirtualWorkspace, VirtualWorkspaceId,
+    VirtualWorkspaceManager, grid_neighbor,
 };
 use crate::sys::screen::SpaceId;
 
@@ -27,6 +30,29 @@ pub struct GroupContainerInfo {
     pub window_ids: Vec<crate::actor::app::WindowId>,
 }
 
+/// A node of the layout tree as the debug overlay draws it.
+#[derive(Debug, Clone)]
+pub struct DebugNodeOutline {
+    /// `None` in layouts that have no container tree.
+    pub node_id: Option<crate::model::tree::NodeId>,
+    /// The container kind, or `None` for a window.
+    pub kind: Option<super::LayoutKind>,
+    pub frame: CGRect,
+    /// This node's share of its parent split, for children of a split container.
+    pub ratio: Option<f64>,
+    pub depth: usize,
+    pub window_id: Option<WindowId>,
+}
+
+/// The layout of one space as the debug overlay draws it.
+#[derive(Debug, Clone)]
+pub struct DebugLayoutOutline {
+    /// Where tiled windows go, inside the outer gaps and any reserved insets.
+    pub tiling_area: CGRect,
+    pub gaps: crate::common::config::GapSettings,
+    pub nodes: Vec<DebugNodeOutline>,
+}
+
 #[derive(Debug, Default)]
 struct WindowRemovalImpact {
     active_space: Option<SpaceId>,
@@ -46,16 +72,37 @@ pub enum LayoutCommand {
     NextWindow,
     PrevWindow,
     MoveFocus(#[serde(rename = "direction")] Direction),
+    /// Select the container around the selection (i3's `focus parent`), so the
+    /// next move, resize or layout command acts on all of it.
+    #[serde(alias = "focus_parent")]
     Ascend,
+    #[serde(alias = "focus_child")]
     Descend,
     MoveNode(Direction),
 
     JoinWindow(Direction),
+    /// Wrap the selection in a new container of the given kind, so the next
+    /// window opens beside it in that direction (i3's `split`).
+    Split(super::LayoutKind),
+    /// Change the kind of the container around the selection, or of the
+    /// selection itself when it is a container (i3's `layout splith|stacking|...`).
+    SetContainerLayout(super::LayoutKind),
+    /// Step that container through the given kinds, or through all four when
+    /// the list is empty (i3's `layout toggle ...`).
+    CycleContainerLayout(Vec<super::LayoutKind>),
     ToggleStack,
     ToggleOrientation,
     UnjoinWindows,
+    /// Move the selection into the stack of the window in the given direction,
+    /// turning that window into a stack if it is not grouped yet.
+    AbsorbIntoStack(Direction),
+    /// Take the selection out of its stack and place it next to the stack.
+    ExpelFromStack,
     ToggleFocusFloating,
     ToggleWindowFloating,
+    /// Pin the focused floating window to its macOS space, so it stays on screen
+    /// through workspace switches there, or unpin it.
+    TogglePinToSpace,
     ToggleFullscreen,
     ToggleFullscreenWithinGaps,
 
@@ -64,6 +111,13 @@ pub enum LayoutCommand {
     ResizeWindowBy {
         amount: f64,
     },
+    /// Set, grow or shrink the selection's width or height within its parent,
+    /// e.g. `resize = { mode = "set", axis = "width", amount = "60%" }`.
+    Resize {
+        mode: super::ResizeMode,
+        axis: super::ResizeAxis,
+        amount: super::ResizeAmount,
+    },
 
     /// Scroll the strip by a normalized delta (scaled by column step width)
     ScrollStrip {
@@ -78,6 +132,9 @@ pub enum LayoutCommand {
     NextWorkspace(Option<bool>),
     PrevWorkspace(Option<bool>),
     SwitchToWorkspace(usize),
+    /// Switch to the workspace next to the active one in the workspace grid
+    /// (`grid_columns`), without wrapping around its edges.
+    FocusWorkspace(Direction),
     MoveWindowToWorkspace {
         workspace: usize,
         window_id: Option<u32>,
@@ -88,6 +145,15 @@ pub enum LayoutCommand {
     },
     CreateWorkspace,
     SwitchToLastWorkspace,
+    /// Move the active workspace one place earlier in the workspace order
+    MoveWorkspaceLeft,
+    /// Move the active workspace one place later in the workspace order
+    MoveWorkspaceRight,
+    /// Move a workspace (the active one if unset) to `index` in the workspace order
+    SetWorkspaceIndex {
+        workspace: Option<usize>,
+        index: usize,
+    },
 
     SwapWindows(crate::actor::app::WindowId, crate::actor::app::WindowId),
 
@@ -102,6 +168,8 @@ pub enum LayoutCommand {
     AddScratchpad,
     ToggleScratchpad,
     ToggleScratchpadNamed(String),
+    /// Toggle the ultrawide centered column on the display showing the current space
+    ToggleUltrawideColumn,
 }
 
 #[non_exhaustive]
@@ -163,6 +231,33 @@ pub struct LayoutEngine {
     space_display_map: HashMap<SpaceId, Option<String>>,
     #[serde(skip)]
     display_last_space: HashMap<String, SpaceId>,
+    /// Runtime on/off state of the ultrawide column per display UUID (enabled when absent).
+    #[serde(skip)]
+    ultrawide_column_enabled: HashMap<String, bool>,
+    /// Space reserved per display UUID for detected bars and user-drawn exclusion zones.
+    #[serde(skip)]
+    reserved_insets: HashMap<String, crate::common::config::OuterGaps>,
+    /// Tiling area per space from the last layout pass, for turning `resize`
+    /// amounts given in pixels into shares of the tree.
+    #[serde(skip)]
+    tiling_areas: HashMap<SpaceId, CGRect>,
+    /// What the auto-float heuristics decided when each window was first laid out.
+    /// Windows are only judged once, so toggling one back to tiled sticks.
+    #[serde(skip)]
+    auto_float_decisions: HashMap<WindowId, Option<AutoFloatReason>>,
+    /// Managed windows and whether they float, as last reported to subscribers.
+    #[serde(skip)]
+    published_windows: HashMap<WindowId, bool>,
+    /// Workspace names and spaces as last reported to subscribers.
+    #[serde(skip)]
+    published_workspaces: HashMap<VirtualWorkspaceId, (SpaceId, String)>,
+    /// Where the user last put each window while it floated, on the current
+    /// display configuration. Kept up to date by the reactor.
+    #[serde(skip)]
+    remembered_float_frames: HashMap<WindowId, CGRect>,
+    /// Windows in the order they were last focused, most recent last.
+    #[serde(skip)]
+    focus_history: Vec<WindowId>,
 }
 
 impl LayoutEngine {
@@ -188,6 +283,17 @@ impl LayoutEngine {
         Some((ws_id, layout))
     }
 
+    /// Reorders the workspace at position `workspace` (the active one if
+    /// `None`) and tells subscribers the new order.
+    fn move_workspace_to_index(&mut self, space: SpaceId, workspace: Option<usize>, index: usize) {
+        let Some(workspace_id) = self.workspace_id_for_index(space, workspace) else {
+            return;
+        };
+        if self.virtual_workspace_manager.move_workspace(space, workspace_id, index) {
+            self.broadcast_workspaces_reordered(space);
+        }
+    }
+
     fn workspace_id_for_index(
         &mut self,
         space: SpaceId,
@@ -201,6 +307,32 @@ impl LayoutEngine {
         }
     }
 
+    /// Index of the workspace called `name` on `space`, adding one at the end
+    /// when there is none.
+    pub fn named_workspace_index(&mut self, space: SpaceId, name: &str) -> Option<usize> {
+        let workspaces = self.virtual_workspace_manager.list_workspaces(space);
+        if let Some(index) = workspaces.iter().position(|(_, n)| n == name) {
+            return Some(index);
+        }
+        match self.virtual_workspace_manager.create_workspace(space, Some(name.to_string())) {
+            Ok(_) => {
+                self.broadcast_workspace_changed(space);
+                Some(workspaces.len())
+            }
+            Err(e) => {
+                warn!("Failed to create workspace {name:?}: {:?}", e);
+                None
+            }
+        }
+    }
+
+    /// Windows assigned to the workspace at `index` on `space`, or to the active one.
+    pub fn workspace_windows_at(&mut self, space: SpaceId, index: Option<usize>) -> Vec<WindowId> {
+        self.workspace_id_for_index(space, index)
+            .map(|id| self.virtual_workspace_manager.workspace_windows(space, id))
+            .unwrap_or_default()
+    }
+
     fn switch_workspace_layout_mode(
         &mut self,
         space: SpaceId,
@@ -232,6 +364,7 @@ impl LayoutEngine {
         }
 
         window_order.retain(|wid| !self.floating.is_floating(*wid));
+        let container = self.workspace_container_rule(space, workspace_id);
 
         let Some(workspace) = self.virtual_workspace_manager.workspaces.get_mut(workspace_id)
         else {
@@ -242,6 +375,9 @@ impl LayoutEngine {
             VirtualWorkspace::create_layout_system(mode, &self.layout_settings);
 
         let new_layout = workspace.layout_system.create_layout();
+        if let Some(kind) = container {
+            workspace.layout_system.set_root_kind(new_layout, kind);
+        }
         self.workspace_layouts
             .replace_layouts_for_workspace(space, workspace_id, new_layout);
 
@@ -322,6 +458,8 @@ impl LayoutEngine {
         let Some((ws_id, layout_id)) = self.workspace_and_layout(space) else {
             return Vec::new();
         };
+        let (gaps, stack_line_thickness) = self.smart_spacing(space, gaps, stack_line_thickness);
+        let gaps = &gaps;
         let stack_offset = self.layout_settings.stack.stack_offset;
         match self.workspace_tree(ws_id) {
             LayoutSystemKind::Traditional(s) => {
@@ -416,6 +554,11 @@ impl LayoutEngine {
                 _ => {}
             }
         }
+
+        let spaces: Vec<SpaceId> = self.space_display_map.keys().copied().collect();
+        for space in spaces {
+            self.apply_display_layout_overrides(space);
+        }
     }
 
     pub fn update_virtual_workspace_settings(
@@ -482,7 +625,7 @@ impl LayoutEngine {
         }
     }
 
-    fn active_floating_windows_in_workspace(&self, space: SpaceId) -> Vec<WindowId> {
+    pub fn active_floating_windows_in_workspace(&self, space: SpaceId) -> Vec<WindowId> {
         self.floating
             .active_flat(space)
             .into_iter()
@@ -872,6 +1015,7 @@ impl LayoutEngine {
             self.focused_window = None;
         }
         self.window_layout_constraints.remove(&wid);
+        self.auto_float_decisions.remove(&wid);
 
         if let Some(space) = removal.active_space {
             self.broadcast_windows_changed(space);
@@ -923,14 +1067,33 @@ impl LayoutEngine {
                 },
             };
 
+        if !self.floating.is_floating(wid)
+            && self.tiled_window_limit_reached(space, assigned_workspace, wid, &[])
+        {
+            debug!(
+                ?wid,
+                ?space,
+                "display tiled window limit reached; floating new window"
+            );
+            self.floating.add_floating(wid);
+        }
+
         let should_be_floating = self.floating.is_floating(wid);
 
         if should_be_floating {
             self.floating.add_active(space, wid.pid, wid);
+            self.restore_remembered_float_frame(space, wid);
         } else if let Some(layout) = self.workspace_layouts.active(space, assigned_workspace) {
             if !self.workspace_tree(assigned_workspace).contains_window(layout, wid) {
-                self.workspace_tree_mut(assigned_workspace)
-                    .add_window_after_selection(layout, wid);
+                // A new or emptied workspace starts over with the container its rule asks for.
+                let kind = self.workspace_container_rule(space, assigned_workspace);
+                let tree = self.workspace_tree_mut(assigned_workspace);
+                if let Some(kind) = kind
+                    && tree.visible_windows_in_layout(layout).is_empty()
+                {
+                    tree.set_root_kind(layout, kind);
+                }
+                tree.add_window_after_selection(layout, wid);
             }
         } else {
             warn!(
@@ -1017,13 +1180,265 @@ impl LayoutEngine {
         tiled_membership_changed
     }
 
+    /// Windows about to be tiled for the first time whose app rule names a
+    /// `stack_with` group.
+    fn new_windows_with_stack_rule(
+        &self,
+        space: SpaceId,
+        tiled_by_workspace: &HashMap<VirtualWorkspaceId, Vec<WindowId>>,
+    ) -> Vec<(VirtualWorkspaceId, WindowId)> {
+        tiled_by_workspace
+            .iter()
+            .flat_map(|(&ws_id, wids)| wids.iter().map(move |&wid| (ws_id, wid)))
+            .filter(|&(ws_id, wid)| {
+                self.virtual_workspace_manager.rule_stack_group(wid).is_some()
+                    && !self.workspace_layouts.active(space, ws_id).is_some_and(|layout| {
+                        self.workspace_tree(ws_id).contains_window(layout, wid)
+                    })
+            })
+            .collect()
+    }
+
+    /// Moves each window into the stack of the first window of its `stack_with`
+    /// group already tiled on the same workspace, if there is one.
+    fn stack_windows_by_rule(
+        &mut self,
+        space: SpaceId,
+        windows: &[(VirtualWorkspaceId, WindowId)],
+    ) {
+        let default_orientation = self.layout_settings.stack.default_orientation;
+        for &(ws_id, wid) in windows {
+            let Some(layout) = self.workspace_layouts.active(space, ws_id) else {
+                continue;
+            };
+            let vwm = &self.virtual_workspace_manager;
+            let group = vwm.rule_stack_group(wid);
+            let anchor = vwm.workspace_windows(space, ws_id).into_iter().find(|&other| {
+                other != wid
+                    && vwm.rule_stack_group(other) == group
+                    && self.workspace_tree(ws_id).contains_window(layout, other)
+            });
+            if let Some(anchor) = anchor {
+                self.workspace_tree_mut(ws_id).stack_window_with(
+                    layout,
+                    wid,
+                    anchor,
+                    default_orientation,
+                );
+            }
+        }
+    }
+
     pub fn update_space_display(&mut self, space: SpaceId, display_uuid: Option<String>) {
+        let previous = self.space_display_map.get(&space).cloned().flatten();
+        let changed = previous != display_uuid;
         if let Some(uuid) = display_uuid {
             self.space_display_map.insert(space, Some(uuid.clone()));
             self.display_last_space.insert(uuid, space);
         } else {
             self.space_display_map.remove(&space);
         }
+        if changed {
+            self.apply_display_layout_overrides(space);
+        }
+    }
+
+    /// Gaps for a display from the layout settings plus any reserved insets.
+    pub fn gaps_for_display(
+        &self,
+        display_uuid: Option<&str>,
+    ) -> crate::common::config::GapSettings {
+        let mut gaps = self.layout_settings.gaps_for_display(display_uuid);
+        if let Some(insets) = display_uuid.and_then(|uuid| self.reserved_insets.get(uuid)) {
+            gaps.outer.add(insets);
+        }
+        gaps
+    }
+
+    /// Replaces the reserved insets, returning whether anything changed.
+    pub fn set_reserved_insets(
+        &mut self,
+        insets: HashMap<String, crate::common::config::OuterGaps>,
+    ) -> bool {
+        if self.reserved_insets == insets {
+            return false;
+        }
+        self.reserved_insets = insets;
+        true
+    }
+
+    fn display_uuid_str(&self, space: SpaceId) -> Option<&str> {
+        self.space_display_map.get(&space).and_then(|uuid| uuid.as_deref())
+    }
+
+    /// Whether the active workspace on `space` shows at most one tiled window, which is
+    /// when smart gaps and smart borders kick in. The monocle stack layout always counts,
+    /// since only its selected window is in view.
+    fn shows_single_tiled_window(&self, space: SpaceId) -> bool {
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return false;
+        };
+        matches!(self.workspace_tree(ws_id), LayoutSystemKind::Stack(_))
+            || self.workspace_tree(ws_id).visible_windows_in_layout(layout).len() <= 1
+    }
+
+    fn smart_gaps_active(&self, space: SpaceId) -> bool {
+        let enabled = self
+            .virtual_workspace_manager
+            .active_workspace_rule_setting(space, |rule| rule.smart_gaps)
+            .unwrap_or(self.layout_settings.smart_gaps);
+        enabled && self.shows_single_tiled_window(space)
+    }
+
+    /// Whether smart borders currently hide the stack line indicators on `space`.
+    pub fn smart_borders_active(&self, space: SpaceId) -> bool {
+        let enabled = self
+            .virtual_workspace_manager
+            .active_workspace_rule_setting(space, |rule| rule.smart_borders)
+            .unwrap_or(self.layout_settings.smart_borders);
+        enabled && self.shows_single_tiled_window(space)
+    }
+
+    /// The gaps and stack line thickness to lay out `space` with after smart gaps and
+    /// smart borders are applied. Smart gaps keep display padding, bar reservations
+    /// and reserved insets, so a lone window still clears the menu bar and any bars.
+    fn smart_spacing(
+        &self,
+        space: SpaceId,
+        gaps: &crate::common::config::GapSettings,
+        stack_line_thickness: f64,
+    ) -> (crate::common::config::GapSettings, f64) {
+        let gaps = if self.smart_gaps_active(space) {
+            let display_uuid = self.display_uuid_str(space);
+            let mut outer = self.layout_settings.reserved_edges_for_display(display_uuid);
+            if let Some(insets) = display_uuid.and_then(|uuid| self.reserved_insets.get(uuid)) {
+                outer.add(insets);
+            }
+            crate::common::config::GapSettings { outer, ..Default::default() }
+        } else {
+            gaps.clone()
+        };
+        if self.smart_borders_active(space) {
+            return (gaps, 0.0);
+        }
+        (gaps, stack_line_thickness)
+    }
+
+    /// Re-applies the configured root orientation for the display currently showing `space`.
+    fn apply_display_layout_overrides(&mut self, space: SpaceId) {
+        let Some(display) = self.layout_settings.display_override(self.display_uuid_str(space))
+        else {
+            return;
+        };
+        if !display.workspace_names.is_empty() {
+            self.virtual_workspace_manager
+                .apply_display_workspace_names(space, &display.workspace_names);
+        }
+        let Some(orientation) = display.orientation else {
+            return;
+        };
+        for (ws_id, layout) in self.workspace_layouts.active_layouts_for_space(space) {
+            if self.workspace_container_rule(space, ws_id).is_some() {
+                continue;
+            }
+            self.workspace_tree_mut(ws_id).set_root_orientation(layout, orientation);
+        }
+    }
+
+    fn workspace_container_rule(
+        &self,
+        space: SpaceId,
+        ws_id: VirtualWorkspaceId,
+    ) -> Option<super::LayoutKind> {
+        self.virtual_workspace_manager
+            .workspace_rule_setting(space, ws_id, |rule| rule.container)
+    }
+
+    /// The kind of the root container of a workspace's layout on `space`, for
+    /// layouts that have one.
+    pub fn root_container_kind(
+        &self,
+        space: SpaceId,
+        ws_id: VirtualWorkspaceId,
+    ) -> Option<super::LayoutKind> {
+        let layout = self.workspace_layouts.active(space, ws_id)?;
+        self.workspace_tree(ws_id).root_kind(layout)
+    }
+
+    /// The configured column width and side-column flag if ultrawide mode is active for `space`.
+    fn ultrawide_column_for_space(&self, space: SpaceId) -> Option<(f64, bool)> {
+        let uuid = self.display_uuid_str(space)?;
+        let overrides = self.layout_settings.display_override(Some(uuid))?;
+        let max_width = overrides.max_width?;
+        if !self.ultrawide_column_enabled.get(uuid).copied().unwrap_or(true) {
+            return None;
+        }
+        Some((max_width, overrides.side_columns))
+    }
+
+    fn arrange_side_columns(
+        &self,
+        ws_id: VirtualWorkspaceId,
+        layout: LayoutId,
+        screen: CGRect,
+        center: CGRect,
+        gaps: &crate::common::config::GapSettings,
+        positions: &mut HashMap<WindowId, CGRect>,
+    ) {
+        let tree = self.workspace_tree(ws_id);
+        let visible: Vec<WindowId> = tree
+            .visible_windows_in_layout(layout)
+            .into_iter()
+            .filter(|wid| positions.contains_key(wid))
+            .collect();
+        if visible.len() < 2 {
+            return;
+        }
+        let primary = tree
+            .selected_window(layout)
+            .filter(|wid| visible.contains(wid))
+            .unwrap_or(visible[0]);
+        let primary_frame = super::utils::compute_tiling_area(center, gaps);
+        positions.insert(primary, primary_frame);
+
+        let secondary: Vec<WindowId> = visible.into_iter().filter(|wid| *wid != primary).collect();
+        let frames = super::utils::side_column_frames(screen, primary_frame, gaps, secondary.len());
+        for (wid, frame) in secondary.into_iter().zip(frames) {
+            positions.insert(wid, frame);
+        }
+    }
+
+    /// Whether tiling `wid` in `ws_id` would exceed the display's `max_tiled_windows`.
+    /// `pending` holds windows about to be tiled in the same batch.
+    fn tiled_window_limit_reached(
+        &self,
+        space: SpaceId,
+        ws_id: VirtualWorkspaceId,
+        wid: WindowId,
+        pending: &[WindowId],
+    ) -> bool {
+        let Some(limit) = self
+            .layout_settings
+            .display_override(self.display_uuid_str(space))
+            .and_then(|o| o.max_tiled_windows)
+        else {
+            return false;
+        };
+        let Some(layout) = self.workspace_layouts.active(space, ws_id) else {
+            return false;
+        };
+        let tree = self.workspace_tree(ws_id);
+        if tree.contains_window(layout, wid) {
+            return false;
+        }
+        let tiled = self
+            .virtual_workspace_manager
+            .workspace_windows(space, ws_id)
+            .into_iter()
+            .filter(|w| *w != wid && !self.floating.is_floating(*w))
+            .filter(|w| tree.contains_window(layout, *w) || pending.contains(w))
+            .count();
+        tiled >= limit
     }
 
     pub fn last_space_for_display_uuid(&self, display_uuid: &str) -> Option<SpaceId> {
@@ -1098,6 +1513,14 @@ impl LayoutEngine {
             broadcast_tx,
             space_display_map: HashMap::default(),
             display_last_space: HashMap::default(),
+            ultrawide_column_enabled: HashMap::default(),
+            reserved_insets: HashMap::default(),
+            tiling_areas: HashMap::default(),
+            auto_float_decisions: HashMap::default(),
+            published_windows: HashMap::default(),
+            published_workspaces: HashMap::default(),
+            remembered_float_frames: HashMap::default(),
+            focus_history: Vec::new(),
         }
     }
 
@@ -1199,6 +1622,17 @@ impl LayoutEngine {
                     let ax_role_ref = ax_role_opt.as_deref();
                     let ax_subrole_ref = ax_subrole_opt.as_deref();
 
+                    let auto_float = !self.auto_float_decisions.contains_key(&wid) && {
+                        let reason = self.layout_settings.auto_float.reason(
+                            ax_subrole_ref,
+                            is_resizable,
+                            size_hint.width,
+                            size_hint.height,
+                        );
+                        self.auto_float_decisions.insert(wid, reason);
+                        reason.is_some()
+                    };
+
                     let was_floating = self.floating.is_floating(wid);
                     let assignment = match self
                         .virtual_workspace_manager
@@ -1243,12 +1677,25 @@ impl LayoutEngine {
                     };
 
                     let should_float = rule_says_float
+                        || auto_float
                         || rule_says_scratchpad.is_some()
-                        || (!prev_rule_decision && was_floating);
+                        || (!prev_rule_decision && was_floating)
+                        || self.tiled_window_limit_reached(
+                            space,
+                            assigned_workspace,
+                            wid,
+                            windows_by_workspace
+                                .get(&assigned_workspace)
+                                .map(Vec::as_slice)
+                                .unwrap_or_default(),
+                        );
 
                     if should_float {
                         self.floating.add_floating(wid);
                         self.floating.add_active(space, pid, wid);
+                        if !was_floating {
+                            self.restore_remembered_float_frame(space, wid);
+                        }
                     } else if was_floating {
                         self.floating.remove_floating(wid);
                     }
@@ -1272,7 +1719,9 @@ impl LayoutEngine {
 
                 // `windows_by_workspace` already excludes floating windows.
                 let tiled_by_workspace = windows_by_workspace;
+                let rule_stacked = self.new_windows_with_stack_rule(space, &tiled_by_workspace);
                 if self.sync_tiled_windows_for_app(space, pid, &tiled_by_workspace) {
+                    self.stack_windows_by_rule(space, &rule_stacked);
                     self.broadcast_windows_changed(space);
                     self.rebalance_all_layouts();
                 }
@@ -1311,6 +1760,7 @@ impl LayoutEngine {
                 }
                 self.floating.remove_all_for_pid(pid);
                 self.window_layout_constraints.retain(|wid, _| wid.pid != pid);
+                self.auto_float_decisions.retain(|wid, _| wid.pid != pid);
                 self.scratchpad.remove_for_app(pid);
 
                 self.virtual_workspace_manager.remove_windows_for_app(pid);
@@ -1375,6 +1825,8 @@ impl LayoutEngine {
                 }
             }
             LayoutEvent::WindowRemoved(wid) => {
+                self.remembered_float_frames.remove(&wid);
+                self.focus_history.retain(|&w| w != wid);
                 self.remove_window_internal(wid, false);
             }
             LayoutEvent::WindowRemovedPreserveFloating(wid) => {
@@ -1382,6 +1834,8 @@ impl LayoutEngine {
             }
             LayoutEvent::WindowFocused(space, wid) => {
                 self.focused_window = Some(wid);
+                self.focus_history.retain(|&w| w != wid);
+                self.focus_history.push(wid);
 
                 // If user manually focuses an inactive scratchpad (e.g. via Dock/Cmd-Tab), make it active
                 if self.scratchpad.is_scratchpad(wid) && !self.scratchpad.is_active(wid) {
@@ -1417,8 +1871,7 @@ impl LayoutEngine {
                         );
                         continue;
                     };
-                    let gaps =
-                        self.layout_settings.gaps.effective_for_display(display_uuid.as_deref());
+                    let gaps = self.gaps_for_display(display_uuid.as_deref());
                     self.workspace_tree_mut(ws_id).on_window_resized(
                         layout,
                         wid,
@@ -1435,6 +1888,10 @@ impl LayoutEngine {
         EventResponse::default()
     }
 
+    #[instrument(
+        name = "layout_engine::handle_command",
+        skip(self, visible_spaces, visible_space_centers)
+    )]
     pub fn handle_command(
         &mut self,
         space: Option<SpaceId>,
@@ -1487,23 +1944,11 @@ impl LayoutEngine {
 
                     self.floating.remove_active(space, wid.pid, wid);
                 }
+                self.virtual_workspace_manager.unpin_window(wid);
                 self.floating.remove_floating(wid);
                 self.floating.set_last_focus(None);
             } else {
-                if let Some(space) = space {
-                    self.floating.add_active(space, wid.pid, wid);
-                    if let Some((ws_id, _)) = self.workspace_and_layout(space) {
-                        self.workspace_tree_mut(ws_id).remove_window(wid);
-                    } else {
-                        debug!(
-                            "No active workspace/layout for space {:?}; leaving window {:?} out of tiling removal",
-                            space, wid
-                        );
-                    }
-                }
-                self.floating.add_floating(wid);
-                self.floating.set_last_focus(Some(wid));
-                debug!("Removed window {:?} from tiling tree, now floating", wid);
+                self.float_window(space, wid);
             }
             return EventResponse::default();
         }
@@ -1669,6 +2114,20 @@ impl LayoutEngine {
                     }
                 }
             }
+            LayoutCommand::TogglePinToSpace => {
+                let Some(wid) = self.focused_window.filter(|_| is_floating) else {
+                    debug!("Only floating windows can be pinned to a space");
+                    return EventResponse::default();
+                };
+                if self.virtual_workspace_manager.unpin_window(wid) {
+                    debug!(?wid, "Unpinned window from its space");
+                } else if self.virtual_workspace_manager.pin_window(space, wid) {
+                    debug!(?wid, ?space, "Pinned window to its space");
+                } else {
+                    debug!(?wid, ?space, "Focused window is not on this space; not pinning");
+                }
+                EventResponse::default()
+            }
             LayoutCommand::ToggleFullscreenWithinGaps => {
                 let raise_windows = self
                     .workspace_tree_mut(workspace_id)
@@ -1688,16 +2147,51 @@ impl LayoutEngine {
             LayoutCommand::NextWorkspace(_)
             | LayoutCommand::PrevWorkspace(_)
             | LayoutCommand::SwitchToWorkspace(_)
+            | LayoutCommand::FocusWorkspace(_)
             | LayoutCommand::MoveWindowToWorkspace { .. }
             | LayoutCommand::SetWorkspaceLayout { .. }
             | LayoutCommand::CreateWorkspace
-            | LayoutCommand::SwitchToLastWorkspace => EventResponse::default(),
+            | LayoutCommand::SwitchToLastWorkspace
+            | LayoutCommand::MoveWorkspaceLeft
+            | LayoutCommand::MoveWorkspaceRight
+            | LayoutCommand::SetWorkspaceIndex { .. } => EventResponse::default(),
             LayoutCommand::JoinWindow(direction) => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 self.workspace_tree_mut(workspace_id)
                     .join_selection_with_direction(layout, direction);
                 EventResponse::default()
             }
+            LayoutCommand::Split(kind) => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_tree_mut(workspace_id).split_selection(layout, kind);
+                EventResponse::default()
+            }
+            LayoutCommand::SetContainerLayout(kind) => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let raised =
+                    self.workspace_tree_mut(workspace_id).set_selected_container_kind(layout, kind);
+                Self::response_for_raised_windows(raised)
+            }
+            LayoutCommand::CycleContainerLayout(kinds) => {
+                let Some(current) =
+                    self.workspace_tree(workspace_id).selected_container_kind(layout)
+                else {
+                    return EventResponse::default();
+                };
+                let kinds = if kinds.is_empty() {
+                    super::LayoutKind::ALL.to_vec()
+                } else {
+                    kinds
+                };
+                let next = match kinds.iter().position(|&kind| kind == current) {
+                    Some(idx) => kinds[(idx + 1) % kinds.len()],
+                    None => kinds[0],
+                };
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let raised =
+                    self.workspace_tree_mut(workspace_id).set_selected_container_kind(layout, next);
+                Self::response_for_raised_windows(raised)
+            }
             LayoutCommand::ToggleStack => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 let default_orientation: crate::common::config::StackDefaultOrientation =
@@ -1743,6 +2237,20 @@ impl LayoutEngine {
                 self.workspace_tree_mut(workspace_id).unjoin_selection(layout);
                 EventResponse::default()
             }
+            LayoutCommand::AbsorbIntoStack(direction) => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let default_orientation = self.layout_settings.stack.default_orientation;
+                let stacked_windows = self
+                    .workspace_tree_mut(workspace_id)
+                    .absorb_selection_into_stack(layout, direction, default_orientation);
+                Self::response_for_raised_windows(stacked_windows)
+            }
+            LayoutCommand::ExpelFromStack => {
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                let expelled =
+                    self.workspace_tree_mut(workspace_id).expel_selection_from_stack(layout);
+                Self::response_for_raised_windows(expelled)
+            }
             LayoutCommand::ToggleOrientation => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
 
@@ -1844,6 +2352,33 @@ impl LayoutEngine {
                 self.workspace_tree_mut(workspace_id).resize_selection_by(layout, amount);
                 EventResponse::default()
             }
+            LayoutCommand::Resize { mode, axis, amount } => {
+                if is_floating {
+                    return EventResponse::default();
+                }
+                let orientation = axis.orientation();
+                let share = match amount {
+                    super::ResizeAmount::Percent(percent) => Share::OfParent(percent / 100.0),
+                    super::ResizeAmount::Pixels(pixels) => {
+                        let Some(area) = self.tiling_areas.get(&space) else {
+                            return EventResponse::default();
+                        };
+                        let extent = match orientation {
+                            super::Orientation::Horizontal => area.size.width,
+                            super::Orientation::Vertical => area.size.height,
+                        };
+                        Share::OfScreen(pixels / extent.max(1.0))
+                    }
+                };
+                self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
+                self.workspace_tree_mut(workspace_id).resize_selection_share(
+                    layout,
+                    orientation,
+                    mode,
+                    share,
+                );
+                EventResponse::default()
+            }
             LayoutCommand::AdjustMasterRatio { delta } => {
                 self.workspace_layouts.mark_last_saved(space, workspace_id, layout);
                 if let LayoutSystemKind::MasterStack(s) = self.workspace_tree_mut(workspace_id) {
@@ -1885,6 +2420,22 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::ToggleUltrawideColumn => {
+                let Some(uuid) = self.display_uuid_for_space(space) else {
+                    return EventResponse::default();
+                };
+                let configured = self
+                    .layout_settings
+                    .display_override(Some(&uuid))
+                    .is_some_and(|o| o.max_width.is_some());
+                if !configured {
+                    warn!("No ultrawide max_width configured for display {uuid}; ignoring toggle");
+                    return EventResponse::default();
+                }
+                let enabled = self.ultrawide_column_enabled.entry(uuid).or_insert(true);
+                *enabled = !*enabled;
+                EventResponse::default()
+            }
             LayoutCommand::CenterSelection => {
                 if let LayoutSystemKind::Scrolling(system) = self.workspace_tree_mut(workspace_id) {
                     system.center_selected_column(layout);
@@ -2049,6 +2600,8 @@ impl LayoutEngine {
     {
         use crate::model::HideCorner;
 
+        let (gaps, stack_line_thickness) = self.smart_spacing(space, gaps, stack_line_thickness);
+        let gaps = &gaps;
         let mut positions = HashMap::default();
         let window_size = |wid| {
             get_window_frame(wid)
@@ -2105,9 +2658,16 @@ impl LayoutEngine {
 
         if let Some(active_workspace_id) = self.virtual_workspace_manager.active_workspace(space) {
             if let Some(layout) = self.workspace_layouts.active(space, active_workspace_id) {
+                let ultrawide = self.ultrawide_column_for_space(space);
+                let tiling_screen = match ultrawide {
+                    Some((max_width, _)) => super::utils::centered_column_area(screen, max_width),
+                    None => screen,
+                };
+                let tiling_area = super::utils::compute_tiling_area(tiling_screen, gaps);
+                self.tiling_areas.insert(space, tiling_area);
                 let tiled_positions = self.workspace_tree(active_workspace_id).calculate_layout(
                     layout,
-                    screen,
+                    tiling_screen,
                     self.layout_settings.stack.stack_offset,
                     &self.window_layout_constraints,
                     gaps,
@@ -2119,6 +2679,19 @@ impl LayoutEngine {
                 for (wid, rect) in tiled_positions {
                     positions.insert(wid, rect);
                 }
+
+                if let Some((_, true)) = ultrawide
+                    && tiling_screen.size.width < screen.size.width
+                {
+                    self.arrange_side_columns(
+                        active_workspace_id,
+                        layout,
+                        screen,
+                        tiling_screen,
+                        gaps,
+                        &mut positions,
+                    );
+                }
             }
 
             let floating_positions = self
@@ -2357,6 +2930,17 @@ impl LayoutEngine {
         self.workspace_tree(ws_id).selected_window(layout)
     }
 
+    /// The windows under the selection when it is a container rather than a
+    /// single window, or `None` when a window is selected.
+    pub(crate) fn selected_container_windows(&self, space: SpaceId) -> Option<Vec<WindowId>> {
+        let (ws_id, layout) = self.workspace_and_layout(space)?;
+        let tree = self.workspace_tree(ws_id);
+        if tree.selected_window(layout).is_some() {
+            return None;
+        }
+        Some(tree.visible_windows_under_selection(layout))
+    }
+
     pub fn handle_virtual_workspace_command(
         &mut self,
         space: SpaceId,
@@ -2438,6 +3022,20 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::FocusWorkspace(direction) => {
+                let Some(index) = self.active_workspace_idx(space) else {
+                    return EventResponse::default();
+                };
+                let count = self.virtual_workspace_manager_mut().list_workspaces(space).len();
+                let columns = self.virtual_workspace_manager.grid_columns.unwrap_or(count);
+                match grid_neighbor(index as usize, count, columns, *direction) {
+                    Some(target) => self.handle_virtual_workspace_command(
+                        space,
+                        &LayoutCommand::SwitchToWorkspace(target),
+                    ),
+                    None => EventResponse::default(),
+                }
+            }
             LayoutCommand::MoveWindowToWorkspace {
                 workspace: workspace_index,
                 window_id: maybe_id,
@@ -2454,103 +3052,7 @@ impl LayoutEngine {
                     }
                 };
 
-                let inferred_space = self.space_with_window(focused_window);
-                let op_space = if inferred_space == Some(space) {
-                    space
-                } else {
-                    inferred_space.unwrap_or(space)
-                };
-
-                let workspaces = self.virtual_workspace_manager_mut().list_workspaces(op_space);
-                let Some((target_workspace_id, _)) = workspaces.get(*workspace_index) else {
-                    return EventResponse::default();
-                };
-                let target_workspace_id = *target_workspace_id;
-
-                let Some(current_workspace_id) =
-                    self.virtual_workspace_manager.workspace_for_window(op_space, focused_window)
-                else {
-                    return EventResponse::default();
-                };
-
-                if current_workspace_id == target_workspace_id {
-                    return EventResponse::default();
-                }
-
-                let is_floating = self.floating.is_floating(focused_window);
-
-                if is_floating {
-                    self.floating.remove_active_for_window(focused_window);
-                } else {
-                    self.remove_window_from_all_tiling_trees(focused_window);
-                }
-
-                let assigned = self.virtual_workspace_manager.assign_window_to_workspace(
-                    op_space,
-                    focused_window,
-                    target_workspace_id,
-                );
-                if !assigned {
-                    if is_floating {
-                        self.floating.add_active(op_space, focused_window.pid, focused_window);
-                    } else if let Some(prev_layout) =
-                        self.workspace_layouts.active(op_space, current_workspace_id)
-                    {
-                        self.workspace_tree_mut(current_workspace_id)
-                            .add_window_after_selection(prev_layout, focused_window);
-                    }
-                    return EventResponse::default();
-                }
-
-                if !is_floating {
-                    if let Some(target_layout) =
-                        self.workspace_layouts.active(op_space, target_workspace_id)
-                    {
-                        self.workspace_tree_mut(target_workspace_id)
-                            .add_window_after_selection(target_layout, focused_window);
-                    }
-                }
-
-                let active_workspace = self.virtual_workspace_manager.active_workspace(op_space);
-
-                if Some(target_workspace_id) == active_workspace {
-                    if is_floating {
-                        self.floating.add_active(op_space, focused_window.pid, focused_window);
-                    }
-                    return EventResponse {
-                        focus_window: Some(focused_window),
-                        raise_windows: vec![],
-                        boundary_hit: None,
-                        ..Default::default()
-                    };
-                } else if Some(current_workspace_id) == active_workspace {
-                    self.focused_window = None;
-                    self.virtual_workspace_manager.set_last_focused_window(
-                        op_space,
-                        current_workspace_id,
-                        None,
-                    );
-
-                    let remaining_windows =
-                        self.virtual_workspace_manager.windows_in_active_workspace(op_space);
-                    if let Some(&new_focus) = remaining_windows.first() {
-                        return EventResponse {
-                            focus_window: Some(new_focus),
-                            raise_windows: vec![],
-                            boundary_hit: None,
-                            ..Default::default()
-                        };
-                    }
-                }
-
-                self.virtual_workspace_manager.set_last_focused_window(
-                    op_space,
-                    target_workspace_id,
-                    Some(focused_window),
-                );
-
-                self.broadcast_windows_changed(op_space);
-                EventResponse::default()
+                self.move_window_to_workspace(space, focused_window, *workspace_index)
             }
             LayoutCommand::CreateWorkspace => {
                 match self.virtual_workspace_manager.create_workspace(space, None) {
@@ -2563,6 +3065,22 @@ impl LayoutEngine {
                 }
                 EventResponse::default()
             }
+            LayoutCommand::MoveWorkspaceLeft => {
+                if let Some(index) = self.active_workspace_idx(space).filter(|&i| i > 0) {
+                    self.move_workspace_to_index(space, None, index as usize - 1);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::MoveWorkspaceRight => {
+                if let Some(index) = self.active_workspace_idx(space) {
+                    self.move_workspace_to_index(space, None, index as usize + 1);
+                }
+                EventResponse::default()
+            }
+            LayoutCommand::SetWorkspaceIndex { workspace, index } => {
+                self.move_workspace_to_index(space, *workspace, *index);
+                EventResponse::default()
+            }
             LayoutCommand::SwitchToLastWorkspace => {
                 if let Some(last_workspace) = self.virtual_workspace_manager.last_workspace(space) {
                     self.virtual_workspace_manager.set_active_workspace(space, last_workspace);
@@ -2652,6 +3170,113 @@ impl LayoutEngine {
         self.virtual_workspace_manager.active_workspace_idx(space)
     }
 
+    /// Moves `window_id` to the workspace at `workspace_index` on the space that
+    /// holds it, following the window with focus when that workspace is active.
+    pub fn move_window_to_workspace(
+        &mut self,
+        space: SpaceId,
+        window_id: WindowId,
+        workspace_index: usize,
+    ) -> EventResponse {
+        let inferred_space = self.space_with_window(window_id);
+        let op_space = if inferred_space == Some(space) {
+            space
+        } else {
+            inferred_space.unwrap_or(space)
+        };
+
+        let workspaces = self.virtual_workspace_manager_mut().list_workspaces(op_space);
+        let Some((target_workspace_id, _)) = workspaces.get(workspace_index) else {
+            return EventResponse::default();
+        };
+        let target_workspace_id = *target_workspace_id;
+
+        let Some(current_workspace_id) =
+            self.virtual_workspace_manager.workspace_for_window(op_space, window_id)
+        else {
+            return EventResponse::default();
+        };
+
+        if current_workspace_id == target_workspace_id {
+            return EventResponse::default();
+        }
+
+        let is_floating = self.floating.is_floating(window_id);
+
+        if is_floating {
+            self.floating.remove_active_for_window(window_id);
+        } else {
+            self.remove_window_from_all_tiling_trees(window_id);
+        }
+
+        let assigned = self.virtual_workspace_manager.assign_window_to_workspace(
+            op_space,
+            window_id,
+            target_workspace_id,
+        );
+        if !assigned {
+            if is_floating {
+                self.floating.add_active(op_space, window_id.pid, window_id);
+            } else if let Some(prev_layout) =
+                self.workspace_layouts.active(op_space, current_workspace_id)
+            {
+                self.workspace_tree_mut(current_workspace_id)
+                    .add_window_after_selection(prev_layout, window_id);
+            }
+            return EventResponse::default();
+        }
+
+        if !is_floating {
+            if let Some(target_layout) =
+                self.workspace_layouts.active(op_space, target_workspace_id)
+            {
+                self.workspace_tree_mut(target_workspace_id)
+                    .add_window_after_selection(target_layout, window_id);
+            }
+        }
+
+        let active_workspace = self.virtual_workspace_manager.active_workspace(op_space);
+
+        if Some(target_workspace_id) == active_workspace {
+            if is_floating {
+                self.floating.add_active(op_space, window_id.pid, window_id);
+            }
+            return EventResponse {
+                focus_window: Some(window_id),
+                raise_windows: vec![],
+                boundary_hit: None,
+                ..Default::default()
+            };
+        } else if Some(current_workspace_id) == active_workspace {
+            self.focused_window = None;
+            self.virtual_workspace_manager.set_last_focused_window(
+                op_space,
+                current_workspace_id,
+                None,
+            );
+
+            let remaining_windows =
+                self.virtual_workspace_manager.windows_in_active_workspace(op_space);
+            if let Some(&new_focus) = remaining_windows.first() {
+                return EventResponse {
+                    focus_window: Some(new_focus),
+                    raise_windows: vec![],
+                    boundary_hit: None,
+                    ..Default::default()
+                };
+            }
+        }
+
+        self.virtual_workspace_manager.set_last_focused_window(
+            op_space,
+            target_workspace_id,
+            Some(window_id),
+        );
+
+        self.broadcast_windows_changed(op_space);
+        EventResponse::default()
+    }
+
     pub fn move_window_to_space(
         &mut self,
         source_space: SpaceId,
@@ -2795,10 +3420,254 @@ impl LayoutEngine {
         self.virtual_workspace_manager.get_stats()
     }
 
+    /// Tiled windows showing on `space`, leaving out the ones behind others in a stack.
+    pub fn visible_tiled_windows(&self, space: SpaceId) -> Vec<WindowId> {
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return Vec::new();
+        };
+        let windows = self.workspace_tree(ws_id).visible_windows_in_layout(layout);
+        self.filter_active_workspace_windows(space, windows)
+    }
+
+    /// The selected tiled window of the workspace showing on `space`.
+    pub fn selected_window(&self, space: SpaceId) -> Option<WindowId> {
+        let (ws_id, layout) = self.workspace_and_layout(space)?;
+        self.workspace_tree(ws_id).selected_window(layout)
+    }
+
+    /// The layout tree of the workspace showing on `space`, for the debug overlay.
+    /// Frames follow the split ratios and ignore window size constraints. Layouts
+    /// without a container tree show only their windows.
+    pub fn debug_layout_outline(
+        &self,
+        space: SpaceId,
+        screen: CGRect,
+        gaps: &crate::common::config::GapSettings,
+        stack_line_thickness: f64,
+        stack_line_horiz: crate::common::config::HorizontalPlacement,
+        stack_line_vert: crate::common::config::VerticalPlacement,
+    ) -> Option<DebugLayoutOutline> {
+        let (ws_id, layout) = self.workspace_and_layout(space)?;
+        let (gaps, stack_line_thickness) = self.smart_spacing(space, gaps, stack_line_thickness);
+        let screen = match self.ultrawide_column_for_space(space) {
+            Some((max_width, _)) => super::utils::centered_column_area(screen, max_width),
+            None => screen,
+        };
+        let stack_offset = self.layout_settings.stack.stack_offset;
+        let nodes = match self.workspace_tree(ws_id) {
+            LayoutSystemKind::Traditional(s) => s.debug_outlines(
+                layout,
+                screen,
+                stack_offset,
+                &gaps,
+                stack_line_thickness,
+                stack_line_horiz,
+                stack_line_vert,
+            ),
+            tree => tree
+                .calculate_layout(
+                    layout,
+                    screen,
+                    stack_offset,
+                    &self.window_layout_constraints,
+                    &gaps,
+                    stack_line_thickness,
+                    stack_line_horiz,
+                    stack_line_vert,
+                )
+                .into_iter()
+                .map(|(wid, frame)| DebugNodeOutline {
+                    node_id: None,
+                    kind: None,
+                    frame,
+                    ratio: None,
+                    depth: 0,
+                    window_id: Some(wid),
+                })
+                .collect(),
+        };
+        Some(DebugLayoutOutline {
+            tiling_area: super::utils::compute_tiling_area(screen, &gaps),
+            gaps,
+            nodes,
+        })
+    }
+
+    /// The area tiled windows were last laid out in on `space`, inside its gaps.
+    pub fn tiling_area(&self, space: SpaceId) -> Option<CGRect> {
+        self.tiling_areas.get(&space).copied()
+    }
+
+    /// Moves the tiled window `wid` on `space` as far as it goes in `direction`,
+    /// one `move_node` step at a time. Returns whether it moved at all.
+    pub fn move_window_to_edge(
+        &mut self,
+        space: SpaceId,
+        wid: WindowId,
+        direction: Direction,
+    ) -> bool {
+        // Layouts that wrap around would otherwise keep swapping forever.
+        const MAX_STEPS: usize = 64;
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return false;
+        };
+        if !self.workspace_tree_mut(ws_id).select_window(layout, wid) {
+            return false;
+        }
+        self.workspace_layouts.mark_last_saved(space, ws_id, layout);
+        let tree = self.workspace_tree_mut(ws_id);
+        let mut moved = false;
+        for _ in 0..MAX_STEPS {
+            if !tree.move_selection(layout, direction) {
+                break;
+            }
+            moved = true;
+        }
+        moved
+    }
+
+    /// Whether `wid` is tiled on `space` but covered by the selected window of
+    /// its stack.
+    pub fn is_hidden_in_stack(&self, space: SpaceId, wid: WindowId) -> bool {
+        let Some((ws_id, layout)) = self.workspace_and_layout(space) else {
+            return false;
+        };
+        !self.floating.is_floating(wid)
+            && self.workspace_tree(ws_id).contains_window(layout, wid)
+            && !self.visible_tiled_windows(space).contains(&wid)
+    }
+
     pub fn is_window_floating(&self, window_id: WindowId) -> bool {
         self.floating.is_floating(window_id)
     }
 
+    /// Windows still placed on a space other than `live_spaces`, with that space.
+    /// After a display is unplugged these are the windows its spaces held.
+    pub fn windows_off_spaces(&self, live_spaces: &[SpaceId]) -> Vec<(WindowId, SpaceId)> {
+        let mut windows: Vec<(WindowId, SpaceId)> = self
+            .virtual_workspace_manager
+            .window_to_workspace
+            .keys()
+            .filter(|(space, _)| !live_spaces.contains(space))
+            .map(|&(space, wid)| (wid, space))
+            .collect();
+        windows.sort_unstable();
+        windows
+    }
+
+    /// Moves `wid` from `source_space` to `target_space` without taking focus,
+    /// into the workspace with the same index it had on `source_space` so each
+    /// workspace keeps its windows. When the target has no workspace at that
+    /// index the window lands on the active one.
+    pub fn adopt_window(
+        &mut self,
+        source_space: SpaceId,
+        target_space: SpaceId,
+        target_screen_size: CGSize,
+        wid: WindowId,
+    ) {
+        let workspace_index = self
+            .virtual_workspace_manager
+            .workspace_for_window(source_space, wid)
+            .and_then(|workspace| {
+                self.virtual_workspace_manager
+                    .list_workspaces(source_space)
+                    .iter()
+                    .position(|(id, _)| *id == workspace)
+            });
+        let focused_window = self.focused_window;
+        let _ = self.move_window_to_space(source_space, target_space, target_screen_size, wid);
+        if let Some(index) = workspace_index
+            && self.active_workspace_idx(target_space) != Some(index as u64)
+        {
+            let _ = self.move_window_to_workspace(target_space, wid, index);
+        }
+        self.focused_window = focused_window;
+    }
+
+    pub fn set_remembered_float_frame(&mut self, wid: WindowId, frame: Option<CGRect>) {
+        match frame {
+            Some(frame) => self.remembered_float_frames.insert(wid, frame),
+            None => self.remembered_float_frames.remove(&wid),
+        };
+    }
+
+    /// Puts a window that just started floating back where the user last had it,
+    /// instead of wherever the app or the tiling layout left it.
+    fn restore_remembered_float_frame(&mut self, space: SpaceId, wid: WindowId) {
+        let Some(&frame) = self.remembered_float_frames.get(&wid) else {
+            return;
+        };
+        let workspace = self
+            .virtual_workspace_manager
+            .workspace_for_window(space, wid)
+            .or_else(|| self.virtual_workspace_manager.active_workspace(space));
+        if let Some(workspace) = workspace {
+            self.virtual_workspace_manager
+                .store_floating_position(space, workspace, wid, frame);
+        }
+    }
+
+    /// The windows `gather_windows` would pull into the active workspace of
+    /// `space`, with the space each is on, most recently focused first. Windows
+    /// that were never focused follow in a stable order.
+    pub fn windows_to_gather(
+        &self,
+        space: SpaceId,
+        from_other_workspaces: bool,
+        from_spaces: &[SpaceId],
+    ) -> Vec<(WindowId, SpaceId)> {
+        let active = self.virtual_workspace_manager.active_workspace(space);
+        let mut windows: Vec<(WindowId, SpaceId)> = self
+            .virtual_workspace_manager
+            .window_to_workspace
+            .iter()
+            .filter(|&(&(window_space, _), &workspace)| {
+                if window_space == space {
+                    from_other_workspaces && Some(workspace) != active
+                } else {
+                    from_spaces.contains(&window_space)
+                }
+            })
+            .map(|(&(window_space, wid), _)| (wid, window_space))
+            .collect();
+        let recency = |wid: WindowId| {
+            self.focus_history
+                .iter()
+                .rposition(|&w| w == wid)
+                .map(|i| self.focus_history.len() - i)
+        };
+        windows.sort_by_key(|&(wid, _)| (recency(wid).unwrap_or(usize::MAX), wid));
+        windows
+    }
+
+    /// Takes `wid` out of the tiling tree of `space` and floats it.
+    pub fn float_window(&mut self, space: Option<SpaceId>, wid: WindowId) {
+        if let Some(space) = space {
+            self.floating.add_active(space, wid.pid, wid);
+            self.restore_remembered_float_frame(space, wid);
+            if let Some((ws_id, _)) = self.workspace_and_layout(space) {
+                self.workspace_tree_mut(ws_id).remove_window(wid);
+            } else {
+                debug!(
+                    "No active workspace/layout for space {:?}; leaving window {:?} out of tiling removal",
+                    space, wid
+                );
+            }
+        }
+        self.floating.add_floating(wid);
+        self.floating.set_last_focus(Some(wid));
+        debug!("Removed window {:?} from tiling tree, now floating", wid);
+    }
+
+    /// The heuristic that floated `window_id`, while it is still floating.
+    pub fn auto_float_reason(&self, window_id: WindowId) -> Option<AutoFloatReason> {
+        if !self.is_window_floating(window_id) {
+            return None;
+        }
+        self.auto_float_decisions.get(&window_id).copied().flatten()
+    }
+
     fn update_active_floating_windows(&mut self, space: SpaceId) {
         let mut windows_in_workspace =
             self.virtual_workspace_manager.windows_in_active_workspace(space);
@@ -2843,6 +3712,19 @@ impl LayoutEngine {
         }
     }
 
+    fn broadcast_workspaces_reordered(&mut self, space_id: SpaceId) {
+        if let Some(ref broadcast_tx) = self.broadcast_tx {
+            let (workspace_ids, workspace_names) =
+                self.virtual_workspace_manager.list_workspaces(space_id).into_iter().unzip();
+            let _ = broadcast_tx.send(BroadcastEvent::WorkspacesReordered {
+                workspace_ids,
+                workspace_names,
+                space_id,
+                display_uuid: self.display_uuid_for_space(space_id),
+            });
+        }
+    }
+
     fn broadcast_windows_changed(&self, space_id: SpaceId) {
         if let Some(ref broadcast_tx) = self.broadcast_tx {
             if let Some((workspace_id, workspace_name)) =
@@ -2869,6 +3751,93 @@ impl LayoutEngine {
         }
     }
 
+    /// Reports workspaces that were created, renamed or deleted and windows that
+    /// started or stopped being managed or changed between tiling and floating
+    /// since the last call. Comparing snapshots catches every path that changes
+    /// them, of which there are many.
+    pub fn broadcast_state_changes(&mut self) {
+        let Some(broadcast_tx) = self.broadcast_tx.clone() else {
+            return;
+        };
+
+        let workspaces: HashMap<VirtualWorkspaceId, (SpaceId, String)> = self
+            .virtual_workspace_manager
+            .workspaces
+            .iter()
+            .map(|(id, workspace)| (id, (workspace.space, workspace.name.clone())))
+            .collect();
+        if workspaces != self.published_workspaces {
+            let mut events = Vec::new();
+            for (&workspace_id, (space_id, name)) in &self.published_workspaces {
+                if !workspaces.contains_key(&workspace_id) {
+                    events.push(BroadcastEvent::WorkspaceDeleted {
+                        workspace_id,
+                        workspace_name: name.clone(),
+                        space_id: *space_id,
+                        display_uuid: self.display_uuid_for_space(*space_id),
+                    });
+                }
+            }
+            for (&workspace_id, (space_id, name)) in &workspaces {
+                let display_uuid = self.display_uuid_for_space(*space_id);
+                match self.published_workspaces.get(&workspace_id) {
+                    None => events.push(BroadcastEvent::WorkspaceCreated {
+                        workspace_id,
+                        workspace_name: name.clone(),
+                        space_id: *space_id,
+                        display_uuid,
+                    }),
+                    Some((_, previous)) if previous != name => {
+                        events.push(BroadcastEvent::WorkspaceRenamed {
+                            workspace_id,
+                            previous_name: previous.clone(),
+                            workspace_name: name.clone(),
+                            space_id: *space_id,
+                            display_uuid,
+                        });
+                    }
+                    Some(_) => {}
+                }
+            }
+            for event in events {
+                let _ = broadcast_tx.send(event);
+            }
+            self.published_workspaces = workspaces;
+        }
+
+        let mut windows: HashMap<WindowId, bool> = HashMap::default();
+        let managed = self.virtual_workspace_manager.window_to_workspace.keys().map(|(_, wid)| wid);
+        for &wid in managed.chain(self.scratchpad.iter()) {
+            windows.insert(wid, self.floating.is_floating(wid));
+        }
+        if windows == self.published_windows {
+            return;
+        }
+        let mut gone: Vec<WindowId> = self
+            .published_windows
+            .keys()
+            .filter(|wid| !windows.contains_key(wid))
+            .copied()
+            .collect();
+        gone.sort_unstable();
+        for window_id in gone {
+            let _ = broadcast_tx.send(BroadcastEvent::WindowUnmanaged { window_id });
+        }
+        let mut current: Vec<(WindowId, bool)> = windows.iter().map(|(&w, &f)| (w, f)).collect();
+        current.sort_unstable();
+        for (window_id, floating) in current {
+            let event = match self.published_windows.get(&window_id) {
+                None => BroadcastEvent::WindowManaged { window_id, floating },
+                Some(&was) if was != floating => {
+                    BroadcastEvent::WindowFloatingChanged { window_id, floating }
+                }
+                Some(_) => continue,
+            };
+            let _ = broadcast_tx.send(event);
+        }
+        self.published_windows = windows;
+    }
+
     pub fn debug_log_workspace_stats(&self) {
         let stats = self.virtual_workspace_manager.get_stats();
         info!(
@@ -2927,9 +3896,10 @@ mod tests {
     use objc2_core_foundation::{CGPoint, CGSize};
 
     use super::*;
+    use crate::actor::broadcast::Message;
     use crate::common::collections::HashMap;
     use crate::common::config::{
-        LayoutMode, LayoutSettings, VirtualWorkspaceSettings, WorkspaceLayoutRule,
+        LayoutMode, LayoutSettings, OuterGaps, VirtualWorkspaceSettings, WorkspaceLayoutRule,
         WorkspaceSelector,
     };
 
@@ -3046,7 +4016,13 @@ mod tests {
         let mut settings = VirtualWorkspaceSettings::default();
         settings.workspace_rules = vec![WorkspaceLayoutRule {
             workspace: WorkspaceSelector::Name(workspace_name),
-            layout: LayoutMode::Scrolling,
+            layout: Some(LayoutMode::Scrolling),
+            container: None,
+            smart_gaps: None,
+            smart_borders: None,
+            stack_line_horiz_placement: None,
+            stack_line_vert_placement: None,
+            stack_line_inside: None,
         }];
 
         engine.update_virtual_workspace_settings(&settings);
@@ -3454,4 +4430,401 @@ mod tests {
             before
         );
     }
+
+    #[test]
+    fn auto_float_judges_a_window_once_so_retiling_it_sticks() {
+        let mut settings = LayoutSettings::default();
+        settings.auto_float.min_width = 300.0;
+        let mut engine = LayoutEngine::new(&VirtualWorkspaceSettings::default(), &settings, None);
+        let space = SpaceId::new(95);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let pid: pid_t = 5154;
+        let small = WindowId::new(pid, 1);
+        let update = |engine: &mut LayoutEngine| {
+            let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(
+                space,
+                pid,
+                vec![(
+                    small,
+                    None,
+                    None,
+                    Some("AXStandardWindow".into()),
+                    true,
+                    CGSize::new(200.0, 400.0),
+                    None,
+                    None,
+                )],
+                None,
+            ));
+        };
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        update(&mut engine);
+        assert!(engine.is_window_floating(small));
+        assert_eq!(engine.auto_float_reason(small), Some(AutoFloatReason::TooSmall));
+
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, small));
+        let _ = engine.handle_command(
+            Some(space),
+            &[space],
+            &HashMap::default(),
+            LayoutCommand::ToggleWindowFloating,
+        );
+        assert!(!engine.is_window_floating(small));
+        update(&mut engine);
+        assert!(!engine.is_window_floating(small));
+        assert_eq!(engine.auto_float_reason(small), None);
+    }
+
+    #[test]
+    fn named_workspace_index_creates_the_workspace_only_once() {
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(93);
+        let before = engine.virtual_workspace_manager_mut().list_workspaces(space).len();
+
+        let index = engine.named_workspace_index(space, "web").unwrap();
+        assert_eq!(index, before);
+        assert_eq!(engine.named_workspace_index(space, "web"), Some(index));
+        let workspaces = engine.virtual_workspace_manager_mut().list_workspaces(space);
+        assert_eq!(workspaces.len(), before + 1);
+        assert_eq!(workspaces[index].1, "web");
+        assert!(engine.workspace_windows_at(space, Some(index)).is_empty());
+    }
+
+    #[test]
+    fn move_window_to_workspace_pulls_a_hidden_window_onto_the_active_workspace() {
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(95);
+        let window = WindowId::new(5154, 1);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        assert!(engine.add_window_to_layout(space, window));
+
+        let switch = LayoutCommand::SwitchToWorkspace(1);
+        let _ = engine.handle_virtual_workspace_command(space, &switch);
+        assert!(!engine.is_window_in_active_workspace(space, window));
+
+        let response = engine.move_window_to_workspace(space, window, 1);
+        assert_eq!(response.focus_window, Some(window));
+        assert!(engine.is_window_in_active_workspace(space, window));
+        assert!(engine.workspace_windows_at(space, Some(0)).is_empty());
+    }
+
+    #[test]
+    fn pinned_floating_window_stays_active_through_workspace_switches() {
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(96);
+        let window = WindowId::new(5155, 1);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        assert!(engine.add_window_to_layout(space, window));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, window));
+        let run = |engine: &mut LayoutEngine, command: LayoutCommand| {
+            let _ = engine.handle_command(Some(space), &[space], &HashMap::default(), command);
+        };
+        run(&mut engine, LayoutCommand::ToggleWindowFloating);
+        run(&mut engine, LayoutCommand::TogglePinToSpace);
+
+        let switch = LayoutCommand::SwitchToWorkspace(1);
+        let _ = engine.handle_virtual_workspace_command(space, &switch);
+        assert!(engine.is_window_in_active_workspace(space, window));
+        assert_eq!(engine.active_floating_windows_in_workspace(space), [window]);
+
+        run(&mut engine, LayoutCommand::TogglePinToSpace);
+        let switch = LayoutCommand::SwitchToWorkspace(0);
+        let _ = engine.handle_virtual_workspace_command(space, &switch);
+        assert!(!engine.is_window_in_active_workspace(space, window));
+    }
+
+    #[test]
+    fn focus_workspace_moves_through_the_grid() {
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings.default_workspace_count = 4;
+        settings.grid_columns = Some(2);
+        let mut engine = LayoutEngine::new(&settings, &LayoutSettings::default(), None);
+        let space = SpaceId::new(97);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        let mut focus = |direction| {
+            let command = LayoutCommand::FocusWorkspace(direction);
+            let _ = engine.handle_virtual_workspace_command(space, &command);
+            engine.active_workspace_idx(space)
+        };
+        assert_eq!(focus(Direction::Down), Some(2));
+        assert_eq!(focus(Direction::Right), Some(3));
+        assert_eq!(focus(Direction::Right), Some(3));
+        assert_eq!(focus(Direction::Up), Some(1));
+        assert_eq!(focus(Direction::Up), Some(1));
+    }
+
+    #[test]
+    fn windows_to_gather_come_most_recently_focused_first() {
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(98);
+        let other_space = SpaceId::new(99);
+        let size = CGSize::new(1200.0, 800.0);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, size));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(other_space, size));
+        let [here, hidden_old, hidden_new, elsewhere] =
+            [1, 2, 3, 4].map(|idx| WindowId::new(5157, idx));
+        for wid in [here, hidden_old, hidden_new] {
+            assert!(engine.add_window_to_layout(space, wid));
+        }
+        assert!(engine.add_window_to_layout(other_space, elsewhere));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, hidden_old));
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, hidden_new));
+        for wid in [hidden_old, hidden_new] {
+            let _ = engine.move_window_to_workspace(space, wid, 1);
+        }
+
+        assert_eq!(engine.windows_to_gather(space, true, &[other_space]), vec![
+            (hidden_new, space),
+            (hidden_old, space),
+            (elsewhere, other_space)
+        ]);
+        assert_eq!(engine.windows_to_gather(space, false, &[other_space]), vec![(
+            elsewhere,
+            other_space
+        )]);
+        assert!(engine.windows_to_gather(space, false, &[]).is_empty());
+    }
+
+    #[test]
+    fn adopted_windows_keep_their_workspace_index() {
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(95);
+        let gone = SpaceId::new(96);
+        let size = CGSize::new(1200.0, 800.0);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, size));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(gone, size));
+        let [staying, shown, hidden] = [1, 2, 3].map(|idx| WindowId::new(5158, idx));
+        assert!(engine.add_window_to_layout(space, staying));
+        assert!(engine.add_window_to_layout(gone, shown));
+        assert!(engine.add_window_to_layout(gone, hidden));
+        let _ = engine.move_window_to_workspace(gone, hidden, 2);
+        let _ = engine.handle_event(LayoutEvent::WindowFocused(space, staying));
+
+        let orphans = engine.windows_off_spaces(&[space]);
+        assert_eq!(orphans, vec![(shown, gone), (hidden, gone)]);
+        for (wid, source) in orphans {
+            engine.adopt_window(source, space, size, wid);
+        }
+
+        assert!(engine.windows_off_spaces(&[space]).is_empty());
+        assert_eq!(engine.workspace_windows_at(space, Some(0)), vec![staying, shown]);
+        assert_eq!(engine.workspace_windows_at(space, Some(2)), vec![hidden]);
+        assert_eq!(engine.focused_window, Some(staying));
+    }
+
+    #[test]
+    fn floating_a_window_restores_its_remembered_frame() {
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            None,
+        );
+        let space = SpaceId::new(97);
+        let window = WindowId::new(5156, 1);
+        let frame = CGRect::new(CGPoint::new(40.0, 60.0), CGSize::new(500.0, 300.0));
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        assert!(engine.add_window_to_layout(space, window));
+        engine.set_remembered_float_frame(window, Some(frame));
+
+        engine.float_window(Some(space), window);
+        let workspace = engine.active_workspace(space).unwrap();
+        assert_eq!(
+            engine.virtual_workspace_manager.get_floating_position(space, workspace, window),
+            Some(frame)
+        );
+    }
+
+    #[test]
+    fn broadcast_state_changes_reports_each_transition_once() {
+        let (tx, mut rx) = crate::actor::broadcast::channel();
+        let mut engine = LayoutEngine::new(
+            &VirtualWorkspaceSettings::default(),
+            &LayoutSettings::default(),
+            Some(tx),
+        );
+        let mut drain = || {
+            let mut events = Vec::new();
+            while let Ok((_, message)) = rx.try_recv() {
+                if let Message::Event(event) = message {
+                    events.push(event);
+                }
+            }
+            events
+        };
+        let space = SpaceId::new(96);
+        let window = WindowId::new(5155, 1);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        engine.broadcast_state_changes();
+        let created = drain()
+            .into_iter()
+            .filter(|event| matches!(event, BroadcastEvent::WorkspaceCreated { .. }))
+            .count();
+        assert_eq!(created, engine.virtual_workspace_manager.workspaces.len());
+
+        assert!(engine.add_window_to_layout(space, window));
+        engine.broadcast_state_changes();
+        engine.broadcast_state_changes();
+        let managed: Vec<_> = drain()
+            .into_iter()
+            .filter(|event| matches!(event, BroadcastEvent::WindowManaged { .. }))
+            .collect();
+        assert!(matches!(
+            managed.as_slice(),
+            [BroadcastEvent::WindowManaged { window_id, floating: false }] if *window_id == window
+        ));
+
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(window));
+        engine.broadcast_state_changes();
+        assert!(drain().iter().any(|event| matches!(
+            event,
+            BroadcastEvent::WindowUnmanaged { window_id } if *window_id == window
+        )));
+    }
+
+    #[test]
+    fn smart_gaps_drop_gaps_around_a_lone_window_unless_a_workspace_rule_opts_out() {
+        let mut settings = LayoutSettings::default();
+        settings.smart_gaps = true;
+        settings.gaps.outer = OuterGaps {
+            top: 10.0,
+            left: 10.0,
+            bottom: 10.0,
+            right: 10.0,
+        };
+        settings.gaps.inner.horizontal = 10.0;
+        let mut engine = LayoutEngine::new(&VirtualWorkspaceSettings::default(), &settings, None);
+        let space = SpaceId::new(94);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let first = WindowId::new(5153, 1);
+        let second = WindowId::new(5153, 2);
+        let layout = |engine: &mut LayoutEngine| -> HashMap<WindowId, CGRect> {
+            let gaps = engine.layout_settings.gaps_for_display(None);
+            engine
+                .calculate_layout_with_virtual_workspaces(
+                    space,
+                    screen,
+                    &gaps,
+                    0.0,
+                    Default::default(),
+                    Default::default(),
+                    |_| None,
+                    &[screen],
+                )
+                .into_iter()
+                .collect()
+        };
+
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        assert!(engine.add_window_to_layout(space, first));
+        assert_eq!(layout(&mut engine)[&first], screen);
+
+        let _ = engine.add_window_to_layout(space, second);
+        assert_eq!(layout(&mut engine)[&first].origin.x, 10.0);
+
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(second));
+        let mut workspaces = VirtualWorkspaceSettings::default();
+        workspaces.workspace_rules = vec![WorkspaceLayoutRule {
+            workspace: WorkspaceSelector::Index(0),
+            layout: None,
+            container: None,
+            smart_gaps: Some(false),
+            smart_borders: None,
+            stack_line_horiz_placement: None,
+            stack_line_vert_placement: None,
+            stack_line_inside: None,
+        }];
+        engine.update_virtual_workspace_settings(&workspaces);
+        assert_eq!(layout(&mut engine)[&first].origin.x, 10.0);
+    }
+
+    #[test]
+    fn workspace_container_rule_applies_whenever_the_workspace_is_empty() {
+        use crate::layout_engine::LayoutKind;
+
+        let mut workspaces = VirtualWorkspaceSettings::default();
+        workspaces.workspace_rules = vec![WorkspaceLayoutRule {
+            workspace: WorkspaceSelector::Index(0),
+            layout: None,
+            container: Some(LayoutKind::VerticalStack),
+            smart_gaps: None,
+            smart_borders: None,
+            stack_line_horiz_placement: None,
+            stack_line_vert_placement: None,
+            stack_line_inside: None,
+        }];
+        let mut engine = LayoutEngine::new(&workspaces, &LayoutSettings::default(), None);
+        let space = SpaceId::new(95);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, CGSize::new(1200.0, 800.0)));
+        let ws_id = engine.active_workspace_id(space).unwrap();
+        let first = WindowId::new(5155, 1);
+        let second = WindowId::new(5155, 2);
+        let root = |engine: &LayoutEngine| engine.root_container_kind(space, ws_id);
+
+        let _ = engine.add_window_to_layout(space, first);
+        assert_eq!(root(&engine), Some(LayoutKind::VerticalStack));
+
+        // A container changed by hand is kept until the workspace empties out.
+        let layout = engine.workspace_layouts.active(space, ws_id).unwrap();
+        engine.workspace_tree_mut(ws_id).set_root_kind(layout, LayoutKind::Horizontal);
+        let _ = engine.add_window_to_layout(space, second);
+        assert_eq!(root(&engine), Some(LayoutKind::Horizontal));
+
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(first));
+        let _ = engine.handle_event(LayoutEvent::WindowRemoved(second));
+        let _ = engine.add_window_to_layout(space, first);
+        assert_eq!(root(&engine), Some(LayoutKind::VerticalStack));
+    }
+
+    #[test]
+    fn move_window_to_edge_moves_past_every_sibling() {
+        let mut engine = test_engine();
+        let space = SpaceId::new(93);
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1200.0, 800.0));
+        let pid: pid_t = 5252;
+        let windows = (1..=3)
+            .map(|idx| {
+                let size = CGSize::new(400.0, 800.0);
+                (WindowId::new(pid, idx), None, None, None, true, size, None, None)
+            })
+            .collect();
+        let last = WindowId::new(pid, 3);
+        let _ = engine.handle_event(LayoutEvent::SpaceExposed(space, screen.size));
+        let _ = engine.handle_event(LayoutEvent::WindowsOnScreenUpdated(space, pid, windows, None));
+
+        assert!(engine.move_window_to_edge(space, last, Direction::Left));
+        assert!(!engine.move_window_to_edge(space, last, Direction::Left));
+
+        let gaps = engine.layout_settings.gaps.clone();
+        let layout = engine.calculate_layout(
+            space,
+            screen,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        let leftmost = layout.iter().min_by(|a, b| a.1.origin.x.total_cmp(&b.1.origin.x));
+        assert_eq!(leftmost.map(|(wid, _)| *wid), Some(last));
+    }
 }
@@ -1,13 +1,16 @@
+use std::str::FromStr;
+
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Orientation {
     Horizontal,
     Vertical,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum Direction {
     Left,
@@ -56,8 +59,76 @@ impl Direction {
     }
 }
 
+/// The dimension of the selection a `resize` command changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeAxis {
+    Width,
+    Height,
+}
+
+impl ResizeAxis {
+    pub fn orientation(self) -> Orientation {
+        match self {
+            ResizeAxis::Width => Orientation::Horizontal,
+            ResizeAxis::Height => Orientation::Vertical,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResizeMode {
+    /// Make the selection exactly `amount` wide or tall.
+    Set,
+    Grow,
+    Shrink,
+}
+
+/// A `resize` amount written as `"60%"` of the parent container or `"50px"`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ResizeAmount {
+    Percent(f64),
+    Pixels(f64),
+}
+
+impl FromStr for ResizeAmount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (number, make): (_, fn(f64) -> Self) = if let Some(n) = s.strip_suffix('%') {
+            (n, ResizeAmount::Percent)
+        } else if let Some(n) = s.strip_suffix("px") {
+            (n, ResizeAmount::Pixels)
+        } else {
+            return Err(format!("expected \"60%\" or \"50px\", got {s:?}"));
+        };
+        match number.trim().parse::<f64>() {
+            Ok(value) if value.is_finite() && value >= 0.0 => Ok(make(value)),
+            _ => Err(format!("invalid resize amount {s:?}")),
+        }
+    }
+}
+
+impl TryFrom<String> for ResizeAmount {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { s.parse() }
+}
+
+impl From<ResizeAmount> for String {
+    fn from(amount: ResizeAmount) -> String {
+        match amount {
+            ResizeAmount::Percent(value) => format!("{value}%"),
+            ResizeAmount::Pixels(value) => format!("{value}px"),
+        }
+    }
+}
+
 #[allow(unused)]
-#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum LayoutKind {
     #[default]
@@ -68,6 +139,14 @@ pub enum LayoutKind {
 }
 
 impl LayoutKind {
+    /// Every kind, in the order `layout cycle` visits them.
+    pub const ALL: [LayoutKind; 4] = [
+        LayoutKind::Horizontal,
+        LayoutKind::Vertical,
+        LayoutKind::VerticalStack,
+        LayoutKind::HorizontalStack,
+    ];
+
     pub fn from(orientation: Orientation) -> Self {
         match orientation {
             Orientation::Horizontal => LayoutKind::Horizontal,
@@ -4,10 +4,18 @@ use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::HashMap;
-use crate::layout_engine::{Direction, LayoutKind};
+use crate::layout_engine::{Direction, LayoutKind, Orientation, ResizeMode};
 
 slotmap::new_key_type! { pub struct LayoutId; }
 
+/// A resize amount as a fraction of the selection's parent container or of
+/// the whole tiling area, which is how pixel amounts arrive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Share {
+    OfParent(f64),
+    OfScreen(f64),
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct WindowLayoutConstraints {
     pub is_resizable: bool,
@@ -163,9 +171,60 @@ pub trait LayoutSystem: Serialize + for<'de> Deserialize<'de> {
     ) -> Vec<WindowId>;
     fn parent_of_selection_is_stacked(&self, layout: LayoutId) -> bool;
     fn unjoin_selection(&mut self, _layout: LayoutId);
+    /// Move the selected window into the stack of the window in `direction`, stacking that
+    /// window first if it is not in one yet. Returns the windows to raise.
+    fn absorb_selection_into_stack(
+        &mut self,
+        _layout: LayoutId,
+        _direction: Direction,
+        _default_orientation: crate::common::config::StackDefaultOrientation,
+    ) -> Vec<WindowId> {
+        vec![]
+    }
+    /// Move `wid` into the stack holding `anchor`, stacking `anchor` first if it is not in
+    /// one yet. Returns whether `wid` moved.
+    fn stack_window_with(
+        &mut self,
+        _layout: LayoutId,
+        _wid: WindowId,
+        _anchor: WindowId,
+        _default_orientation: crate::common::config::StackDefaultOrientation,
+    ) -> bool {
+        false
+    }
+    /// Take the selected window out of its stack and place it beside the stack.
+    fn expel_selection_from_stack(&mut self, _layout: LayoutId) -> Vec<WindowId> { vec![] }
+    /// The kind of the container that container layout commands act on: the selection when
+    /// it is a container, otherwise the selected window's parent.
+    fn selected_container_kind(&self, _layout: LayoutId) -> Option<LayoutKind> { None }
+    /// Change the kind of that container. Returns the windows to raise.
+    fn set_selected_container_kind(
+        &mut self,
+        _layout: LayoutId,
+        _kind: LayoutKind,
+    ) -> Vec<WindowId> {
+        vec![]
+    }
     fn resize_selection_by(&mut self, layout: LayoutId, amount: f64);
+    /// Set or step the selection's share of its parent along `orientation`,
+    /// leaving every sibling a minimum share. Returns whether anything changed.
+    fn resize_selection_share(
+        &mut self,
+        _layout: LayoutId,
+        _orientation: Orientation,
+        _mode: ResizeMode,
+        _amount: Share,
+    ) -> bool {
+        false
+    }
     fn rebalance(&mut self, layout: LayoutId);
     fn toggle_tile_orientation(&mut self, layout: LayoutId);
+    /// Force the orientation of the root split. Layouts without a root split ignore this.
+    fn set_root_orientation(&mut self, _layout: LayoutId, _orientation: Orientation) {}
+    /// Give the root container `kind`. Layouts without a root container ignore this.
+    fn set_root_kind(&mut self, _layout: LayoutId, _kind: LayoutKind) {}
+    /// The kind of the root container, for layouts that have one.
+    fn root_kind(&self, _layout: LayoutId) -> Option<LayoutKind> { None }
 }
 
 mod traditional;
@@ -1622,4 +1622,13 @@ impl LayoutSystem for BspLayoutSystem {
             }
         }
     }
+
+    fn set_root_orientation(&mut self, layout: LayoutId, orientation: Orientation) {
+        let Some(state) = self.layouts.get(layout) else {
+            return;
+        };
+        if let Some(NodeKind::Split { orientation: current, .. }) = self.kind.get_mut(state.root) {
+            *current = orientation;
+        }
+    }
 }
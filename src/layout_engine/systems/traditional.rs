@@ -5,13 +5,17 @@ use tracing::warn;
 use crate::actor::app::{WindowId, pid_t};
 use crate::common::collections::HashMap;
 use crate::layout_engine::systems::constraints::{AxisConstraints, solve_axis_lengths};
-use crate::layout_engine::systems::{LayoutSystem, WindowLayoutConstraints};
+use crate::layout_engine::systems::{LayoutSystem, Share, WindowLayoutConstraints};
 use crate::layout_engine::utils::compute_tiling_area;
-use crate::layout_engine::{Direction, LayoutId, LayoutKind, Orientation};
+use crate::layout_engine::{Direction, LayoutId, LayoutKind, Orientation, ResizeMode};
 use crate::model::selection::*;
 use crate::model::tree::{self, NodeId, NodeMap, OwnedNode, Tree};
 use crate::sys::geometry::Round;
 
+/// The smallest share of a container that a `resize` leaves each sibling.
+/// Window minimum sizes are still honored when the layout is calculated.
+const MIN_SIBLING_SHARE: f64 = 0.05;
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct TraditionalLayoutSystem {
     pub(crate) tree: Tree<Components>,
@@ -1003,36 +1007,186 @@ impl LayoutSystem for TraditionalLayoutSystem {
         }
     }
 
+    fn absorb_selection_into_stack(
+        &mut self,
+        layout: LayoutId,
+        direction: Direction,
+        default_orientation: crate::common::config::StackDefaultOrientation,
+    ) -> Vec<WindowId> {
+        let selection = self.selection(layout);
+        if self.window_at(selection).is_none() {
+            return vec![];
+        }
+        let Some((target, _)) = self
+            .traverse_internal(selection, direction)
+            .and_then(|node| self.find_best_focus_target(node))
+        else {
+            return vec![];
+        };
+        let Some(stack) = self.stack_around(layout, selection, target, default_orientation) else {
+            return vec![];
+        };
+        // Enter the stack from the side the window came from.
+        let first = stack.first_child(self.map());
+        let detached = selection.detach(&mut self.tree);
+        match (direction, first) {
+            (Direction::Right | Direction::Down, Some(first)) => detached.insert_before(first),
+            _ => detached.push_back(stack),
+        };
+        self.select(selection);
+        self.visible_windows_under_internal(stack)
+    }
+
+    fn stack_window_with(
+        &mut self,
+        layout: LayoutId,
+        wid: WindowId,
+        anchor: WindowId,
+        default_orientation: crate::common::config::StackDefaultOrientation,
+    ) -> bool {
+        let window = &self.tree.data.window;
+        let (Some(node), Some(target)) =
+            (window.node_for(layout, wid), window.node_for(layout, anchor))
+        else {
+            return false;
+        };
+        if node == target {
+            return false;
+        }
+        let Some(stack) = self.stack_around(layout, node, target, default_orientation) else {
+            return false;
+        };
+        node.detach(&mut self.tree).push_back(stack);
+        self.select(node);
+        true
+    }
+
+    fn expel_selection_from_stack(&mut self, layout: LayoutId) -> Vec<WindowId> {
+        let selection = self.selection(layout);
+        let Some(wid) = self.window_at(selection) else {
+            return vec![];
+        };
+        let stack = selection.parent(self.map()).filter(|&p| self.layout(p).is_stacked());
+        let Some(stack) = stack else {
+            return vec![];
+        };
+        if stack.parent(self.map()).is_none() {
+            let kind = LayoutKind::from(self.layout(stack).orientation());
+            self.nest_in_container_internal(layout, stack, kind);
+        }
+        selection.detach(&mut self.tree).insert_after(stack);
+        self.select(selection);
+        vec![wid]
+    }
+
+    fn selected_container_kind(&self, layout: LayoutId) -> Option<LayoutKind> {
+        self.selected_container(layout).map(|container| self.layout(container))
+    }
+
+    fn set_selected_container_kind(&mut self, layout: LayoutId, kind: LayoutKind) -> Vec<WindowId> {
+        let Some(container) = self.selected_container(layout) else {
+            return vec![];
+        };
+        if self.layout(container) == kind {
+            return vec![];
+        }
+        self.set_layout(container, kind);
+        self.visible_windows_under_internal(container)
+    }
+
     fn resize_selection_by(&mut self, layout: LayoutId, amount: f64) {
         if amount == 0.0 {
             return;
         }
+        // The selection may be a container after `ascend`; it resizes as a whole.
         let selection = self.selection(layout);
-        if let Some(_focused_window) = self.window_at(selection) {
-            let candidates = selection
-                .ancestors(self.map())
-                .filter(|&node| {
-                    if let Some(parent) = node.parent(self.map()) {
-                        !self.layout(parent).is_group()
-                    } else {
-                        false
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            for direction in [
-                crate::layout_engine::Direction::Right,
-                crate::layout_engine::Direction::Down,
-                crate::layout_engine::Direction::Left,
-                crate::layout_engine::Direction::Up,
-            ] {
-                if candidates.iter().any(|&node| self.resize_internal(node, amount, direction)) {
-                    break;
+        let candidates = selection
+            .ancestors(self.map())
+            .filter(|&node| {
+                if let Some(parent) = node.parent(self.map()) {
+                    !self.layout(parent).is_group()
+                } else {
+                    false
                 }
+            })
+            .collect::<Vec<_>>();
+
+        for direction in [
+            crate::layout_engine::Direction::Right,
+            crate::layout_engine::Direction::Down,
+            crate::layout_engine::Direction::Left,
+            crate::layout_engine::Direction::Up,
+        ] {
+            if candidates.iter().any(|&node| self.resize_internal(node, amount, direction)) {
+                break;
             }
         }
     }
 
+    fn resize_selection_share(
+        &mut self,
+        layout: LayoutId,
+        orientation: Orientation,
+        mode: ResizeMode,
+        amount: Share,
+    ) -> bool {
+        let map = &self.tree.map;
+        let splits_along = |node: NodeId| {
+            let kind = self.tree.data.layout.kind(node);
+            kind.orientation() == orientation && !kind.is_group()
+        };
+        let selection = self.selection(layout);
+        let Some(node) = selection.ancestors(map).find(|n| n.parent(map).is_some_and(splits_along))
+        else {
+            return false;
+        };
+        let parent = node.parent(map).unwrap();
+        let siblings: Vec<_> = parent.children(map).filter(|&child| child != node).collect();
+        let layout_info = &self.tree.data.layout;
+        let Some(current) = layout_info.proportion(map, node) else {
+            return false;
+        };
+
+        // How much of the tiling area the parent spans along the axis.
+        let parent_extent = parent
+            .ancestors(map)
+            .filter(|n| n.parent(map).is_some_and(splits_along))
+            .filter_map(|n| layout_info.proportion(map, n))
+            .product::<f64>();
+        let amount = match amount {
+            Share::OfParent(fraction) => fraction,
+            Share::OfScreen(fraction) => fraction / parent_extent.max(f64::EPSILON),
+        };
+        let target = match mode {
+            ResizeMode::Set => amount,
+            ResizeMode::Grow => current + amount,
+            ResizeMode::Shrink => current - amount,
+        };
+        let floor = MIN_SIBLING_SHARE * siblings.len() as f64;
+        let target = target.clamp(MIN_SIBLING_SHARE, (1.0 - floor).max(MIN_SIBLING_SHARE));
+        if (target - current).abs() < 1e-6 {
+            return false;
+        }
+
+        // Siblings keep their proportions to each other in what is left over.
+        let total = f64::from(layout_info.info[parent].total);
+        let sibling_sizes: f64 =
+            siblings.iter().map(|&s| f64::from(layout_info.info[s].size.max(0.0))).sum();
+        let spare = (1.0 - target - floor).max(0.0) * total;
+        let info = &mut self.tree.data.layout.info;
+        for &sibling in &siblings {
+            let weight = if sibling_sizes > 0.0 {
+                f64::from(info[sibling].size.max(0.0)) / sibling_sizes
+            } else {
+                1.0 / siblings.len() as f64
+            };
+            info[sibling].size = (MIN_SIBLING_SHARE * total + spare * weight) as f32;
+        }
+        info[node].size = (target * total) as f32;
+        info[parent].total = parent.children(map).map(|child| info[child].size).sum();
+        true
+    }
+
     fn rebalance(&mut self, layout: LayoutId) {
         let root = self.root(layout);
         self.rebalance_node(root)
@@ -1116,9 +1270,42 @@ impl LayoutSystem for TraditionalLayoutSystem {
 
         self.rebalance(layout);
     }
+
+    fn set_root_orientation(&mut self, layout: LayoutId, orientation: Orientation) {
+        let root = self.root(layout);
+        let current_kind = self.layout(root);
+        if current_kind.is_group() || current_kind.orientation() == orientation {
+            return;
+        }
+        self.set_layout(root, LayoutKind::from(orientation));
+        self.rebalance(layout);
+    }
+
+    fn set_root_kind(&mut self, layout: LayoutId, kind: LayoutKind) {
+        let root = self.root(layout);
+        if self.layout(root) != kind {
+            self.set_layout(root, kind);
+            self.rebalance(layout);
+        }
+    }
+
+    fn root_kind(&self, layout: LayoutId) -> Option<LayoutKind> {
+        Some(self.layout(self.root(layout)))
+    }
 }
 
 impl TraditionalLayoutSystem {
+    /// The container `layout` commands change: the selection itself after `ascend`,
+    /// otherwise the container holding the selected window.
+    fn selected_container(&self, layout: LayoutId) -> Option<NodeId> {
+        let selection = self.selection(layout);
+        if self.window_at(selection).is_some() {
+            selection.parent(self.map())
+        } else {
+            Some(selection)
+        }
+    }
+
     fn split_new_sibling_from_selection(&mut self, selection: NodeId, new_sibling: NodeId) {
         let map = &self.tree.map;
         let Some(parent) = selection.parent(map) else {
@@ -1337,6 +1524,65 @@ impl TraditionalLayoutSystem {
         out
     }
 
+    /// Every node under the root of `layout` with the frame its split ratios give
+    /// it, parents before children.
+    pub(crate) fn debug_outlines(
+        &self,
+        layout: LayoutId,
+        screen: CGRect,
+        stack_offset: f64,
+        gaps: &crate::common::config::GapSettings,
+        stack_line_thickness: f64,
+        stack_line_horiz: crate::common::config::HorizontalPlacement,
+        stack_line_vert: crate::common::config::VerticalPlacement,
+    ) -> Vec<crate::layout_engine::engine::DebugNodeOutline> {
+        let map = &self.tree.map;
+        let info = &self.tree.data.layout.info;
+        let mut out = Vec::new();
+        let root = self.root(layout);
+        let mut stack = vec![(root, compute_tiling_area(screen, gaps), None, 0)];
+
+        while let Some((node, rect, ratio, depth)) = stack.pop() {
+            let window_id = self.window_at(node);
+            let kind = self.tree.data.layout.kind(node);
+            out.push(crate::layout_engine::engine::DebugNodeOutline {
+                node_id: Some(node),
+                kind: window_id.is_none().then_some(kind),
+                frame: rect,
+                ratio,
+                depth,
+                window_id,
+            });
+
+            let children: Vec<_> = node.children(map).collect();
+            if kind.is_stacked() && !children.is_empty() {
+                let layout_res = stack_layout_result(
+                    rect,
+                    children.len(),
+                    stack_offset,
+                    kind == LayoutKind::HorizontalStack,
+                    stack_line_thickness,
+                    stack_line_horiz,
+                    stack_line_vert,
+                );
+                for (i, &child) in children.iter().enumerate().rev() {
+                    let child_rect = layout_res.get_focused_frame_for_index(i, i);
+                    stack.push((child, child_rect, None, depth + 1));
+                }
+                continue;
+            }
+
+            let total: f32 = children.iter().map(|&child| info[child].size).sum();
+            for &child in children.iter().rev() {
+                let child_rect = self.calculate_child_frame_in_container(node, child, rect, gaps);
+                let ratio = f64::from(info[child].size) / f64::from(total);
+                stack.push((child, child_rect, Some(ratio), depth + 1));
+            }
+        }
+
+        out
+    }
+
     fn calculate_child_frame_in_axis(
         &self,
         parent_rect: CGRect,
@@ -1919,6 +2165,28 @@ impl TraditionalLayoutSystem {
         check_or_resize(true);
     }
 
+    /// The stack `node` should join to sit with `target`: the stack `target` is
+    /// in, or a new one around it. `None` when `node` is already in that stack.
+    fn stack_around(
+        &mut self,
+        layout: LayoutId,
+        node: NodeId,
+        target: NodeId,
+        default_orientation: crate::common::config::StackDefaultOrientation,
+    ) -> Option<NodeId> {
+        let target_parent = target.parent(self.map());
+        match target_parent {
+            Some(parent) if self.layout(parent).is_stacked() => {
+                (node.parent(self.map()) != Some(parent)).then_some(parent)
+            }
+            _ => {
+                let parent_kind = target_parent.map(|p| self.layout(p)).unwrap_or_default();
+                let kind = stack_kind_within(parent_kind, default_orientation);
+                Some(self.nest_in_container_internal(layout, target, kind))
+            }
+        }
+    }
+
     fn nest_in_container_internal(
         &mut self,
         layout: LayoutId,
@@ -2033,6 +2301,23 @@ impl TraditionalLayoutSystem {
     }
 }
 
+/// The stack kind for a new group inside a container of kind `parent`.
+fn stack_kind_within(
+    parent: LayoutKind,
+    default_orientation: crate::common::config::StackDefaultOrientation,
+) -> LayoutKind {
+    use crate::common::config::StackDefaultOrientation;
+    let orientation = match (default_orientation, parent.orientation()) {
+        (StackDefaultOrientation::Horizontal, _)
+        | (StackDefaultOrientation::Same, Orientation::Horizontal)
+        | (StackDefaultOrientation::Perpendicular, Orientation::Vertical) => {
+            Orientation::Horizontal
+        }
+        _ => Orientation::Vertical,
+    };
+    LayoutKind::stack_with_offset(orientation)
+}
+
 #[derive(Default, Serialize, Deserialize, Debug)]
 pub(crate) struct Components {
     selection: Selection,
@@ -2866,6 +3151,9 @@ fn adjust_stack_container_rect(
     container_rect
 }
 
+#[cfg(test)]
+mod properties;
+
 #[cfg(test)]
 mod tests {
     use objc2_core_foundation::{CGPoint, CGRect, CGSize};
@@ -3255,6 +3543,206 @@ mod tests {
         assert_eq!(child_windows, vec![left, a, b], "{}", system.draw_tree(layout));
     }
 
+    #[test]
+    fn absorb_stacks_the_neighbor_and_expel_restores_the_split() {
+        use crate::common::config::StackDefaultOrientation;
+
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        let windows_under = |system: &TraditionalLayoutSystem, node: NodeId| -> Vec<_> {
+            node.children(system.map())
+                .filter_map(|child| system.window_at(child))
+                .collect()
+        };
+
+        assert!(system.select_window(layout, w(3)));
+        let raised = system.absorb_selection_into_stack(
+            layout,
+            Direction::Left,
+            StackDefaultOrientation::Perpendicular,
+        );
+        assert_eq!(raised, vec![w(3)]);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        let stack = system.selection(layout).parent(system.map()).unwrap();
+        assert_eq!(system.layout(stack), LayoutKind::VerticalStack);
+        assert_eq!(windows_under(&system, stack), vec![w(2), w(3)]);
+
+        assert_eq!(system.expel_selection_from_stack(layout), vec![w(3)]);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        let root = system.root(layout);
+        assert_eq!(windows_under(&system, root), vec![w(1), w(2), w(3)]);
+        assert!(system.expel_selection_from_stack(layout).is_empty());
+    }
+
+    #[test]
+    fn absorb_grows_an_existing_stack_from_the_side_it_enters() {
+        use crate::common::config::StackDefaultOrientation;
+
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for idx in 1..=3 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+
+        let same = StackDefaultOrientation::Same;
+        assert!(system.select_window(layout, w(1)));
+        let _ = system.absorb_selection_into_stack(layout, Direction::Right, same);
+        assert!(system.select_window(layout, w(3)));
+        let _ = system.absorb_selection_into_stack(layout, Direction::Left, same);
+
+        let stack = system.selection(layout).parent(system.map()).unwrap();
+        assert_eq!(system.layout(stack), LayoutKind::HorizontalStack);
+        let stacked: Vec<_> = stack
+            .children(system.map())
+            .filter_map(|child| system.window_at(child))
+            .collect();
+        assert_eq!(stacked, vec![w(1), w(2), w(3)], "{}", system.draw_tree(layout));
+        assert!(
+            system.absorb_selection_into_stack(layout, Direction::Left, same).is_empty(),
+            "a window already in the neighbor's stack stays put"
+        );
+    }
+
+    #[test]
+    fn stack_window_with_stacks_the_anchor_on_demand() {
+        use crate::common::config::StackDefaultOrientation;
+
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        for idx in 1..=4 {
+            system.add_window_after_selection(layout, w(idx));
+        }
+        let same = StackDefaultOrientation::Same;
+        let stacked_with = |system: &TraditionalLayoutSystem, wid| -> Vec<_> {
+            let node = system.tree.data.window.node_for(layout, wid).unwrap();
+            let stack = node.parent(system.map()).unwrap();
+            stack
+                .children(system.map())
+                .filter_map(|child| system.window_at(child))
+                .collect()
+        };
+
+        assert!(system.stack_window_with(layout, w(3), w(1), same));
+        assert_eq!(stacked_with(&system, w(1)), vec![w(1), w(3)]);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        assert!(system.stack_window_with(layout, w(4), w(3), same));
+        assert_eq!(stacked_with(&system, w(1)), vec![w(1), w(3), w(4)]);
+        assert!(!system.stack_window_with(layout, w(4), w(1), same));
+        assert!(!system.stack_window_with(layout, w(2), w(2), same));
+    }
+
+    #[test]
+    fn resizing_after_ascend_resizes_the_whole_container() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.split_selection(layout, LayoutKind::Vertical);
+        system.add_window_after_selection(layout, w(3));
+
+        assert!(system.ascend_selection(layout));
+        let container = system.selection(layout);
+        assert_eq!(system.selected_window(layout), None);
+        assert_eq!(system.visible_windows_under_selection(layout), vec![w(2), w(3)]);
+
+        let before = system.tree.data.layout.info[container].size;
+        system.resize_selection_by(layout, 0.1);
+        assert!(system.tree.data.layout.info[container].size > before);
+        assert_eq!(system.selection(layout), container);
+    }
+
+    #[test]
+    fn container_layout_commands_change_the_container_at_any_depth() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.split_selection(layout, LayoutKind::Vertical);
+        system.add_window_after_selection(layout, w(3));
+        let w3 = system.tree.data.window.node_for(layout, w(3)).unwrap();
+        let inner = w3.parent(system.map()).unwrap();
+
+        assert_eq!(
+            system.selected_container_kind(layout),
+            Some(LayoutKind::Vertical)
+        );
+        let raised = system.set_selected_container_kind(layout, LayoutKind::VerticalStack);
+        assert_eq!(raised, vec![w(3)]);
+        assert_eq!(system.layout(inner), LayoutKind::VerticalStack);
+        assert_eq!(system.layout(root), LayoutKind::Horizontal);
+        assert_eq!(system.selected_window(layout), Some(w(3)));
+        assert!(system.set_selected_container_kind(layout, LayoutKind::VerticalStack).is_empty());
+
+        assert!(system.ascend_selection(layout));
+        system.set_selected_container_kind(layout, LayoutKind::Horizontal);
+        assert_eq!(system.layout(inner), LayoutKind::Horizontal);
+
+        assert!(system.ascend_selection(layout));
+        assert_eq!(
+            system.selected_container_kind(layout),
+            Some(LayoutKind::Horizontal)
+        );
+        system.set_selected_container_kind(layout, LayoutKind::Vertical);
+        assert_eq!(system.layout(root), LayoutKind::Vertical);
+        assert_eq!(system.layout(inner), LayoutKind::Horizontal);
+    }
+
+    #[test]
+    fn share_resizes_set_and_step_the_ratio_and_leave_siblings_a_minimum() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.add_window_after_selection(layout, w(3));
+        let node = system.tree.data.window.node_for(layout, w(3)).unwrap();
+        let share = |system: &TraditionalLayoutSystem, node| {
+            system.tree.data.layout.proportion(&system.tree.map, node).unwrap()
+        };
+
+        let horizontal = Orientation::Horizontal;
+        assert!(system.resize_selection_share(
+            layout,
+            horizontal,
+            ResizeMode::Set,
+            Share::OfParent(0.6)
+        ));
+        assert!((share(&system, node) - 0.6).abs() < 1e-4);
+
+        assert!(system.resize_selection_share(
+            layout,
+            horizontal,
+            ResizeMode::Grow,
+            Share::OfParent(0.5)
+        ));
+        assert!((share(&system, node) - 0.9).abs() < 1e-4);
+        let siblings: Vec<_> = root.children(&system.tree.map).filter(|&n| n != node).collect();
+        for sibling in siblings {
+            assert!(share(&system, sibling) >= MIN_SIBLING_SHARE - 1e-4);
+        }
+
+        assert!(!system.resize_selection_share(
+            layout,
+            Orientation::Vertical,
+            ResizeMode::Grow,
+            Share::OfParent(0.1)
+        ));
+    }
+
     #[test]
     fn visible_windows_follow_tree_order() {
         let mut system = TraditionalLayoutSystem::default();
@@ -4992,4 +5480,44 @@ mod tests {
             .expect("right node proportion missing");
         assert_eq!(before, after);
     }
+
+    #[test]
+    fn debug_outlines_follow_the_split_ratios() {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        let root = system.root(layout);
+        system.tree.data.layout.set_kind(root, LayoutKind::Horizontal);
+        system.add_window_after_selection(layout, w(1));
+        system.add_window_after_selection(layout, w(2));
+        system.split_selection(layout, LayoutKind::Vertical);
+        system.add_window_after_selection(layout, w(3));
+
+        let screen = CGRect::new(CGPoint::new(0.0, 0.0), CGSize::new(1000.0, 800.0));
+        let gaps = Default::default();
+        let outlines = system.debug_outlines(
+            layout,
+            screen,
+            0.0,
+            &gaps,
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+
+        assert_eq!(outlines.len(), 5);
+        assert_eq!(outlines[0].node_id, Some(root));
+        assert_eq!(outlines[0].kind, Some(LayoutKind::Horizontal));
+        assert_eq!(outlines[0].frame, screen);
+        assert_eq!(outlines[0].ratio, None);
+
+        let outline = |wid| outlines.iter().find(|o| o.window_id == Some(wid)).unwrap();
+        assert_eq!(outline(w(1)).ratio, Some(0.5));
+        assert_eq!(outline(w(1)).depth, 1);
+        assert_eq!(outline(w(1)).kind, None);
+        let w3 = outline(w(3));
+        let bottom_right = CGRect::new(CGPoint::new(500.0, 400.0), CGSize::new(500.0, 400.0));
+        assert_eq!(w3.depth, 2);
+        assert_eq!(w3.ratio, Some(0.5));
+        assert_eq!(w3.frame, bottom_right);
+    }
 }
@@ -0,0 +1,331 @@
+//! Randomized command sequences checked against the layout tree's invariants.
+//!
+//! Each case drives a fresh [`TraditionalLayoutSystem`] through a seeded sequence
+//! of window and layout operations. After every step the tree must stay
+//! well-formed, container sizes must add up, and the computed frames must stay
+//! on screen without overlapping. A failing sequence is shrunk to the shortest
+//! subsequence that still fails before it is reported, along with its seed.
+
+use std::collections::BTreeSet;
+use std::panic::{self, AssertUnwindSafe};
+
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+
+use super::*;
+use crate::layout_engine::{Direction, LayoutKind};
+use crate::sys::geometry::CGRectExt;
+
+const CASES: u64 = 200;
+const STEPS: usize = 60;
+/// Frames may be rounded to whole points, so allow that much slop.
+const TOLERANCE: f64 = 1.0;
+const SCREEN: CGRect = CGRect {
+    origin: CGPoint { x: 0.0, y: 0.0 },
+    size: CGSize { width: 1440.0, height: 900.0 },
+};
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Add,
+    /// Indices pick from the live windows, modulo their count.
+    Remove(usize),
+    Select(usize),
+    Swap(usize, usize),
+    MoveFocus(Direction),
+    MoveSelection(Direction),
+    Split(LayoutKind),
+    Join(Direction),
+    Unjoin,
+    Ascend,
+    Descend,
+    Resize(f64),
+    Rebalance,
+    ToggleOrientation,
+}
+
+/// xorshift64*, so cases are reproducible from their seed alone.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self { Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1) }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn below(&mut self, n: u64) -> usize { (self.next() % n) as usize }
+
+    fn direction(&mut self) -> Direction {
+        [
+            Direction::Left,
+            Direction::Right,
+            Direction::Up,
+            Direction::Down,
+        ][self.below(4)]
+    }
+
+    fn op(&mut self) -> Op {
+        // Adds are weighted up so trees grow deep enough to be interesting.
+        match self.below(16) {
+            0..=3 => Op::Add,
+            4 => Op::Remove(self.below(64)),
+            5 => Op::Select(self.below(64)),
+            6 => Op::Swap(self.below(64), self.below(64)),
+            7 => Op::MoveFocus(self.direction()),
+            8 => Op::MoveSelection(self.direction()),
+            9 => Op::Split([LayoutKind::Horizontal, LayoutKind::Vertical][self.below(2)]),
+            10 => Op::Join(self.direction()),
+            11 => Op::Unjoin,
+            12 => [Op::Ascend, Op::Descend][self.below(2)],
+            13 => Op::Resize((self.below(9) as f64 - 4.0) * 0.05),
+            14 => Op::Rebalance,
+            _ => Op::ToggleOrientation,
+        }
+    }
+}
+
+struct Harness {
+    system: TraditionalLayoutSystem,
+    layout: LayoutId,
+    live: Vec<WindowId>,
+    next_idx: u32,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let mut system = TraditionalLayoutSystem::default();
+        let layout = system.create_layout();
+        Harness {
+            system,
+            layout,
+            live: Vec::new(),
+            next_idx: 1,
+        }
+    }
+
+    fn pick(&self, idx: usize) -> Option<WindowId> {
+        (!self.live.is_empty()).then(|| self.live[idx % self.live.len()])
+    }
+
+    fn apply(&mut self, op: Op) {
+        let layout = self.layout;
+        match op {
+            Op::Add => {
+                let wid = WindowId::new(1 + (self.next_idx % 3) as pid_t, self.next_idx);
+                self.next_idx += 1;
+                self.system.add_window_after_selection(layout, wid);
+                self.live.push(wid);
+            }
+            Op::Remove(idx) => {
+                if let Some(wid) = self.pick(idx) {
+                    self.system.remove_window(wid);
+                    self.live.retain(|&live| live != wid);
+                }
+            }
+            Op::Select(idx) => {
+                if let Some(wid) = self.pick(idx) {
+                    self.system.select_window(layout, wid);
+                }
+            }
+            Op::Swap(a, b) => {
+                if let (Some(a), Some(b)) = (self.pick(a), self.pick(b)) {
+                    self.system.swap_windows(layout, a, b);
+                }
+            }
+            Op::MoveFocus(direction) => {
+                self.system.move_focus(layout, direction);
+            }
+            Op::MoveSelection(direction) => {
+                self.system.move_selection(layout, direction);
+            }
+            Op::Split(kind) => self.system.split_selection(layout, kind),
+            Op::Join(direction) => self.system.join_selection_with_direction(layout, direction),
+            Op::Unjoin => self.system.unjoin_selection(layout),
+            Op::Ascend => {
+                self.system.ascend_selection(layout);
+            }
+            Op::Descend => {
+                self.system.descend_selection(layout);
+            }
+            Op::Resize(amount) => self.system.resize_selection_by(layout, amount),
+            Op::Rebalance => self.system.rebalance(layout),
+            Op::ToggleOrientation => self.system.toggle_tile_orientation(layout),
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        self.check_tree()?;
+        self.check_sizes()?;
+        self.check_frames()
+    }
+
+    fn check_tree(&self) -> Result<(), String> {
+        let map = self.system.map();
+        let root = self.system.root(self.layout);
+        let mut seen = BTreeSet::new();
+        for node in root.traverse_preorder(map) {
+            for child in node.children(map) {
+                if child.parent(map) != Some(node) {
+                    return Err(format!("{child:?} does not point back to its parent {node:?}"));
+                }
+            }
+            if let Some(wid) = self.system.window_at(node) {
+                if node.children(map).next().is_some() {
+                    return Err(format!("window node for {wid:?} has children"));
+                }
+                if !seen.insert(wid) {
+                    return Err(format!("{wid:?} appears twice in the tree"));
+                }
+            }
+        }
+        let live: BTreeSet<_> = self.live.iter().copied().collect();
+        if seen != live {
+            return Err(format!("tree holds {seen:?}, expected {live:?}"));
+        }
+        Ok(())
+    }
+
+    fn check_sizes(&self) -> Result<(), String> {
+        let map = self.system.map();
+        let info = &self.system.tree.data.layout.info;
+        for node in self.system.root(self.layout).traverse_preorder(map) {
+            let children: Vec<_> = node.children(map).collect();
+            if children.is_empty() {
+                continue;
+            }
+            let mut sum = 0.0;
+            for &child in &children {
+                let size = info[child].size;
+                if !size.is_finite() || size < 0.0 {
+                    return Err(format!("{child:?} has size {size}"));
+                }
+                sum += size;
+            }
+            let total = info[node].total;
+            if (sum - total).abs() > 0.01 * total.max(1.0) {
+                return Err(format!("children of {node:?} sum to {sum}, total is {total}"));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_frames(&self) -> Result<(), String> {
+        let frames = self.system.calculate_layout(
+            self.layout,
+            SCREEN,
+            0.0,
+            &HashMap::default(),
+            &Default::default(),
+            0.0,
+            Default::default(),
+            Default::default(),
+        );
+        let bounds = CGRect::new(
+            CGPoint::new(SCREEN.origin.x - TOLERANCE, SCREEN.origin.y - TOLERANCE),
+            CGSize::new(
+                SCREEN.size.width + 2.0 * TOLERANCE,
+                SCREEN.size.height + 2.0 * TOLERANCE,
+            ),
+        );
+        for (wid, frame) in &frames {
+            if !bounds.contains_rect(*frame) {
+                return Err(format!("{wid:?} at {frame:?} is off screen"));
+            }
+        }
+        for (i, (a_wid, a)) in frames.iter().enumerate() {
+            for (b_wid, b) in &frames[i + 1..] {
+                let overlap = a.intersection(b);
+                if overlap.size.width > TOLERANCE && overlap.size.height > TOLERANCE {
+                    return Err(format!("{a_wid:?} at {a:?} overlaps {b_wid:?} at {b:?}"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs `ops` from scratch, returning the first invariant violation or panic.
+fn run(ops: &[Op]) -> Result<(), String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut harness = Harness::new();
+        for (step, &op) in ops.iter().enumerate() {
+            harness.apply(op);
+            harness.check().map_err(|e| format!("after step {step} ({op:?}): {e}"))?;
+        }
+        Ok(())
+    }))
+    .unwrap_or_else(|payload| {
+        let msg = payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_default();
+        Err(format!("panicked: {msg}"))
+    })
+}
+
+/// Greedily drops chunks of `ops`, halving the chunk size whenever no chunk can
+/// be removed, until no single op can go without `fails` turning false.
+fn shrink(mut ops: Vec<Op>, fails: impl Fn(&[Op]) -> bool) -> Vec<Op> {
+    let mut chunk = ops.len() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        let mut removed = false;
+        while start < ops.len() {
+            let end = (start + chunk).min(ops.len());
+            let candidate: Vec<Op> = ops[..start].iter().chain(&ops[end..]).copied().collect();
+            if fails(&candidate) {
+                ops = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+        if !removed {
+            chunk /= 2;
+        }
+    }
+    ops
+}
+
+#[test]
+fn random_command_sequences_preserve_layout_invariants() {
+    for seed in 0..CASES {
+        let mut rng = Rng::new(seed);
+        let ops: Vec<Op> = (0..STEPS).map(|_| rng.op()).collect();
+        if run(&ops).is_ok() {
+            continue;
+        }
+        let minimal = shrink(ops, |ops| run(ops).is_err());
+        let error = run(&minimal).unwrap_err();
+        panic!(
+            "seed {seed} failed with {} ops: {minimal:#?}\n{error}",
+            minimal.len()
+        );
+    }
+}
+
+#[test]
+fn shrinking_finds_the_smallest_failing_sequence() {
+    // Removing a window that was never added is the only way this "fails".
+    fn fails(ops: &[Op]) -> bool {
+        let adds = ops.iter().filter(|op| matches!(op, Op::Add)).count();
+        let removes = ops.iter().filter(|op| matches!(op, Op::Remove(_))).count();
+        removes > adds
+    }
+    let ops = vec![
+        Op::Add,
+        Op::Rebalance,
+        Op::Remove(0),
+        Op::Unjoin,
+        Op::Remove(1),
+    ];
+    assert!(fails(&ops));
+
+    let minimal = shrink(ops, fails);
+    assert_eq!(minimal.len(), 1);
+    assert!(matches!(minimal[0], Op::Remove(_)));
+}
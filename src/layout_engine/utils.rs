@@ -1,8 +1,8 @@
-use objc2_core_foundation::CGRect;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 
-pub fn compute_tiling_area(screen: CGRect, gaps: &crate::common::config::GapSettings) -> CGRect {
-    use objc2_core_foundation::{CGPoint, CGSize};
+use crate::layout_engine::Direction;
 
+pub fn compute_tiling_area(screen: CGRect, gaps: &crate::common::config::GapSettings) -> CGRect {
     use crate::sys::geometry::Round;
     if gaps.outer.top == 0.0
         && gaps.outer.left == 0.0
@@ -24,3 +24,343 @@ pub fn compute_tiling_area(screen: CGRect, gaps: &crate::common::config::GapSett
         .round()
     }
 }
+
+/// Constrains `screen` to at most `max_width`, centered horizontally. An odd
+/// leftover puts the extra point on the right so the column stays on whole points.
+pub fn centered_column_area(screen: CGRect, max_width: f64) -> CGRect {
+    if max_width <= 0.0 || screen.size.width <= max_width {
+        return screen;
+    }
+    CGRect {
+        origin: CGPoint {
+            x: screen.origin.x + ((screen.size.width - max_width) / 2.0).floor(),
+            y: screen.origin.y,
+        },
+        size: CGSize {
+            width: max_width,
+            height: screen.size.height,
+        },
+    }
+}
+
+/// Frames for `count` secondary windows placed in columns either side of `center`,
+/// alternating left then right. Each column is split evenly from top to bottom.
+pub fn side_column_frames(
+    screen: CGRect,
+    center: CGRect,
+    gaps: &crate::common::config::GapSettings,
+    count: usize,
+) -> Vec<CGRect> {
+    use crate::sys::geometry::Round;
+
+    let area = compute_tiling_area(screen, gaps);
+    let left = CGRect {
+        origin: area.origin,
+        size: CGSize {
+            width: (center.origin.x - gaps.inner.horizontal - area.origin.x).max(0.0),
+            height: area.size.height,
+        },
+    };
+    let right_x = center.origin.x + center.size.width + gaps.inner.horizontal;
+    let right = CGRect {
+        origin: CGPoint { x: right_x, y: area.origin.y },
+        size: CGSize {
+            width: (area.origin.x + area.size.width - right_x).max(0.0),
+            height: area.size.height,
+        },
+    };
+
+    let left_count = count.div_ceil(2);
+    let right_count = count / 2;
+    let split = |column: CGRect, n: usize, i: usize| -> CGRect {
+        let total_gap = gaps.inner.vertical * n.saturating_sub(1) as f64;
+        let height = ((column.size.height - total_gap) / n as f64).max(0.0);
+        CGRect {
+            origin: CGPoint {
+                x: column.origin.x,
+                y: column.origin.y + i as f64 * (height + gaps.inner.vertical),
+            },
+            size: CGSize {
+                width: column.size.width,
+                height,
+            },
+        }
+        .round()
+    };
+
+    (0..count)
+        .map(|i| {
+            if i % 2 == 0 {
+                split(left, left_count, i / 2)
+            } else {
+                split(right, right_count, i / 2)
+            }
+        })
+        .collect()
+}
+
+/// The largest rectangle inside `area` that overlaps none of `occupied`, or `None`
+/// when they cover all of it. Candidate edges are those of `area` and `occupied`.
+pub fn largest_empty_area(area: CGRect, occupied: &[CGRect]) -> Option<CGRect> {
+    use crate::sys::geometry::CGRectExt;
+
+    let (min, max) = (area.min(), area.max());
+    let mut xs = vec![min.x, max.x];
+    let mut ys = vec![min.y, max.y];
+    for rect in occupied {
+        xs.extend([rect.min().x, rect.max().x].map(|x| x.clamp(min.x, max.x)));
+        ys.extend([rect.min().y, rect.max().y].map(|y| y.clamp(min.y, max.y)));
+    }
+    for edges in [&mut xs, &mut ys] {
+        edges.sort_by(f64::total_cmp);
+        edges.dedup();
+    }
+
+    let mut best: Option<CGRect> = None;
+    for (i, &left) in xs.iter().enumerate() {
+        for &right in &xs[i + 1..] {
+            for (j, &top) in ys.iter().enumerate() {
+                for &bottom in &ys[j + 1..] {
+                    let candidate = CGRect::new(
+                        CGPoint::new(left, top),
+                        CGSize::new(right - left, bottom - top),
+                    );
+                    if occupied.iter().any(|rect| rect.intersection(&candidate).area() > 0.0) {
+                        // Growing downwards only covers more of the same window.
+                        break;
+                    }
+                    if best.is_none_or(|best| candidate.area() > best.area()) {
+                        best = Some(candidate);
+                    }
+                }
+            }
+        }
+    }
+    best
+}
+
+/// Edge insets `screen` needs so that tiled windows do not overlap any of `bars`.
+/// Wide bars reserve the top or bottom edge, tall bars the left or right edge.
+pub fn bar_insets_for_screen(screen: CGRect, bars: &[CGRect]) -> crate::common::config::OuterGaps {
+    let mut insets = crate::common::config::OuterGaps::default();
+    let (min_x, max_x) = (screen.origin.x, screen.origin.x + screen.size.width);
+    let (min_y, max_y) = (screen.origin.y, screen.origin.y + screen.size.height);
+    for bar in bars {
+        let bar_min_x = bar.origin.x.max(min_x);
+        let bar_max_x = (bar.origin.x + bar.size.width).min(max_x);
+        let bar_min_y = bar.origin.y.max(min_y);
+        let bar_max_y = (bar.origin.y + bar.size.height).min(max_y);
+        if bar_min_x >= bar_max_x || bar_min_y >= bar_max_y {
+            continue;
+        }
+        let width = bar_max_x - bar_min_x;
+        let height = bar_max_y - bar_min_y;
+        if width >= screen.size.width / 2.0 && width >= height {
+            if (bar_min_y + bar_max_y) / 2.0 < (min_y + max_y) / 2.0 {
+                insets.top = insets.top.max(bar_max_y - min_y);
+            } else {
+                insets.bottom = insets.bottom.max(max_y - bar_min_y);
+            }
+        } else if height >= screen.size.height / 2.0 {
+            if (bar_min_x + bar_max_x) / 2.0 < (min_x + max_x) / 2.0 {
+                insets.left = insets.left.max(bar_max_x - min_x);
+            } else {
+                insets.right = insets.right.max(max_x - bar_min_x);
+            }
+        }
+    }
+    insets
+}
+
+/// Insets that keep tiled windows clear of user-drawn exclusion zones. Each zone
+/// reserves space from whichever screen edge costs the least area to give up.
+pub fn exclusion_insets_for_screen(
+    screen: CGRect,
+    zones: &[CGRect],
+) -> crate::common::config::OuterGaps {
+    let mut insets = crate::common::config::OuterGaps::default();
+    let (min_x, max_x) = (screen.origin.x, screen.origin.x + screen.size.width);
+    let (min_y, max_y) = (screen.origin.y, screen.origin.y + screen.size.height);
+    for zone in zones {
+        let zone_min_x = zone.origin.x.max(min_x);
+        let zone_max_x = (zone.origin.x + zone.size.width).min(max_x);
+        let zone_min_y = zone.origin.y.max(min_y);
+        let zone_max_y = (zone.origin.y + zone.size.height).min(max_y);
+        if zone_min_x >= zone_max_x || zone_min_y >= zone_max_y {
+            continue;
+        }
+        let candidates = [
+            (zone_max_y - min_y, screen.size.width),
+            (zone_max_x - min_x, screen.size.height),
+            (max_y - zone_min_y, screen.size.width),
+            (max_x - zone_min_x, screen.size.height),
+        ];
+        let Some((edge, _)) = candidates
+            .iter()
+            .enumerate()
+            .min_by(|(_, (a, a_len)), (_, (b, b_len))| (a * a_len).total_cmp(&(b * b_len)))
+        else {
+            continue;
+        };
+        let depth = candidates[edge].0;
+        match edge {
+            0 => insets.top = insets.top.max(depth),
+            1 => insets.left = insets.left.max(depth),
+            2 => insets.bottom = insets.bottom.max(depth),
+            _ => insets.right = insets.right.max(depth),
+        }
+    }
+    insets
+}
+
+/// The candidate lying nearest `from` in `direction`, judged by frames alone.
+///
+/// Only candidates whose center is past the center of `from` count. They are
+/// ranked by the distance between centers along `direction` plus twice the gap
+/// between the frames across it, so a window level with `from` beats a nearer
+/// one that is off to the side.
+pub fn nearest_in_direction<T>(
+    from: CGRect,
+    direction: Direction,
+    candidates: impl IntoIterator<Item = (T, CGRect)>,
+) -> Option<T> {
+    let gap = |a_min: f64, a_max: f64, b_min: f64, b_max: f64| {
+        (b_min - a_max).max(a_min - b_max).max(0.0)
+    };
+    let (center, max) = (from.mid(), from.max());
+    candidates
+        .into_iter()
+        .filter_map(|(item, frame)| {
+            let (mid, frame_max) = (frame.mid(), frame.max());
+            let across_x = gap(from.origin.x, max.x, frame.origin.x, frame_max.x);
+            let across_y = gap(from.origin.y, max.y, frame.origin.y, frame_max.y);
+            let (along, across) = match direction {
+                Direction::Left => (center.x - mid.x, across_y),
+                Direction::Right => (mid.x - center.x, across_y),
+                Direction::Up => (center.y - mid.y, across_x),
+                Direction::Down => (mid.y - center.y, across_x),
+            };
+            (along > 0.0).then_some((item, along + 2.0 * across))
+        })
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(item, _)| item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::config::{GapSettings, InnerGaps, OuterGaps};
+
+    #[test]
+    fn largest_empty_area_picks_the_biggest_uncovered_rectangle() {
+        let rect = |x, y, w, h| CGRect::new(CGPoint::new(x, y), CGSize::new(w, h));
+        let screen = rect(0.0, 0.0, 1000.0, 800.0);
+        assert_eq!(largest_empty_area(screen, &[]), Some(screen));
+
+        let left_half = rect(0.0, 0.0, 500.0, 800.0);
+        let top_right = rect(500.0, 0.0, 500.0, 300.0);
+        assert_eq!(
+            largest_empty_area(screen, &[left_half, top_right]),
+            Some(rect(500.0, 300.0, 500.0, 500.0))
+        );
+        assert_eq!(largest_empty_area(screen, &[screen]), None);
+    }
+
+    #[test]
+    fn bar_insets_reserve_the_edge_each_bar_sits_on() {
+        let rect = |x, y, w, h| CGRect::new(CGPoint::new(x, y), CGSize::new(w, h));
+        let left_screen = rect(0.0, 0.0, 1000.0, 800.0);
+        let right_screen = rect(1000.0, 0.0, 1000.0, 800.0);
+        let gaps = |top, left, bottom, right| OuterGaps { top, left, bottom, right };
+
+        // A bar drawn on one display leaves the other alone.
+        let top_bar = rect(0.0, 0.0, 1000.0, 30.0);
+        let side_bar = rect(1960.0, 0.0, 40.0, 800.0);
+        let bars = [top_bar, side_bar];
+        assert_eq!(
+            bar_insets_for_screen(left_screen, &bars),
+            gaps(30.0, 0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            bar_insets_for_screen(right_screen, &bars),
+            gaps(0.0, 0.0, 0.0, 40.0)
+        );
+
+        // One bar across every display reserves the same edge on each.
+        let spanning = [rect(0.0, 770.0, 2000.0, 30.0)];
+        for screen in [left_screen, right_screen] {
+            assert_eq!(
+                bar_insets_for_screen(screen, &spanning),
+                gaps(0.0, 0.0, 30.0, 0.0)
+            );
+        }
+
+        // Windows too small to be a bar, and bars on no screen, reserve nothing.
+        let not_bars = [
+            rect(100.0, 0.0, 200.0, 30.0),
+            rect(-500.0, 0.0, 400.0, 30.0),
+        ];
+        assert_eq!(
+            bar_insets_for_screen(left_screen, &not_bars),
+            OuterGaps::default()
+        );
+    }
+
+    #[test]
+    fn centered_column_area_only_narrows_wide_screens() {
+        let rect = |x, y, w, h| CGRect::new(CGPoint::new(x, y), CGSize::new(w, h));
+        let screen = rect(100.0, 25.0, 1000.0, 800.0);
+        assert_eq!(
+            centered_column_area(screen, 600.0),
+            rect(300.0, 25.0, 600.0, 800.0)
+        );
+
+        // Screens no wider than the column, and unset widths, are left alone.
+        assert_eq!(centered_column_area(screen, 1000.0), screen);
+        assert_eq!(centered_column_area(screen, 1600.0), screen);
+        assert_eq!(centered_column_area(screen, 0.0), screen);
+
+        // An odd leftover keeps the column on whole points.
+        let odd = rect(0.0, 0.0, 1001.0, 800.0);
+        assert_eq!(centered_column_area(odd, 800.0), rect(100.0, 0.0, 800.0, 800.0));
+    }
+
+    #[test]
+    fn side_column_frames_alternate_left_then_right() {
+        let rect = |x, y, w, h| CGRect::new(CGPoint::new(x, y), CGSize::new(w, h));
+        let screen = rect(0.0, 0.0, 1200.0, 800.0);
+        let center = rect(300.0, 0.0, 600.0, 800.0);
+        let gaps = GapSettings {
+            outer: OuterGaps::default(),
+            inner: InnerGaps {
+                horizontal: 10.0,
+                vertical: 10.0,
+            },
+            ..Default::default()
+        };
+        let frames = |count| side_column_frames(screen, center, &gaps, count);
+
+        assert!(frames(0).is_empty());
+        // A single side window takes the whole left column.
+        assert_eq!(frames(1), [rect(0.0, 0.0, 290.0, 800.0)]);
+        assert_eq!(frames(3), [
+            rect(0.0, 0.0, 290.0, 395.0),
+            rect(910.0, 0.0, 290.0, 800.0),
+            rect(0.0, 405.0, 290.0, 395.0),
+        ]);
+    }
+
+    #[test]
+    fn nearest_in_direction_prefers_windows_level_with_the_start() {
+        let rect = |x, y, w, h| CGRect::new(CGPoint::new(x, y), CGSize::new(w, h));
+        let focused = rect(500.0, 0.0, 500.0, 800.0);
+        let float = rect(150.0, 300.0, 200.0, 200.0);
+        let low_corner = rect(380.0, 900.0, 100.0, 100.0);
+        let candidates = [("float", float), ("low_corner", low_corner)];
+        let nearest = |from, direction| nearest_in_direction(from, direction, candidates);
+        assert_eq!(nearest(focused, Direction::Left), Some("float"));
+        assert_eq!(nearest(focused, Direction::Right), None);
+        assert_eq!(nearest(float, Direction::Right), Some("low_corner"));
+        assert_eq!(nearest(float, Direction::Down), Some("low_corner"));
+    }
+}
@@ -1,8 +1,10 @@
+pub mod exclusion_zones;
 pub mod selection;
 pub mod server;
 pub mod tree;
 pub mod tx_store;
 pub mod virtual_workspace;
+pub mod window_identity;
 pub use virtual_workspace::{
     HideCorner, VirtualWorkspace, VirtualWorkspaceId, VirtualWorkspaceManager,
 };
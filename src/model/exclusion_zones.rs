@@ -0,0 +1,147 @@
+//! Persistent per-display exclusion zones that tiled windows must stay clear of.
+
+use std::path::{Path, PathBuf};
+
+use objc2_core_foundation::CGRect;
+use serde::{Deserialize, Serialize};
+
+use crate::sys::geometry::CGRectDef;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ExclusionZone {
+    pub id: u32,
+    pub display_uuid: String,
+    /// Zone bounds relative to the origin of the display frame.
+    #[serde(with = "CGRectDef")]
+    pub rect: CGRect,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ExclusionZoneStore {
+    zones: Vec<ExclusionZone>,
+    next_id: u32,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+impl ExclusionZoneStore {
+    /// Loads zones from `path`, starting empty if the file is missing or unreadable.
+    pub fn load(path: PathBuf) -> Self {
+        let mut store = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str::<Self>(&contents).unwrap_or_else(|e| {
+                tracing::warn!("Failed to parse exclusion zones at {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        store.path = Some(path);
+        store
+    }
+
+    fn save(&self) {
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        if let Err(e) = Self::write(path, self) {
+            tracing::warn!("Failed to save exclusion zones to {}: {e}", path.display());
+        }
+    }
+
+    fn write(path: &Path, store: &Self) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(store)?)?;
+        Ok(())
+    }
+
+    pub fn zones(&self) -> &[ExclusionZone] { &self.zones }
+
+    pub fn add(&mut self, display_uuid: String, rect: CGRect) -> u32 {
+        self.next_id += 1;
+        let id = self.next_id;
+        self.zones.push(ExclusionZone { id, display_uuid, rect });
+        self.save();
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) -> bool {
+        let before = self.zones.len();
+        self.zones.retain(|zone| zone.id != id);
+        let removed = self.zones.len() != before;
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    /// Removes every zone, or only those on `display_uuid` when given.
+    pub fn clear(&mut self, display_uuid: Option<&str>) -> usize {
+        let before = self.zones.len();
+        self.zones
+            .retain(|zone| display_uuid.is_some_and(|uuid| zone.display_uuid != uuid));
+        let removed = before - self.zones.len();
+        if removed > 0 {
+            self.save();
+        }
+        removed
+    }
+
+    pub fn rects_for_display<'a>(
+        &'a self,
+        display_uuid: &'a str,
+    ) -> impl Iterator<Item = CGRect> + 'a {
+        self.zones
+            .iter()
+            .filter(move |zone| zone.display_uuid == display_uuid)
+            .map(|zone| zone.rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGSize};
+
+    use super::*;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> CGRect {
+        CGRect::new(CGPoint::new(x, y), CGSize::new(w, h))
+    }
+
+    #[test]
+    fn ids_are_not_reused_after_removal() {
+        let mut store = ExclusionZoneStore::default();
+        let a = store.add("A".into(), rect(0.0, 0.0, 10.0, 10.0));
+        assert!(store.remove(a));
+        let b = store.add("A".into(), rect(0.0, 0.0, 10.0, 10.0));
+        assert_ne!(a, b);
+        assert!(!store.remove(a));
+    }
+
+    #[test]
+    fn clear_can_target_a_single_display() {
+        let mut store = ExclusionZoneStore::default();
+        store.add("A".into(), rect(0.0, 0.0, 10.0, 10.0));
+        store.add("B".into(), rect(0.0, 0.0, 10.0, 10.0));
+        store.add("A".into(), rect(5.0, 5.0, 10.0, 10.0));
+
+        assert_eq!(store.clear(Some("A")), 2);
+        assert_eq!(store.rects_for_display("A").count(), 0);
+        assert_eq!(store.rects_for_display("B").count(), 1);
+        assert_eq!(store.clear(None), 1);
+        assert!(store.zones().is_empty());
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let path =
+            std::env::temp_dir().join(format!("rift-exclusion-zones-{}.json", std::process::id()));
+        let mut store = ExclusionZoneStore::load(path.clone());
+        let id = store.add("A".into(), rect(1.0, 2.0, 3.0, 4.0));
+
+        let reloaded = ExclusionZoneStore::load(path.clone());
+        assert_eq!(reloaded.zones(), store.zones());
+        assert_eq!(reloaded.zones()[0].id, id);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -1,9 +1,13 @@
-use objc2_core_foundation::CGRect;
+use objc2_app_kit::NSWindowLevel;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::actor::app::{AppInfo, AppThreadHandle, WindowId, pid_t};
+use crate::common::config::WorkspaceSelector;
 use crate::common::log::MetricsCommand;
 use crate::layout_engine::{Direction, LayoutCommand};
+use crate::model::virtual_workspace::VirtualWorkspaceId;
 use crate::sys::app::WindowInfo;
 use crate::sys::screen::SpaceId;
 use crate::sys::window_server::WindowServerId;
@@ -19,7 +23,7 @@ pub enum Command {
     Reactor(ReactorCommand),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum DisplaySelector {
     Direction(Direction),
@@ -27,6 +31,111 @@ pub enum DisplaySelector {
     Uuid(String),
 }
 
+/// Which windows `gather_windows` pulls into the current workspace.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum GatherSource {
+    /// The other workspaces on the current display.
+    Workspace,
+    /// Every workspace on the other displays.
+    Display,
+    #[default]
+    All,
+}
+
+impl GatherSource {
+    pub fn includes_workspaces(self) -> bool { matches!(self, Self::Workspace | Self::All) }
+
+    pub fn includes_displays(self) -> bool { matches!(self, Self::Display | Self::All) }
+}
+
+/// Where `throw` sends a window.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThrowTarget {
+    /// The far side of its workspace: the end of the layout for a tiled
+    /// window, the edge of the tiling area for a floating one.
+    Edge(Direction),
+    Display(DisplaySelector),
+    Workspace(WorkspaceSelector),
+}
+
+/// Where `exec` wants the first new window of the app it launched to go.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WindowPlacement {
+    pub app: LaunchedApp,
+    /// Workspace index on the window's display.
+    pub workspace: Option<usize>,
+    pub floating: bool,
+    pub display: Option<DisplaySelector>,
+}
+
+/// The app an `exec` placement waits for a window from.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchedApp {
+    /// The process `exec` spawned.
+    Pid(pid_t),
+    /// An app started with `open -b`.
+    BundleId(String),
+    /// An app started with `open -a` or `open Foo.app`, by the name of its bundle.
+    Name(String),
+}
+
+impl LaunchedApp {
+    /// Works out which app `command` starts. `open` hands the launch to Launch
+    /// Services and exits, so the app is named by its arguments instead of by
+    /// `spawned`, the pid of `open` itself.
+    pub fn for_command(command: &[String], spawned: pid_t) -> Self {
+        let [program, args @ ..] = command else {
+            return LaunchedApp::Pid(spawned);
+        };
+        if !program.rsplit('/').next().is_some_and(|name| name == "open") {
+            return LaunchedApp::Pid(spawned);
+        }
+        let app_name = |arg: &str| {
+            let name = arg.trim_end_matches('/').rsplit('/').next().unwrap_or(arg);
+            name.strip_suffix(".app").unwrap_or(name).to_string()
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--args" {
+                break;
+            }
+            if arg.starts_with('-') && !arg.starts_with("--") {
+                // Flags can be grouped, as in `open -na Terminal`.
+                if arg.ends_with('b')
+                    && let Some(bundle_id) = args.next()
+                {
+                    return LaunchedApp::BundleId(bundle_id.clone());
+                }
+                if arg.ends_with('a')
+                    && let Some(name) = args.next()
+                {
+                    return LaunchedApp::Name(app_name(name));
+                }
+                continue;
+            }
+            if arg.trim_end_matches('/').ends_with(".app") {
+                return LaunchedApp::Name(app_name(arg));
+            }
+        }
+        LaunchedApp::Pid(spawned)
+    }
+
+    pub fn matches(&self, pid: pid_t, info: Option<&AppInfo>) -> bool {
+        match self {
+            LaunchedApp::Pid(spawned) => *spawned == pid,
+            LaunchedApp::BundleId(bundle_id) => info
+                .and_then(|info| info.bundle_id.as_deref())
+                .is_some_and(|id| id.eq_ignore_ascii_case(bundle_id)),
+            LaunchedApp::Name(name) => info
+                .and_then(|info| info.localized_name.as_deref())
+                .is_some_and(|localized| localized.eq_ignore_ascii_case(name)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum ReactorCommand {
@@ -35,6 +144,10 @@ pub enum ReactorCommand {
     SaveAndExit,
     SwitchSpace(Direction),
     ToggleSpaceActivated,
+    /// Toggles activation of the space currently shown on the given display.
+    ToggleDisplayActivated {
+        display_uuid: String,
+    },
     FocusWindow {
         window_id: WindowId,
         window_server_id: Option<WindowServerId>,
@@ -51,6 +164,81 @@ pub enum ReactorCommand {
         selector: DisplaySelector,
         window_id: Option<u32>,
     },
+    ToggleWindowStyling,
+    TogglePresentationMode {
+        #[serde(default)]
+        monocle: bool,
+    },
+    /// Draw the layout tree, gaps and reserved regions over the desktop, or
+    /// stop drawing them.
+    ToggleDebugOverlay,
+    SelectExclusionZone,
+    RemoveExclusionZone {
+        id: u32,
+    },
+    ClearExclusionZones {
+        #[serde(default)]
+        display_uuid: Option<String>,
+    },
+    PauseSpace {
+        #[serde(default)]
+        space_id: Option<SpaceId>,
+    },
+    ResumeSpace {
+        #[serde(default)]
+        space_id: Option<SpaceId>,
+    },
+    /// Moves windows back to the frames they had when rift started managing them.
+    RestoreAll {
+        #[serde(default)]
+        only_floats: bool,
+    },
+    /// Keep a window (the focused one by default) above normal windows, or put
+    /// it back at its own level if it already is.
+    ToggleTopmost {
+        #[serde(default)]
+        window_server_id: Option<WindowServerId>,
+    },
+    /// Send the focused floating window behind the other floats of its
+    /// workspace and focus the one now in front.
+    FloatLower,
+    /// Bring the bottom-most float overlapping the focused one to the front.
+    FloatCycle,
+    /// Switch to the named project's workspace, creating it and launching the
+    /// project's apps when it has no windows yet.
+    ProjectOpen {
+        name: String,
+    },
+    /// Close the windows on a workspace (by name, or the active one). Apps
+    /// still get to ask about unsaved changes.
+    ProjectClose {
+        #[serde(default)]
+        name: Option<String>,
+    },
+    /// Move the pointer to `settings.pointer.banish_corner` and hide it.
+    BanishPointer,
+    /// Turn `settings.pointer.hide_while_typing` on or off until the next reload.
+    TogglePointerHiding,
+    /// Move the windows of other workspaces and/or displays into the current
+    /// workspace, most recently focused first.
+    GatherWindows {
+        #[serde(default)]
+        from: GatherSource,
+    },
+    /// Show the focused window magnified to fill its display, or stop zooming.
+    ZoomIntoWindow,
+    /// Pick a region with the mouse and show it magnified, or stop zooming.
+    ZoomIntoRegion,
+    /// Label every window on screen and focus the one whose label is typed.
+    ShowWindowHints,
+    /// Move the focused window to an edge, display or workspace in one step,
+    /// optionally keeping focus on it.
+    Throw {
+        #[serde(flatten)]
+        target: ThrowTarget,
+        #[serde(default)]
+        follow: bool,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -91,6 +279,55 @@ pub enum DragState {
     },
 }
 
+/// What dragging a window with the `[settings.modifier_drag]` modifiers held does.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierDragMode {
+    Move,
+    Resize,
+}
+
+/// A drag the event tap took from the app under the pointer because the modifier
+/// drag modifiers were held when the button went down.
+#[derive(Debug, Clone)]
+pub struct ModifierDrag {
+    pub(crate) window: WindowId,
+    pub(crate) mode: ModifierDragMode,
+    pub(crate) start: CGPoint,
+    pub(crate) start_frame: CGRect,
+}
+
+impl ModifierDrag {
+    /// Windows are never resized below this, in points, so they can't vanish.
+    const MIN_SIZE: f64 = 64.0;
+
+    /// The window's frame with the pointer at `loc`. A resize moves the edges
+    /// nearest to where the drag started and keeps the opposite ones in place.
+    pub fn frame_at(&self, loc: CGPoint) -> CGRect {
+        let dx = loc.x - self.start.x;
+        let dy = loc.y - self.start.y;
+        let frame = self.start_frame;
+        if self.mode == ModifierDragMode::Move {
+            let origin = CGPoint::new(frame.origin.x + dx, frame.origin.y + dy);
+            return CGRect::new(origin, frame.size);
+        }
+
+        let (mut min_x, mut max_x) = (frame.origin.x, frame.origin.x + frame.size.width);
+        let (mut min_y, mut max_y) = (frame.origin.y, frame.origin.y + frame.size.height);
+        if self.start.x < frame.origin.x + frame.size.width / 2.0 {
+            min_x = (min_x + dx).min(max_x - Self::MIN_SIZE);
+        } else {
+            max_x = (max_x + dx).max(min_x + Self::MIN_SIZE);
+        }
+        if self.start.y < frame.origin.y + frame.size.height / 2.0 {
+            min_y = (min_y + dy).min(max_y - Self::MIN_SIZE);
+        } else {
+            max_y = (max_y + dy).max(min_y + Self::MIN_SIZE);
+        }
+        let size = CGSize::new(max_x - min_x, max_y - min_y);
+        CGRect::new(CGPoint::new(min_x, min_y), size)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MissionControlState {
     Inactive,
@@ -133,6 +370,8 @@ pub(crate) struct AppState {
     #[allow(unused)]
     pub(crate) info: AppInfo,
     pub(crate) handle: AppThreadHandle,
+    /// Set while the app is hidden, to tell its windows apart from minimized ones.
+    pub(crate) is_hidden: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -148,17 +387,28 @@ pub(crate) struct WindowState {
     /// This value only updates monotonically with respect to writes; in other
     /// words, we only accept reads when we know they come after the last write.
     pub(crate) frame_monotonic: CGRect,
+    /// Where the window was when rift first saw it, for putting it back.
+    pub(crate) original_frame: CGRect,
     pub(crate) is_manageable: bool,
     pub(crate) ignore_app_rule: bool,
+    /// The workspace a minimized window was in, so overlays can keep showing it
+    /// there after it leaves the layout. `None` if it was never laid out.
+    pub(crate) minimized_in: Option<VirtualWorkspaceId>,
+    /// The level the window had before `toggle_topmost` lifted it above normal
+    /// windows, or `None` if it is not topmost.
+    pub(crate) topmost_from: Option<NSWindowLevel>,
 }
 
 impl From<WindowInfo> for WindowState {
     fn from(info: WindowInfo) -> WindowState {
         WindowState {
             frame_monotonic: info.frame,
+            original_frame: info.frame,
             info,
             is_manageable: false,
             ignore_app_rule: false,
+            minimized_in: None,
+            topmost_from: None,
         }
     }
 }
@@ -1,11 +1,18 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use objc2_core_foundation::CGPoint;
 use serde::de::Deserializer;
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::actor::app::{WindowId, pid_t};
+use crate::common::config::{AppWorkspaceRule, AutoFloatReason};
+use crate::layout_engine::LayoutKind;
+use crate::model::space_activation::ActivationReason;
 use crate::sys::app::WindowInfo;
-use crate::sys::geometry::CGRectDef;
+use crate::sys::geometry::{CGPointDef, CGRectDef};
 use crate::sys::screen::{ScreenId, ScreenInfo, SpaceId};
 use crate::sys::window_server::WindowServerId;
 
@@ -15,6 +22,8 @@ pub struct WorkspaceData {
     pub index: usize,
     pub name: String,
     pub layout_mode: String,
+    /// Kind of the layout's root container, for layouts that have one.
+    pub root_container: Option<LayoutKind>,
     pub is_active: bool,
     pub window_count: usize,
     pub windows: Vec<WindowData>,
@@ -34,6 +43,14 @@ pub struct WindowData {
     pub id: WindowId,
     pub is_floating: bool,
     pub is_focused: bool,
+    /// The window's app is hidden. Hidden windows are also reported as
+    /// minimized in `info`, so check this first to tell the two apart.
+    pub is_hidden: bool,
+    /// The auto-float heuristic that floated the window, if one did.
+    pub float_reason: Option<AutoFloatReason>,
+    /// Identity that survives rift restarts and app relaunches; see
+    /// [`crate::model::window_identity`].
+    pub rift_id: Option<u64>,
     pub app_name: Option<String>,
     pub info: WindowInfo,
 }
@@ -69,6 +86,144 @@ pub struct DisplayData {
     pub inactive_space_ids: Vec<u64>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceData {
+    pub space_id: u64,
+    pub display_uuid: String,
+    pub display_name: Option<String>,
+    /// True if the space is currently shown on its display.
+    pub is_visible: bool,
+    /// True if rift manages windows on this space.
+    pub is_active: bool,
+    /// True if layout on this space is paused with `pause_space`.
+    pub is_paused: bool,
+    /// The rule that decided `is_active`.
+    pub reason: ActivationReason,
+    /// Set when a `display_activation` pattern overrides `default_disable` for the display.
+    pub display_rule_default_disable: Option<bool>,
+}
+
+/// What is under the mouse pointer, as reported by `query cursor`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorData {
+    /// Global screen coordinates, origin at the top left of the main display.
+    #[serde(with = "CGPointDef")]
+    pub position: CGPoint,
+    pub display: Option<ScreenInfo>,
+    /// The topmost window rift manages under the pointer, if any.
+    pub window: Option<WindowData>,
+    /// The workspace holding `window`.
+    pub workspace: Option<WorkspaceLayoutData>,
+}
+
+/// The app whose menus are in the menu bar, as reported by `query menu-bar-owner`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuBarOwnerData {
+    pub pid: pid_t,
+    /// Unset for apps rift does not manage.
+    pub bundle_id: Option<String>,
+    pub name: Option<String>,
+    /// The display whose menu bar is the active one.
+    pub display_uuid: Option<String>,
+}
+
+/// Whether rift is still moving windows, as reported by `query animation-state`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnimationStateData {
+    /// True until the last layout has landed and any workspace switch has
+    /// stabilized; scripts can wait for this before their next command.
+    pub in_flight: bool,
+    pub workspace_switch: bool,
+    /// Windows that haven't reported the frame rift last asked for.
+    pub pending_windows: usize,
+}
+
+/// Why rift treats a window the way it does, as reported by `query explain-window`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowExplanation {
+    pub id: WindowId,
+    pub app_name: Option<String>,
+    pub bundle_id: Option<String>,
+    pub title: String,
+    pub ax_role: Option<String>,
+    pub ax_subrole: Option<String>,
+    /// Every app rule matching the window, in config order.
+    pub matched_rules: Vec<MatchedAppRule>,
+    pub is_managed: bool,
+    pub is_floating: bool,
+    pub float_reason: Option<AutoFloatReason>,
+    pub workspace: Option<WorkspaceLayoutData>,
+    /// Whether layout changes on the window's space are animated right now.
+    pub animate: bool,
+    pub shadow: bool,
+    pub corner_radius: f64,
+    /// How rift arrived at the fields above, one step per line.
+    pub decisions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchedAppRule {
+    /// Position of the rule in `virtual_workspaces.app_rules`.
+    pub index: usize,
+    /// False when a more specific matching rule was applied instead.
+    pub applied: bool,
+    pub rule: AppWorkspaceRule,
+}
+
+/// The state dump behind `rift-cli report`; each field becomes one file in the bundle.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportData {
+    pub version: String,
+    pub os_version: String,
+    /// Seconds since the Unix epoch.
+    pub generated_at: u64,
+    /// The loaded config with every command it can run stripped.
+    pub config: serde_json::Value,
+    /// The layout engine in the same format as the restore file.
+    pub layout: String,
+    pub displays: Vec<DisplayData>,
+    pub spaces: Vec<SpaceData>,
+    /// Workspaces and their windows, keyed by the space they are on.
+    pub workspaces: BTreeMap<u64, Vec<WorkspaceData>>,
+    pub applications: Vec<ApplicationData>,
+    pub metrics: serde_json::Value,
+    /// The latest reactor events, one per line and oldest first.
+    pub events: String,
+}
+
+/// What `rift-cli screenshot` captures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotTarget {
+    /// The focused window.
+    Window,
+    /// The current workspace's windows drawn at their layout frames.
+    Workspace,
+    /// The whole display holding the focused window.
+    Display,
+}
+
+impl ScreenshotTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Window => "window",
+            Self::Workspace => "workspace",
+            Self::Display => "display",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScreenshotData {
+    pub target: ScreenshotTarget,
+    pub path: PathBuf,
+    /// Pixel size of the written PNG.
+    pub width: usize,
+    pub height: usize,
+    /// Windows drawn into the image: 1 for a window, 0 for a display as shown.
+    pub windows: usize,
+}
+
 impl Serialize for WindowData {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where S: Serializer {
@@ -81,6 +236,12 @@ impl Serialize for WindowData {
             frame: &'a objc2_core_foundation::CGRect,
             is_floating: bool,
             is_focused: bool,
+            is_minimized: bool,
+            is_hidden: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            float_reason: Option<AutoFloatReason>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            rift_id: Option<u64>,
             bundle_id: Option<&'a String>,
             app_name: Option<&'a String>,
             window_server_id: Option<u32>,
@@ -92,6 +253,10 @@ impl Serialize for WindowData {
             frame: &self.info.frame,
             is_floating: self.is_floating,
             is_focused: self.is_focused,
+            is_minimized: self.info.is_minimized,
+            is_hidden: self.is_hidden,
+            float_reason: self.float_reason,
+            rift_id: self.rift_id,
             bundle_id: self.info.bundle_id.as_ref(),
             app_name: self.app_name.as_ref(),
             window_server_id: self.info.sys_id.map(|id| id.as_u32()),
@@ -113,6 +278,14 @@ impl<'de> Deserialize<'de> for WindowData {
             frame: objc2_core_foundation::CGRect,
             is_floating: bool,
             is_focused: bool,
+            #[serde(default)]
+            is_minimized: bool,
+            #[serde(default)]
+            is_hidden: bool,
+            #[serde(default)]
+            float_reason: Option<AutoFloatReason>,
+            #[serde(default)]
+            rift_id: Option<u64>,
             bundle_id: Option<String>,
             app_name: Option<String>,
             window_server_id: Option<u32>,
@@ -122,7 +295,7 @@ impl<'de> Deserialize<'de> for WindowData {
         let info = WindowInfo {
             is_standard: true,
             is_root: true,
-            is_minimized: false,
+            is_minimized: helper.is_minimized,
             is_resizable: true,
             min_size: None,
             max_size: None,
@@ -133,12 +306,16 @@ impl<'de> Deserialize<'de> for WindowData {
             path: None,
             ax_role: None,
             ax_subrole: None,
+            ax_identifier: None,
         };
 
         Ok(WindowData {
             id: helper.id,
             is_floating: helper.is_floating,
             is_focused: helper.is_focused,
+            is_hidden: helper.is_hidden,
+            float_reason: helper.float_reason,
+            rift_id: helper.rift_id,
             app_name: helper.app_name,
             info,
         })
@@ -239,11 +416,15 @@ mod tests {
             path: None,
             ax_role: None,
             ax_subrole: None,
+            ax_identifier: None,
         };
         let data = WindowData {
             id: WindowId::new(123, 7),
             is_floating: true,
             is_focused: false,
+            is_hidden: false,
+            float_reason: None,
+            rift_id: Some(5),
             app_name: Some("Test App".to_string()),
             info,
         };
@@ -255,6 +436,9 @@ mod tests {
             "frame": { "origin": { "x": 1.0, "y": 2.0 }, "size": { "width": 3.0, "height": 4.0 } },
             "is_floating": true,
             "is_focused": false,
+            "is_minimized": false,
+            "is_hidden": false,
+            "rift_id": 5,
             "bundle_id": "com.example.test",
             "app_name": "Test App",
             "window_server_id": 99,
@@ -262,6 +446,25 @@ mod tests {
         assert_eq!(value, expected);
     }
 
+    #[test]
+    fn window_data_without_visibility_flags_is_visible() {
+        let value = json!({
+            "id": { "pid": 123, "idx": 7 },
+            "title": "Test",
+            "frame": { "origin": { "x": 1.0, "y": 2.0 }, "size": { "width": 3.0, "height": 4.0 } },
+            "is_floating": false,
+            "is_focused": true,
+            "bundle_id": null,
+            "app_name": null,
+            "window_server_id": null,
+        });
+
+        let data: WindowData = serde_json::from_value(value).expect("deserialize WindowData");
+        assert!(!data.info.is_minimized);
+        assert!(!data.is_hidden);
+        assert_eq!(data.float_reason, None);
+    }
+
     #[test]
     fn display_data_serializes_with_legacy_shape() {
         let info = ScreenInfo {
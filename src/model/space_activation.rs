@@ -1,4 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 use crate::common::collections::{HashMap, HashSet};
+use crate::common::config::DisplayActivationRules;
 use crate::sys::screen::{ScreenId, ScreenInfo, SpaceId};
 
 /// this is how we decide which macos spaces (and/or displays) are considered active.
@@ -7,6 +10,7 @@ use crate::sys::screen::{ScreenId, ScreenInfo, SpaceId};
 /// - current screen -> (space, display_uuid) snapshots
 /// - login window activation state
 /// - configuration flags (default_disable, one_space)
+/// - per-display `default_disable` overrides matched by display name/UUID
 /// - user "toggle" commands (target space/display context)
 #[derive(Debug, Default)]
 pub struct SpaceActivationPolicy {
@@ -23,7 +27,17 @@ pub struct SpaceActivationPolicy {
     last_known_space_by_screen: HashMap<ScreenId, SpaceId>,
     last_known_display_by_screen: HashMap<ScreenId, String>,
 
+    /// Spaces the user has temporarily paused. Paused spaces stay active (windows
+    /// are still tracked) but no layout is applied to them until resumed.
+    paused_spaces: HashSet<SpaceId>,
+
+    display_rules: DisplayActivationRules,
+    /// `default_disable` for displays matched by `display_rules`, keyed by display UUID.
+    display_default_overrides: HashMap<String, bool>,
+
     pub login_window_active: bool,
+    /// Set by the panic hotkey; no space is managed again until rift restarts.
+    kill_switch_engaged: bool,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -32,6 +46,32 @@ pub struct SpaceActivationConfig {
     pub one_space: bool,
 }
 
+/// The rule that decided whether a space is managed, in order of precedence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationReason {
+    KillSwitch,
+    LoginWindow,
+    OneSpace,
+    SpaceDisabled,
+    DisplayDisabled,
+    SpaceEnabled,
+    DisplayEnabled,
+    DefaultDisabled,
+    DefaultEnabled,
+}
+
+impl ActivationReason {
+    pub fn is_enabled(self) -> bool {
+        matches!(
+            self,
+            ActivationReason::SpaceEnabled
+                | ActivationReason::DisplayEnabled
+                | ActivationReason::DefaultEnabled
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ToggleSpaceContext {
     pub space: SpaceId,
@@ -49,12 +89,34 @@ impl SpaceActivationPolicy {
             starting_space: None,
             last_known_space_by_screen: HashMap::default(),
             last_known_display_by_screen: HashMap::default(),
+            paused_spaces: HashSet::default(),
+            display_rules: DisplayActivationRules::default(),
+            display_default_overrides: HashMap::default(),
             login_window_active: false,
+            kill_switch_engaged: false,
         }
     }
 
     pub fn set_login_window_active(&mut self, active: bool) { self.login_window_active = active; }
 
+    pub fn kill_switch_engaged(&self) -> bool { self.kill_switch_engaged }
+
+    /// Deactivates every space for good. Returns `true` if it was not already engaged.
+    pub fn engage_kill_switch(&mut self) -> bool {
+        !std::mem::replace(&mut self.kill_switch_engaged, true)
+    }
+
+    /// Takes effect on the next `on_spaces_updated`.
+    pub fn set_display_rules(&mut self, rules: DisplayActivationRules) {
+        self.display_rules = rules;
+    }
+
+    fn default_disable_for(&self, cfg: SpaceActivationConfig, display_uuid: Option<&str>) -> bool {
+        display_uuid
+            .and_then(|uuid| self.display_default_overrides.get(uuid).copied())
+            .unwrap_or(cfg.default_disable)
+    }
+
     #[allow(dead_code)]
     pub fn on_space_created(&mut self, space: SpaceId) { self.known_user_spaces.insert(space); }
 
@@ -86,7 +148,7 @@ impl SpaceActivationPolicy {
                 // Only transfer activation when the previous space id is no longer known
                 // (e.g. space id churn on reconnect), not for normal space switches.
                 if !self.known_user_spaces.contains(&previous_space) {
-                    self.transfer_space_activation(cfg, previous_space, new_space);
+                    self.transfer_space_activation(previous_space, new_space);
                 }
             }
 
@@ -103,7 +165,7 @@ impl SpaceActivationPolicy {
                 self.last_known_display_by_screen.get(&screen.id).cloned()
             {
                 if previous_display != new_display {
-                    self.transfer_display_activation(cfg, &previous_display, new_display);
+                    self.transfer_display_activation(&previous_display, new_display);
                 }
             }
             self.last_known_display_by_screen.insert(screen.id, new_display.to_string());
@@ -124,6 +186,21 @@ impl SpaceActivationPolicy {
         self.disabled_displays.retain(|uuid| active_displays.contains(uuid));
         self.enabled_displays.retain(|uuid| active_displays.contains(uuid));
 
+        // re-evaluate name/uuid patterns, falling back to the last known uuid so a
+        // display keeps its override while the uuid is briefly unavailable
+        self.display_default_overrides.clear();
+        for screen in screens {
+            let display_uuid = screen
+                .display_uuid_opt()
+                .or_else(|| self.last_known_display_by_screen.get(&screen.id).map(|v| v.as_str()));
+            let Some(display_uuid) = display_uuid else { continue };
+            if let Some(default_disable) =
+                self.display_rules.default_disable_for(screen.name.as_deref(), display_uuid)
+            {
+                self.display_default_overrides.insert(display_uuid.to_string(), default_disable);
+            }
+        }
+
         // apply display level activation status
         for screen in screens {
             let Some(space) = screen.space else { continue };
@@ -132,7 +209,7 @@ impl SpaceActivationPolicy {
                 .or_else(|| self.last_known_display_by_screen.get(&screen.id).map(|v| v.as_str()));
             let Some(display_uuid) = display_uuid else { continue };
 
-            if cfg.default_disable {
+            if self.default_disable_for(cfg, Some(display_uuid)) {
                 if self.enabled_displays.contains(display_uuid) {
                     self.enabled_spaces.insert(space);
                 }
@@ -154,17 +231,30 @@ impl SpaceActivationPolicy {
         }
     }
 
+    /// Returns `true` if the space was not already paused.
+    pub fn pause_space(&mut self, space: SpaceId) -> bool { self.paused_spaces.insert(space) }
+
+    /// Returns `true` if the space was paused.
+    pub fn resume_space(&mut self, space: SpaceId) -> bool { self.paused_spaces.remove(&space) }
+
+    pub fn is_space_paused(&self, space: SpaceId) -> bool { self.paused_spaces.contains(&space) }
+
+    pub fn paused_spaces(&self) -> impl Iterator<Item = SpaceId> + '_ {
+        self.paused_spaces.iter().copied()
+    }
+
     /// This mutates the policy state only; Reactor is responsible for recomputing
     /// active spaces and performing any follow-up actions.
     pub fn toggle_space_activated(&mut self, cfg: SpaceActivationConfig, ctx: ToggleSpaceContext) {
-        let space_currently_enabled = if cfg.default_disable {
+        let default_disable = self.default_disable_for(cfg, ctx.display_uuid.as_deref());
+        let space_currently_enabled = if default_disable {
             self.enabled_spaces.contains(&ctx.space)
         } else {
             !self.disabled_spaces.contains(&ctx.space)
         };
 
         if space_currently_enabled {
-            if cfg.default_disable {
+            if default_disable {
                 self.enabled_spaces.remove(&ctx.space);
                 if let Some(uuid) = ctx.display_uuid.as_ref() {
                     self.enabled_displays.remove(uuid);
@@ -172,7 +262,7 @@ impl SpaceActivationPolicy {
             } else {
                 self.disabled_spaces.insert(ctx.space);
             }
-        } else if cfg.default_disable {
+        } else if default_disable {
             self.enabled_spaces.insert(ctx.space);
             if let Some(uuid) = ctx.display_uuid.as_ref() {
                 self.enabled_displays.insert(uuid.clone());
@@ -191,29 +281,8 @@ impl SpaceActivationPolicy {
         let mut out: Vec<Option<SpaceId>> = cur_spaces.to_vec();
 
         for (idx, space_opt) in out.iter_mut().enumerate() {
-            let display_uuid = cur_display_uuids.get(idx).and_then(|v| v.as_ref());
-            let (display_enabled, display_disabled) = if cfg.default_disable {
-                (
-                    display_uuid.map(|u| self.enabled_displays.contains(u)).unwrap_or(false),
-                    display_uuid.map(|u| self.disabled_displays.contains(u)).unwrap_or(false),
-                )
-            } else {
-                (false, false)
-            };
-
-            // this is the core logic for deciding whats what
-            let enabled = match *space_opt {
-                _ if self.login_window_active => false,
-                Some(space) if cfg.one_space && Some(space) != self.starting_space => false,
-                Some(space) if self.disabled_spaces.contains(&space) => false,
-                _ if display_disabled => false,
-                Some(space) if self.enabled_spaces.contains(&space) => true,
-                _ if display_enabled => true,
-                _ if cfg.default_disable => false,
-                _ => true,
-            };
-
-            if !enabled {
+            let display_uuid = cur_display_uuids.get(idx).and_then(|v| v.as_deref());
+            if !self.activation_reason(cfg, *space_opt, display_uuid).is_enabled() {
                 *space_opt = None;
             }
         }
@@ -221,36 +290,67 @@ impl SpaceActivationPolicy {
         out
     }
 
-    fn transfer_space_activation(
-        &mut self,
+    pub fn activation_reason(
+        &self,
         cfg: SpaceActivationConfig,
-        old_space: SpaceId,
-        new_space: SpaceId,
-    ) {
-        if cfg.default_disable {
-            if self.enabled_spaces.remove(&old_space) {
-                self.enabled_spaces.insert(new_space);
+        space: Option<SpaceId>,
+        display_uuid: Option<&str>,
+    ) -> ActivationReason {
+        let default_disable = self.default_disable_for(cfg, display_uuid);
+        let (display_enabled, display_disabled) = if default_disable {
+            (
+                display_uuid.map(|u| self.enabled_displays.contains(u)).unwrap_or(false),
+                display_uuid.map(|u| self.disabled_displays.contains(u)).unwrap_or(false),
+            )
+        } else {
+            (false, false)
+        };
+
+        // this is the core logic for deciding whats what
+        match space {
+            _ if self.kill_switch_engaged => ActivationReason::KillSwitch,
+            _ if self.login_window_active => ActivationReason::LoginWindow,
+            Some(space) if cfg.one_space && Some(space) != self.starting_space => {
+                ActivationReason::OneSpace
             }
-        } else if self.disabled_spaces.remove(&old_space) {
+            Some(space) if self.disabled_spaces.contains(&space) => ActivationReason::SpaceDisabled,
+            _ if display_disabled => ActivationReason::DisplayDisabled,
+            Some(space) if self.enabled_spaces.contains(&space) => ActivationReason::SpaceEnabled,
+            _ if display_enabled => ActivationReason::DisplayEnabled,
+            _ if default_disable => ActivationReason::DefaultDisabled,
+            _ => ActivationReason::DefaultEnabled,
+        }
+    }
+
+    /// The `default_disable` a `display_activation` pattern assigned to this display, if any.
+    pub fn display_rule_default(&self, display_uuid: &str) -> Option<bool> {
+        self.display_default_overrides.get(display_uuid).copied()
+    }
+
+    // Both sets are carried over because the default for a space depends on which
+    // display it is on, which may differ from the global `default_disable`.
+    pub fn transfer_space_activation(&mut self, old_space: SpaceId, new_space: SpaceId) {
+        if self.enabled_spaces.remove(&old_space) {
+            self.enabled_spaces.insert(new_space);
+        }
+        if self.disabled_spaces.remove(&old_space) {
             self.disabled_spaces.insert(new_space);
         }
 
+        if self.paused_spaces.remove(&old_space) {
+            self.paused_spaces.insert(new_space);
+        }
+
         if self.starting_space == Some(old_space) {
             self.starting_space = Some(new_space);
         }
     }
 
-    fn transfer_display_activation(
-        &mut self,
-        cfg: SpaceActivationConfig,
-        old_display: &str,
-        new_display: &str,
-    ) {
-        if cfg.default_disable {
-            if self.enabled_displays.remove(old_display) {
-                self.enabled_displays.insert(new_display.to_string());
-            }
-        } else if self.disabled_displays.remove(old_display) {
+    fn transfer_display_activation(&mut self, old_display: &str, new_display: &str) {
+        if self.enabled_displays.remove(old_display) {
+            self.enabled_displays.insert(new_display.to_string());
+        }
+        if self.disabled_displays.remove(old_display) {
             self.disabled_displays.insert(new_display.to_string());
         }
     }
@@ -520,6 +620,107 @@ mod tests {
         assert!(policy.disabled_spaces.contains(&SpaceId::new(2)));
     }
 
+    #[test]
+    fn paused_space_stays_active_and_survives_space_id_churn() {
+        let mut policy = SpaceActivationPolicy::new();
+        let cfg = SpaceActivationConfig {
+            default_disable: false,
+            one_space: false,
+        };
+
+        policy.on_spaces_updated(cfg, &[input(1, Some(1), Some("display-a"))]);
+        assert!(policy.pause_space(SpaceId::new(1)));
+        assert!(!policy.pause_space(SpaceId::new(1)));
+
+        let spaces = [Some(SpaceId::new(1))];
+        let displays = [Some("display-a".to_string())];
+        let active = policy.compute_active_spaces(cfg, &spaces, &displays);
+        assert_eq!(active, vec![Some(SpaceId::new(1))]);
+
+        policy.on_space_destroyed(SpaceId::new(1));
+        policy.on_spaces_updated(cfg, &[input(1, Some(2), Some("display-a"))]);
+
+        assert!(!policy.is_space_paused(SpaceId::new(1)));
+        assert!(policy.is_space_paused(SpaceId::new(2)));
+        assert!(policy.resume_space(SpaceId::new(2)));
+        assert_eq!(policy.paused_spaces().count(), 0);
+    }
+
+    #[test]
+    fn display_name_pattern_overrides_default_disable() {
+        let mut policy = SpaceActivationPolicy::new();
+        policy.set_display_rules(DisplayActivationRules {
+            disable: vec!["Sidecar*".to_string()],
+            enable: vec!["display-b".to_string()],
+        });
+        let cfg = SpaceActivationConfig {
+            default_disable: false,
+            one_space: false,
+        };
+
+        let mut sidecar = input(1, Some(1), Some("display-a"));
+        sidecar.name = Some("Sidecar Display".to_string());
+        policy.on_spaces_updated(cfg, &[sidecar, input(2, Some(2), Some("display-b"))]);
+
+        let spaces = [Some(SpaceId::new(1)), Some(SpaceId::new(2))];
+        let displays = [Some("display-a".to_string()), Some("display-b".to_string())];
+        let active = policy.compute_active_spaces(cfg, &spaces, &displays);
+        assert_eq!(active, vec![None, Some(SpaceId::new(2))]);
+
+        // Toggling still works on a pattern-disabled display.
+        policy.toggle_space_activated(cfg, ToggleSpaceContext {
+            space: SpaceId::new(1),
+            display_uuid: Some("display-a".to_string()),
+        });
+        let active = policy.compute_active_spaces(cfg, &spaces, &displays);
+        assert_eq!(active, vec![Some(SpaceId::new(1)), Some(SpaceId::new(2))]);
+
+        let default_disable = SpaceActivationConfig { default_disable: true, ..cfg };
+        let active = policy.compute_active_spaces(default_disable, &spaces, &displays);
+        assert_eq!(active[1], Some(SpaceId::new(2)));
+    }
+
+    #[test]
+    fn activation_reason_reports_deciding_rule() {
+        let mut policy = SpaceActivationPolicy::new();
+        let cfg = SpaceActivationConfig {
+            default_disable: true,
+            one_space: false,
+        };
+        policy.on_spaces_updated(cfg, &[input(1, Some(1), Some("display-a"))]);
+
+        let reason = |policy: &SpaceActivationPolicy, cfg| {
+            policy.activation_reason(cfg, Some(SpaceId::new(1)), Some("display-a"))
+        };
+        assert_eq!(reason(&policy, cfg), ActivationReason::DefaultDisabled);
+
+        policy.toggle_space_activated(cfg, ToggleSpaceContext {
+            space: SpaceId::new(1),
+            display_uuid: Some("display-a".to_string()),
+        });
+        assert_eq!(reason(&policy, cfg), ActivationReason::SpaceEnabled);
+        assert_eq!(
+            policy.activation_reason(cfg, Some(SpaceId::new(2)), Some("display-a")),
+            ActivationReason::DisplayEnabled
+        );
+
+        let one_space = SpaceActivationConfig { one_space: true, ..cfg };
+        assert_eq!(
+            policy.activation_reason(one_space, Some(SpaceId::new(2)), Some("display-a")),
+            ActivationReason::OneSpace
+        );
+
+        policy.set_login_window_active(true);
+        assert_eq!(reason(&policy, cfg), ActivationReason::LoginWindow);
+        assert!(!ActivationReason::LoginWindow.is_enabled());
+
+        assert!(policy.engage_kill_switch());
+        assert!(!policy.engage_kill_switch());
+        policy.set_login_window_active(false);
+        assert_eq!(reason(&policy, cfg), ActivationReason::KillSwitch);
+        assert!(!ActivationReason::KillSwitch.is_enabled());
+    }
+
     #[test]
     fn starting_space_clears_when_missing() {
         let mut policy = SpaceActivationPolicy::new();
@@ -76,6 +76,11 @@ impl WindowTxStore {
     pub fn last_txid(&self, id: &WindowServerId) -> TransactionId {
         self.get(id).map(|record| record.txid).unwrap_or_default()
     }
+
+    /// Number of windows with a requested frame that hasn't been seen yet.
+    pub fn pending_count(&self) -> usize {
+        self.0.iter().filter(|entry| entry.target.is_some()).count()
+    }
 }
 
 #[cfg(test)]
@@ -128,4 +133,20 @@ mod tests {
         assert_eq!(record.txid, txid_2);
         assert_eq!(record.target, None);
     }
+
+    #[test]
+    fn pending_count_only_counts_outstanding_targets() {
+        let store = WindowTxStore::new();
+        let target = CGRect::new(CGPoint::new(1.0, 1.0), CGSize::new(2.0, 2.0));
+        for id in 1..=3 {
+            let wsid = WindowServerId::new(id);
+            let txid = store.next_txid(wsid);
+            store.insert(wsid, txid, target);
+        }
+        assert_eq!(store.pending_count(), 3);
+
+        store.clear_target(&WindowServerId::new(1));
+        store.remove(&WindowServerId::new(2));
+        assert_eq!(store.pending_count(), 1);
+    }
 }
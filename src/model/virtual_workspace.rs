@@ -161,10 +161,17 @@ pub struct VirtualWorkspaceManager {
     pub active_workspace_per_space:
         HashMap<SpaceId, (Option<VirtualWorkspaceId>, VirtualWorkspaceId)>,
     pub window_to_workspace: HashMap<(SpaceId, WindowId), VirtualWorkspaceId>,
+    /// Floating windows that follow the active workspace of the space they are
+    /// pinned to, rather than staying in the workspace they were assigned.
+    #[serde(default)]
+    pinned_windows: HashSet<(SpaceId, WindowId)>,
     #[serde(skip)]
     window_rule_floating: HashMap<(SpaceId, WindowId), bool>,
     #[serde(skip)]
     window_rule_scratchpad: HashMap<(SpaceId, WindowId), String>,
+    /// The `stack_with` group of the app rule each window last matched.
+    #[serde(skip)]
+    window_rule_stack: HashMap<WindowId, String>,
     #[serde(skip)]
     last_rule_decision: HashMap<(SpaceId, WindowId), bool>,
     floating_positions: HashMap<(SpaceId, VirtualWorkspaceId), FloatingWindowPositions>,
@@ -184,6 +191,8 @@ pub struct VirtualWorkspaceManager {
     #[serde(skip)]
     pub workspace_auto_back_and_forth: bool,
     #[serde(skip)]
+    pub grid_columns: Option<usize>,
+    #[serde(skip)]
     pub workspace_rules: Vec<crate::common::config::WorkspaceLayoutRule>,
     #[serde(skip)]
     pub default_layout_mode: LayoutMode,
@@ -222,8 +231,10 @@ impl VirtualWorkspaceManager {
             workspaces_by_space: HashMap::default(),
             active_workspace_per_space: HashMap::default(),
             window_to_workspace: HashMap::default(),
+            pinned_windows: HashSet::default(),
             window_rule_floating: HashMap::default(),
             window_rule_scratchpad: HashMap::default(),
+            window_rule_stack: HashMap::default(),
             last_rule_decision: HashMap::default(),
             floating_positions: HashMap::default(),
             workspace_counter: 1,
@@ -234,6 +245,7 @@ impl VirtualWorkspaceManager {
             default_workspace_names: config.workspace_names.clone(),
             default_workspace,
             workspace_auto_back_and_forth: config.workspace_auto_back_and_forth,
+            grid_columns: config.grid_columns,
             workspace_rules: config.workspace_rules.clone(),
             default_layout_mode: layout_settings.mode,
             layout_settings: layout_settings.clone(),
@@ -255,6 +267,7 @@ impl VirtualWorkspaceManager {
         self.default_workspace_count = config.default_workspace_count;
         self.default_workspace_names = config.workspace_names.clone();
         self.workspace_auto_back_and_forth = config.workspace_auto_back_and_forth;
+        self.grid_columns = config.grid_columns;
         self.rebuild_app_rule_regex_cache();
 
         let target_count = self.default_workspace_count.max(1).min(self.max_workspaces);
@@ -328,17 +341,47 @@ impl VirtualWorkspaceManager {
         }
     }
 
+    /// Workspace rules that target the workspace, last first, so that the last
+    /// matching rule wins like it does for app_rules.
+    fn matching_workspace_rules<'a>(
+        &'a self,
+        index: usize,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a crate::common::config::WorkspaceLayoutRule> + 'a {
+        self.workspace_rules.iter().rev().filter(move |rule| match &rule.workspace {
+            WorkspaceSelector::Index(idx) => *idx == index,
+            WorkspaceSelector::Name(n) => n == name,
+        })
+    }
+
     fn resolve_layout_mode_for_workspace(&self, index: usize, name: &str) -> LayoutMode {
-        // Check workspace_rules (last matching rule wins, like app_rules)
-        for rule in self.workspace_rules.iter().rev() {
-            match &rule.workspace {
-                WorkspaceSelector::Index(idx) if *idx == index => return rule.layout,
-                WorkspaceSelector::Name(n) if n == name => return rule.layout,
-                _ => continue,
-            }
-        }
-        // Fall back to global default
-        self.default_layout_mode
+        self.matching_workspace_rules(index, name)
+            .find_map(|rule| rule.layout)
+            .unwrap_or(self.default_layout_mode)
+    }
+
+    /// A setting from the last workspace rule that targets the active workspace on
+    /// `space` and sets it.
+    pub fn active_workspace_rule_setting<T>(
+        &self,
+        space: SpaceId,
+        setting: impl Fn(&crate::common::config::WorkspaceLayoutRule) -> Option<T>,
+    ) -> Option<T> {
+        self.workspace_rule_setting(space, self.active_workspace(space)?, setting)
+    }
+
+    /// A setting from the last workspace rule that targets `workspace_id` on
+    /// `space` and sets it.
+    pub fn workspace_rule_setting<T>(
+        &self,
+        space: SpaceId,
+        workspace_id: VirtualWorkspaceId,
+        setting: impl Fn(&crate::common::config::WorkspaceLayoutRule) -> Option<T>,
+    ) -> Option<T> {
+        let workspaces = self.workspaces_by_space.get(&space)?;
+        let index = workspaces.iter().position(|id| *id == workspace_id)?;
+        let name = &self.workspaces.get(workspace_id)?.name;
+        self.matching_workspace_rules(index, name).find_map(setting)
     }
 
     pub fn desired_layout_mode_for_workspace(&self, index: usize, name: &str) -> LayoutMode {
@@ -393,6 +436,12 @@ impl VirtualWorkspaceManager {
         }
         self.window_to_workspace = new_window_to_workspace;
 
+        self.pinned_windows = std::mem::take(&mut self.pinned_windows)
+            .into_iter()
+            .filter(|(space, _)| *space != new_space)
+            .map(|(space, wid)| (if space == old_space { new_space } else { space }, wid))
+            .collect();
+
         let mut new_window_rule_floating = HashMap::default();
         for ((space, wid), is_float) in std::mem::take(&mut self.window_rule_floating) {
             if space == new_space && old_space != new_space {
@@ -482,6 +531,11 @@ impl VirtualWorkspaceManager {
 
     pub fn workspace_auto_back_and_forth(&self) -> bool { self.workspace_auto_back_and_forth }
 
+    /// The `stack_with` group from the app rule the window matched, if any.
+    pub fn rule_stack_group(&self, window_id: WindowId) -> Option<&str> {
+        self.window_rule_stack.get(&window_id).map(String::as_str)
+    }
+
     pub fn set_active_workspace(
         &mut self,
         space: SpaceId,
@@ -494,6 +548,7 @@ impl VirtualWorkspaceManager {
                 && self.workspaces.get(workspace_id).map(|w| w.space) == Some(space)
             {
                 self.active_workspace_per_space.insert(space, (active, workspace_id));
+                self.carry_pinned_windows(space, workspace_id);
                 true
             } else {
                 error!(
@@ -507,6 +562,63 @@ impl VirtualWorkspaceManager {
         })
     }
 
+    /// Moves the windows pinned to `space` into `workspace_id`, along with
+    /// their floating positions.
+    fn carry_pinned_windows(&mut self, space: SpaceId, workspace_id: VirtualWorkspaceId) {
+        let pinned: Vec<WindowId> = self
+            .pinned_windows
+            .iter()
+            .filter(|(pinned_space, _)| *pinned_space == space)
+            .map(|(_, wid)| *wid)
+            .collect();
+        for wid in pinned {
+            let Some(from) = self.window_to_workspace.get(&(space, wid)).copied() else {
+                continue;
+            };
+            if from == workspace_id {
+                continue;
+            }
+            if let Some(workspace) = self.workspaces.get_mut(from) {
+                workspace.remove_window(wid);
+            }
+            if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
+                workspace.add_window(wid);
+            }
+            self.window_to_workspace.insert((space, wid), workspace_id);
+            let position = self
+                .floating_positions
+                .get_mut(&(space, from))
+                .and_then(|positions| positions.remove_position(wid));
+            if let Some(position) = position {
+                self.store_floating_position(space, workspace_id, wid, position);
+            }
+        }
+    }
+
+    /// Pins a window on `space` so that it moves along to whichever workspace
+    /// becomes active there. Returns false if the window is not on that space.
+    pub fn pin_window(&mut self, space: SpaceId, window_id: WindowId) -> bool {
+        if !self.window_to_workspace.contains_key(&(space, window_id)) {
+            return false;
+        }
+        self.pinned_windows.insert((space, window_id));
+        if let Some(active) = self.active_workspace(space) {
+            self.carry_pinned_windows(space, active);
+        }
+        true
+    }
+
+    /// Leaves the window in the workspace it is in now.
+    pub fn unpin_window(&mut self, window_id: WindowId) -> bool {
+        let before = self.pinned_windows.len();
+        self.pinned_windows.retain(|(_, wid)| *wid != window_id);
+        self.pinned_windows.len() != before
+    }
+
+    pub fn is_window_pinned(&self, window_id: WindowId) -> bool {
+        self.pinned_windows.iter().any(|(_, wid)| *wid == window_id)
+    }
+
     fn filtered_workspace_ids(
         &self,
         space: SpaceId,
@@ -621,6 +733,8 @@ impl VirtualWorkspaceManager {
 
             if let Some((existing_space, old_workspace_id)) = existing_mapping {
                 if existing_space != space {
+                    // Pinning is to a space; a window that leaves it stays put elsewhere.
+                    self.pinned_windows.remove(&(existing_space, window_id));
                     if let Some(old_workspace) = self.workspaces.get_mut(old_workspace_id) {
                         old_workspace.remove_window(window_id);
                     }
@@ -682,6 +796,7 @@ impl VirtualWorkspaceManager {
     }
 
     pub fn remove_window(&mut self, window_id: WindowId) {
+        self.window_rule_stack.remove(&window_id);
         let keys: Vec<(SpaceId, WindowId)> = self
             .window_to_workspace
             .keys()
@@ -693,6 +808,7 @@ impl VirtualWorkspaceManager {
                 if let Some(workspace) = self.workspaces.get_mut(workspace_id) {
                     workspace.remove_window(wid);
                 }
+                self.pinned_windows.remove(&(space, wid));
                 self.window_rule_floating.remove(&(space, wid));
                 self.last_rule_decision.remove(&(space, wid));
             }
@@ -700,6 +816,7 @@ impl VirtualWorkspaceManager {
     }
 
     pub fn remove_windows_for_app(&mut self, pid: pid_t) {
+        self.window_rule_stack.retain(|wid, _| wid.pid != pid);
         let windows_to_remove: Vec<_> = self
             .window_to_workspace
             .keys()
@@ -717,6 +834,7 @@ impl VirtualWorkspaceManager {
                 if let Some(workspace) = self.workspaces.get_mut(ws_id) {
                     workspace.remove_window(window_id);
                 }
+                self.pinned_windows.remove(&(space, window_id));
                 self.window_rule_floating.remove(&(space, window_id));
                 self.last_rule_decision.remove(&(space, window_id));
             }
@@ -1118,6 +1236,51 @@ impl VirtualWorkspaceManager {
         }
     }
 
+    /// Gives the workspaces of `space` the names a display has for them, by
+    /// position, skipping any that no longer have their default name.
+    pub fn apply_display_workspace_names(&mut self, space: SpaceId, names: &[String]) {
+        self.ensure_space_initialized(space);
+        let Some(ids) = self.workspaces_by_space.get(&space) else {
+            return;
+        };
+        for (idx, (&id, name)) in ids.iter().zip(names).enumerate() {
+            let default = self
+                .default_workspace_names
+                .get(idx)
+                .cloned()
+                .unwrap_or_else(|| format!("Workspace {}", idx + 1));
+            if let Some(workspace) = self.workspaces.get_mut(id)
+                && workspace.name == default
+            {
+                workspace.name = name.clone();
+            }
+        }
+    }
+
+    /// Moves a workspace to position `index` in its space's order, shifting the
+    /// ones in between. An index past the end moves it to the end. Returns
+    /// whether the order changed.
+    pub fn move_workspace(
+        &mut self,
+        space: SpaceId,
+        workspace_id: VirtualWorkspaceId,
+        index: usize,
+    ) -> bool {
+        let Some(ids) = self.workspaces_by_space.get_mut(&space) else {
+            return false;
+        };
+        let Some(current) = ids.iter().position(|&id| id == workspace_id) else {
+            return false;
+        };
+        let index = index.min(ids.len() - 1);
+        if current == index {
+            return false;
+        }
+        let id = ids.remove(current);
+        ids.insert(index, id);
+        true
+    }
+
     pub fn workspace_windows(
         &self,
         space: SpaceId,
@@ -1177,12 +1340,25 @@ impl VirtualWorkspaceManager {
 
         let existing_assignment = self.window_to_workspace.get(&(space, window_id)).copied();
 
+        match rule_match.as_ref().and_then(|rule| rule.stack_with.clone()) {
+            Some(group) => {
+                self.window_rule_stack.insert(window_id, group);
+            }
+            None => {
+                self.window_rule_stack.remove(&window_id);
+            }
+        }
+
         if let Some(rule) = rule_match {
             if !rule.manage {
                 self.window_rule_floating.remove(&(space, window_id));
                 return Ok(AppRuleResult::Unmanaged);
             }
 
+            // Windows handled through the window server alone can't be resized, and
+            // often not even moved, so they would never fill a tile.
+            let floating = rule.floating || rule.window_server_only;
+
             let target_workspace_id = if let Some(ref ws_sel) = rule.workspace {
                 let maybe_idx: Option<usize> = match ws_sel {
                     WorkspaceSelector::Index(i) => Some(*i),
@@ -1240,7 +1416,7 @@ impl VirtualWorkspaceManager {
             };
 
             if let Some(existing_ws) = existing_assignment {
-                if rule.floating {
+                if floating {
                     self.window_rule_floating.insert((space, window_id), true);
                 } else {
                     self.window_rule_floating.remove(&(space, window_id));
@@ -1260,14 +1436,14 @@ impl VirtualWorkspaceManager {
 
                 return Ok(AppRuleResult::Managed(AppRuleAssignment {
                     workspace_id: existing_ws,
-                    floating: rule.floating,
+                    floating,
                     scratchpad: scratchpad_name,
                     prev_rule_decision,
                 }));
             }
 
             if self.assign_window_to_workspace(space, window_id, target_workspace_id) {
-                if rule.floating {
+                if floating {
                     self.window_rule_floating.insert((space, window_id), true);
                 } else {
                     self.window_rule_floating.remove(&(space, window_id));
@@ -1292,7 +1468,7 @@ impl VirtualWorkspaceManager {
 
                 return Ok(AppRuleResult::Managed(AppRuleAssignment {
                     workspace_id: target_workspace_id,
-                    floating: rule.floating,
+                    floating,
                     scratchpad: scratchpad_name,
                     prev_rule_decision,
                 }));
@@ -1359,7 +1535,7 @@ impl VirtualWorkspaceManager {
         }
     }
 
-    fn find_matching_app_rule(
+    pub(crate) fn find_matching_app_rule(
         &self,
         app_bundle_id: Option<&str>,
         app_name: Option<&str>,
@@ -1367,6 +1543,36 @@ impl VirtualWorkspaceManager {
         ax_role: Option<&str>,
         ax_subrole: Option<&str>,
     ) -> Option<&AppWorkspaceRule> {
+        let matches =
+            self.scored_app_rules(app_bundle_id, app_name, window_title, ax_role, ax_subrole);
+        Self::most_specific_app_rule(&matches)
+    }
+
+    /// Every app rule that matches the window, with its position in the config,
+    /// in config order. [`Self::find_matching_app_rule`] picks one of them.
+    pub(crate) fn matching_app_rules(
+        &self,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> Vec<(usize, &AppWorkspaceRule)> {
+        self.scored_app_rules(app_bundle_id, app_name, window_title, ax_role, ax_subrole)
+            .into_iter()
+            .map(|(idx, rule, _)| (idx, rule))
+            .collect()
+    }
+
+    /// Matching rules as (index, rule, number of criteria set).
+    fn scored_app_rules(
+        &self,
+        app_bundle_id: Option<&str>,
+        app_name: Option<&str>,
+        window_title: Option<&str>,
+        ax_role: Option<&str>,
+        ax_subrole: Option<&str>,
+    ) -> Vec<(usize, &AppWorkspaceRule, usize)> {
         let mut matches: Vec<(usize, &AppWorkspaceRule, usize)> = Vec::new();
 
         for (idx, rule) in self.app_rules.iter().enumerate() {
@@ -1475,6 +1681,12 @@ impl VirtualWorkspaceManager {
             matches.push((idx, rule, score));
         }
 
+        matches
+    }
+
+    fn most_specific_app_rule<'a>(
+        matches: &[(usize, &'a AppWorkspaceRule, usize)],
+    ) -> Option<&'a AppWorkspaceRule> {
         if matches.is_empty() {
             return None;
         }
@@ -1484,7 +1696,7 @@ impl VirtualWorkspaceManager {
         }
 
         let mut groups: HashMap<&str, Vec<&(usize, &AppWorkspaceRule, usize)>> = HashMap::default();
-        for entry in &matches {
+        for entry in matches {
             if let Some(ref app_id) = entry.1.app_id {
                 if !app_id.is_empty() {
                     groups.entry(app_id.as_str()).or_default().push(entry);
@@ -1576,6 +1788,33 @@ impl FloatingWindowPositions {
     }
 }
 
+/// The workspace next to `index` in `direction` when `count` workspaces are laid
+/// out row by row, `columns` to a row.
+///
+/// There is no wrapping: moving off an edge, or into the unfilled end of the
+/// last row, gives `None`.
+pub fn grid_neighbor(
+    index: usize,
+    count: usize,
+    columns: usize,
+    direction: Direction,
+) -> Option<usize> {
+    if index >= count {
+        return None;
+    }
+    let columns = columns.clamp(1, count);
+    let column = index % columns;
+    let target = match direction {
+        Direction::Left if column > 0 => index - 1,
+        Direction::Left => return None,
+        Direction::Right if column + 1 < columns => index + 1,
+        Direction::Right => return None,
+        Direction::Up => index.checked_sub(columns)?,
+        Direction::Down => index + columns,
+    };
+    (target < count).then_some(target)
+}
+
 #[derive(Debug, Clone)]
 pub struct WorkspaceStats {
     pub total_workspaces: usize,
@@ -1590,6 +1829,24 @@ mod tests {
     use crate::actor::app::WindowId;
     use crate::sys::screen::SpaceId;
 
+    #[test]
+    fn grid_neighbor_stops_at_edges_and_missing_cells() {
+        use Direction::*;
+        // 0 1 2
+        // 3 4
+        assert_eq!(grid_neighbor(0, 5, 3, Right), Some(1));
+        assert_eq!(grid_neighbor(2, 5, 3, Right), None);
+        assert_eq!(grid_neighbor(3, 5, 3, Left), None);
+        assert_eq!(grid_neighbor(1, 5, 3, Down), Some(4));
+        assert_eq!(grid_neighbor(2, 5, 3, Down), None);
+        assert_eq!(grid_neighbor(4, 5, 3, Right), None);
+        assert_eq!(grid_neighbor(4, 5, 3, Up), Some(1));
+        assert_eq!(grid_neighbor(1, 5, 3, Up), None);
+        // Without a grid every workspace sits in one row.
+        assert_eq!(grid_neighbor(2, 5, 5, Right), Some(3));
+        assert_eq!(grid_neighbor(2, 5, 5, Down), None);
+    }
+
     fn expect_managed(result: Result<AppRuleResult, WorkspaceError>) -> AppRuleAssignment {
         match result {
             Ok(AppRuleResult::Managed(decision)) => decision,
@@ -1643,6 +1900,30 @@ mod tests {
         assert_eq!(workspace.name, "Test Workspace");
     }
 
+    #[test]
+    fn test_move_workspace() {
+        fn order(manager: &mut VirtualWorkspaceManager, space: SpaceId) -> Vec<VirtualWorkspaceId> {
+            manager.list_workspaces(space).into_iter().map(|(id, _)| id).collect()
+        }
+
+        let mut manager = VirtualWorkspaceManager::new();
+        let space = SpaceId::new(1);
+        let ids = order(&mut manager, space);
+        assert!(ids.len() >= 3);
+
+        assert!(manager.move_workspace(space, ids[0], 2));
+        assert_eq!(&order(&mut manager, space)[..3], &[ids[1], ids[2], ids[0]]);
+
+        assert!(manager.move_workspace(space, ids[0], 0));
+        assert_eq!(order(&mut manager, space), ids);
+
+        // Out of range indices clamp to the end; a no-op move reports no change.
+        assert!(manager.move_workspace(space, ids[1], usize::MAX));
+        assert_eq!(order(&mut manager, space).last(), Some(&ids[1]));
+        assert!(!manager.move_workspace(space, ids[1], usize::MAX));
+        assert!(!manager.move_workspace(SpaceId::new(2), ids[1], 0));
+    }
+
     #[test]
     fn test_window_assignment() {
         let mut manager = VirtualWorkspaceManager::new();
@@ -1716,6 +1997,41 @@ mod tests {
         assert!(is_window_visible(&manager, window2, space));
     }
 
+    #[test]
+    fn pinned_windows_follow_the_active_workspace_of_their_space() {
+        let mut manager = VirtualWorkspaceManager::new();
+        let space = SpaceId::new(1);
+        let other_space = SpaceId::new(2);
+        let ws1_id = manager.create_workspace(space, Some("WS1".to_string())).unwrap();
+        let ws2_id = manager.create_workspace(space, Some("WS2".to_string())).unwrap();
+        let other_ws1 = manager.create_workspace(other_space, None).unwrap();
+        let other_ws2 = manager.create_workspace(other_space, None).unwrap();
+        let pinned = WindowId::new(1, 1);
+        let other = WindowId::new(1, 2);
+        let frame = CGRect::new(CGPoint::new(10.0, 20.0), CGSize::new(300.0, 200.0));
+
+        manager.set_active_workspace(space, ws1_id);
+        manager.set_active_workspace(other_space, other_ws1);
+        manager.assign_window_to_workspace(space, pinned, ws1_id);
+        manager.assign_window_to_workspace(other_space, other, other_ws1);
+        manager.store_floating_position(space, ws1_id, pinned, frame);
+        assert!(manager.pin_window(space, pinned));
+        assert!(!manager.pin_window(space, other));
+
+        manager.set_active_workspace(other_space, other_ws2);
+        assert_eq!(manager.workspace_for_window(space, pinned), Some(ws1_id));
+
+        manager.set_active_workspace(space, ws2_id);
+        assert_eq!(manager.workspace_for_window(space, pinned), Some(ws2_id));
+        assert!(manager.is_window_in_active_workspace(space, pinned));
+        assert_eq!(manager.get_floating_position(space, ws2_id, pinned), Some(frame));
+        assert_eq!(manager.get_floating_position(space, ws1_id, pinned), None);
+
+        assert!(manager.unpin_window(pinned));
+        manager.set_active_workspace(space, ws1_id);
+        assert_eq!(manager.workspace_for_window(space, pinned), Some(ws2_id));
+    }
+
     #[test]
     fn default_workspace_setting_applied() {
         let mut settings = VirtualWorkspaceSettings::default();
@@ -1732,6 +2048,21 @@ mod tests {
         assert_eq!(manager.active_workspace(space), Some(expected_ws));
     }
 
+    #[test]
+    fn display_workspace_names_keep_renamed_workspaces() {
+        let mut manager = VirtualWorkspaceManager::new();
+        let space = SpaceId::new(1);
+        let first = manager.list_workspaces(space)[0].0;
+        manager.rename_workspace(space, first, "Mine".to_string());
+
+        let names = ["Left 1".to_string(), "Left 2".to_string()];
+        manager.apply_display_workspace_names(space, &names);
+
+        let workspaces = manager.list_workspaces(space);
+        assert_eq!(workspaces[0].1, "Mine");
+        assert_eq!(workspaces[1].1, "Left 2");
+    }
+
     #[test]
     fn test_workspace_navigation() {
         let mut manager = VirtualWorkspaceManager::new();
@@ -1776,6 +2107,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Match by app_name -> workspace 1
             AppWorkspaceRule {
@@ -1789,6 +2125,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Title substring -> workspace 0
             AppWorkspaceRule {
@@ -1802,6 +2143,11 @@ mod tests {
                 title_substring: Some("Preferences".into()),
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Title regex -> workspace 2
             AppWorkspaceRule {
@@ -1815,6 +2161,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // AX role + subrole floating
             AppWorkspaceRule {
@@ -1828,6 +2179,11 @@ mod tests {
                 title_substring: None,
                 ax_role: Some("AXWindow".into()),
                 ax_subrole: Some("AXDialog".into()),
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Workspace by name
             AppWorkspaceRule {
@@ -1841,6 +2197,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Specificity tie breaking generic vs substring (generic workspace 0, specific workspace 2)
             AppWorkspaceRule {
@@ -1854,6 +2215,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             AppWorkspaceRule {
                 app_id: Some("com.example.tie".into()),
@@ -1866,6 +2232,11 @@ mod tests {
                 title_substring: Some("Editor".into()),
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Reapplication: Bitwarden title becomes floating
             AppWorkspaceRule {
@@ -1879,6 +2250,11 @@ mod tests {
                 title_substring: Some("Bitwarden".into()),
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             AppWorkspaceRule {
                 app_id: Some("app.zen-browser.zen".into()),
@@ -1891,6 +2267,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             // Workspace override when specific rule matches different workspace + floating
             AppWorkspaceRule {
@@ -1904,6 +2285,11 @@ mod tests {
                 title_substring: None,
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
             AppWorkspaceRule {
                 app_id: Some("app.zen-browser.zen".into()),
@@ -1916,6 +2302,11 @@ mod tests {
                 title_substring: Some("bitwarden".into()),
                 ax_role: None,
                 ax_subrole: None,
+                shadow: None,
+                corner_radius: None,
+                new_window_focus: None,
+                stack_with: None,
+                window_server_only: false,
             },
         ];
 
@@ -2109,4 +2500,104 @@ mod tests {
         );
         assert!(bw2_updated_assignment.floating);
     }
+
+    #[test]
+    fn matching_app_rules_lists_every_match_in_config_order() {
+        let rule = |app_id: &str, title_substring: Option<&str>| AppWorkspaceRule {
+            app_id: Some(app_id.into()),
+            workspace: None,
+            floating: title_substring.is_some(),
+            scratchpad: crate::common::config::ScratchpadConfig::Boolean(false),
+            manage: true,
+            app_name: None,
+            title_regex: None,
+            title_substring: title_substring.map(Into::into),
+            ax_role: None,
+            ax_subrole: None,
+            shadow: None,
+            corner_radius: None,
+            new_window_focus: None,
+            stack_with: None,
+            window_server_only: false,
+        };
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings.app_rules = vec![
+            rule("com.example.editor", None),
+            rule("com.example.other", None),
+            rule("com.example.editor", Some("Settings")),
+        ];
+        let manager =
+            VirtualWorkspaceManager::new_with_config(&settings, &LayoutSettings::default());
+
+        let matches = manager.matching_app_rules(
+            Some("com.example.editor"),
+            None,
+            Some("Editor Settings"),
+            None,
+            None,
+        );
+        let indices: Vec<usize> = matches.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(indices, vec![0, 2]);
+
+        let applied = manager
+            .find_matching_app_rule(
+                Some("com.example.editor"),
+                None,
+                Some("Editor Settings"),
+                None,
+                None,
+            )
+            .unwrap();
+        assert!(std::ptr::eq(applied, matches[1].1));
+    }
+
+    #[test]
+    fn window_server_only_rules_float_their_windows() {
+        let mut settings = VirtualWorkspaceSettings::default();
+        settings.app_rules = vec![AppWorkspaceRule {
+            app_id: Some("com.example.legacy".into()),
+            workspace: None,
+            floating: false,
+            scratchpad: crate::common::config::ScratchpadConfig::Boolean(false),
+            manage: true,
+            app_name: None,
+            title_regex: None,
+            title_substring: None,
+            ax_role: None,
+            ax_subrole: None,
+            shadow: None,
+            corner_radius: None,
+            new_window_focus: None,
+            stack_with: None,
+            window_server_only: true,
+        }];
+        let mut manager =
+            VirtualWorkspaceManager::new_with_config(&settings, &LayoutSettings::default());
+
+        let space = SpaceId::new(1);
+        let legacy = WindowId::new(10, 1);
+        let other = WindowId::new(11, 1);
+        let assignment = assign(
+            &mut manager,
+            legacy,
+            space,
+            Some("com.example.legacy"),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(assignment.floating);
+        let assignment = assign(
+            &mut manager,
+            other,
+            space,
+            Some("com.example.other"),
+            None,
+            None,
+            None,
+            None,
+        );
+        assert!(!assignment.floating);
+    }
 }
@@ -0,0 +1,408 @@
+//! Stable window identities that survive rift restarts and app relaunches.
+//!
+//! A [`WindowId`] only lives as long as the process that owns the window, and a
+//! window server id only as long as the window. Features that remember something
+//! about a window across restarts key it by a `rift_id` instead. When a window
+//! appears it is matched against the identities seen before, in order of how much
+//! the evidence can be trusted:
+//!
+//! 1. a window server id the identity has had, which holds while the app keeps
+//!    running and only rift restarted;
+//! 2. the window's `AXIdentifier`, which some apps set and keep across launches;
+//! 3. the window's title.
+//!
+//! Matches never cross bundle ids, and an identity claimed by a live window is
+//! not handed out to a second one. A window that matches nothing gets a new id.
+//!
+//! Identities also carry what rift remembers about the window, such as where the
+//! user last put it while it floated.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use objc2_core_foundation::CGRect;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+
+use crate::actor::app::WindowId;
+use crate::common::collections::HashMap;
+use crate::sys::geometry::CGRectDef;
+
+/// How many window server ids an identity keeps. Windows that are closed and
+/// reopened within one app session get a new id each time.
+const SERVER_ID_HISTORY: usize = 8;
+/// Identities beyond this many are dropped, least recently seen first.
+const MAX_IDENTITIES: usize = 1024;
+/// How long changes wait before being written out, so that an app opening or
+/// closing a burst of windows costs one write.
+const FLUSH_DELAY: Duration = Duration::from_secs(2);
+
+/// What is known about a window when it appears.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowFingerprint<'a> {
+    pub bundle_id: &'a str,
+    pub ax_identifier: Option<&'a str>,
+    pub title: &'a str,
+    pub server_id: Option<u32>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WindowIdentity {
+    pub rift_id: u64,
+    pub bundle_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ax_identifier: Option<String>,
+    pub title: String,
+    /// Window server ids this window has had, oldest first.
+    #[serde(default)]
+    pub server_ids: Vec<u32>,
+    /// Store clock value of the last time a window claimed this identity.
+    #[serde(default)]
+    last_seen: u64,
+    /// The frame the user last gave the window while it floated, keyed by the
+    /// display configuration it was on.
+    #[serde_as(as = "HashMap<_, CGRectDef>")]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    float_frames: HashMap<String, CGRect>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WindowIdentityStore {
+    identities: Vec<WindowIdentity>,
+    next_id: u64,
+    clock: u64,
+    #[serde(skip)]
+    live: HashMap<WindowId, u64>,
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// When remembered state first changed without being written out since.
+    #[serde(skip)]
+    dirty_since: Option<Instant>,
+}
+
+impl WindowIdentityStore {
+    /// Loads identities from `path`, starting empty if the file is missing or unreadable.
+    pub fn load(path: PathBuf) -> Self {
+        let mut store = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str::<Self>(&contents).unwrap_or_else(|e| {
+                tracing::warn!("Failed to parse window identities at {}: {e}", path.display());
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        };
+        store.path = Some(path);
+        store
+    }
+
+    fn save(&mut self) {
+        self.dirty_since = None;
+        let Some(path) = self.path.as_deref() else {
+            return;
+        };
+        if let Err(e) = Self::write(path, self) {
+            tracing::warn!("Failed to save window identities to {}: {e}", path.display());
+        }
+    }
+
+    fn write(path: &Path, store: &Self) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string(store)?)?;
+        Ok(())
+    }
+
+    /// The `rift_id` of a live window, if it has been assigned one.
+    pub fn rift_id(&self, window_id: WindowId) -> Option<u64> { self.live.get(&window_id).copied() }
+
+    pub fn identity(&self, rift_id: u64) -> Option<&WindowIdentity> {
+        self.identities.iter().find(|identity| identity.rift_id == rift_id)
+    }
+
+    /// Gives `window_id` the identity `fingerprint` matches, or a new one, and
+    /// returns its `rift_id`. Calling this again for the same window keeps its id.
+    pub fn assign(&mut self, window_id: WindowId, fingerprint: WindowFingerprint) -> u64 {
+        if let Some(rift_id) = self.rift_id(window_id) {
+            return rift_id;
+        }
+        self.clock += 1;
+        let index = match self.find_match(&fingerprint) {
+            Some(index) => index,
+            None => {
+                self.next_id += 1;
+                self.identities.push(WindowIdentity {
+                    rift_id: self.next_id,
+                    bundle_id: fingerprint.bundle_id.to_owned(),
+                    ax_identifier: None,
+                    title: String::new(),
+                    server_ids: Vec::new(),
+                    last_seen: 0,
+                    float_frames: HashMap::default(),
+                });
+                self.identities.len() - 1
+            }
+        };
+
+        let identity = &mut self.identities[index];
+        if let Some(ax_identifier) = fingerprint.ax_identifier {
+            identity.ax_identifier = Some(ax_identifier.to_owned());
+        }
+        if !fingerprint.title.is_empty() {
+            identity.title = fingerprint.title.to_owned();
+        }
+        if let Some(server_id) = fingerprint.server_id
+            && identity.server_ids.last() != Some(&server_id)
+        {
+            identity.server_ids.retain(|&id| id != server_id);
+            identity.server_ids.push(server_id);
+            if identity.server_ids.len() > SERVER_ID_HISTORY {
+                identity.server_ids.remove(0);
+            }
+        }
+        identity.last_seen = self.clock;
+        let rift_id = identity.rift_id;
+        self.live.insert(window_id, rift_id);
+
+        self.prune();
+        self.mark_dirty();
+        rift_id
+    }
+
+    /// Keeps the stored title current so the next launch can match on it.
+    pub fn update_title(&mut self, window_id: WindowId, title: &str) {
+        let Some(rift_id) = self.rift_id(window_id) else {
+            return;
+        };
+        if let Some(identity) = self.identities.iter_mut().find(|i| i.rift_id == rift_id)
+            && !title.is_empty()
+        {
+            identity.title = title.to_owned();
+            self.mark_dirty();
+        }
+    }
+
+    /// The frame the user last gave the window while it floated on `displays`.
+    pub fn float_frame(&self, window_id: WindowId, displays: &str) -> Option<CGRect> {
+        let rift_id = self.rift_id(window_id)?;
+        self.identity(rift_id)?.float_frames.get(displays).copied()
+    }
+
+    /// Remembers where the user put a floating window.
+    pub fn remember_float_frame(&mut self, window_id: WindowId, displays: &str, frame: CGRect) {
+        let Some(rift_id) = self.rift_id(window_id) else {
+            return;
+        };
+        if let Some(identity) = self.identities.iter_mut().find(|i| i.rift_id == rift_id) {
+            identity.float_frames.insert(displays.to_owned(), frame);
+            self.mark_dirty();
+        }
+    }
+
+    /// Frees the identity of a window that closed so a later window can claim it.
+    pub fn release(&mut self, window_id: WindowId) {
+        if self.live.remove(&window_id).is_some() {
+            self.mark_dirty();
+        }
+    }
+
+    /// Changes are written out by [`Self::flush`], at most [`FLUSH_DELAY`] after
+    /// the first one, rather than as they happen.
+    fn mark_dirty(&mut self) { self.dirty_since.get_or_insert_with(Instant::now); }
+
+    /// How long until unsaved changes are due to be written out, if there are any.
+    pub fn flush_delay(&self, now: Instant) -> Option<Duration> {
+        let since = self.dirty_since?;
+        Some((since + FLUSH_DELAY).saturating_duration_since(now))
+    }
+
+    /// Writes out unsaved changes now.
+    pub fn flush(&mut self) {
+        if self.dirty_since.is_some() {
+            self.save();
+        }
+    }
+
+    fn find_match(&self, fingerprint: &WindowFingerprint) -> Option<usize> {
+        let claimed: Vec<u64> = self.live.values().copied().collect();
+        let candidates = || {
+            self.identities.iter().enumerate().filter(|(_, identity)| {
+                identity.bundle_id == fingerprint.bundle_id && !claimed.contains(&identity.rift_id)
+            })
+        };
+        // Prefer the most recently seen identity when several share a title.
+        let best = |matches: &dyn Fn(&WindowIdentity) -> bool| {
+            candidates()
+                .filter(|(_, identity)| matches(identity))
+                .max_by_key(|(_, identity)| identity.last_seen)
+                .map(|(index, _)| index)
+        };
+
+        if let Some(server_id) = fingerprint.server_id
+            && let Some(index) = best(&|identity| identity.server_ids.contains(&server_id))
+        {
+            return Some(index);
+        }
+        if let Some(ax_identifier) = fingerprint.ax_identifier
+            && let Some(index) =
+                best(&|identity| identity.ax_identifier.as_deref() == Some(ax_identifier))
+        {
+            return Some(index);
+        }
+        if fingerprint.title.is_empty() {
+            return None;
+        }
+        best(&|identity| identity.title == fingerprint.title)
+    }
+
+    fn prune(&mut self) {
+        if self.identities.len() <= MAX_IDENTITIES {
+            return;
+        }
+        let claimed: Vec<u64> = self.live.values().copied().collect();
+        let mut unclaimed: Vec<u64> = self
+            .identities
+            .iter()
+            .filter(|identity| !claimed.contains(&identity.rift_id))
+            .map(|identity| identity.last_seen)
+            .collect();
+        unclaimed.sort_unstable();
+        let excess = self.identities.len() - MAX_IDENTITIES;
+        let Some(&cutoff) = unclaimed.get(excess.saturating_sub(1)) else {
+            return;
+        };
+        self.identities
+            .retain(|identity| identity.last_seen > cutoff || claimed.contains(&identity.rift_id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use objc2_core_foundation::{CGPoint, CGSize};
+
+    use super::*;
+
+    fn fingerprint<'a>(title: &'a str, server_id: Option<u32>) -> WindowFingerprint<'a> {
+        WindowFingerprint {
+            bundle_id: "com.example.editor",
+            ax_identifier: None,
+            title,
+            server_id,
+        }
+    }
+
+    #[test]
+    fn a_restart_keeps_ids_through_the_window_server_id() {
+        let mut store = WindowIdentityStore::default();
+        let first = store.assign(WindowId::new(10, 1), fingerprint("notes.txt", Some(100)));
+        let second = store.assign(WindowId::new(10, 2), fingerprint("notes.txt", Some(101)));
+        assert_ne!(first, second);
+
+        // Rift restarts: the app and its windows are still there, titles changed.
+        store.live.clear();
+        assert_eq!(
+            store.assign(WindowId::new(10, 1), fingerprint("a", Some(101))),
+            second
+        );
+        assert_eq!(
+            store.assign(WindowId::new(10, 2), fingerprint("b", Some(100))),
+            first
+        );
+    }
+
+    #[test]
+    fn an_app_relaunch_matches_on_identifier_then_title() {
+        let mut store = WindowIdentityStore::default();
+        let main = store.assign(WindowId::new(10, 1), WindowFingerprint {
+            ax_identifier: Some("main"),
+            ..fingerprint("Inbox", Some(100))
+        });
+        let settings = store.assign(WindowId::new(10, 2), fingerprint("Settings", Some(101)));
+        store.release(WindowId::new(10, 1));
+        store.release(WindowId::new(10, 2));
+
+        let relaunched_main = store.assign(WindowId::new(20, 1), WindowFingerprint {
+            ax_identifier: Some("main"),
+            ..fingerprint("Drafts", Some(300))
+        });
+        assert_eq!(relaunched_main, main);
+        assert_eq!(
+            store.assign(WindowId::new(20, 2), fingerprint("Settings", None)),
+            settings
+        );
+        let unknown = store.assign(WindowId::new(20, 3), fingerprint("Settings", None));
+        assert!(unknown != main && unknown != settings);
+    }
+
+    #[test]
+    fn identities_do_not_cross_bundle_ids() {
+        let mut store = WindowIdentityStore::default();
+        let editor = store.assign(WindowId::new(10, 1), fingerprint("Untitled", Some(100)));
+        store.release(WindowId::new(10, 1));
+        let other = store.assign(WindowId::new(11, 1), WindowFingerprint {
+            bundle_id: "com.example.other",
+            ..fingerprint("Untitled", Some(100))
+        });
+        assert_ne!(editor, other);
+    }
+
+    #[test]
+    fn float_frames_are_kept_per_display_configuration() {
+        let frame = CGRect::new(CGPoint::new(10.0, 20.0), CGSize::new(300.0, 200.0));
+        let mut store = WindowIdentityStore::default();
+        store.assign(WindowId::new(10, 1), fingerprint("Calculator", Some(100)));
+        store.remember_float_frame(WindowId::new(10, 1), "laptop", frame);
+        store.release(WindowId::new(10, 1));
+
+        store.assign(WindowId::new(20, 1), fingerprint("Calculator", Some(200)));
+        assert_eq!(store.float_frame(WindowId::new(20, 1), "laptop"), Some(frame));
+        assert_eq!(store.float_frame(WindowId::new(20, 1), "laptop,monitor"), None);
+    }
+
+    #[test]
+    fn changes_are_written_out_once_the_flush_delay_runs_out() {
+        let path = std::env::temp_dir().join(format!(
+            "rift-window-identities-flush-{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let mut store = WindowIdentityStore::load(path.clone());
+        assert_eq!(store.flush_delay(Instant::now()), None);
+
+        store.assign(WindowId::new(10, 1), fingerprint("notes.txt", Some(100)));
+        let delay = store.flush_delay(Instant::now()).unwrap();
+        assert!(delay <= FLUSH_DELAY);
+        store.assign(WindowId::new(10, 2), fingerprint("todo.txt", Some(101)));
+        store.release(WindowId::new(10, 1));
+        // Later changes do not push the write back.
+        assert!(store.flush_delay(Instant::now()).unwrap() <= delay);
+        assert_eq!(
+            store.flush_delay(Instant::now() + FLUSH_DELAY),
+            Some(Duration::ZERO)
+        );
+        assert!(!path.exists());
+
+        store.flush();
+        assert!(path.exists());
+        assert_eq!(store.flush_delay(Instant::now()), None);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn round_trips_through_disk() {
+        let path = std::env::temp_dir()
+            .join(format!("rift-window-identities-{}.json", std::process::id()));
+        let mut store = WindowIdentityStore::load(path.clone());
+        let id = store.assign(WindowId::new(10, 1), fingerprint("notes.txt", Some(100)));
+        store.release(WindowId::new(10, 1));
+        store.flush();
+
+        let mut reloaded = WindowIdentityStore::load(path.clone());
+        assert_eq!(reloaded.identity(id), store.identity(id));
+        assert_eq!(
+            reloaded.assign(WindowId::new(30, 1), fingerprint("notes.txt", None)),
+            id
+        );
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -4,6 +4,7 @@ use objc2_core_graphics::CGError;
 
 pub mod accessibility;
 pub mod app;
+pub mod appearance;
 pub mod axuielement;
 pub mod carbon;
 
@@ -15,11 +16,14 @@ pub mod enhanced_ui;
 pub mod event;
 pub mod event_tap;
 pub mod executor;
+pub mod focus_mode;
 pub mod geometry;
 pub mod haptics;
 pub mod hotkey;
+pub mod idle;
 pub mod mach;
 pub mod observer;
+pub mod other_wm;
 pub mod power;
 pub mod process;
 pub mod run_loop;
@@ -29,6 +33,7 @@ pub mod skylight;
 pub mod timer;
 pub mod window_notify;
 pub mod window_server;
+pub mod window_style;
 
 #[inline(always)]
 pub fn cg_ok(err: CGError) -> Result<(), CGError> {
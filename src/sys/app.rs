@@ -16,6 +16,7 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 
+use super::enhanced_ui::AnimationQuirk;
 use super::geometry::CGRectDef;
 use super::window_server::{WindowServerId, WindowServerInfo};
 use crate::sys::axuielement::{
@@ -353,6 +354,23 @@ pub fn running_apps(bundle: Option<String>) -> impl Iterator<Item = (pid_t, AppI
         })
 }
 
+/// Bundle id of the agent that draws Notification Center banners and alerts.
+pub const NOTIFICATION_CENTER_BUNDLE_ID: &str = "com.apple.notificationcenterui";
+
+/// Whether `pid` is the Notification Center agent. Its banners sit above every
+/// app and it becomes frontmost while one is clicked or hovered.
+pub fn is_notification_center(pid: pid_t) -> bool {
+    NSRunningApplication::with_process_id(pid)
+        .and_then(|app| app.bundle_id())
+        .is_some_and(|id| id.to_string() == NOTIFICATION_CENTER_BUNDLE_ID)
+}
+
+/// The app whose menus are in the menu bar. This is normally the frontmost app,
+/// but an app can activate without taking over the menu bar.
+pub fn menu_bar_owner() -> Option<pid_t> {
+    NSWorkspace::sharedWorkspace().menuBarOwningApplication().map(|app| app.pid())
+}
+
 pub trait NSRunningApplicationExt {
     fn with_process_id(pid: pid_t) -> Option<Retained<Self>>;
     fn pid(&self) -> pid_t;
@@ -376,6 +394,9 @@ impl NSRunningApplicationExt for NSRunningApplication {
 pub struct AppInfo {
     pub bundle_id: Option<String>,
     pub localized_name: Option<String>,
+    /// Set by the app thread once it has looked at the app.
+    #[serde(default)]
+    pub animation_quirk: Option<AnimationQuirk>,
 }
 
 impl From<&NSRunningApplication> for AppInfo {
@@ -383,6 +404,7 @@ impl From<&NSRunningApplication> for AppInfo {
         AppInfo {
             bundle_id: app.bundle_id().as_deref().map(ToString::to_string),
             localized_name: app.localized_name().as_deref().map(ToString::to_string),
+            animation_quirk: None,
         }
     }
 }
@@ -408,6 +430,10 @@ pub struct WindowInfo {
     pub path: Option<PathBuf>,
     pub ax_role: Option<String>,
     pub ax_subrole: Option<String>,
+    /// `AXIdentifier`, which apps that restore their windows tend to keep stable
+    /// across launches.
+    #[serde(default)]
+    pub ax_identifier: Option<String>,
 }
 
 impl WindowInfo {
@@ -429,6 +455,11 @@ impl WindowInfo {
             .or_else(|| WindowServerId::try_from(element).ok());
         let is_minimized = element.minimized().unwrap_or_default();
         let is_resizable = element.can_resize().unwrap_or(true);
+        let ax_identifier = if is_standard {
+            element.identifier().ok().filter(|identifier| !identifier.is_empty())
+        } else {
+            None
+        };
 
         let (bundle_id, path) = if !is_standard {
             (None, None)
@@ -457,6 +488,7 @@ impl WindowInfo {
             path,
             ax_role,
             ax_subrole,
+            ax_identifier,
         };
 
         Ok((info, server_info))
@@ -0,0 +1,24 @@
+//! Reads whether the system is in Dark or Light mode.
+
+use objc2_app_kit::{NSAppearanceNameAqua, NSAppearanceNameDarkAqua, NSApplication};
+use objc2_foundation::{MainThreadMarker, NSArray};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Appearance {
+    Light,
+    #[default]
+    Dark,
+}
+
+/// The appearance AppKit resolves for our app, which follows the system setting.
+/// It only updates once the run loop has handled the appearance change, so read
+/// it a moment after `AppleInterfaceThemeChangedNotification` rather than inside it.
+pub fn current_appearance(mtm: MainThreadMarker) -> Appearance {
+    let appearance = NSApplication::sharedApplication(mtm).effectiveAppearance();
+    let (aqua, dark_aqua) = unsafe { (NSAppearanceNameAqua, NSAppearanceNameDarkAqua) };
+    let candidates = NSArray::from_slice(&[aqua, dark_aqua]);
+    match appearance.bestMatchFromAppearancesWithNames(&candidates) {
+        Some(name) if &*name == dark_aqua => Appearance::Dark,
+        _ => Appearance::Light,
+    }
+}
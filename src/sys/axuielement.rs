@@ -214,6 +214,12 @@ impl AXUIElement {
         Ok(string.to_string())
     }
 
+    pub fn identifier(&self) -> Result<String> {
+        let value = self.copy_required_attribute("AXIdentifier")?;
+        let string = self.downcast::<CFString>(value)?;
+        Ok(string.to_string())
+    }
+
     pub fn frontmost(&self) -> Result<bool> { self.bool_attribute("AXFrontmost") }
 
     pub fn main_window(&self) -> Result<AXUIElement> {
@@ -1,9 +1,41 @@
+use serde::{Deserialize, Serialize};
+
 use crate::sys::axuielement::AXUIElement;
 
 const K_AX_ENHANCED_USER_INTERFACE: &str = "AXEnhancedUserInterface";
+const K_AX_MANUAL_ACCESSIBILITY: &str = "AXManualAccessibility";
+
+/// Why an app's windows are moved without animation: apps in these modes
+/// animate or defer accessibility moves themselves and end up fighting ours.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationQuirk {
+    /// `AXEnhancedUserInterface` was on when rift first saw the app.
+    EnhancedUserInterface,
+    /// The app answers to `AXManualAccessibility`, as Electron apps do.
+    ManualAccessibility,
+}
+
+/// Checks an app for the accessibility modes that make animated moves jank.
+/// Call before rift has changed either attribute itself.
+pub fn animation_quirk(app: &AXUIElement) -> Option<AnimationQuirk> {
+    if app.bool_attribute(K_AX_ENHANCED_USER_INTERFACE).unwrap_or(false) {
+        Some(AnimationQuirk::EnhancedUserInterface)
+    } else if app.bool_attribute(K_AX_MANUAL_ACCESSIBILITY).is_ok() {
+        Some(AnimationQuirk::ManualAccessibility)
+    } else {
+        None
+    }
+}
 
+/// Runs `f` with enhanced user interface off, turning it back on afterwards
+/// only if it was on before.
 pub fn with_enhanced_ui_disabled<F, R>(element: &AXUIElement, f: F) -> R
 where F: FnOnce() -> R {
+    let was_enabled = element.bool_attribute(K_AX_ENHANCED_USER_INTERFACE).unwrap_or(false);
+    if !was_enabled {
+        return f();
+    }
     let _ = element.set_bool_attribute(K_AX_ENHANCED_USER_INTERFACE, false);
     let result = f();
     let _ = element.set_bool_attribute(K_AX_ENHANCED_USER_INTERFACE, true);
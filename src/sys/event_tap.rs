@@ -67,6 +67,15 @@ unsafe fn trampoline_drop(ptr: *mut c_void) {
     }
 }
 
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
+/// Whether some process has secure event input on. While it is, macOS keeps
+/// keystrokes from every event tap, ours included.
+pub fn secure_input_enabled() -> bool { unsafe { IsSecureEventInputEnabled() != 0 } }
+
 pub struct EventTap {
     port: CFRetained<CFMachPort>,
     source: CFRetained<CFRunLoopSource>,
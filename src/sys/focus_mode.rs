@@ -0,0 +1,111 @@
+//! Reads the active macOS Focus (Do Not Disturb) mode.
+//!
+//! There is no public API for this, so we read the same JSON stores the
+//! Control Center uses under `~/Library/DoNotDisturb/DB`. Reading them requires
+//! Full Disk Access on recent macOS versions; without it we simply report no
+//! active Focus.
+
+use std::path::PathBuf;
+
+use serde_json::Value;
+use tracing::trace;
+
+const ASSERTIONS_FILE: &str = "Assertions.json";
+const MODE_CONFIGURATIONS_FILE: &str = "ModeConfigurations.json";
+
+fn db_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join("Library").join("DoNotDisturb").join("DB"))
+}
+
+fn read_json(name: &str) -> Option<Value> {
+    let path = db_dir()?.join(name);
+    let buf = std::fs::read_to_string(&path)
+        .inspect_err(|err| trace!(?path, %err, "Unable to read focus database"))
+        .ok()?;
+    serde_json::from_str(&buf).ok()
+}
+
+/// Returns the display name of the active Focus mode, if any.
+pub fn current_focus_mode() -> Option<String> {
+    let assertions = read_json(ASSERTIONS_FILE)?;
+    let identifier = active_mode_identifier(&assertions)?;
+    let name = read_json(MODE_CONFIGURATIONS_FILE)
+        .and_then(|configs| mode_name(&configs, &identifier))
+        .unwrap_or_else(|| fallback_mode_name(&identifier));
+    Some(name)
+}
+
+fn active_mode_identifier(assertions: &Value) -> Option<String> {
+    assertions
+        .get("data")?
+        .as_array()?
+        .iter()
+        .filter_map(|entry| entry.get("storeAssertionRecords")?.as_array())
+        .flatten()
+        .find_map(|record| {
+            record
+                .get("assertionDetails")?
+                .get("assertionDetailsModeIdentifier")?
+                .as_str()
+                .map(str::to_owned)
+        })
+}
+
+fn mode_name(configs: &Value, identifier: &str) -> Option<String> {
+    configs.get("data")?.as_array()?.iter().find_map(|entry| {
+        entry
+            .get("modeConfigurations")?
+            .get(identifier)?
+            .get("mode")?
+            .get("name")?
+            .as_str()
+            .map(str::to_owned)
+    })
+}
+
+/// `com.apple.donotdisturb.mode.default` -> "Do Not Disturb", otherwise the
+/// last identifier component, e.g. `com.apple.focus.work` -> "work".
+fn fallback_mode_name(identifier: &str) -> String {
+    if identifier == "com.apple.donotdisturb.mode.default" {
+        return "Do Not Disturb".to_string();
+    }
+    identifier.rsplit('.').next().unwrap_or(identifier).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn resolves_active_mode_name() {
+        let assertions = json!({
+            "data": [{
+                "storeAssertionRecords": [{
+                    "assertionDetails": {
+                        "assertionDetailsModeIdentifier": "com.apple.focus.work"
+                    }
+                }]
+            }]
+        });
+        let configs = json!({
+            "data": [{
+                "modeConfigurations": {
+                    "com.apple.focus.work": { "mode": { "name": "Work" } }
+                }
+            }]
+        });
+
+        let identifier = active_mode_identifier(&assertions).unwrap();
+        assert_eq!(identifier, "com.apple.focus.work");
+        assert_eq!(mode_name(&configs, &identifier).as_deref(), Some("Work"));
+        assert_eq!(fallback_mode_name(&identifier), "work");
+    }
+
+    #[test]
+    fn no_assertions_means_no_focus() {
+        assert!(active_mode_identifier(&json!({ "data": [{}] })).is_none());
+        assert!(active_mode_identifier(&json!({})).is_none());
+    }
+}
@@ -76,6 +76,7 @@ impl SameAs for ic::CGSize {}
 
 pub trait CGRectExt {
     fn intersection(&self, other: &Self) -> Self;
+    fn union(&self, other: &Self) -> Self;
     fn contains(&self, point: ic::CGPoint) -> bool;
     fn contains_rect(&self, other: Self) -> bool;
     fn area(&self) -> f64;
@@ -93,6 +94,17 @@ impl CGRectExt for ic::CGRect {
         }
     }
 
+    fn union(&self, other: &Self) -> Self {
+        let min_x = f64::min(self.min().x, other.min().x);
+        let max_x = f64::max(self.max().x, other.max().x);
+        let min_y = f64::min(self.min().y, other.min().y);
+        let max_y = f64::max(self.max().y, other.max().y);
+        ic::CGRect {
+            origin: ic::CGPoint::new(min_x, min_y),
+            size: ic::CGSize::new(max_x - min_x, max_y - min_y),
+        }
+    }
+
     fn contains(&self, point: ic::CGPoint) -> bool {
         (self.min().x..=self.max().x).contains(&point.x)
             && (self.min().y..=self.max().y).contains(&point.y)
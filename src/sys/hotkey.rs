@@ -8,6 +8,7 @@ use std::sync::LazyLock;
 use anyhow::anyhow;
 use objc2_core_foundation::CFData;
 use objc2_core_graphics::{CGEvent, CGEventField, CGEventFlags};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -39,6 +40,18 @@ impl Modifiers {
 
     pub fn remove(&mut self, other: Modifiers) { self.0 &= !other.0; }
 
+    /// Widens each left- or right-only modifier to cover both sides, matching
+    /// what [`modifiers_from_flags`] reports.
+    pub fn to_generic(&self) -> Modifiers {
+        let mut generic = Modifiers::empty();
+        for m in MOD_FAMILIES {
+            if self.intersects(m.generic) {
+                generic.insert(m.generic);
+            }
+        }
+        generic
+    }
+
     pub fn has_generic_modifiers(&self) -> bool {
         MOD_FAMILIES.iter().any(|m| self.contains(m.generic))
     }
@@ -857,6 +870,7 @@ type CFStringRef = *const c_void;
 #[link(name = "Carbon", kind = "framework")]
 unsafe extern "C" {
     fn TISCopyCurrentASCIICapableKeyboardLayoutInputSource() -> *mut c_void;
+    fn TISCopyCurrentKeyboardLayoutInputSource() -> *mut c_void;
     fn TISGetInputSourceProperty(keyboard: *const c_void, property: CFStringRef) -> *mut CFData;
     fn UCKeyTranslate(
         keyLayoutPtr: *const u8,
@@ -885,27 +899,24 @@ const VIRTUAL_KEYCODE_NUMS: &[u16] = &[
     0x5B, 0x5C,
 ];
 
+/// The character each key types without modifiers on `keyboard`, an input source
+/// that this function releases.
 #[cfg(target_os = "macos")]
-fn generate_virtual_keymap() -> StdHashMap<String, KeyCode> {
-    let mut keymap = StdHashMap::new();
-
-    let keyboard = unsafe { TISCopyCurrentASCIICapableKeyboardLayoutInputSource() };
+fn layout_characters(keyboard: *mut c_void) -> Vec<(KeyCode, String)> {
+    let mut characters = Vec::new();
     if keyboard.is_null() {
-        tracing::warn!("Could not get ASCII-capable keyboard layout input source");
-        return keymap;
+        tracing::warn!("Could not get keyboard layout input source");
+        return characters;
     }
 
     let layout_data = NonNull::new(unsafe {
         TISGetInputSourceProperty(keyboard, kTISPropertyUnicodeKeyLayoutData)
     });
 
-    unsafe {
-        super::skylight::CFRelease(keyboard.cast());
-    }
-
     let Some(layout_data) = layout_data else {
         tracing::warn!("Could not get keyboard layout data");
-        return keymap;
+        unsafe { super::skylight::CFRelease(keyboard.cast()) };
+        return characters;
     };
 
     let layout_ptr = unsafe { CFData::byte_ptr(layout_data.as_ref()) };
@@ -948,21 +959,87 @@ fn generate_virtual_keymap() -> StdHashMap<String, KeyCode> {
 
             let s = String::from_utf16_lossy(&chars[..len]).to_lowercase();
 
-            keymap.entry(s).or_insert(key_code_enum);
+            characters.push((key_code_enum, s));
         }
     }
 
+    unsafe {
+        super::skylight::CFRelease(keyboard.cast());
+    }
+
+    characters
+}
+
+#[cfg(target_os = "macos")]
+fn generate_virtual_keymap() -> StdHashMap<String, KeyCode> {
+    let mut keymap = StdHashMap::new();
+    let keyboard = unsafe { TISCopyCurrentASCIICapableKeyboardLayoutInputSource() };
+    for (key_code, s) in layout_characters(keyboard) {
+        keymap.entry(s).or_insert(key_code);
+    }
     keymap
 }
 
+#[cfg(target_os = "macos")]
+fn generate_character_keys() -> StdHashMap<KeyCode, KeyCode> {
+    let current: StdHashMap<KeyCode, String> =
+        layout_characters(unsafe { TISCopyCurrentKeyboardLayoutInputSource() })
+            .into_iter()
+            .collect();
+    let ascii: StdHashMap<KeyCode, String> =
+        layout_characters(unsafe { TISCopyCurrentASCIICapableKeyboardLayoutInputSource() })
+            .into_iter()
+            .collect();
+    character_keys(&current, &ascii)
+}
+
+/// Maps each physical key to the key at the US ANSI position of the character it
+/// types on the `current` layout. Layouts without Latin letters, like Russian,
+/// fall back to the `ascii` layout, the way macOS menu shortcuts do. Keys that
+/// already match are left out.
+#[cfg(target_os = "macos")]
+fn character_keys(
+    current: &StdHashMap<KeyCode, String>,
+    ascii: &StdHashMap<KeyCode, String>,
+) -> StdHashMap<KeyCode, KeyCode> {
+    let mut keys = StdHashMap::new();
+    for &vk in VIRTUAL_KEYCODE_NUMS {
+        let Some(physical) = cg_keycode_to_keycode(vk) else {
+            continue;
+        };
+        let typed = current
+            .get(&physical)
+            .and_then(|s| fallback_keycode_from_char(s))
+            .or_else(|| ascii.get(&physical).and_then(|s| fallback_keycode_from_char(s)));
+        if let Some(typed) = typed
+            && typed != physical
+        {
+            keys.insert(physical, typed);
+        }
+    }
+    keys
+}
+
+static CHARACTER_KEYS: LazyLock<RwLock<StdHashMap<KeyCode, KeyCode>>> =
+    LazyLock::new(|| RwLock::new(generate_character_keys()));
+
+/// Rebuilds the table behind [`character_key_code`] after the input source changes.
+pub fn refresh_character_keys() { *CHARACTER_KEYS.write() = generate_character_keys(); }
+
+/// The key a binding has to name to match a press of `physical` when hotkeys
+/// follow the characters of the active input source rather than key positions.
+pub fn character_key_code(physical: KeyCode) -> KeyCode {
+    CHARACTER_KEYS.read().get(&physical).copied().unwrap_or(physical)
+}
+
 pub static VIRTUAL_KEYMAP: LazyLock<StdHashMap<String, KeyCode>> =
     LazyLock::new(generate_virtual_keymap);
 
+/// Key for a one-character hotkey token. Hotkeys name physical keys, so the US
+/// ANSI position of the character wins; the ASCII-capable layout only covers
+/// characters that are not on a US keyboard.
 pub fn keycode_from_char(ch: &str) -> Option<KeyCode> {
-    VIRTUAL_KEYMAP
-        .get(&ch.to_lowercase())
-        .copied()
-        .or_else(|| fallback_keycode_from_char(ch))
+    fallback_keycode_from_char(ch).or_else(|| VIRTUAL_KEYMAP.get(&ch.to_lowercase()).copied())
 }
 
 fn fallback_keycode_from_char(ch: &str) -> Option<KeyCode> {
@@ -1011,6 +1088,17 @@ fn fallback_keycode_from_char(ch: &str) -> Option<KeyCode> {
         '7' => Digit7,
         '8' => Digit8,
         '9' => Digit9,
+        '-' => Minus,
+        '=' => Equal,
+        ',' => Comma,
+        '.' => Period,
+        '/' => Slash,
+        ';' => Semicolon,
+        '\'' => Quote,
+        '`' => Backquote,
+        '\\' => Backslash,
+        '[' => BracketLeft,
+        ']' => BracketRight,
         _ => return None,
     };
     Some(code)
@@ -1044,6 +1132,59 @@ mod tests {
         assert_eq!(fallback_keycode_from_char("Z"), Some(KeyCode::KeyZ));
     }
 
+    #[test]
+    fn test_single_characters_name_us_key_positions() {
+        assert_eq!(keycode_from_char("h"), Some(KeyCode::KeyH));
+        assert_eq!(keycode_from_char(","), Some(KeyCode::Comma));
+        assert_eq!(keycode_from_char("["), Some(KeyCode::BracketLeft));
+    }
+
+    #[test]
+    fn test_character_keys_follow_the_active_layout() {
+        let layout = |keys: &[(KeyCode, &str)]| -> StdHashMap<KeyCode, String> {
+            keys.iter().map(|&(key, s)| (key, s.to_string())).collect()
+        };
+        let us = layout(&[
+            (KeyCode::KeyA, "a"),
+            (KeyCode::KeyQ, "q"),
+            (KeyCode::KeyH, "h"),
+            (KeyCode::Semicolon, ";"),
+        ]);
+
+        // AZERTY swaps A and Q and types "m" where US has ";".
+        let azerty = layout(&[
+            (KeyCode::KeyA, "q"),
+            (KeyCode::KeyQ, "a"),
+            (KeyCode::KeyH, "h"),
+            (KeyCode::Semicolon, "m"),
+        ]);
+        let keys = character_keys(&azerty, &us);
+        assert_eq!(keys.get(&KeyCode::KeyQ), Some(&KeyCode::KeyA));
+        assert_eq!(keys.get(&KeyCode::KeyA), Some(&KeyCode::KeyQ));
+        assert_eq!(keys.get(&KeyCode::Semicolon), Some(&KeyCode::KeyM));
+        assert_eq!(keys.get(&KeyCode::KeyH), None);
+        assert_eq!(keys.len(), 3);
+
+        // Cyrillic characters name no key, so the ASCII layout decides instead.
+        let russian = layout(&[
+            (KeyCode::KeyA, "ф"),
+            (KeyCode::KeyQ, "й"),
+            (KeyCode::KeyH, "р"),
+            (KeyCode::Semicolon, "ж"),
+        ]);
+        assert!(character_keys(&russian, &us).is_empty());
+        assert_eq!(
+            character_keys(&russian, &azerty).get(&KeyCode::KeyQ),
+            Some(&KeyCode::KeyA)
+        );
+    }
+
+    #[test]
+    fn test_character_key_code_keeps_keys_that_type_nothing() {
+        assert_eq!(character_key_code(KeyCode::ArrowLeft), KeyCode::ArrowLeft);
+        assert_eq!(character_key_code(KeyCode::F5), KeyCode::F5);
+    }
+
     #[test]
     fn test_from_str_uses_virtual_keymap() {
         let result = KeyCode::from_str("h");
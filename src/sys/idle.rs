@@ -0,0 +1,78 @@
+//! Tracks how long the user has gone without touching any input device.
+
+unsafe extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+}
+
+/// `kCGEventSourceStateHIDSystemState`: input as it arrives from the hardware,
+/// so events posted by other apps do not count as activity.
+const HID_SYSTEM_STATE: i32 = 1;
+/// `kCGAnyInputEventType`.
+const ANY_INPUT_EVENT: u32 = !0;
+
+/// Seconds since the last keyboard, mouse or trackpad event.
+pub fn seconds_since_last_input() -> f64 {
+    unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT) }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdleTransition {
+    /// The threshold at this index was crossed.
+    Idle(usize),
+    /// Input arrived after the threshold at this index had been crossed.
+    Resumed(usize),
+}
+
+/// Turns polled idle times into crossings of a list of thresholds.
+///
+/// Each threshold goes idle once per idle period and resumes once input
+/// comes back, latest threshold first.
+#[derive(Debug, Default)]
+pub struct IdleWatcher {
+    last_idle_secs: f64,
+    /// Indices of the thresholds crossed in this idle period, in crossing order.
+    crossed: Vec<usize>,
+}
+
+impl IdleWatcher {
+    pub fn observe(&mut self, idle_secs: f64, thresholds: &[u64]) -> Vec<IdleTransition> {
+        let mut transitions = Vec::new();
+        // The idle time only goes down when there was input since the last poll.
+        if idle_secs < self.last_idle_secs {
+            transitions.extend(self.crossed.drain(..).rev().map(IdleTransition::Resumed));
+        }
+        self.last_idle_secs = idle_secs;
+
+        for (index, &after_secs) in thresholds.iter().enumerate() {
+            if idle_secs >= after_secs as f64 && !self.crossed.contains(&index) {
+                self.crossed.push(index);
+                transitions.push(IdleTransition::Idle(index));
+            }
+        }
+        transitions
+    }
+
+    pub fn is_idle(&self) -> bool { !self.crossed.is_empty() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IdleTransition::{Idle, Resumed};
+    use super::*;
+
+    #[test]
+    fn thresholds_fire_once_per_idle_period_and_resume_in_reverse() {
+        let mut watcher = IdleWatcher::default();
+        let thresholds = [600, 60];
+        assert_eq!(watcher.observe(30.0, &thresholds), []);
+        assert_eq!(watcher.observe(65.0, &thresholds), [Idle(1)]);
+        assert_eq!(watcher.observe(70.0, &thresholds), []);
+        assert_eq!(watcher.observe(601.0, &thresholds), [Idle(0)]);
+        assert!(watcher.is_idle());
+
+        assert_eq!(watcher.observe(2.0, &thresholds), [Resumed(0), Resumed(1)]);
+        assert!(!watcher.is_idle());
+        assert_eq!(watcher.observe(7.0, &thresholds), []);
+        assert_eq!(watcher.observe(61.0, &thresholds), [Idle(1)]);
+    }
+}
@@ -0,0 +1,120 @@
+//! Finds other window managers that would fight rift over window positions.
+//!
+//! The app-based ones are found by bundle identifier. yabai runs as a plain
+//! launchd agent with no bundle, so it is looked up by process name instead.
+
+use std::process::Command;
+use std::time::Duration;
+
+use objc2_app_kit::NSRunningApplication;
+use objc2_foundation::NSString;
+use tracing::{info, warn};
+
+use crate::common::config::OtherWmPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtherWm {
+    Yabai,
+    Amethyst,
+    Rectangle,
+}
+
+impl OtherWm {
+    pub fn name(self) -> &'static str {
+        match self {
+            OtherWm::Yabai => "yabai",
+            OtherWm::Amethyst => "Amethyst",
+            OtherWm::Rectangle => "Rectangle",
+        }
+    }
+
+    fn bundle_id(self) -> Option<&'static str> {
+        match self {
+            OtherWm::Yabai => None,
+            OtherWm::Amethyst => Some("com.amethyst.Amethyst"),
+            OtherWm::Rectangle => Some("com.knollsoft.Rectangle"),
+        }
+    }
+
+    fn is_running(self) -> bool {
+        match self.bundle_id() {
+            Some(bundle_id) => {
+                let bundle_id = NSString::from_str(bundle_id);
+                NSRunningApplication::runningApplicationsWithBundleIdentifier(&bundle_id).count()
+                    > 0
+            }
+            None => Command::new("/usr/bin/pgrep")
+                .args(["-x", self.name()])
+                .output()
+                .is_ok_and(|output| output.status.success()),
+        }
+    }
+}
+
+/// The window managers from [`OtherWm`] that are running right now.
+pub fn running() -> Vec<OtherWm> {
+    [OtherWm::Yabai, OtherWm::Amethyst, OtherWm::Rectangle]
+        .into_iter()
+        .filter(|wm| wm.is_running())
+        .collect()
+}
+
+/// Applies `policy` to the window managers `running` reports at startup,
+/// returning once rift may go ahead. `Wait` polls every `poll_interval` until
+/// none are left.
+pub fn hand_over(
+    policy: OtherWmPolicy,
+    poll_interval: Duration,
+    mut running: impl FnMut() -> Vec<OtherWm>,
+) {
+    if policy == OtherWmPolicy::Ignore {
+        return;
+    }
+    let others = running();
+    if others.is_empty() {
+        return;
+    }
+    let names: Vec<&str> = others.iter().map(|wm| wm.name()).collect();
+    if policy == OtherWmPolicy::Warn {
+        warn!(
+            "{} is also running and will fight rift over window positions; quit it, or set \
+             settings.other_window_manager = \"wait\" to take over once it exits",
+            names.join(", ")
+        );
+        return;
+    }
+    info!("Waiting for {} to quit before starting", names.join(", "));
+    while !running().is_empty() {
+        std::thread::sleep(poll_interval);
+    }
+    info!("Other window managers have quit; starting");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs [`hand_over`] against a yabai that quits after `quits_after`
+    /// checks, returning how many checks were made.
+    fn checks_made(policy: OtherWmPolicy, quits_after: usize) -> usize {
+        let mut checks = 0;
+        hand_over(policy, Duration::ZERO, || {
+            checks += 1;
+            if checks <= quits_after {
+                vec![OtherWm::Yabai]
+            } else {
+                Vec::new()
+            }
+        });
+        checks
+    }
+
+    #[test]
+    fn each_policy_checks_for_other_window_managers_as_often_as_it_needs() {
+        assert_eq!(checks_made(OtherWmPolicy::Ignore, 3), 0);
+        assert_eq!(checks_made(OtherWmPolicy::Warn, 3), 1);
+        // Waiting keeps checking until yabai is gone, and not past that.
+        assert_eq!(checks_made(OtherWmPolicy::Wait, 3), 4);
+        assert_eq!(checks_made(OtherWmPolicy::Wait, 0), 1);
+    }
+}
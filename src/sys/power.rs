@@ -1,8 +1,16 @@
+use std::ffi::c_void;
+use std::ptr::NonNull;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+use objc2_core_foundation::{
+    CFRetained, CFRunLoop, CFRunLoopMode, CFRunLoopSource, CFString, CFType, kCFRunLoopCommonModes,
+};
 use objc2_foundation::NSProcessInfo;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::sys::skylight::CFRelease;
 
 static LOW_POWER_MODE: Lazy<Arc<AtomicBool>> = Lazy::new(|| Arc::new(AtomicBool::new(false)));
 
@@ -17,3 +25,71 @@ pub fn init_power_state() {
     let initial_state = process_info.isLowPowerModeEnabled();
     LOW_POWER_MODE.store(initial_state, Ordering::Relaxed);
 }
+
+/// Where the Mac is drawing power from. Macs without a battery are always on AC.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    #[default]
+    Ac,
+    /// The internal battery or a UPS.
+    Battery,
+}
+
+impl PowerSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PowerSource::Ac => "ac",
+            PowerSource::Battery => "battery",
+        }
+    }
+}
+
+unsafe extern "C" {
+    fn IOPSCopyPowerSourcesInfo() -> *mut CFType;
+    fn IOPSGetProvidingPowerSourceType(snapshot: *mut CFType) -> *const CFString;
+    fn IOPSNotificationCreateRunLoopSource(
+        callback: unsafe extern "C-unwind" fn(*mut c_void),
+        context: *mut c_void,
+    ) -> *mut CFRunLoopSource;
+}
+
+pub fn current_power_source() -> PowerSource {
+    unsafe {
+        let snapshot = IOPSCopyPowerSourcesInfo();
+        if snapshot.is_null() {
+            return PowerSource::Ac;
+        }
+        // Follows the get rule, so the string belongs to the snapshot.
+        let kind = IOPSGetProvidingPowerSourceType(snapshot);
+        let source = match kind.as_ref().map(|kind| kind.to_string()).as_deref() {
+            Some("Battery Power" | "UPS Power") => PowerSource::Battery,
+            _ => PowerSource::Ac,
+        };
+        CFRelease(snapshot);
+        source
+    }
+}
+
+/// Calls `handler` on the current thread's run loop whenever the power sources
+/// change. That includes battery level updates, so the source passed in is
+/// often the same as last time. The registration is never removed.
+pub fn watch_power_source<F: Fn(PowerSource) + 'static>(handler: F) {
+    unsafe extern "C-unwind" fn callback<F: Fn(PowerSource)>(context: *mut c_void) {
+        // SAFETY: `context` is the leaked box created below.
+        let handler = unsafe { &*(context as *const F) };
+        handler(current_power_source());
+    }
+
+    let context = Box::into_raw(Box::new(handler));
+    let source = unsafe { IOPSNotificationCreateRunLoopSource(callback::<F>, context.cast()) };
+    let Some(source) = NonNull::new(source) else {
+        drop(unsafe { Box::from_raw(context) });
+        return;
+    };
+    let source = unsafe { CFRetained::from_raw(source) };
+    if let Some(run_loop) = CFRunLoop::current() {
+        let mode: &CFRunLoopMode = unsafe { kCFRunLoopCommonModes.expect("kCFRunLoopCommonModes") };
+        run_loop.add_source(Some(&source), Some(mode));
+    }
+}
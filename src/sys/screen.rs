@@ -564,14 +564,17 @@ impl NSScreenExt for NSScreen {
 }
 
 pub fn get_active_space_number() -> Option<SpaceId> {
-    current_space_for_display_uuid(
-        &unsafe {
-            CFRetained::<CFString>::from_raw(NonNull::new(SLSCopyActiveMenuBarDisplayIdentifier(
-                SLSMainConnectionID(),
-            ))?)
-        }
-        .to_string(),
-    )
+    current_space_for_display_uuid(&active_menu_bar_display_uuid()?)
+}
+
+/// UUID of the display whose menu bar is the active one.
+pub fn active_menu_bar_display_uuid() -> Option<String> {
+    let uuid = unsafe {
+        CFRetained::<CFString>::from_raw(NonNull::new(SLSCopyActiveMenuBarDisplayIdentifier(
+            SLSMainConnectionID(),
+        ))?)
+    };
+    Some(uuid.to_string())
 }
 
 pub fn current_space_for_display_uuid(display_uuid: &str) -> Option<SpaceId> {
@@ -12,30 +12,40 @@ use nix::unistd::getuid;
 
 const LAUNCHCTL_PATH: &str = "/bin/launchctl";
 const RIFT_PLIST: &str = "git.acsandmann.rift";
+/// What the service runs with when neither the caller nor the environment has a PATH.
+const DEFAULT_PATH: &str = "/opt/homebrew/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin";
 
 #[derive(Subcommand)]
 pub enum ServiceCommands {
     /// Install the per-user launchd service
-    Install,
+    Install {
+        /// PATH the service runs with, instead of the current shell's.
+        #[arg(long, value_name = "PATH")]
+        user_path: Option<String>,
+    },
     /// Uninstall the per-user launchd service
     Uninstall,
     /// Start (or bootstrap) the service
-    Start,
+    Start {
+        /// PATH the service runs with, instead of the one it was installed with.
+        #[arg(long, value_name = "PATH")]
+        user_path: Option<String>,
+    },
     /// Stop (or bootout/kill) the service
     Stop,
     /// Restart the service (kickstart -k)
     Restart,
 }
 
-pub fn handle_service_command(cmd: &ServiceCommands) -> Result<&'static str, String> {
-    match cmd {
-        ServiceCommands::Install => service_install()
+pub fn handle_service_command(cmd: &ServiceCommands) -> Result<String, String> {
+    let message = match cmd {
+        ServiceCommands::Install { user_path } => service_install(user_path.as_deref())
             .map(|_| "Service installed.")
             .map_err(|e| format!("Failed to install service: {}", e)),
         ServiceCommands::Uninstall => service_uninstall()
             .map(|_| "Service uninstalled.")
             .map_err(|e| format!("Failed to uninstall service: {}", e)),
-        ServiceCommands::Start => service_start()
+        ServiceCommands::Start { user_path } => service_start(user_path.as_deref())
             .map(|_| "Service started.")
             .map_err(|e| format!("Failed to start service: {}", e)),
         ServiceCommands::Stop => service_stop()
@@ -44,14 +54,55 @@ pub fn handle_service_command(cmd: &ServiceCommands) -> Result<&'static str, Str
         ServiceCommands::Restart => service_restart()
             .map(|_| "Service restarted.")
             .map_err(|e| format!("Failed to restart service: {}", e)),
+    }?;
+    let mut message = message.to_string();
+    if !matches!(cmd, ServiceCommands::Uninstall | ServiceCommands::Stop) {
+        for other in other_rift_agents() {
+            message.push_str(&format!(
+                "\nWarning: '{}' also starts rift at login; remove it to avoid running two.",
+                other.display()
+            ));
+        }
     }
+    Ok(message)
 }
 
-fn plist_path() -> io::Result<PathBuf> {
-    let home = env::var_os("HOME")
+fn home_dir() -> io::Result<PathBuf> {
+    env::var_os("HOME")
         .map(PathBuf::from)
-        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "HOME not set"))?;
-    Ok(home.join("Library").join("LaunchAgents").join(format!("{RIFT_PLIST}.plist")))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "HOME not set"))
+}
+
+fn launch_agents_dir() -> io::Result<PathBuf> {
+    Ok(home_dir()?.join("Library").join("LaunchAgents"))
+}
+
+fn plist_path() -> io::Result<PathBuf> {
+    Ok(launch_agents_dir()?.join(format!("{RIFT_PLIST}.plist")))
+}
+
+fn log_dir() -> io::Result<PathBuf> { Ok(home_dir()?.join("Library").join("Logs").join("rift")) }
+
+/// Hand-written LaunchAgents, or ones left from an older label, that start rift
+/// next to this service.
+fn other_rift_agents() -> Vec<PathBuf> {
+    let (Ok(dir), Ok(own)) = (launch_agents_dir(), plist_path()) else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| *path != own && path.extension().is_some_and(|ext| ext == "plist"))
+        .filter(|path| fs::read_to_string(path).is_ok_and(|plist| starts_rift(&plist)))
+        .collect();
+    found.sort();
+    found
+}
+
+fn starts_rift(plist: &str) -> bool {
+    plist.contains("/rift</string>") || plist.contains("<string>rift</string>")
 }
 
 fn find_rift_executable_in_path(path_env: &std::ffi::OsStr) -> io::Result<Option<PathBuf>> {
@@ -103,21 +154,47 @@ fn find_rift_executable() -> io::Result<PathBuf> {
     ))
 }
 
-fn plist_contents() -> io::Result<String> {
-    let user =
-        env::var("USER").map_err(|_| io::Error::new(io::ErrorKind::Other, "env USER not set"))?;
-    let path_env =
-        env::var("PATH").map_err(|_| io::Error::new(io::ErrorKind::Other, "env PATH not set"))?;
+/// The `PATH` the service runs with: `user_path` if given, else the one the
+/// installed plist already has so an earlier override sticks, else the current
+/// one, else [`DEFAULT_PATH`].
+fn service_path_env(user_path: Option<&str>, installed: Option<&str>) -> String {
+    if let Some(path) = user_path {
+        return path.to_string();
+    }
+    if let Some(path) = installed.and_then(installed_path_env) {
+        return path;
+    }
+    env::var("PATH").unwrap_or_else(|_| DEFAULT_PATH.to_string())
+}
+
+fn installed_path_env(plist: &str) -> Option<String> {
+    let (_, rest) = plist.split_once("<key>PATH</key>")?;
+    let (_, rest) = rest.split_once("<string>")?;
+    let (path, _) = rest.split_once("</string>")?;
+    Some(path.replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&"))
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
 
-    let agent_exe = find_rift_executable()?;
+fn plist_contents(path_env: &str) -> io::Result<String> {
+    let agent_exe = match find_rift_executable_in_path(path_env.as_ref())? {
+        Some(exe) => exe,
+        None => find_rift_executable()?,
+    };
     let exe_str = agent_exe
         .to_str()
         .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "non-UTF8 executable path"))?;
+    let log_dir = log_dir()?;
+    let log_dir = log_dir
+        .to_str()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "non-UTF8 home directory"))?;
 
     let plist = format!(
-        r#"<?xml version=\"1.0\" encoding=\"UTF-8\"?>
-<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">
-<plist version=\"1.0\">
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
 <dict>
     <key>Label</key>
     <string>{name}</string>
@@ -141,10 +218,12 @@ fn plist_contents() -> io::Result<String> {
         <key>Crashed</key>
         <true/>
     </dict>
+    <key>ThrottleInterval</key>
+    <integer>10</integer>
     <key>StandardOutPath</key>
-    <string>/tmp/rift_{user}.out.log</string>
+    <string>{log_dir}/rift.out.log</string>
     <key>StandardErrorPath</key>
-    <string>/tmp/rift_{user}.err.log</string>
+    <string>{log_dir}/rift.err.log</string>
     <key>ProcessType</key>
     <string>Interactive</string>
     <key>LimitLoadToSessionType</key>
@@ -155,9 +234,9 @@ fn plist_contents() -> io::Result<String> {
 </plist>
 "#,
         name = RIFT_PLIST,
-        exe = exe_str,
-        path_env = path_env,
-        user = user
+        exe = xml_escape(exe_str),
+        path_env = xml_escape(path_env),
+        log_dir = xml_escape(log_dir),
     );
 
     Ok(plist)
@@ -218,29 +297,58 @@ fn service_is_running() -> io::Result<bool> {
     }
 }
 
-pub fn service_install_internal(plist_path: &Path) -> io::Result<()> {
-    let plist = plist_contents()?;
+/// The pid of the running service, if launchd has one.
+fn service_pid() -> Option<u32> {
+    let service_target = format!("gui/{}/{}", getuid(), RIFT_PLIST);
+    let output = Command::new(LAUNCHCTL_PATH).args(["print", &service_target]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("pid = ")?.parse().ok())
+}
+
+/// Rift processes that are not the service, e.g. ones started from a terminal.
+/// Starting the service next to them would leave two window managers fighting.
+fn duplicate_rift_pids() -> Vec<u32> {
+    let Ok(output) = Command::new("/usr/bin/pgrep").args(["-x", "rift"]).output() else {
+        return Vec::new();
+    };
+    let service = service_pid();
+    let own = std::process::id();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().parse().ok())
+        .filter(|&pid| pid != own && Some(pid) != service)
+        .collect()
+}
+
+pub fn service_install_internal(plist_path: &Path, user_path: Option<&str>) -> io::Result<()> {
+    let path_env = service_path_env(user_path, None);
+    let plist = plist_contents(&path_env)?;
+    fs::create_dir_all(log_dir()?)?;
     write_file_atomic(plist_path, &plist)?;
     Ok(())
 }
 
-fn ensure_plist_up_to_date(plist_path: &Path) -> io::Result<bool> {
-    let desired = plist_contents()?;
-    match fs::read_to_string(plist_path) {
-        Ok(existing) if existing == desired => Ok(false),
-        Ok(_) => {
-            write_file_atomic(plist_path, &desired)?;
-            Ok(true)
-        }
-        Err(err) if err.kind() == io::ErrorKind::NotFound => {
-            write_file_atomic(plist_path, &desired)?;
-            Ok(true)
-        }
-        Err(err) => Err(err),
+fn ensure_plist_up_to_date(plist_path: &Path, user_path: Option<&str>) -> io::Result<bool> {
+    let existing = match fs::read_to_string(plist_path) {
+        Ok(existing) => Some(existing),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err),
+    };
+    let path_env = service_path_env(user_path, existing.as_deref());
+    let desired = plist_contents(&path_env)?;
+    if existing.as_deref() == Some(desired.as_str()) {
+        return Ok(false);
     }
+    fs::create_dir_all(log_dir()?)?;
+    write_file_atomic(plist_path, &desired)?;
+    Ok(true)
 }
 
-pub fn service_install() -> io::Result<()> {
+pub fn service_install(user_path: Option<&str>) -> io::Result<()> {
     let plist_path = plist_path()?;
     if plist_path.is_file() {
         return Err(io::Error::new(
@@ -248,7 +356,7 @@ pub fn service_install() -> io::Result<()> {
             format!("service file '{}' is already installed", plist_path.display()),
         ));
     }
-    service_install_internal(&plist_path)
+    service_install_internal(&plist_path, user_path)
 }
 
 pub fn service_uninstall() -> io::Result<()> {
@@ -269,10 +377,28 @@ pub fn service_uninstall() -> io::Result<()> {
     Ok(())
 }
 
-pub fn service_start() -> io::Result<()> {
+/// Refuses to (re)start the service while [`duplicate_rift_pids`] finds any.
+fn ensure_no_duplicate_rift() -> io::Result<()> {
+    let duplicates = duplicate_rift_pids();
+    if duplicates.is_empty() {
+        return Ok(());
+    }
+    let pids: Vec<_> = duplicates.iter().map(u32::to_string).collect();
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!(
+            "rift is already running outside the service (pid {}); quit it first",
+            pids.join(", ")
+        ),
+    ))
+}
+
+pub fn service_start(user_path: Option<&str>) -> io::Result<()> {
+    ensure_no_duplicate_rift()?;
+
     let plist_path = plist_path()?;
     if !plist_path.is_file() {
-        service_install_internal(&plist_path).map_err(|e| {
+        service_install_internal(&plist_path, user_path).map_err(|e| {
             io::Error::new(
                 e.kind(),
                 format!(
@@ -288,11 +414,7 @@ pub fn service_start() -> io::Result<()> {
     let service_target = format!("gui/{}/{}", uid, RIFT_PLIST);
     let domain_target = format!("gui/{}", uid);
 
-    let plist_changed = if env::var_os("USER").is_some() && env::var_os("PATH").is_some() {
-        ensure_plist_up_to_date(&plist_path)?
-    } else {
-        false
-    };
+    let plist_changed = ensure_plist_up_to_date(&plist_path, user_path)?;
 
     let is_bootstrapped = run_launchctl(&["print", &service_target], true).unwrap_or(1);
     if is_bootstrapped != 0 {
@@ -348,6 +470,7 @@ pub fn service_restart() -> io::Result<()> {
             format!("service file '{}' is not installed", plist_path.display()),
         ));
     }
+    ensure_no_duplicate_rift()?;
 
     let uid = getuid();
     let service_target = format!("gui/{}/{}", uid, RIFT_PLIST);
@@ -432,4 +555,20 @@ mod tests {
             .expect("expected to find rift in PATH");
         assert_eq!(found, link);
     }
+
+    #[test]
+    fn user_path_override_sticks_across_rewrites() {
+        let installed = format!(
+            "<key>PATH</key>\n        <string>{}</string>",
+            xml_escape("/opt/a&b/bin:/usr/bin")
+        );
+        assert_eq!(service_path_env(None, Some(&installed)), "/opt/a&b/bin:/usr/bin");
+        assert_eq!(service_path_env(Some("/bin"), Some(&installed)), "/bin");
+    }
+
+    #[test]
+    fn detects_hand_written_agents() {
+        assert!(starts_rift("<string>/opt/homebrew/bin/rift</string>"));
+        assert!(!starts_rift("<string>/opt/homebrew/bin/rift-cli</string>"));
+    }
 }
@@ -397,9 +397,15 @@ unsafe extern "C" {
         shape: *mut CFType,
     ) -> CGError;
     pub fn SLSOrderWindow(cid: cid_t, wid: u32, order: c_int, relative_to: u32) -> CGError;
+    pub fn SLSMoveWindow(cid: cid_t, wid: u32, point: *const CGPoint) -> CGError;
     pub fn SLSSetWindowTags(cid: cid_t, wid: u32, tags: *mut u64, tag_count: c_int) -> CGError;
     pub fn SLSClearWindowTags(cid: cid_t, wid: u32, tags: *mut u64, tag_count: c_int) -> CGError;
     pub fn CGSNewRegionWithRect(rect: *const CGRect, region: *mut *mut CFType) -> CGError;
+    pub fn CGSNewRegionWithRectList(
+        rects: *const CGRect,
+        count: u32,
+        region: *mut *mut CFType,
+    ) -> CGError;
     pub fn CGRegionCreateEmptyRegion() -> *mut CFType;
     pub fn SLWindowContextCreate(cid: cid_t, wid: u32, options: *mut CFType) -> *mut CGContext;
     pub fn SLSSetWindowProperty(
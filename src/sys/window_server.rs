@@ -14,7 +14,7 @@ use objc2_core_foundation::{
 use objc2_core_graphics::{
     CGBitmapInfo, CGColorSpace, CGContext, CGError, CGImage, CGInterpolationQuality, CGWindowID,
     CGWindowListCopyWindowInfo, CGWindowListOption, kCGNullWindowID, kCGWindowBounds,
-    kCGWindowLayer, kCGWindowNumber, kCGWindowOwnerPID,
+    kCGWindowLayer, kCGWindowNumber, kCGWindowOwnerName, kCGWindowOwnerPID,
 };
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
@@ -213,6 +213,15 @@ pub fn get_visible_windows_with_layer(layer: Option<i32>) -> Vec<WindowServerInf
         .collect()
 }
 
+/// Normal-layer windows `pid` has on screen, for apps whose windows can't be
+/// listed over accessibility.
+pub fn app_windows_on_screen(pid: pid_t) -> Vec<WindowServerInfo> {
+    get_visible_windows_with_layer(Some(0))
+        .into_iter()
+        .filter(|info| info.pid == pid)
+        .collect()
+}
+
 pub fn connection_id_for_pid(pid: pid_t) -> Option<i32> {
     let psn = ProcessSerialNumber::for_pid(pid).ok()?;
     let mut connection_id: c_int = 0;
@@ -337,6 +346,78 @@ fn make_info(
     None
 }
 
+/// Frames of on-screen windows owned by a process whose name matches one of `owners`
+/// (case-insensitive). Used to find custom status bars such as sketchybar.
+pub fn visible_window_frames_for_owners(owners: &[String]) -> Vec<CGRect> {
+    if owners.is_empty() {
+        return Vec::new();
+    }
+    get_visible_windows_raw::<CFDictionary<CFString, CFType>>()
+        .iter()
+        .filter_map(|win| {
+            let owner = win.get(unsafe { kCGWindowOwnerName })?.downcast::<CFString>().ok()?;
+            let owner = owner.to_string();
+            if !owners.iter().any(|o| o.eq_ignore_ascii_case(&owner)) {
+                return None;
+            }
+            let dict = win.get(unsafe { kCGWindowBounds })?.downcast::<CFDictionary>().ok()?;
+            let mut frame = CGRect::default();
+            unsafe {
+                CGRectMakeWithDictionaryRepresentation(
+                    CFRetained::<CFDictionary<_, _>>::as_ptr(&dict).as_ptr(),
+                    &mut frame,
+                )
+            };
+            Some(frame)
+        })
+        .collect()
+}
+
+/// Name of the process that owns window `id`, as the window list reports it.
+pub fn window_owner_name(id: WindowServerId) -> Option<String> {
+    let windows: CFRetained<CFArray<CFDictionary<CFString, CFType>>> = unsafe {
+        let windows =
+            CGWindowListCopyWindowInfo(CGWindowListOption::OptionIncludingWindow, id.as_u32())?;
+        CFRetained::cast_unchecked(windows)
+    };
+    let window = windows.iter().next()?;
+    let owner = window.get(unsafe { kCGWindowOwnerName })?.downcast::<CFString>().ok()?;
+    Some(owner.to_string())
+}
+
+unsafe extern "C" {
+    fn CGDisplayIsCaptured(display: u32) -> u32;
+    fn CGShieldingWindowLevel() -> i32;
+}
+
+/// Whether a process has captured `display` for exclusive use, as some games do.
+pub fn display_is_captured(display: u32) -> bool { unsafe { CGDisplayIsCaptured(display) != 0 } }
+
+/// Whether `pid` has an on-screen window covering all of `bounds` that rift does not
+/// manage. The window must sit at the shielding level, as games that take over a
+/// display do, unless the display shows a native fullscreen space, where any layer
+/// counts. A window rift tiles edge to edge never qualifies.
+pub fn pid_covers_bounds(
+    pid: pid_t,
+    bounds: CGRect,
+    fullscreen_space: bool,
+    is_managed: impl Fn(WindowServerId) -> bool,
+) -> bool {
+    const SLOP: f64 = 1.0;
+    let shielding_level = unsafe { CGShieldingWindowLevel() };
+    get_visible_windows_with_layer(None).iter().any(|info| {
+        info.pid == pid
+            && (info.layer >= shielding_level || (fullscreen_space && info.layer >= 0))
+            && !is_managed(info.id)
+            && info.frame.origin.x <= bounds.origin.x + SLOP
+            && info.frame.origin.y <= bounds.origin.y + SLOP
+            && info.frame.origin.x + info.frame.size.width
+                >= bounds.origin.x + bounds.size.width - SLOP
+            && info.frame.origin.y + info.frame.size.height
+                >= bounds.origin.y + bounds.size.height - SLOP
+    })
+}
+
 #[cfg(test)]
 pub fn get_windows(ids: &[WindowServerId]) -> Vec<WindowServerInfo> {
     ids.iter()
@@ -446,6 +527,16 @@ pub fn window_level(wid: u32) -> Option<NSWindowLevel> {
 
 pub fn window_sub_level(wid: u32) -> c_int { unsafe { mach_get_window_sub_level(wid) } }
 
+#[cfg(test)]
+pub fn set_window_level(_wid: u32, _level: NSWindowLevel) -> Result<(), CGError> { Ok(()) }
+
+/// Moves another process's window to `level`. Best effort: the window server
+/// may refuse this for windows our connection does not own.
+#[cfg(not(test))]
+pub fn set_window_level(wid: u32, level: NSWindowLevel) -> Result<(), CGError> {
+    cg_ok(unsafe { SLSSetWindowLevel(*G_CONNECTION, wid, level as c_int) })
+}
+
 fn iterator_window_suitable(iterator: *mut CFType) -> bool {
     let tags = unsafe { SLSWindowIteratorGetTags(iterator) };
     let attributes = unsafe { SLSWindowIteratorGetAttributes(iterator) };
@@ -544,6 +635,36 @@ pub fn space_window_list_for_connection(
     windows
 }
 
+#[cfg(test)]
+thread_local! {
+    static TEST_STACKING_ORDER: std::cell::RefCell<Vec<u32>> = Default::default();
+}
+
+/// Stands in for the window server's stacking order in tests, front to back.
+#[cfg(test)]
+pub fn set_test_stacking_order(order: Vec<u32>) {
+    TEST_STACKING_ORDER.with(|stack| *stack.borrow_mut() = order);
+}
+
+#[cfg(test)]
+pub fn space_stacking_order(_space: u64) -> Vec<u32> {
+    TEST_STACKING_ORDER.with(|stack| stack.borrow().clone())
+}
+
+/// The visible windows of `space`, front to back.
+#[cfg(not(test))]
+pub fn space_stacking_order(space: u64) -> Vec<u32> {
+    space_window_list_for_connection(&[space], 0, false)
+}
+
+/// The frontmost window of the frontmost app on the active space.
+pub fn front_window() -> Option<WindowServerId> {
+    match get_front_window(*G_CONNECTION) {
+        0 => None,
+        id => Some(WindowServerId::new(id)),
+    }
+}
+
 pub fn app_window_suitable(id: WindowServerId) -> bool {
     let cf = cf_array_from_ids(&[id]);
 
@@ -694,9 +815,16 @@ unsafe extern "C" {
     ) -> *mut CGContext;
 
     pub fn CGBitmapContextCreateImage(c: *mut CGContext) -> *mut CGImage;
+
+    fn CGWindowListCreateImage(
+        screen_bounds: CGRect,
+        list_option: u32,
+        window_id: u32,
+        image_option: u32,
+    ) -> *mut CGImage;
 }
 
-fn capture_window(id: WindowServerId) -> Option<CapturedWindowImage> {
+pub fn capture_window(id: WindowServerId) -> Option<CapturedWindowImage> {
     unsafe {
         let imgs_ref = SLSHWCaptureWindowList(
             *G_CONNECTION,
@@ -726,6 +854,125 @@ pub fn capture_window_image(
     resize_cgimage_fit(img.cg_image(), target_w, target_h)
 }
 
+/// Composites what is on screen inside `rect` from the windows below `below`,
+/// so an overlay window can show what it covers without capturing itself.
+pub fn capture_region_below(rect: CGRect, below: u32) -> Option<CapturedWindowImage> {
+    const ON_SCREEN_BELOW_WINDOW: u32 = 1 << 2;
+    const BEST_RESOLUTION: u32 = 1 << 3;
+    let img =
+        unsafe { CGWindowListCreateImage(rect, ON_SCREEN_BELOW_WINDOW, below, BEST_RESOLUTION) };
+    NonNull::new(img).map(|p| CapturedWindowImage(unsafe { CFRetained::from_raw(p) }))
+}
+
+/// Everything on screen inside `rect`, at the display's backing resolution.
+pub fn capture_display(rect: CGRect) -> Option<CapturedWindowImage> {
+    const ON_SCREEN_ONLY: u32 = 1 << 0;
+    const BEST_RESOLUTION: u32 = 1 << 3;
+    let img =
+        unsafe { CGWindowListCreateImage(rect, ON_SCREEN_ONLY, kCGNullWindowID, BEST_RESOLUTION) };
+    NonNull::new(img).map(|p| CapturedWindowImage(unsafe { CFRetained::from_raw(p) }))
+}
+
+/// Draws each image stretched into its frame on a transparent canvas covering
+/// `bounds`, back to front. `scale` maps points to pixels.
+pub fn composite_images(
+    bounds: CGRect,
+    scale: f64,
+    layers: &[(CGRect, CapturedWindowImage)],
+) -> Option<CapturedWindowImage> {
+    let width = (bounds.size.width * scale).round() as usize;
+    let height = (bounds.size.height * scale).round() as usize;
+    if width == 0 || height == 0 {
+        return None;
+    }
+    unsafe {
+        let cs = CGColorSpace::new_device_rgb()?;
+        let ctx = NonNull::new(CGBitmapContextCreate(
+            std::ptr::null_mut(),
+            width,
+            height,
+            8,
+            0,
+            CFRetained::as_ptr(&cs).as_ptr(),
+            CGBitmapInfo(2u32 | 2 << 12),
+        ))?;
+        let ctx = CFRetained::from_raw(ctx);
+        CGContext::set_interpolation_quality(Some(ctx.as_ref()), CGInterpolationQuality::High);
+        for (frame, image) in layers {
+            // Bitmap contexts put the origin at the bottom left, window frames at the top left.
+            let dst = CGRect::new(
+                CGPoint::new(
+                    (frame.origin.x - bounds.origin.x) * scale,
+                    (bounds.max().y - frame.max().y) * scale,
+                ),
+                CGSize::new(frame.size.width * scale, frame.size.height * scale),
+            );
+            CGContext::draw_image(Some(ctx.as_ref()), dst, Some(image.cg_image()));
+        }
+        let out = CGBitmapContextCreateImage(CFRetained::as_ptr(&ctx).as_ptr());
+        NonNull::new(out).map(|p| CapturedWindowImage(CFRetained::from_raw(p)))
+    }
+}
+
+#[link(name = "CoreFoundation", kind = "framework")]
+unsafe extern "C" {
+    fn CFURLCreateFromFileSystemRepresentation(
+        allocator: *const c_void,
+        buffer: *const u8,
+        len: isize,
+        is_directory: bool,
+    ) -> *mut CFType;
+}
+
+#[link(name = "ImageIO", kind = "framework")]
+unsafe extern "C" {
+    fn CGImageDestinationCreateWithURL(
+        url: *const CFType,
+        type_: *const CFString,
+        count: usize,
+        options: *const c_void,
+    ) -> *mut CFType;
+
+    fn CGImageDestinationAddImage(
+        dest: *mut CFType,
+        image: *mut CGImage,
+        properties: *const c_void,
+    );
+
+    fn CGImageDestinationFinalize(dest: *mut CFType) -> bool;
+}
+
+pub fn write_png(image: &CapturedWindowImage, path: &std::path::Path) -> Result<(), String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let bytes = path.as_os_str().as_bytes();
+    let png = CFString::from_str("public.png");
+    unsafe {
+        let url = CFURLCreateFromFileSystemRepresentation(
+            std::ptr::null(),
+            bytes.as_ptr(),
+            bytes.len() as isize,
+            false,
+        );
+        if url.is_null() {
+            return Err(format!("Invalid path {}", path.display()));
+        }
+        let dest = CGImageDestinationCreateWithURL(url, &*png, 1, std::ptr::null());
+        CFRelease(url);
+        if dest.is_null() {
+            return Err(format!("Cannot write to {}", path.display()));
+        }
+        CGImageDestinationAddImage(dest, image.as_ptr(), std::ptr::null());
+        let written = CGImageDestinationFinalize(dest);
+        CFRelease(dest);
+        if written {
+            Ok(())
+        } else {
+            Err(format!("Failed to encode {}", path.display()))
+        }
+    }
+}
+
 pub fn resize_cgimage_fit(
     src: &CGImage,
     target_w: usize,
@@ -795,6 +1042,60 @@ pub fn make_key_window(pid: pid_t, wsid: WindowServerId) -> Result<(), CGError>
     Ok(())
 }
 
+/// Moves a window without going through its app. The app is not told about the
+/// move, and there is no equivalent for changing the window's size. Like
+/// [`order_windows`], this is usually refused for windows of other apps.
+pub fn move_window(id: WindowServerId, origin: CGPoint) -> Result<(), CGError> {
+    cg_ok(unsafe { SLSMoveWindow(*G_CONNECTION, id.0, &origin) })
+}
+
+/// Stacks `back_to_front` on top of all other windows in one screen update, the
+/// last id ending up frontmost. Windows of other apps can usually only be
+/// ordered by their owner, so callers need a fallback for when this fails.
+pub fn order_windows(back_to_front: &[WindowServerId]) -> Result<(), CGError> {
+    const ORDER_ABOVE: c_int = 1;
+
+    with_updates_disabled(|| {
+        let mut below = 0;
+        for wsid in back_to_front {
+            cg_ok(unsafe { SLSOrderWindow(*G_CONNECTION, wsid.0, ORDER_ABOVE, below) })?;
+            below = wsid.0;
+        }
+        Ok(())
+    })
+}
+
+/// Runs `f` with screen updates held back for this connection, so everything it
+/// does to our windows reaches the screen together.
+pub fn with_updates_disabled<R>(f: impl FnOnce() -> R) -> R {
+    SLSDisableUpdate(*G_CONNECTION);
+    let result = f();
+    SLSReenableUpdate(*G_CONNECTION);
+    result
+}
+
+#[cfg(test)]
+pub fn order_out_window(_id: WindowServerId) -> Result<(), CGError> { Ok(()) }
+
+/// Takes a window off screen without moving it, as if its app had ordered it
+/// out. Like [`order_windows`], this is usually refused for other apps' windows.
+#[cfg(not(test))]
+pub fn order_out_window(id: WindowServerId) -> Result<(), CGError> {
+    const ORDER_OUT: c_int = 0;
+    cg_ok(unsafe { SLSOrderWindow(*G_CONNECTION, id.0, ORDER_OUT, 0) })
+}
+
+#[cfg(test)]
+pub fn order_in_window(_id: WindowServerId) -> Result<(), CGError> { Ok(()) }
+
+/// Puts a window taken off screen by [`order_out_window`] back, in front of
+/// the windows around it.
+#[cfg(not(test))]
+pub fn order_in_window(id: WindowServerId) -> Result<(), CGError> {
+    const ORDER_ABOVE: c_int = 1;
+    cg_ok(unsafe { SLSOrderWindow(*G_CONNECTION, id.0, ORDER_ABOVE, 0) })
+}
+
 pub fn allow_hide_mouse() -> Result<(), CGError> {
     let cid = unsafe { SLSMainConnectionID() };
     let property = CFString::from_str("SetsCursorInBackground");
@@ -0,0 +1,198 @@
+//! Shadow and corner styling for windows owned by other processes.
+//!
+//! These calls go through private SkyLight APIs on the main connection. The
+//! window server only honours them for windows our connection is allowed to
+//! modify, so every operation is best effort and callers should treat errors
+//! as "styling unavailable" rather than something to retry.
+
+use std::ptr::{self, NonNull};
+
+use objc2_core_foundation::{CFRetained, CFType, CGPoint, CGRect, CGSize};
+use objc2_core_graphics::CGError;
+
+use super::skylight::{
+    CGSNewRegionWithRectList, G_CONNECTION, SLSClearWindowTags, SLSSetWindowShape, SLSSetWindowTags,
+};
+use super::window_server::WindowServerId;
+use crate::sys::cg_ok;
+
+const TAG_BITSET_LEN: i32 = 64;
+const NO_SHADOW_TAG: u64 = 1 << 3;
+
+/// Visual treatment requested for a single window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowStyle {
+    pub shadow: bool,
+    pub corner_radius: f64,
+}
+
+impl Default for WindowStyle {
+    fn default() -> Self {
+        Self {
+            shadow: true,
+            corner_radius: 0.0,
+        }
+    }
+}
+
+pub fn set_shadow(wsid: WindowServerId, enabled: bool) -> Result<(), CGError> {
+    let mut tags = NO_SHADOW_TAG;
+    unsafe {
+        if enabled {
+            cg_ok(SLSClearWindowTags(
+                *G_CONNECTION,
+                wsid.as_u32(),
+                &mut tags,
+                TAG_BITSET_LEN,
+            ))
+        } else {
+            cg_ok(SLSSetWindowTags(
+                *G_CONNECTION,
+                wsid.as_u32(),
+                &mut tags,
+                TAG_BITSET_LEN,
+            ))
+        }
+    }
+}
+
+/// Masks the window to a rounded rectangle of the given size. A radius of zero
+/// restores a plain rectangular shape.
+pub fn set_corner_radius(wsid: WindowServerId, size: CGSize, radius: f64) -> Result<(), CGError> {
+    let rects = rounded_rect_scanlines(size, radius);
+    let mut region: *mut CFType = ptr::null_mut();
+    cg_ok(unsafe { CGSNewRegionWithRectList(rects.as_ptr(), rects.len() as u32, &mut region) })?;
+    let Some(region) = NonNull::new(region) else {
+        return Err(CGError::Failure);
+    };
+    let region = unsafe { CFRetained::<CFType>::from_raw(region) };
+    cg_ok(unsafe {
+        SLSSetWindowShape(
+            *G_CONNECTION,
+            wsid.as_u32(),
+            0.0,
+            0.0,
+            CFRetained::<CFType>::as_ptr(&region).as_ptr(),
+        )
+    })
+}
+
+/// Approximates a rounded rectangle with one rect per row inside the corner
+/// arcs plus a single rect for the straight middle section.
+fn rounded_rect_scanlines(size: CGSize, radius: f64) -> Vec<CGRect> {
+    let radius = radius.min(size.width / 2.0).min(size.height / 2.0).max(0.0).floor();
+    if radius < 1.0 {
+        return vec![CGRect::new(CGPoint::ZERO, size)];
+    }
+
+    let rows = radius as usize;
+    let mut rects = Vec::with_capacity(rows * 2 + 1);
+    for row in 0..rows {
+        let dy = radius - row as f64 - 0.5;
+        let inset = (radius - (radius * radius - dy * dy).max(0.0).sqrt()).round();
+        let width = size.width - inset * 2.0;
+        rects.push(CGRect::new(
+            CGPoint::new(inset, row as f64),
+            CGSize::new(width, 1.0),
+        ));
+        rects.push(CGRect::new(
+            CGPoint::new(inset, size.height - row as f64 - 1.0),
+            CGSize::new(width, 1.0),
+        ));
+    }
+    // The arcs meet when the radius is half the height, leaving no middle.
+    if size.height > radius * 2.0 {
+        rects.push(CGRect::new(
+            CGPoint::new(0.0, radius),
+            CGSize::new(size.width, size.height - radius * 2.0),
+        ));
+    }
+    rects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts every rect is non-empty and inside `size`, and that each row of
+    /// `size` is covered by exactly one of them.
+    fn assert_rows_tiled_once(size: CGSize, rects: &[CGRect]) {
+        for rect in rects {
+            assert!(
+                rect.size.width > 0.0 && rect.size.height > 0.0,
+                "{rect:?} is empty"
+            );
+            assert!(
+                rect.origin.x >= 0.0 && rect.origin.y >= 0.0,
+                "{rect:?} starts outside"
+            );
+            assert!(
+                rect.origin.x + rect.size.width <= size.width,
+                "{rect:?} is too wide"
+            );
+            assert!(
+                rect.origin.y + rect.size.height <= size.height,
+                "{rect:?} is too tall"
+            );
+        }
+        for row in 0..size.height as usize {
+            let y = row as f64 + 0.5;
+            let covering = rects
+                .iter()
+                .filter(|rect| rect.origin.y <= y && y < rect.origin.y + rect.size.height)
+                .count();
+            assert_eq!(covering, 1, "row {row} is covered {covering} times");
+        }
+    }
+
+    #[test]
+    fn zero_radius_is_a_single_rect() {
+        let size = CGSize::new(40.0, 30.0);
+        assert_eq!(rounded_rect_scanlines(size, 0.0), [CGRect::new(
+            CGPoint::ZERO,
+            size
+        )]);
+        assert_eq!(rounded_rect_scanlines(size, -4.0), [CGRect::new(
+            CGPoint::ZERO,
+            size
+        )]);
+    }
+
+    #[test]
+    fn corner_rows_are_inset_and_tile_the_height() {
+        let size = CGSize::new(40.0, 30.0);
+        let rects = rounded_rect_scanlines(size, 8.0);
+        assert_eq!(rects.len(), 8 * 2 + 1);
+        assert_rows_tiled_once(size, &rects);
+
+        // Rows get wider towards the middle and the corners are cut away.
+        let top_rows: Vec<_> = rects.iter().filter(|rect| rect.origin.y < 8.0).collect();
+        assert!(top_rows[0].origin.x > 0.0);
+        assert!(top_rows.windows(2).all(|pair| pair[0].origin.x >= pair[1].origin.x));
+        assert_eq!(
+            rects.last(),
+            Some(&CGRect::new(CGPoint::new(0.0, 8.0), CGSize::new(40.0, 14.0)))
+        );
+    }
+
+    #[test]
+    fn oversized_radius_is_clamped_to_half_the_short_side() {
+        // Even height: the arcs meet in the middle with nothing between them.
+        let size = CGSize::new(40.0, 10.0);
+        let rects = rounded_rect_scanlines(size, 50.0);
+        assert_eq!(rects.len(), 5 * 2);
+        assert_rows_tiled_once(size, &rects);
+
+        // Odd height leaves a one-point middle row.
+        let size = CGSize::new(40.0, 11.0);
+        let rects = rounded_rect_scanlines(size, 50.0);
+        assert_eq!(rects.len(), 5 * 2 + 1);
+        assert_rows_tiled_once(size, &rects);
+
+        // A narrow rect clamps to half its width instead.
+        let size = CGSize::new(6.0, 40.0);
+        let rects = rounded_rect_scanlines(size, 50.0);
+        assert_eq!(rects.len(), 3 * 2 + 1);
+        assert_rows_tiled_once(size, &rects);
+    }
+}
@@ -1,4 +1,12 @@
 pub mod common;
+pub mod debug_overlay;
+pub mod hints;
 pub mod menu_bar;
 pub mod mission_control;
+pub mod overlay_host;
+pub mod region_select;
+pub mod selection_outline;
 pub mod stack_line;
+pub mod text;
+pub mod theme;
+pub mod zoom;
@@ -1,9 +1,12 @@
 use std::ptr;
+use std::time::{Duration, Instant};
 
 use objc2_core_foundation::{CFType, CGPoint, CGRect, CGSize};
 use objc2_core_graphics::CGContext;
 use objc2_quartz_core::{CALayer, CATransaction};
 
+use crate::common::config::WorkspaceScrollSettings;
+use crate::layout_engine::LayoutCommand;
 use crate::model::server::WindowData;
 use crate::sys::skylight::{
     CFRelease, G_CONNECTION, SLSFlushWindowContentRegion, SLWindowContextCreate,
@@ -134,3 +137,68 @@ pub fn compute_window_layout_metrics(
         disp_h,
     })
 }
+
+/// Turns scroll-wheel input over an indicator into workspace switches.
+#[derive(Debug, Default)]
+pub struct WorkspaceScroll {
+    last_switch: Option<Instant>,
+}
+
+impl WorkspaceScroll {
+    /// `delta_y` is `NSEvent.scrollingDeltaY`, positive when scrolling up.
+    pub fn on_scroll(
+        &mut self,
+        settings: &WorkspaceScrollSettings,
+        delta_y: f64,
+        now: Instant,
+    ) -> Option<LayoutCommand> {
+        if !settings.enabled || delta_y == 0.0 {
+            return None;
+        }
+        let debounce = Duration::from_millis(settings.debounce_ms);
+        if self.last_switch.is_some_and(|last| now.duration_since(last) < debounce) {
+            return None;
+        }
+        self.last_switch = Some(now);
+        if (delta_y < 0.0) != settings.invert {
+            Some(LayoutCommand::NextWorkspace(None))
+        } else {
+            Some(LayoutCommand::PrevWorkspace(None))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrolling_switches_once_per_debounce_window() {
+        let mut settings = WorkspaceScrollSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        let mut scroll = WorkspaceScroll::default();
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+
+        assert!(matches!(
+            scroll.on_scroll(&settings, -3.0, at(0)),
+            Some(LayoutCommand::NextWorkspace(None))
+        ));
+        assert!(scroll.on_scroll(&settings, -3.0, at(100)).is_none());
+        assert!(matches!(
+            scroll.on_scroll(&settings, 2.0, at(300)),
+            Some(LayoutCommand::PrevWorkspace(None))
+        ));
+
+        settings.invert = true;
+        assert!(matches!(
+            scroll.on_scroll(&settings, 2.0, at(600)),
+            Some(LayoutCommand::NextWorkspace(None))
+        ));
+        assert!(scroll.on_scroll(&settings, 0.0, at(900)).is_none());
+        settings.enabled = false;
+        assert!(scroll.on_scroll(&settings, -3.0, at(1200)).is_none());
+    }
+}
@@ -0,0 +1,246 @@
+//! Draws the live layout tree over the desktop, for diagnosing layout problems.
+//!
+//! Shown by `toggle_debug_overlay`. Every container and window of the active
+//! workspace gets an outline colored by its depth in the tree and a label with
+//! its node id, container kind and share of its parent's split, so it can be
+//! matched against `rift-cli execute debug` output. The band between the screen
+//! edge and the tiling area is shaded, and exclusion zones are shaded in a
+//! second color. The reactor sends a new snapshot after every layout pass.
+
+use std::fmt::Write as _;
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::NSStatusWindowLevel;
+use objc2_core_foundation::{CFString, CGPoint, CGRect, CGSize};
+use objc2_foundation::MainThreadMarker;
+use objc2_quartz_core::{CALayer, CATextLayer};
+
+use crate::common::config::{GapSettings, InnerGaps, OuterGaps};
+use crate::layout_engine::engine::{DebugLayoutOutline, DebugNodeOutline};
+use crate::sys::cgs_window::CgsWindowError;
+use crate::sys::geometry::CGRectExt;
+use crate::sys::screen::SpaceId;
+use crate::ui::common::with_disabled_actions;
+use crate::ui::overlay_host::{OverlayHost, OverlayWindowStyle, screen_metrics};
+use crate::ui::stack_line::Color;
+use crate::ui::text::LabelFont;
+
+const FONT_SIZE: f64 = 11.0;
+const LABEL_PADDING: f64 = 3.0;
+const BORDER_WIDTH: f64 = 2.0;
+/// Outline colors by depth, cycling for deeper trees.
+const DEPTH_COLORS: [(f64, f64, f64); 5] = [
+    (0.2, 0.6, 1.0),
+    (0.3, 0.85, 0.4),
+    (1.0, 0.75, 0.2),
+    (0.85, 0.4, 1.0),
+    (0.2, 0.9, 0.9),
+];
+
+/// What the debug overlay shows for one active space.
+#[derive(Debug, Clone)]
+pub struct DebugOverlayInfo {
+    pub space_id: SpaceId,
+    /// The frame the space is laid out in, in global coordinates.
+    pub screen: CGRect,
+    pub layout: DebugLayoutOutline,
+    /// Exclusion zones on the space's display, in global coordinates.
+    pub exclusion_zones: Vec<CGRect>,
+}
+
+/// The overlay window for one display.
+pub struct DebugOverlayWindow {
+    host: OverlayHost,
+    font: LabelFont,
+}
+
+impl DebugOverlayWindow {
+    pub fn new(mtm: MainThreadMarker, screen: CGRect) -> Result<Self, CgsWindowError> {
+        let style = OverlayWindowStyle {
+            level: NSStatusWindowLevel as i32,
+            blur_radius: None,
+        };
+        let host = OverlayHost::new(mtm, screen, scale_for(mtm, screen), style)?;
+        Ok(Self {
+            host,
+            font: LabelFont::new(None, FONT_SIZE),
+        })
+    }
+
+    /// Redraws the overlay from `info`, moving it onto `info.screen` if needed.
+    pub fn show(&self, info: &DebugOverlayInfo) -> Result<(), CgsWindowError> {
+        if self.host.frame() != info.screen {
+            self.host.move_to(info.screen, scale_for(self.host.mtm(), info.screen));
+        }
+        let root = self.host.root_layer();
+        let local = |rect: CGRect| CGRect::new(self.host.to_local(rect.origin), rect.size);
+        with_disabled_actions(|| {
+            unsafe { root.setSublayers(None) };
+            let reserved = Color::new(1.0, 0.3, 0.3, 0.18);
+            for band in reserved_bands(info.screen, info.layout.tiling_area) {
+                root.addSublayer(&fill_layer(local(band), reserved));
+            }
+            let excluded = Color::new(1.0, 0.55, 0.0, 0.3);
+            for zone in &info.exclusion_zones {
+                root.addSublayer(&fill_layer(local(*zone), excluded));
+            }
+            for node in &info.layout.nodes {
+                let (r, g, b) = DEPTH_COLORS[node.depth % DEPTH_COLORS.len()];
+                let frame = local(node.frame);
+                root.addSublayer(&outline_layer(frame, Color::new(r, g, b, 0.85)));
+                // Nested labels would all land on the same corner; step them down by depth.
+                let offset = node.depth as f64 * (self.font.line_height() + LABEL_PADDING);
+                let at = CGPoint::new(frame.origin.x + BORDER_WIDTH, frame.origin.y + offset);
+                root.addSublayer(&self.label_layer(&node_label(node), at));
+            }
+            let screen = local(info.screen);
+            let bottom = screen.origin.y + screen.size.height - self.font.line_height();
+            let at = CGPoint::new(screen.origin.x, bottom);
+            root.addSublayer(&self.label_layer(&gaps_label(&info.layout.gaps), at));
+        });
+        self.host.present();
+        self.host.order_in()
+    }
+
+    pub fn hide(&self) -> Result<(), CgsWindowError> { self.host.order_out() }
+
+    /// A dark badge with `text`, its top-left corner at `origin`.
+    fn label_layer(&self, text: &str, origin: CGPoint) -> Retained<CALayer> {
+        let text_size = CGSize::new(self.font.measure(text), self.font.line_height());
+        let size = CGSize::new(text_size.width + 2.0 * LABEL_PADDING, text_size.height);
+        let badge = CALayer::layer();
+        badge.setFrame(CGRect::new(origin, size));
+        badge.setCornerRadius(LABEL_PADDING);
+        let fill = Color::new(0.0, 0.0, 0.0, 0.7).to_nscolor();
+        badge.setBackgroundColor(Some(&fill.CGColor()));
+
+        let text_layer = CATextLayer::layer();
+        text_layer.setContentsScale(self.host.scale());
+        let cf_text = CFString::from_str(text);
+        let raw = cf_text.as_ref() as *const AnyObject;
+        unsafe {
+            text_layer.setString(Some(&*raw));
+            text_layer.setFont(Some(self.font.as_cf_type()));
+        }
+        text_layer.setFontSize(self.font.size());
+        let ink = Color::new(1.0, 1.0, 1.0, 0.95).to_nscolor();
+        text_layer.setForegroundColor(Some(&ink.CGColor()));
+        text_layer.setFrame(CGRect::new(CGPoint::new(LABEL_PADDING, 0.0), text_size));
+        badge.addSublayer(&text_layer);
+        badge
+    }
+}
+
+fn scale_for(mtm: MainThreadMarker, screen: CGRect) -> f64 {
+    screen_metrics(mtm)
+        .iter()
+        .find(|metric| metric.bounds.contains(screen.mid()))
+        .map_or(1.0, |metric| metric.scale)
+}
+
+fn fill_layer(frame: CGRect, color: Color) -> Retained<CALayer> {
+    let layer = CALayer::layer();
+    layer.setFrame(frame);
+    layer.setBackgroundColor(Some(&color.to_nscolor().CGColor()));
+    layer
+}
+
+fn outline_layer(frame: CGRect, color: Color) -> Retained<CALayer> {
+    let layer = CALayer::layer();
+    layer.setFrame(frame);
+    layer.setBorderWidth(BORDER_WIDTH);
+    layer.setBorderColor(Some(&color.to_nscolor().CGColor()));
+    let fill = Color { a: 0.04, ..color };
+    layer.setBackgroundColor(Some(&fill.to_nscolor().CGColor()));
+    layer
+}
+
+/// The parts of `screen` outside `tiling_area`: outer gaps plus whatever bars,
+/// exclusion zones and notification banners reserve.
+fn reserved_bands(screen: CGRect, tiling_area: CGRect) -> Vec<CGRect> {
+    let (left, top) = (screen.origin.x, screen.origin.y);
+    let right = left + screen.size.width;
+    let bottom = top + screen.size.height;
+    let (inner_left, inner_top) = (tiling_area.origin.x, tiling_area.origin.y);
+    let inner_right = inner_left + tiling_area.size.width;
+    let inner_bottom = inner_top + tiling_area.size.height;
+    let inner_height = tiling_area.size.height;
+    let rect = |x: f64, y: f64, w: f64, h: f64| CGRect::new(CGPoint::new(x, y), CGSize::new(w, h));
+    [
+        rect(left, top, screen.size.width, inner_top - top),
+        rect(left, inner_bottom, screen.size.width, bottom - inner_bottom),
+        rect(left, inner_top, inner_left - left, inner_height),
+        rect(inner_right, inner_top, right - inner_right, inner_height),
+    ]
+    .into_iter()
+    .filter(|band| band.size.width > 0.0 && band.size.height > 0.0)
+    .collect()
+}
+
+fn node_label(node: &DebugNodeOutline) -> String {
+    let mut label = match node.node_id {
+        Some(id) => format!("{id:?}"),
+        None => String::new(),
+    };
+    if let Some(kind) = node.kind {
+        let _ = write!(label, " {kind:?}");
+    }
+    if let Some(wid) = node.window_id {
+        let _ = write!(label, " {}:{}", wid.pid, wid.idx);
+    }
+    if let Some(ratio) = node.ratio {
+        let _ = write!(label, " {:.1}%", ratio * 100.0);
+    }
+    label.trim_start().to_string()
+}
+
+fn gaps_label(gaps: &GapSettings) -> String {
+    let OuterGaps { top, left, bottom, right } = gaps.outer;
+    let InnerGaps { horizontal, vertical } = gaps.inner;
+    format!("outer {top} {right} {bottom} {left} · inner {horizontal}×{vertical}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::app::WindowId;
+    use crate::layout_engine::LayoutKind;
+
+    fn rect(x: f64, y: f64, w: f64, h: f64) -> CGRect {
+        CGRect::new(CGPoint::new(x, y), CGSize::new(w, h))
+    }
+
+    #[test]
+    fn reserved_bands_cover_the_screen_outside_the_tiling_area() {
+        let screen = rect(0.0, 25.0, 1000.0, 775.0);
+        let bands = reserved_bands(screen, rect(10.0, 65.0, 980.0, 725.0));
+        assert_eq!(bands, [
+            rect(0.0, 25.0, 1000.0, 40.0),
+            rect(0.0, 790.0, 1000.0, 10.0),
+            rect(0.0, 65.0, 10.0, 725.0),
+            rect(990.0, 65.0, 10.0, 725.0),
+        ]);
+        assert!(reserved_bands(screen, screen).is_empty());
+    }
+
+    #[test]
+    fn labels_name_the_node_and_its_share() {
+        let container = DebugNodeOutline {
+            node_id: None,
+            kind: Some(LayoutKind::Vertical),
+            frame: CGRect::ZERO,
+            ratio: Some(0.375),
+            depth: 1,
+            window_id: None,
+        };
+        assert_eq!(node_label(&container), "Vertical 37.5%");
+        let window = DebugNodeOutline {
+            kind: None,
+            ratio: None,
+            window_id: Some(WindowId::new(42, 7)),
+            ..container
+        };
+        assert_eq!(node_label(&window), "42:7");
+    }
+}
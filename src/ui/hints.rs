@@ -0,0 +1,340 @@
+//! Keyboard hints for jumping straight to a window.
+//!
+//! Every window on screen gets a one- or two-letter label over its middle.
+//! Typing a label reports that window through the completion callback; Escape
+//! cancels and Delete takes back the last letter. Keys are read by character,
+//! so the labels follow the active keyboard layout.
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::NSColor;
+use objc2_core_foundation::{CFString, CGPoint, CGRect, CGSize};
+use objc2_core_graphics::CGEventType;
+use objc2_foundation::MainThreadMarker;
+use objc2_quartz_core::{CALayer, CATextLayer};
+use tracing::{debug, warn};
+
+use crate::sys::app::pid_t;
+use crate::sys::cgs_window::CgsWindowError;
+use crate::sys::geometry::CGRectExt;
+use crate::sys::hotkey::{KeyCode, cg_keycode_to_keycode, character_key_code, keycode_from_char};
+use crate::sys::window_server::{self, WindowServerInfo};
+use crate::ui::common::with_disabled_actions;
+use crate::ui::overlay_host::{
+    OverlayContent, OverlayHost, OverlayInput, OverlayWindowStyle, screen_metrics,
+};
+use crate::ui::text::LabelFont;
+
+const ESCAPE_KEYCODE: u16 = 53;
+const DELETE_KEYCODE: u16 = 51;
+const FONT_SIZE: f64 = 20.0;
+const LABEL_PADDING: f64 = 6.0;
+/// How much of a window has to be on a display, each way, for it to get a
+/// hint. Keeps out windows parked at the edge of the screen by workspaces.
+const MIN_VISIBLE: f64 = 32.0;
+
+/// Labels for `count` windows from `alphabet`, shortest first.
+///
+/// Letters are used on their own while they last; once there are more windows
+/// than letters, the letters at the end become prefixes of two-letter labels,
+/// as few of them as fit everything. No label is the start of another, so a
+/// label is done as soon as it is typed. At most `alphabet.len()²` are made.
+pub fn hint_labels(alphabet: &[char], count: usize) -> Vec<String> {
+    let letters = alphabet.len();
+    let singles = (0..=letters)
+        .rev()
+        .find(|&singles| singles + (letters - singles) * letters >= count)
+        .unwrap_or(0);
+    let doubles = alphabet[singles..]
+        .iter()
+        .flat_map(|&first| alphabet.iter().map(move |&second| format!("{first}{second}")));
+    let singles = alphabet[..singles].iter().map(char::to_string);
+    singles.chain(doubles).take(count).collect()
+}
+
+/// The windows worth a hint, out of `windows` listed front to back: ones that
+/// show enough of themselves on one of `screens` and are not wholly behind a
+/// window further forward.
+pub fn hint_targets(windows: Vec<WindowServerInfo>, screens: &[CGRect]) -> Vec<WindowServerInfo> {
+    let mut targets: Vec<WindowServerInfo> = Vec::new();
+    for window in windows {
+        let on_screen = screens.iter().any(|screen| {
+            let visible = screen.intersection(&window.frame);
+            visible.size.width >= MIN_VISIBLE && visible.size.height >= MIN_VISIBLE
+        });
+        if on_screen && !targets.iter().any(|front| front.frame.contains_rect(window.frame)) {
+            targets.push(window);
+        }
+    }
+    targets
+}
+
+struct Hint {
+    label: String,
+    target: WindowServerInfo,
+    layer: Retained<CALayer>,
+}
+
+pub struct HintOverlay {
+    /// One per display. The first also owns the key tap.
+    hosts: Vec<OverlayHost>,
+    hints: Vec<Hint>,
+    keys: Vec<(char, KeyCode)>,
+    typed: RefCell<String>,
+    finished: Cell<bool>,
+    on_done: Box<dyn Fn(Option<WindowServerInfo>)>,
+}
+
+impl HintOverlay {
+    /// Labels the windows on every display. `on_done` receives the window whose
+    /// label was typed, or `None` if hinting was cancelled.
+    pub fn show(
+        mtm: MainThreadMarker,
+        alphabet: &str,
+        on_done: impl Fn(Option<WindowServerInfo>) + 'static,
+    ) -> Result<Rc<Self>, CgsWindowError> {
+        let metrics = screen_metrics(mtm);
+        let screens: Vec<CGRect> = metrics.iter().map(|metric| metric.bounds).collect();
+        let own_pid = std::process::id() as pid_t;
+        let windows = window_server::get_visible_windows_with_layer(Some(0))
+            .into_iter()
+            .filter(|window| window.pid != own_pid)
+            .collect();
+        let targets = hint_targets(windows, &screens);
+
+        let keys = alphabet_keys(alphabet);
+        let letters: Vec<char> = keys.iter().map(|(letter, _)| *letter).collect();
+        let labels = hint_labels(&letters, targets.len());
+
+        let hosts = metrics
+            .iter()
+            .map(|metric| {
+                OverlayHost::new(mtm, metric.bounds, metric.scale, OverlayWindowStyle::default())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let font = LabelFont::new(None, FONT_SIZE);
+        let hints = labels
+            .into_iter()
+            .zip(targets)
+            .filter_map(|(label, target)| {
+                let (host, anchor) = hosts
+                    .iter()
+                    .map(|host| (host, host.frame().intersection(&target.frame)))
+                    .max_by(|(_, a), (_, b)| a.area().total_cmp(&b.area()))?;
+                let center = host.to_local(anchor.mid());
+                let layer = label_layer(&label, center, &font, host.scale());
+                host.root_layer().addSublayer(&layer);
+                Some(Hint { label, target, layer })
+            })
+            .collect();
+
+        let overlay = Rc::new(Self {
+            hosts,
+            hints,
+            keys,
+            typed: RefCell::new(String::new()),
+            finished: Cell::new(false),
+            on_done: Box::new(on_done),
+        });
+        if overlay.hints.is_empty() {
+            debug!("No windows to show hints for");
+            overlay.cancel();
+            return Ok(overlay);
+        }
+        let content: Weak<dyn OverlayContent> = Rc::downgrade(&overlay);
+        for host in &overlay.hosts {
+            host.attach(content.clone());
+            host.present();
+            host.order_in()?;
+        }
+        if overlay.host().acquire_input(&[CGEventType::KeyDown], false).is_none() {
+            warn!("Unable to install event tap for window hints; cancelling");
+            overlay.cancel();
+        }
+        Ok(overlay)
+    }
+
+    fn handle_key(&self, keycode: u16) {
+        match keycode {
+            ESCAPE_KEYCODE => return self.cancel(),
+            DELETE_KEYCODE => {
+                self.typed.borrow_mut().pop();
+            }
+            _ => {
+                let Some(letter) = self.letter_for(keycode) else {
+                    return;
+                };
+                let typed = {
+                    let mut typed = self.typed.borrow_mut();
+                    typed.push(letter);
+                    typed.clone()
+                };
+                if let Some(hint) = self.hints.iter().find(|hint| hint.label == typed) {
+                    return self.finish(Some(hint.target.clone()));
+                }
+                if !self.hints.iter().any(|hint| hint.label.starts_with(&typed)) {
+                    // A letter that leads nowhere is dropped rather than ending hinting.
+                    self.typed.borrow_mut().pop();
+                }
+            }
+        }
+        self.update_labels();
+    }
+
+    fn letter_for(&self, keycode: u16) -> Option<char> {
+        let key = character_key_code(cg_keycode_to_keycode(keycode)?);
+        let (letter, _) = self.keys.iter().find(|(_, letter_key)| *letter_key == key)?;
+        Some(*letter)
+    }
+
+    /// Hides the labels that no longer start with what has been typed.
+    fn update_labels(&self) {
+        let typed = self.typed.borrow();
+        with_disabled_actions(|| {
+            for hint in &self.hints {
+                hint.layer.setHidden(!hint.label.starts_with(typed.as_str()));
+            }
+        });
+        for host in &self.hosts {
+            host.present();
+        }
+    }
+
+    fn finish(&self, result: Option<WindowServerInfo>) {
+        if self.finished.replace(true) {
+            return;
+        }
+        for host in &self.hosts {
+            host.set_input_enabled(false);
+            let _ = host.order_out();
+        }
+        (self.on_done)(result);
+    }
+
+    pub fn is_finished(&self) -> bool { self.finished.get() }
+
+    /// Cancels hinting if it is still in progress.
+    pub fn cancel(&self) { self.finish(None); }
+}
+
+impl OverlayContent for HintOverlay {
+    fn host(&self) -> &OverlayHost { &self.hosts[0] }
+
+    fn handle_input(&self, input: OverlayInput) -> bool {
+        if self.finished.get() {
+            return false;
+        }
+        match input {
+            OverlayInput::KeyDown { keycode, .. } => self.handle_key(keycode),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Drop for HintOverlay {
+    fn drop(&mut self) {
+        if !self.finished.replace(true) {
+            for host in &self.hosts {
+                let _ = host.order_out();
+            }
+        }
+    }
+}
+
+/// The letters of `alphabet` with the keys that type them, lowercased and
+/// without repeats. Letters no key types are dropped.
+fn alphabet_keys(alphabet: &str) -> Vec<(char, KeyCode)> {
+    let mut keys: Vec<(char, KeyCode)> = Vec::new();
+    for letter in alphabet.chars().flat_map(char::to_lowercase) {
+        if keys.iter().any(|(seen, _)| *seen == letter) {
+            continue;
+        }
+        if let Some(key) = keycode_from_char(&letter.to_string()) {
+            keys.push((letter, key));
+        }
+    }
+    keys
+}
+
+/// A rounded badge showing `label` in capitals, centered on `center`.
+fn label_layer(label: &str, center: CGPoint, font: &LabelFont, scale: f64) -> Retained<CALayer> {
+    let text = label.to_uppercase();
+    let text_size = CGSize::new(font.measure(&text), font.line_height());
+    let size = CGSize::new(
+        text_size.width + 2.0 * LABEL_PADDING,
+        text_size.height + LABEL_PADDING,
+    );
+    let badge = CALayer::layer();
+    badge.setFrame(CGRect::new(
+        CGPoint::new(center.x - size.width / 2.0, center.y - size.height / 2.0),
+        size,
+    ));
+    badge.setCornerRadius(size.height / 4.0);
+    let fill = NSColor::colorWithRed_green_blue_alpha(1.0, 0.85, 0.25, 0.95);
+    let border = NSColor::colorWithRed_green_blue_alpha(0.0, 0.0, 0.0, 0.35);
+    badge.setBackgroundColor(Some(&fill.CGColor()));
+    badge.setBorderColor(Some(&border.CGColor()));
+    badge.setBorderWidth(1.0);
+
+    let text_layer = CATextLayer::layer();
+    text_layer.setContentsScale(scale);
+    let cf_text = CFString::from_str(&text);
+    let raw = cf_text.as_ref() as *const AnyObject;
+    unsafe {
+        text_layer.setString(Some(&*raw));
+        text_layer.setFont(Some(font.as_cf_type()));
+    }
+    text_layer.setFontSize(font.size());
+    let ink = NSColor::colorWithRed_green_blue_alpha(0.0, 0.0, 0.0, 0.9);
+    text_layer.setForegroundColor(Some(&ink.CGColor()));
+    text_layer.setFrame(CGRect::new(
+        CGPoint::new(LABEL_PADDING, LABEL_PADDING / 2.0),
+        text_size,
+    ));
+    badge.addSublayer(&text_layer);
+    badge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys::window_server::WindowServerId;
+
+    #[test]
+    fn labels_stay_single_letters_until_they_run_out() {
+        let alphabet = ['a', 's', 'd'];
+        assert_eq!(hint_labels(&alphabet, 2), ["a", "s"]);
+        assert_eq!(hint_labels(&alphabet, 3), ["a", "s", "d"]);
+        assert_eq!(hint_labels(&alphabet, 5), ["a", "s", "da", "ds", "dd"]);
+        let seven = ["a", "sa", "ss", "sd", "da", "ds", "dd"];
+        assert_eq!(hint_labels(&alphabet, 7), seven);
+        assert_eq!(hint_labels(&alphabet, 12).len(), 9);
+        assert!(hint_labels(&alphabet, 0).is_empty());
+    }
+
+    #[test]
+    fn hidden_and_covered_windows_get_no_hint() {
+        let window = |id, x, y, w, h| WindowServerInfo {
+            id: WindowServerId::new(id),
+            pid: 1,
+            layer: 0,
+            frame: CGRect::new(CGPoint::new(x, y), CGSize::new(w, h)),
+            min_frame: CGSize::ZERO,
+            max_frame: CGSize::ZERO,
+        };
+        let screen = CGRect::new(CGPoint::ZERO, CGSize::new(1000.0, 800.0));
+        let windows = vec![
+            window(1, 0.0, 0.0, 600.0, 800.0),
+            window(2, 100.0, 100.0, 300.0, 300.0),
+            window(3, 400.0, 0.0, 600.0, 800.0),
+            window(4, 990.0, 790.0, 600.0, 800.0),
+        ];
+        let targets = hint_targets(windows, &[screen]);
+        let ids: Vec<u32> = targets.iter().map(|window| window.id.as_u32()).collect();
+        assert_eq!(ids, [1, 3]);
+    }
+}
@@ -6,7 +6,7 @@ use objc2::rc::Retained;
 use objc2::runtime::{AnyObject, ProtocolObject};
 use objc2::{ClassType, DefinedClass, MainThreadOnly, Message, define_class, msg_send, sel};
 use objc2_app_kit::{
-    NSColor, NSControlStateValueOff, NSControlStateValueOn, NSEventModifierFlags, NSFont,
+    NSColor, NSControlStateValueOff, NSControlStateValueOn, NSEvent, NSEventModifierFlags, NSFont,
     NSFontAttributeName, NSForegroundColorAttributeName, NSGraphicsContext, NSMenu, NSMenuItem,
     NSStatusBar, NSStatusItem, NSVariableStatusItemLength, NSView,
 };
@@ -30,7 +30,7 @@ use crate::common::config::{
 };
 use crate::layout_engine::LayoutCommand;
 use crate::model::VirtualWorkspaceId;
-use crate::model::server::{WindowData, WorkspaceData};
+use crate::model::server::{SpaceData, WindowData, WorkspaceData};
 use crate::sys::hotkey::{Hotkey, KeyCode, Modifiers};
 use crate::sys::screen::SpaceId;
 use crate::ui::common::compute_window_layout_metrics;
@@ -47,11 +47,17 @@ const FONT_SIZE: f64 = 12.0;
 pub enum MenuAction {
     SetLayout(LayoutMode),
     ToggleSpaceActivated,
+    ToggleDisplayActivated(usize),
+    SetSpacePaused(bool),
     NextWorkspace,
     PrevWorkspace,
     SwitchToWorkspace(usize),
+    /// A scroll over the icon, with its `scrollingDeltaY`.
+    Scroll(f64),
     OpenGitHub,
     OpenDocumentation,
+    /// Opens the page of the release the update checker found.
+    OpenRelease,
     OpenMatrix,
     OpenConfig,
     ReloadConfig,
@@ -71,7 +77,7 @@ impl MenuIcon {
     pub fn new(mtm: MainThreadMarker, action_tx: UnboundedSender<MenuAction>) -> Self {
         let status_bar = NSStatusBar::systemStatusBar();
         let status_item = status_bar.statusItemWithLength(NSVariableStatusItemLength);
-        let view = MenuIconView::new(mtm);
+        let view = MenuIconView::new(mtm, action_tx.clone());
         let menu_handler = MenuActionHandler::new(mtm, action_tx);
         let menu = build_status_menu(
             mtm,
@@ -79,6 +85,9 @@ impl MenuIcon {
             None,
             SpaceId::new(0),
             true,
+            false,
+            None,
+            &[],
             &[],
             &MenuShortcuts::default(),
         );
@@ -103,6 +112,10 @@ impl MenuIcon {
         &mut self,
         active_space: SpaceId,
         active_space_is_activated: bool,
+        active_space_is_paused: bool,
+        secure_input: bool,
+        available_update: Option<&str>,
+        displays: &[SpaceData],
         workspaces: &[WorkspaceData],
         _active_workspace: Option<VirtualWorkspaceId>,
         _windows: &[WindowData],
@@ -120,6 +133,10 @@ impl MenuIcon {
             active_layout,
             active_space,
             active_space_is_activated,
+            active_space_is_paused,
+            secure_input,
+            available_update,
+            displays,
             workspaces,
             &shortcuts,
         );
@@ -240,6 +257,10 @@ impl MenuIcon {
             self.view.setFrameOrigin(CGPoint::new(x, y));
         }
 
+        // Dim the icon while layout on the active space is paused, or while secure
+        // input keeps hotkeys from reaching us.
+        let dimmed = active_space_is_paused || secure_input;
+        self.view.setAlphaValue(if dimmed { 0.5 } else { 1.0 });
         self.view.setNeedsDisplay(true);
     }
 }
@@ -285,6 +306,7 @@ struct MenuIconViewIvars {
     layout: RefCell<MenuIconLayout>,
     active_text_attrs: Retained<NSDictionary<NSAttributedStringKey, AnyObject>>,
     inactive_text_attrs: Retained<NSDictionary<NSAttributedStringKey, AnyObject>>,
+    action_tx: UnboundedSender<MenuAction>,
 }
 
 fn as_any_object<T: Message>(obj: &T) -> &AnyObject {
@@ -362,12 +384,37 @@ fn build_status_menu(
     active_layout: Option<LayoutMode>,
     _active_space: SpaceId,
     active_space_is_activated: bool,
+    active_space_is_paused: bool,
+    secure_input: bool,
+    available_update: Option<&str>,
+    displays: &[SpaceData],
     workspaces: &[WorkspaceData],
     shortcuts: &MenuShortcuts,
 ) -> Retained<NSMenu> {
     let title = NSString::from_str("Rift");
     let menu: Retained<NSMenu> = unsafe { msg_send![NSMenu::alloc(mtm), initWithTitle: &*title] };
 
+    if secure_input {
+        // No action, so the item shows greyed out as a plain notice.
+        let title = "Hotkeys Paused: Secure Input Is On";
+        menu.addItem(&make_menu_item(mtm, title, None, None, None, None, None));
+        add_separator(&menu);
+    }
+
+    if let Some(version) = available_update {
+        let title = format!("Update Available: {version}…");
+        menu.addItem(&make_menu_item(
+            mtm,
+            &title,
+            Some(sel!(onOpenRelease:)),
+            Some(handler),
+            None,
+            None,
+            None,
+        ));
+        add_separator(&menu);
+    }
+
     let layout_item = make_menu_item(mtm, "Layout", None, None, None, None, None);
     let layout_submenu_title = NSString::from_str("Layout");
     let layout_submenu: Retained<NSMenu> =
@@ -463,6 +510,38 @@ fn build_status_menu(
         shortcuts.toggle_space_activation.as_ref(),
         None,
     ));
+    if displays.len() > 1 {
+        let displays_item = make_menu_item(mtm, "Displays", None, None, None, None, None);
+        let displays_submenu_title = NSString::from_str("Displays");
+        let displays_submenu: Retained<NSMenu> =
+            unsafe { msg_send![NSMenu::alloc(mtm), initWithTitle: &*displays_submenu_title] };
+        for (idx, display) in displays.iter().enumerate() {
+            let label =
+                display.display_name.clone().unwrap_or_else(|| format!("Display {}", idx + 1));
+            displays_submenu.addItem(&make_menu_item(
+                mtm,
+                &label,
+                Some(sel!(onToggleDisplayActivation:)),
+                Some(handler),
+                Some(display.is_active),
+                None,
+                Some(idx as isize),
+            ));
+        }
+        displays_item.setSubmenu(Some(&displays_submenu));
+        menu.addItem(&displays_item);
+    }
+    // The tag carries the state the item switches to, so the handler doesn't need
+    // to know whether the space is currently paused.
+    menu.addItem(&make_menu_item(
+        mtm,
+        "Pause Layout",
+        Some(sel!(onToggleSpacePaused:)),
+        Some(handler),
+        Some(active_space_is_paused),
+        None,
+        Some(if active_space_is_paused { 0 } else { 1 }),
+    ));
 
     add_separator(&menu);
     menu.addItem(&make_menu_item(
@@ -718,6 +797,23 @@ define_class!(
             self.emit(MenuAction::ToggleSpaceActivated);
         }
 
+        #[unsafe(method(onToggleDisplayActivation:))]
+        fn on_toggle_display_activation(&self, sender: Option<&NSMenuItem>) {
+            if let Some(sender) = sender {
+                let tag = sender.tag();
+                if tag >= 0 {
+                    self.emit(MenuAction::ToggleDisplayActivated(tag as usize));
+                }
+            }
+        }
+
+        #[unsafe(method(onToggleSpacePaused:))]
+        fn on_toggle_space_paused(&self, sender: Option<&NSMenuItem>) {
+            if let Some(sender) = sender {
+                self.emit(MenuAction::SetSpacePaused(sender.tag() != 0));
+            }
+        }
+
         #[unsafe(method(onNextWorkspace:))]
         fn on_next_workspace(&self, _sender: Option<&AnyObject>) {
             self.emit(MenuAction::NextWorkspace);
@@ -753,6 +849,11 @@ define_class!(
             self.emit(MenuAction::OpenGitHub);
         }
 
+        #[unsafe(method(onOpenRelease:))]
+        fn on_open_release(&self, _sender: Option<&AnyObject>) {
+            self.emit(MenuAction::OpenRelease);
+        }
+
         #[unsafe(method(onOpenMatrix:))]
         fn on_open_matrix(&self, _sender: Option<&AnyObject>) {
             self.emit(MenuAction::OpenMatrix);
@@ -818,7 +919,7 @@ fn build_cached_text_line(
 }
 
 impl MenuIconView {
-    fn new(mtm: MainThreadMarker) -> Retained<Self> {
+    fn new(mtm: MainThreadMarker, action_tx: UnboundedSender<MenuAction>) -> Retained<Self> {
         let font = NSFont::menuBarFontOfSize(FONT_SIZE);
         let active_color = NSColor::blackColor();
         let inactive_color = NSColor::whiteColor();
@@ -830,6 +931,7 @@ impl MenuIconView {
             layout: RefCell::new(MenuIconLayout::default()),
             active_text_attrs: active_attrs,
             inactive_text_attrs: inactive_attrs,
+            action_tx,
         });
         unsafe { msg_send![super(view), initWithFrame: frame] }
     }
@@ -946,6 +1048,11 @@ define_class!(
     struct MenuIconView;
 
     impl MenuIconView {
+        #[unsafe(method(scrollWheel:))]
+        fn scroll_wheel(&self, event: &NSEvent) {
+            let _ = self.ivars().action_tx.send(MenuAction::Scroll(event.scrollingDeltaY()));
+        }
+
         #[unsafe(method(drawRect:))]
         fn draw_rect(&self, _dirty_rect: NSRect) {
             let layout = self.ivars().layout.borrow();
@@ -1,6 +1,6 @@
 use core::ffi::c_void;
-use std::cell::RefCell;
-use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
@@ -10,34 +10,36 @@ use dispatchr::time::Time;
 use objc2::msg_send;
 use objc2::rc::{Retained, autoreleasepool};
 use objc2::runtime::AnyObject;
-use objc2_app_kit::{NSApplication, NSColor, NSPopUpMenuWindowLevel, NSScreen};
+use objc2_app_kit::NSApplication;
 use objc2_core_foundation::{CFRetained, CFString, CGPoint, CGRect, CGSize};
-use objc2_core_graphics::{
-    CGColor, CGDisplayBounds, CGEvent, CGEventField, CGEventFlags, CGEventTapOptions,
-    CGEventTapProxy, CGEventType,
+use objc2_core_graphics::{CGColor, CGEventFlags, CGEventType, CGPath};
+use objc2_foundation::{MainThreadMarker, NSArray, NSNumber};
+use objc2_quartz_core::{
+    CALayer, CAShapeLayer, CATextLayer, CATransaction, kCAAlignmentCenter, kCAAlignmentLeft,
+    kCAAlignmentRight,
 };
-use objc2_foundation::MainThreadMarker;
-use objc2_quartz_core::{CALayer, CATextLayer, CATransaction};
 use once_cell::sync::Lazy;
 use parking_lot::{Mutex, RwLock};
-use tracing::info;
 
 use crate::actor::app::WindowId;
 use crate::common::collections::{HashMap, HashSet, hash_map};
-use crate::common::config::Config;
+use crate::common::config::{Config, MissionControlSelection};
+use crate::layout_engine::Direction;
 use crate::model::server::{WindowData, WorkspaceData};
-use crate::model::virtual_workspace::VirtualWorkspaceId;
-use crate::sys::cgs_window::CgsWindow;
+use crate::model::virtual_workspace::{VirtualWorkspaceId, grid_neighbor};
+use crate::sys::appearance::Appearance;
+use crate::sys::cgs_window::CgsWindowError;
 use crate::sys::dispatch::DispatchExt;
-use crate::sys::event::current_cursor_location;
-use crate::sys::geometry::CGRectExt;
-use crate::sys::screen::{
-    CoordinateConverter, NSScreenExt, ScreenCache, ScreenId, ScreenInfo, get_active_space_number,
-};
+use crate::sys::screen::get_active_space_number;
 use crate::sys::window_server::{CapturedWindowImage, WindowServerId};
 use crate::ui::common::{
     compute_window_layout_metrics, render_layer_to_cgs_window, with_disabled_actions,
 };
+use crate::ui::overlay_host::{
+    OverlayContent, OverlayHost, OverlayInput, OverlayWindowStyle, screen_metrics,
+};
+use crate::ui::text::{FittedLabel, LabelFont, TextDirection, interface_direction};
+use crate::ui::theme::OverlayTheme;
 
 #[derive(Debug, Clone)]
 struct CaptureTask {
@@ -51,7 +53,7 @@ struct CaptureJob {
     task: CaptureTask,
     cache: Arc<RwLock<HashMap<WindowId, CapturedWindowImage>>>,
     generation: u64,
-    overlay_ptr_bits: usize,
+    refresh: RefreshHandle,
 }
 
 struct CapturePool {
@@ -95,11 +97,7 @@ static CAPTURE_POOL: Lazy<CapturePool> = Lazy::new(|| {
                     if let Some(mut set) = IN_FLIGHT.try_lock() {
                         set.remove(&(job.generation, job.task.window_id));
                     }
-                    if let Some(overlay) =
-                        unsafe { (job.overlay_ptr_bits as *const MissionControlOverlay).as_ref() }
-                    {
-                        overlay.request_refresh();
-                    }
+                    job.refresh.request();
                 } else {
                     if let Some(mut set) = IN_FLIGHT.try_lock() {
                         set.remove(&(job.generation, job.task.window_id));
@@ -112,72 +110,115 @@ static CAPTURE_POOL: Lazy<CapturePool> = Lazy::new(|| {
     CapturePool { sender: tx }
 });
 
-extern "C" fn refresh_coalesced_cb(ctx: *mut c_void) {
-    if ctx.is_null() {
-        return;
-    }
-    let overlay = unsafe { &*(ctx as *const MissionControlOverlay) };
-    overlay.refresh_pending.store(false, Ordering::Release);
-    overlay.refresh_previews();
+static NEXT_OVERLAY_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// Overlays alive on the main thread, so callbacks that only carry an id
+    /// (including ones queued from capture workers) can find them if they still exist.
+    static LIVE_OVERLAYS: RefCell<HashMap<u64, Weak<MissionControlOverlay>>> =
+        RefCell::new(HashMap::default());
 }
 
-struct FadeCompletionCtx {
-    overlay_ptr_bits: usize,
-    fade_id: u64,
-    final_alpha: f32,
+fn live_overlay(id: u64) -> Option<Rc<MissionControlOverlay>> {
+    LIVE_OVERLAYS.with(|live| live.borrow().get(&id).and_then(Weak::upgrade))
 }
 
-extern "C" fn fade_completion_callback(ctx: *mut c_void) {
-    if ctx.is_null() {
-        return;
-    }
-    unsafe {
-        let boxed = Box::from_raw(ctx as *mut FadeCompletionCtx);
-        if boxed.overlay_ptr_bits == 0 {
+/// Thread-safe way to ask an overlay to repaint its previews. Requests are
+/// coalesced and delivered on the main queue; they are dropped if the overlay
+/// has gone away in the meantime.
+#[derive(Clone)]
+struct RefreshHandle {
+    overlay_id: u64,
+    pending: Arc<AtomicBool>,
+}
+
+impl RefreshHandle {
+    fn request(&self) {
+        if self.pending.swap(true, Ordering::AcqRel) {
             return;
         }
-        if let Some(overlay) = (boxed.overlay_ptr_bits as *const MissionControlOverlay).as_ref() {
-            overlay.finish_fade(boxed.fade_id, boxed.final_alpha);
-        }
+        let ctx = Box::into_raw(Box::new(self.clone())) as *mut c_void;
+        queue::main().after_f(Time::new_after(Time::NOW, 8000000), ctx, refresh_coalesced_cb);
     }
 }
 
-fn schedule_fade_completion(overlay_ptr_bits: usize, fade_id: u64, final_alpha: f32) {
-    if overlay_ptr_bits == 0 {
+extern "C" fn refresh_coalesced_cb(ctx: *mut c_void) {
+    if ctx.is_null() {
         return;
     }
-    let ctx = Box::into_raw(Box::new(FadeCompletionCtx {
-        overlay_ptr_bits,
-        fade_id,
-        final_alpha,
-    })) as *mut c_void;
-    queue::main().after_f(Time::NOW, ctx, fade_completion_callback);
+    let handle = unsafe { Box::from_raw(ctx as *mut RefreshHandle) };
+    handle.pending.store(false, Ordering::Release);
+    if let Some(overlay) = live_overlay(handle.overlay_id) {
+        overlay.refresh_previews();
+    }
 }
 
-static WORKSPACE_BACKGROUND_COLOR: Lazy<Retained<CGColor>> =
-    Lazy::new(|| CGColor::new_generic_gray(1.0, 0.03).into());
-
-static SELECTED_BORDER_COLOR: Lazy<Retained<CGColor>> =
-    Lazy::new(|| CGColor::new_generic_rgb(0.2, 0.45, 1.0, 0.85).into());
-
-static WORKSPACE_BORDER_COLOR: Lazy<Retained<CGColor>> =
-    Lazy::new(|| CGColor::new_generic_gray(1.0, 0.12).into());
-
 static WINDOW_BORDER_COLOR: Lazy<Retained<CGColor>> =
     Lazy::new(|| CGColor::new_generic_gray(0.0, 0.65).into());
 
-static OVERLAY_BACKGROUND_COLOR: Lazy<Retained<CGColor>> =
-    Lazy::new(|| CGColor::new_generic_gray(0.0, 0.25).into());
-
 #[derive(Debug, Clone)]
 pub enum MissionControlMode {
     AllWorkspaces(Vec<WorkspaceData>),
     CurrentWorkspace(Vec<WindowData>),
 }
 
+impl MissionControlMode {
+    /// The same view without minimized windows or windows of hidden apps.
+    fn without_minimized(&self) -> MissionControlMode {
+        let shown = |window: &WindowData| !window.info.is_minimized;
+        match self {
+            MissionControlMode::AllWorkspaces(workspaces) => MissionControlMode::AllWorkspaces(
+                workspaces
+                    .iter()
+                    .map(|ws| {
+                        let mut ws = ws.clone();
+                        ws.windows.retain(shown);
+                        ws
+                    })
+                    .collect(),
+            ),
+            MissionControlMode::CurrentWorkspace(windows) => MissionControlMode::CurrentWorkspace(
+                windows.iter().filter(|window| shown(window)).cloned().collect(),
+            ),
+        }
+    }
+}
+
+/// Why a window's preview is dimmed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WindowBadge {
+    Minimized,
+    Hidden,
+}
+
+impl WindowBadge {
+    fn of(window: &WindowData) -> Option<Self> {
+        if window.is_hidden {
+            Some(WindowBadge::Hidden)
+        } else if window.info.is_minimized {
+            Some(WindowBadge::Minimized)
+        } else {
+            None
+        }
+    }
+
+    fn text(self) -> &'static str {
+        match self {
+            WindowBadge::Minimized => "Minimized",
+            WindowBadge::Hidden => "Hidden",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum MissionControlAction {
     SwitchToWorkspace(usize),
+    /// A workspace tile was dragged from position `from` to `to` in the
+    /// workspace order.
+    MoveWorkspace {
+        from: usize,
+        to: usize,
+    },
     FocusWindow {
         window_id: WindowId,
         window_server_id: Option<WindowServerId>,
@@ -185,29 +226,49 @@ pub enum MissionControlAction {
     Dismiss,
 }
 
+/// A workspace name fitted to its tile, kept so unchanged labels aren't
+/// measured again on every redraw.
 struct WorkspaceLabelText {
     text: String,
+    max_width: f64,
+    fitted: FittedLabel,
     attributed: CFRetained<CFString>,
 }
 
 impl WorkspaceLabelText {
-    fn new(text: &str) -> Self {
-        let cf_string = CFString::from_str(text);
+    fn new(text: &str, max_width: f64, font: &LabelFont, fallback: TextDirection) -> Self {
+        let fitted = font.fit(text, max_width, fallback);
         Self {
             text: text.to_owned(),
-            attributed: cf_string,
-        }
-    }
-
-    fn update(&mut self, text: &str) -> bool {
-        if self.text == text {
+            max_width,
+            attributed: CFString::from_str(&fitted.text),
+            fitted,
+        }
+    }
+
+    /// Refits the label if its text or room changed. Returns whether the string
+    /// shown needs to be applied to the layer again.
+    fn update(
+        &mut self,
+        text: &str,
+        max_width: f64,
+        font: &LabelFont,
+        fallback: TextDirection,
+    ) -> bool {
+        if self.text == text && self.max_width == max_width {
             return false;
         }
 
+        let fitted = font.fit(text, max_width, fallback);
+        let changed = fitted.text != self.fitted.text;
+        if changed {
+            self.attributed = CFString::from_str(&fitted.text);
+        }
         self.text.clear();
         self.text.push_str(text);
-        self.attributed = CFString::from_str(text);
-        true
+        self.max_width = max_width;
+        self.fitted = fitted;
+        changed
     }
 
     unsafe fn apply_to(&self, layer: &CATextLayer) {
@@ -221,6 +282,10 @@ impl WorkspaceLabelText {
 #[derive(Default)]
 struct PreviewLayerStyle {
     is_selected: Option<bool>,
+    /// Dashed outline over a floating window.
+    float_outline: Option<Retained<CAShapeLayer>>,
+    /// Dims a minimized or hidden window, with a badge saying which it is.
+    scrim: Option<(WindowBadge, Retained<CALayer>, Retained<CATextLayer>)>,
 }
 
 impl PreviewLayerStyle {
@@ -232,18 +297,133 @@ impl PreviewLayerStyle {
             true
         }
     }
+
+    /// Forgets the applied style so the next draw restyles the preview from
+    /// scratch, e.g. after a theme change.
+    fn reset(&mut self) {
+        self.is_selected = None;
+        if let Some(outline) = self.float_outline.take() {
+            outline.removeFromSuperlayer();
+        }
+        if let Some((_, scrim, _)) = self.scrim.take() {
+            scrim.removeFromSuperlayer();
+        }
+    }
+
+    /// Adds, updates or removes the float outline and minimized scrim on the
+    /// preview `layer`, which is `size` points big.
+    fn decorate(
+        &mut self,
+        layer: &CALayer,
+        size: CGSize,
+        window: &WindowData,
+        theme: &OverlayTheme,
+        scale: f64,
+    ) {
+        let bounds = CGRect::new(CGPoint::ZERO, size);
+
+        if window.is_floating && !window.info.is_minimized {
+            let outline = self.float_outline.get_or_insert_with(|| {
+                let outline = CAShapeLayer::layer();
+                outline.setFillColor(None);
+                outline.setZPosition(1.0);
+                layer.addSublayer(&outline);
+                outline
+            });
+            let line_width = theme.border_width.max(1.0) * 1.5;
+            let dashes = NSArray::from_retained_slice(&[
+                NSNumber::new_f64(line_width * 4.0),
+                NSNumber::new_f64(line_width * 3.0),
+            ]);
+            let inset = line_width / 2.0;
+            let path_rect = CGRect::new(
+                CGPoint::new(inset, inset),
+                CGSize::new(
+                    (size.width - line_width).max(0.0),
+                    (size.height - line_width).max(0.0),
+                ),
+            );
+            let path = unsafe { CGPath::with_rounded_rect(path_rect, 4.0, 4.0, std::ptr::null()) };
+            outline.setFrame(bounds);
+            outline.setPath(Some(&path));
+            outline.setStrokeColor(Some(&theme.label));
+            outline.setLineWidth(line_width);
+            outline.setLineDashPattern(Some(&dashes));
+        } else if let Some(outline) = self.float_outline.take() {
+            outline.removeFromSuperlayer();
+        }
+
+        let badge = WindowBadge::of(window);
+        if self.scrim.as_ref().map(|(kind, ..)| *kind) != badge {
+            if let Some((_, scrim, _)) = self.scrim.take() {
+                scrim.removeFromSuperlayer();
+            }
+        }
+        let Some(badge) = badge else { return };
+        let (_, scrim, label) = self.scrim.get_or_insert_with(|| {
+            let scrim = CALayer::layer();
+            scrim.setZPosition(2.0);
+            let label = CATextLayer::layer();
+            label.setContentsScale(scale);
+            label.setAlignmentMode(unsafe { kCAAlignmentCenter });
+            scrim.addSublayer(&label);
+            layer.addSublayer(&scrim);
+            (badge, scrim, label)
+        });
+        scrim.setFrame(bounds);
+        scrim.setBackgroundColor(Some(&theme.scrim));
+
+        let font = &theme.font;
+        let max_width = size.width - 2.0 * LABEL_PADDING;
+        let fitted = font.fit(badge.text(), max_width, TextDirection::LeftToRight);
+        let text = CFString::from_str(&fitted.text);
+        let raw = text.as_ref() as *const AnyObject;
+        unsafe {
+            label.setString(Some(&*raw));
+            label.setFont(Some(font.as_cf_type()));
+        }
+        label.setFontSize(font.size());
+        label.setForegroundColor(Some(&theme.label));
+        label.setFrame(CGRect::new(
+            CGPoint::new(
+                (size.width - fitted.width) / 2.0,
+                (size.height - font.line_height()) / 2.0,
+            ),
+            CGSize::new(fitted.width, font.line_height()),
+        ));
+    }
+}
+
+/// The last selection in each view, kept by id rather than position so it can
+/// be found again after workspaces or windows come and go.
+#[derive(Clone, Debug, Default)]
+pub struct SelectionMemory {
+    workspace: Option<String>,
+    window: Option<WindowId>,
 }
 
 pub struct MissionControlState {
     mode: Option<MissionControlMode>,
+    /// `mode` as it was handed in, before minimized windows were filtered out.
+    unfiltered_mode: Option<MissionControlMode>,
+    hide_minimized: bool,
+    /// Give empty workspaces a placeholder tile instead of leaving them out.
+    show_empty: bool,
+    /// The configured workspace grid width; unset keeps the default layout.
+    grid_columns: Option<usize>,
     on_action: Option<Rc<dyn Fn(MissionControlAction)>>,
     selection: Option<Selection>,
+    drag: Option<WorkspaceDrag>,
+    memory: SelectionMemory,
+    /// Open on the remembered selection instead of the active item.
+    remember_selection: bool,
     preview_cache: Arc<RwLock<HashMap<WindowId, CapturedWindowImage>>>,
     preview_layers: HashMap<WindowId, Retained<CALayer>>,
     preview_layer_styles: HashMap<WindowId, PreviewLayerStyle>,
     workspace_layers: HashMap<String, Retained<CALayer>>,
     workspace_label_layers: HashMap<String, Retained<CATextLayer>>,
     workspace_label_strings: HashMap<String, WorkspaceLabelText>,
+    workspace_placeholder_layers: HashMap<String, Retained<CATextLayer>>,
     ready_previews: HashSet<WindowId>,
     render_root: Option<Retained<CALayer>>,
     render_window_id: Option<u32>,
@@ -256,14 +436,22 @@ impl Default for MissionControlState {
     fn default() -> Self {
         Self {
             mode: None,
+            unfiltered_mode: None,
+            hide_minimized: false,
+            show_empty: false,
+            grid_columns: None,
             on_action: None,
             selection: None,
+            drag: None,
+            memory: SelectionMemory::default(),
+            remember_selection: false,
             preview_cache: Arc::new(RwLock::new(HashMap::default())),
             preview_layers: HashMap::default(),
             preview_layer_styles: HashMap::default(),
             workspace_layers: HashMap::default(),
             workspace_label_layers: HashMap::default(),
             workspace_label_strings: HashMap::default(),
+            workspace_placeholder_layers: HashMap::default(),
             ready_previews: HashSet::default(),
             render_root: None,
             render_window_id: None,
@@ -275,8 +463,14 @@ impl Default for MissionControlState {
 
 impl MissionControlState {
     fn set_mode(&mut self, mode: MissionControlMode) {
-        self.mode = Some(mode);
+        self.mode = Some(if self.hide_minimized {
+            mode.without_minimized()
+        } else {
+            mode.clone()
+        });
+        self.unfiltered_mode = Some(mode);
         self.selection = None;
+        self.drag = None;
         let _new_gen = CURRENT_GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
         self.ready_previews.clear();
         self.prune_preview_cache();
@@ -285,9 +479,81 @@ impl MissionControlState {
 
     fn mode(&self) -> Option<&MissionControlMode> { self.mode.as_ref() }
 
+    /// Shows or leaves out minimized and hidden windows, keeping the selected
+    /// item selected if it is still shown.
+    fn set_hide_minimized(&mut self, hide: bool) -> bool {
+        if self.hide_minimized == hide {
+            return false;
+        }
+        self.hide_minimized = hide;
+        let Some(unfiltered) = self.unfiltered_mode.as_ref() else {
+            return false;
+        };
+        let mode = if hide {
+            unfiltered.without_minimized()
+        } else {
+            unfiltered.clone()
+        };
+        if let Some(selection) = self.selection.take() {
+            self.remember(selection);
+        }
+        self.mode = Some(mode);
+        self.prune_preview_cache();
+        let remember = std::mem::replace(&mut self.remember_selection, true);
+        self.ensure_selection();
+        self.remember_selection = remember;
+        true
+    }
+
+    /// Shows or leaves out empty workspaces, keeping the selected workspace
+    /// selected.
+    fn set_show_empty(&mut self, show: bool) -> bool {
+        if self.show_empty == show {
+            return false;
+        }
+        if let Some(selection) = self.selection.take() {
+            self.remember(selection);
+        }
+        self.show_empty = show;
+        let remember = std::mem::replace(&mut self.remember_selection, true);
+        self.ensure_selection();
+        self.remember_selection = remember;
+        true
+    }
+
+    /// Moves the workspace at `from` to `to`, as the layout engine will once the
+    /// drag ends, and keeps it selected.
+    fn move_workspace(&mut self, from: usize, to: usize) -> bool {
+        let mut moved_id = None;
+        for mode in [self.mode.as_mut(), self.unfiltered_mode.as_mut()].into_iter().flatten() {
+            let MissionControlMode::AllWorkspaces(workspaces) = mode else {
+                return false;
+            };
+            if from == to || from >= workspaces.len() || to >= workspaces.len() {
+                return false;
+            }
+            let ws = workspaces.remove(from);
+            moved_id = Some(ws.id.clone());
+            workspaces.insert(to, ws);
+            for (index, ws) in workspaces.iter_mut().enumerate() {
+                ws.index = index;
+            }
+        }
+        let Some(MissionControlMode::AllWorkspaces(workspaces)) = self.mode.as_ref() else {
+            return false;
+        };
+        let visible = workspaces.iter().filter(|ws| workspace_is_shown(ws, self.show_empty));
+        if let Some(idx) = visible.position(|ws| Some(&ws.id) == moved_id.as_ref()) {
+            self.selection = Some(Selection::Workspace(idx));
+        }
+        true
+    }
+
     fn purge(&mut self) {
         self.mode = None;
+        self.unfiltered_mode = None;
         self.selection = None;
+        self.drag = None;
         self.on_action = None;
 
         let _new_gen = CURRENT_GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
@@ -308,6 +574,9 @@ impl MissionControlState {
             layer.removeFromSuperlayer();
         }
         self.workspace_label_strings.clear();
+        for (_id, layer) in self.workspace_placeholder_layers.drain() {
+            layer.removeFromSuperlayer();
+        }
 
         self.render_root = None;
         self.render_window_id = None;
@@ -324,11 +593,31 @@ impl MissionControlState {
         };
         if is_valid {
             self.selection = Some(selection);
+            self.remember(selection);
+        }
+    }
+
+    fn remember(&mut self, selection: Selection) {
+        match (selection, self.mode.as_ref()) {
+            (Selection::Workspace(idx), Some(MissionControlMode::AllWorkspaces(workspaces))) => {
+                let show_empty = self.show_empty;
+                let mut visible = workspaces.iter().filter(|ws| workspace_is_shown(ws, show_empty));
+                self.memory.workspace = visible.nth(idx).map(|ws| ws.id.clone());
+            }
+            (Selection::Window(idx), Some(MissionControlMode::CurrentWorkspace(windows))) => {
+                self.memory.window = windows.get(idx).map(|window| window.id);
+            }
+            _ => {}
         }
     }
 
     fn highlight_active_workspace(&mut self, active_id: Option<String>) -> bool {
         let target = active_id.as_deref();
+        if let Some(MissionControlMode::AllWorkspaces(workspaces)) = self.unfiltered_mode.as_mut() {
+            for ws in workspaces.iter_mut() {
+                ws.is_active = target == Some(ws.id.as_str());
+            }
+        }
         if let Some(mode) = self.mode.as_mut() {
             if let MissionControlMode::AllWorkspaces(workspaces) = mode {
                 let mut changed = false;
@@ -340,7 +629,7 @@ impl MissionControlState {
                         ws.is_active = should_be_active;
                         changed = true;
                     }
-                    let should_be_visible = !ws.windows.is_empty() || ws.is_active;
+                    let should_be_visible = workspace_is_shown(ws, self.show_empty);
                     if should_be_visible {
                         if ws.is_active {
                             active_selection = Some(visible_index);
@@ -367,26 +656,38 @@ impl MissionControlState {
         if self.selection.is_some() {
             return;
         }
+        let memory = if self.remember_selection {
+            self.memory.clone()
+        } else {
+            SelectionMemory::default()
+        };
         match self.mode.as_ref() {
             Some(MissionControlMode::AllWorkspaces(workspaces)) => {
                 let mut visible_idx = 0usize;
+                let mut remembered = None;
                 let mut desired = None;
                 for ws in workspaces {
-                    if !ws.windows.is_empty() || ws.is_active {
+                    if workspace_is_shown(ws, self.show_empty) {
+                        if memory.workspace.as_deref() == Some(ws.id.as_str()) {
+                            remembered = Some(Selection::Workspace(visible_idx));
+                        }
                         if desired.is_none() && ws.is_active {
                             desired = Some(Selection::Workspace(visible_idx));
                         }
                         visible_idx += 1;
                     }
                 }
-                if let Some(sel) = desired {
+                if let Some(sel) = remembered.or(desired) {
                     self.selection = Some(sel);
                 } else if visible_idx > 0 {
                     self.selection = Some(Selection::Workspace(0));
                 }
             }
             Some(MissionControlMode::CurrentWorkspace(windows)) => {
-                if let Some((idx, _)) = windows.iter().enumerate().find(|(_, win)| win.is_focused) {
+                let remembered =
+                    memory.window.and_then(|wid| windows.iter().position(|win| win.id == wid));
+                let focused = windows.iter().position(|win| win.is_focused);
+                if let Some(idx) = remembered.or(focused) {
                     self.selection = Some(Selection::Window(idx));
                 } else if !windows.is_empty() {
                     self.selection = Some(Selection::Window(0));
@@ -459,6 +760,23 @@ enum Selection {
     Window(usize),
 }
 
+/// Whether a workspace gets a tile in the all-workspaces view. Empty workspaces
+/// other than the active one only do when `show_empty` is set.
+fn workspace_is_shown(ws: &WorkspaceData, show_empty: bool) -> bool {
+    show_empty || !ws.windows.is_empty() || ws.is_active
+}
+
+/// A press on a workspace tile that may turn into a drag to reorder it.
+#[derive(Debug, Clone, Copy)]
+struct WorkspaceDrag {
+    /// Position of the workspace when the press started.
+    origin: usize,
+    /// Position it has been dragged to so far.
+    current: usize,
+    start: CGPoint,
+    dragging: bool,
+}
+
 #[derive(Clone, Copy)]
 enum NavDirection {
     Left,
@@ -467,6 +785,17 @@ enum NavDirection {
     Down,
 }
 
+impl From<NavDirection> for Direction {
+    fn from(direction: NavDirection) -> Self {
+        match direction {
+            NavDirection::Left => Direction::Left,
+            NavDirection::Right => Direction::Right,
+            NavDirection::Up => Direction::Up,
+            NavDirection::Down => Direction::Down,
+        }
+    }
+}
+
 fn workspace_column_count(count: usize) -> usize {
     if count == 0 {
         1
@@ -476,6 +805,7 @@ fn workspace_column_count(count: usize) -> usize {
 }
 
 const MISSION_CONTROL_MARGIN: f64 = 48.0;
+const LABEL_PADDING: f64 = 6.0;
 const WINDOW_TILE_INSET: f64 = 3.0;
 const WINDOW_TILE_GAP: f64 = 1.0;
 const WINDOW_TILE_MIN_SIZE: f64 = 2.0;
@@ -484,24 +814,42 @@ const WINDOW_TILE_MAX_SCALE: f64 = 1.0;
 const SMALL_TILE_MIN_FRACTION: f64 = 0.44;
 const INNER_RELAX_FACTOR: f64 = 0.94;
 const WORKSPACE_TILE_SPACING: f64 = 20.0;
+/// Share of its grid cell's width that an empty workspace's placeholder takes up.
+const PLACEHOLDER_WIDTH_FRACTION: f64 = 0.4;
+const PLACEHOLDER_OPACITY: f32 = 0.6;
+/// Size of the "+" on a placeholder, relative to the label font.
+const PLACEHOLDER_PLUS_SCALE: f64 = 3.0;
+/// How far the pointer has to move sideways before a press on a workspace tile
+/// becomes a drag rather than a click.
+const WORKSPACE_DRAG_THRESHOLD: f64 = 6.0;
 const CURRENT_WS_TILE_SPACING: f64 = 48.0;
 const CURRENT_WS_TILE_PADDING: f64 = 16.0;
 const CURRENT_WS_TILE_SCALE_FACTOR: f64 = 0.9;
 const SYNC_PREWARM_LIMIT: usize = 3;
 
-struct WorkspaceGrid {
+/// Placement of workspace tiles in the all-workspaces view: one row, or two once
+/// there are more tiles than columns. A configured grid is filled row by row
+/// instead, matching `focus_workspace`.
+pub struct WorkspaceGrid {
     bounds: CGRect,
     rows: usize,
     tile_size: CGSize,
+    grid_columns: Option<usize>,
 }
 
 impl WorkspaceGrid {
-    fn new(tile_count: usize, bounds: CGRect) -> Option<Self> {
+    pub fn new(tile_count: usize, bounds: CGRect, grid_columns: Option<usize>) -> Option<Self> {
         if tile_count == 0 {
             return None;
         }
-        let cols = workspace_column_count(tile_count);
-        let rows = if tile_count > cols { 2 } else { 1 };
+        let grid_columns = grid_columns.map(|columns| columns.clamp(1, tile_count));
+        let (cols, rows) = match grid_columns {
+            Some(cols) => (cols, tile_count.div_ceil(cols)),
+            None => {
+                let cols = workspace_column_count(tile_count);
+                (cols, if tile_count > cols { 2 } else { 1 })
+            }
+        };
         let spacing = WORKSPACE_TILE_SPACING;
         let tile_w = (bounds.size.width - spacing * ((cols + 1) as f64)) / (cols as f64);
         let tile_h = (bounds.size.height - spacing * ((rows + 1) as f64)) / (rows as f64);
@@ -509,18 +857,21 @@ impl WorkspaceGrid {
             bounds,
             rows,
             tile_size: CGSize::new(tile_w, tile_h),
+            grid_columns,
         })
     }
 
     fn position_for(&self, order_idx: usize) -> (usize, usize) {
-        if self.rows == 1 {
+        if let Some(cols) = self.grid_columns {
+            (order_idx / cols, order_idx % cols)
+        } else if self.rows == 1 {
             (0, order_idx)
         } else {
             (order_idx % self.rows, order_idx / self.rows)
         }
     }
 
-    fn rect_for(&self, order_idx: usize) -> CGRect {
+    pub fn rect_for(&self, order_idx: usize) -> CGRect {
         let (row, col) = self.position_for(order_idx);
         let spacing = WORKSPACE_TILE_SPACING;
         let x = self.bounds.origin.x + spacing + (self.tile_size.width + spacing) * (col as f64);
@@ -529,83 +880,13 @@ impl WorkspaceGrid {
     }
 }
 
-#[derive(Clone, Copy)]
-enum WindowLayoutKind {
+#[derive(Clone, Copy, Debug)]
+pub enum WindowLayoutKind {
     PreserveOriginal,
     Exploded,
 }
 
-struct FadeState {
-    id: u64,
-}
-
 impl MissionControlOverlay {
-    fn gather_screen_metrics(
-        &self,
-    ) -> Option<(Vec<(ScreenInfo, f64, CGRect)>, CoordinateConverter)> {
-        let mut cache = ScreenCache::new(self.mtm);
-        let Some((screens, converter)) = cache.refresh() else {
-            return None;
-        };
-
-        let ns_screens = NSScreen::screens(self.mtm);
-        let mut metrics = Vec::new();
-        for screen in ns_screens.iter() {
-            if let Ok(screen_id) = screen.get_number()
-                && let Some(info) = screens.iter().find(|info| info.id == screen_id)
-            {
-                // Use raw display bounds for cursor hit-testing (menu bar/dock included).
-                let raw_bounds = CGDisplayBounds(screen_id.as_u32());
-                metrics.push((info.clone(), screen.backingScaleFactor(), raw_bounds));
-            }
-        }
-
-        if metrics.is_empty() {
-            None
-        } else {
-            Some((metrics, converter))
-        }
-    }
-
-    fn screen_under_cursor_with(
-        &self,
-        metrics: &[(ScreenInfo, f64, CGRect)],
-    ) -> Option<(ScreenInfo, f64)> {
-        if let Ok(loc) = current_cursor_location() {
-            return metrics
-                .iter()
-                .find(|(_, _, frame)| frame.contains(loc))
-                .map(|(screen, scale, _)| (screen.clone(), *scale));
-        }
-
-        None
-    }
-
-    fn active_space_metric_with(
-        &self,
-        metrics: &[(ScreenInfo, f64, CGRect)],
-    ) -> Option<(ScreenInfo, f64)> {
-        let active_space = get_active_space_number()?;
-        metrics
-            .iter()
-            .find(|(info, _, _)| info.space == Some(active_space))
-            .map(|(info, scale, _)| (info.clone(), *scale))
-    }
-
-    fn current_overlay_metric(
-        &self,
-        metrics: &[(ScreenInfo, f64, CGRect)],
-    ) -> Option<(ScreenInfo, f64)> {
-        let center = CGPoint::new(
-            self.frame.origin.x + self.frame.size.width / 2.0,
-            self.frame.origin.y + self.frame.size.height / 2.0,
-        );
-        metrics
-            .iter()
-            .find(|(_, _, frame)| frame.contains(center))
-            .map(|(info, scale, _)| (info.clone(), *scale))
-    }
-
     fn rect_contains_point(rect: CGRect, point: CGPoint) -> bool {
         point.x >= rect.origin.x
             && point.x <= rect.origin.x + rect.size.width
@@ -627,14 +908,16 @@ impl MissionControlOverlay {
 
     fn workspace_index_at_point(
         workspaces: &[WorkspaceData],
+        show_empty: bool,
+        grid_columns: Option<usize>,
         point: CGPoint,
         bounds: CGRect,
     ) -> Option<(usize, usize)> {
         if !Self::rect_contains_point(bounds, point) {
             return None;
         }
-        let visible = Self::visible_workspaces(workspaces);
-        let grid = WorkspaceGrid::new(visible.len(), bounds)?;
+        let visible = Self::visible_workspaces(workspaces, show_empty);
+        let grid = WorkspaceGrid::new(visible.len(), bounds, grid_columns)?;
         for (order_idx, (original_idx, _)) in visible.iter().enumerate() {
             let rect = grid.rect_for(order_idx);
             if Self::rect_contains_point(rect, point) {
@@ -665,7 +948,9 @@ impl MissionControlOverlay {
         None
     }
 
-    fn compute_exploded_layout(windows: &[WindowData], bounds: CGRect) -> Option<Vec<CGRect>> {
+    /// Lays the current workspace's windows out on a grid chosen to best match the
+    /// aspect ratio of `bounds`. Rects are returned in the order of `windows`.
+    pub fn compute_exploded_layout(windows: &[WindowData], bounds: CGRect) -> Option<Vec<CGRect>> {
         if windows.is_empty() {
             return None;
         }
@@ -779,7 +1064,7 @@ impl MissionControlOverlay {
         Some(rects)
     }
 
-    fn compute_window_rects(
+    pub fn compute_window_rects(
         windows: &[WindowData],
         bounds: CGRect,
         kind: WindowLayoutKind,
@@ -808,12 +1093,16 @@ impl MissionControlOverlay {
         visible: &[(usize, &WorkspaceData)],
         current: usize,
         direction: NavDirection,
+        grid_columns: Option<usize>,
     ) -> Option<usize> {
         if visible.is_empty() {
             return None;
         }
         let len = visible.len();
         let mut idx = current.min(len.saturating_sub(1));
+        if let Some(columns) = grid_columns {
+            return Some(grid_neighbor(idx, len, columns, direction.into()).unwrap_or(idx));
+        }
         let cols = workspace_column_count(len);
         let rows = if len > cols { 2 } else { 1 };
 
@@ -926,12 +1215,13 @@ impl MissionControlOverlay {
                 Some(MissionControlMode::AllWorkspaces(workspaces)),
                 Some(Selection::Workspace(idx)),
             ) => {
-                let visible = Self::visible_workspaces(workspaces);
+                let visible = Self::visible_workspaces(workspaces, state.show_empty);
                 if visible.is_empty() {
                     None
                 } else {
                     let idx = idx.min(visible.len().saturating_sub(1));
-                    Self::navigate_workspaces(&visible, idx, direction).map(Selection::Workspace)
+                    Self::navigate_workspaces(&visible, idx, direction, state.grid_columns)
+                        .map(Selection::Workspace)
                 }
             }
             (Some(MissionControlMode::CurrentWorkspace(windows)), Some(Selection::Window(idx))) => {
@@ -943,7 +1233,7 @@ impl MissionControlOverlay {
                 }
             }
             (Some(MissionControlMode::AllWorkspaces(workspaces)), None) => {
-                if Self::visible_workspaces(workspaces).is_empty() {
+                if Self::visible_workspaces(workspaces, state.show_empty).is_empty() {
                     None
                 } else {
                     Some(Selection::Workspace(0))
@@ -982,13 +1272,14 @@ impl MissionControlOverlay {
                 Some(MissionControlMode::AllWorkspaces(workspaces)),
                 Some(Selection::Workspace(idx)),
             ) => {
-                let visible = Self::visible_workspaces(workspaces);
+                let visible = Self::visible_workspaces(workspaces, state.show_empty);
                 if visible.is_empty() {
                     None
                 } else {
                     let len = visible.len();
                     let idx = idx.min(len.saturating_sub(1));
-                    Self::next_workspace_index(idx, len, forward).map(Selection::Workspace)
+                    Self::next_workspace_index(idx, len, forward, state.grid_columns)
+                        .map(Selection::Workspace)
                 }
             }
             (Some(MissionControlMode::CurrentWorkspace(windows)), Some(Selection::Window(idx))) => {
@@ -1006,7 +1297,7 @@ impl MissionControlOverlay {
                 }
             }
             (Some(MissionControlMode::AllWorkspaces(workspaces)), None) => {
-                let visible = Self::visible_workspaces(workspaces);
+                let visible = Self::visible_workspaces(workspaces, state.show_empty);
                 if visible.is_empty() {
                     None
                 } else {
@@ -1036,7 +1327,12 @@ impl MissionControlOverlay {
         false
     }
 
-    fn next_workspace_index(current_idx: usize, len: usize, forward: bool) -> Option<usize> {
+    fn next_workspace_index(
+        current_idx: usize,
+        len: usize,
+        forward: bool,
+        grid_columns: Option<usize>,
+    ) -> Option<usize> {
         if len == 0 {
             return None;
         }
@@ -1044,10 +1340,13 @@ impl MissionControlOverlay {
         let rows = if len > columns { 2 } else { 1 };
 
         let mut order: Vec<usize> = (0..len).collect();
-        order.sort_by_key(|&order_idx| {
-            let (row, col) = Self::workspace_grid_position(order_idx, rows);
-            (row, col)
-        });
+        // A configured grid is already in reading order.
+        if grid_columns.is_none() {
+            order.sort_by_key(|&order_idx| {
+                let (row, col) = Self::workspace_grid_position(order_idx, rows);
+                (row, col)
+            });
+        }
 
         let current_pos = order.iter().position(|&idx| idx == current_idx)?;
         let next_pos = if forward {
@@ -1078,7 +1377,7 @@ impl MissionControlOverlay {
                     Some(MissionControlMode::AllWorkspaces(workspaces)),
                     Some(Selection::Workspace(idx)),
                 ) => {
-                    let visible = Self::visible_workspaces(workspaces);
+                    let visible = Self::visible_workspaces(workspaces, state.show_empty);
                     if visible.is_empty() {
                         None
                     } else {
@@ -1115,11 +1414,23 @@ impl MissionControlOverlay {
         }
     }
 
-    fn visible_workspaces<'a>(workspaces: &'a [WorkspaceData]) -> Vec<(usize, &'a WorkspaceData)> {
+    /// A slim tile centred in the grid cell of an empty workspace.
+    fn placeholder_rect(cell: CGRect) -> CGRect {
+        let width = cell.size.width * PLACEHOLDER_WIDTH_FRACTION;
+        CGRect::new(
+            CGPoint::new(cell.origin.x + (cell.size.width - width) / 2.0, cell.origin.y),
+            CGSize::new(width, cell.size.height),
+        )
+    }
+
+    fn visible_workspaces<'a>(
+        workspaces: &'a [WorkspaceData],
+        show_empty: bool,
+    ) -> Vec<(usize, &'a WorkspaceData)> {
         workspaces
             .iter()
             .enumerate()
-            .filter(|(_, ws)| !ws.windows.is_empty() || ws.is_active)
+            .filter(|(_, ws)| workspace_is_shown(ws, show_empty))
             .collect()
     }
 
@@ -1131,8 +1442,12 @@ impl MissionControlOverlay {
         bounds: CGRect,
         selected: Option<usize>,
     ) {
-        let visible = Self::visible_workspaces(workspaces);
-        let Some(grid) = WorkspaceGrid::new(visible.len(), bounds) else {
+        let (show_empty, grid_columns) = {
+            let state = state.borrow();
+            (state.show_empty, state.grid_columns)
+        };
+        let visible = Self::visible_workspaces(workspaces, show_empty);
+        let Some(grid) = WorkspaceGrid::new(visible.len(), bounds, grid_columns) else {
             return;
         };
         let parent_layer = parent_layer;
@@ -1142,7 +1457,14 @@ impl MissionControlOverlay {
             for (order_idx, (original_idx, _)) in visible.iter().enumerate() {
                 autoreleasepool(|_| {
                     let ws = &workspaces[*original_idx];
-                    let rect = grid.rect_for(order_idx);
+                    // Only reachable with `show_empty`; the active workspace
+                    // always gets a full tile.
+                    let is_placeholder = ws.windows.is_empty() && !ws.is_active;
+                    let rect = if is_placeholder {
+                        Self::placeholder_rect(grid.rect_for(order_idx))
+                    } else {
+                        grid.rect_for(order_idx)
+                    };
                     visible_ids.insert(ws.id.clone());
                     let (ws_layer, label_layer) = {
                         let mut st = state.borrow_mut();
@@ -1152,7 +1474,7 @@ impl MissionControlOverlay {
                             .or_insert_with(|| {
                                 let lay = CALayer::layer();
                                 parent_layer.addSublayer(&lay);
-                                lay.setContentsScale(self.scale);
+                                lay.setContentsScale(self.host.scale());
                                 lay
                             })
                             .clone();
@@ -1162,43 +1484,96 @@ impl MissionControlOverlay {
                             .or_insert_with(|| {
                                 let tl = CATextLayer::layer();
                                 parent_layer.addSublayer(&tl);
-                                tl.setContentsScale(self.scale);
+                                tl.setContentsScale(self.host.scale());
                                 tl
                             })
                             .clone();
+                        (ws_layer, label_layer)
+                    };
+                    let theme = self.theme.borrow();
+                    let label = {
+                        let max_width = (rect.size.width - 2.0 * LABEL_PADDING).max(0.0);
+                        let direction = self.label_direction;
+                        let mut st = state.borrow_mut();
                         match st.workspace_label_strings.entry(ws.id.clone()) {
                             hash_map::Entry::Occupied(mut occ) => {
-                                if occ.get_mut().update(&ws.name) {
+                                let cache = occ.get_mut();
+                                if cache.update(&ws.name, max_width, &theme.font, direction) {
                                     unsafe {
-                                        occ.get().apply_to(&label_layer);
+                                        cache.apply_to(&label_layer);
                                     }
                                 }
+                                cache.fitted.clone()
                             }
                             hash_map::Entry::Vacant(vac) => {
-                                let cache = WorkspaceLabelText::new(&ws.name);
+                                let cache = WorkspaceLabelText::new(
+                                    &ws.name,
+                                    max_width,
+                                    &theme.font,
+                                    direction,
+                                );
                                 unsafe {
                                     cache.apply_to(&label_layer);
                                 }
-                                vac.insert(cache);
+                                vac.insert(cache).fitted.clone()
                             }
                         }
-                        (ws_layer, label_layer)
                     };
                     ws_layer.setFrame(rect);
                     ws_layer.setCornerRadius(6.0);
-                    ws_layer.setBackgroundColor(Some(&**WORKSPACE_BACKGROUND_COLOR));
+                    ws_layer.setBackgroundColor(Some(&theme.tile_background));
 
                     let is_selected = Some(order_idx) == selected;
                     if is_selected {
-                        ws_layer.setBorderColor(Some(&**SELECTED_BORDER_COLOR));
-
-                        ws_layer.setBorderWidth(3.0);
+                        ws_layer.setBorderColor(Some(&theme.accent));
+                        ws_layer.setBorderWidth(theme.selected_border_width);
                     } else {
-                        ws_layer.setBorderColor(Some(&**WORKSPACE_BORDER_COLOR));
-
-                        ws_layer.setBorderWidth(1.0);
+                        ws_layer.setBorderColor(Some(&theme.tile_border));
+                        ws_layer.setBorderWidth(theme.border_width);
                     }
+                    ws_layer.setOpacity(if is_placeholder && !is_selected {
+                        PLACEHOLDER_OPACITY
+                    } else {
+                        1.0
+                    });
                     ws_layer.setZPosition(-1.0);
+                    if is_placeholder {
+                        let plus = state
+                            .borrow_mut()
+                            .workspace_placeholder_layers
+                            .entry(ws.id.clone())
+                            .or_insert_with(|| {
+                                let tl = CATextLayer::layer();
+                                parent_layer.addSublayer(&tl);
+                                tl.setContentsScale(self.host.scale());
+                                tl.setAlignmentMode(unsafe { kCAAlignmentCenter });
+                                let text = CFString::from_str("+");
+                                let raw = text.as_ref() as *const AnyObject;
+                                unsafe {
+                                    tl.setString(Some(&*raw));
+                                }
+                                tl
+                            })
+                            .clone();
+                        let height = theme.font.line_height() * PLACEHOLDER_PLUS_SCALE;
+                        plus.setFrame(CGRect::new(
+                            CGPoint::new(
+                                rect.origin.x,
+                                rect.origin.y + (rect.size.height - height) / 2.0,
+                            ),
+                            CGSize::new(rect.size.width, height),
+                        ));
+                        unsafe {
+                            plus.setFont(Some(theme.font.as_cf_type()));
+                        }
+                        plus.setFontSize(theme.font.size() * PLACEHOLDER_PLUS_SCALE);
+                        plus.setForegroundColor(Some(&theme.label));
+                        plus.setZPosition(2.0);
+                    } else if let Some(plus) =
+                        state.borrow_mut().workspace_placeholder_layers.remove(&ws.id)
+                    {
+                        plus.removeFromSuperlayer();
+                    }
                     self.draw_windows_tile(
                         state,
                         parent_layer,
@@ -1207,18 +1582,30 @@ impl MissionControlOverlay {
                         None,
                         WindowLayoutKind::PreserveOriginal,
                     );
-                    let label_height = 18.0;
+                    // Right-to-left names hug the right edge of the tile.
+                    let (label_x, alignment) = match label.direction {
+                        TextDirection::LeftToRight => {
+                            (rect.origin.x + LABEL_PADDING, unsafe { kCAAlignmentLeft })
+                        }
+                        TextDirection::RightToLeft => (
+                            rect.origin.x + rect.size.width - LABEL_PADDING - label.width,
+                            unsafe { kCAAlignmentRight },
+                        ),
+                    };
                     let label_frame = CGRect::new(
-                        CGPoint::new(rect.origin.x + 6.0, rect.origin.y + 6.0),
-                        CGSize::new((rect.size.width - 12.0).max(10.0), label_height),
+                        CGPoint::new(label_x, rect.origin.y + LABEL_PADDING),
+                        CGSize::new(label.width, theme.font.line_height()),
                     );
                     label_layer.setFrame(label_frame);
-                    label_layer.setContentsScale(self.scale);
+                    label_layer.setContentsScale(self.host.scale());
                     label_layer.setMasksToBounds(false);
 
-                    label_layer.setFontSize(12.0);
-                    let fg = NSColor::labelColor();
-                    label_layer.setForegroundColor(Some(&fg.CGColor()));
+                    unsafe {
+                        label_layer.setFont(Some(theme.font.as_cf_type()));
+                    }
+                    label_layer.setFontSize(theme.font.size());
+                    label_layer.setAlignmentMode(alignment);
+                    label_layer.setForegroundColor(Some(&theme.label));
 
                     label_layer.setZPosition(2.0);
                 });
@@ -1244,6 +1631,14 @@ impl MissionControlOverlay {
                 }
             });
             st.workspace_label_strings.retain(|id, _| visible_ids.contains(id));
+            st.workspace_placeholder_layers.retain(|id, layer| {
+                if visible_ids.contains(id) {
+                    true
+                } else {
+                    layer.removeFromSuperlayer();
+                    false
+                }
+            });
         }
     }
 
@@ -1280,15 +1675,19 @@ impl MissionControlOverlay {
                             .or_insert_with(|| {
                                 let lay = CALayer::layer();
                                 parent_layer.addSublayer(&lay);
-                                lay.setContentsScale(self.scale);
+                                lay.setContentsScale(self.host.scale());
                                 lay
                             })
                             .clone();
-                        let style_changed = s
-                            .preview_layer_styles
-                            .entry(window.id)
-                            .or_insert_with(Default::default)
-                            .update_selected(is_selected);
+                        let style = s.preview_layer_styles.entry(window.id).or_default();
+                        let style_changed = style.update_selected(is_selected);
+                        style.decorate(
+                            &layer,
+                            rect.size,
+                            window,
+                            &self.theme.borrow(),
+                            self.host.scale(),
+                        );
                         let maybe_img_ptr = {
                             let cache = s.preview_cache.read();
                             cache
@@ -1311,11 +1710,12 @@ impl MissionControlOverlay {
                     layer.setFrame(rect);
                     layer.setMasksToBounds(true);
                     layer.setCornerRadius(4.0);
-                    layer.setContentsScale(self.scale);
+                    layer.setContentsScale(self.host.scale());
                     if style_changed {
                         if is_selected {
-                            layer.setBorderColor(Some(&**SELECTED_BORDER_COLOR));
-                            layer.setBorderWidth(3.0);
+                            let theme = self.theme.borrow();
+                            layer.setBorderColor(Some(&theme.accent));
+                            layer.setBorderWidth(theme.selected_border_width);
                             layer.setZPosition(1.0);
                         } else {
                             layer.setBorderColor(Some(&**WINDOW_BORDER_COLOR));
@@ -1326,17 +1726,7 @@ impl MissionControlOverlay {
                     }
 
                     if !had_image {
-                        let (tw, th) = if matches!(layout, WindowLayoutKind::Exploded) {
-                            (
-                                window.info.frame.size.width.max(1.0) as usize,
-                                window.info.frame.size.height.max(1.0) as usize,
-                            )
-                        } else {
-                            (
-                                (rect.size.width * 1.5).max(2.0) as usize,
-                                (rect.size.height * 1.5).max(2.0) as usize,
-                            )
-                        };
+                        let (tw, th) = Self::capture_target_size(window, rect, layout);
                         self.schedule_capture(state, window, tw, th);
                     }
                 });
@@ -1346,6 +1736,25 @@ impl MissionControlOverlay {
 
     fn draw_window_outline(_rect: CGRect, _is_selected: bool) {}
 
+    /// Pixel size to capture a preview at. Exploded tiles can be shown large, so
+    /// they get the full window; thumbnails get 1.5x their tile for sharpness.
+    pub fn capture_target_size(
+        window: &WindowData,
+        rect: CGRect,
+        layout: WindowLayoutKind,
+    ) -> (usize, usize) {
+        match layout {
+            WindowLayoutKind::Exploded => (
+                window.info.frame.size.width.max(1.0) as usize,
+                window.info.frame.size.height.max(1.0) as usize,
+            ),
+            WindowLayoutKind::PreserveOriginal => (
+                (rect.size.width * 1.5).max(2.0) as usize,
+                (rect.size.height * 1.5).max(2.0) as usize,
+            ),
+        }
+    }
+
     fn schedule_capture(
         &self,
         state: &RefCell<MissionControlState>,
@@ -1380,7 +1789,7 @@ impl MissionControlOverlay {
             },
             cache: st.preview_cache.clone(),
             generation,
-            overlay_ptr_bits: self as *const _ as usize,
+            refresh: self.refresh_handle(),
         };
         let _ = CAPTURE_POOL.sender.send(job);
     }
@@ -1441,10 +1850,8 @@ impl MissionControlOverlay {
 
         let generation = CURRENT_GENERATION.fetch_add(1, Ordering::AcqRel) + 1;
 
-        let (preview_cache, overlay_ptr_bits) = {
-            let st = state_cell.borrow();
-            (st.preview_cache.clone(), self as *const _ as usize)
-        };
+        let preview_cache = state_cell.borrow().preview_cache.clone();
+        let refresh = self.refresh_handle();
 
         let sync_limit = SYNC_PREWARM_LIMIT.min(tasks.len());
         let async_tasks = tasks.split_off(sync_limit);
@@ -1483,11 +1890,7 @@ impl MissionControlOverlay {
                     if let Ok(mut st) = state_cell.try_borrow_mut() {
                         st.ready_previews.insert(task.window_id);
                     }
-                    if let Some(overlay) =
-                        unsafe { (overlay_ptr_bits as *const MissionControlOverlay).as_ref() }
-                    {
-                        overlay.request_refresh();
-                    }
+                    refresh.request();
                 }
                 None => {
                     let mut set = IN_FLIGHT.lock();
@@ -1514,7 +1917,7 @@ impl MissionControlOverlay {
                 task,
                 cache: preview_cache.clone(),
                 generation,
-                overlay_ptr_bits,
+                refresh: refresh.clone(),
             };
             if CAPTURE_POOL.sender.send(job).is_err() {
                 break;
@@ -1577,7 +1980,7 @@ impl MissionControlOverlay {
             (mode, state.selected_workspace(), state.selected_window())
         };
 
-        parent_layer.setBackgroundColor(Some(&**OVERLAY_BACKGROUND_COLOR));
+        parent_layer.setBackgroundColor(Some(&self.theme.borrow().dim));
 
         let content_bounds = Self::content_bounds(bounds);
         match mode {
@@ -1604,95 +2007,77 @@ impl MissionControlOverlay {
     }
 }
 
+/// The overlay is main-thread only and shared through an [`Rc`]. Work queued
+/// from capture workers carries the overlay's id rather than a pointer and
+/// looks it up in `LIVE_OVERLAYS`, so it becomes a no-op once the owner drops
+/// the overlay.
 pub struct MissionControlOverlay {
-    cgs_window: CgsWindow,
-    root_layer: Retained<CALayer>,
-    frame: CGRect,
-    mtm: MainThreadMarker,
-    key_tap: RefCell<Option<crate::sys::event_tap::EventTap>>,
-    fade_enabled: bool,
-    fade_duration_ms: f64,
+    id: u64,
+    host: OverlayHost,
+    fade_enabled: Cell<bool>,
+    fade_duration_ms: Cell<f64>,
+    theme: RefCell<OverlayTheme>,
+    /// Direction for labels that have none of their own, from the system language.
+    label_direction: TextDirection,
     has_shown: RefCell<bool>,
     state: RefCell<MissionControlState>,
-    fade_state: RefCell<Option<FadeState>>,
-    fade_counter: AtomicU64,
     pending_hide: RefCell<bool>,
-    refresh_pending: AtomicBool,
-    scale: f64,
-    coordinate_converter: CoordinateConverter,
+    refresh_pending: Arc<AtomicBool>,
 }
 
 impl MissionControlOverlay {
-    pub fn new(config: Config, mtm: MainThreadMarker, frame: CGRect, scale: f64) -> Self {
-        let mut frame = frame;
-        let mut scale = scale;
-        let mut coordinate_converter = CoordinateConverter::default();
-
-        let mut cache = ScreenCache::new(mtm);
-        if let Some((screens, converter)) = cache.refresh() {
-            coordinate_converter = converter;
-
-            let active_space = get_active_space_number();
-            if let Some(target) = screens
-                .iter()
-                .find(|screen| screen.space == active_space)
-                .or_else(|| screens.first())
-            {
-                frame = CGDisplayBounds(target.id.as_u32());
-                scale = NSScreen::screens(mtm)
-                    .iter()
-                    .find_map(|ns| {
-                        let id = ns.get_number().ok()?;
-                        if id == target.id {
-                            Some(ns.backingScaleFactor())
-                        } else {
-                            None
-                        }
-                    })
-                    .unwrap_or(scale);
-            }
-        }
-
-        let root_layer = CALayer::layer();
-        root_layer.setGeometryFlipped(true);
-
-        root_layer.setFrame(CGRect::new(CGPoint::new(0.0, 0.0), frame.size));
-        root_layer.setContentsScale(scale);
-
-        let cgs_window = CgsWindow::new(frame).expect("failed to create CGS window");
-        let _ = cgs_window.set_resolution(scale);
-        let _ = cgs_window.set_opacity(false);
-        let _ = cgs_window.set_alpha(1.0);
-        let _ = cgs_window.set_level(NSPopUpMenuWindowLevel as i32);
-        let _ = cgs_window.set_blur(30, None);
-
-        Self {
-            cgs_window,
-            root_layer,
-            frame,
-            mtm,
-            key_tap: RefCell::new(None),
-            fade_enabled: config.settings.ui.mission_control.fade_enabled,
-            fade_duration_ms: config.settings.ui.mission_control.fade_duration_ms,
+    pub fn new(
+        config: Config,
+        mtm: MainThreadMarker,
+        frame: CGRect,
+        scale: f64,
+        appearance: Appearance,
+    ) -> Result<Rc<Self>, CgsWindowError> {
+        let metrics = screen_metrics(mtm);
+        let active_space = get_active_space_number();
+        let (frame, scale) = metrics
+            .iter()
+            .find(|metric| metric.screen.space == active_space)
+            .or_else(|| metrics.first())
+            .map_or((frame, scale), |metric| (metric.bounds, metric.scale));
+
+        let theme = OverlayTheme::new(&config.settings.ui.theme, appearance);
+        let style = OverlayWindowStyle {
+            blur_radius: Some(theme.blur_radius),
+            ..Default::default()
+        };
+        let host = OverlayHost::new(mtm, frame, scale, style)?;
+
+        let mut state = MissionControlState::default();
+        state.remember_selection =
+            config.settings.ui.mission_control.selection == MissionControlSelection::Remember;
+        state.hide_minimized = config.settings.ui.mission_control.hide_minimized;
+        state.show_empty = config.settings.ui.mission_control.show_empty_workspaces;
+        state.grid_columns = config.virtual_workspaces.grid_columns;
+
+        let id = NEXT_OVERLAY_ID.fetch_add(1, Ordering::Relaxed);
+        let overlay = Rc::new(Self {
+            id,
+            host,
+            fade_enabled: Cell::new(config.settings.ui.mission_control.fade_enabled),
+            fade_duration_ms: Cell::new(config.settings.ui.mission_control.fade_duration_ms),
+            theme: RefCell::new(theme),
+            label_direction: interface_direction(mtm),
             has_shown: RefCell::new(false),
-            state: RefCell::new(MissionControlState::default()),
-            fade_state: RefCell::new(None),
-            fade_counter: AtomicU64::new(0),
+            state: RefCell::new(state),
             pending_hide: RefCell::new(false),
-            refresh_pending: AtomicBool::new(false),
-            scale,
-            coordinate_converter,
-        }
+            refresh_pending: Arc::new(AtomicBool::new(false)),
+        });
+        let content: Weak<dyn OverlayContent> = Rc::downgrade(&overlay);
+        overlay.host.attach(content);
+        LIVE_OVERLAYS.with(|live| live.borrow_mut().insert(id, Rc::downgrade(&overlay)));
+        Ok(overlay)
     }
 
-    fn request_refresh(&self) {
-        if !self.refresh_pending.swap(true, Ordering::AcqRel) {
-            let ptr = self as *const _ as usize;
-            queue::main().after_f(
-                Time::new_after(Time::NOW, 8000000),
-                ptr as *mut c_void,
-                refresh_coalesced_cb,
-            );
+    fn refresh_handle(&self) -> RefreshHandle {
+        RefreshHandle {
+            overlay_id: self.id,
+            pending: self.refresh_pending.clone(),
         }
     }
 
@@ -1700,111 +2085,93 @@ impl MissionControlOverlay {
         self.state.borrow_mut().on_action = Some(f);
     }
 
-    pub fn set_fade_enabled(&mut self, enabled: bool) { self.fade_enabled = enabled; }
+    pub fn set_fade_enabled(&self, enabled: bool) { self.fade_enabled.set(enabled); }
 
-    pub fn set_fade_duration_ms(&mut self, ms: f64) { self.fade_duration_ms = ms.max(0.0); }
-
-    fn current_screen_metrics(&self) -> (ScreenInfo, f64, CoordinateConverter) {
-        if let Some((metrics, converter)) = self.gather_screen_metrics() {
-            if let Some(cursor_metric) = self.screen_under_cursor_with(&metrics) {
-                let (screen, scale) = cursor_metric;
-                return (screen, scale, converter);
-            }
+    pub fn set_fade_duration_ms(&self, ms: f64) { self.fade_duration_ms.set(ms.max(0.0)); }
 
-            if let Some(space_metric) = self.active_space_metric_with(&metrics) {
-                let (screen, scale) = space_metric;
-                return (screen, scale, converter);
-            }
+    pub fn set_remember_selection(&self, remember: bool) {
+        self.state.borrow_mut().remember_selection = remember;
+    }
 
-            if let Some(overlay_metric) = self.current_overlay_metric(&metrics) {
-                let (screen, scale) = overlay_metric;
-                return (screen, scale, converter);
-            }
+    /// Leaves minimized windows and windows of hidden apps out of the overlay.
+    pub fn set_hide_minimized(&self, hide: bool) {
+        let changed = self.state.borrow_mut().set_hide_minimized(hide);
+        if changed && *self.has_shown.borrow() {
+            self.draw_and_present();
+        }
+    }
 
-            if let Some((screen, scale, _)) = metrics.first() {
-                return (screen.clone(), *scale, converter);
-            }
+    pub fn set_show_empty_workspaces(&self, show: bool) {
+        let changed = self.state.borrow_mut().set_show_empty(show);
+        if changed && *self.has_shown.borrow() {
+            self.draw_and_present();
         }
+    }
 
-        (
-            ScreenInfo {
-                id: ScreenId::new(0),
-                frame: self.frame,
-                display_uuid: String::new(),
-                name: None,
-                space: None,
-            },
-            self.scale,
-            self.coordinate_converter,
-        )
+    pub fn set_grid_columns(&self, columns: Option<usize>) {
+        let changed = std::mem::replace(&mut self.state.borrow_mut().grid_columns, columns);
+        if changed != columns && *self.has_shown.borrow() {
+            self.draw_and_present();
+        }
     }
 
-    pub fn update(&self, mode: MissionControlMode) {
-        self.stop_active_fade();
-        *self.pending_hide.borrow_mut() = false;
+    /// What was last selected, to hand to the next overlay when this one is dropped.
+    pub fn selection_memory(&self) -> SelectionMemory { self.state.borrow().memory.clone() }
 
-        {
-            let (screen, scale, converter) = self.current_screen_metrics();
-            let screen_id = screen.id.as_u32();
-            let new_frame = if screen_id == 0 {
-                self.frame
-            } else {
-                CGDisplayBounds(screen_id)
-            };
-            let new_scale = scale;
-
-            let frame_changed = new_frame.origin.x != self.frame.origin.x
-                || new_frame.origin.y != self.frame.origin.y
-                || new_frame.size.width != self.frame.size.width
-                || new_frame.size.height != self.frame.size.height;
-            let scale_changed = (new_scale - self.scale).abs() > f64::EPSILON;
-
-            if frame_changed || scale_changed {
-                let _ = self.cgs_window.set_shape(new_frame);
-                let _ = self.cgs_window.set_resolution(new_scale);
-
-                unsafe {
-                    let me = self as *const _ as *mut MissionControlOverlay;
-                    (*me).frame = new_frame;
-                    (*me).scale = new_scale;
-                }
+    pub fn restore_selection_memory(&self, memory: SelectionMemory) {
+        self.state.borrow_mut().memory = memory;
+    }
 
-                self.root_layer.setFrame(CGRect::new(CGPoint::new(0.0, 0.0), self.frame.size));
-                self.root_layer.setContentsScale(self.scale);
-            }
-            unsafe {
-                let me = self as *const _ as *mut MissionControlOverlay;
-                (*me).coordinate_converter = converter;
+    /// Restyles the overlay, redrawing it straight away if it is on screen.
+    pub fn set_theme(&self, theme: OverlayTheme) {
+        self.host.set_blur_radius(theme.blur_radius);
+        *self.theme.borrow_mut() = theme;
+        {
+            let mut st = self.state.borrow_mut();
+            // Preview borders are only restyled when their selection changes.
+            for style in st.preview_layer_styles.values_mut() {
+                style.reset();
             }
+            // Labels were measured in the old font.
+            st.workspace_label_strings.clear();
+        }
+        if *self.has_shown.borrow() {
+            self.draw_and_present();
         }
+    }
+
+    pub fn update(&self, mode: MissionControlMode) {
+        self.host.stop_fade();
+        *self.pending_hide.borrow_mut() = false;
+        self.host.move_to_preferred_screen();
 
         {
             let mut st = self.state.borrow_mut();
             st.set_mode(mode.clone());
 
-            st.render_root = Some(self.root_layer.clone());
-            st.render_window_id = Some(self.cgs_window.id());
-            st.render_size = Some(self.frame.size);
+            st.render_root = Some(self.host.root_layer().clone());
+            st.render_window_id = Some(self.host.window_id());
+            st.render_size = Some(self.host.frame().size);
 
             st.suppress_live_present = false;
         }
         self.prewarm_previews();
 
-        if self.fade_enabled && !*self.has_shown.borrow() {
-            let _ = self.cgs_window.set_alpha(0.0);
+        if self.fade_enabled.get() && !*self.has_shown.borrow() {
+            self.host.set_alpha(0.0);
         } else {
-            let _ = self.cgs_window.set_alpha(1.0);
+            self.host.set_alpha(1.0);
         }
-        let _ = self.cgs_window.order_above(None);
+        let _ = self.host.order_in();
 
-        let app = NSApplication::sharedApplication(self.mtm);
+        let app = NSApplication::sharedApplication(self.host.mtm());
         let _ = app.activate();
         self.ensure_key_tap();
 
         self.draw_and_present();
 
-        if self.fade_enabled && !*self.has_shown.borrow() {
-            self.fade_in();
+        if self.fade_enabled.get() && !*self.has_shown.borrow() {
+            self.host.fade(0.0, 1.0, self.fade_duration_ms.get());
         }
         *self.has_shown.borrow_mut() = true;
     }
@@ -1817,9 +2184,9 @@ impl MissionControlOverlay {
             prev
         };
 
-        if self.fade_enabled && was_shown {
+        if self.fade_enabled.get() && was_shown {
             *self.pending_hide.borrow_mut() = true;
-            if !self.fade_out() {
+            if !self.host.fade(1.0, 0.0, self.fade_duration_ms.get()) {
                 self.finalize_hide();
             }
         } else {
@@ -1829,16 +2196,16 @@ impl MissionControlOverlay {
 
     fn finalize_hide(&self) {
         objc2::rc::autoreleasepool(|_| {
-            self.stop_active_fade();
-            self.key_tap.borrow_mut().take();
+            self.host.stop_fade();
+            self.host.release_input();
 
             {
                 let mut s = self.state.borrow_mut();
                 s.purge();
             }
 
-            let _ = self.cgs_window.order_out();
-            let _ = self.cgs_window.set_alpha(1.0);
+            let _ = self.host.order_out();
+            self.host.set_alpha(1.0);
             CATransaction::flush();
 
             *self.has_shown.borrow_mut() = false;
@@ -1846,89 +2213,6 @@ impl MissionControlOverlay {
         });
     }
 
-    fn fade_in(&self) {
-        self.stop_active_fade();
-        let duration_ms = self.fade_duration_ms.max(0.0);
-        if duration_ms <= 0.0 {
-            let _ = self.cgs_window.set_alpha(1.0);
-            return;
-        }
-
-        let fade_id = self.fade_counter.fetch_add(1, Ordering::AcqRel) + 1;
-        let overlay_ptr_bits = self as *const MissionControlOverlay as usize;
-
-        CATransaction::begin();
-        CATransaction::setAnimationDuration(duration_ms / 1000.0);
-        self.root_layer.setOpacity(0.0);
-        self.root_layer.setOpacity(1.0);
-
-        CATransaction::commit();
-
-        schedule_fade_completion(overlay_ptr_bits, fade_id, 1.0f32);
-
-        self.fade_state.borrow_mut().replace(FadeState { id: fade_id });
-    }
-
-    fn fade_out(&self) -> bool {
-        self.stop_active_fade();
-        let duration_ms = self.fade_duration_ms.max(0.0);
-        if duration_ms <= 0.0 {
-            let _ = self.cgs_window.set_alpha(0.0);
-            return false;
-        }
-
-        let fade_id = self.fade_counter.fetch_add(1, Ordering::AcqRel) + 1;
-        let overlay_ptr_bits = self as *const MissionControlOverlay as usize;
-
-        CATransaction::begin();
-        CATransaction::setAnimationDuration(duration_ms / 1000.0);
-
-        self.root_layer.setOpacity(1.0);
-        self.root_layer.setOpacity(0.0);
-
-        CATransaction::commit();
-
-        schedule_fade_completion(overlay_ptr_bits, fade_id, 0.0f32);
-
-        self.fade_state.borrow_mut().replace(FadeState { id: fade_id });
-        true
-    }
-
-    fn stop_active_fade(&self) {
-        self.root_layer.removeAllAnimations();
-        self.fade_state.borrow_mut().take();
-    }
-
-    fn finish_fade(&self, fade_id: u64, final_alpha: f32) {
-        match self.fade_state.try_borrow_mut() {
-            Ok(mut slot) => {
-                let matches = slot.as_ref().map_or(false, |state| state.id == fade_id);
-                if !matches {
-                    return;
-                }
-                slot.take();
-                drop(slot);
-            }
-            Err(_) => {
-                let overlay_ptr_bits = self as *const MissionControlOverlay as usize;
-                schedule_fade_completion(overlay_ptr_bits, fade_id, final_alpha);
-                return;
-            }
-        }
-
-        let _ = self.cgs_window.set_alpha(final_alpha);
-
-        let should_finalize = if final_alpha <= 0.0 {
-            *self.pending_hide.borrow()
-        } else {
-            false
-        };
-
-        if should_finalize {
-            self.finalize_hide();
-        }
-    }
-
     pub fn refresh_active_workspace(&self, active_workspace: Option<VirtualWorkspaceId>) {
         let active_id = active_workspace.map(|ws| format!("{:?}", ws));
         let mut state = match self.state.try_borrow_mut() {
@@ -1942,17 +2226,16 @@ impl MissionControlOverlay {
     }
 
     fn draw_and_present(&self) {
+        let bounds = self.host.bounds();
+        let root_layer = self.host.root_layer();
         with_disabled_actions(|| {
-            self.root_layer.setFrame(CGRect::new(CGPoint::new(0.0, 0.0), self.frame.size));
-            self.root_layer.setGeometryFlipped(true);
+            root_layer.setFrame(bounds);
+            root_layer.setGeometryFlipped(true);
 
-            self.draw_contents_into_layer(
-                CGRect::new(CGPoint::new(0.0, 0.0), self.frame.size),
-                &self.root_layer,
-            );
+            self.draw_contents_into_layer(bounds, root_layer);
         });
 
-        render_layer_to_cgs_window(self.cgs_window.id(), self.frame.size, &self.root_layer);
+        self.host.present();
     }
 
     fn emit_action(&self, action: MissionControlAction) {
@@ -2017,6 +2300,18 @@ impl MissionControlOverlay {
                 self.activate_selection_action();
                 true
             }
+            // M
+            46 => {
+                let hide = !self.state.borrow().hide_minimized;
+                self.set_hide_minimized(hide);
+                true
+            }
+            // E
+            14 => {
+                let show = !self.state.borrow().show_empty;
+                self.set_show_empty_workspaces(show);
+                true
+            }
             48 => {
                 let forward = !flags.contains(CGEventFlags::MaskShift);
                 if self.cycle_selection(forward) {
@@ -2030,9 +2325,7 @@ impl MissionControlOverlay {
     }
 
     fn handle_click_global(&self, g_pt: CGPoint) {
-        let lx = g_pt.x - self.frame.origin.x;
-        let ly = g_pt.y - self.frame.origin.y;
-        let pt = CGPoint::new(lx, ly);
+        let pt = self.host.to_local(g_pt);
 
         let mut state = match self.state.try_borrow_mut() {
             Ok(s) => s,
@@ -2042,15 +2335,33 @@ impl MissionControlOverlay {
             Some(m) => m,
             None => return,
         };
-        let content_bounds = Self::content_bounds(CGRect::new(
-            CGPoint::new(0.0, 0.0),
-            CGSize::new(self.frame.size.width, self.frame.size.height),
-        ));
+        let content_bounds = Self::content_bounds(self.host.bounds());
+        let show_empty = state.show_empty;
+        let grid_columns = state.grid_columns;
 
         let new_sel = match mode {
             MissionControlMode::AllWorkspaces(workspaces) => {
-                Self::workspace_index_at_point(workspaces, pt, content_bounds)
-                    .map(|(order_idx, _)| Selection::Workspace(order_idx))
+                // Workspaces are switched to on release, so the press can
+                // become a drag instead.
+                if let Some((order_idx, original_idx)) = Self::workspace_index_at_point(
+                    workspaces,
+                    show_empty,
+                    grid_columns,
+                    pt,
+                    content_bounds,
+                ) {
+                    state.set_selection(Selection::Workspace(order_idx));
+                    state.drag = Some(WorkspaceDrag {
+                        origin: original_idx,
+                        current: original_idx,
+                        start: pt,
+                        dragging: false,
+                    });
+                    drop(state);
+                    self.draw_and_present();
+                    return;
+                }
+                None
             }
             MissionControlMode::CurrentWorkspace(windows) => {
                 Self::window_at_point(windows, pt, content_bounds, WindowLayoutKind::Exploded)
@@ -2073,9 +2384,7 @@ impl MissionControlOverlay {
     }
 
     fn handle_move_global(&self, g_pt: CGPoint) {
-        let lx = g_pt.x - self.frame.origin.x;
-        let ly = g_pt.y - self.frame.origin.y;
-        let pt = CGPoint::new(lx, ly);
+        let pt = self.host.to_local(g_pt);
 
         let mut state = match self.state.try_borrow_mut() {
             Ok(s) => s,
@@ -2085,16 +2394,19 @@ impl MissionControlOverlay {
             Some(m) => m,
             None => return,
         };
-        let content_bounds = Self::content_bounds(CGRect::new(
-            CGPoint::new(0.0, 0.0),
-            CGSize::new(self.frame.size.width, self.frame.size.height),
-        ));
+        let content_bounds = Self::content_bounds(self.host.bounds());
+        let show_empty = state.show_empty;
+        let grid_columns = state.grid_columns;
 
         let new_sel = match mode {
-            MissionControlMode::AllWorkspaces(workspaces) => {
-                Self::workspace_index_at_point(workspaces, pt, content_bounds)
-                    .map(|(order_idx, _)| Selection::Workspace(order_idx))
-            }
+            MissionControlMode::AllWorkspaces(workspaces) => Self::workspace_index_at_point(
+                workspaces,
+                show_empty,
+                grid_columns,
+                pt,
+                content_bounds,
+            )
+            .map(|(order_idx, _)| Selection::Workspace(order_idx)),
             MissionControlMode::CurrentWorkspace(windows) => {
                 Self::window_at_point(windows, pt, content_bounds, WindowLayoutKind::Exploded)
                     .map(|(order_idx, _)| Selection::Window(order_idx))
@@ -2110,115 +2422,106 @@ impl MissionControlOverlay {
         }
     }
 
-    fn ensure_key_tap(&self) {
-        if self.key_tap.borrow().is_some() {
+    /// Reorders the pressed workspace tile to whichever tile the pointer is over.
+    fn handle_drag_global(&self, g_pt: CGPoint) {
+        let pt = self.host.to_local(g_pt);
+
+        let mut state = match self.state.try_borrow_mut() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+        let Some(mut drag) = state.drag else {
+            return;
+        };
+        if !drag.dragging && (pt.x - drag.start.x).abs() < WORKSPACE_DRAG_THRESHOLD {
             return;
         }
+        drag.dragging = true;
+        state.drag = Some(drag);
 
-        #[repr(C)]
-        struct KeyCtx {
-            overlay: *const MissionControlOverlay,
-            consumes: bool,
+        let Some(MissionControlMode::AllWorkspaces(workspaces)) = state.mode() else {
+            return;
+        };
+        let content_bounds = Self::content_bounds(self.host.bounds());
+        let Some((_, target)) = Self::workspace_index_at_point(
+            workspaces,
+            state.show_empty,
+            state.grid_columns,
+            pt,
+            content_bounds,
+        ) else {
+            return;
+        };
+        if state.move_workspace(drag.current, target) {
+            state.drag = Some(WorkspaceDrag { current: target, ..drag });
+            drop(state);
+            self.draw_and_present();
         }
+    }
 
-        unsafe fn drop_ctx(ptr: *mut c_void) {
-            unsafe {
-                drop(Box::from_raw(ptr as *mut KeyCtx));
-            }
-        }
-
-        unsafe extern "C-unwind" fn key_callback(
-            _proxy: CGEventTapProxy,
-            etype: CGEventType,
-            event: core::ptr::NonNull<CGEvent>,
-            user_info: *mut c_void,
-        ) -> *mut CGEvent {
-            let ctx = unsafe { &*(user_info as *const KeyCtx) };
-            let mut handled = false;
-            if let Some(overlay) = unsafe { ctx.overlay.as_ref() } {
-                match etype {
-                    CGEventType::KeyDown => {
-                        let keycode = unsafe {
-                            CGEvent::integer_value_field(
-                                Some(event.as_ref()),
-                                CGEventField::KeyboardEventKeycode,
-                            ) as u16
-                        };
-                        let flags = unsafe { CGEvent::flags(Some(event.as_ref())) };
-                        handled = overlay.handle_keycode(keycode, flags);
-                    }
-                    CGEventType::LeftMouseDown => {
-                        let loc = unsafe { CGEvent::location(Some(event.as_ref())) };
-                        overlay.handle_click_global(loc);
-                        handled = true;
-                    }
-                    CGEventType::LeftMouseUp => {
-                        handled = true;
-                    }
-                    CGEventType::MouseMoved => {
-                        let loc = unsafe { CGEvent::location(Some(event.as_ref())) };
-                        overlay.handle_move_global(loc);
-                        handled = true;
-                    }
-                    _ => {}
-                }
+    fn handle_release_global(&self) {
+        let drag = match self.state.try_borrow_mut() {
+            Ok(mut state) => state.drag.take(),
+            Err(_) => return,
+        };
+        match drag {
+            Some(drag) if !drag.dragging => self.activate_selection_action(),
+            Some(drag) if drag.current != drag.origin => {
+                self.emit_action(MissionControlAction::MoveWorkspace {
+                    from: drag.origin,
+                    to: drag.current,
+                });
             }
-            if handled && ctx.consumes {
-                core::ptr::null_mut()
-            } else {
-                event.as_ptr()
-            }
-        }
-
-        let mask = (1u64 << CGEventType::KeyDown.0 as u64)
-            | (1u64 << CGEventType::LeftMouseDown.0 as u64)
-            | (1u64 << CGEventType::LeftMouseUp.0 as u64)
-            | (1u64 << CGEventType::MouseMoved.0 as u64);
-
-        let overlay_ptr = self as *const _;
-
-        let tap = unsafe {
-            let ctx_ptr = Box::into_raw(Box::new(KeyCtx {
-                overlay: overlay_ptr,
-                consumes: true,
-            })) as *mut c_void;
-            match crate::sys::event_tap::EventTap::new_with_options(
-                CGEventTapOptions::Default,
-                mask,
-                Some(key_callback),
-                ctx_ptr,
-                Some(drop_ctx),
-            ) {
-                Some(tap) => Some(tap),
-                None => {
-                    drop_ctx(ctx_ptr);
-                    let ctx_ptr = Box::into_raw(Box::new(KeyCtx {
-                        overlay: overlay_ptr,
-                        consumes: false,
-                    })) as *mut c_void;
-                    match crate::sys::event_tap::EventTap::new_listen_only(
-                        mask,
-                        Some(key_callback),
-                        ctx_ptr,
-                        Some(drop_ctx),
-                    ) {
-                        Some(tap) => {
-                            info!(
-                                "Falling back to listen-only event tap; Mission Control overlay input will pass through"
-                            );
-                            Some(tap)
-                        }
-                        None => {
-                            drop_ctx(ctx_ptr);
-                            None
-                        }
-                    }
-                }
+            _ => {}
+        }
+    }
+
+    fn ensure_key_tap(&self) {
+        let events = [
+            CGEventType::KeyDown,
+            CGEventType::LeftMouseDown,
+            CGEventType::LeftMouseUp,
+            CGEventType::LeftMouseDragged,
+            CGEventType::MouseMoved,
+        ];
+        let _ = self.host.acquire_input(&events, true);
+    }
+}
+
+impl OverlayContent for MissionControlOverlay {
+    fn host(&self) -> &OverlayHost { &self.host }
+
+    fn handle_input(&self, input: OverlayInput) -> bool {
+        match input {
+            OverlayInput::KeyDown { keycode, flags } => self.handle_keycode(keycode, flags),
+            OverlayInput::MouseDown(point) => {
+                self.handle_click_global(point);
+                true
             }
-        };
+            OverlayInput::MouseUp(_) => {
+                self.handle_release_global();
+                true
+            }
+            OverlayInput::MouseMoved(point) => {
+                self.handle_move_global(point);
+                true
+            }
+            OverlayInput::MouseDragged(point) => {
+                self.handle_drag_global(point);
+                true
+            }
+        }
+    }
 
-        if let Some(t) = tap {
-            self.key_tap.borrow_mut().replace(t);
+    fn fade_finished(&self, final_alpha: f32) {
+        if final_alpha <= 0.0 && *self.pending_hide.borrow() {
+            self.finalize_hide();
         }
     }
 }
+
+impl Drop for MissionControlOverlay {
+    fn drop(&mut self) {
+        let _ = LIVE_OVERLAYS.try_with(|live| live.borrow_mut().remove(&self.id));
+    }
+}
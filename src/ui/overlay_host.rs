@@ -0,0 +1,467 @@
+//! Window, input and animation plumbing shared by the full-screen overlays.
+//!
+//! An [`OverlayHost`] owns the CGS window and root layer an overlay draws into,
+//! follows the overlay between displays, acquires the event tap that feeds it
+//! input (downgrading to a listen-only tap when allowed), runs fades, and
+//! presents the layer tree to the window server. Overlays such as Mission
+//! Control and region selection only supply content: they draw into
+//! [`OverlayHost::root_layer`] and implement [`OverlayContent`].
+
+use core::ffi::c_void;
+use std::cell::{Cell, RefCell};
+use std::rc::Weak;
+use std::time::{Duration, Instant};
+
+use dispatchr::queue;
+use dispatchr::time::Time;
+use objc2::rc::Retained;
+use objc2_app_kit::{NSPopUpMenuWindowLevel, NSScreen};
+use objc2_core_foundation::{CGPoint, CGRect};
+use objc2_core_graphics::{
+    CGDisplayBounds, CGEvent, CGEventField, CGEventFlags, CGEventTapOptions, CGEventTapProxy,
+    CGEventType,
+};
+use objc2_foundation::MainThreadMarker;
+use objc2_quartz_core::{CALayer, CATransaction};
+use tracing::{info, warn};
+
+use crate::actor::kill_switch;
+use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
+use crate::sys::dispatch::DispatchExt;
+use crate::sys::event::current_cursor_location;
+use crate::sys::event_tap::EventTap;
+use crate::sys::geometry::CGRectExt;
+use crate::sys::screen::{NSScreenExt, ScreenCache, ScreenInfo, get_active_space_number};
+use crate::ui::common::render_layer_to_cgs_window;
+
+/// Input delivered to an overlay by its host's event tap, in global coordinates.
+#[derive(Clone, Copy, Debug)]
+pub enum OverlayInput {
+    KeyDown { keycode: u16, flags: CGEventFlags },
+    MouseDown(CGPoint),
+    MouseDragged(CGPoint),
+    MouseUp(CGPoint),
+    MouseMoved(CGPoint),
+}
+
+impl OverlayInput {
+    fn from_event(etype: CGEventType, event: &CGEvent) -> Option<Self> {
+        let location = || CGEvent::location(Some(event));
+        Some(match etype {
+            CGEventType::KeyDown => OverlayInput::KeyDown {
+                keycode: CGEvent::integer_value_field(
+                    Some(event),
+                    CGEventField::KeyboardEventKeycode,
+                ) as u16,
+                flags: CGEvent::flags(Some(event)),
+            },
+            CGEventType::LeftMouseDown => OverlayInput::MouseDown(location()),
+            CGEventType::LeftMouseDragged => OverlayInput::MouseDragged(location()),
+            CGEventType::LeftMouseUp => OverlayInput::MouseUp(location()),
+            CGEventType::MouseMoved => OverlayInput::MouseMoved(location()),
+            _ => return None,
+        })
+    }
+}
+
+/// Content drawn by an [`OverlayHost`]. All methods run on the main thread.
+pub trait OverlayContent {
+    fn host(&self) -> &OverlayHost;
+
+    /// Returns whether `input` was handled. Handled input is swallowed unless
+    /// the host had to fall back to a listen-only tap.
+    fn handle_input(&self, input: OverlayInput) -> bool;
+
+    /// Called when a fade started with [`OverlayHost::fade`] runs to completion.
+    fn fade_finished(&self, _final_alpha: f32) {}
+}
+
+/// How the host's event tap ended up being installed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputMode {
+    /// Input reaches the overlay and handled events stop there.
+    Exclusive,
+    /// Input reaches the overlay but also passes through to other apps.
+    ListenOnly,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OverlayWindowStyle {
+    pub level: i32,
+    pub blur_radius: Option<i32>,
+}
+
+impl Default for OverlayWindowStyle {
+    fn default() -> Self {
+        Self {
+            level: NSPopUpMenuWindowLevel as i32,
+            blur_radius: None,
+        }
+    }
+}
+
+/// Spaces out attempts to create overlay windows after the window server refuses
+/// one, as it can for a while around login and fast user switching. Owners keep
+/// running without the overlay and try again once [`CreationBackoff::ready`].
+#[derive(Debug, Default)]
+pub struct CreationBackoff {
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl CreationBackoff {
+    const INITIAL_DELAY: Duration = Duration::from_millis(500);
+    const MAX_DELAY: Duration = Duration::from_secs(30);
+
+    pub fn ready(&self, now: Instant) -> bool { self.retry_at.is_none_or(|at| now >= at) }
+
+    /// Time left until the next attempt is allowed.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.retry_at.map_or(Duration::ZERO, |at| at.saturating_duration_since(now))
+    }
+
+    /// Records a failed attempt and returns how long to wait before the next one.
+    pub fn record_failure(&mut self, now: Instant) -> Duration {
+        let delay = Self::INITIAL_DELAY
+            .saturating_mul(1 << self.failures.min(16))
+            .min(Self::MAX_DELAY);
+        self.failures = self.failures.saturating_add(1);
+        self.retry_at = Some(now + delay);
+        delay
+    }
+
+    pub fn record_success(&mut self) { *self = Self::default(); }
+}
+
+/// A display an overlay could be shown on.
+#[derive(Clone, Debug)]
+pub struct ScreenMetric {
+    pub screen: ScreenInfo,
+    pub scale: f64,
+    /// Raw display bounds, menu bar and dock included, for cursor hit-testing.
+    pub bounds: CGRect,
+}
+
+pub fn screen_metrics(mtm: MainThreadMarker) -> Vec<ScreenMetric> {
+    let mut cache = ScreenCache::new(mtm);
+    let Some((screens, _)) = cache.refresh() else {
+        return Vec::new();
+    };
+    NSScreen::screens(mtm)
+        .iter()
+        .filter_map(|ns_screen| {
+            let screen_id = ns_screen.get_number().ok()?;
+            let info = screens.iter().find(|info| info.id == screen_id)?;
+            Some(ScreenMetric {
+                screen: info.clone(),
+                scale: ns_screen.backingScaleFactor(),
+                bounds: CGDisplayBounds(screen_id.as_u32()),
+            })
+        })
+        .collect()
+}
+
+/// The display under the cursor, else the one showing the active space, else
+/// the one holding the center of `current`, else the first one.
+pub fn preferred_screen(
+    metrics: &[ScreenMetric],
+    current: Option<CGRect>,
+) -> Option<&ScreenMetric> {
+    let under_cursor = current_cursor_location()
+        .ok()
+        .and_then(|cursor| metrics.iter().find(|metric| metric.bounds.contains(cursor)));
+    under_cursor
+        .or_else(|| {
+            let active_space = get_active_space_number()?;
+            metrics.iter().find(|metric| metric.screen.space == Some(active_space))
+        })
+        .or_else(|| {
+            let center = current?.mid();
+            metrics.iter().find(|metric| metric.bounds.contains(center))
+        })
+        .or_else(|| metrics.first())
+}
+
+struct TapCtx {
+    content: Weak<dyn OverlayContent>,
+    consumes: bool,
+}
+
+unsafe fn drop_tap_ctx(ptr: *mut c_void) { unsafe { drop(Box::from_raw(ptr as *mut TapCtx)) }; }
+
+unsafe extern "C-unwind" fn tap_callback(
+    _proxy: CGEventTapProxy,
+    etype: CGEventType,
+    event: core::ptr::NonNull<CGEvent>,
+    user_info: *mut c_void,
+) -> *mut CGEvent {
+    // The tap lives on the main run loop, so the weak handle is only touched on
+    // the main thread. Copy what we need out of `ctx` first: if the content
+    // drops its last strong reference while handling input, the tap goes too.
+    let (content, consumes) = {
+        let ctx = unsafe { &*(user_info as *const TapCtx) };
+        (ctx.content.upgrade(), ctx.consumes)
+    };
+    // This tap sees keys before rift's own, so it has to honour the panic hotkey
+    // itself; otherwise a misbehaving overlay could swallow the way out.
+    if etype == CGEventType::KeyDown && kill_switch::check_key_down(unsafe { event.as_ref() }) {
+        return core::ptr::null_mut();
+    }
+    let input = OverlayInput::from_event(etype, unsafe { event.as_ref() });
+    let handled = match (content, input) {
+        (Some(content), Some(input)) => content.handle_input(input),
+        _ => false,
+    };
+    if handled && consumes {
+        core::ptr::null_mut()
+    } else {
+        event.as_ptr()
+    }
+}
+
+/// Owns an overlay's window and everything needed to get its content on screen.
+pub struct OverlayHost {
+    mtm: MainThreadMarker,
+    tap: RefCell<Option<(EventTap, InputMode)>>,
+    cgs_window: CgsWindow,
+    root_layer: Retained<CALayer>,
+    frame: Cell<CGRect>,
+    scale: Cell<f64>,
+    content: RefCell<Option<Weak<dyn OverlayContent>>>,
+    fade_counter: Cell<u64>,
+    active_fade: Cell<Option<u64>>,
+}
+
+impl OverlayHost {
+    pub fn new(
+        mtm: MainThreadMarker,
+        frame: CGRect,
+        scale: f64,
+        style: OverlayWindowStyle,
+    ) -> Result<Self, CgsWindowError> {
+        let root_layer = CALayer::layer();
+        root_layer.setGeometryFlipped(true);
+        root_layer.setFrame(CGRect::new(CGPoint::ZERO, frame.size));
+        root_layer.setContentsScale(scale);
+
+        let cgs_window = CgsWindow::new(frame)?;
+        let _ = cgs_window.set_resolution(scale);
+        let _ = cgs_window.set_opacity(false);
+        let _ = cgs_window.set_alpha(1.0);
+        let _ = cgs_window.set_level(style.level);
+        if let Some(radius) = style.blur_radius {
+            let _ = cgs_window.set_blur(radius, None);
+        }
+
+        Ok(Self {
+            mtm,
+            tap: RefCell::new(None),
+            cgs_window,
+            root_layer,
+            frame: Cell::new(frame),
+            scale: Cell::new(scale),
+            content: RefCell::new(None),
+            fade_counter: Cell::new(0),
+            active_fade: Cell::new(None),
+        })
+    }
+
+    /// Connects the content that receives this host's input and fade callbacks.
+    pub fn attach(&self, content: Weak<dyn OverlayContent>) { self.content.replace(Some(content)); }
+
+    pub fn mtm(&self) -> MainThreadMarker { self.mtm }
+
+    pub fn root_layer(&self) -> &Retained<CALayer> { &self.root_layer }
+
+    pub fn window_id(&self) -> u32 { self.cgs_window.id() }
+
+    /// Frame of the overlay window in global coordinates.
+    pub fn frame(&self) -> CGRect { self.frame.get() }
+
+    /// Bounds of the root layer, in the overlay's own coordinates.
+    pub fn bounds(&self) -> CGRect { CGRect::new(CGPoint::ZERO, self.frame.get().size) }
+
+    pub fn scale(&self) -> f64 { self.scale.get() }
+
+    /// Converts a global point into the root layer's coordinates.
+    pub fn to_local(&self, point: CGPoint) -> CGPoint {
+        let origin = self.frame.get().origin;
+        CGPoint::new(point.x - origin.x, point.y - origin.y)
+    }
+
+    /// Resizes the window onto `frame` at `scale`. Returns whether anything changed.
+    pub fn move_to(&self, frame: CGRect, scale: f64) -> bool {
+        let frame_changed = frame != self.frame.get();
+        let scale_changed = (scale - self.scale.get()).abs() > f64::EPSILON;
+        if !frame_changed && !scale_changed {
+            return false;
+        }
+        let _ = self.cgs_window.set_shape(frame);
+        let _ = self.cgs_window.set_resolution(scale);
+        self.frame.set(frame);
+        self.scale.set(scale);
+        self.root_layer.setFrame(self.bounds());
+        self.root_layer.setContentsScale(scale);
+        true
+    }
+
+    /// Moves the window onto the [`preferred_screen`], if there is one.
+    pub fn move_to_preferred_screen(&self) -> bool {
+        let metrics = screen_metrics(self.mtm);
+        let Some(metric) = preferred_screen(&metrics, Some(self.frame.get())) else {
+            return false;
+        };
+        self.move_to(metric.bounds, metric.scale)
+    }
+
+    /// Renders the root layer into the window.
+    pub fn present(&self) {
+        render_layer_to_cgs_window(self.window_id(), self.frame.get().size, &self.root_layer);
+    }
+
+    pub fn order_in(&self) -> Result<(), CgsWindowError> { self.cgs_window.order_above(None) }
+
+    pub fn order_out(&self) -> Result<(), CgsWindowError> { self.cgs_window.order_out() }
+
+    pub fn set_alpha(&self, alpha: f32) { let _ = self.cgs_window.set_alpha(alpha); }
+
+    /// Blurs whatever is behind the window by `radius` points; 0 turns it off.
+    pub fn set_blur_radius(&self, radius: i32) {
+        if let Err(err) = self.cgs_window.set_blur(radius, None) {
+            warn!(?err, "failed to set overlay blur radius");
+        }
+    }
+
+    /// Installs an event tap for the `events` the content wants. If an exclusive
+    /// tap is refused and `allow_listen_only` is set, falls back to observing
+    /// input without swallowing it. Does nothing if a tap is already installed.
+    pub fn acquire_input(
+        &self,
+        events: &[CGEventType],
+        allow_listen_only: bool,
+    ) -> Option<InputMode> {
+        if let Some((_, mode)) = self.tap.borrow().as_ref() {
+            return Some(*mode);
+        }
+        let content = self.content.borrow().clone()?;
+        let mask = events.iter().fold(0u64, |mask, etype| mask | (1u64 << etype.0 as u64));
+
+        let install = |consumes: bool| {
+            let ctx = Box::into_raw(Box::new(TapCtx {
+                content: content.clone(),
+                consumes,
+            })) as *mut c_void;
+            let tap = unsafe {
+                if consumes {
+                    EventTap::new_with_options(
+                        CGEventTapOptions::Default,
+                        mask,
+                        Some(tap_callback),
+                        ctx,
+                        Some(drop_tap_ctx),
+                    )
+                } else {
+                    EventTap::new_listen_only(mask, Some(tap_callback), ctx, Some(drop_tap_ctx))
+                }
+            };
+            if tap.is_none() {
+                unsafe { drop_tap_ctx(ctx) };
+            }
+            tap
+        };
+
+        let (tap, mode) = match install(true) {
+            Some(tap) => (tap, InputMode::Exclusive),
+            None if allow_listen_only => {
+                let tap = install(false)?;
+                info!("Falling back to listen-only event tap; overlay input will pass through");
+                (tap, InputMode::ListenOnly)
+            }
+            None => return None,
+        };
+        self.tap.replace(Some((tap, mode)));
+        Some(mode)
+    }
+
+    pub fn set_input_enabled(&self, enabled: bool) {
+        if let Some((tap, _)) = self.tap.borrow().as_ref() {
+            tap.set_enabled(enabled);
+        }
+    }
+
+    pub fn release_input(&self) { self.tap.borrow_mut().take(); }
+
+    /// Animates the root layer from `from` to `to` opacity and settles the window
+    /// alpha on `to` when done, then tells the content. Returns false if the
+    /// change was applied immediately instead, because `duration_ms` is zero or
+    /// no content is attached.
+    ///
+    /// The pending completion keeps the content alive, so a fade-out still
+    /// finishes after its owner has let go of the overlay.
+    pub fn fade(&self, from: f32, to: f32, duration_ms: f64) -> bool {
+        self.stop_fade();
+        let content = self.content.borrow().as_ref().and_then(Weak::upgrade);
+        let Some(content) = content.filter(|_| duration_ms > 0.0) else {
+            self.set_alpha(to);
+            return false;
+        };
+
+        let fade_id = self.fade_counter.get() + 1;
+        self.fade_counter.set(fade_id);
+
+        CATransaction::begin();
+        CATransaction::setAnimationDuration(duration_ms / 1000.0);
+        self.root_layer.setOpacity(from);
+        self.root_layer.setOpacity(to);
+        CATransaction::commit();
+
+        self.active_fade.set(Some(fade_id));
+        queue::main().after_f_s(Time::NOW, (content, fade_id, to), |(content, fade_id, to)| {
+            if content.host().finish_fade(fade_id) {
+                content.host().set_alpha(to);
+                content.fade_finished(to);
+            }
+        });
+        true
+    }
+
+    pub fn stop_fade(&self) {
+        self.root_layer.removeAllAnimations();
+        self.active_fade.set(None);
+    }
+
+    fn finish_fade(&self, fade_id: u64) -> bool {
+        if self.active_fade.get() != Some(fade_id) {
+            return false;
+        }
+        self.active_fade.set(None);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn creation_backoff_doubles_up_to_the_cap_and_resets_on_success() {
+        let start = Instant::now();
+        let mut backoff = CreationBackoff::default();
+        assert!(backoff.ready(start));
+
+        assert_eq!(backoff.record_failure(start), Duration::from_millis(500));
+        assert!(!backoff.ready(start));
+        assert!(backoff.ready(start + Duration::from_millis(500)));
+        assert_eq!(backoff.record_failure(start), Duration::from_secs(1));
+        assert_eq!(backoff.remaining(start), Duration::from_secs(1));
+
+        for _ in 0..40 {
+            backoff.record_failure(start);
+        }
+        assert_eq!(backoff.remaining(start), Duration::from_secs(30));
+
+        backoff.record_success();
+        assert!(backoff.ready(start));
+        assert_eq!(backoff.record_failure(start), Duration::from_millis(500));
+    }
+}
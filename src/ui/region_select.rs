@@ -0,0 +1,186 @@
+//! Full-screen crosshair overlay for picking a rectangle with the mouse.
+//!
+//! The overlay covers the display under the cursor and swallows mouse input
+//! until the user finishes a click-drag (or presses Escape). The result is
+//! reported in global screen coordinates through the completion callback.
+
+use std::cell::Cell;
+use std::rc::{Rc, Weak};
+
+use objc2::rc::Retained;
+use objc2_app_kit::{NSColor, NSCursor};
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_core_graphics::CGEventType;
+use objc2_foundation::MainThreadMarker;
+use objc2_quartz_core::CALayer;
+use tracing::warn;
+
+use crate::sys::cgs_window::CgsWindowError;
+use crate::sys::geometry::CGRectExt;
+use crate::ui::common::with_disabled_actions;
+use crate::ui::overlay_host::{
+    OverlayContent, OverlayHost, OverlayInput, OverlayWindowStyle, preferred_screen, screen_metrics,
+};
+
+const ESCAPE_KEYCODE: u16 = 53;
+const MIN_SELECTION_SIZE: f64 = 8.0;
+
+pub struct RegionSelectOverlay {
+    host: OverlayHost,
+    selection_layer: Retained<CALayer>,
+    anchor: Cell<Option<CGPoint>>,
+    finished: Cell<bool>,
+    on_done: Box<dyn Fn(Option<CGRect>)>,
+}
+
+impl RegionSelectOverlay {
+    /// Shows the overlay on the display under the cursor. `on_done` receives the
+    /// selected rect, or `None` if the selection was cancelled.
+    pub fn show(
+        mtm: MainThreadMarker,
+        on_done: impl Fn(Option<CGRect>) + 'static,
+    ) -> Result<Rc<Self>, CgsWindowError> {
+        let metrics = screen_metrics(mtm);
+        let (frame, scale) = preferred_screen(&metrics, None)
+            .map(|metric| (metric.bounds, metric.scale))
+            .unwrap_or((CGRect::new(CGPoint::ZERO, CGSize::new(1280.0, 800.0)), 1.0));
+        let host = OverlayHost::new(mtm, frame, scale, OverlayWindowStyle::default())?;
+
+        let root_layer = host.root_layer();
+        let dim = NSColor::colorWithRed_green_blue_alpha(0.0, 0.0, 0.0, 0.25);
+        root_layer.setBackgroundColor(Some(&dim.CGColor()));
+
+        let selection_layer = CALayer::layer();
+        let fill = NSColor::colorWithRed_green_blue_alpha(0.0, 0.5, 1.0, 0.2);
+        let border = NSColor::colorWithRed_green_blue_alpha(1.0, 1.0, 1.0, 0.9);
+        selection_layer.setBackgroundColor(Some(&fill.CGColor()));
+        selection_layer.setBorderColor(Some(&border.CGColor()));
+        selection_layer.setBorderWidth(1.5);
+        selection_layer.setHidden(true);
+        root_layer.addSublayer(&selection_layer);
+
+        let overlay = Rc::new(Self {
+            host,
+            selection_layer,
+            anchor: Cell::new(None),
+            finished: Cell::new(false),
+            on_done: Box::new(on_done),
+        });
+        let content: Weak<dyn OverlayContent> = Rc::downgrade(&overlay);
+        overlay.host.attach(content);
+
+        overlay.host.present();
+        overlay.host.order_in()?;
+        NSCursor::crosshairCursor().set();
+        let events = [
+            CGEventType::KeyDown,
+            CGEventType::LeftMouseDown,
+            CGEventType::LeftMouseDragged,
+            CGEventType::LeftMouseUp,
+        ];
+        if overlay.host.acquire_input(&events, false).is_none() {
+            warn!("Unable to install event tap for region selection; cancelling");
+            overlay.cancel();
+        }
+        Ok(overlay)
+    }
+
+    fn clamp(&self, point: CGPoint) -> CGPoint {
+        let frame = self.host.frame();
+        let max = frame.max();
+        CGPoint::new(
+            point.x.clamp(frame.origin.x, max.x),
+            point.y.clamp(frame.origin.y, max.y),
+        )
+    }
+
+    fn selection_rect(anchor: CGPoint, point: CGPoint) -> CGRect {
+        let origin = CGPoint::new(anchor.x.min(point.x), anchor.y.min(point.y));
+        let size = CGSize::new((anchor.x - point.x).abs(), (anchor.y - point.y).abs());
+        CGRect::new(origin, size)
+    }
+
+    fn handle_mouse_down(&self, point: CGPoint) {
+        let point = self.clamp(point);
+        self.anchor.set(Some(point));
+        self.update_selection(point);
+    }
+
+    fn handle_mouse_dragged(&self, point: CGPoint) {
+        if self.anchor.get().is_some() {
+            self.update_selection(self.clamp(point));
+        }
+    }
+
+    fn handle_mouse_up(&self, point: CGPoint) {
+        let Some(anchor) = self.anchor.take() else {
+            return;
+        };
+        let rect = Self::selection_rect(anchor, self.clamp(point));
+        if rect.size.width < MIN_SELECTION_SIZE || rect.size.height < MIN_SELECTION_SIZE {
+            // Treat a plain click as a mis-click and let the user try again.
+            self.selection_layer.setHidden(true);
+            self.host.present();
+            return;
+        }
+        self.finish(Some(rect));
+    }
+
+    fn update_selection(&self, point: CGPoint) {
+        let Some(anchor) = self.anchor.get() else {
+            return;
+        };
+        let rect = Self::selection_rect(anchor, point);
+        let local = CGRect::new(self.host.to_local(rect.origin), rect.size);
+        with_disabled_actions(|| {
+            self.selection_layer.setFrame(local);
+            self.selection_layer.setHidden(false);
+        });
+        self.host.present();
+    }
+
+    fn finish(&self, result: Option<CGRect>) {
+        if self.finished.replace(true) {
+            return;
+        }
+        self.host.set_input_enabled(false);
+        let _ = self.host.order_out();
+        NSCursor::arrowCursor().set();
+        (self.on_done)(result);
+    }
+
+    /// Cancels the selection if it is still in progress.
+    pub fn cancel(&self) { self.finish(None); }
+}
+
+impl OverlayContent for RegionSelectOverlay {
+    fn host(&self) -> &OverlayHost { &self.host }
+
+    fn handle_input(&self, input: OverlayInput) -> bool {
+        if self.finished.get() {
+            return false;
+        }
+        match input {
+            OverlayInput::MouseDown(point) => self.handle_mouse_down(point),
+            OverlayInput::MouseDragged(point) => self.handle_mouse_dragged(point),
+            OverlayInput::MouseUp(point) => self.handle_mouse_up(point),
+            OverlayInput::KeyDown { keycode, .. } => {
+                if keycode == ESCAPE_KEYCODE {
+                    self.cancel();
+                }
+            }
+            OverlayInput::MouseMoved(_) => return false,
+        }
+        true
+    }
+}
+
+impl Drop for RegionSelectOverlay {
+    fn drop(&mut self) {
+        if !self.finished.get() {
+            self.finished.set(true);
+            let _ = self.host.order_out();
+            NSCursor::arrowCursor().set();
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! A short-lived border around the selected container.
+//!
+//! After `ascend` the selection can be a whole container, and nothing on screen
+//! shows which one. The stack line actor draws this outline around the
+//! container's windows for a moment so the next move, resize or layout command
+//! has a visible target.
+
+use objc2::rc::Retained;
+use objc2_app_kit::NSStatusWindowLevel;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_quartz_core::CALayer;
+use tracing::warn;
+
+use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
+use crate::ui::common::{render_layer_to_cgs_window, with_disabled_actions};
+use crate::ui::stack_line::Color;
+
+const BORDER_WIDTH: f64 = 3.0;
+const CORNER_RADIUS: f64 = 8.0;
+
+pub struct SelectionOutlineWindow {
+    root_layer: Retained<CALayer>,
+    border_layer: Retained<CALayer>,
+    cgs_window: CgsWindow,
+}
+
+impl SelectionOutlineWindow {
+    pub fn new(frame: CGRect) -> Result<Self, CgsWindowError> {
+        let cgs_window = CgsWindow::new(outset(frame))?;
+        if let Err(err) = cgs_window.set_opacity(false) {
+            warn!(error=?err, "failed to set selection outline opacity");
+        }
+        if let Err(err) = cgs_window.set_level(NSStatusWindowLevel as i32) {
+            warn!(error=?err, "failed to set selection outline level");
+        }
+        let root_layer = CALayer::layer();
+        let border_layer = CALayer::layer();
+        root_layer.addSublayer(&border_layer);
+        Ok(Self {
+            root_layer,
+            border_layer,
+            cgs_window,
+        })
+    }
+
+    /// Draws the outline around `frame`, a container's bounds in screen coordinates.
+    pub fn show(&self, frame: CGRect, color: Color) -> Result<(), CgsWindowError> {
+        let frame = outset(frame);
+        self.cgs_window.set_shape(frame)?;
+        let bounds = CGRect::new(CGPoint::new(0.0, 0.0), frame.size);
+        with_disabled_actions(|| {
+            self.root_layer.setFrame(bounds);
+            self.border_layer.setFrame(bounds);
+            self.border_layer.setBorderWidth(BORDER_WIDTH);
+            self.border_layer.setBorderColor(Some(&color.to_nscolor().CGColor()));
+            self.border_layer.setCornerRadius(CORNER_RADIUS);
+        });
+        render_layer_to_cgs_window(self.cgs_window.id(), frame.size, &self.root_layer);
+        self.cgs_window.order_above(None)
+    }
+
+    pub fn hide(&self) -> Result<(), CgsWindowError> { self.cgs_window.order_out() }
+}
+
+/// Grows `frame` so the border sits just outside the windows instead of over them.
+fn outset(frame: CGRect) -> CGRect {
+    CGRect::new(
+        CGPoint::new(frame.origin.x - BORDER_WIDTH, frame.origin.y - BORDER_WIDTH),
+        CGSize::new(
+            frame.size.width + 2.0 * BORDER_WIDTH,
+            frame.size.height + 2.0 * BORDER_WIDTH,
+        ),
+    )
+}
@@ -8,7 +8,8 @@ use objc2_quartz_core::CALayer;
 use tracing::warn;
 
 use crate::actor::app::WindowId;
-use crate::common::config::{HorizontalPlacement, VerticalPlacement};
+use crate::common::config::{StackLineSettings, ThemeColor, ThemeSettings};
+use crate::sys::appearance::Appearance;
 use crate::sys::cgs_window::{CgsWindow, CgsWindowError};
 use crate::sys::screen::SpaceId;
 use crate::ui::common::{render_layer_to_cgs_window, with_disabled_actions};
@@ -35,6 +36,10 @@ impl Color {
     }
 }
 
+impl From<ThemeColor> for Color {
+    fn from(c: ThemeColor) -> Self { Self::new(c.r, c.g, c.b, c.a) }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct IndicatorConfig {
     pub bar_thickness: f64,
@@ -42,8 +47,6 @@ pub struct IndicatorConfig {
     pub unselected_color: Color,
     pub border_color: Color,
     pub border_width: f64,
-    pub horizontal_placement: HorizontalPlacement,
-    pub vertical_placement: VerticalPlacement,
     pub spacing: f64,
 }
 
@@ -55,23 +58,27 @@ impl Default for IndicatorConfig {
             unselected_color: Color { r: 0.5, g: 0.5, b: 0.5, a: 0.4 },
             border_color: Color { r: 0.3, g: 0.3, b: 0.3, a: 0.6 },
             border_width: 0.3,
-            horizontal_placement: HorizontalPlacement::Top,
-            vertical_placement: VerticalPlacement::Right,
             spacing: 4.0,
         }
     }
 }
 
-impl From<&crate::common::config::StackLineSettings> for IndicatorConfig {
-    fn from(config: &crate::common::config::StackLineSettings) -> Self {
+impl IndicatorConfig {
+    pub fn new(config: &StackLineSettings, theme: &ThemeSettings, system: Appearance) -> Self {
+        let appearance = theme.effective_appearance(system);
+        // Light segments disappear against light windows, so go darker there.
+        let (unselected_color, border_color) = match appearance {
+            Appearance::Dark => (Color::light_gray(), Color::gray()),
+            Appearance::Light => {
+                (Color::new(0.55, 0.55, 0.55, 1.0), Color::new(0.4, 0.4, 0.4, 1.0))
+            }
+        };
         Self {
             bar_thickness: config.thickness,
-            selected_color: Color::blue(),
-            unselected_color: Color::light_gray(),
-            border_color: Color::gray(),
+            selected_color: theme.palette(appearance).accent_color.into(),
+            unselected_color,
+            border_color,
             border_width: 0.5,
-            horizontal_placement: config.horiz_placement,
-            vertical_placement: config.vert_placement,
             spacing: config.spacing,
         }
     }
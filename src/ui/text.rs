@@ -0,0 +1,237 @@
+//! Measuring and fitting overlay labels.
+//!
+//! Labels are measured with Core Text and cut down to the space they are given,
+//! ending in an ellipsis, instead of being clipped by a fixed frame. Each label
+//! also reports its base direction so Arabic and Hebrew names can be aligned to
+//! the right edge of their tile.
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, ProtocolObject};
+use objc2_app_kit::{NSApplication, NSFont, NSFontAttributeName, NSUserInterfaceLayoutDirection};
+use objc2_core_foundation::{CFAttributedString, CFDictionary, CFString, CFType, CGFloat};
+use objc2_core_text::CTLine;
+use objc2_foundation::{
+    MainThreadMarker, NSAttributedStringKey, NSDictionary, NSMutableDictionary, NSString,
+};
+
+const ELLIPSIS: char = '…';
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// The base direction of `text`, taken from its first strongly directional
+/// character as in the Unicode bidi algorithm. `None` if it has none, e.g. a
+/// label that is only digits and punctuation.
+pub fn text_direction(text: &str) -> Option<TextDirection> {
+    text.chars().find_map(|c| {
+        if is_strong_rtl(c) {
+            Some(TextDirection::RightToLeft)
+        } else if c.is_alphabetic() {
+            Some(TextDirection::LeftToRight)
+        } else {
+            None
+        }
+    })
+}
+
+/// The direction of the language the user runs macOS in.
+pub fn interface_direction(mtm: MainThreadMarker) -> TextDirection {
+    match NSApplication::sharedApplication(mtm).userInterfaceLayoutDirection() {
+        NSUserInterfaceLayoutDirection::RightToLeft => TextDirection::RightToLeft,
+        _ => TextDirection::LeftToRight,
+    }
+}
+
+fn is_strong_rtl(c: char) -> bool {
+    matches!(
+        c as u32,
+        // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic and Arabic Extended.
+        0x0590..=0x08FF
+            // Hebrew and Arabic presentation forms.
+            | 0xFB1D..=0xFDFF
+            | 0xFE70..=0xFEFF
+            // Historic right-to-left scripts and Arabic mathematical symbols.
+            | 0x10800..=0x10FFF
+            | 0x1E800..=0x1EFFF
+    )
+}
+
+/// Shortens `text` so that, with an ellipsis appended, `measure` reports at most
+/// `max_width`. Returns the text to show and its measured width. Text that fits
+/// is returned unchanged; if not even the ellipsis fits, the result is empty.
+pub fn truncate_to_width(
+    text: &str,
+    max_width: f64,
+    measure: impl Fn(&str) -> f64,
+) -> (String, f64) {
+    let full = measure(text);
+    if full <= max_width {
+        return (text.to_owned(), full);
+    }
+
+    // Candidate cut points, never splitting a combining mark from its base.
+    let cuts: Vec<usize> = text
+        .char_indices()
+        .filter(|&(idx, c)| idx > 0 && !is_combining(c))
+        .map(|(idx, _)| idx)
+        .collect();
+    let with_ellipsis = |end: usize| {
+        let mut candidate = text[..end].trim_end().to_owned();
+        candidate.push(ELLIPSIS);
+        candidate
+    };
+
+    // Binary search for the longest prefix that still fits.
+    let (mut lo, mut hi) = (0, cuts.len());
+    let mut best = None;
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        let candidate = with_ellipsis(cuts[mid]);
+        let width = measure(&candidate);
+        if width <= max_width {
+            best = Some((candidate, width));
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    best.unwrap_or_else(|| {
+        let ellipsis = ELLIPSIS.to_string();
+        let width = measure(&ellipsis);
+        if width <= max_width {
+            (ellipsis, width)
+        } else {
+            (String::new(), 0.0)
+        }
+    })
+}
+
+fn is_combining(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F
+            | 0x0591..=0x05C7
+            | 0x0610..=0x061A
+            | 0x064B..=0x065F
+            | 0x1AB0..=0x1AFF
+            | 0x1DC0..=0x1DFF
+            | 0x200D
+            | 0x20D0..=0x20FF
+            | 0xFE00..=0xFE0F
+            | 0xFE20..=0xFE2F
+    )
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FittedLabel {
+    pub text: String,
+    pub width: f64,
+    pub direction: TextDirection,
+}
+
+/// A font for overlay text, with what Core Text needs to measure strings in it.
+pub struct LabelFont {
+    font: Retained<NSFont>,
+    attrs: Retained<NSDictionary<NSAttributedStringKey, AnyObject>>,
+}
+
+impl LabelFont {
+    /// `family` is a font name such as `"Menlo"` or `"Helvetica Neue Bold"`. The
+    /// system font is used when it is unset or not installed.
+    pub fn new(family: Option<&str>, size: f64) -> Self {
+        let font = family
+            .and_then(|name| NSFont::fontWithName_size(&NSString::from_str(name), size))
+            .unwrap_or_else(|| NSFont::systemFontOfSize(size));
+        let attrs = NSMutableDictionary::<NSAttributedStringKey, AnyObject>::new();
+        unsafe {
+            let font_obj: &AnyObject = &*(Retained::as_ptr(&font) as *const AnyObject);
+            attrs.setObject_forKeyedSubscript(
+                Some(font_obj),
+                ProtocolObject::from_ref(NSFontAttributeName),
+            );
+        }
+        let attrs = unsafe { Retained::cast_unchecked(attrs) };
+        Self { font, attrs }
+    }
+
+    /// The font as the `CTFont` that `CATextLayer::setFont` expects; `NSFont` is
+    /// toll-free bridged to it.
+    pub fn as_cf_type(&self) -> &CFType {
+        unsafe { &*(Retained::as_ptr(&self.font) as *const CFType) }
+    }
+
+    pub fn size(&self) -> f64 { self.font.pointSize() }
+
+    /// Height of one line of text, for sizing label frames.
+    pub fn line_height(&self) -> f64 {
+        (self.font.ascender() - self.font.descender() + self.font.leading()).ceil()
+    }
+
+    pub fn measure(&self, text: &str) -> f64 {
+        if text.is_empty() {
+            return 0.0;
+        }
+        let ns_text = NSString::from_str(text);
+        let cf_text: &CFString = ns_text.as_ref();
+        let cf_attrs: &CFDictionary<NSAttributedStringKey, AnyObject> = self.attrs.as_ref();
+        let Some(attributed) =
+            (unsafe { CFAttributedString::new(None, Some(cf_text), Some(cf_attrs.as_opaque())) })
+        else {
+            return 0.0;
+        };
+        let line = unsafe { CTLine::with_attributed_string(&attributed) };
+        let (mut ascent, mut descent, mut leading): (CGFloat, CGFloat, CGFloat) = (0.0, 0.0, 0.0);
+        unsafe { line.typographic_bounds(&mut ascent, &mut descent, &mut leading) }.ceil()
+    }
+
+    /// Fits `text` into `max_width` points. Labels with no strong direction of
+    /// their own use `fallback`, normally [`interface_direction`].
+    pub fn fit(&self, text: &str, max_width: f64, fallback: TextDirection) -> FittedLabel {
+        let (fitted, width) = truncate_to_width(text, max_width, |s| self.measure(s));
+        FittedLabel {
+            text: fitted,
+            width,
+            direction: text_direction(text).unwrap_or(fallback),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fits `text` with every character 10 points wide.
+    fn fit(text: &str, max_width: f64) -> (String, f64) {
+        truncate_to_width(text, max_width, |s| s.chars().count() as f64 * 10.0)
+    }
+
+    #[test]
+    fn text_that_fits_is_left_alone() {
+        assert_eq!(fit("Mail", 40.0), ("Mail".into(), 40.0));
+    }
+
+    #[test]
+    fn long_text_is_cut_at_the_widest_prefix_that_fits() {
+        assert_eq!(fit("Workspace 10", 60.0), ("Works…".into(), 60.0));
+        // Trailing spaces before the ellipsis are dropped.
+        assert_eq!(fit("Web Dev", 50.0), ("Web…".into(), 40.0));
+        assert_eq!(fit("Chat", 10.0), ("…".into(), 10.0));
+        assert_eq!(fit("Chat", 5.0), (String::new(), 0.0));
+    }
+
+    #[test]
+    fn truncation_keeps_combining_marks_with_their_base() {
+        assert_eq!(fit("cafe\u{301}ss", 60.0).0, "cafe\u{301}…");
+    }
+
+    #[test]
+    fn direction_comes_from_the_first_strong_character() {
+        assert_eq!(text_direction("עבודה 2"), Some(TextDirection::RightToLeft));
+        assert_eq!(text_direction("3 - بريد"), Some(TextDirection::RightToLeft));
+        assert_eq!(text_direction("Mail مرحبا"), Some(TextDirection::LeftToRight));
+        assert_eq!(text_direction("12 (3)"), None);
+    }
+}
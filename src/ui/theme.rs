@@ -0,0 +1,56 @@
+//! `ui.theme` resolved into the colors and font the overlays draw with.
+
+use objc2::rc::Retained;
+use objc2_core_graphics::CGColor;
+
+use crate::common::config::{ThemeColor, ThemeSettings};
+use crate::sys::appearance::Appearance;
+use crate::ui::text::LabelFont;
+
+pub fn cg_color(color: ThemeColor) -> Retained<CGColor> {
+    CGColor::new_generic_rgb(color.r, color.g, color.b, color.a).into()
+}
+
+pub struct OverlayTheme {
+    pub accent: Retained<CGColor>,
+    /// Fills the whole overlay to dim the desktop behind it.
+    pub dim: Retained<CGColor>,
+    /// Laid over previews of minimized and hidden windows to set them apart.
+    pub scrim: Retained<CGColor>,
+    pub tile_background: Retained<CGColor>,
+    pub tile_border: Retained<CGColor>,
+    pub label: Retained<CGColor>,
+    pub font: LabelFont,
+    pub border_width: f64,
+    pub selected_border_width: f64,
+    pub blur_radius: i32,
+}
+
+impl OverlayTheme {
+    /// `system` is the current system appearance; `settings.appearance` may override it.
+    pub fn new(settings: &ThemeSettings, system: Appearance) -> Self {
+        let appearance = settings.effective_appearance(system);
+        let settings = &settings.palette(appearance);
+        // Dark mode dims the desktop; light mode washes it out instead.
+        let (wash, ink) = match appearance {
+            Appearance::Dark => (0.0, 1.0),
+            Appearance::Light => (1.0, 0.0),
+        };
+        Self {
+            accent: cg_color(settings.accent_color),
+            dim: CGColor::new_generic_gray(wash, settings.overlay_dim.clamp(0.0, 1.0)).into(),
+            scrim: CGColor::new_generic_gray(wash, 0.55).into(),
+            tile_background: cg_color(settings.tile_background),
+            tile_border: cg_color(settings.border_color),
+            label: CGColor::new_generic_gray(ink, 0.85).into(),
+            font: LabelFont::new(settings.font_family.as_deref(), settings.font_size.max(1.0)),
+            border_width: settings.border_width.max(0.0),
+            selected_border_width: settings.selected_border_width.max(0.0),
+            blur_radius: settings.blur_radius.max(0),
+        }
+    }
+}
+
+impl Default for OverlayTheme {
+    fn default() -> Self { Self::new(&ThemeSettings::default(), Appearance::default()) }
+}
@@ -0,0 +1,227 @@
+//! Magnifies a window or a region of the screen onto its whole display.
+//!
+//! Meant for presentations and screencasts. The overlay covers the display the
+//! target is on and shows a capture of it scaled to fit, taken again every frame
+//! so video and typing stay live underneath. Escape or a click dismisses it;
+//! other keys still reach the focused app.
+
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use dispatchr::queue;
+use dispatchr::time::Time;
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::AnyObject;
+use objc2_app_kit::NSColor;
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
+use objc2_core_graphics::{CGEventType, CGImage};
+use objc2_foundation::MainThreadMarker;
+use objc2_quartz_core::CALayer;
+use tracing::{debug, warn};
+
+use crate::sys::cgs_window::CgsWindowError;
+use crate::sys::dispatch::DispatchExt;
+use crate::sys::geometry::CGRectExt;
+use crate::sys::window_server::{self, CapturedWindowImage, WindowServerId};
+use crate::ui::common::with_disabled_actions;
+use crate::ui::overlay_host::{
+    OverlayContent, OverlayHost, OverlayInput, OverlayWindowStyle, preferred_screen, screen_metrics,
+};
+
+const ESCAPE_KEYCODE: u16 = 53;
+/// Time between captures, about 30 a second.
+const REFRESH_INTERVAL_NS: i64 = 33_000_000;
+
+/// What a [`ZoomOverlay`] magnifies.
+#[derive(Clone, Copy, Debug)]
+pub enum ZoomSource {
+    Window(WindowServerId),
+    /// A rectangle in global screen coordinates.
+    Region(CGRect),
+}
+
+impl ZoomSource {
+    fn frame(&self) -> Option<CGRect> {
+        match *self {
+            ZoomSource::Window(wsid) => window_server::get_window(wsid).map(|window| window.frame),
+            ZoomSource::Region(rect) => Some(rect),
+        }
+    }
+}
+
+pub struct ZoomOverlay {
+    host: OverlayHost,
+    image_layer: Retained<CALayer>,
+    source: ZoomSource,
+    /// The capture the image layer is showing.
+    image: RefCell<Option<CapturedWindowImage>>,
+    finished: Cell<bool>,
+    on_done: Box<dyn Fn()>,
+}
+
+impl ZoomOverlay {
+    /// Shows `source` magnified on the display holding its center. `on_done`
+    /// runs once the overlay is dismissed, including when a zoomed window closes.
+    pub fn show(
+        mtm: MainThreadMarker,
+        source: ZoomSource,
+        on_done: impl Fn() + 'static,
+    ) -> Result<Rc<Self>, CgsWindowError> {
+        let metrics = screen_metrics(mtm);
+        let target = source.frame();
+        let (frame, scale) = target
+            .and_then(|target| metrics.iter().find(|metric| metric.bounds.contains(target.mid())))
+            .or_else(|| preferred_screen(&metrics, target))
+            .map(|metric| (metric.bounds, metric.scale))
+            .unwrap_or((CGRect::new(CGPoint::ZERO, CGSize::new(1280.0, 800.0)), 1.0));
+        let host = OverlayHost::new(mtm, frame, scale, OverlayWindowStyle::default())?;
+
+        let root_layer = host.root_layer();
+        root_layer.setBackgroundColor(Some(&NSColor::blackColor().CGColor()));
+        let image_layer = CALayer::layer();
+        image_layer.setContentsScale(scale);
+        root_layer.addSublayer(&image_layer);
+
+        let overlay = Rc::new(Self {
+            host,
+            image_layer,
+            source,
+            image: RefCell::new(None),
+            finished: Cell::new(false),
+            on_done: Box::new(on_done),
+        });
+        let content: Weak<dyn OverlayContent> = Rc::downgrade(&overlay);
+        overlay.host.attach(content);
+
+        // Ordered in first: a region is captured from the windows below this one.
+        overlay.host.order_in()?;
+        overlay.refresh();
+        let events = [CGEventType::KeyDown, CGEventType::LeftMouseDown];
+        if overlay.host.acquire_input(&events, false).is_none() {
+            warn!("Unable to install event tap for zoom; dismissing");
+            overlay.dismiss();
+        } else {
+            Self::schedule_refresh(Rc::downgrade(&overlay));
+        }
+        Ok(overlay)
+    }
+
+    pub fn is_finished(&self) -> bool { self.finished.get() }
+
+    /// Captures the source again and shows it. Returns false if nothing could
+    /// be captured, as happens once a zoomed window has closed.
+    fn refresh(&self) -> bool {
+        let bounds = self.host.bounds();
+        let image = match self.source {
+            ZoomSource::Window(wsid) => {
+                let scale = self.host.scale();
+                let width = (bounds.size.width * scale) as usize;
+                let height = (bounds.size.height * scale) as usize;
+                window_server::capture_window_image(wsid, width, height)
+            }
+            ZoomSource::Region(rect) => {
+                window_server::capture_region_below(rect, self.host.window_id())
+            }
+        };
+        let Some(image) = image else {
+            return false;
+        };
+
+        let size = CGSize::new(
+            CGImage::width(Some(image.cg_image())) as f64,
+            CGImage::height(Some(image.cg_image())) as f64,
+        );
+        with_disabled_actions(|| {
+            self.image_layer.setFrame(fit(bounds, size));
+            unsafe {
+                let contents = image.as_ptr() as *mut AnyObject;
+                let _: () = msg_send![&*self.image_layer, setContents: contents];
+            }
+        });
+        self.image.replace(Some(image));
+        self.host.present();
+        true
+    }
+
+    fn schedule_refresh(overlay: Weak<Self>) {
+        let when = Time::new_after(Time::NOW, REFRESH_INTERVAL_NS);
+        queue::main().after_f_s(when, overlay, |overlay| {
+            let Some(overlay) = overlay.upgrade().filter(|overlay| !overlay.is_finished()) else {
+                return;
+            };
+            if overlay.refresh() || matches!(overlay.source, ZoomSource::Region(_)) {
+                Self::schedule_refresh(Rc::downgrade(&overlay));
+            } else {
+                debug!("Zoomed window can no longer be captured; dismissing zoom");
+                overlay.dismiss();
+            }
+        });
+    }
+
+    /// Takes the overlay down if it is still showing.
+    pub fn dismiss(&self) {
+        if self.finished.replace(true) {
+            return;
+        }
+        self.host.set_input_enabled(false);
+        let _ = self.host.order_out();
+        (self.on_done)();
+    }
+}
+
+impl OverlayContent for ZoomOverlay {
+    fn host(&self) -> &OverlayHost { &self.host }
+
+    fn handle_input(&self, input: OverlayInput) -> bool {
+        if self.finished.get() {
+            return false;
+        }
+        match input {
+            OverlayInput::KeyDown { keycode, .. } if keycode == ESCAPE_KEYCODE => self.dismiss(),
+            OverlayInput::MouseDown(_) => self.dismiss(),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Drop for ZoomOverlay {
+    fn drop(&mut self) {
+        if !self.finished.replace(true) {
+            let _ = self.host.order_out();
+        }
+    }
+}
+
+/// The largest rect with the aspect ratio of `content` that fits centered in `bounds`.
+fn fit(bounds: CGRect, content: CGSize) -> CGRect {
+    if content.width <= 0.0 || content.height <= 0.0 {
+        return bounds;
+    }
+    let scale = (bounds.size.width / content.width).min(bounds.size.height / content.height);
+    let size = CGSize::new(content.width * scale, content.height * scale);
+    let origin = CGPoint::new(
+        bounds.origin.x + (bounds.size.width - size.width) / 2.0,
+        bounds.origin.y + (bounds.size.height - size.height) / 2.0,
+    );
+    CGRect::new(origin, size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_letterboxes_content_with_a_different_aspect_ratio() {
+        let bounds = CGRect::new(CGPoint::ZERO, CGSize::new(1600.0, 900.0));
+        assert_eq!(
+            fit(bounds, CGSize::new(400.0, 400.0)),
+            CGRect::new(CGPoint::new(350.0, 0.0), CGSize::new(900.0, 900.0))
+        );
+        assert_eq!(
+            fit(bounds, CGSize::new(3200.0, 900.0)),
+            CGRect::new(CGPoint::new(0.0, 225.0), CGSize::new(1600.0, 450.0))
+        );
+    }
+}